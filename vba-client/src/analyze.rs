@@ -0,0 +1,124 @@
+// vba-client/src/analyze.rs
+//
+// Static tools over .bas files.
+//
+// `vba-client callgraph [--json] file.bas...`
+// - Each file is a module named after its file stem.
+// - Prints Graphviz DOT by default, JSON with `--json`.
+// - Unreachable procedures, recursion cycles, calls to another module's
+//   Private procedures and names defined in several modules are summarized
+//   on stderr.
+//
+// `vba-client transpile [--rust] file.bas`
+// - Prints Python (default) or Rust source; untranslated constructs are
+//   listed on stderr.
+//
+// `vba-client parse-report file.bas...`
+// - Lists every parse-tree node the AST builder could not convert, then
+//   the totals per category and node kind over all files.
+//
+// `vba-client check --corpus dir [--json]`
+// - Parses every .bas/.cls/.frm/.vba file under `dir` and prints the
+//   parser coverage summary (JSON with `--json`).
+
+use std::fs;
+use std::path::Path;
+
+use vba_utils::analysis::{check_corpus, CallGraph};
+use vba_utils::ast::{parse_source, parse_source_with_report, BuildReport, Program};
+use vba_utils::preprocess::default_constants;
+use vba_utils::transpiler::{self, Target};
+
+pub fn callgraph(args: &[String]) -> Result<(), String> {
+    let json = args.iter().any(|a| a == "--json");
+    let files: Vec<&String> = args.iter().filter(|a| !a.starts_with('-')).collect();
+    if files.is_empty() {
+        return Err("missing .bas files".into());
+    }
+
+    let mut modules: Vec<(String, Program)> = Vec::new();
+    for file in files {
+        let code = fs::read_to_string(file).map_err(|e| format!("cannot read {}: {}", file, e))?;
+        let name = Path::new(file)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| file.clone());
+        let program = parse_source(&code).map_err(|e| e.to_string())?;
+        // Exported modules carry their VBA name, which may differ from the file's
+        let name = program.attributes().module_name().map_or(name, str::to_string);
+        modules.push((name, program));
+    }
+
+    let refs: Vec<(&str, &Program)> = modules.iter().map(|(n, p)| (n.as_str(), p)).collect();
+    let graph = CallGraph::build(&refs);
+    print!("{}", if json { graph.to_json() } else { graph.to_dot() });
+
+    for p in graph.unreachable() {
+        eprintln!("⚠️  unreachable: {} {}", p.kind, p.qualified_name());
+    }
+    for cycle in graph.cycles() {
+        let names: Vec<String> = cycle.iter().map(|p| p.qualified_name()).collect();
+        eprintln!("🔁 recursion: {}", names.join(" -> "));
+    }
+    for message in graph.violation_messages() {
+        eprintln!("❌ visibility: {}", message);
+    }
+    for message in graph.conflict_messages() {
+        eprintln!("❌ ambiguous name: {}", message);
+    }
+    Ok(())
+}
+
+pub fn transpile(args: &[String]) -> Result<(), String> {
+    let target = if args.iter().any(|a| a == "--rust") { Target::Rust } else { Target::Python };
+    let file = args.iter().find(|a| !a.starts_with('-')).ok_or("missing .bas file")?;
+    let code = fs::read_to_string(file).map_err(|e| format!("cannot read {}: {}", file, e))?;
+
+    let program = parse_source(&code).map_err(|e| e.to_string())?;
+    let out = transpiler::transpile(&program, target);
+    print!("{}", out.source);
+    if !out.is_complete() {
+        eprintln!("⚠️  {} construct(s) not translated:", out.untranslated.len());
+        eprint!("{}", out.report());
+    }
+    Ok(())
+}
+
+pub fn parse_report(args: &[String]) -> Result<(), String> {
+    if args.is_empty() {
+        return Err("missing .bas files".into());
+    }
+    let mut total = BuildReport::default();
+    for file in args {
+        let code = fs::read_to_string(file).map_err(|e| format!("cannot read {}: {}", file, e))?;
+        let (_, report) = parse_source_with_report(&code, &default_constants()).map_err(|e| e.to_string())?;
+        for node in &report.skipped {
+            let text = node.text.lines().next().unwrap_or("").trim();
+            println!("{}:{}:{}: {:?} {}: {}", file, node.span.line, node.span.column, node.category, node.kind, text);
+        }
+        total.merge(report);
+    }
+    println!("\n{} file(s), {} skipped node(s)", args.len(), total.skipped.len());
+    for (category, count) in total.by_category() {
+        println!("  {:?}: {}", category, count);
+    }
+    for (category, kind, count) in total.by_kind() {
+        println!("  {:>6}  {:?} {}", count, category, kind);
+    }
+    Ok(())
+}
+
+pub fn check(args: &[String]) -> Result<(), String> {
+    let json = args.iter().any(|a| a == "--json");
+    let dir = match args.iter().position(|a| a == "--corpus") {
+        Some(i) => args.get(i + 1).ok_or("--corpus needs a directory")?,
+        None => return Err("missing --corpus <dir>".into()),
+    };
+    let report = check_corpus(Path::new(dir)).map_err(|e| format!("cannot read {}: {}", dir, e))?;
+    if json {
+        print!("{}", report.to_json());
+    } else {
+        print!("{}", report.to_text());
+    }
+    Ok(())
+}
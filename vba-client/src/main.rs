@@ -10,13 +10,132 @@
 // }
 
 // vba-client/src/main.rs
-use tree_sitter::Parser;
-use vba_parser::language as tree_sitter_vba;
-use vba_utils::{print_parse_tree, Context, Program, RuntimeConfig};
+mod analyze;
+mod repl;
+mod run;
+
+use vba_utils::{print_parse_tree, AutoRunPolicy, Context, ImplicitVariablePolicy, Program, RuntimeConfig};
 use vba_utils::ast::Statement;
 use vba_utils::vm::ProgramExecutor; // ✅ import ProgramExecutor
 
 fn main() {
+    // `vba-client repl` starts the interactive REPL, `vba-client run file.bas`
+    // runs a file, `vba-client test file.bas...` runs its Test* Subs,
+    // `vba-client callgraph file.bas...` prints the call graph,
+    // `vba-client transpile file.bas` translates to Python or Rust,
+    // `vba-client parse-report file.bas...` lists what the parser skipped,
+    // `vba-client check --corpus dir` summarizes parser coverage of a corpus,
+    // `vba-client config [--config file]` lists the runtime settings;
+    // no arguments runs the demo macro below
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("repl") => {
+            repl::run(RuntimeConfig::default());
+            return;
+        }
+        Some("run") => {
+            match run::RunOptions::from_args(&args[1..]) {
+                Ok(opts) => {
+                    // Settings file, then VBA_* variables, then flags
+                    let mut config = RuntimeConfig::builder();
+                    if let Some(path) = &opts.config {
+                        config = config.file(path);
+                    }
+                    config = config.env();
+                    if let Some(host) = opts.host {
+                        config = config.host(host);
+                    }
+                    if opts.batch_writes {
+                        config = config.write_batching(true);
+                    }
+                    if let Some(profile) = opts.sandbox {
+                        config = config.sandbox(profile);
+                    }
+                    if let Some(entry) = &opts.entry {
+                        config = config.auto_run(AutoRunPolicy::Named(entry.clone()));
+                    }
+                    if opts.dry_run {
+                        config = config.dry_run(true);
+                    }
+                    if opts.strict {
+                        config = config.implicit_variables(ImplicitVariablePolicy::Report);
+                    }
+                    match config.try_build() {
+                        Ok(config) => run::run(&opts, config),
+                        Err(e) => eprintln!("invalid configuration: {}", e),
+                    }
+                }
+                Err(e) => eprintln!("{}\nusage: vba-client run [--watch] [--keep-workbook] [--coverage <out>] [--profile] [--word] [--batch-writes] [--dry-run] [--config <file>] <file.bas>", e),
+            }
+            return;
+        }
+        Some("test") => {
+            let (config, files) = match &args[1..] {
+                [flag, path, files @ ..] if flag == "--config" => (RuntimeConfig::builder().file(path), files),
+                files => (RuntimeConfig::builder(), files),
+            };
+            let result = config.env().try_build().and_then(|config| run::test(files, config));
+            match result {
+                Ok(report) => {
+                    print!("{}", report.to_text());
+                    if !report.succeeded() {
+                        std::process::exit(1);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{}\nusage: vba-client test [--config <file>] <file.bas>...", e);
+                    std::process::exit(2);
+                }
+            }
+            return;
+        }
+        Some("config") => {
+            let mut config = RuntimeConfig::builder();
+            match &args[1..] {
+                [] => {}
+                [flag, path] if flag == "--config" => config = config.file(path),
+                _ => {
+                    eprintln!("usage: vba-client config [--config <file>]");
+                    return;
+                }
+            }
+            match config.env().try_build() {
+                Ok(config) => {
+                    for setting in config.describe() {
+                        println!("{:<20} {:<16} (default {}) {}", setting.key, setting.value, setting.default, setting.description);
+                    }
+                }
+                Err(e) => eprintln!("invalid configuration: {}", e),
+            }
+            return;
+        }
+        Some("callgraph") => {
+            if let Err(e) = analyze::callgraph(&args[1..]) {
+                eprintln!("{}\nusage: vba-client callgraph [--json] <file.bas>...", e);
+            }
+            return;
+        }
+        Some("parse-report") => {
+            if let Err(e) = analyze::parse_report(&args[1..]) {
+                eprintln!("{}\nusage: vba-client parse-report <file.bas>...", e);
+            }
+            return;
+        }
+        Some("check") => {
+            if let Err(e) = analyze::check(&args[1..]) {
+                eprintln!("{}\nusage: vba-client check --corpus <dir> [--json]", e);
+            }
+            return;
+        }
+        Some("transpile") => {
+            if let Err(e) = analyze::transpile(&args[1..]) {
+                eprintln!("{}\nusage: vba-client transpile [--rust] <file.bas>", e);
+            }
+            return;
+        }
+        _ => {}
+    }
+
     let vba_code = r#"
 
     Sub AutoOpen()
@@ -457,22 +576,17 @@ End Sub
 
     "#;
 
-    // Set up parser
-    let mut parser = Parser::new();
-    parser
-        .set_language(tree_sitter_vba())
-        .expect("Failed to load grammar");
-
-    // Parse input
-    let tree = parser.parse(vba_code, None).expect("Parsing failed");
-    let root_node = tree.root_node();
-
     // println!("\n🔍 Tree-sitter Parse Tree:");
    // print_parse_tree(vba_code); // <-- You already have a utility for this!
 
-    // Build AST from the parse tree
+    // Parse and build the AST
     use vba_utils::ast::build_ast;
-    let program: Program = build_ast(root_node, vba_code);
+    let program: Program = build_ast(vba_code);
+
+    // Report anything the AST builder could not understand; run the rest anyway
+    for diag in &program.diagnostics {
+        eprintln!("⚠️  line {}:{}: {}", diag.span.line, diag.span.column, diag.message);
+    }
 
     // DUMP THE WHOLE AST
     // dbg!(&program);
@@ -498,6 +612,7 @@ End Sub
     }
     
     // Print workbook ID and cell value [0,0] (A1) from active workbook
+    let workbook = ctx.static_workbook.enter();
     println!("\n📊 Checking workbook and cell value:");
     
     // Get workbook ID
@@ -512,6 +627,11 @@ End Sub
         Ok(value) => println!("✅ Cell A1 value: {}", value),
         Err(e) => println!("❌ Error reading A1: {}", e),
     }
+
+    drop(workbook);
+    if let Err(e) = executor.shutdown(&mut ctx) {
+        eprintln!("Shutdown error: {}", e);
+    }
 }
 //Sub somemacro()
 
@@ -0,0 +1,380 @@
+// vba-client/src/repl.rs
+//
+// Interactive read-eval-print loop: `vba-client repl`
+//
+// - One Context lives for the whole session, so variables, Subs, Types and
+//   Enums defined in earlier entries stay available.
+// - `Sub`/`Function`/`Property`/`Type`/`Enum` blocks are registered as
+//   module declarations; anything else runs as immediate statements.
+// - `? expr` (or `Print expr`) evaluates an expression and prints it.
+// - Block statements (If/For/Do/With/Select and declarations) continue
+//   over several lines until they are closed.
+
+use std::io::{self, BufRead, Write};
+
+use vba_utils::ast::{parse_source, Program, Statement};
+use vba_utils::interpreter::{execute_statement_list, run_statement_list_vm};
+use vba_utils::vm::ProgramExecutor;
+use vba_utils::{Context, ExecutionEngine, RuntimeConfig, VbaRuntime};
+
+const HELP: &str = "\
+Enter VBA statements, or a whole Sub/Function to define it.
+  ? expr       evaluate and print an expression
+  :vars        list variables
+  :subs        list defined Subs and Functions
+  :reset       start over with an empty context
+  :help        show this help
+  :quit        leave the REPL";
+
+const DECLARATION_KEYWORDS: [&str; 7] =
+    ["sub", "function", "property", "type", "enum", "declare", "event"];
+
+pub fn run(config: RuntimeConfig) {
+    println!("VBA REPL - :help for commands, :quit to exit");
+    let stdin = io::stdin();
+    if let Err(e) = run_session(config, stdin.lock(), &mut io::stdout()) {
+        eprintln!("Error: {}", e);
+    }
+}
+
+/// Run the REPL over `input` until it ends or `:quit`, writing prompts and
+/// results to `out`
+pub fn run_session(config: RuntimeConfig, input: impl BufRead, out: &mut impl Write) -> io::Result<()> {
+    let mut ctx = Context::with_config(config.clone());
+    vba_utils::host::excel::initialize_excel_host(&mut ctx);
+
+    let mut lines = input.lines();
+    let mut reader = EntryReader::default();
+
+    loop {
+        write!(out, "{}", if reader.is_continuing() { "...> " } else { "vba> " })?;
+        out.flush()?;
+
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+        let trimmed = line.trim();
+
+        if !reader.is_continuing() {
+            match trimmed {
+                "" => continue,
+                ":quit" | ":q" | ":exit" => break,
+                ":help" => {
+                    writeln!(out, "{}", HELP)?;
+                    continue;
+                }
+                ":vars" => {
+                    write!(out, "{}", ctx.debug_vars())?;
+                    continue;
+                }
+                ":subs" => {
+                    print_subs(&ctx, out)?;
+                    continue;
+                }
+                ":reset" => {
+                    shutdown(&mut ctx, out)?;
+                    ctx = Context::with_config(config.clone());
+                    vba_utils::host::excel::initialize_excel_host(&mut ctx);
+                    writeln!(out, "Context reset")?;
+                    continue;
+                }
+                _ => {}
+            }
+            if let Some(expr) = print_expression(trimmed) {
+                match VbaRuntime::eval_expression(expr, &mut ctx) {
+                    Ok(v) => writeln!(out, "{}", v.as_string())?,
+                    Err(e) => writeln!(out, "Error: {}", e)?,
+                }
+                continue;
+            }
+        }
+
+        let Some(entry) = reader.push(&line) else { continue };
+        if is_declaration(&entry) {
+            define(&entry, &mut ctx, out)?;
+        } else {
+            execute(&entry, &mut ctx, out)?;
+        }
+    }
+    shutdown(&mut ctx, out)
+}
+
+/// Collects lines until every block they open is closed
+#[derive(Default)]
+struct EntryReader {
+    buffer: String,
+    depth: i32,
+}
+
+impl EntryReader {
+    /// Whether an unfinished block is buffered (the `...>` prompt)
+    fn is_continuing(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    /// Add a line; returns the entry once it is complete. A stray closing
+    /// line (`Next` without `For`) completes the entry too, so a bad entry
+    /// never leaves the reader stuck inside a block.
+    fn push(&mut self, line: &str) -> Option<String> {
+        self.buffer.push_str(line);
+        self.buffer.push('\n');
+        self.depth += block_delta(line.trim());
+        if self.depth > 0 {
+            return None;
+        }
+        self.depth = 0;
+        Some(std::mem::take(&mut self.buffer))
+    }
+}
+
+fn shutdown(ctx: &mut Context, out: &mut impl Write) -> io::Result<()> {
+    if let Err(e) = vba_utils::host::excel::shutdown_excel_host(ctx) {
+        writeln!(out, "Error: {}", e)?;
+    }
+    Ok(())
+}
+
+/// `? expr` and `Print expr`, as in the VBA Immediate window
+fn print_expression(line: &str) -> Option<&str> {
+    if let Some(rest) = line.strip_prefix('?') {
+        return Some(rest.trim());
+    }
+    let (head, rest) = line.split_once(char::is_whitespace)?;
+    head.eq_ignore_ascii_case("print").then(|| rest.trim())
+}
+
+fn first_keyword(line: &str) -> String {
+    let mut words = line.split_whitespace().map(str::to_ascii_lowercase);
+    let mut word = words.next().unwrap_or_default();
+    while matches!(word.as_str(), "public" | "private" | "friend" | "static") {
+        word = words.next().unwrap_or_default();
+    }
+    word
+}
+
+fn is_declaration(entry: &str) -> bool {
+    let first = entry.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("");
+    DECLARATION_KEYWORDS.contains(&first_keyword(first).as_str())
+}
+
+/// How many blocks `line` opens (positive) or closes (negative)
+fn block_delta(line: &str) -> i32 {
+    let code = strip_comment(line).trim();
+    let lower = code.to_ascii_lowercase();
+    let first = first_keyword(code);
+
+    if lower.starts_with("end ") && lower != "end" {
+        let closes = ["sub", "function", "property", "type", "enum", "if", "with", "select"];
+        let second = lower.split_whitespace().nth(1).unwrap_or("");
+        return if closes.contains(&second) { -1 } else { 0 };
+    }
+    match first.as_str() {
+        "next" | "loop" | "wend" => -1,
+        "sub" | "function" | "property" | "type" | "enum" | "for" | "do" | "while" | "with" => 1,
+        "select" => 1,
+        // Only the block form; `If x Then y` is complete on one line
+        "if" if lower.ends_with("then") => 1,
+        _ => 0,
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '\'' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn report(program: &Program, out: &mut impl Write) -> io::Result<bool> {
+    for d in &program.diagnostics {
+        writeln!(out, "Syntax error: line {}: {}", d.span.line, d.message)?;
+    }
+    Ok(program.diagnostics.is_empty())
+}
+
+fn report_err(ctx: &mut Context, out: &mut impl Write) -> io::Result<()> {
+    if let Some(err) = ctx.err.take() {
+        writeln!(out, "Run-time error '{}': {}", err.number, err.description)?;
+    }
+    Ok(())
+}
+
+fn define(entry: &str, ctx: &mut Context, out: &mut impl Write) -> io::Result<()> {
+    let program = match parse_source(entry) {
+        Ok(p) => p,
+        Err(e) => return writeln!(out, "Error: {}", e),
+    };
+    if !report(&program, out)? {
+        return Ok(());
+    }
+    if let Err(e) = ProgramExecutor::new(program).load(ctx) {
+        writeln!(out, "Error: {}", e)?;
+    }
+    report_err(ctx, out)
+}
+
+fn execute(entry: &str, ctx: &mut Context, out: &mut impl Write) -> io::Result<()> {
+    let source = format!("Sub __Repl()\n{}End Sub\n", entry);
+    let program = match parse_source(&source) {
+        Ok(p) => p,
+        Err(e) => return writeln!(out, "Error: {}", e),
+    };
+    if !report(&program, out)? {
+        return Ok(());
+    }
+    let body = match program.statements.into_iter().next() {
+        Some(Statement::Subroutine { body, .. }) => body,
+        _ => return writeln!(out, "Error: could not parse entry"),
+    };
+
+    // Statements run at module level, so Dim'd names persist across entries
+    let _workbook = ctx.static_workbook.enter();
+    match ctx.runtime_config.engine {
+        ExecutionEngine::Vm => {
+            run_statement_list_vm(body, ctx, 0);
+        }
+        ExecutionEngine::Interpreter => {
+            execute_statement_list(&body, ctx);
+        }
+    }
+    report_err(ctx, out)
+}
+
+fn print_subs(ctx: &Context, out: &mut impl Write) -> io::Result<()> {
+    let mut names: Vec<String> = ctx
+        .subs
+        .keys()
+        .map(|s| s.to_string())
+        .filter(|n| !n.starts_with("__"))
+        .collect();
+    names.sort_by_key(|n| n.to_lowercase());
+    for name in names {
+        let kind = if ctx.is_function(&name) { "Function" } else { "Sub" };
+        writeln!(out, "  {} {}", kind, name)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vba_utils::ExcelBackendKind;
+
+    /// Run a session over `input`, returning everything it wrote
+    fn session(input: &str) -> String {
+        let config = RuntimeConfig::builder().excel_backend(ExcelBackendKind::Static).build();
+        let mut out = Vec::new();
+        run_session(config, input.as_bytes(), &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    /// Lines the session printed, without prompts
+    fn printed(output: &str) -> Vec<&str> {
+        output.lines().map(strip_prompts).filter(|l| !l.is_empty()).collect()
+    }
+
+    fn strip_prompts(mut line: &str) -> &str {
+        while let Some(rest) = line.strip_prefix("vba> ").or_else(|| line.strip_prefix("...> ")) {
+            line = rest;
+        }
+        line
+    }
+
+    #[test]
+    fn test_reader_collects_blocks_until_closed() {
+        let mut reader = EntryReader::default();
+        assert_eq!(reader.push("For i = 1 To 2"), None);
+        assert_eq!(reader.push("  If i > 1 Then"), None);
+        assert_eq!(reader.push("    x = i"), None);
+        assert!(reader.is_continuing());
+        assert_eq!(reader.push("  End If"), None);
+        assert_eq!(reader.push("Next"), Some("For i = 1 To 2\n  If i > 1 Then\n    x = i\n  End If\nNext\n".into()));
+        assert!(!reader.is_continuing());
+    }
+
+    #[test]
+    fn test_reader_completes_single_line_entries() {
+        let mut reader = EntryReader::default();
+        assert_eq!(reader.push("If x Then y = 1"), Some("If x Then y = 1\n".into()));
+        // A keyword inside a comment or string opens nothing
+        assert_eq!(reader.push("x = \"For\" ' Do"), Some("x = \"For\" ' Do\n".into()));
+    }
+
+    #[test]
+    fn test_reader_recovers_from_stray_closing_lines() {
+        let mut reader = EntryReader::default();
+        assert_eq!(reader.push("Next"), Some("Next\n".into()));
+        assert_eq!(reader.push("Sub Main()"), None);
+        assert_eq!(reader.push("End Sub"), Some("Sub Main()\nEnd Sub\n".into()));
+    }
+
+    #[test]
+    fn test_print_expression() {
+        assert_eq!(print_expression("? 1 + 2"), Some("1 + 2"));
+        assert_eq!(print_expression("?x"), Some("x"));
+        assert_eq!(print_expression("PRINT x & y"), Some("x & y"));
+        assert_eq!(print_expression("Printer = 1"), None);
+    }
+
+    #[test]
+    fn test_declarations_are_told_from_statements() {
+        assert!(is_declaration("\nPrivate Function F()\nEnd Function\n"));
+        assert!(is_declaration("Type Point\nX As Long\nEnd Type\n"));
+        assert!(!is_declaration("Static total As Long\n"));
+        assert!(!is_declaration("subtotal = 1\n"));
+    }
+
+    #[test]
+    fn test_session_commands() {
+        let output = session(":help\n:subs\n:quit\n:help\n");
+        assert_eq!(output.matches(HELP).count(), 1);
+        assert!(output.starts_with("vba> "));
+    }
+
+    #[test]
+    fn test_session_ends_with_its_input() {
+        assert_eq!(session(""), "vba> ");
+        assert_eq!(session("\n\n"), "vba> vba> vba> ");
+    }
+
+    // The tests below parse VBA, so they need the generated grammar
+
+    #[test]
+    fn test_session_prints_expressions() {
+        assert_eq!(printed(&session("? 1 + 2\nPrint \"a\" & \"b\"\n")), vec!["3", "ab"]);
+    }
+
+    #[test]
+    fn test_session_runs_multi_line_blocks() {
+        let output = session("total = 0\nFor i = 1 To 3\ntotal = total + i\nNext\n? total\n");
+        assert!(output.contains("...> "));
+        assert_eq!(printed(&output), vec!["6"]);
+    }
+
+    #[test]
+    fn test_session_keeps_definitions() {
+        let output = session("Function Twice(x)\nTwice = x * 2\nEnd Function\n? Twice(21)\n:subs\n");
+        assert_eq!(printed(&output), vec!["42", "  Function Twice"]);
+    }
+
+    #[test]
+    fn test_session_recovers_from_errors() {
+        let output = session("x = 1 / 0\nx = = 1\n? 2 + 2\n");
+        let printed = printed(&output);
+        assert!(printed[0].starts_with("Run-time error '11'"), "{:?}", printed);
+        assert!(printed[1].contains("rror"), "{:?}", printed);
+        assert_eq!(printed.last(), Some(&"4"));
+    }
+
+    #[test]
+    fn test_session_reset_forgets_variables() {
+        let output = session("x = 5\n:reset\n? IsEmpty(x)\n");
+        assert_eq!(printed(&output), vec!["Context reset", "true"]);
+    }
+}
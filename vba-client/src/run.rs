@@ -0,0 +1,538 @@
+// vba-client/src/run.rs
+//
+// Run a .bas file:
+// `vba-client run [--watch] [--keep-workbook] [--coverage out] [--profile] [--word] [--batch-writes] [--entry Sub] [--sandbox profile] [--deobfuscate] [--strict] [--dry-run] file.bas`
+//
+// - Without flags the file is parsed and executed once.
+// - `--watch` polls the file and re-parses/re-executes it whenever it
+//   changes, with a fresh Context each time. A version that fails to load
+//   is reported and not run; the last good one stays current.
+// - `--keep-workbook` (with `--watch`) keeps the static engine's cells and
+//   formats between runs instead of starting from an empty workbook.
+// - `--coverage out` writes line/branch coverage after each run: an HTML
+//   page when `out` ends in `.html`, an LCOV tracefile otherwise.
+// - `--profile` prints time per Sub/Function and the hottest lines.
+// - `--word` runs the macro against the Word object model instead of Excel
+//   and prints the text of every document it touched.
+// - `--batch-writes` coalesces consecutive cell writes into block writes to
+//   the Excel backend and reports how many backend calls that saved.
+// - `--entry Sub` runs that Sub instead of the first auto-exec macro (or
+//   Main); when nothing runs, the Subs that could are listed.
+// - `--sandbox analysis|trusted|interactive` applies that SandboxProfile.
+// - `--deobfuscate` folds constants and string-building calls and drops
+//   dead branches before running, and prints how much each pass rewrote.
+// - `--strict` lists the variables the macro created without declaring
+//   them, as Option Explicit would have caught them.
+// - `--dry-run` applies none of the macro's cell, sheet, save and file
+//   writes and lists them instead, for approval before a real run.
+// - After each run, hyperlinks the macro added to the workbook are listed
+//   with their targets, and any Outlook mail it composed is listed with its
+//   recipients, subject and attachments (mail is never actually sent).
+// - Calls made on late-bound objects no host implements (CreateObject of
+//   WScript.Shell, Scripting.FileSystemObject, ...) are listed in order.
+//
+// Run a file's unit tests:
+// `vba-client test [--config file] file.bas...`
+//
+// - Every `Test*` Sub of each file runs with `Assert` available, and a line
+//   per test is printed with the failed assertions and their lines. The
+//   process exits with status 1 when any test failed.
+
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use vba_utils::ast::parse_source_with_constants;
+use vba_utils::host::behavior;
+use vba_utils::host::dll;
+use vba_utils::host::dry_run;
+use vba_utils::host::excel::engine;
+use vba_utils::host::excel::objects::indices_to_address;
+use vba_utils::host::excel::static_engine::{self, StaticWorkbook};
+use vba_utils::host::outlook::mail;
+use vba_utils::host::stub;
+use vba_utils::host::userform;
+use vba_utils::host::vb;
+use vba_utils::host::word::document as word_document;
+use vba_utils::transform::PassPipeline;
+use vba_utils::vm::{CoverageReport, ProgramExecutor, TestReport};
+use vba_utils::{Context, HostApplication, Program, RuntimeConfig, SandboxProfile};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+pub struct RunOptions {
+    pub path: String,
+    pub watch: bool,
+    pub keep_workbook: bool,
+    pub coverage: Option<String>,
+    pub profile: bool,
+    /// Settings file (TOML, or JSON by extension) applied before the
+    /// `VBA_*` environment and the flags
+    pub config: Option<String>,
+    pub host: Option<HostApplication>,
+    pub batch_writes: bool,
+    pub entry: Option<String>,
+    pub sandbox: Option<SandboxProfile>,
+    pub deobfuscate: bool,
+    pub strict: bool,
+    pub dry_run: bool,
+}
+
+impl RunOptions {
+    /// Parse the arguments following `run`
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut path = None;
+        let mut watch = false;
+        let mut keep_workbook = false;
+        let mut coverage = None;
+        let mut profile = false;
+        let mut config = None;
+        let mut host = None;
+        let mut batch_writes = false;
+        let mut entry = None;
+        let mut sandbox = None;
+        let mut deobfuscate = false;
+        let mut strict = false;
+        let mut dry_run = false;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--watch" | "-w" => watch = true,
+                "--keep-workbook" => keep_workbook = true,
+                "--profile" => profile = true,
+                "--word" => host = Some(HostApplication::Word),
+                "--batch-writes" => batch_writes = true,
+                "--deobfuscate" => deobfuscate = true,
+                "--strict" => strict = true,
+                "--dry-run" => dry_run = true,
+                "--entry" => {
+                    entry = Some(args.next().ok_or("--entry needs a Sub name")?.clone());
+                }
+                "--sandbox" => {
+                    sandbox = Some(match args.next().map(|s| s.to_ascii_lowercase()).as_deref() {
+                        Some("analysis") => SandboxProfile::Analysis,
+                        Some("trusted") => SandboxProfile::Trusted,
+                        Some("interactive") => SandboxProfile::Interactive,
+                        _ => return Err("--sandbox needs analysis, trusted or interactive".to_string()),
+                    });
+                }
+                "--config" => {
+                    config = Some(args.next().ok_or("--config needs a settings file")?.clone());
+                }
+                "--coverage" => {
+                    coverage = Some(args.next().ok_or("--coverage needs an output file")?.clone());
+                }
+                flag if flag.starts_with('-') => return Err(format!("unknown option: {}", flag)),
+                file if path.is_none() => path = Some(file.to_string()),
+                extra => return Err(format!("unexpected argument: {}", extra)),
+            }
+        }
+        let path = path.ok_or("missing file to run")?;
+        Ok(RunOptions { path, watch, keep_workbook, coverage, profile, config, host, batch_writes, entry, sandbox, deobfuscate, strict, dry_run })
+    }
+}
+
+pub fn run(opts: &RunOptions, config: RuntimeConfig) {
+    if !opts.watch {
+        match load(&opts.path, &config) {
+            Ok((code, program)) => run_program(opts, config, StaticWorkbook::new(), &code, program),
+            Err(e) => eprintln!("❌ {}", e),
+        }
+        return;
+    }
+
+    println!("👀 Watching {} (Ctrl+C to stop)", opts.path);
+    let mut watch = Watch::default();
+    let mut workbook = StaticWorkbook::new();
+    loop {
+        if watch.changed(modified_time(&opts.path)) {
+            match watch.reload(load(&opts.path, &config)) {
+                Ok((code, program)) => {
+                    if !opts.keep_workbook {
+                        workbook = StaticWorkbook::new();
+                    }
+                    println!("\n🔁 Running {}", opts.path);
+                    run_program(opts, config.clone(), workbook.clone(), code, program.clone());
+                }
+                Err(e) => eprintln!("❌ {} (keeping the previous version)", e),
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// What `run --watch` has seen of the file: when it last changed and the
+/// last version that parsed
+#[derive(Default)]
+struct Watch {
+    last_seen: Option<SystemTime>,
+    current: Option<(String, Program)>,
+}
+
+impl Watch {
+    /// Whether the file changed since the last call, given its modification
+    /// time (`None` while it cannot be read, which is not a change)
+    fn changed(&mut self, modified: Option<SystemTime>) -> bool {
+        if modified.is_none() || modified == self.last_seen {
+            return false;
+        }
+        self.last_seen = modified;
+        true
+    }
+
+    /// Make a freshly loaded version current; when it failed to load the
+    /// previous version stays current and the error is returned
+    fn reload(&mut self, loaded: Result<(String, Program), String>) -> Result<(&str, &Program), String> {
+        let (code, program) = self.current.insert(loaded?);
+        Ok((code.as_str(), program))
+    }
+
+    /// The last version that loaded
+    #[cfg(test)]
+    fn current(&self) -> Option<&Program> {
+        self.current.as_ref().map(|(_, program)| program)
+    }
+}
+
+/// Run the unit tests of each file in `files`, each in a fresh Context as
+/// its own module; returns the combined report
+pub fn test(files: &[String], config: RuntimeConfig) -> Result<TestReport, String> {
+    if files.is_empty() {
+        return Err("missing .bas files".into());
+    }
+    let mut report = TestReport::default();
+    for file in files {
+        let code = fs::read_to_string(file).map_err(|e| format!("cannot read {}: {}", file, e))?;
+        let program = parse_source_with_constants(&code, &config.compiler_constants).map_err(|e| e.to_string())?;
+        for diag in &program.diagnostics {
+            eprintln!("⚠️  {} line {}:{}: {}", file, diag.span.line, diag.span.column, diag.message);
+        }
+        let name = Path::new(file).file_stem().map_or_else(|| file.clone(), |s| s.to_string_lossy().into_owned());
+        let name = program.attributes().module_name().map_or(name, str::to_string);
+
+        let mut ctx = Context::with_config(config.clone());
+        let executor = ProgramExecutor::new(program).with_module(name);
+        let result = executor.run_tests(&mut ctx);
+        executor.shutdown(&mut ctx).map_err(|e| e.to_string())?;
+        report.results.extend(result.map_err(|e| e.to_string())?.results);
+    }
+    Ok(report)
+}
+
+fn modified_time(path: &str) -> Option<SystemTime> {
+    fs::metadata(Path::new(path)).and_then(|m| m.modified()).ok()
+}
+
+/// Read and parse the file at `path`, returning its source and program
+fn load(path: &str, config: &RuntimeConfig) -> Result<(String, Program), String> {
+    let code = fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    let program = parse_source_with_constants(&code, &config.compiler_constants).map_err(|e| e.to_string())?;
+    Ok((code, program))
+}
+
+fn run_program(opts: &RunOptions, config: RuntimeConfig, workbook: StaticWorkbook, code: &str, program: Program) {
+    let path = opts.path.as_str();
+    // Report anything the AST builder could not understand; run the rest anyway
+    for diag in &program.diagnostics {
+        eprintln!("⚠️  line {}:{}: {}", diag.span.line, diag.span.column, diag.message);
+    }
+
+    let mut ctx = Context::with_config(config);
+    ctx.static_workbook = workbook;
+    if opts.coverage.is_some() {
+        ctx.start_coverage();
+    }
+    let mut executor = ProgramExecutor::new(program);
+    if opts.deobfuscate {
+        for outcome in executor.transform(&mut PassPipeline::deobfuscation()) {
+            println!("🧹 {}: {} rewrites", outcome.pass, outcome.changes);
+        }
+    }
+    let result = if opts.profile {
+        executor.execute_profiled(&mut ctx).map(|report| println!("\n⏱️  Profile\n{}", report.to_text()))
+    } else {
+        executor.execute(&mut ctx)
+    };
+    if let Err(e) = result {
+        eprintln!("Program execution error: {}", e);
+    }
+    if executor.detect_entrypoint(&ctx).is_none() {
+        print_entrypoints(&executor);
+    }
+    if let Some(err) = &ctx.err {
+        eprintln!("Run-time error '{}': {}", err.number, err.description);
+    }
+    if let (Some(out), Some(hits)) = (&opts.coverage, ctx.take_coverage()) {
+        write_coverage(out, path, code, &CoverageReport::new(executor.program(), &hits));
+    }
+    // What the run left in its workbook
+    let _workbook = ctx.static_workbook.enter();
+    match ctx.runtime_config.host {
+        HostApplication::Excel => {
+            print_hyperlinks();
+            if ctx.runtime_config.write_batching {
+                print_write_batching();
+            }
+        }
+        HostApplication::Word => print_documents(),
+    }
+    if let Some(plan) = dry_run::take(&mut ctx) {
+        println!("\n📝 Planned writes (not applied)");
+        if plan.is_empty() {
+            println!("  (none)");
+        }
+        for mutation in &plan.mutations {
+            println!("  {}", mutation.to_text());
+        }
+    }
+    if let Some(report) = ctx.stop_report() {
+        println!("\n⏹️  {}", report.to_text());
+    }
+    print_implicit_variables(&ctx);
+    print_mail();
+    print_interactions();
+    print_stub_calls();
+    print_dll_calls();
+    print_clipboard();
+    print_forms();
+    print_scheduled(&ctx);
+}
+
+fn print_hyperlinks() {
+    let links = static_engine::static_list_hyperlinks(None);
+    if links.is_empty() {
+        return;
+    }
+    println!("\n🔗 Hyperlinks");
+    for (sheet, row, col, link) in links {
+        let target = match (link.address.is_empty(), link.sub_address.is_empty()) {
+            (false, false) => format!("{}#{}", link.address, link.sub_address),
+            (true, _) => link.sub_address,
+            (false, true) => link.address,
+        };
+        println!("  {}!{} -> {}", sheet, indices_to_address(row, col), target);
+    }
+}
+
+fn print_documents() {
+    println!("\n📄 Documents");
+    for doc in word_document::all_documents() {
+        let state = if doc.closed { " (closed)" } else { "" };
+        println!("  {}{}: {:?}", doc.full_name(), state, doc.text);
+    }
+}
+
+fn print_mail() {
+    let items = mail::mail_items();
+    if items.is_empty() {
+        return;
+    }
+    println!("\n📧 Mail");
+    for item in items {
+        let state = if item.sent { "sent" } else if item.displayed { "displayed" } else { "unsent" };
+        println!("  [{}] To: {} | Subject: {:?}", state, item.to, item.subject);
+        if !item.cc.is_empty() || !item.bcc.is_empty() {
+            println!("    Cc: {} | Bcc: {}", item.cc, item.bcc);
+        }
+        for attachment in &item.attachments {
+            println!("    📎 {}", attachment);
+        }
+    }
+}
+
+fn print_write_batching() {
+    let stats = engine::write_batch_stats();
+    println!(
+        "\n📦 Write batching: {} cell writes in {} backend calls ({} round trips saved)",
+        stats.cells_written,
+        stats.backend_writes,
+        stats.round_trips_saved()
+    );
+}
+
+fn print_implicit_variables(ctx: &Context) {
+    let variables = ctx.implicit_variables();
+    if variables.is_empty() {
+        return;
+    }
+    println!("\n⚠️  Undeclared variables");
+    for variable in variables {
+        println!("  {}", variable.to_text());
+    }
+}
+
+fn print_interactions() {
+    let interactions = behavior::interactions();
+    if interactions.is_empty() {
+        return;
+    }
+    println!("\n🖱️  Desktop interactions");
+    for interaction in interactions {
+        println!("  {}", interaction.to_text());
+    }
+}
+
+fn print_clipboard() {
+    let text = vb::clipboard_text();
+    if text.is_empty() {
+        return;
+    }
+    println!("\n📋 Clipboard");
+    println!("  {:?}", text);
+}
+
+fn print_forms() {
+    let forms = userform::forms();
+    if forms.is_empty() {
+        return;
+    }
+    println!("\n🪟 UserForms");
+    for form in forms {
+        println!("  {} (shown {}x)", form.name, form.shown);
+    }
+}
+
+fn print_scheduled(ctx: &Context) {
+    if ctx.scheduled().is_empty() {
+        return;
+    }
+    println!("\n⏰ Scheduled (Application.OnTime, not run)");
+    for entry in ctx.scheduled() {
+        println!("  {} at {}", entry.procedure, entry.at);
+    }
+}
+
+fn print_stub_calls() {
+    let calls = stub::calls();
+    if calls.is_empty() {
+        return;
+    }
+    println!("\n🧩 Late-bound calls");
+    for call in calls {
+        println!("  {}", call.to_text());
+    }
+}
+
+fn print_dll_calls() {
+    let calls = dll::calls();
+    if calls.is_empty() {
+        return;
+    }
+    println!("\n🔌 Declare'd DLL calls");
+    for call in calls {
+        println!("  {} [{:?}]", call.to_text(), call.outcome);
+    }
+}
+
+fn write_coverage(out: &str, source_path: &str, code: &str, report: &CoverageReport) {
+    let text = if out.ends_with(".html") {
+        report.to_html(source_path, code)
+    } else {
+        report.to_lcov(source_path)
+    };
+    match fs::write(out, text) {
+        Ok(()) => println!(
+            "📊 Coverage: {}/{} lines, {}/{} branches → {}",
+            report.lines_hit(),
+            report.lines_found(),
+            report.branches_hit(),
+            report.branches_found(),
+            out
+        ),
+        Err(e) => eprintln!("❌ Cannot write {}: {}", out, e),
+    }
+}
+
+fn print_entrypoints(executor: &ProgramExecutor) {
+    let entrypoints = executor.entrypoints();
+    if entrypoints.is_empty() {
+        return;
+    }
+    println!("\n📋 Macros (run one with --entry):");
+    for entry in entrypoints {
+        let note = if entry.private { " (Private)" } else { "" };
+        println!("  {}{}", entry.name, note);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vba_utils::VbaAstNode as Statement;
+
+    fn at(secs: u64) -> Option<SystemTime> {
+        Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+    }
+
+    /// A program of `n` empty Subs, told apart by their count
+    fn program(n: usize) -> Program {
+        let statements = (0..n)
+            .map(|i| Statement::Subroutine { visibility: None, name: format!("S{}", i), params: vec![], body: vec![].into() })
+            .collect();
+        Program { statements, diagnostics: vec![] }
+    }
+
+    #[test]
+    fn test_first_sighting_is_a_change() {
+        assert!(Watch::default().changed(at(1)));
+    }
+
+    #[test]
+    fn test_unchanged_time_is_not_a_change() {
+        let mut watch = Watch::default();
+        watch.changed(at(1));
+        assert!(!watch.changed(at(1)));
+    }
+
+    #[test]
+    fn test_new_time_is_a_change() {
+        let mut watch = Watch::default();
+        watch.changed(at(1));
+        assert!(watch.changed(at(2)));
+        assert!(!watch.changed(at(2)));
+    }
+
+    #[test]
+    fn test_unreadable_file_is_not_a_change() {
+        let mut watch = Watch::default();
+        assert!(!watch.changed(None));
+        watch.changed(at(1));
+        assert!(!watch.changed(None));
+        // Coming back unchanged after a save in progress does not re-run
+        assert!(!watch.changed(at(1)));
+    }
+
+    #[test]
+    fn test_reload_makes_the_new_program_current() {
+        let mut watch = Watch::default();
+        watch.reload(Ok(("a".into(), program(1)))).unwrap();
+        let (code, current) = watch.reload(Ok(("b".into(), program(2)))).unwrap();
+        assert_eq!(code, "b");
+        assert_eq!(current.statements.len(), 2);
+        assert_eq!(watch.current().unwrap().statements.len(), 2);
+    }
+
+    #[test]
+    fn test_failed_reload_keeps_the_previous_program() {
+        let mut watch = Watch::default();
+        watch.reload(Ok(("a".into(), program(1)))).unwrap();
+        let err = watch.reload(Err("syntax error".into())).unwrap_err();
+        assert_eq!(err, "syntax error");
+        assert_eq!(watch.current().unwrap().statements.len(), 1);
+    }
+
+    #[test]
+    fn test_failed_first_load_has_no_program() {
+        let mut watch = Watch::default();
+        assert!(watch.reload(Err("syntax error".into())).is_err());
+        assert!(watch.current().is_none());
+    }
+
+    #[test]
+    fn test_load_reports_a_missing_file() {
+        let config = RuntimeConfig::default();
+        let err = load("/nonexistent/watch.bas", &config).unwrap_err();
+        assert!(err.starts_with("Cannot read /nonexistent/watch.bas"), "{}", err);
+    }
+}
@@ -52,7 +52,7 @@ module.exports = grammar({
 
   extras: $ => [
     /[ \t]+/,  // spaces and tabs
-    /_/,        // line continuation
+    $.line_continuation,  // ` _` at end of line
     $.comment,   // VBA comments
   ],
 
@@ -63,12 +63,17 @@ module.exports = grammar({
     statement: $ => choice(
       $.blank_line,
       $.option_explicit_statement,
+      $.option_private_module_statement,
+      $.deftype_statement,
+      $.declare_statement,
+      $.attribute_statement,
       $.subroutine,
       $.function_definition,
       $.property_get,
       $.property_let,
       $.property_set,
       $.dim_statement,
+      $.static_statement,
       $.redim_statement,
       $.enum_statement,  
       $.type_statement,
@@ -86,6 +91,7 @@ module.exports = grammar({
       $.on_error_statement,
       $.resume_statement,
       $.exit_statement,
+      $.stop_statement,
 
     ),
 
@@ -99,8 +105,50 @@ module.exports = grammar({
       /\r?\n/
     ),
 
-    // Subroutine Definition: Sub Name(params) ... End Sub
+    // Option Private Module: public members stay inside the project
+    option_private_module_statement: $ => seq(
+      token(/Option/i),
+      token(/Private/i),
+      token(/Module/i),
+      /\r?\n/
+    ),
+
+    // DefInt A-Z, DefStr S: default type of undeclared names by first letter
+    deftype_statement: $ => seq(
+      field('keyword', token(/Def(Bool|Byte|Int|Lng|LngLng|LngPtr|Cur|Sng|Dbl|Dec|Date|Str|Obj|Var)/i)),
+      commaSep1(field('range', $.letter_range)),
+      /\r?\n/
+    ),
+    letter_range: $ => token(/[A-Za-z]([ \t]*-[ \t]*[A-Za-z])?/),
+
+    // Attribute VB_Name = "Module1", Attribute Foo.VB_Description = "..."
+    attribute_statement: $ => seq(
+      token(/Attribute/i),
+      optional(seq(field('target', $.identifier), '.')),
+      field('name', $.identifier),
+      '=',
+      commaSep1(field('value', $.expression)),
+      /\r?\n/
+    ),
+
+    // Declare [PtrSafe] Function Name Lib "dll" [Alias "Entry"] (params) [As Type]
+    declare_statement: $ => seq(
+      optional(field('visibility', choice(token(/Public/i), token(/Private/i)))),
+      token(/Declare/i),
+      optional(field('ptr_safe', token(/PtrSafe/i))),
+      field('kind', choice(token(/Sub/i), token(/Function/i))),
+      field('name', $.identifier),
+      token(/Lib/i),
+      field('library', $.string_literal),
+      optional(seq(token(/Alias/i), field('alias', $.string_literal))),
+      optional(field('params', $.parameter_list)),
+      optional(seq(token(/As/i), field('return_type', choice($.primitive_type, $.identifier)))),
+      /\r?\n/
+    ),
+
+    // Subroutine Definition: [Public|Private] Sub Name(params) ... End Sub
     subroutine: $ => seq(
+      optional(field('visibility', choice(token(/Public/i), token(/Private/i)))),
       token(/Sub/i),
       field("name", $.identifier),
       optional(field("params", $.parameter_list)),
@@ -231,6 +279,24 @@ module.exports = grammar({
       ),
       /\r?\n/
     ),
+
+    // Static statement: Static var [As Type], a local kept between calls
+    static_statement: $ => seq(
+      $.keyword_Static,
+      commaSep(
+        seq(
+          field('name', $.identifier),
+          optional(seq(choice(
+            token(/as/i),token(/As/i)),
+            field('type', choice(
+                $.primitive_type,
+                $.identifier
+            ))
+          ))
+        )
+      ),
+      /\r?\n/
+    ),
     // Add the enum_statement rule:
     enum_statement: $ => seq(
       // Optional visibility modifier (Public or Private)
@@ -317,7 +383,9 @@ module.exports = grammar({
 
     // Enhanced Assignment: lvalue = expression
     // Supports: x = 5, obj.prop = value, arr(1) = data, obj.method().prop = val
+    // `Let x = 5` is the same value assignment with the optional keyword
     assignment_statement: $ => prec.right(PREC.assignment, seq(
+      optional($.keyword_Let),
       field('target', $.lvalue),
       optional(/[ \t]+/),
       token('='),    
@@ -347,6 +415,7 @@ module.exports = grammar({
     indexed_access: $ => prec.left(PREC.member, seq(
       field('object', choice(
         $.identifier,
+        $.bracket_expression,
         $.property_access,
         $.indexed_access,
         $.function_call
@@ -356,10 +425,11 @@ module.exports = grammar({
       ')'
     )),
 
-    // MsgBox statement: MsgBox expr
+    // MsgBox statement: MsgBox prompt [, buttons [, title [, helpfile, context]]]
     msgbox_statement: $ => seq(
       token(/MsgBox/i),
       $.expression,
+      repeat(seq(',', optional($.expression))),
       /\r?\n/
     ),
 
@@ -399,20 +469,93 @@ module.exports = grammar({
       ),
       
       // Inline form: NO newline after Then, NO End If
+      // If x Then a = 1: b = 2 Else c = 3
       prec.right(seq(
         $.keyword_If,
         $.expression,
         $.keyword_Then,
         // NO newline - stays on same line
-        $.statement,
+        $.inline_statements,
         
         optional(seq(
           $.keyword_Else,
-          $.statement
-        ))
-        // NO End If allowed here
+          $.inline_statements
+        )),
+        // NO End If allowed here; the last line of a file may lack its newline
+        optional(/\r?\n/)
       ))
     ),   
+
+    // Colon-separated statements of a single-line If branch. They share
+    // the line, so each is the newline-free form of its statement.
+    inline_statements: $ => prec.right(seq(
+      $._inline_statement,
+      repeat(seq(':', $._inline_statement))
+    )),
+
+    _inline_statement: $ => choice(
+      alias($._inline_assignment, $.assignment_statement),
+      alias($._inline_set, $.set_statement),
+      alias($._inline_call, $.call_statement),
+      alias($._inline_exit, $.exit_statement),
+      alias($._inline_msgbox, $.msgbox_statement),
+      alias($._inline_resume, $.resume_statement),
+      alias($._inline_stop, $.stop_statement),
+      $.goto_statement,
+    ),
+
+    _inline_assignment: $ => seq(
+      optional($.keyword_Let),
+      field('target', $.lvalue),
+      '=',
+      field('value', $.expression)
+    ),
+
+    _inline_set: $ => seq(
+      token(/Set/i),
+      field('target', $.lvalue),
+      '=',
+      field('value', $.expression)
+    ),
+
+    _inline_call: $ => seq(
+      optional(token(/Call/i)),
+      field("function", choice($.identifier, $.qualified_identifier, $.with_member_access)),
+      optional(choice(
+        $.argument_list,
+        seq(" ", commaSep1($.expression))
+      ))
+    ),
+
+    _inline_exit: $ => seq(
+      token(/Exit/i),
+      field('exit_type', choice(
+        token.immediate(/For/i),
+        token.immediate(/Do/i),
+        token.immediate(/While/i),
+        token.immediate(/Sub/i),
+        token.immediate(/Function/i),
+        token.immediate(/Property/i),
+        token.immediate(/Select/i)
+      ))
+    ),
+
+    _inline_msgbox: $ => seq(
+      token(/MsgBox/i),
+      $.expression,
+      repeat(seq(',', optional($.expression)))
+    ),
+
+    _inline_resume: $ => prec.left(seq(
+      token(/Resume/i),
+      optional(choice(
+        token(/Next/i),
+        field('label', $.identifier)
+      ))
+    )),
+
+    _inline_stop: $ => token(/Stop/i),
+
     // For...Next statement: For counter = start To end [Step step]
     for_statement: $ => seq(
       token(/For/i),
@@ -501,15 +644,15 @@ module.exports = grammar({
       optional(/\r?\n/)
     ),
         
-   // Call statement: Call Func(args)
+   // Call statement: Call Func(args) or implicit `Func a, b`
     call_statement: $ => seq(
       optional(token(/Call/i)),           // allow `Call Foo()` or just `Foo()`
-      field("function", $.identifier),
+      field("function", choice($.identifier, $.qualified_identifier, $.with_member_access)),  // Foo, Module1.Foo or .Foo in a With block
       optional(choice(
         $.argument_list,             // e.g. Foo(a, b)
         seq(
           " ",                        // a space
-          $.expression                // then a single expression, e.g. Foo "bar"
+          commaSep1($.expression)     // then bare arguments, e.g. Foo "bar", 2
         )
       )),
       /\r?\n/                             // require statement-terminating newline
@@ -550,6 +693,13 @@ module.exports = grammar({
       )),
       $._statement_terminator
     ),
+
+    // Stop: break into the debugger (RuntimeConfig::stop_policy decides)
+    stop_statement: $ => seq(
+      token(/Stop/i),
+      /\r?\n/
+    ),
+
     on_error_statement: $ => prec.left(seq(
       token(/On/i), token(/Error/i),
       choice(
@@ -587,7 +737,10 @@ module.exports = grammar({
       $.date_literal,
       $.currency_literal,
       $.float_literal,
+      $.hex_literal,           // &HFF, &HFFFF&
+      $.octal_literal,         // &O17
       $.nothing_literal,       // Added: Nothing
+      $.bracket_expression,    // [A1], [SUM(A1:A3)]: Application.Evaluate
       $.identifier
     ),
     vba_builtin_constant: $ => choice(
@@ -830,6 +983,9 @@ module.exports = grammar({
     // Nothing literal
     nothing_literal: $ => token(/Nothing/i),
 
+    // Excel shorthand for Application.Evaluate: [A1].Value, [SUM(A1:A3)]
+    bracket_expression: $ => token(seq('[', /[^\]\r\n]+/, ']')),
+
     // Bare function calls: Name(args)
     function_call: $ => prec.left(PREC.call, seq(
       $.identifier,
@@ -906,6 +1062,7 @@ module.exports = grammar({
       'Integer',
       'Long',
       'LongLong',   // <-- added
+      'LongPtr',    // LongLong on 64-bit Office, Long on 32-bit
       'Object' ,
       'Single',
       'String',
@@ -969,15 +1126,17 @@ module.exports = grammar({
     keyword_ReDim:   $ => token(/ReDim/i),
     keyword_Preserve:$ => token(/Preserve/i),
 
-    // Identifiers must not conflict with reserved keywords
+    // Identifiers must not conflict with reserved keywords.
+    // A trailing type character (x%, s$, Left$) declares the type.
     identifier: $ => token(prec(-1,
       seq(
         /[A-Za-z]/,
-        repeat(/[A-Za-z0-9_]/)
+        repeat(/[A-Za-z0-9_]/),
+        optional(/[%&$#!@]/)
       )
     )),
 
-    integer_literal: _ => /\d+/,   
+    integer_literal: _ => /\d+[%&!#@^]?/,   
     byte_literal: $ => token(/\d{1,3}/),  // matches 0–255 in source       
     string_literal: $ => seq(
       '"',
@@ -1003,8 +1162,21 @@ module.exports = grammar({
       token.immediate(/[0-9]{1,2}\/[0-9]{1,2}\/[0-9]{2,4}( [0-9]{1,2}:[0-9]{2}(:[0-9]{2})?)?/),
       token.immediate('#')
     ),
-    float_literal: $ => token(/[0-9]+\.[0-9]+/),
+    float_literal: $ => token(choice(
+      /([0-9]+\.[0-9]+|\.[0-9]+)([eE][+-]?[0-9]+)?[!#@]?/,  // 1.5, .5, 2.5E-3#
+      /[0-9]+[eE][+-]?[0-9]+[!#@]?/                      // 1e-5
+    )),
+    // Optional type character: % Integer, & Long, ^ LongLong
+    hex_literal: _ => token(/&[Hh][0-9A-Fa-f]+[%&^]?/),
+    octal_literal: _ => token(/&[Oo][0-7]+[%&^]?/),
     // VBA comment support
+    // Line continuation: `_` followed by the line break it hides
+    line_continuation: $ => token(seq(
+      '_',
+      /[ \t]*/,
+      /\r?\n/
+    )),
+
     comment: $ => token(seq(
       "'",
       /[^\r\n]*/
@@ -0,0 +1,103 @@
+// Execution benchmarks for the interpreter and the VM
+//
+// This bench file covers:
+// - A million-iteration For loop
+// - String concatenation in a loop
+// - Range writes in a loop
+// - Recursive Function calls
+//
+// Every workload runs on both engines so the numbers can be compared
+// side by side. Parsing happens once, outside the measured closure.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use vba_utils::ast::{build_ast, Program};
+use vba_utils::vm::ProgramExecutor;
+use vba_utils::{Context, ExecutionEngine, RuntimeConfig};
+
+const ENGINES: [(&str, ExecutionEngine); 2] = [
+    ("vm", ExecutionEngine::Vm),
+    ("interpreter", ExecutionEngine::Interpreter),
+];
+
+fn parse(code: &str) -> Program {
+    build_ast(code)
+}
+
+/// Benchmark `code` on every engine under `name`
+fn bench_macro(c: &mut Criterion, name: &str, code: &str, samples: usize) {
+    let program = parse(code);
+    let mut group = c.benchmark_group(name);
+    group.sample_size(samples);
+
+    for (label, engine) in ENGINES {
+        group.bench_function(label, |b| {
+            b.iter_batched(
+                || {
+                    let ctx = Context::with_config(RuntimeConfig::builder().engine(engine).build());
+                    (ProgramExecutor::new(program.clone()), ctx)
+                },
+                |(executor, mut ctx)| {
+                    let _ = executor.execute_entrypoint(&mut ctx, "AutoOpen");
+                    ctx
+                },
+                BatchSize::PerIteration,
+            );
+        });
+    }
+    group.finish();
+}
+
+fn bench_for_loop(c: &mut Criterion) {
+    let code = "Sub AutoOpen()\n\
+                \x20   Dim i As Long, total As Long\n\
+                \x20   For i = 1 To 1000000\n\
+                \x20       total = total + 1\n\
+                \x20   Next i\n\
+                End Sub\n";
+    bench_macro(c, "for_loop_1m", code, 10);
+}
+
+fn bench_string_concat(c: &mut Criterion) {
+    let code = "Sub AutoOpen()\n\
+                \x20   Dim i As Long, s As String\n\
+                \x20   For i = 1 To 10000\n\
+                \x20       s = s & \"x\" & i\n\
+                \x20   Next i\n\
+                End Sub\n";
+    bench_macro(c, "string_concat_10k", code, 20);
+}
+
+fn bench_range_writes(c: &mut Criterion) {
+    let code = "Sub AutoOpen()\n\
+                \x20   Dim i As Long\n\
+                \x20   For i = 1 To 1000\n\
+                \x20       Range(\"A\" & i).Value = i\n\
+                \x20   Next i\n\
+                End Sub\n";
+    bench_macro(c, "range_writes_1k", code, 20);
+}
+
+fn bench_recursion(c: &mut Criterion) {
+    let code = "Function Fib(ByVal n As Long) As Long\n\
+                \x20   If n < 2 Then\n\
+                \x20       Fib = n\n\
+                \x20   Else\n\
+                \x20       Fib = Fib(n - 1) + Fib(n - 2)\n\
+                \x20   End If\n\
+                End Function\n\
+                \n\
+                Sub AutoOpen()\n\
+                \x20   Dim r As Long\n\
+                \x20   r = Fib(18)\n\
+                End Sub\n";
+    bench_macro(c, "recursive_fib_18", code, 10);
+}
+
+criterion_group!(
+    benches,
+    bench_for_loop,
+    bench_string_concat,
+    bench_range_writes,
+    bench_recursion
+);
+criterion_main!(benches);
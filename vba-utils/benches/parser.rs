@@ -0,0 +1,68 @@
+// Parser benchmarks
+//
+// This bench file covers:
+// - tree-sitter parse of a generated ~5k-line module
+// - build_ast of the same module (preprocess, normalize, parse and build)
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use tree_sitter::Parser;
+use vba_parser::language as tree_sitter_vba;
+use vba_utils::ast::build_ast;
+
+/// A module of `procs` procedures, each about 25 lines of typical macro code
+fn large_module(procs: usize) -> String {
+    let mut src = String::from("Option Explicit\n\n");
+    for p in 0..procs {
+        src.push_str(&format!(
+            "Function Compute{p}(ByVal n As Long) As Long\n\
+             \x20   Dim i As Long, total As Long\n\
+             \x20   Dim s As String\n\
+             \x20   total = 0\n\
+             \x20   For i = 1 To n\n\
+             \x20       If i Mod 2 = 0 Then\n\
+             \x20           total = total + i * 2\n\
+             \x20       ElseIf i Mod 3 = 0 Then\n\
+             \x20           total = total - i\n\
+             \x20       Else\n\
+             \x20           total = total + 1\n\
+             \x20       End If\n\
+             \x20   Next i\n\
+             \x20   Do While total > 1000\n\
+             \x20       total = total \\ 2\n\
+             \x20   Loop\n\
+             \x20   s = \"Result: \" & CStr(total)\n\
+             \x20   Select Case total\n\
+             \x20       Case 0\n\
+             \x20           s = s & \" (zero)\"\n\
+             \x20       Case Else\n\
+             \x20           s = s & \" (\" & Len(s) & \")\"\n\
+             \x20   End Select\n\
+             \x20   Compute{p} = total\n\
+             End Function\n\n"
+        ));
+    }
+    src
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let src = large_module(200);
+    let lines = src.lines().count() as u64;
+
+    let mut group = c.benchmark_group("parse_5k_lines");
+    group.throughput(Throughput::Elements(lines));
+
+    group.bench_function("tree_sitter", |b| {
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_vba()).expect("Failed to set VBA language");
+        b.iter(|| parser.parse(black_box(&src), None).expect("Failed to parse VBA code"));
+    });
+
+    group.bench_function("build_ast", |b| {
+        b.iter(|| build_ast(black_box(&src)));
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse);
+criterion_main!(benches);
@@ -0,0 +1,30 @@
+//! Fuzz the whole pipeline: parse → build_ast → execute.
+//!
+//! Any input must produce a Program (possibly with diagnostics) and run to
+//! completion or a runtime error, never a panic.
+//!
+//! Run with: `cargo +nightly fuzz run build_and_execute` from `vba-utils/`.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tree_sitter::Parser;
+use vba_parser::language as tree_sitter_vba;
+use vba_utils::ast::build_ast;
+use vba_utils::vm::ProgramExecutor;
+use vba_utils::Context;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(code) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let mut parser = Parser::new();
+    parser.set_language(tree_sitter_vba()).expect("Failed to set VBA language");
+    let Some(tree) = parser.parse(code, None) else {
+        return;
+    };
+
+    let program = build_ast(tree.root_node(), code);
+    let mut ctx = Context::new();
+    let _ = ProgramExecutor::new(program).execute(&mut ctx);
+});
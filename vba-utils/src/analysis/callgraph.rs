@@ -0,0 +1,668 @@
+// vba-utils/src/analysis/callgraph.rs
+
+//! Static call graph across one or more modules.
+//!
+//! A procedure "calls" another when its body mentions the other's name:
+//! a `Call` statement, a function call, a bare identifier (parameterless
+//! Functions and Subs), or `Module.Name`. A string literal holding a
+//! procedure name (`Application.Run "Name"`, `OnAction`) counts as well,
+//! so procedures that are only started dynamically are not reported dead.
+//!
+//! Names resolve case-insensitively, to the caller's own module first and
+//! to every other module otherwise; `Module.Name` resolves to that module
+//! only. `Private` procedures are skipped outside their module; naming one
+//! anyway is recorded in [`CallGraph::violations`]. String references are
+//! exempt, since `Application.Run` reaches them. A bare name that resolves
+//! to procedures of several other modules is recorded in
+//! [`CallGraph::ambiguities`]; VBA would not compile it.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write as _;
+
+use crate::ast::{AssignmentTarget, Expression, Program, Statement};
+use crate::vm::program::KNOWN_ENTRYPOINTS;
+
+/// A Sub, Function or Property procedure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcedureNode {
+    pub module: String,
+    pub name: String,
+    /// "Sub", "Function", "Property Get", "Property Let" or "Property Set"
+    pub kind: &'static str,
+    pub visibility: Visibility,
+}
+
+impl ProcedureNode {
+    /// `Module.Name`
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}", self.module, self.name)
+    }
+}
+
+/// Who may call a procedure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    /// `Public` (or no keyword): any module, and other projects
+    Public,
+    /// Public in an `Option Private Module` module: any module of this project
+    Project,
+    /// `Private`: its own module only
+    Private,
+}
+
+impl Visibility {
+    fn of(keyword: Option<&str>, private_module: bool) -> Self {
+        match keyword {
+            Some(k) if k.eq_ignore_ascii_case("private") => Visibility::Private,
+            _ if private_module => Visibility::Project,
+            _ => Visibility::Public,
+        }
+    }
+}
+
+/// A bare call that matches procedures of several other modules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmbiguousCall {
+    /// Index into `CallGraph::procedures`
+    pub caller: usize,
+    /// The name as called, lowercase
+    pub name: String,
+    /// Indices of the procedures it could mean
+    pub candidates: Vec<usize>,
+}
+
+/// A procedure naming a `Private` procedure of another module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisibilityViolation {
+    /// Indices into `CallGraph::procedures`
+    pub caller: usize,
+    pub callee: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CallGraph {
+    pub procedures: Vec<ProcedureNode>,
+    /// (caller, callee) as indices into `procedures`
+    pub edges: BTreeSet<(usize, usize)>,
+    /// Calls that only resolve to another module's Private procedures;
+    /// they get no edge
+    pub violations: Vec<VisibilityViolation>,
+    /// Bare calls to a name several other modules define; they get an edge
+    /// to every candidate
+    pub ambiguities: Vec<AmbiguousCall>,
+}
+
+impl CallGraph {
+    /// Build the graph of a single module named `Module1`.
+    pub fn from_program(program: &Program) -> Self {
+        CallGraph::build(&[("Module1", program)])
+    }
+
+    /// Build the graph of several modules, given as (module name, program).
+    pub fn build(modules: &[(&str, &Program)]) -> Self {
+        let mut graph = CallGraph::default();
+        let mut bodies = Vec::new();
+        for (module, program) in modules {
+            let private_module = program.statements.iter().any(|s| matches!(s, Statement::OptionPrivateModule));
+            for stmt in &program.statements {
+                if let Some((name, kind, keyword)) = procedure_header(stmt) {
+                    graph.procedures.push(ProcedureNode {
+                        module: module.to_string(),
+                        name: name.to_string(),
+                        kind,
+                        visibility: Visibility::of(keyword, private_module),
+                    });
+                    bodies.push(stmt);
+                }
+            }
+        }
+
+        let mut by_name: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, p) in graph.procedures.iter().enumerate() {
+            by_name.entry(p.name.to_lowercase()).or_default().push(i);
+        }
+        let module_names: BTreeSet<String> = modules.iter().map(|(m, _)| m.to_lowercase()).collect();
+
+        for (caller, stmt) in bodies.into_iter().enumerate() {
+            let mut names = Mentions::default();
+            for body in stmt.child_bodies() {
+                collect_names(body, &module_names, &mut names);
+            }
+            let own_module = graph.procedures[caller].module.clone();
+            // Module.Name: that module's procedure, if visible from here
+            for (module, name) in &names.qualified {
+                let Some(targets) = by_name.get(name) else { continue };
+                for &callee in targets.iter().filter(|&&t| graph.procedures[t].module.eq_ignore_ascii_case(module)) {
+                    let target = &graph.procedures[callee];
+                    if target.visibility == Visibility::Private && target.module != own_module {
+                        graph.violations.push(VisibilityViolation { caller, callee });
+                    } else {
+                        graph.edges.insert((caller, callee));
+                    }
+                }
+            }
+            let own_module = &own_module;
+            let direct = names.direct.iter().map(|n| (n, false));
+            for (name, by_string) in direct.chain(names.by_string.iter().map(|n| (n, true))) {
+                let Some(targets) = by_name.get(name) else { continue };
+                let (local, other): (Vec<usize>, Vec<usize>) =
+                    targets.iter().partition(|&&t| graph.procedures[t].module == *own_module);
+                let resolved = if !local.is_empty() {
+                    local
+                } else if by_string {
+                    other
+                } else {
+                    let (hidden, visible): (Vec<usize>, Vec<usize>) =
+                        other.into_iter().partition(|&t| graph.procedures[t].visibility == Visibility::Private);
+                    if visible.is_empty() {
+                        graph.violations.extend(hidden.into_iter().map(|callee| VisibilityViolation { caller, callee }));
+                    }
+                    let modules: BTreeSet<String> =
+                        visible.iter().map(|&t| graph.procedures[t].module.to_lowercase()).collect();
+                    if modules.len() > 1 {
+                        graph.ambiguities.push(AmbiguousCall { caller, name: name.clone(), candidates: visible.clone() });
+                    }
+                    visible
+                };
+                for callee in resolved {
+                    graph.edges.insert((caller, callee));
+                }
+            }
+        }
+        graph
+    }
+
+    /// Indices of procedures named `name` (any module, case-insensitive).
+    /// `Module.Name` restricts the match to that module.
+    pub fn find(&self, name: &str) -> Vec<usize> {
+        let (module, name) = match name.split_once('.') {
+            Some((m, n)) => (Some(m), n),
+            None => (None, name),
+        };
+        (0..self.procedures.len())
+            .filter(|&i| {
+                let p = &self.procedures[i];
+                p.name.eq_ignore_ascii_case(name) && module.is_none_or(|m| p.module.eq_ignore_ascii_case(m))
+            })
+            .collect()
+    }
+
+    /// Procedures called by `name`.
+    pub fn callees(&self, name: &str) -> Vec<&ProcedureNode> {
+        let from = self.find(name);
+        let to: BTreeSet<usize> = self.edges.iter().filter(|(a, _)| from.contains(a)).map(|(_, b)| *b).collect();
+        to.into_iter().map(|i| &self.procedures[i]).collect()
+    }
+
+    /// Procedures that call `name`.
+    pub fn callers(&self, name: &str) -> Vec<&ProcedureNode> {
+        let to = self.find(name);
+        let from: BTreeSet<usize> = self.edges.iter().filter(|(_, b)| to.contains(b)).map(|(a, _)| *a).collect();
+        from.into_iter().map(|i| &self.procedures[i]).collect()
+    }
+
+    /// One line per entry of `violations`.
+    pub fn violation_messages(&self) -> Vec<String> {
+        self.violations
+            .iter()
+            .map(|v| {
+                let (caller, callee) = (&self.procedures[v.caller], &self.procedures[v.callee]);
+                format!(
+                    "{} calls Private {} {}, which is not visible outside module {}",
+                    caller.qualified_name(),
+                    callee.kind,
+                    callee.qualified_name(),
+                    callee.module
+                )
+            })
+            .collect()
+    }
+
+    /// Names defined by procedures of more than one module, each with the
+    /// indices of its procedures. Property Get/Let/Set of one module share
+    /// a name without conflicting.
+    pub fn conflicts(&self) -> Vec<(String, Vec<usize>)> {
+        let mut by_name: std::collections::BTreeMap<String, Vec<usize>> = std::collections::BTreeMap::new();
+        for (i, p) in self.procedures.iter().enumerate() {
+            by_name.entry(p.name.to_lowercase()).or_default().push(i);
+        }
+        by_name
+            .into_values()
+            .filter(|group| {
+                let modules: BTreeSet<String> = group.iter().map(|&i| self.procedures[i].module.to_lowercase()).collect();
+                modules.len() > 1
+            })
+            .map(|group| (self.procedures[group[0]].name.clone(), group))
+            .collect()
+    }
+
+    /// One line per entry of `conflicts()`, then one per ambiguous call.
+    pub fn conflict_messages(&self) -> Vec<String> {
+        let names = |group: &[usize]| -> Vec<String> {
+            group.iter().map(|&i| format!("{} {}", self.procedures[i].kind, self.procedures[i].qualified_name())).collect()
+        };
+        let mut messages: Vec<String> = self
+            .conflicts()
+            .into_iter()
+            .map(|(name, group)| format!("{} is defined in several modules: {}", name, names(&group).join(", ")))
+            .collect();
+        messages.extend(self.ambiguities.iter().map(|a| {
+            format!(
+                "{} calls {} without a module name, which is ambiguous between {}",
+                self.procedures[a.caller].qualified_name(),
+                self.procedures[a.candidates[0]].name,
+                names(&a.candidates).join(" and ")
+            )
+        }));
+        messages
+    }
+
+    /// Procedures not reachable from any of `roots`.
+    pub fn unreachable_from(&self, roots: &[&str]) -> Vec<&ProcedureNode> {
+        let mut seen = vec![false; self.procedures.len()];
+        let mut stack: Vec<usize> = roots.iter().flat_map(|r| self.find(r)).collect();
+        while let Some(i) = stack.pop() {
+            if std::mem::replace(&mut seen[i], true) {
+                continue;
+            }
+            stack.extend(self.edges.iter().filter(|(a, _)| *a == i).map(|(_, b)| *b));
+        }
+        (0..self.procedures.len()).filter(|&i| !seen[i]).map(|i| &self.procedures[i]).collect()
+    }
+
+    /// Procedures not reachable from an entry point: the auto-run macros
+    /// the executor knows, plus every `Object_Event` style handler.
+    pub fn unreachable(&self) -> Vec<&ProcedureNode> {
+        let handlers: Vec<&str> = self
+            .procedures
+            .iter()
+            .filter(|p| p.name.contains('_'))
+            .map(|p| p.name.as_str())
+            .collect();
+        let roots: Vec<&str> = KNOWN_ENTRYPOINTS.iter().copied().chain(handlers).collect();
+        self.unreachable_from(&roots)
+    }
+
+    /// Groups of mutually recursive procedures, including procedures that
+    /// call themselves. Each group is sorted; groups are in index order.
+    pub fn cycles(&self) -> Vec<Vec<&ProcedureNode>> {
+        let n = self.procedures.len();
+        let mut adjacency = vec![Vec::new(); n];
+        for (a, b) in &self.edges {
+            adjacency[*a].push(*b);
+        }
+
+        let mut tarjan = Tarjan {
+            adjacency: &adjacency,
+            index: vec![None; n],
+            low: vec![0; n],
+            on_stack: vec![false; n],
+            stack: Vec::new(),
+            next: 0,
+            groups: Vec::new(),
+        };
+        for v in 0..n {
+            if tarjan.index[v].is_none() {
+                tarjan.visit(v);
+            }
+        }
+
+        let mut groups: Vec<Vec<usize>> = tarjan
+            .groups
+            .into_iter()
+            .filter(|g| g.len() > 1 || self.edges.contains(&(g[0], g[0])))
+            .map(|mut g| {
+                g.sort_unstable();
+                g
+            })
+            .collect();
+        groups.sort();
+        groups.into_iter().map(|g| g.into_iter().map(|i| &self.procedures[i]).collect()).collect()
+    }
+
+    /// Graphviz DOT; unreachable procedures are drawn dashed.
+    pub fn to_dot(&self) -> String {
+        let dead: Vec<&ProcedureNode> = self.unreachable();
+        let mut out = String::from("digraph calls {\n    node [shape=box];\n");
+        for (i, p) in self.procedures.iter().enumerate() {
+            let style = if dead.contains(&p) { ", style=dashed" } else { "" };
+            let _ = writeln!(out, "    p{} [label=\"{}\\n{}\"{}];", i, escape(&p.qualified_name()), p.kind, style);
+        }
+        for (a, b) in &self.edges {
+            let _ = writeln!(out, "    p{} -> p{};", a, b);
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// JSON with `procedures`, `calls`, `unreachable`, `cycles`,
+    /// `violations` and `conflicts`; procedures are referred to by
+    /// qualified name.
+    pub fn to_json(&self) -> String {
+        let quote = |p: &ProcedureNode| format!("\"{}\"", escape(&p.qualified_name()));
+        let list = |items: Vec<String>| format!("[{}]", items.join(", "));
+
+        let procedures = list(
+            self.procedures
+                .iter()
+                .map(|p| {
+                    format!(
+                        "{{\"module\": \"{}\", \"name\": \"{}\", \"kind\": \"{}\", \"visibility\": \"{:?}\"}}",
+                        escape(&p.module),
+                        escape(&p.name),
+                        p.kind,
+                        p.visibility
+                    )
+                })
+                .collect(),
+        );
+        let calls = list(
+            self.edges
+                .iter()
+                .map(|(a, b)| format!("[{}, {}]", quote(&self.procedures[*a]), quote(&self.procedures[*b])))
+                .collect(),
+        );
+        let unreachable = list(self.unreachable().into_iter().map(quote).collect());
+        let cycles = list(self.cycles().into_iter().map(|g| list(g.into_iter().map(quote).collect())).collect());
+        let violations = list(
+            self.violations
+                .iter()
+                .map(|v| format!("[{}, {}]", quote(&self.procedures[v.caller]), quote(&self.procedures[v.callee])))
+                .collect(),
+        );
+        let conflicts = list(
+            self.conflicts()
+                .into_iter()
+                .map(|(_, group)| list(group.into_iter().map(|i| quote(&self.procedures[i])).collect()))
+                .collect(),
+        );
+
+        format!(
+            "{{\n  \"procedures\": {},\n  \"calls\": {},\n  \"unreachable\": {},\n  \"cycles\": {},\n  \"violations\": {},\n  \"conflicts\": {}\n}}\n",
+            procedures, calls, unreachable, cycles, violations, conflicts
+        )
+    }
+}
+
+struct Tarjan<'a> {
+    adjacency: &'a [Vec<usize>],
+    index: Vec<Option<usize>>,
+    low: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next: usize,
+    groups: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, v: usize) {
+        self.index[v] = Some(self.next);
+        self.low[v] = self.next;
+        self.next += 1;
+        self.stack.push(v);
+        self.on_stack[v] = true;
+
+        for &w in &self.adjacency[v] {
+            match self.index[w] {
+                None => {
+                    self.visit(w);
+                    self.low[v] = self.low[v].min(self.low[w]);
+                }
+                Some(iw) if self.on_stack[w] => self.low[v] = self.low[v].min(iw),
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.low[v]) == self.index[v] {
+            let mut group = Vec::new();
+            while let Some(w) = self.stack.pop() {
+                self.on_stack[w] = false;
+                group.push(w);
+                if w == v {
+                    break;
+                }
+            }
+            self.groups.push(group);
+        }
+    }
+}
+
+/// Name, kind and visibility keyword of a procedure definition.
+fn procedure_header(stmt: &Statement) -> Option<(&str, &'static str, Option<&str>)> {
+    let (name, kind, visibility) = match stmt {
+        Statement::Subroutine { name, visibility, .. } => (name, "Sub", visibility),
+        Statement::Function { name, visibility, .. } => (name, "Function", visibility),
+        Statement::PropertyGet { name, visibility, .. } => (name, "Property Get", visibility),
+        Statement::PropertyLet { name, visibility, .. } => (name, "Property Let", visibility),
+        Statement::PropertySet { name, visibility, .. } => (name, "Property Set", visibility),
+        _ => return None,
+    };
+    Some((name, kind, visibility.as_deref()))
+}
+
+/// Lowercase names a procedure body mentions, split by how they appear.
+#[derive(Default)]
+struct Mentions {
+    /// Identifiers and calls
+    direct: BTreeSet<String>,
+    /// `Module.Name` of a known module, as (module, name)
+    qualified: BTreeSet<(String, String)>,
+    /// String literals that look like procedure names
+    by_string: BTreeSet<String>,
+}
+
+impl Mentions {
+    fn insert(&mut self, name: String) -> bool {
+        self.direct.insert(name)
+    }
+}
+
+/// Lowercase names read anywhere in `stmts`. Assignment targets are left
+/// out: `Name = value` inside a Function sets its result.
+fn collect_names(stmts: &[Statement], modules: &BTreeSet<String>, out: &mut Mentions) {
+    for stmt in stmts {
+        match stmt {
+            Statement::Call { function, args } => {
+                let function = function.to_lowercase();
+                // `Call Module.Proc`
+                match function.split_once('.') {
+                    Some((module, name)) if modules.contains(module) => {
+                        out.qualified.insert((module.to_string(), name.to_string()))
+                    }
+                    _ => out.insert(function),
+                };
+                args.iter().for_each(|a| collect_expr(a, modules, out));
+            }
+            Statement::Set { expr, .. } => collect_expr(expr, modules, out),
+            Statement::Assignment { lvalue, rvalue, .. } => {
+                match lvalue {
+                    AssignmentTarget::PropertyAccess { object, .. } | AssignmentTarget::DefaultMember { object } => {
+                        collect_expr(object, modules, out)
+                    }
+                    AssignmentTarget::WithMethodCall { args, .. } => {
+                        args.iter().for_each(|a| collect_expr(a, modules, out))
+                    }
+                    _ => {}
+                }
+                collect_expr(rvalue, modules, out);
+            }
+            Statement::Expression(expr) => collect_expr(expr, modules, out),
+            Statement::MsgBox { expr, buttons, title } => {
+                collect_expr(expr, modules, out);
+                [buttons, title].into_iter().flatten().for_each(|e| collect_expr(e, modules, out));
+            }
+            Statement::If { condition, else_if, .. } => {
+                collect_expr(condition, modules, out);
+                else_if.iter().for_each(|(c, _)| collect_expr(c, modules, out));
+            }
+            Statement::For(f) => {
+                for e in [Some(&f.start), Some(&f.end), f.step.as_ref()].into_iter().flatten() {
+                    collect_expr(e, modules, out);
+                }
+            }
+            Statement::DoWhile(d) => d.condition.iter().for_each(|c| collect_expr(c, modules, out)),
+            Statement::With { object, .. } => collect_expr(object, modules, out),
+            Statement::ReDim { variables, .. } => {
+                for bound in variables.iter().flat_map(|v| &v.bounds) {
+                    bound.lower.iter().for_each(|e| collect_expr(e, modules, out));
+                    collect_expr(&bound.upper, modules, out);
+                }
+            }
+            _ => {}
+        }
+        for body in stmt.child_bodies() {
+            collect_names(body, modules, out);
+        }
+    }
+}
+
+fn collect_expr(expr: &Expression, modules: &BTreeSet<String>, out: &mut Mentions) {
+    match expr {
+        Expression::Identifier(name) => {
+            out.insert(name.to_lowercase());
+        }
+        // A procedure name in a string: Application.Run "Name", OnAction
+        Expression::String(s) if is_identifier(s) => {
+            out.by_string.insert(s.to_lowercase());
+        }
+        Expression::BinaryOp { left, right, .. } => {
+            collect_expr(left, modules, out);
+            collect_expr(right, modules, out);
+        }
+        Expression::UnaryOp { expr, .. } | Expression::Parenthesized(expr) => collect_expr(expr, modules, out),
+        Expression::FunctionCall { function, args } => {
+            collect_expr(function, modules, out);
+            args.iter().for_each(|a| collect_expr(a, modules, out));
+        }
+        Expression::PropertyAccess { obj, property } => {
+            // Module.Proc
+            if let Expression::Identifier(module) = obj.as_ref() {
+                if modules.contains(&module.to_lowercase()) {
+                    out.qualified.insert((module.to_lowercase(), property.to_lowercase()));
+                }
+            }
+            collect_expr(obj, modules, out);
+        }
+        Expression::WithMethodCall { args, .. } => args.iter().for_each(|a| collect_expr(a, modules, out)),
+        _ => {}
+    }
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+pub(super) fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sub(name: &str, body: Vec<Statement>) -> Statement {
+        Statement::Subroutine { visibility: None, name: name.into(), params: vec![], body: body.into() }
+    }
+
+    fn call(name: &str) -> Statement {
+        Statement::Call { function: name.into(), args: vec![] }
+    }
+
+    fn program(statements: Vec<Statement>) -> Program {
+        Program { statements, diagnostics: vec![] }
+    }
+
+    #[test]
+    fn test_edges_unreachable_and_cycles() {
+        let main = program(vec![
+            sub("AutoOpen", vec![call("Ping")]),
+            sub("Ping", vec![call("pong")]),
+            sub("Pong", vec![call("Ping")]),
+            sub("Orphan", vec![call("Orphan")]),
+        ]);
+        let graph = CallGraph::from_program(&main);
+
+        assert_eq!(graph.callees("AutoOpen")[0].name, "Ping");
+        assert_eq!(graph.callers("Ping").len(), 2);
+        let dead: Vec<&str> = graph.unreachable().iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(dead, vec!["Orphan"]);
+
+        let cycles: Vec<Vec<&str>> =
+            graph.cycles().iter().map(|g| g.iter().map(|p| p.name.as_str()).collect()).collect();
+        assert_eq!(cycles, vec![vec!["Ping", "Pong"], vec!["Orphan"]]);
+    }
+
+    #[test]
+    fn test_resolves_across_modules_and_strings() {
+        let a = program(vec![sub("AutoOpen", vec![
+            call("Helper"),
+            Statement::Expression(Expression::FunctionCall {
+                function: Box::new(Expression::PropertyAccess {
+                    obj: Box::new(Expression::Identifier("Application".into())),
+                    property: "Run".into(),
+                }),
+                args: vec![Expression::String("Later".into())],
+            }),
+        ])]);
+        let b = program(vec![sub("Helper", vec![]), sub("Later", vec![]), sub("Unused", vec![])]);
+        let graph = CallGraph::build(&[("ModA", &a), ("ModB", &b)]);
+
+        let called: Vec<String> = graph.callees("ModA.AutoOpen").iter().map(|p| p.qualified_name()).collect();
+        assert_eq!(called, vec!["ModB.Helper", "ModB.Later"]);
+        assert_eq!(graph.unreachable().len(), 1);
+
+        let json = graph.to_json();
+        assert!(json.contains("\"unreachable\": [\"ModB.Unused\"]"));
+        assert!(graph.to_dot().contains("style=dashed"));
+    }
+
+    #[test]
+    fn test_private_procedures_stay_in_their_module() {
+        let private_sub = |name: &str| Statement::Subroutine {
+            visibility: Some("Private".into()),
+            name: name.into(),
+            params: vec![],
+            body: Vec::new().into(),
+        };
+        let a = program(vec![sub("AutoOpen", vec![
+            call("Secret"),
+            call("ModB.Shared"),
+            Statement::Expression(Expression::String("Secret".into())),
+        ])]);
+        let b = program(vec![
+            Statement::OptionPrivateModule,
+            private_sub("Secret"),
+            sub("Shared", vec![call("Secret")]),
+        ]);
+        let graph = CallGraph::build(&[("ModA", &a), ("ModB", &b)]);
+
+        let kinds: Vec<Visibility> = graph.procedures.iter().map(|p| p.visibility).collect();
+        assert_eq!(kinds, vec![Visibility::Public, Visibility::Private, Visibility::Project]);
+        assert_eq!(graph.violation_messages(), vec![
+            "ModA.AutoOpen calls Private Sub ModB.Secret, which is not visible outside module ModB".to_string()
+        ]);
+        // The string reference (Application.Run) still reaches it
+        assert_eq!(graph.callers("ModB.Secret").len(), 2);
+        assert!(graph.to_json().contains("\"violations\": [[\"ModA.AutoOpen\", \"ModB.Secret\"]]"));
+    }
+
+    #[test]
+    fn test_qualified_calls_and_name_conflicts() {
+        let a = program(vec![sub("Init", vec![]), sub("AutoOpen", vec![call("Init"), call("ModB.Init")])]);
+        let b = program(vec![sub("Init", vec![])]);
+        let c = program(vec![sub("Start", vec![call("Init")])]);
+        let graph = CallGraph::build(&[("ModA", &a), ("ModB", &b), ("ModC", &c)]);
+
+        let called: Vec<String> = graph.callees("ModA.AutoOpen").iter().map(|p| p.qualified_name()).collect();
+        assert_eq!(called, vec!["ModA.Init", "ModB.Init"]);
+        assert_eq!(graph.callees("ModC.Start").len(), 2);
+        assert_eq!(graph.conflicts(), vec![("Init".to_string(), vec![0, 2])]);
+        assert_eq!(graph.conflict_messages(), vec![
+            "Init is defined in several modules: Sub ModA.Init, Sub ModB.Init".to_string(),
+            "ModC.Start calls Init without a module name, which is ambiguous between Sub ModA.Init and Sub ModB.Init".to_string(),
+        ]);
+        assert!(graph.to_json().contains("\"conflicts\": [[\"ModA.Init\", \"ModB.Init\"]]"));
+    }
+}
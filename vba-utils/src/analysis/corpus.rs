@@ -0,0 +1,233 @@
+// vba-utils/src/analysis/corpus.rs
+
+//! Parser conformance over a directory of real-world macros.
+//!
+//! [`check_corpus`] parses every VBA module under a directory (recursively;
+//! `.bas`, `.cls`, `.frm` and `.vba` files) and keeps each file's
+//! [`BuildReport`]. The [`CorpusReport`] aggregates what the grammar and
+//! the AST builder could not handle, per category and node kind, so the
+//! most frequent gaps can be fixed first; `to_json` is the summary for CI
+//! dashboards.
+//!
+//! Files are read as UTF-8 with invalid bytes replaced, since exported
+//! modules are usually in the ANSI code page. Line coverage counts a line
+//! as covered unless a skipped node starts or ends on it.
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use super::callgraph::escape;
+use crate::ast::{parse_source_with_report, BuildReport};
+use crate::preprocess::default_constants;
+
+/// Extensions of the files [`check_corpus`] parses
+pub const CORPUS_EXTENSIONS: [&str; 4] = ["bas", "cls", "frm", "vba"];
+
+/// One parsed file
+#[derive(Debug, Clone)]
+pub struct FileResult {
+    pub path: PathBuf,
+    /// Physical lines in the file
+    pub lines: usize,
+    pub report: BuildReport,
+    /// Set when the file could not be read or parsed at all
+    pub error: Option<String>,
+}
+
+impl FileResult {
+    /// Nothing skipped and no error
+    pub fn is_clean(&self) -> bool {
+        self.error.is_none() && self.report.is_complete()
+    }
+
+    /// Lines a skipped node starts or ends on
+    pub fn skipped_lines(&self) -> usize {
+        let lines: BTreeSet<usize> =
+            self.report.skipped.iter().flat_map(|n| [n.span.line, n.span.end_line]).collect();
+        lines.len().min(self.lines)
+    }
+}
+
+/// Results for every file of a corpus, in path order
+#[derive(Debug, Clone, Default)]
+pub struct CorpusReport {
+    pub files: Vec<FileResult>,
+}
+
+impl CorpusReport {
+    pub fn clean_files(&self) -> usize {
+        self.files.iter().filter(|f| f.is_clean()).count()
+    }
+
+    pub fn failed_files(&self) -> usize {
+        self.files.iter().filter(|f| f.error.is_some()).count()
+    }
+
+    /// Skipped nodes of all files together
+    pub fn totals(&self) -> BuildReport {
+        let mut total = BuildReport::default();
+        for file in &self.files {
+            total.merge(file.report.clone());
+        }
+        total
+    }
+
+    /// Share of lines without a skipped node, 0.0 to 1.0 (1.0 when empty)
+    pub fn line_coverage(&self) -> f64 {
+        let lines: usize = self.files.iter().filter(|f| f.error.is_none()).map(|f| f.lines).sum();
+        let skipped: usize = self.files.iter().map(FileResult::skipped_lines).sum();
+        if lines == 0 {
+            return 1.0;
+        }
+        1.0 - skipped as f64 / lines as f64
+    }
+
+    /// JSON with the totals (`files`, `clean`, `failed`, `lines`,
+    /// `line_coverage`), `by_category`, `by_kind` (most frequent first) and
+    /// per-file counts.
+    pub fn to_json(&self) -> String {
+        let totals = self.totals();
+        let by_category = totals
+            .by_category()
+            .into_iter()
+            .map(|(category, count)| format!("\"{:?}\": {}", category, count))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let by_kind = totals
+            .by_kind()
+            .into_iter()
+            .map(|(category, kind, count)| {
+                format!("{{\"category\": \"{:?}\", \"kind\": \"{}\", \"count\": {}}}", category, escape(&kind), count)
+            })
+            .collect::<Vec<_>>()
+            .join(",\n    ");
+        let files = self
+            .files
+            .iter()
+            .map(|f| {
+                let error = f.error.as_ref().map_or("null".to_string(), |e| format!("\"{}\"", escape(e)));
+                format!(
+                    "{{\"path\": \"{}\", \"lines\": {}, \"skipped\": {}, \"skipped_lines\": {}, \"error\": {}}}",
+                    escape(&f.path.display().to_string()),
+                    f.lines,
+                    f.report.skipped.len(),
+                    f.skipped_lines(),
+                    error
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",\n    ");
+        format!(
+            "{{\n  \"files\": {},\n  \"clean\": {},\n  \"failed\": {},\n  \"lines\": {},\n  \"line_coverage\": {:.4},\n  \"by_category\": {{{}}},\n  \"by_kind\": [\n    {}\n  ],\n  \"per_file\": [\n    {}\n  ]\n}}\n",
+            self.files.len(),
+            self.clean_files(),
+            self.failed_files(),
+            self.files.iter().map(|f| f.lines).sum::<usize>(),
+            self.line_coverage(),
+            by_category,
+            by_kind,
+            files
+        )
+    }
+
+    /// Human-readable summary: totals, then the node kinds by frequency
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{} file(s): {} clean, {} with skipped nodes, {} failed; {:.1}% of lines covered",
+            self.files.len(),
+            self.clean_files(),
+            self.files.len() - self.clean_files() - self.failed_files(),
+            self.failed_files(),
+            self.line_coverage() * 100.0
+        );
+        for (category, kind, count) in self.totals().by_kind() {
+            let _ = writeln!(out, "  {:>6}  {:?} {}", count, category, kind);
+        }
+        out
+    }
+}
+
+/// Parse one module's source
+pub fn check_source(path: impl Into<PathBuf>, source: &str) -> FileResult {
+    let path = path.into();
+    let lines = source.lines().count();
+    match parse_source_with_report(source, &default_constants()) {
+        Ok((_, report)) => FileResult { path, lines, report, error: None },
+        Err(e) => FileResult { path, lines, report: BuildReport::default(), error: Some(e.to_string()) },
+    }
+}
+
+/// Parse every module under `dir`; only a missing or unreadable `dir`
+/// fails, a file that cannot be read is recorded with its error
+pub fn check_corpus(dir: &Path) -> std::io::Result<CorpusReport> {
+    let mut paths = Vec::new();
+    collect_modules(dir, &mut paths)?;
+    paths.sort();
+    let files = paths
+        .into_iter()
+        .map(|path| match std::fs::read(&path) {
+            Ok(bytes) => check_source(path, &String::from_utf8_lossy(&bytes)),
+            Err(e) => FileResult { path, lines: 0, report: BuildReport::default(), error: Some(e.to_string()) },
+        })
+        .collect();
+    Ok(CorpusReport { files })
+}
+
+fn collect_modules(dir: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_modules(&path, out)?;
+        } else if path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| CORPUS_EXTENSIONS.iter().any(|x| x.eq_ignore_ascii_case(e)))
+        {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{SkipCategory, SkippedNode, Span};
+
+    fn skipped(category: SkipCategory, kind: &str, line: usize) -> SkippedNode {
+        let span = Span { line, column: 1, end_line: line, end_column: 10 };
+        SkippedNode { category, kind: kind.into(), span, text: "x".into() }
+    }
+
+    #[test]
+    fn test_corpus_summary() {
+        let gaps = BuildReport {
+            skipped: vec![
+                skipped(SkipCategory::Syntax, "ERROR", 3),
+                skipped(SkipCategory::Statement, "implements_statement", 5),
+                skipped(SkipCategory::Statement, "implements_statement", 5),
+            ],
+        };
+        let report = CorpusReport {
+            files: vec![
+                FileResult { path: "a.bas".into(), lines: 10, report: BuildReport::default(), error: None },
+                FileResult { path: "b\\c.cls".into(), lines: 10, report: gaps, error: None },
+                FileResult { path: "d.frm".into(), lines: 0, report: BuildReport::default(), error: Some("denied".into()) },
+            ],
+        };
+
+        assert_eq!((report.clean_files(), report.failed_files()), (1, 1));
+        assert_eq!(report.files[1].skipped_lines(), 2);
+        assert!((report.line_coverage() - 0.9).abs() < 1e-9);
+        let json = report.to_json();
+        assert!(json.contains("\"line_coverage\": 0.9000"), "{}", json);
+        assert!(json.contains("\"by_category\": {\"Syntax\": 1, \"Statement\": 2}"), "{}", json);
+        assert!(json.contains("{\"category\": \"Statement\", \"kind\": \"implements_statement\", \"count\": 2}"), "{}", json);
+        assert!(json.contains("\"path\": \"b\\\\c.cls\""), "{}", json);
+        assert!(json.contains("\"error\": \"denied\""), "{}", json);
+        assert!(report.to_text().starts_with("3 file(s): 1 clean, 1 with skipped nodes, 1 failed; 90.0%"));
+    }
+}
@@ -0,0 +1,7 @@
+//! Static analysis over parsed programs (no execution).
+
+pub mod callgraph;
+pub mod corpus;
+
+pub use callgraph::{AmbiguousCall, CallGraph, ProcedureNode, Visibility, VisibilityViolation};
+pub use corpus::{check_corpus, CorpusReport, FileResult};
@@ -1,10 +1,321 @@
 
+use std::rc::Rc;
+
 use tree_sitter::Node;
 
+use crate::error::VbaError;
+use crate::interpreter::compiled::CompiledCache;
+use crate::preprocess::CompilerConstants;
+use crate::symbol::Symbol;
+
 /// A whole VBA program.
 #[derive(Debug, Clone)]
 pub struct Program {
     pub statements: Vec<Statement>,
+    /// Syntax errors and statements that could not be built. Code covered by
+    /// a diagnostic is kept as `Statement::Unparsed` and skipped at run time.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Program {
+    /// True when every statement in the source was understood.
+    pub fn is_fully_parsed(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// The `Attribute` lines of an exported module, including those inside
+    /// procedures (`Attribute Main.VB_Description = "..."`).
+    pub fn attributes(&self) -> ModuleAttributes {
+        let mut entries = Vec::new();
+        for stmt in &self.statements {
+            match stmt {
+                Statement::Attribute(attribute) => entries.push(attribute.clone()),
+                Statement::Subroutine { body, .. }
+                | Statement::Function { body, .. }
+                | Statement::PropertyGet { body, .. }
+                | Statement::PropertyLet { body, .. }
+                | Statement::PropertySet { body, .. } => entries.extend(body.iter().filter_map(|s| match s {
+                    Statement::Attribute(attribute) => Some(attribute.clone()),
+                    _ => None,
+                })),
+                _ => {}
+            }
+        }
+        ModuleAttributes { entries }
+    }
+}
+
+/// `Attribute [Target.]Name = Value[, Value]`, as exported in .bas/.cls/.frm files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribute {
+    /// The procedure or member for `Attribute Main.VB_Description`; None
+    /// for module attributes
+    pub target: Option<String>,
+    pub name: String,
+    /// Values with string quotes removed; other values as written
+    pub values: Vec<String>,
+}
+
+/// Every attribute of one module, in source order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModuleAttributes {
+    pub entries: Vec<Attribute>,
+}
+
+impl ModuleAttributes {
+    /// First value of an attribute; `target` None for module attributes
+    pub fn get(&self, target: Option<&str>, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|a| {
+                a.name.eq_ignore_ascii_case(name)
+                    && match (&a.target, target) {
+                        (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+                        (None, None) => true,
+                        _ => false,
+                    }
+            })
+            .and_then(|a| a.values.first())
+            .map(String::as_str)
+    }
+
+    /// `VB_Name`: the module or class name the file was exported from
+    pub fn module_name(&self) -> Option<&str> {
+        self.get(None, "VB_Name")
+    }
+
+    /// `VB_PredeclaredId = True`: the class has a default instance
+    pub fn predeclared_id(&self) -> bool {
+        self.flag("VB_PredeclaredId")
+    }
+
+    /// `VB_Exposed = True`: the class is visible to other projects
+    pub fn exposed(&self) -> bool {
+        self.flag("VB_Exposed")
+    }
+
+    /// `VB_Description` of a procedure
+    pub fn description(&self, procedure: &str) -> Option<&str> {
+        self.get(Some(procedure), "VB_Description")
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.get(None, name).is_some_and(|v| v.eq_ignore_ascii_case("True") || v == "-1")
+    }
+}
+
+/// Source location of a node (1-based lines and columns).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl Span {
+    fn from_node(node: Node) -> Self {
+        let start = node.start_position();
+        let end = node.end_position();
+        Span {
+            line: start.row + 1,
+            column: start.column + 1,
+            end_line: end.row + 1,
+            end_column: end.column + 1,
+        }
+    }
+}
+
+/// A recoverable problem found while building the AST.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub span: Span,
+}
+
+/// Why a parse-tree node did not make it into the AST
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SkipCategory {
+    /// ERROR or MISSING node: the grammar could not parse the text
+    Syntax,
+    /// Parsed, but no Statement could be built; kept as `Statement::Unparsed`
+    Statement,
+    /// Parsed, but no Expression could be built; dropped from its statement
+    Expression,
+}
+
+/// One node [`build_ast`] could not convert
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedNode {
+    pub category: SkipCategory,
+    /// Grammar node kind (`ERROR`, `implements_statement`, ...)
+    pub kind: String,
+    pub span: Span,
+    /// Source text of the node
+    pub text: String,
+}
+
+/// Everything a build skipped, from [`build_ast_with_report`]. Reports for
+/// several files can be merged to measure parser coverage over a corpus.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuildReport {
+    pub skipped: Vec<SkippedNode>,
+}
+
+impl BuildReport {
+    /// True when nothing was skipped
+    pub fn is_complete(&self) -> bool {
+        self.skipped.is_empty()
+    }
+
+    /// Skipped nodes per category
+    pub fn by_category(&self) -> std::collections::BTreeMap<SkipCategory, usize> {
+        let mut counts = std::collections::BTreeMap::new();
+        for node in &self.skipped {
+            *counts.entry(node.category).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Skipped nodes per category and node kind, most frequent first
+    pub fn by_kind(&self) -> Vec<(SkipCategory, String, usize)> {
+        let mut counts: std::collections::BTreeMap<(SkipCategory, &str), usize> = std::collections::BTreeMap::new();
+        for node in &self.skipped {
+            *counts.entry((node.category, node.kind.as_str())).or_insert(0) += 1;
+        }
+        let mut counts: Vec<_> = counts.into_iter().map(|((category, kind), n)| (category, kind.to_string(), n)).collect();
+        counts.sort_by_key(|c| std::cmp::Reverse(c.2));
+        counts
+    }
+
+    /// Add another file's skipped nodes
+    pub fn merge(&mut self, other: BuildReport) {
+        self.skipped.extend(other.skipped);
+    }
+}
+
+thread_local! {
+    /// Collects skipped nodes while [`build_ast_with_report`] runs
+    static BUILD_REPORT: std::cell::RefCell<Option<BuildReport>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Note a node the AST will not contain, if a report is being collected.
+/// A node already noted (by the builder that failed on it) counts once.
+fn report_skipped(category: SkipCategory, node: Node, source: &str) {
+    BUILD_REPORT.with(|report| {
+        if let Some(report) = report.borrow_mut().as_mut() {
+            let span = Span::from_node(node);
+            if report.skipped.last().is_some_and(|last| last.category == category && last.span == span) {
+                return;
+            }
+            report.skipped.push(SkippedNode {
+                category,
+                kind: node.kind().to_string(),
+                span,
+                text: source_text(source, node).trim_end().to_string(),
+            });
+        }
+    });
+}
+
+/// A statement list together with the source line of each statement.
+///
+/// Bodies are shared (VM frames hold a clone, never a copy). Lines are
+/// 1-based; 0 means the statement was built without a source node.
+#[derive(Debug, Clone, Default)]
+pub struct Body {
+    stmts: Rc<[Statement]>,
+    lines: Rc<[usize]>,
+}
+
+impl Body {
+    pub fn new(stmts: Vec<Statement>, lines: Vec<usize>) -> Self {
+        debug_assert_eq!(stmts.len(), lines.len());
+        Body { stmts: stmts.into(), lines: lines.into() }
+    }
+
+    /// Source line of the statement at `pc`, if known.
+    pub fn line(&self, pc: usize) -> Option<usize> {
+        self.lines.get(pc).copied().filter(|&l| l > 0)
+    }
+
+    pub fn lines(&self) -> &[usize] {
+        &self.lines
+    }
+
+    /// Whether both are the same shared list (not just equal statements).
+    pub(crate) fn ptr_eq(&self, other: &Body) -> bool {
+        Rc::ptr_eq(&self.stmts, &other.stmts)
+    }
+
+    /// Mutable access while the body is still unshared (AST building).
+    fn parts_mut(&mut self) -> Option<(&mut [Statement], &mut [usize])> {
+        Some((Rc::get_mut(&mut self.stmts)?, Rc::get_mut(&mut self.lines)?))
+    }
+}
+
+impl std::ops::Deref for Body {
+    type Target = [Statement];
+
+    fn deref(&self) -> &[Statement] {
+        &self.stmts
+    }
+}
+
+impl From<Vec<Statement>> for Body {
+    fn from(stmts: Vec<Statement>) -> Self {
+        let lines = vec![0; stmts.len()];
+        Body::new(stmts, lines)
+    }
+}
+
+impl From<&[Statement]> for Body {
+    fn from(stmts: &[Statement]) -> Self {
+        stmts.to_vec().into()
+    }
+}
+
+impl IntoIterator for Body {
+    type Item = Statement;
+    type IntoIter = std::vec::IntoIter<Statement>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Vec::from(&*self.stmts).into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Body {
+    type Item = &'a Statement;
+    type IntoIter = std::slice::Iter<'a, Statement>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.stmts.iter()
+    }
+}
+
+/// Collects statements and their lines while a body is being built.
+#[derive(Default)]
+struct BodyBuilder {
+    stmts: Vec<Statement>,
+    lines: Vec<usize>,
+}
+
+impl BodyBuilder {
+    /// Build `node` (a `statement` wrapper) and append it.
+    fn push_node(&mut self, node: Node, source: &str) {
+        if let Some(stmt) = build_statement(node, source) {
+            self.stmts.push(stmt);
+            self.lines.push(Span::from_node(node).line);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.stmts.len()
+    }
+
+    fn finish(self) -> Body {
+        Body::new(self.stmts, self.lines)
+    }
 }
 
 /// All the statement kinds in your grammar.
@@ -13,32 +324,59 @@ pub enum Statement {
     BlankLine,
     Comment(String),
     OptionExplicit,
+    /// `Option Private Module`: Public members are visible only inside the project
+    OptionPrivateModule,
+    /// `Attribute VB_Name = "Module1"`: metadata only, see `Program::attributes`
+    Attribute(Attribute),
+    /// `Declare Function Name Lib "dll" Alias "Entry" (...)`: an external
+    /// procedure, dispatched through `host::dll`
+    Declare {
+        visibility: Option<String>,
+        /// Declared `PtrSafe`, as 64-bit Office requires
+        ptr_safe: bool,
+        name: String,
+        is_function: bool,
+        library: String,
+        alias: Option<String>,
+        params: Vec<Parameter>,
+        return_type: Option<String>,
+    },
+    /// `DefInt A-Z`: undeclared names starting with these letters get `type_name`
+    DefType {
+        type_name: String,
+        ranges: Vec<(char, char)>,
+    },
     Subroutine {
+        visibility: Option<String>,
         name: String,
         params: Vec<Parameter>,  // Changed from Vec<String> to support modifiers
-        body: Vec<Statement>,
+        body: Body,
     },
     Function {
+        visibility: Option<String>,
         name: String,
         params: Vec<Parameter>,
         return_type: Option<String>,
-        body: Vec<Statement>,
+        body: Body,
     },
     PropertyGet {
+        visibility: Option<String>,
         name: String,
         params: Vec<Parameter>,
         return_type: Option<String>,
-        body: Vec<Statement>,
+        body: Body,
     },
     PropertyLet {
+        visibility: Option<String>,
         name: String,
         params: Vec<Parameter>,
-        body: Vec<Statement>,
+        body: Body,
     },
     PropertySet {
+        visibility: Option<String>,
         name: String,
         params: Vec<Parameter>,
-        body: Vec<Statement>,
+        body: Body,
     },
     ReDim {
         preserve: bool,
@@ -47,29 +385,45 @@ pub enum Statement {
     Dim {
         names: Vec<(String, Option<String>)>,
     },
+    /// `Static x As Long`: a local that keeps its value between calls
+    Static {
+        names: Vec<(String, Option<String>)>,
+    },
+    /// `Set x = obj`: stores the reference; anything but an object or
+    /// Nothing is error 424
     Set {
         target: String,
         expr: Expression,
     },
+    /// `x = 5` or `Let x = 5`: stores a value; an object is read, and an
+    /// object variable assigned, through its default member (error 91 for
+    /// Nothing)
     Assignment {
         lvalue: AssignmentTarget,
         rvalue: Expression,
+        /// `rvalue` compiled on first execution
+        rvalue_compiled: CompiledCache,
     },
     MsgBox {
         expr: Expression,
+        /// VbMsgBoxStyle: buttons + icon + default button
+        buttons: Option<Expression>,
+        title: Option<Expression>,
     },
     GoTo {
         label: String,
     },
     If {
         condition: Expression,
-        then_branch: Vec<Statement>,
-        else_if: Vec<(Expression, Vec<Statement>)>,
-        else_branch: Vec<Statement>,
+        then_branch: Body,
+        else_if: Vec<(Expression, Body)>,
+        else_branch: Body,
     },
     For(ForStatement),
     DoWhile(DoWhileStatement),
     Exit(ExitType), 
+    /// `Stop`: halts, pauses or is ignored per `RuntimeConfig::stop_policy`
+    Stop,
     Enum {                              
         visibility: Option<String>,     
         name: String,                  
@@ -90,9 +444,13 @@ pub enum Statement {
     },
     With {
         object: Expression,
-        body: Vec<Statement>,
+        body: Body,
+    },
+    /// Source that did not parse or has no AST mapping yet.
+    Unparsed {
+        text: String,
+        span: Span,
     },
-    
 }
 
 /// Parameter with modifiers (ByRef/ByVal, Optional, ParamArray)
@@ -161,6 +519,8 @@ pub enum Expression {
     Date(chrono::NaiveDate), // Use chrono for dates
     Double(f64),       // ✅ add
     Decimal(f64),      // ✅ add (or use rust_decimal::Decimal if you want fixed precision)
+    Long(i32),         // 100000, 5&, &HFFFF&
+    LongLong(i64),     // 5^, &HFFFFFFFFFF^
     // Binary {
     //     left: Box<Expression>,
     //     operator: String,
@@ -191,6 +551,9 @@ pub enum Expression {
         args: Vec<Expression>,
     },
     BuiltInConstant(String), 
+    /// An argument wrapped in its own parentheses in a call statement,
+    /// e.g. `Foo (x)`. VBA evaluates it as a temporary, forcing ByVal.
+    Parenthesized(Box<Expression>),
 }
 
 /// Represents an argument in a function call
@@ -235,7 +598,7 @@ pub struct ForStatement {
     pub start: Expression,            // Initial value expression
     pub end: Expression,              // End value expression  
     pub step: Option<Expression>,     // Optional step expression
-    pub body: Vec<Statement>,         // Loop body statements
+    pub body: Body,                   // Loop body statements (shared with VM frames)
     pub next_counter: Option<String>, // Optional counter after Next (for validation)
 }
 
@@ -244,7 +607,8 @@ pub struct DoWhileStatement {
     pub condition: Option<Expression>,     // None for infinite Do...Loop
     pub condition_type: DoWhileConditionType,
     pub test_at_end: bool,                 // true = Do...Loop While, false = Do While...Loop
-    pub body: Vec<Statement>,
+    pub body: Body,
+    pub condition_compiled: CompiledCache, // `condition` compiled on first test
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -321,6 +685,9 @@ pub enum AssignmentTarget {
         method: String,
         args: Vec<Expression>,
     },
+    DefaultMember {                  // Default member: Range("A1") = 5, c(1) = "x"
+        object: Box<Expression>,
+    },
 }
 
 impl std::fmt::Display for AssignmentTarget {
@@ -330,13 +697,161 @@ impl std::fmt::Display for AssignmentTarget {
             AssignmentTarget::PropertyAccess { object, property } => write!(f, "{:?}.{}", object, property),
             AssignmentTarget::WithMemberAccess { property } => write!(f, ".{}", property),
             AssignmentTarget::WithMethodCall { method, args } => write!(f, ".{}({:?})", method, args),
+            AssignmentTarget::DefaultMember { object } => write!(f, "{:?}", object),
         }
     }
 }
 
 
-/// Build the top-level AST from the `source_file` node.
-pub fn build_ast(root: Node, source: &str) -> Program {
+/// Parse `source` and build its AST.
+///
+/// Takes the source only: `#If` blocks are resolved against the default
+/// compiler constants (see [`build_ast_with_constants`]) and multi-statement
+/// lines (`a = 1: b = 2`) and `_` line continuations are normalized into one
+/// statement per line before the single parse, so those statements are not
+/// lost inside ERROR nodes. Lines in the AST are those of `source`.
+///
+/// Never fails: anything that cannot be built becomes `Statement::Unparsed`
+/// and is reported in `Program::diagnostics`. Use [`parse_source`] to get
+/// an error when the grammar cannot be loaded instead.
+pub fn build_ast(source: &str) -> Program {
+    build_ast_with_constants(source, &crate::preprocess::default_constants())
+}
+
+/// [`build_ast`] with the compiler constants `#If` conditions see
+/// (`RuntimeConfig::compiler_constants`).
+pub fn build_ast_with_constants(source: &str, constants: &CompilerConstants) -> Program {
+    build_ast_with_report(source, constants).0
+}
+
+/// [`build_ast_with_constants`], also listing every parse-tree node that
+/// could not be converted (syntax errors, statements kept as
+/// `Statement::Unparsed`, expressions dropped from their statement).
+pub fn build_ast_with_report(source: &str, constants: &CompilerConstants) -> (Program, BuildReport) {
+    parse_source_with_report(source, constants).unwrap_or_else(|e| {
+        let span = Span { line: 1, column: 1, end_line: 1, end_column: 1 };
+        let program = Program { statements: Vec::new(), diagnostics: vec![Diagnostic { message: e.to_string(), span }] };
+        (program, BuildReport::default())
+    })
+}
+
+/// Build the statements of an already parsed tree of `source` as they are,
+/// without resolving `#If` or normalizing `:` and `_` (which would need a
+/// parse of different text). Prefer [`build_ast`].
+pub(crate) fn build_parsed_tree(root: Node, source: &str) -> Program {
+    build_program(root, source)
+}
+
+/// Preprocess, normalize and parse `source` once, then build the AST with
+/// the lines of `source`
+fn build_preprocessed(source: &str, constants: &CompilerConstants) -> Result<Program, VbaError> {
+    let (preprocessed, directive_errors) = crate::preprocess::preprocess(source, constants);
+    let mut program = build_normalized(&preprocessed)?;
+    let mut errors = directive_errors;
+    if crate::preprocess::is_win64(constants) {
+        errors.extend(crate::preprocess::declares_without_ptr_safe(&preprocessed));
+    }
+    program.diagnostics.extend(errors.into_iter().map(|e| Diagnostic {
+        message: e.message,
+        span: Span { line: e.line, column: 1, end_line: e.line, end_column: 1 },
+    }));
+    Ok(program)
+}
+
+/// Parse the normalized form of `preprocessed` and map its lines back
+fn build_normalized(preprocessed: &str) -> Result<Program, VbaError> {
+    let (normalized, line_map) = normalize_with_line_map(preprocessed);
+    let mut parser = tree_sitter::Parser::new();
+    parser
+        .set_language(vba_parser::language())
+        .map_err(|e| VbaError::parse(format!("could not load VBA grammar: {}", e)))?;
+    let tree = parser
+        .parse(&normalized, None)
+        .ok_or_else(|| VbaError::parse("parser returned no tree"))?;
+    let mut program = build_program(tree.root_node(), &normalized);
+    let map_line = |line: usize| line_map.get(line.wrapping_sub(1)).map_or(line, |r| r + 1);
+    let remap = |span: &mut Span| {
+        span.line = map_line(span.line);
+        span.end_line = map_line(span.end_line);
+    };
+    for_each_unparsed_mut(&mut program.statements, &mut |_, span| remap(span));
+    walk_bodies_mut(&mut program.statements, &mut |_, lines| {
+        for line in lines.iter_mut().filter(|l| **l > 0) {
+            *line = map_line(*line);
+        }
+    });
+    for diag in &mut program.diagnostics {
+        remap(&mut diag.span);
+    }
+    BUILD_REPORT.with(|report| {
+        for node in report.borrow_mut().iter_mut().flat_map(|r| &mut r.skipped) {
+            remap(&mut node.span);
+        }
+    });
+    Ok(program)
+}
+
+/// Parse `source` with the VBA grammar and build its AST, as [`build_ast`]
+/// does.
+///
+/// Fails only when the grammar cannot be loaded; syntax problems are
+/// reported in `Program::diagnostics` as with [`build_ast`].
+pub fn parse_source(source: &str) -> Result<Program, VbaError> {
+    parse_source_with_constants(source, &crate::preprocess::default_constants())
+}
+
+/// [`parse_source`] with the compiler constants `#If` conditions see.
+pub fn parse_source_with_constants(source: &str, constants: &CompilerConstants) -> Result<Program, VbaError> {
+    parse_source_with_report(source, constants).map(|(program, _)| program)
+}
+
+/// [`parse_source_with_constants`] with the [`BuildReport`] of what was skipped.
+pub fn parse_source_with_report(source: &str, constants: &CompilerConstants) -> Result<(Program, BuildReport), VbaError> {
+    BUILD_REPORT.with(|report| report.replace(Some(BuildReport::default())));
+    let program = build_preprocessed(source, constants);
+    let mut report = BUILD_REPORT.with(|report| report.take()).unwrap_or_default();
+    report.skipped.sort_by_key(|node| (node.span.line, node.span.column));
+    Ok((program?, report))
+}
+
+/// Parse a single expression such as `Len("abc") + 1`.
+///
+/// The text is parsed as the right-hand side of an assignment inside a
+/// throwaway Sub, so anything valid there is accepted.
+pub fn parse_expression(text: &str) -> Result<Expression, VbaError> {
+    const PREFIX: &str = "__EvalResult = ";
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(VbaError::parse("empty expression"));
+    }
+    if text.contains(['\n', '\r']) {
+        return Err(VbaError::parse("expression must be a single line"));
+    }
+
+    let source = format!("Sub __Eval()\n{}{}\nEnd Sub\n", PREFIX, text);
+    let program = parse_source(&source)?;
+    if let Some(d) = program.diagnostics.first() {
+        // Where in `text` the problem is, when it is on the expression's line
+        let span = (d.span.line == 2 && d.span.end_line == 2).then(|| Span {
+            line: 1,
+            column: d.span.column.saturating_sub(PREFIX.len()).max(1),
+            end_line: 1,
+            end_column: d.span.end_column.saturating_sub(PREFIX.len()).max(1),
+        });
+        return Err(VbaError::ParseError { message: format!("invalid expression: {}", d.message), span });
+    }
+    let body = match program.statements.into_iter().next() {
+        Some(Statement::Subroutine { body, .. }) => body,
+        _ => return Err(VbaError::parse(format!("invalid expression: {}", text))),
+    };
+    let mut stmts = body.into_iter().filter(|s| !matches!(s, Statement::BlankLine));
+    match (stmts.next(), stmts.next()) {
+        (Some(Statement::Assignment { rvalue, .. }), None) => Ok(rvalue),
+        _ => Err(VbaError::parse(format!("invalid expression: {}", text))),
+    }
+}
+
+fn build_program(root: Node, source: &str) -> Program {
     let mut stmts = Vec::new();
     let mut cursor = root.walk();
     for stmt_wr in root.named_children(&mut cursor) {
@@ -344,7 +859,284 @@ pub fn build_ast(root: Node, source: &str) -> Program {
             stmts.push(stmt);
         }
     }
-    Program { statements: stmts }
+
+    let mut diagnostics = Vec::new();
+    collect_syntax_errors(root, source, &mut diagnostics);
+    check_label_targets(root, source, &mut diagnostics);
+    for_each_unparsed_mut(&mut stmts, &mut |text, span| {
+        let covered = diagnostics.iter().any(|d| d.span == *span);
+        if !covered {
+            let first_line = text.lines().next().unwrap_or("").trim();
+            diagnostics.push(Diagnostic {
+                message: format!("could not build statement: {}", first_line),
+                span: *span,
+            });
+        }
+    });
+    diagnostics.sort_by_key(|d| (d.span.line, d.span.column));
+
+    Program { statements: stmts, diagnostics }
+}
+
+/// Report every ERROR and MISSING node under `node`.
+fn collect_syntax_errors(node: Node, source: &str, out: &mut Vec<Diagnostic>) {
+    if node.is_error() {
+        report_skipped(SkipCategory::Syntax, node, source);
+        let text = extract(source, node);
+        out.push(Diagnostic {
+            message: format!("syntax error near `{}`", text.lines().next().unwrap_or("").trim()),
+            span: Span::from_node(node),
+        });
+        return;
+    }
+    if node.is_missing() {
+        report_skipped(SkipCategory::Syntax, node, source);
+        out.push(Diagnostic {
+            message: format!("missing `{}`", node.kind()),
+            span: Span::from_node(node),
+        });
+        return;
+    }
+    if !node.has_error() {
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_syntax_errors(child, source, out);
+    }
+}
+
+/// Report `GoTo`/`On Error GoTo`/`Resume` targets with no matching label in
+/// the same procedure, and labels defined twice. Labels match
+/// case-insensitively, as in VBA.
+fn check_label_targets(node: Node, source: &str, out: &mut Vec<Diagnostic>) {
+    const PROCEDURES: [&str; 5] =
+        ["subroutine", "function_definition", "property_get", "property_let", "property_set"];
+
+    if !PROCEDURES.contains(&node.kind()) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            check_label_targets(child, source, out);
+        }
+        return;
+    }
+
+    let mut labels: Vec<Node> = Vec::new();
+    let mut targets: Vec<Node> = Vec::new();
+    collect_label_nodes(node, &mut labels, &mut targets);
+
+    let mut defined = std::collections::HashSet::new();
+    for label in labels {
+        let name = extract(source, label);
+        if !defined.insert(Symbol::intern(&name)) {
+            out.push(Diagnostic {
+                message: format!("duplicate label: {}", name),
+                span: Span::from_node(label),
+            });
+        }
+    }
+    for target in targets {
+        let name = extract(source, target);
+        if !defined.contains(&Symbol::intern(&name)) {
+            out.push(Diagnostic {
+                message: format!("label not defined: {}", name),
+                span: Span::from_node(target),
+            });
+        }
+    }
+}
+
+fn collect_label_nodes<'t>(node: Node<'t>, labels: &mut Vec<Node<'t>>, targets: &mut Vec<Node<'t>>) {
+    let first_identifier = |n: Node<'t>| {
+        let mut cursor = n.walk();
+        let found = n.named_children(&mut cursor).find(|c| c.kind() == "identifier");
+        found
+    };
+    match node.kind() {
+        "label_statement" => labels.extend(first_identifier(node)),
+        "goto_statement" => targets.extend(first_identifier(node)),
+        "on_error_statement" => targets.extend(
+            node.child_by_field_name("target").filter(|t| t.kind() == "identifier"),
+        ),
+        "resume_statement" => targets.extend(node.child_by_field_name("label")),
+        _ => {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                collect_label_nodes(child, labels, targets);
+            }
+        }
+    }
+}
+
+/// Visit every `Statement::Unparsed`, including those nested in bodies.
+fn for_each_unparsed_mut(stmts: &mut [Statement], f: &mut dyn FnMut(&str, &mut Span)) {
+    walk_bodies_mut(stmts, &mut |stmts, _| {
+        for stmt in stmts {
+            if let Statement::Unparsed { text, span } = stmt {
+                f(text, span);
+            }
+        }
+    });
+}
+
+/// Call `f` with `stmts` (lines empty) and then with every nested body.
+/// Bodies are still unshared while the AST is being built.
+fn walk_bodies_mut(stmts: &mut [Statement], f: &mut dyn FnMut(&mut [Statement], &mut [usize])) {
+    f(stmts, &mut []);
+    for stmt in stmts {
+        walk_nested_mut(stmt, f);
+    }
+}
+
+fn walk_nested_mut(stmt: &mut Statement, f: &mut dyn FnMut(&mut [Statement], &mut [usize])) {
+    for body in stmt.child_bodies_mut() {
+        if let Some((stmts, lines)) = body.parts_mut() {
+            f(stmts, lines);
+            for stmt in stmts {
+                walk_nested_mut(stmt, f);
+            }
+        }
+    }
+}
+
+/// Rewrite source so every statement sits on its own physical line.
+///
+/// - `_` at the end of a line joins it with the next one; the consumed line
+///   breaks are re-emitted after the logical line so later line numbers hold.
+/// - `:` separators outside strings, date literals and comments split the
+///   line. A leading `Label:` stays intact and `:=` is left alone.
+/// - Single-line `If ... Then a: b` is kept whole, since splitting it would
+///   move `b` out of the conditional.
+pub fn normalize_logical_lines(source: &str) -> String {
+    normalize_with_line_map(source).0
+}
+
+/// `normalize_logical_lines`, plus the original 0-based row of every
+/// output row.
+fn normalize_with_line_map(source: &str) -> (String, Vec<usize>) {
+    let mut out = String::with_capacity(source.len());
+    let mut line_map = Vec::new();
+    let mut pending_breaks = String::new();
+    let mut logical = String::new();
+    let mut logical_row = 0;
+
+    for (row, line) in source.split_inclusive('\n').enumerate() {
+        let eol_len = if line.ends_with("\r\n") { 2 } else if line.ends_with('\n') { 1 } else { 0 };
+        let (content, eol) = line.split_at(line.len() - eol_len);
+        if logical.is_empty() && pending_breaks.is_empty() {
+            logical_row = row;
+        }
+
+        if let Some(head) = continued_line_head(content).filter(|_| !eol.is_empty()) {
+            logical.push_str(head);
+            logical.push(' ');
+            pending_breaks.push_str(eol);
+            continue;
+        }
+
+        logical.push_str(content);
+        let split = split_statement_separators(&logical);
+        line_map.extend(std::iter::repeat_n(logical_row, split.matches('\n').count() + 1));
+        line_map.extend(logical_row + 1..=row);
+        out.push_str(&split);
+        out.push_str(eol);
+        out.push_str(&pending_breaks);
+        logical.clear();
+        pending_breaks.clear();
+    }
+    (out, line_map)
+}
+
+/// For a line ending in a ` _` continuation outside a string, the text
+/// before the underscore.
+fn continued_line_head(content: &str) -> Option<&str> {
+    let head = content.trim_end().strip_suffix('_')?;
+    let preceded_by_space = head.is_empty() || head.ends_with([' ', '\t']);
+    (preceded_by_space && head.matches('"').count() % 2 == 0).then_some(head)
+}
+
+/// Split one logical line on top-level `:` separators.
+fn split_statement_separators(line: &str) -> String {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with('#') || trimmed.to_ascii_lowercase().starts_with("rem ") {
+        return line.to_string();
+    }
+
+    let bytes = line.as_bytes();
+    let mut splits = Vec::new();
+    let mut in_string = false;
+    let mut in_date = false;
+    let mut code_end = line.len();
+    for (i, &b) in bytes.iter().enumerate() {
+        match b {
+            b'"' if !in_date => in_string = !in_string,
+            b'#' if !in_string => {
+                let opens_date = bytes.get(i + 1).is_some_and(|c| c.is_ascii_digit())
+                    && line[i + 1..].contains('#');
+                if in_date || opens_date {
+                    in_date = !in_date;
+                }
+            }
+            b'\'' if !in_string && !in_date => {
+                code_end = i;
+                break;
+            }
+            b':' if !in_string && !in_date && bytes.get(i + 1) != Some(&b'=') => splits.push(i),
+            _ => {}
+        }
+    }
+    if splits.is_empty() || is_single_line_if(&line[..code_end]) {
+        return line.to_string();
+    }
+
+    let indent = &line[..line.len() - trimmed.len()];
+    let mut pieces: Vec<String> = Vec::new();
+    let mut start = 0;
+    for (n, &at) in splits.iter().enumerate() {
+        let piece = line[start..at].trim();
+        if n == 0 && is_label_name(piece) {
+            pieces.push(format!("{}:", piece));
+        } else if !piece.is_empty() {
+            pieces.push(piece.to_string());
+        }
+        start = at + 1;
+    }
+    let rest = line[start..].trim();
+    if !rest.is_empty() {
+        pieces.push(rest.to_string());
+    }
+
+    pieces
+        .iter()
+        .map(|p| format!("{}{}", indent, p))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `If cond Then stmt` with a statement after `Then` on the same line.
+fn is_single_line_if(code: &str) -> bool {
+    let lower = code.trim().to_ascii_lowercase();
+    if !lower.starts_with("if ") {
+        return false;
+    }
+    let words: Vec<&str> = lower.split_whitespace().collect();
+    match words.iter().position(|w| *w == "then") {
+        Some(pos) => pos + 1 < words.len(),
+        None => false,
+    }
+}
+
+/// A bare identifier or line number that VBA reads as a label when it
+/// starts a line followed by `:`.
+fn is_label_name(piece: &str) -> bool {
+    const NOT_LABELS: &[&str] = &["else", "end", "stop", "loop", "next", "wend", "resume", "return", "do"];
+    let mut chars = piece.chars();
+    let valid = match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() => chars.all(|c| c.is_ascii_alphanumeric() || c == '_'),
+        Some(c) if c.is_ascii_digit() => chars.all(|c| c.is_ascii_digit()),
+        _ => false,
+    };
+    valid && !NOT_LABELS.contains(&piece.to_ascii_lowercase().as_str())
 }
 
 /// Helper: Build a Parameter from a "parameter" node
@@ -406,20 +1198,38 @@ fn build_parameters(node: Option<Node>, source: &str) -> Vec<Parameter> {
 }
 
 /// Helper: Build body statements from a procedure node
-fn build_body(node: Node, source: &str) -> Vec<Statement> {
-    let mut body = Vec::new();
+fn build_body(node: Node, source: &str) -> Body {
+    let mut body = BodyBuilder::default();
     let mut cursor = node.walk();
     
     for stmt_wrapper in node.named_children(&mut cursor).filter(|n| n.kind() == "statement") {
-        if let Some(stmt) = build_statement(stmt_wrapper, source) {
-            body.push(stmt);
-        }
+        body.push_node(stmt_wrapper, source);
     }
-    body
+    body.finish()
 }
 
-/// Recursively build a Statement, unwrapping the generic `"statement"` wrappers.
+/// Build a Statement, keeping anything that fails to build as
+/// `Statement::Unparsed` instead of dropping it.
 fn build_statement(node: Node, source: &str) -> Option<Statement> {
+    if let Some(stmt) = build_statement_node(node, source) {
+        return Some(stmt);
+    }
+    let text = extract(source, node);
+    if node.kind() == "line_continuation" || text.trim().is_empty() {
+        return None;
+    }
+    // ERROR nodes are reported with the syntax errors
+    if !node.is_error() {
+        report_skipped(SkipCategory::Statement, node, source);
+    }
+    Some(Statement::Unparsed {
+        text: text.trim_end().to_string(),
+        span: Span::from_node(node),
+    })
+}
+
+/// Recursively build a Statement, unwrapping the generic `"statement"` wrappers.
+fn build_statement_node(node: Node, source: &str) -> Option<Statement> {
     // eprintln!(
     //     "🔹 build_statement: kind = {:15} text = {:?}",
     //     node.kind(),
@@ -442,8 +1252,12 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
             let cleaned_comment = comment_text.strip_prefix('\'').unwrap_or(&comment_text);
             Some(Statement::Comment(cleaned_comment.trim().to_string()))
         }
+        // ` _` continuations are trivia between tokens of the same statement
+        "line_continuation" => None,
 
         "subroutine" => {
+            let visibility = node.child_by_field_name("visibility")
+                .map(|v| extract(source, v));
             let name_node = node.child_by_field_name("name")?;
             let name = extract(source, name_node);
 
@@ -456,27 +1270,32 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
                 eprintln!("    ✅ built stmt: {:?}", stmt);
             }
 
-            Some(Statement::Subroutine { name, params, body })
+            Some(Statement::Subroutine { visibility, name, params, body })
         }
 
         "function_definition" => {
+            let visibility = node.child_by_field_name("visibility")
+                .map(|v| extract(source, v));
             let name_node = node.child_by_field_name("name")?;
             let name = extract(source, name_node);
 
             eprintln!("🔨 Building Function `{}` — scanning children…", name);
 
             let params = build_parameters(node.child_by_field_name("params"), source);
-            let return_type = node.child_by_field_name("return_type").map(|n| extract(source, n));
+            let return_type = node.child_by_field_name("return_type").map(|n| extract(source, n))
+                .or_else(|| split_type_suffix(&source_text(source, name_node)).1.map(str::to_string));
             let body = build_body(node, source);
 
             for stmt in &body {
                 eprintln!("    ✅ built stmt: {:?}", stmt);
             }
 
-            Some(Statement::Function { name, params, return_type, body })
+            Some(Statement::Function { visibility, name, params, return_type, body })
         }
 
         "property_get" => {
+            let visibility = node.child_by_field_name("visibility")
+                .map(|v| extract(source, v));
             let name_node = node.child_by_field_name("name")?;
             let name = extract(source, name_node);
 
@@ -486,10 +1305,12 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
             let return_type = node.child_by_field_name("return_type").map(|n| extract(source, n));
             let body = build_body(node, source);
 
-            Some(Statement::PropertyGet { name, params, return_type, body })
+            Some(Statement::PropertyGet { visibility, name, params, return_type, body })
         }
 
         "property_let" => {
+            let visibility = node.child_by_field_name("visibility")
+                .map(|v| extract(source, v));
             let name_node = node.child_by_field_name("name")?;
             let name = extract(source, name_node);
 
@@ -498,10 +1319,12 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
             let params = build_parameters(node.child_by_field_name("params"), source);
             let body = build_body(node, source);
 
-            Some(Statement::PropertyLet { name, params, body })
+            Some(Statement::PropertyLet { visibility, name, params, body })
         }
 
         "property_set" => {
+            let visibility = node.child_by_field_name("visibility")
+                .map(|v| extract(source, v));
             let name_node = node.child_by_field_name("name")?;
             let name = extract(source, name_node);
 
@@ -510,7 +1333,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
             let params = build_parameters(node.child_by_field_name("params"), source);
             let body = build_body(node, source);
 
-            Some(Statement::PropertySet { name, params, body })
+            Some(Statement::PropertySet { visibility, name, params, body })
         }
 
         "redim_statement" => {
@@ -549,7 +1372,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
             Some(Statement::ReDim { preserve, variables })
         }
 
-        "dim_statement" => {
+        "dim_statement" | "static_statement" => {
             let mut names = Vec::new();
 
             let mut child_cursor = node.walk();
@@ -560,8 +1383,10 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
             while i < children.len() {
                 let id = &children[i];
                 if id.kind() == "identifier" {
-                    let var = extract(source, *id);
-                    let mut ty: Option<String> = None;
+                    let spelled = source_text(source, *id);
+                    let (var, suffix) = split_type_suffix(&spelled);
+                    let var = var.to_string();
+                    let mut ty: Option<String> = suffix.map(str::to_string);
 
                     // Look ahead for a following type (primitive_type or identifier)
                     if i + 1 < children.len() {
@@ -578,7 +1403,10 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
                 i += 1;
             }
 
-            Some(Statement::Dim { names })
+            match node.kind() {
+                "static_statement" => Some(Statement::Static { names }),
+                _ => Some(Statement::Dim { names }),
+            }
         }
 
         "set_statement" => {
@@ -599,6 +1427,8 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
             //   ERROR: " " (ignore)
             //   =: "="     (ignore)
             //   expression: "10+78"
+            // `Let j = 10` has a leading keyword_Let, which changes nothing:
+            // every assignment without Set is a Let
             
             let mut target: Option<AssignmentTarget> = None;
             let mut expr: Option<Expression> = None;
@@ -612,7 +1442,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
                         for lvalue_child in child.named_children(&mut lvalue_cursor) {
                             match lvalue_child.kind() {
                                 "identifier" => {
-                                    let name = extract(source, lvalue_child);
+                                    let name = source_text(source, lvalue_child);
                                     target = Some(AssignmentTarget::Identifier(name));
                                     break;
                                 }
@@ -688,6 +1518,12 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
                                     target = Some(AssignmentTarget::WithMethodCall { method: method_name, args });
                                     break;
                                 }
+                                "indexed_access" => {
+                                    // Range("A1") = 5, c(1) = "x": the object's default member
+                                    target = build_expression(lvalue_child, source)
+                                        .map(|object| AssignmentTarget::DefaultMember { object: Box::new(object) });
+                                    break;
+                                }
                                 _ => {}
                             }
                         }
@@ -711,7 +1547,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
                     .filter(|n| n.kind() == "identifier" || n.kind() == "expression");
                 if target.is_none() {
                     if let Some(id_node) = parts.next() {
-                        let name = extract(source, id_node);
+                        let name = source_text(source, id_node);
                         target = Some(AssignmentTarget::Identifier(name));
                     }
                 }
@@ -723,7 +1559,11 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
             }
             
             if let (Some(target_val), Some(expression)) = (target.clone(), expr.clone()) {
-                Some(Statement::Assignment { lvalue: target_val, rvalue: expression })
+                Some(Statement::Assignment {
+                    lvalue: target_val,
+                    rvalue: expression,
+                    rvalue_compiled: CompiledCache::default(),
+                })
             } else {
                 eprintln!("⚠️ Failed to build assignment statement - target: {:?}, expr: {:?}", &target, &expr);
                 None
@@ -731,15 +1571,17 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
         }
 
         "msgbox_statement" => {
-            let mut expressions = Vec::new();
+            // One slot per argument position; `MsgBox "x", , "Title"` leaves
+            // the buttons slot empty
+            let mut slots: Vec<Option<Expression>> = vec![None];
             
             let mut cursor = node.walk();
             for child in node.children(&mut cursor) {
                 match child.kind() {
+                    "," => slots.push(None),
                     "expression" => {
-                        // Direct expression child
-                        if let Some(expr) = build_expression(child, source) {
-                            expressions.push(expr);
+                        if let Some(slot) = slots.last_mut() {
+                            *slot = build_expression(child, source);
                         }
                     }
                     "ERROR" => {
@@ -747,9 +1589,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
                         let mut err_cursor = child.walk();
                         for err_child in child.children(&mut err_cursor) {
                             if err_child.kind() == "expression" {
-                                if let Some(expr) = build_expression(err_child, source) {
-                                    expressions.push(expr);
-                                }
+                                slots.push(build_expression(err_child, source));
                             }
                         }
                     }
@@ -757,12 +1597,14 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
                 }
             }
             
-            // First expression is the message to display
-            let message = expressions.into_iter().next();
+            let mut slots = slots.into_iter();
+            let message = slots.next().flatten();
+            let buttons = slots.next().flatten();
+            let title = slots.next().flatten();
             
             if let Some(msg_expr) = message {
                 eprintln!("  ✅ built MsgBox with expr: {:?}", msg_expr);
-                Some(Statement::MsgBox { expr: msg_expr })
+                Some(Statement::MsgBox { expr: msg_expr, buttons, title })
             } else {
                 eprintln!("  ⚠️ MsgBox has no expression");
                 None
@@ -792,14 +1634,14 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
             
             // --- statement being built -----------------------------------------------
             let mut condition: Option<Expression> = None;
-            let mut then_branch: Vec<Statement> = Vec::new();
-            let mut else_if: Vec<(Expression, Vec<Statement>)> = Vec::new();
-            let mut else_branch: Vec<Statement> = Vec::new();
+            let mut then_branch = BodyBuilder::default();
+            let mut else_if: Vec<(Expression, Body)> = Vec::new();
+            let mut else_branch = BodyBuilder::default();
 
             // Sections: before_then | then_body | elseif_condition | elseif_body | else_body
             let mut current_section = "before_then";
             let mut current_elseif_condition: Option<Expression> = None;
-            let mut current_elseif_statements: Vec<Statement> = Vec::new();
+            let mut current_elseif_statements = BodyBuilder::default();
 
             // iterate children with access to byte gaps
             let mut ic = node.walk();
@@ -860,7 +1702,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
 
                     "keyword_ElseIf" => {
                         if let Some(cond) = current_elseif_condition.take() {
-                            else_if.push((cond, std::mem::take(&mut current_elseif_statements)));
+                            else_if.push((cond, std::mem::take(&mut current_elseif_statements).finish()));
                         }
                         current_section = "elseif_condition";
                         continue;
@@ -868,7 +1710,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
 
                     "keyword_Else" => {
                         if let Some(cond) = current_elseif_condition.take() {
-                            else_if.push((cond, std::mem::take(&mut current_elseif_statements)));
+                            else_if.push((cond, std::mem::take(&mut current_elseif_statements).finish()));
                         }
                         current_section = "else_body";
                         continue;
@@ -877,7 +1719,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
                     // Handle End If properly
                     "keyword_End_If" => {
                         if let Some(cond) = current_elseif_condition.take() {
-                            else_if.push((cond, std::mem::take(&mut current_elseif_statements)));
+                            else_if.push((cond, std::mem::take(&mut current_elseif_statements).finish()));
                         }
                         break; // End of if statement
                     }
@@ -903,7 +1745,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
                         // Keep your old gap-based heuristic as a backup
                         if is_end_if || has(&gap_after, "if") {
                             if let Some(cond) = current_elseif_condition.take() {
-                                else_if.push((cond, std::mem::take(&mut current_elseif_statements)));
+                                else_if.push((cond, std::mem::take(&mut current_elseif_statements).finish()));
                             }
                             break; // End of this If block
                         }
@@ -916,18 +1758,18 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
                 // --- FALLBACK GAP SWITCHES ----
                 if has(&gap_before, "end if") {
                     if let Some(cond) = current_elseif_condition.take() {
-                        else_if.push((cond, std::mem::take(&mut current_elseif_statements)));
+                        else_if.push((cond, std::mem::take(&mut current_elseif_statements).finish()));
                     }
                     break;
                 }
                 if has(&gap_before, "else if") || has(&gap_before, "elseif") {
                     if let Some(cond) = current_elseif_condition.take() {
-                        else_if.push((cond, std::mem::take(&mut current_elseif_statements)));
+                        else_if.push((cond, std::mem::take(&mut current_elseif_statements).finish()));
                     }
                     current_section = "elseif_condition";
                 } else if has(&gap_before, "else") {
                     if let Some(cond) = current_elseif_condition.take() {
-                        else_if.push((cond, std::mem::take(&mut current_elseif_statements)));
+                        else_if.push((cond, std::mem::take(&mut current_elseif_statements).finish()));
                     }
                     current_section = "else_body";
                 } else if has(&gap_before, "then") && current_section == "before_then" {
@@ -947,7 +1789,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
                             current_section = "elseif_body";
                         } else {
                             if let Some(cond) = current_elseif_condition.take() {
-                                else_if.push((cond, std::mem::take(&mut current_elseif_statements)));
+                                else_if.push((cond, std::mem::take(&mut current_elseif_statements).finish()));
                             }
                             current_elseif_condition = build_expression(*child, source);
                             current_section = "elseif_body";
@@ -955,13 +1797,25 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
                     }
 
                     "statement" => {
-                        if let Some(stmt) = build_statement(*child, source) {
-                            match current_section {
-                                "then_body"   => then_branch.push(stmt),
-                                "elseif_body" => current_elseif_statements.push(stmt),
-                                "else_body"   => else_branch.push(stmt),
-                                _             => { /* ignore stray statements */ }
-                            }
+                        match current_section {
+                            "then_body"   => then_branch.push_node(*child, source),
+                            "elseif_body" => current_elseif_statements.push_node(*child, source),
+                            "else_body"   => else_branch.push_node(*child, source),
+                            _             => { /* ignore stray statements */ }
+                        }
+                    }
+
+                    // `If x Then a = 1: b = 2 Else c = 3`: each side of the
+                    // Else is one list of colon-separated statements
+                    "inline_statements" => {
+                        let branch = match current_section {
+                            "then_body" => &mut then_branch,
+                            "else_body" => &mut else_branch,
+                            _ => continue,
+                        };
+                        let mut sc = child.walk();
+                        for stmt in child.named_children(&mut sc) {
+                            branch.push_node(stmt, source);
                         }
                     }
 
@@ -971,7 +1825,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
 
             // flush any trailing ElseIf at end of block
             if let Some(cond) = current_elseif_condition.take() {
-                else_if.push((cond, std::mem::take(&mut current_elseif_statements)));
+                else_if.push((cond, std::mem::take(&mut current_elseif_statements).finish()));
             }
 
             // Debug output
@@ -985,9 +1839,9 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
             if let Some(cond) = condition {
                 Some(Statement::If {
                     condition: cond,
-                    then_branch,
+                    then_branch: then_branch.finish(),
                     else_if,
-                    else_branch,
+                    else_branch: else_branch.finish(),
                 })
             } else {
                 eprintln!("Failed to build if statement - no condition found");
@@ -1000,7 +1854,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
             let mut start = None;
             let mut end = None;
             let mut step = None;
-            let mut body = Vec::new();
+            let mut body = BodyBuilder::default();
             let mut next_counter = None;
 
             // Extract counter (loop variable)
@@ -1027,9 +1881,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
             let mut fc = node.walk();
             for child in node.named_children(&mut fc) {
                 if child.kind() == "statement" {
-                    if let Some(stmt) = build_statement(child, source) {
-                        body.push(stmt);
-                    }
+                    body.push_node(child, source);
                 }
             }
             
@@ -1044,7 +1896,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
                     start: start_expr,
                     end: end_expr,
                     step,
-                    body,
+                    body: body.finish(),
                     next_counter,
                 }))
             } else {
@@ -1058,7 +1910,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
             let mut condition: Option<Expression> = None;
             let mut condition_type = DoWhileConditionType::Infinite;
             let mut test_at_end = false;
-            let mut body = Vec::new();
+            let mut body = BodyBuilder::default();
             
             // Extract condition if present
             if let Some(cond_node) = node.child_by_field_name("condition") {
@@ -1089,9 +1941,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
             let mut cursor = node.walk();
             for child in node.named_children(&mut cursor) {
                 if child.kind() == "statement" {
-                    if let Some(stmt) = build_statement(child, source) {
-                        body.push(stmt);
-                    }
+                    body.push_node(child, source);
                 }
             }
             
@@ -1102,13 +1952,14 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
                 condition,
                 condition_type,
                 test_at_end,
-                body,
+                body: body.finish(),
+                condition_compiled: CompiledCache::default(),
             }))
         }
 
         "with_statement" => {
             let mut object: Option<Expression> = None;
-            let mut body = Vec::new();
+            let mut body = BodyBuilder::default();
             
             // Extract the object expression (the thing we're "With"ing)
             if let Some(obj_node) = node.child_by_field_name("object") {
@@ -1119,9 +1970,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
             let mut cursor = node.walk();
             for child in node.named_children(&mut cursor) {
                 if child.kind() == "statement" {
-                    if let Some(stmt) = build_statement(child, source) {
-                        body.push(stmt);
-                    }
+                    body.push_node(child, source);
                 }
             }
             
@@ -1129,7 +1978,7 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
                 eprintln!("✅ Built With statement: object={:?}, body_len={}", obj_expr, body.len());
                 Some(Statement::With {
                     object: obj_expr,
-                    body,
+                    body: body.finish(),
                 })
             } else {
                 eprintln!("⚠️ With statement missing object expression");
@@ -1137,6 +1986,8 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
             }
         }
 
+        "stop_statement" => Some(Statement::Stop),
+
         "exit_statement" => {
             // Preferred path: use the grammar field if present.
             if let Some(exit_type_node) = node.child_by_field_name("exit_type") {
@@ -1305,9 +2156,18 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
 
        "call_statement" => {
             let mut function: Option<String> = None;
+            // `.Method a, b` inside a With block
+            let mut with_member: Option<String> = None;
             let mut args: Vec<Expression> = Vec::new();
 
-            // only the named children: identifier, argument_list, expression
+            // `Call Foo(x)` passes x as an argument; `Foo (x)` passes a
+            // parenthesized (ByVal) expression.
+            let has_call_keyword = node
+                .child(0)
+                .map(|c| !c.is_named() && extract(source, c).eq_ignore_ascii_case("call"))
+                .unwrap_or(false);
+
+            // only the named children: identifier, argument_list, expression(s)
             let mut c = node.walk();
             for child in node.named_children(&mut c) {
                 match child.kind() {
@@ -1317,31 +2177,53 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
                         function = Some(name);
                     }
 
+                    // `Call Module1.Foo`: kept as written, resolved at run time
+                    "qualified_identifier" if function.is_none() => {
+                        function = Some(extract(source, child).split_whitespace().collect());
+                    }
+
+                    "with_member_access" if function.is_none() && with_member.is_none() => {
+                        with_member = child.child_by_field_name("property").map(|p| extract(source, p));
+                    }
+
                     "argument_list" => {
                         let (exprs, _) = parse_argument_list(child, source);
+                        let force_byval = !has_call_keyword && exprs.len() == 1;
                         for expr in exprs {
                             eprintln!("  📥 collected arg from list: {:?}", expr);
-                            args.push(expr);
+                            if force_byval {
+                                args.push(Expression::Parenthesized(Box::new(expr)));
+                            } else {
+                                args.push(expr);
+                            }
                         }
                     }
 
                     "expression" => {
-                        // unwrap the single-expr form
+                        // bare form: `Foo a, (b)`
                         let mut ec = child.walk();
-                        let inner = child
-                            .named_children(&mut ec)
-                            .next()
-                            .expect("expression wrapper should have a child");
-                        let expr = build_expression(inner, source)
-                            .expect("expression child should build");
-                        eprintln!("  📥 collected single-expr arg: {:?}", expr);
-                        args.push(expr);
+                        let Some(inner) = child.named_children(&mut ec).next() else {
+                            continue;
+                        };
+                        let Some(expr) = build_expression(inner, source) else {
+                            report_skipped(SkipCategory::Expression, inner, source);
+                            continue;
+                        };
+                        if inner.kind() == "parenthesized_expression" {
+                            args.push(Expression::Parenthesized(Box::new(expr)));
+                        } else {
+                            args.push(expr);
+                        }
                     }
 
                     _ => {}
                 }
             }
 
+            if let Some(method) = with_member {
+                return Some(Statement::Expression(Expression::WithMethodCall { method, args }));
+            }
+
             let fn_name = function.unwrap_or_default();
             eprintln!("⟳ resolved function = `{}`, arg count = {}", fn_name, args.len());
             eprintln!("  ✅ emitting Call AST for `{}`\n", fn_name);
@@ -1355,6 +2237,60 @@ fn build_statement(node: Node, source: &str) -> Option<Statement> {
             eprintln!("✅ Parsed Option Explicit");
             Some(Statement::OptionExplicit)
         }
+        "option_private_module_statement" => Some(Statement::OptionPrivateModule),
+        "declare_statement" => {
+            let string_field = |field: &str| match build_expression(node.child_by_field_name(field)?, source)? {
+                Expression::String(s) => Some(s),
+                _ => None,
+            };
+            let visibility = node.child_by_field_name("visibility").map(|v| extract(source, v));
+            let name = extract(source, node.child_by_field_name("name")?);
+            let is_function = node
+                .child_by_field_name("kind")
+                .is_some_and(|k| extract(source, k).eq_ignore_ascii_case("Function"));
+            let params = build_parameters(node.child_by_field_name("params"), source);
+            let return_type = node.child_by_field_name("return_type").map(|n| extract(source, n));
+            Some(Statement::Declare {
+                visibility,
+                ptr_safe: node.child_by_field_name("ptr_safe").is_some(),
+                name,
+                is_function,
+                library: string_field("library")?,
+                alias: string_field("alias"),
+                params,
+                return_type,
+            })
+        }
+        "attribute_statement" => {
+            let target = node.child_by_field_name("target").map(|t| extract(source, t));
+            let name = extract(source, node.child_by_field_name("name")?);
+            let mut cursor = node.walk();
+            let values = node
+                .children_by_field_name("value", &mut cursor)
+                .map(|v| {
+                    let inner = v.named_child(0).unwrap_or(v);
+                    match build_expression(inner, source) {
+                        Some(Expression::String(s)) => s,
+                        _ => extract(source, inner),
+                    }
+                })
+                .collect();
+            Some(Statement::Attribute(Attribute { target, name, values }))
+        }
+        "deftype_statement" => {
+            let keyword = node.child_by_field_name("keyword")?;
+            let type_name = deftype_name(&extract(source, keyword))?.to_string();
+            let mut cursor = node.walk();
+            let ranges = node
+                .children_by_field_name("range", &mut cursor)
+                .filter_map(|r| {
+                    let letters: Vec<char> = extract(source, r).chars().filter(|c| c.is_ascii_alphabetic()).collect();
+                    let first = letters.first()?.to_ascii_uppercase();
+                    Some((first, letters.last()?.to_ascii_uppercase()))
+                })
+                .collect();
+            Some(Statement::DefType { type_name, ranges })
+        }
 
         _ => {
             eprintln!("⚠️ Unhandled statement type: {} with text: {:?}", 
@@ -1377,10 +2313,8 @@ fn build_expression(node: Node, source: &str) -> Option<Expression> {
             None
         }
         
-        "integer_literal" => {
-            let text = extract(source, node);
-            text.parse::<i64>().ok().map(Expression::Integer)
-        }
+        "integer_literal" => typed_number(&extract(source, node)),
+        "hex_literal" | "octal_literal" => radix_number(&source_text(source, node)),
         "boolean_literal" => {
             let text = extract(source, node);
             let cleaned = text.trim().to_lowercase();
@@ -1420,9 +2354,9 @@ fn build_expression(node: Node, source: &str) -> Option<Expression> {
             // e.g. "3.1415926535"
             let raw = extract(source, node);      // keep the String alive
             let text = raw.trim();                // borrow it safely
-            match text.parse::<f64>() {
-                Ok(f) => Some(Expression::Double(f)),
-                Err(_) => {
+            match typed_number(text) {
+                Some(expr) => Some(expr),
+                None => {
                     eprintln!("❌ Failed to parse float_literal: {}", text);
                     None
                 }
@@ -1455,6 +2389,17 @@ fn build_expression(node: Node, source: &str) -> Option<Expression> {
             Some(Expression::Identifier(extract(source, node)))
         }
 
+        "bracket_expression" => {
+            // [A1] / [SUM(A1:A3)] is shorthand for Application.Evaluate("...")
+            let raw = extract(source, node);
+            let inner = raw.trim().trim_start_matches('[').trim_end_matches(']');
+            let evaluate = Expression::PropertyAccess {
+                obj: Box::new(Expression::Identifier("Application".into())),
+                property: "Evaluate".into(),
+            };
+            Some(Expression::FunctionCall { function: Box::new(evaluate), args: vec![Expression::String(inner.to_string())] })
+        }
+
         "parenthesized_expression" => {
             // Handle parentheses: (expr)
             //println!("  🔄 Handling parenthesized expression");
@@ -1588,11 +2533,10 @@ fn build_expression(node: Node, source: &str) -> Option<Expression> {
         "string_literal" => {
             let text = extract(source, node);
             // Remove opening and closing quotes
-            let inner = if text.len() >= 2 {
-                &text[1..text.len()-1]
-            } else {
-                ""
-            };
+            let inner = text
+                .strip_prefix('"')
+                .and_then(|t| t.strip_suffix('"'))
+                .unwrap_or("");
             let unescaped = inner.replace("\"\"", "\"");
             Some(Expression::String(unescaped))
         }
@@ -1725,7 +2669,7 @@ fn build_expression(node: Node, source: &str) -> Option<Expression> {
         
         "vba_builtin_constant" => {
             // Extract the text of the node (e.g., "vbCalGreg")
-            let text = node.utf8_text(source.as_bytes()).unwrap().to_string();
+            let text = extract(source, node);
             Some(Expression::BuiltInConstant(text))
         },
         "indexed_access" => {
@@ -1772,18 +2716,110 @@ fn build_expression(node: Node, source: &str) -> Option<Expression> {
             eprintln!("⚠️ Unhandled expression type: {} with text: {:?}", 
                      node.kind(), 
                      node.utf8_text(source.as_bytes()).unwrap_or(""));
+            if !node.is_error() {
+                report_skipped(SkipCategory::Expression, node, source);
+            }
             None
         }
     }
 }
 
 // Helper function for extracting text from nodes
+/// Node text; identifiers lose their type character (`Left$` is `Left`).
 fn extract(source: &str, node: Node) -> String {
+    let text = source_text(source, node);
+    match node.kind() {
+        "identifier" => split_type_suffix(&text).0.to_string(),
+        _ => text,
+    }
+}
+
+/// Node text as written.
+fn source_text(source: &str, node: Node) -> String {
     node.utf8_text(source.as_bytes())
         .unwrap_or("")
         .to_string()
 }
 
+/// Split a trailing type-declaration character off a name:
+/// `x%` is (`x`, Integer), `s$` is (`s`, String).
+pub fn split_type_suffix(name: &str) -> (&str, Option<&'static str>) {
+    let ty = match name.chars().last() {
+        Some('%') => "Integer",
+        Some('&') => "Long",
+        Some('$') => "String",
+        Some('#') => "Double",
+        Some('!') => "Single",
+        Some('@') => "Currency",
+        Some('^') => "LongLong",
+        _ => return (name, None),
+    };
+    (&name[..name.len() - 1], Some(ty))
+}
+
+/// Numeric literal with an optional type character (`5&`, `1.5!`, `2@`,
+/// `1E-5`). Without one, a whole number is Integer, Long or Double,
+/// whichever first holds it, as in VBA.
+fn typed_number(text: &str) -> Option<Expression> {
+    let (digits, ty) = split_type_suffix(text.trim());
+    let whole = !digits.contains(['.', 'e', 'E']);
+    match ty {
+        None if whole => Some(match digits.parse::<i64>() {
+            Ok(n) if i16::try_from(n).is_ok() => Expression::Integer(n),
+            Ok(n) if i32::try_from(n).is_ok() => Expression::Long(n as i32),
+            _ => Expression::Double(digits.parse().ok()?),
+        }),
+        Some("Integer") if whole => digits.parse::<i16>().ok().map(|n| Expression::Integer(n.into())),
+        Some("Long") if whole => digits.parse::<i32>().ok().map(Expression::Long),
+        Some("LongLong") if whole => digits.parse::<i64>().ok().map(Expression::LongLong),
+        None | Some("Double") => digits.parse::<f64>().ok().map(Expression::Double),
+        Some("Single") => digits.parse::<f32>().ok().map(Expression::Single),
+        Some("Currency") => digits.parse::<f64>().ok().map(Expression::Currency),
+        _ => None,
+    }
+}
+
+/// `&HFF` or `&O17` with an optional `%`, `&` or `^`. Without a type
+/// character it is Integer up to 4 hex digits and Long up to 8. The bits
+/// are kept, so `&HFFFF` is -1 and `&HFFFF&` is 65535.
+fn radix_number(text: &str) -> Option<Expression> {
+    let text = text.trim();
+    let radix = match text.get(..2)?.to_ascii_uppercase().as_str() {
+        "&H" => 16,
+        "&O" => 8,
+        _ => return None,
+    };
+    let (digits, ty) = split_type_suffix(&text[2..]);
+    let n = u64::from_str_radix(digits, radix).ok()?;
+    match ty {
+        None | Some("Integer") if n <= 0xFFFF => Some(Expression::Integer((n as u16 as i16).into())),
+        None | Some("Long") if n <= 0xFFFF_FFFF => Some(Expression::Long(n as u32 as i32)),
+        Some("LongLong") => Some(Expression::LongLong(n as i64)),
+        _ => None,
+    }
+}
+
+/// `DefInt` etc. to the type name a `Dim ... As` would use.
+fn deftype_name(keyword: &str) -> Option<&'static str> {
+    Some(match keyword.to_ascii_lowercase().as_str() {
+        "defbool" => "Boolean",
+        "defbyte" => "Byte",
+        "defint" => "Integer",
+        "deflng" => "Long",
+        "deflnglng" => "LongLong",
+        "deflngptr" => "LongPtr",
+        "defcur" => "Currency",
+        "defsng" => "Single",
+        "defdbl" => "Double",
+        "defdec" => "Decimal",
+        "defdate" => "Date",
+        "defstr" => "String",
+        "defobj" => "Object",
+        "defvar" => "Variant",
+        _ => return None,
+    })
+}
+
 /// Parse arguments from an argument_list node
 /// For now, just extracts expressions - the Argument enum is kept for future use
 fn parse_argument_list(args_node: Node, source: &str) -> (Vec<Expression>, Vec<Argument>) {
@@ -1814,6 +2850,82 @@ impl Statement {
         build_statement(node, source)
             .ok_or_else(|| format!("Failed to build statement from node: {}", node.kind()))
     }
+
+    /// Statement lists nested directly inside this statement, in source order.
+    pub fn child_bodies(&self) -> Vec<&Body> {
+        match self {
+            Statement::Subroutine { body, .. }
+            | Statement::Function { body, .. }
+            | Statement::PropertyGet { body, .. }
+            | Statement::PropertyLet { body, .. }
+            | Statement::PropertySet { body, .. }
+            | Statement::With { body, .. }
+            | Statement::For(ForStatement { body, .. })
+            | Statement::DoWhile(DoWhileStatement { body, .. }) => vec![body],
+            Statement::If { then_branch, else_if, else_branch, .. } => std::iter::once(then_branch)
+                .chain(else_if.iter().map(|(_, b)| b))
+                .chain(std::iter::once(else_branch))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn child_bodies_mut(&mut self) -> Vec<&mut Body> {
+        match self {
+            Statement::Subroutine { body, .. }
+            | Statement::Function { body, .. }
+            | Statement::PropertyGet { body, .. }
+            | Statement::PropertyLet { body, .. }
+            | Statement::PropertySet { body, .. }
+            | Statement::With { body, .. }
+            | Statement::For(ForStatement { body, .. })
+            | Statement::DoWhile(DoWhileStatement { body, .. }) => vec![body],
+            Statement::If { then_branch, else_if, else_branch, .. } => std::iter::once(then_branch)
+                .chain(else_if.iter_mut().map(|(_, b)| b))
+                .chain(std::iter::once(else_branch))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Short name of the statement kind, for traces and reports.
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            Statement::BlankLine => "BlankLine",
+            Statement::Comment(_) => "Comment",
+            Statement::OptionExplicit => "OptionExplicit",
+            Statement::OptionPrivateModule => "OptionPrivateModule",
+            Statement::Attribute(_) => "Attribute",
+            Statement::Declare { .. } => "Declare",
+            Statement::DefType { .. } => "DefType",
+            Statement::Subroutine { .. } => "Sub",
+            Statement::Function { .. } => "Function",
+            Statement::PropertyGet { .. } => "PropertyGet",
+            Statement::PropertyLet { .. } => "PropertyLet",
+            Statement::PropertySet { .. } => "PropertySet",
+            Statement::ReDim { .. } => "ReDim",
+            Statement::Dim { .. } => "Dim",
+            Statement::Static { .. } => "Static",
+            Statement::Set { .. } => "Set",
+            Statement::Assignment { .. } => "Assignment",
+            Statement::MsgBox { .. } => "MsgBox",
+            Statement::GoTo { .. } => "GoTo",
+            Statement::If { .. } => "If",
+            Statement::For(_) => "For",
+            Statement::DoWhile(_) => "Do",
+            Statement::Exit(_) => "Exit",
+            Statement::Stop => "Stop",
+            Statement::Enum { .. } => "Enum",
+            Statement::Type { .. } => "Type",
+            Statement::Label(_) => "Label",
+            Statement::Expression(_) => "Expression",
+            Statement::OnError(_) => "OnError",
+            Statement::Resume(_) => "Resume",
+            Statement::Call { .. } => "Call",
+            Statement::With { .. } => "With",
+            Statement::Unparsed { .. } => "Unparsed",
+        }
+    }
 }
 
 impl ExitType {
@@ -0,0 +1,333 @@
+//! Loading RuntimeConfig settings from TOML, JSON and the environment
+//!
+//! Every setting that can be written as text has a key (`timezone`,
+//! `max_call_depth`, ...). Sources are applied to a builder in order, so
+//! later ones override earlier ones and explicit builder calls override
+//! both:
+//!
+//! ```rust,ignore
+//! let config = RuntimeConfig::builder()
+//!     .file("vba.toml")      // timezone = "Europe/Berlin"
+//!     .env()                 // VBA_MAX_CALL_DEPTH=64
+//!     .user_id("user-67890")
+//!     .try_build()?;
+//! ```
+//!
+//! Files are read with the `toml` and `serde_json` crates, so quoting,
+//! escapes, multi-line strings and comments follow those formats. Settings
+//! are flat: top-level keys for TOML, members of one object for JSON, with
+//! string, number and boolean values; a table or array value is an error.
+//! Unknown keys and bad values are recorded on the builder and reported by
+//! `try_build`.
+
+use std::fmt::Debug;
+
+use crate::runtime_config::{
+    AutoRunPolicy, DllPolicy, ExcelBackendKind, ExecutionEngine, HostApplication, ImplicitVariablePolicy,
+    InteractionPolicy, RuntimeConfig, RuntimeConfigBuilder, SandboxProfile, StopPolicy, UnknownObjectPolicy,
+};
+
+/// Prefix of the environment variables `RuntimeConfigBuilder::env` reads
+/// (`VBA_TIMEZONE`, `VBA_MAX_CALL_DEPTH`, ...)
+pub const ENV_PREFIX: &str = "VBA_";
+
+/// Keys the sources accept, with what they set
+pub const SETTINGS: &[(&str, &str)] = &[
+    ("timezone", "Timezone of Now(), Date() and Time() (IANA name)"),
+    ("locale", "Locale cell text is read in (language[-REGION])"),
+    ("locale_cell_parsing", "Read backend cell text in the locale rather than en-US"),
+    ("workbook_id", "Active workbook identifier"),
+    ("user_id", "Current user identifier"),
+    ("first_day_of_week", "First day of week for date functions (1=Sunday ... 7=Saturday)"),
+    ("first_week_of_year", "First week of year for date functions (1, 2 or 3)"),
+    ("engine", "Statement executor: vm or interpreter"),
+    ("host", "Object model: excel or word"),
+    ("unknown_objects", "CreateObject of unknown ProgIDs: record or error"),
+    ("excel_backend", "Cell store: native or static"),
+    ("write_batching", "Coalesce cell writes into block writes"),
+    ("dry_run", "Plan cell, sheet, save and file writes instead of applying them"),
+    ("rollback_on_error", "Undo the cell writes of a run that fails partway"),
+    ("hibernate_on_wait", "Hibernate the run at Application.Wait"),
+    ("max_call_depth", "Most calls active at once before error 28"),
+    ("tail_calls", "Run Sub calls in tail position in the caller's frame"),
+    ("implicit_variables", "Undeclared variables: allow, report or deny"),
+    ("memory_limit", "Bytes the macro's values may hold before error 7"),
+    ("dll_policy", "Declare'd procedures without a handler: deny or stub"),
+    ("interactions", "SendKeys and AppActivate: record, deny or ignore"),
+    ("expose_environment", "Let Environ, Command$ and CurDir see the host process"),
+    ("sandbox", "Security profile: analysis, trusted or interactive"),
+    ("stop_policy", "Stop statements and breakpoints: halt, pause or ignore"),
+    ("auto_run", "Sub run on execute: first_found, auto_exec_only, disabled or a Sub name"),
+];
+
+/// One setting as `RuntimeConfig::describe` reports it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigSetting {
+    pub key: &'static str,
+    pub description: &'static str,
+    pub value: String,
+    pub default: String,
+}
+
+impl RuntimeConfig {
+    /// Every setting the sources accept, with its current and default value
+    pub fn describe(&self) -> Vec<ConfigSetting> {
+        let defaults = RuntimeConfig::default();
+        SETTINGS
+            .iter()
+            .map(|&(key, description)| ConfigSetting {
+                key,
+                description,
+                value: setting_value(self, key),
+                default: setting_value(&defaults, key),
+            })
+            .collect()
+    }
+}
+
+impl RuntimeConfigBuilder {
+    /// Apply one setting by key, from text ("64", "true", "Europe/Berlin")
+    pub fn set(self, key: &str, value: &str) -> Self {
+        let key = key.trim().to_ascii_lowercase().replace('-', "_");
+        let value = value.trim();
+        // Try it on a scratch builder first so a bad value leaves this one as it was
+        match apply(RuntimeConfigBuilder::default(), &key, value) {
+            Ok(_) => apply(self, &key, value).expect("setting was checked"),
+            Err(message) => self.error(format!("{}: {}", key, message)),
+        }
+    }
+
+    /// Apply the top-level settings of a TOML document
+    pub fn toml(mut self, text: &str) -> Self {
+        match parse_toml(text) {
+            Ok(pairs) => {
+                for (key, value) in pairs {
+                    self = self.set(&key, &value);
+                }
+                self
+            }
+            Err(message) => self.error(format!("TOML: {}", message)),
+        }
+    }
+
+    /// Apply the members of a JSON object
+    pub fn json(mut self, text: &str) -> Self {
+        match parse_json(text) {
+            Ok(pairs) => {
+                for (key, value) in pairs.into_iter().flat_map(|(key, value)| Some((key, value?))) {
+                    self = self.set(&key, &value);
+                }
+                self
+            }
+            Err(message) => self.error(format!("JSON: {}", message)),
+        }
+    }
+
+    /// Apply a settings file: JSON for `.json`, TOML otherwise
+    pub fn file(self, path: impl AsRef<std::path::Path>) -> Self {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(text) if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) => self.json(&text),
+            Ok(text) => self.toml(&text),
+            Err(e) => self.error(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    /// Apply `VBA_<KEY>` variables of the process environment
+    pub fn env(self) -> Self {
+        self.env_vars(std::env::vars())
+    }
+
+    /// Apply `VBA_<KEY>` variables from `vars`; other variables, and
+    /// `VBA_` names that are not settings, are left alone
+    pub fn env_vars(mut self, vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        for (name, value) in vars {
+            let Some(key) = name.strip_prefix(ENV_PREFIX).map(str::to_ascii_lowercase) else {
+                continue;
+            };
+            if SETTINGS.iter().any(|(setting, _)| *setting == key) {
+                self = self.set(&key, &value);
+            }
+        }
+        self
+    }
+}
+
+fn apply(builder: RuntimeConfigBuilder, key: &str, value: &str) -> Result<RuntimeConfigBuilder, String> {
+    Ok(match key {
+        "timezone" => builder.timezone(value),
+        "locale" => builder.locale(value),
+        "locale_cell_parsing" => builder.locale_cell_parsing(flag(value)?),
+        "workbook_id" => builder.workbook_id(value),
+        "user_id" => builder.user_id(value),
+        "first_day_of_week" => builder.first_day_of_week(in_range(value, 1, 7)? as u8),
+        "first_week_of_year" => builder.first_week_of_year(in_range(value, 1, 3)? as u8),
+        "engine" => builder.engine(choose(value, &[ExecutionEngine::Vm, ExecutionEngine::Interpreter])?),
+        "host" => builder.host(choose(value, &[HostApplication::Excel, HostApplication::Word])?),
+        "unknown_objects" => {
+            builder.unknown_objects(choose(value, &[UnknownObjectPolicy::Record, UnknownObjectPolicy::Error])?)
+        }
+        "excel_backend" => builder.excel_backend(match value.to_ascii_lowercase().as_str() {
+            "native" => ExcelBackendKind::Native,
+            "static" => ExcelBackendKind::Static,
+            _ => return Err(format!("expected native or static, got '{}'", value)),
+        }),
+        "write_batching" => builder.write_batching(flag(value)?),
+        "dry_run" => builder.dry_run(flag(value)?),
+        "rollback_on_error" => builder.rollback_on_error(flag(value)?),
+        "hibernate_on_wait" => builder.hibernate_on_wait(flag(value)?),
+        "max_call_depth" => builder.max_call_depth(in_range(value, 1, usize::MAX)?),
+        "tail_calls" => builder.tail_calls(flag(value)?),
+        "implicit_variables" => builder.implicit_variables(choose(
+            value,
+            &[ImplicitVariablePolicy::Allow, ImplicitVariablePolicy::Report, ImplicitVariablePolicy::Deny],
+        )?),
+        "memory_limit" => builder.memory_limit(in_range(value, 1, usize::MAX)?),
+        "dll_policy" => builder.dll_policy(choose(value, &[DllPolicy::Deny, DllPolicy::Stub])?),
+        "interactions" => builder.interactions(choose(
+            value,
+            &[InteractionPolicy::Record, InteractionPolicy::Deny, InteractionPolicy::Ignore],
+        )?),
+        "expose_environment" => builder.expose_environment(flag(value)?),
+        "sandbox" => builder.sandbox(choose(
+            value,
+            &[SandboxProfile::Analysis, SandboxProfile::Trusted, SandboxProfile::Interactive],
+        )?),
+        "stop_policy" => builder.stop_policy(choose(value, &[StopPolicy::Halt, StopPolicy::Pause, StopPolicy::Ignore])?),
+        "auto_run" => builder.auto_run(match snake_case(value).as_str() {
+            "first_found" => AutoRunPolicy::FirstFound,
+            "auto_exec_only" => AutoRunPolicy::AutoExecOnly,
+            "disabled" => AutoRunPolicy::Disabled,
+            _ if !value.is_empty() => AutoRunPolicy::Named(value.to_string()),
+            _ => return Err("expected a Sub name".to_string()),
+        }),
+        _ => return Err("unknown setting".to_string()),
+    })
+}
+
+fn setting_value(config: &RuntimeConfig, key: &str) -> String {
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "none".to_string());
+    match key {
+        "timezone" => config.timezone_name().to_string(),
+        "locale" => config.locale.clone(),
+        "locale_cell_parsing" => config.locale_cell_parsing.to_string(),
+        "workbook_id" => optional(config.workbook_id.clone()),
+        "user_id" => optional(config.user_id.clone()),
+        "first_day_of_week" => config.first_day_of_week.to_string(),
+        "first_week_of_year" => config.first_week_of_year.to_string(),
+        "engine" => variant_name(&config.engine),
+        "host" => variant_name(&config.host),
+        "unknown_objects" => variant_name(&config.unknown_objects),
+        "excel_backend" => match &config.excel_backend {
+            ExcelBackendKind::Native => "native".to_string(),
+            ExcelBackendKind::Static => "static".to_string(),
+            other => format!("{:?}", other),
+        },
+        "write_batching" => config.write_batching.to_string(),
+        "dry_run" => config.dry_run.to_string(),
+        "rollback_on_error" => config.rollback_on_error.to_string(),
+        "hibernate_on_wait" => config.hibernate_on_wait.to_string(),
+        "max_call_depth" => config.max_call_depth.to_string(),
+        "tail_calls" => config.tail_calls.to_string(),
+        "implicit_variables" => variant_name(&config.implicit_variables),
+        "memory_limit" => optional(config.memory_limit.map(|bytes| bytes.to_string())),
+        "dll_policy" => variant_name(&config.dll_policy),
+        "interactions" => variant_name(&config.interactions),
+        "expose_environment" => config.expose_environment.to_string(),
+        "sandbox" => optional(config.sandbox.as_ref().map(variant_name)),
+        "stop_policy" => variant_name(&config.stop_policy),
+        "auto_run" => match &config.auto_run {
+            AutoRunPolicy::Named(name) => name.clone(),
+            other => variant_name(other),
+        },
+        _ => String::new(),
+    }
+}
+
+/// `AutoExecOnly` → `auto_exec_only`
+fn variant_name(value: &impl Debug) -> String {
+    snake_case(&format!("{:?}", value))
+}
+
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            out.push('_');
+        }
+        out.push(if c == '-' { '_' } else { c.to_ascii_lowercase() });
+    }
+    out
+}
+
+fn choose<T: Debug + Copy>(value: &str, options: &[T]) -> Result<T, String> {
+    let wanted = value.to_ascii_lowercase().replace('-', "_");
+    options.iter().copied().find(|option| variant_name(option) == wanted).ok_or_else(|| {
+        let names: Vec<String> = options.iter().map(variant_name).collect();
+        format!("expected {}, got '{}'", names.join(", "), value)
+    })
+}
+
+fn flag(value: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ => Err(format!("expected true or false, got '{}'", value)),
+    }
+}
+
+fn in_range(value: &str, low: usize, high: usize) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(n) if (low..=high).contains(&n) => Ok(n),
+        Ok(_) if high == usize::MAX => Err(format!("expected at least {}, got {}", low, value)),
+        Ok(_) => Err(format!("expected {} to {}, got {}", low, high, value)),
+        Err(_) => Err(format!("expected a whole number, got '{}'", value)),
+    }
+}
+
+/// Top-level settings of a TOML document, as text
+fn parse_toml(text: &str) -> Result<Vec<(String, String)>, String> {
+    let table: toml::Table = text.parse().map_err(|e: toml::de::Error| match e.span() {
+        Some(span) => format!("line {}: {}", text[..span.start].lines().count().max(1), e.message()),
+        None => e.message().to_string(),
+    })?;
+    table
+        .into_iter()
+        .map(|(key, value)| {
+            let text = match value {
+                toml::Value::String(s) => s,
+                toml::Value::Integer(n) => n.to_string(),
+                toml::Value::Float(n) => n.to_string(),
+                toml::Value::Boolean(b) => b.to_string(),
+                toml::Value::Datetime(d) => d.to_string(),
+                toml::Value::Array(_) | toml::Value::Table(_) => {
+                    return Err(format!("\"{}\" must be a string, number or boolean; settings are not nested", key))
+                }
+            };
+            Ok((key, text))
+        })
+        .collect()
+}
+
+/// Members of a JSON object, as text; null values are None
+fn parse_json(text: &str) -> Result<Vec<(String, Option<String>)>, String> {
+    let object = match serde_json::from_str(text).map_err(|e| e.to_string())? {
+        serde_json::Value::Object(object) => object,
+        _ => return Err("expected an object".to_string()),
+    };
+    object
+        .into_iter()
+        .map(|(key, value)| {
+            let text = match value {
+                serde_json::Value::Null => None,
+                serde_json::Value::String(s) => Some(s),
+                serde_json::Value::Number(n) => Some(n.to_string()),
+                serde_json::Value::Bool(b) => Some(b.to_string()),
+                serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+                    return Err(format!("\"{}\" must be a string, number, boolean or null", key))
+                }
+            };
+            Ok((key, text))
+        })
+        .collect()
+}
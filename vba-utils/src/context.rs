@@ -1,9 +1,14 @@
 // vba-utils/src/context.rs
 
 use std::collections::{HashMap, HashSet};
-use crate::ast::Statement;
+use std::rc::Rc;
+use crate::ast::Body;
+use crate::error::Limit;
 use crate::host::ComRegistry;
 use crate::runtime_config::RuntimeConfig;
+use crate::scope::{FrameLayout, LocalSlot, ScopeFrame};
+use crate::symbol::Symbol;
+use crate::vba_errors::VbaErr;
 
 pub type VbaValue = Value;
 
@@ -239,7 +244,7 @@ impl Value {
 
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum DeclaredType {
     Boolean,
     Byte,
@@ -253,14 +258,39 @@ pub enum DeclaredType {
     Object,
     Single,
     String,
+    #[default]
     Variant, // when no type is provided in Dim
 }
 
 impl DeclaredType {
+    /// Value of a freshly declared variable of this type
+    pub fn default_value(self) -> Value {
+        match self {
+            DeclaredType::Byte     => Value::Byte(0),
+            DeclaredType::Integer  => Value::Integer(0),
+            DeclaredType::Long     => Value::Long(0),
+            DeclaredType::LongLong => Value::LongLong(0),
+            DeclaredType::Object   => Value::Object(None),
+            DeclaredType::Currency => Value::Currency(0.0),
+            DeclaredType::Date     => Value::Date(chrono::NaiveDate::from_ymd_opt(1899, 12, 30).unwrap()),
+            DeclaredType::Double   => Value::Double(0.0),
+            DeclaredType::Decimal  => Value::Decimal(0.0),
+            DeclaredType::Single   => Value::Single(0.0),
+            DeclaredType::String   => Value::String(String::new()),
+            DeclaredType::Boolean  => Value::Boolean(false),
+            DeclaredType::Variant  => Value::Empty,  // Uninitialized Variant is Empty
+        }
+    }
+
     pub fn from_opt_str(s: Option<&str>) -> Self {
         match s.map(|t| t.trim().to_ascii_lowercase()).as_deref() {
             Some("byte")     => DeclaredType::Byte,
             Some("integer")  => DeclaredType::Integer,
+            Some("long")     => DeclaredType::Long,
+            // 64-bit LongPtr; `Context::declared_type` follows Win64
+            Some("longlong") | Some("longptr") => DeclaredType::LongLong,
+            Some("single")   => DeclaredType::Single,
+            Some("object")   => DeclaredType::Object,
             Some("currency") => DeclaredType::Currency,
             Some("date")     => DeclaredType::Date,
             Some("double")   => DeclaredType::Double,
@@ -286,31 +316,40 @@ impl Default for ScopeKind {
     }
 }
 
-#[derive(Debug, Default, Clone)]
-struct ScopeFrame {
-    name: Option<String>,
-    kind: ScopeKind,
-    vars: HashMap<String, Value>,
-    types: HashMap<String, DeclaredType>,
-}
+/// A registered Sub/Function/Property: parameters and body.
+///
+/// Both halves are reference counted so calls and VM frames share the
+/// definition instead of copying the AST on every invocation.
+pub type Procedure = (Rc<[crate::ast::Parameter]>, Body);
 
 /// Execution context: holds variables, output **and** subroutine definitions.
 ///
 /// NOTE: `variables` remains your **global** scope for backward compatibility.
-/// New local scopes are held in the private `scopes` stack.
+/// Procedure locals are held in the private `scopes` stack; see [`crate::scope`].
 #[derive(Debug, Default)]
 pub struct Context {
     /// Messages logged (e.g. via MsgBox)
     pub output: Vec<String>,
     /// Global/module-level variables (backward compatible)
-    pub variables: HashMap<String, Value>,
+    pub variables: HashMap<Symbol, Value>,
     /// Subroutine/Function definitions: name → (params, body)
-    pub subs: HashMap<String, (Vec<crate::ast::Parameter>, Vec<Statement>)>,
+    pub subs: HashMap<Symbol, Procedure>,
     /// Function return types: name → return type
-    pub function_return_types: HashMap<String, Option<String>>,
+    pub function_return_types: HashMap<Symbol, Option<String>>,
+    /// Local slot layout per registered procedure, keyed like `subs`
+    layouts: HashMap<Symbol, Rc<FrameLayout>>,
+    /// Modules defining each procedure name, in load order. A procedure
+    /// loaded from a named module is also in `subs` as `Module.Name`.
+    procedure_modules: HashMap<Symbol, Vec<String>>,
+    /// Module whose declarations are being registered (see
+    /// `ProgramExecutor::with_module`)
+    pub(crate) loading_module: Option<String>,
+    /// Module of the Sub `run_subroutine` started, for calls made before
+    /// any procedure scope is pushed
+    pub(crate) entry_module: Option<String>,
 
     // global declared types (module level), parallel to `variables`
-    global_types: HashMap<String, DeclaredType>,
+    global_types: HashMap<Symbol, DeclaredType>,
     
     pub enums: HashMap<String, EnumDefinition>,
 
@@ -318,6 +357,8 @@ pub struct Context {
 
     // private overlay scopes (top is current). Not visible to callers.
     scopes: Vec<ScopeFrame>,
+    /// Values of `Static` locals between calls: procedure -> name -> value
+    statics: HashMap<Symbol, HashMap<Symbol, Value>>,
 
     pub err: Option<ErrObject>,          // last runtime error
     pub on_error_mode: OnErrorMode,      // current mode
@@ -325,9 +366,17 @@ pub struct Context {
     pub resume_valid: bool,
     pub resume_pc: Option<usize>,
     pub resume_location: Option<ResumeLocation>,
+    /// The last error raised met no handler (`OnErrorMode::None`), so the
+    /// procedure it was raised in stopped; a handler taking a later error
+    /// clears it
+    pub(crate) unhandled_error: bool,
 
     pub option_explicit: bool,           // Whether Option Explicit is active
-    declared_vars: HashSet<String>,
+    declared_vars: HashSet<Symbol>,
+    /// DefInt/DefStr/... type per first letter A-Z (Variant when unset)
+    def_types: [DeclaredType; 26],
+    /// `Declare` statements by VBA name
+    declared_procedures: HashMap<Symbol, crate::host::dll::DeclaredProcedure>,
     pub com_registry: ComRegistry,
     
     /// Stack of With block objects (for .Property syntax)
@@ -336,18 +385,160 @@ pub struct Context {
     /// Runtime configuration (timezone, locale, workbook, user)
     /// Passed from application layer at session start
     pub runtime_config: RuntimeConfig,
+
+    /// Statement trace being recorded by the VM, if enabled
+    pub trace: Option<crate::vm::trace::TraceRecorder>,
+
+    /// Line and branch hit counts collected by the VM, if enabled
+    pub coverage: Option<crate::vm::coverage::CoverageHits>,
+
+    /// Procedure and line timings collected while profiling, if enabled
+    pub profiler: Option<crate::vm::profile::Profiler>,
+
+    /// Spans recorded for the run, if enabled (see [`crate::vm::telemetry`])
+    pub telemetry: Option<crate::vm::telemetry::Telemetry>,
+
+    /// Identifies this run in traces and spans
+    run_id: String,
+
+    /// Cells, formats and names of the in-memory workbook this context runs
+    /// against; entered by `ProgramExecutor` so the `static_*` functions
+    /// reach it (see [`crate::host::excel::static_engine::StaticWorkbook`])
+    pub static_workbook: crate::host::excel::static_engine::StaticWorkbook,
+
+    /// Journal of this run's writes while it is journaled (see
+    /// [`crate::host::journal`])
+    pub active_journal: Option<crate::host::journal::ActiveJournal>,
+
+    /// Plan of this run's writes while it is a dry run (see
+    /// [`crate::host::dry_run`])
+    pub dry_run_plan: Option<crate::host::dry_run::ActivePlan>,
+
+    /// Embedder callbacks around statements and host calls, if installed
+    pub(crate) hooks: Option<crate::vm::hooks::HookSlot>,
+
+    /// Set by [`Context::request_hibernation`] until the VM stops
+    pub(crate) hibernation_requested: bool,
+    /// VMs currently running on the Rust stack (nested calls add one each)
+    pub(crate) vm_nesting: usize,
+    /// Image of the run left by the VM when it hibernated
+    hibernation: Option<crate::vm::Hibernation>,
+
+    /// Where the last `Stop` or breakpoint was reached
+    stop: Option<StopReport>,
+    /// Undeclared variables recorded under ImplicitVariablePolicy::Report
+    implicit_variables: Vec<ImplicitVariable>,
+    /// Set when StopPolicy::Halt ended the run; every VM unwinds
+    pub(crate) halted: bool,
+    /// Run the next statement without checking breakpoints: the one just
+    /// reported, or the first after resuming a paused run
+    pub(crate) skip_breakpoint: bool,
+
+    /// Virtual time Now() reads; None follows the system clock
+    clock: Option<chrono::NaiveDateTime>,
+    /// Procedures waiting on Application.OnTime, earliest first
+    schedule: Vec<crate::host::ontime::ScheduledProcedure>,
+    /// Scripted InputBox answers not used yet (see `RuntimeConfig::input_answers`)
+    input_answers: std::collections::VecDeque<Option<String>>,
 }
 
 impl Context {
     pub fn has_sub(&self, name: &str) -> bool {
-        self.subs.contains_key(name)
+        self.subs.contains_key(&Symbol::intern(name))
+    }
+
+    /// Look up a Sub/Function/Property body by (case-insensitive) name.
+    pub fn get_sub(&self, name: &str) -> Option<&Procedure> {
+        self.subs.get(&Symbol::intern(name))
     }
+
     /// Register a subroutine (called during Phase 1)
-    pub fn register_sub(&mut self, name: &str, params: &[crate::ast::Parameter], body: &[Statement]) {
-        self.subs.insert(
-            name.to_string(),
-            (params.to_vec(), body.to_vec()),
-        );
+    pub fn register_sub(&mut self, name: &str, params: &[crate::ast::Parameter], body: &Body) {
+        self.insert_procedure(Symbol::intern(name), params.into(), body.clone(), None);
+    }
+
+    /// Register a `Declare` statement (called during Phase 1)
+    pub fn register_declared(&mut self, name: &str, procedure: crate::host::dll::DeclaredProcedure) {
+        self.declared_procedures.insert(Symbol::intern(name), procedure);
+    }
+
+    /// Look up a `Declare`d procedure
+    pub fn get_declared(&self, name: &str) -> Option<&crate::host::dll::DeclaredProcedure> {
+        self.declared_procedures.get(&Symbol::intern(name))
+    }
+
+    /// Store a procedure together with the slot layout of its locals, also
+    /// as `Module.Name` while a module is loading. Returns that qualified key.
+    fn insert_procedure(
+        &mut self,
+        key: Symbol,
+        params: Rc<[crate::ast::Parameter]>,
+        body: Body,
+        return_var: Option<&str>,
+    ) -> Option<Symbol> {
+        let layout = Rc::new(FrameLayout::for_procedure(&params, &body, return_var));
+        let qualified = self.loading_module.clone().map(|module| {
+            let modules = self.procedure_modules.entry(key).or_default();
+            if !modules.iter().any(|m| m.eq_ignore_ascii_case(&module)) {
+                modules.push(module.clone());
+            }
+            let qualified = Symbol::intern(&format!("{}.{}", module, key.as_str()));
+            self.layouts.insert(qualified, layout.clone());
+            self.subs.insert(qualified, (params.clone(), body.clone()));
+            qualified
+        });
+        self.layouts.insert(key, layout);
+        self.subs.insert(key, (params, body));
+        qualified
+    }
+
+    /// Modules that define a procedure called `name`, in load order; empty
+    /// for procedures registered outside a named module
+    pub fn procedure_modules(&self, name: &str) -> &[String] {
+        self.procedure_modules.get(&Symbol::intern(name)).map_or(&[], Vec::as_slice)
+    }
+
+    /// True when `name` is a loaded module (one that defines a procedure)
+    pub fn is_module(&self, name: &str) -> bool {
+        self.procedure_modules.values().flatten().any(|m| m.eq_ignore_ascii_case(name))
+    }
+
+    /// Module of a registered procedure: the qualifier of `Module.Name`, or
+    /// the only module defining `name`
+    pub fn module_of(&self, name: &str) -> Option<String> {
+        match name.split_once('.') {
+            Some((module, _)) => Some(module.to_string()),
+            None => match self.procedure_modules(name) {
+                [module] => Some(module.clone()),
+                _ => None,
+            },
+        }
+    }
+
+    /// The `subs` key a call to `name` runs, None when there is no such
+    /// procedure. `Module2.Init` is Module2's Init. A bare name defined in
+    /// several modules is the caller's own module's; from any other module
+    /// the call raises error 35 (VBA refuses to compile it with "Ambiguous
+    /// name detected").
+    pub fn resolve_procedure(&self, name: &str) -> anyhow::Result<Option<String>> {
+        if let Some((module, procedure)) = name.split_once('.') {
+            let found = self.procedure_modules(procedure).iter().find(|m| m.eq_ignore_ascii_case(module));
+            return Ok(found.map(|module| format!("{}.{}", module, procedure)));
+        }
+        let modules = self.procedure_modules(name);
+        if modules.len() < 2 {
+            return Ok(self.has_sub(name).then(|| name.to_string()));
+        }
+        let caller = match self.current_procedure() {
+            Some(procedure) => self.module_of(procedure),
+            None => self.entry_module.clone(),
+        };
+        match caller.and_then(|c| modules.iter().find(|m| m.eq_ignore_ascii_case(&c))) {
+            Some(module) => Ok(Some(format!("{}.{}", module, name))),
+            None => Err(VbaErr::SubOrFunctionNotDefined.message(
+                format!("Ambiguous name detected: {} (defined in {})", name, modules.join(", ")),
+            )),
+        }
     }
 
     /// Register a function (called during Phase 1)
@@ -355,14 +546,14 @@ impl Context {
         &mut self,
         name: &str,
         params: &[crate::ast::Parameter],
-        body: &[Statement],
+        body: &Body,
         return_type: &Option<String>,
     ) {
-        self.subs.insert(
-            name.to_string(),
-            (params.to_vec(), body.to_vec()),
-        );
-        self.function_return_types.insert(name.to_string(), return_type.clone());
+        let sym = Symbol::intern(name);
+        if let Some(qualified) = self.insert_procedure(sym, params.into(), body.clone(), Some(name)) {
+            self.function_return_types.insert(qualified, return_type.clone());
+        }
+        self.function_return_types.insert(sym, return_type.clone());
     }
 
     /// Register a property (called during Phase 1)
@@ -371,37 +562,96 @@ impl Context {
         prop_type: &str, // "Get", "Let", "Set"
         name: &str,
         params: &[crate::ast::Parameter],
-        body: &[Statement],
+        body: &Body,
     ) {
         // Store with type prefix to distinguish Get/Let/Set
         let key = format!("{}_{}", prop_type, name);
-        self.register_sub(&key, params, body);
+        self.insert_procedure(Symbol::intern(&key), params.into(), body.clone(), Some(name));
     }
 
     pub fn log(&mut self, msg: &str) {
         println!("{}", msg);
+        if let Some(telemetry) = self.telemetry.as_mut() {
+            telemetry.event(msg);
+        }
         self.output.push(msg.to_string());
     }
 
-    /// Back-compat assignment:
-    /// - If a variable already exists in any active scope (from innermost to outermost), update it there.
-    /// - Otherwise, assign to the **global** map (as the old code did).
-    pub fn set_var(&mut self, name: String, val: Value) {
-        // Try innermost → outermost local scopes
-        for i in (0..self.scopes.len()).rev() {
-            if self.scopes[i].vars.contains_key(&name) {
-                self.scopes[i].vars.insert(name, val);
+    /// Identifies this run: `RuntimeConfig::run_id`, or a fresh ID
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    /// Parse `source` with the configured compiler constants, inside a
+    /// parse span when telemetry is on
+    pub fn parse_source(&mut self, source: &str) -> Result<crate::ast::Program, crate::error::VbaError> {
+        let start = std::time::SystemTime::now();
+        let program = crate::ast::parse_source_with_constants(source, &self.runtime_config.compiler_constants);
+        if let Some(telemetry) = self.telemetry.as_mut() {
+            telemetry.record("parse", crate::vm::telemetry::SpanKind::Parse, start, program.as_ref().err().map(ToString::to_string));
+        }
+        program
+    }
+
+    /// Index of the first scope visible from the innermost one: block
+    /// scopes on top plus the procedure frame below them.
+    fn lexical_start(&self) -> usize {
+        self.scopes
+            .iter()
+            .rposition(|f| f.kind != ScopeKind::Block)
+            .unwrap_or(0)
+    }
+
+    /// Assignment:
+    /// - If the name is a local visible from the current procedure, update it there.
+    /// - Otherwise, if it is a module-level variable, update the global.
+    /// - Otherwise it is implicit: local to the current procedure, or global
+    ///   when no procedure is running (as the old code did).
+    pub fn set_var(&mut self, name: impl Into<Symbol>, val: Value) {
+        let name = name.into();
+        let start = self.lexical_start();
+        for i in (start..self.scopes.len()).rev() {
+            if self.scopes[i].has_slot(name) {
+                self.scopes[i].declare(name, val);
+                return;
+            }
+            if let Some(slot) = self.scopes[i].get_mut(name) {
+                *slot = val;
                 return;
             }
         }
-        // Fall back to global (old behavior)
-        self.variables.insert(name, val);
+        if let Some(slot) = self.variables.get_mut(&name) {
+            *slot = val;
+            return;
+        }
+        match self.scopes.last_mut() {
+            Some(top) => top.declare(name, val),
+            None => {
+                self.variables.insert(name, val);
+            }
+        }
+    }
+    /// Define a module-level variable from Rust before (or between) runs, so
+    /// macros can read embedder data without `Dim`. A global the program
+    /// declares keeps its type and the value is converted to it. The name
+    /// counts as declared under Option Explicit.
+    pub fn define_global(&mut self, name: &str, value: Value) -> anyhow::Result<()> {
+        let symbol = Symbol::intern(name);
+        let value = match self.global_types.get(&symbol) {
+            Some(ty) => crate::interpreter::coerce::coerce_to_declared(value, *ty)?,
+            None => value,
+        };
+        self.variables.insert(symbol, value);
+        self.declared_vars.insert(symbol);
+        Ok(())
     }
-    pub fn set_var_type(&mut self, name: String, ty: DeclaredType) {
-        // try innermost → outermost local scopes
-        for i in (0..self.scopes.len()).rev() {
-            if self.scopes[i].vars.contains_key(&name) || self.scopes[i].types.contains_key(&name) {
-                self.scopes[i].types.insert(name, ty);
+
+    pub fn set_var_type(&mut self, name: impl Into<Symbol>, ty: DeclaredType) {
+        let name = name.into();
+        let start = self.lexical_start();
+        for i in (start..self.scopes.len()).rev() {
+            if self.scopes[i].owns(name) {
+                self.scopes[i].set_type(name, ty);
                 return;
             }
         }
@@ -410,78 +660,232 @@ impl Context {
     }
 
 
-    /// Scope-aware lookup: check innermost → outermost local scopes first, then global.
-    pub fn get_var(&self, name: &str) -> Option<Value> {
-        for frame in self.scopes.iter().rev() {
-            if let Some(v) = frame.vars.get(name) {
+    /// Lexical lookup: the current procedure's locals first, then globals.
+    pub fn get_var(&self, name: impl Into<Symbol>) -> Option<Value> {
+        let name = name.into();
+        for frame in self.scopes[self.lexical_start()..].iter().rev() {
+            if let Some(v) = frame.get(name) {
                 return Some(v.clone());
             }
         }
-        self.variables.get(name).cloned()
+        self.variables.get(&name).cloned()
     }
-    pub fn get_var_type(&self, name: &str) -> Option<DeclaredType> {
-        for frame in self.scopes.iter().rev() {
-            if let Some(t) = frame.types.get(name) {
-                return Some(*t);
+    pub fn get_var_type(&self, name: impl Into<Symbol>) -> Option<DeclaredType> {
+        let name = name.into();
+        for frame in self.scopes[self.lexical_start()..].iter().rev() {
+            if let Some(t) = frame.get_type(name) {
+                return Some(t);
             }
         }
-        self.global_types.get(name).copied()
+        self.global_types.get(&name).copied()
+    }
+
+    /// Every variable visible from the current procedure, locals shadowing
+    /// globals.
+    pub fn visible_vars(&self) -> HashMap<Symbol, &Value> {
+        let mut vars: HashMap<Symbol, &Value> = self.variables.iter().map(|(k, v)| (*k, v)).collect();
+        for frame in &self.scopes[self.lexical_start()..] {
+            vars.extend(frame.iter());
+        }
+        vars
+    }
+
+    /// Start recording a statement trace (VM engine only).
+    pub fn start_trace(&mut self) {
+        self.trace = Some(crate::vm::trace::TraceRecorder::new(self));
+    }
+
+    /// Stop recording and return what was captured.
+    pub fn take_trace(&mut self) -> Option<crate::vm::trace::Trace> {
+        self.trace.take().map(|r| r.finish())
+    }
+
+    /// Start counting line and branch hits (VM engine only).
+    pub fn start_coverage(&mut self) {
+        self.coverage = Some(crate::vm::coverage::CoverageHits::default());
+    }
+
+    /// Stop counting and return the hits so far.
+    pub fn take_coverage(&mut self) -> Option<crate::vm::coverage::CoverageHits> {
+        self.coverage.take()
+    }
+
+    /// Start timing procedures (both engines) and lines (VM engine only).
+    pub fn start_profile(&mut self) {
+        self.profiler = Some(crate::vm::profile::Profiler::default());
+    }
+
+    /// Stop profiling and return the report.
+    pub fn take_profile(&mut self) -> Option<crate::vm::profile::ProfileReport> {
+        self.profiler.take().map(|p| p.finish())
+    }
+
+    /// Start recording spans for parsing, procedures and host calls (both
+    /// engines).
+    pub fn start_telemetry(&mut self) {
+        self.telemetry = Some(crate::vm::telemetry::Telemetry::new(&self.run_id));
+    }
+
+    /// Stop recording and return the spans, the run span closed last.
+    pub fn take_telemetry(&mut self) -> Option<crate::vm::telemetry::TelemetryReport> {
+        self.telemetry.take().map(|t| t.finish())
+    }
+
+    /// Resolve `name` to a slot of the innermost frame, for callers that
+    /// read the same local repeatedly (see `interpreter::compiled`).
+    /// Install callbacks the VM makes around every statement and host
+    /// call (see [`crate::vm::hooks`]), replacing any installed before.
+    pub fn set_hooks(&mut self, hooks: Box<dyn crate::vm::ExecutionHooks>) {
+        self.hooks = Some(crate::vm::hooks::HookSlot(hooks));
+    }
+
+    /// Remove the installed hooks, returning them.
+    pub fn take_hooks(&mut self) -> Option<Box<dyn crate::vm::ExecutionHooks>> {
+        self.hooks.take().map(|slot| slot.0)
+    }
+
+    pub fn resolve_local(&self, name: Symbol) -> Option<LocalSlot> {
+        self.scopes.last()?.resolve(name)
+    }
+
+    /// Read a slot resolved by [`Context::resolve_local`]. `None` when a
+    /// different procedure is running or the local is not declared yet.
+    pub fn get_local(&self, slot: LocalSlot) -> Option<&Value> {
+        self.scopes.last()?.get_slot(slot)
     }
 
 
     /// Define a subroutine for later calls.
-    pub fn define_sub(&mut self, name: String, params: Vec<crate::ast::Parameter>, body: Vec<Statement>) {
-        self.subs.insert(name, (params, body));
+    pub fn define_sub(&mut self, name: impl Into<Symbol>, params: Vec<crate::ast::Parameter>, body: impl Into<Body>) {
+        self.insert_procedure(name.into(), params.into(), body.into(), None);
     }
 
     /// Define a function with return type.
-    pub fn define_function(&mut self, name: String, params: Vec<crate::ast::Parameter>, body: Vec<Statement>, return_type: Option<String>) {
-        self.subs.insert(name.clone(), (params, body));
+    pub fn define_function(&mut self, name: impl Into<Symbol>, params: Vec<crate::ast::Parameter>, body: impl Into<Body>, return_type: Option<String>) {
+        let name = name.into();
+        self.insert_procedure(name, params.into(), body.into(), Some(name.as_str()));
         self.function_return_types.insert(name, return_type);
     }
 
     /// Check if a name is a function (has return type registered)
     pub fn is_function(&self, name: &str) -> bool {
-        self.function_return_types.contains_key(name)
+        self.function_return_types.contains_key(&Symbol::intern(name))
     }
 
     /// Save/restore **global** variable scope (unchanged API & semantics).
     /// If you used this around sub calls before, it will continue to work.
-    pub fn save_scope(&self) -> HashMap<String, Value> {
+    pub fn save_scope(&self) -> HashMap<Symbol, Value> {
         self.variables.clone()
     }
-    pub fn restore_scope(&mut self, old: HashMap<String, Value>) {
+    pub fn restore_scope(&mut self, old: HashMap<Symbol, Value>) {
         self.variables = old;
     }
 
     // === NEW: Scope management (non-breaking additions) =====================
 
-    /// Push a new local scope on the stack.
+    /// Push a new local scope on the stack. Procedure scopes pick up the
+    /// slot layout registered under `name`.
     pub fn push_scope(&mut self, name: impl Into<String>, kind: ScopeKind) {
-        self.scopes.push(ScopeFrame {
-            name: Some(name.into()),
-            kind,
-            vars: HashMap::new(),
-            types: HashMap::new(),
-        });
+        let name = name.into();
+        let layout = match kind {
+            ScopeKind::Block => None,
+            _ => self.layouts.get(&Symbol::intern(&name)).cloned(),
+        };
+        let layout = layout.unwrap_or_else(|| Rc::new(FrameLayout::empty()));
+        if let (Some(profiler), false) = (self.profiler.as_mut(), kind == ScopeKind::Block) {
+            profiler.enter(&name, self.scopes.len() + 1);
+        }
+        if let (Some(telemetry), false) = (self.telemetry.as_mut(), kind == ScopeKind::Block) {
+            telemetry.enter_procedure(&name, self.scopes.len() + 1);
+        }
+        self.scopes.push(ScopeFrame::new(Some(name), kind, layout));
+    }
+
+    /// Push the scope of a Sub/Function call, or raise error 28 ("Out of
+    /// stack space") if `RuntimeConfig::max_call_depth` calls are active.
+    pub fn enter_procedure(&mut self, name: impl Into<String>, kind: ScopeKind) -> anyhow::Result<()> {
+        let depth = self.scopes.iter().filter(|f| f.kind != ScopeKind::Block).count();
+        if depth >= self.runtime_config.max_call_depth {
+            return Err(Limit::CallDepth.error());
+        }
+        self.push_scope(name, kind);
+        Ok(())
+    }
+
+    /// Number of local scopes currently pushed.
+    pub fn scope_depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    /// Name of the innermost procedure scope, if one is active.
+    pub fn current_procedure(&self) -> Option<&str> {
+        self.scopes
+            .iter()
+            .rev()
+            .find(|f| f.kind != ScopeKind::Block)
+            .and_then(|f| f.name.as_deref())
     }
 
-    /// Pop the current local scope. No-op if there is none.
+    /// Pop the current local scope. No-op if there is none. `Static`
+    /// locals it held keep their values for the next call.
     pub fn pop_scope(&mut self) {
-        let _ = self.scopes.pop();
+        let Some(popped) = self.scopes.pop() else {
+            return;
+        };
+        let procedure = match popped.kind {
+            ScopeKind::Block => self.current_procedure(),
+            _ => popped.name.as_deref(),
+        }
+        .map(Symbol::intern);
+        if let Some(statics) = procedure.and_then(|p| self.statics.get_mut(&p)) {
+            for (name, value) in statics.iter_mut() {
+                if let Some(current) = popped.get(*name) {
+                    *value = current.clone();
+                }
+            }
+        }
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.leave(self.scopes.len() + 1);
+        }
+        if let Some(telemetry) = self.telemetry.as_mut() {
+            telemetry.leave_procedure(self.scopes.len() + 1);
+        }
+    }
+
+    /// Pop scopes until `depth` are left.
+    pub(crate) fn pop_scopes_to(&mut self, depth: usize) {
+        while self.scopes.len() > depth {
+            self.pop_scope();
+        }
     }
 
     /// Declare a local (or parameter) in the current scope. If no scope is active,
     /// declares in global (so callers don’t have to special-case).
-    pub fn declare_local(&mut self, name: impl Into<String>, initial: Value) {
+    pub fn declare_local(&mut self, name: impl Into<Symbol>, initial: Value) {
         if let Some(top) = self.scopes.last_mut() {
-            top.vars.insert(name.into(), initial);
+            top.declare(name.into(), initial);
         } else {
             // No active local scope, fall back to global for back-compat.
             self.variables.insert(name.into(), initial);
         }
     }
 
+    /// Value a `Static` local starts the current call with: what it held
+    /// when the procedure last returned, or `initial` on the first call.
+    /// Outside a procedure it is an ordinary variable.
+    pub fn declare_static(&mut self, name: impl Into<Symbol>, initial: Value) -> Value {
+        let Some(procedure) = self.current_procedure().map(Symbol::intern) else {
+            return initial;
+        };
+        self.statics.entry(procedure).or_default().entry(name.into()).or_insert(initial).clone()
+    }
+
+    /// Forget the values `Static` locals kept, as when the module
+    /// variables are initialized again.
+    pub(crate) fn reset_statics(&mut self) {
+        self.statics.clear();
+    }
+
     /// Helper: run a block within a scope (ensures pop even on early return/err).
     pub fn with_scope<R, F>(&mut self, name: impl Into<String>, kind: ScopeKind, f: F) -> R
     where
@@ -499,23 +903,152 @@ impl Context {
     pub fn save_all_scopes(&self) -> SavedScopes {
         SavedScopes {
             globals: self.variables.clone(),
-            stack: self.scopes.iter().map(|f| SavedScopeFrame {
-                name: f.name.clone(),
-                kind: f.kind,
-                vars: f.vars.clone(),
-                types: f.types.clone(),
-            }).collect(),
+            stack: self.scopes.clone(),
         }
     }
 
     pub fn restore_all_scopes(&mut self, snap: SavedScopes) {
         self.variables = snap.globals;
-        self.scopes = snap.stack.into_iter().map(|f| ScopeFrame {
-            name: f.name,
-            kind: f.kind,
-            vars: f.vars,
-            types: f.types,
-        }).collect();
+        self.scopes = snap.stack;
+    }
+
+    /// Capture the macro state: variables and local scopes, declared types,
+    /// error-handling state, the With stack and how much output was logged.
+    /// Host state (cells, documents, mail) is not part of the snapshot.
+    pub fn snapshot(&self) -> ContextSnapshot {
+        ContextSnapshot {
+            scopes: self.save_all_scopes(),
+            global_types: self.global_types.clone(),
+            declared_vars: self.declared_vars.clone(),
+            err: self.err.clone(),
+            on_error_mode: self.on_error_mode,
+            on_error_label: self.on_error_label.clone(),
+            resume_valid: self.resume_valid,
+            resume_pc: self.resume_pc,
+            resume_location: self.resume_location.clone(),
+            with_stack: self.with_stack.clone(),
+            output_len: self.output.len(),
+        }
+    }
+
+    /// Return to a snapshot taken from this context. Output logged since
+    /// the snapshot is dropped. A snapshot can be restored any number of
+    /// times, e.g. to retry after a host failure.
+    pub fn restore(&mut self, snapshot: &ContextSnapshot) {
+        self.restore_all_scopes(snapshot.scopes.clone());
+        self.global_types = snapshot.global_types.clone();
+        self.declared_vars = snapshot.declared_vars.clone();
+        self.err = snapshot.err.clone();
+        self.on_error_mode = snapshot.on_error_mode;
+        self.on_error_label = snapshot.on_error_label.clone();
+        self.resume_valid = snapshot.resume_valid;
+        self.resume_pc = snapshot.resume_pc;
+        self.resume_location = snapshot.resume_location.clone();
+        self.with_stack = snapshot.with_stack.clone();
+        self.output.truncate(snapshot.output_len);
+    }
+
+    /// Ask the VM to hibernate at its next statement boundary, e.g. from a
+    /// host object that waits for input. The run stops and
+    /// [`Context::take_hibernation`] returns its image; see [`crate::vm::hibernate`].
+    pub fn request_hibernation(&mut self) {
+        self.hibernation_requested = true;
+    }
+
+    /// Image of the run if it stopped by hibernating
+    pub fn take_hibernation(&mut self) -> Option<crate::vm::Hibernation> {
+        self.hibernation.take()
+    }
+
+    pub(crate) fn set_hibernation(&mut self, image: crate::vm::Hibernation) {
+        self.hibernation = Some(image);
+    }
+
+    /// Where the run reached a `Stop` statement or breakpoint, if it did
+    pub fn stop_report(&self) -> Option<&StopReport> {
+        self.stop.as_ref()
+    }
+
+    pub(crate) fn set_stop(&mut self, report: StopReport) {
+        self.stop = Some(report);
+    }
+
+    /// Variables the run created without a declaration, under
+    /// `ImplicitVariablePolicy::Report`; one entry per name and procedure
+    pub fn implicit_variables(&self) -> &[ImplicitVariable] {
+        &self.implicit_variables
+    }
+
+    /// `name` is about to be assigned. If that creates it without a
+    /// declaration, record it (Report) or refuse it (Deny, error 451).
+    pub(crate) fn create_implicit(&mut self, name: &str) -> Result<(), String> {
+        use crate::runtime_config::ImplicitVariablePolicy;
+        let policy = self.runtime_config.implicit_variables;
+        if policy == ImplicitVariablePolicy::Allow || self.is_variable_declared(name) || self.get_var(name).is_some() {
+            return Ok(());
+        }
+        if policy == ImplicitVariablePolicy::Deny {
+            return Err(format!("Variable '{}' is not declared (implicit variables are denied)", name));
+        }
+        let procedure = self.current_procedure().map(str::to_string);
+        if !self.implicit_variables.iter().any(|v| v.name.eq_ignore_ascii_case(name) && v.procedure == procedure) {
+            let variable = ImplicitVariable {
+                name: name.to_string(),
+                procedure,
+                line: crate::host::excel::engine::source_line(),
+            };
+            self.log(&variable.to_text());
+            self.implicit_variables.push(variable);
+        }
+        Ok(())
+    }
+
+    /// The current time in the user's timezone, or the virtual clock if
+    /// one is running
+    pub fn now(&self) -> chrono::NaiveDateTime {
+        self.clock.unwrap_or_else(|| {
+            chrono::Utc::now().with_timezone(&self.runtime_config.timezone).naive_local()
+        })
+    }
+
+    /// Start or move the virtual clock
+    pub fn set_clock(&mut self, at: chrono::NaiveDateTime) {
+        self.clock = Some(at);
+    }
+
+    /// Procedures waiting on Application.OnTime, earliest first
+    pub fn scheduled(&self) -> &[crate::host::ontime::ScheduledProcedure] {
+        &self.schedule
+    }
+
+    pub(crate) fn schedule_procedure(&mut self, entry: crate::host::ontime::ScheduledProcedure) {
+        let index = self.schedule.partition_point(|e| e.at <= entry.at);
+        self.schedule.insert(index, entry);
+    }
+
+    /// Drop the pending call of `procedure` at `at`; false if there is none
+    pub(crate) fn cancel_scheduled(&mut self, at: chrono::NaiveDateTime, procedure: &str) -> bool {
+        let found = self.schedule.iter().position(|e| {
+            e.at == at && crate::host::ontime::same_procedure(&e.procedure, procedure)
+        });
+        found.map(|index| self.schedule.remove(index)).is_some()
+    }
+
+    /// Remove and return the procedures due by `now`, earliest first
+    pub(crate) fn take_due(&mut self, now: chrono::NaiveDateTime) -> Vec<crate::host::ontime::ScheduledProcedure> {
+        let due = self.schedule.partition_point(|e| e.at <= now);
+        self.schedule.drain(..due).collect()
+    }
+
+    /// The next scripted InputBox answer (None inside for Cancel), if any
+    /// are left
+    pub(crate) fn next_input_answer(&mut self) -> Option<Option<String>> {
+        self.input_answers.pop_front()
+    }
+
+    /// Local scopes, outermost first
+    pub(crate) fn scope_frames(&self) -> &[ScopeFrame] {
+        &self.scopes
     }
 
     // Add method to define an enum:
@@ -591,7 +1124,7 @@ impl Context {
         
         // Get local variables from current scope (if any)
         if let Some(current_scope) = self.scopes.last() {
-            for name in current_scope.vars.keys() {
+            for (name, _) in current_scope.iter() {
                 vars.push(format!("Local: {}", name));
             }
         }
@@ -611,9 +1144,9 @@ impl Context {
         
         // Local variables from current scope
         if let Some(current_scope) = self.scopes.last() {
-            if !current_scope.vars.is_empty() {
+            if !current_scope.is_empty() {
                 output.push_str("Local scope:\n");
-                for (name, value) in &current_scope.vars {
+                for (name, value) in current_scope.iter() {
                     output.push_str(&format!("  {} = {:?}\n", name, value));
                 }
             }
@@ -646,12 +1179,38 @@ impl Context {
     
     /// Mark a variable as declared (for Option Explicit checking)
     pub fn declare_variable(&mut self, name: &str) {
-        self.declared_vars.insert(name.to_string());
+        self.declared_vars.insert(Symbol::intern(name));
     }
     
     /// Check if a variable has been declared
     pub fn is_variable_declared(&self, name: &str) -> bool {
-        self.declared_vars.contains(name)
+        self.declared_vars.contains(&Symbol::intern(name))
+    }
+    
+    /// `DefInt A-Z` and friends: names starting with `first..=last` default to `ty`
+    pub fn set_def_type(&mut self, first: char, last: char, ty: DeclaredType) {
+        for letter in first.to_ascii_uppercase()..=last.to_ascii_uppercase() {
+            if letter.is_ascii_uppercase() {
+                self.def_types[(letter as u8 - b'A') as usize] = ty;
+            }
+        }
+    }
+
+    /// The type `As <name>` declares in this run; `LongPtr` follows
+    /// [`RuntimeConfig::pointer_type`]
+    pub fn declared_type(&self, name: Option<&str>) -> DeclaredType {
+        match name.map(str::trim) {
+            Some(name) if name.eq_ignore_ascii_case("longptr") => self.runtime_config.pointer_type(),
+            name => DeclaredType::from_opt_str(name),
+        }
+    }
+
+    /// Type of a variable declared without `As` (or not declared at all)
+    pub fn def_type(&self, name: &str) -> DeclaredType {
+        match name.chars().next() {
+            Some(c) if c.is_ascii_alphabetic() => self.def_types[(c.to_ascii_uppercase() as u8 - b'A') as usize],
+            _ => DeclaredType::Variant,
+        }
     }
     
     /// Validate variable usage when Option Explicit is enabled
@@ -679,15 +1238,25 @@ impl Context {
     /// let mut ctx = Context::with_config(config);
     /// ```
     pub fn with_config(config: RuntimeConfig) -> Self {
-        Self {
+        let clock = config.schedule.clock_start;
+        let input_answers = config.input_answers.iter().cloned().collect();
+        let run_id = config.run_id.clone().unwrap_or_else(crate::vm::telemetry::new_run_id);
+        let mut ctx = Self {
             variables: HashMap::new(),
             scopes: Vec::new(),
+            statics: HashMap::new(),
             subs: HashMap::new(),
             function_return_types: HashMap::new(),
+            layouts: HashMap::new(),
+            procedure_modules: HashMap::new(),
+            loading_module: None,
+            entry_module: None,
             types: HashMap::new(),
             enums: HashMap::new(),
             global_types: HashMap::new(),
             declared_vars: HashSet::new(),
+            def_types: [DeclaredType::Variant; 26],
+            declared_procedures: HashMap::new(),
             option_explicit: false,
             on_error_mode: OnErrorMode::None,
             on_error_label: None, 
@@ -695,11 +1264,36 @@ impl Context {
             resume_valid: false,
             resume_pc: None,
             resume_location: None,
+            unhandled_error: false,
             output: Vec::new(),
             com_registry: ComRegistry::new(),
             with_stack: Vec::new(),
             runtime_config: config,
+            trace: None,
+            coverage: None,
+            profiler: None,
+            telemetry: None,
+            run_id,
+            static_workbook: Default::default(),
+            active_journal: None,
+            dry_run_plan: None,
+            hooks: None,
+            hibernation_requested: false,
+            vm_nesting: 0,
+            hibernation: None,
+            stop: None,
+            implicit_variables: Vec::new(),
+            halted: false,
+            skip_breakpoint: false,
+            clock,
+            schedule: Vec::new(),
+            input_answers,
+        };
+        for (name, value) in ctx.runtime_config.globals.clone() {
+            // Nothing is declared yet, so there is no type to convert to
+            let _ = ctx.define_global(&name, value);
         }
+        ctx
     }
 
 }
@@ -707,24 +1301,85 @@ impl Context {
 /// Full-snapshot types are private by default; make them `pub` if you need them externally.
 #[derive(Debug, Clone)]
 pub struct SavedScopes {
-    globals: HashMap<String, Value>,
-    stack: Vec<SavedScopeFrame>,
+    globals: HashMap<Symbol, Value>,
+    stack: Vec<ScopeFrame>,
 }
-
+/// Macro state captured by [`Context::snapshot`]
 #[derive(Debug, Clone)]
-struct SavedScopeFrame {
-    name: Option<String>,
-    kind: ScopeKind,
-    vars: HashMap<String, Value>,
-    types: HashMap<String, DeclaredType>,
+pub struct ContextSnapshot {
+    scopes: SavedScopes,
+    global_types: HashMap<Symbol, DeclaredType>,
+    declared_vars: HashSet<Symbol>,
+    err: Option<ErrObject>,
+    on_error_mode: OnErrorMode,
+    on_error_label: Option<String>,
+    resume_valid: bool,
+    resume_pc: Option<usize>,
+    resume_location: Option<ResumeLocation>,
+    with_stack: Vec<Value>,
+    output_len: usize,
+}
+
+/// A `Stop` statement or breakpoint the run reached
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StopReport {
+    /// Source line, when the VM knows it
+    pub line: Option<usize>,
+    /// A RuntimeConfig breakpoint rather than a `Stop` statement
+    pub breakpoint: bool,
+    /// The StopPolicy applied
+    pub policy: crate::runtime_config::StopPolicy,
 }
+
+impl StopReport {
+    /// `Stop at line 12 (halted)`
+    pub fn to_text(&self) -> String {
+        let what = if self.breakpoint { "Breakpoint" } else { "Stop" };
+        let outcome = match self.policy {
+            crate::runtime_config::StopPolicy::Halt => "halted",
+            crate::runtime_config::StopPolicy::Pause => "paused",
+            crate::runtime_config::StopPolicy::Ignore => "ignored",
+        };
+        match self.line {
+            Some(line) => format!("{} at line {} ({})", what, line, outcome),
+            None => format!("{} ({})", what, outcome),
+        }
+    }
+}
+
+/// A variable created by assigning to a name nothing declared
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImplicitVariable {
+    pub name: String,
+    /// Procedure it was created in; None at module level
+    pub procedure: Option<String>,
+    /// Source line of the first assignment, when the VM knows it
+    pub line: Option<usize>,
+}
+
+impl ImplicitVariable {
+    /// `Implicit variable Total in Main at line 12`
+    pub fn to_text(&self) -> String {
+        let mut text = format!("Implicit variable {}", self.name);
+        if let Some(procedure) = &self.procedure {
+            text.push_str(&format!(" in {}", procedure));
+        }
+        if let Some(line) = self.line {
+            text.push_str(&format!(" at line {}", line));
+        }
+        text
+    }
+}
+
 // === Error handling state (VBA-style) =====================================
 
 #[derive(Debug, Clone, Default)]
 pub struct ErrObject {
     pub number: i32,
     pub description: String,
-    pub source: String, 
+    pub source: String,
+    /// The `RuntimeConfig` limit that raised the error, if one did
+    pub limit: Option<Limit>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -774,4 +1429,425 @@ pub struct FieldDefinition {
     pub field_type: String,
     pub string_length: Option<i64>,
     pub is_array: bool,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_and_restore() {
+        let mut ctx = Context::new();
+        ctx.set_var("SnapTotal", Value::Integer(1));
+        ctx.push_scope("SnapSub", ScopeKind::Subroutine);
+        ctx.declare_local("SnapLocal", Value::String("before".into()));
+        ctx.with_stack.push(Value::String("Range:A1".into()));
+        ctx.log("first");
+        let snapshot = ctx.snapshot();
+
+        // Diverge: new values, an error, a deeper scope and more output
+        ctx.set_var("SnapTotal", Value::Integer(2));
+        ctx.set_var("SnapLocal", Value::String("after".into()));
+        ctx.push_scope("SnapInner", ScopeKind::Subroutine);
+        ctx.with_stack.clear();
+        ctx.on_error_mode = OnErrorMode::ResumeNextAuto;
+        ctx.err = Some(ErrObject { number: 11, description: "Division by zero".into(), source: String::new(), limit: None });
+        ctx.log("second");
+
+        // Restoring twice gives the same state each time
+        for _ in 0..2 {
+            ctx.restore(&snapshot);
+            assert_eq!(ctx.scope_depth(), 1);
+            assert_eq!(ctx.get_var("SnapLocal").unwrap().as_string(), "before");
+            assert_eq!(ctx.get_var("SnapTotal").unwrap().as_string(), "1");
+            assert_eq!(ctx.with_stack.len(), 1);
+            assert!(ctx.err.is_none());
+            assert_eq!(ctx.on_error_mode, OnErrorMode::None);
+            assert_eq!(ctx.output, vec!["first".to_string()]);
+            ctx.set_var("SnapTotal", Value::Integer(3));
+        }
+    }
+
+    fn depth_test_program() -> crate::ast::Program {
+        use crate::ast::{AssignmentTarget, Expression, Program, Statement};
+        let count = |name: &str| Statement::Assignment {
+            lvalue: AssignmentTarget::Identifier(name.into()),
+            rvalue: Expression::BinaryOp {
+                left: Box::new(Expression::Identifier(name.into())),
+                op: "+".into(),
+                right: Box::new(Expression::Integer(1)),
+            },
+            rvalue_compiled: Default::default(),
+        };
+        let call_self = Expression::FunctionCall {
+            function: Box::new(Expression::Identifier("DepthTestFn".into())),
+            args: vec![],
+        };
+        let recurse_sub = Statement::Subroutine {
+            visibility: None,
+            name: "DepthTestSub".into(),
+            params: vec![],
+            body: vec![count("DepthTestSubs"), Statement::Call { function: "DepthTestSub".into(), args: vec![] }].into(),
+        };
+        let recurse_fn = Statement::Function {
+            visibility: None,
+            name: "DepthTestFn".into(),
+            params: vec![],
+            return_type: None,
+            body: vec![
+                count("DepthTestFns"),
+                Statement::Assignment {
+                    lvalue: AssignmentTarget::Identifier("DepthTestFn".into()),
+                    rvalue: call_self.clone(),
+                    rvalue_compiled: Default::default(),
+                },
+            ]
+            .into(),
+        };
+        let main = |name: &str, stmt: Statement| Statement::Subroutine { visibility: None, name: name.into(), params: vec![], body: vec![stmt].into() };
+        Program {
+            statements: vec![
+                Statement::Dim { names: vec![("DepthTestSubs".into(), Some("Long".into())), ("DepthTestFns".into(), Some("Long".into()))] },
+                recurse_sub,
+                recurse_fn,
+                main("DepthTestSubMain", Statement::Call { function: "DepthTestSub".into(), args: vec![] }),
+                main("DepthTestFnMain", Statement::Expression(call_self)),
+            ],
+            diagnostics: vec![],
+        }
+    }
+
+    #[test]
+    fn test_call_depth_limit_raises_error_28() {
+        let executor = crate::vm::ProgramExecutor::new(depth_test_program());
+
+        // Sub recursion runs on VM frames
+        let mut ctx = Context::with_config(RuntimeConfig::builder().max_call_depth(50).build());
+        executor.execute_entrypoint(&mut ctx, "DepthTestSubMain").unwrap();
+        assert_eq!(ctx.err.as_ref().map(|e| (e.number, e.limit)), Some((28, Some(crate::error::Limit::CallDepth))));
+        // The entry Sub is one of the 50 calls
+        assert_eq!(ctx.get_var("DepthTestSubs").unwrap().as_string(), "49");
+        assert_eq!(ctx.scope_depth(), 0);
+
+        // Function recursion runs on the Rust stack (test threads have 2 MB)
+        let mut ctx = Context::with_config(RuntimeConfig::builder().max_call_depth(30).build());
+        executor.execute_entrypoint(&mut ctx, "DepthTestFnMain").unwrap();
+        assert_eq!(ctx.err.as_ref().map(|e| (e.number, e.limit)), Some((28, Some(crate::error::Limit::CallDepth))));
+        assert_eq!(ctx.get_var("DepthTestFns").unwrap().as_string(), "29");
+        assert_eq!(ctx.scope_depth(), 0);
+    }
+
+    #[test]
+    fn test_recursion_return_values_byref_and_tail_calls() {
+        use crate::ast::{AssignmentTarget, Expression, ExitType, Parameter, Program, Statement};
+        let id = |name: &str| Expression::Identifier(name.into());
+        let op = |left: Expression, op: &str, right: Expression| Expression::BinaryOp {
+            left: Box::new(left),
+            op: op.into(),
+            right: Box::new(right),
+        };
+        let call = |name: &str, args: Vec<Expression>| Expression::FunctionCall { function: Box::new(id(name)), args };
+        let assign = |name: &str, rvalue: Expression| Statement::Assignment {
+            lvalue: AssignmentTarget::Identifier(name.into()),
+            rvalue,
+            rvalue_compiled: Default::default(),
+        };
+        let when = |condition: Expression, then: Statement, otherwise: Vec<Statement>| Statement::If {
+            condition,
+            then_branch: vec![then].into(),
+            else_if: vec![],
+            else_branch: otherwise.into(),
+        };
+        let function = |name: &str, param: &str, body: Vec<Statement>| Statement::Function {
+            visibility: None,
+            name: name.into(),
+            params: vec![Parameter::simple(param.into())],
+            return_type: None,
+            body: body.into(),
+        };
+        let program = Program {
+            statements: vec![
+                Statement::Dim { names: vec![("RecFact".into(), None), ("RecLeft".into(), None), ("RecTotal".into(), None)] },
+                // Fact = IIf(n <= 1, 1, n * Fact(n - 1))
+                function("RecFact10", "n", vec![when(
+                    op(id("n"), "<=", Expression::Integer(1)),
+                    assign("RecFact10", Expression::Integer(1)),
+                    vec![assign("RecFact10", op(id("n"), "*", call("RecFact10", vec![op(id("n"), "-", Expression::Integer(1))])))],
+                )]),
+                // ByRef n is counted down through every level
+                function("RecDrain", "n", vec![
+                    assign("n", op(id("n"), "-", Expression::Integer(1))),
+                    when(op(id("n"), ">", Expression::Integer(0)), assign("RecDrain", call("RecDrain", vec![id("n")])), vec![]),
+                ]),
+                Statement::Subroutine {
+                    visibility: None,
+                    name: "RecWalk".into(),
+                    params: vec![Parameter::simple("total".into()), Parameter::simple("depth".into())],
+                    body: vec![
+                        assign("total", op(id("total"), "+", Expression::Integer(1))),
+                        when(op(id("depth"), "<=", Expression::Integer(0)), Statement::Exit(ExitType::Sub), vec![]),
+                        Statement::Call { function: "RecWalk".into(), args: vec![id("total"), op(id("depth"), "-", Expression::Integer(1))] },
+                    ]
+                    .into(),
+                },
+                Statement::Subroutine {
+                    visibility: None,
+                    name: "RecMain".into(),
+                    params: vec![],
+                    body: vec![
+                        assign("RecFact", call("RecFact10", vec![Expression::Integer(10)])),
+                        assign("RecLeft", Expression::Integer(5)),
+                        Statement::Expression(call("RecDrain", vec![id("RecLeft")])),
+                        Statement::Call { function: "RecWalk".into(), args: vec![id("RecTotal"), Expression::Integer(1000)] },
+                    ]
+                    .into(),
+                },
+            ],
+            diagnostics: vec![],
+        };
+        let executor = crate::vm::ProgramExecutor::new(program);
+        let run = |config: RuntimeConfig| {
+            let mut ctx = Context::with_config(config);
+            executor.execute_entrypoint(&mut ctx, "RecMain").unwrap();
+            ctx
+        };
+
+        let ctx = run(RuntimeConfig::builder().max_call_depth(50).tail_calls(true).build());
+        assert!(ctx.err.is_none());
+        assert_eq!(ctx.get_var("RecFact").unwrap().as_string(), "3628800");
+        assert_eq!(ctx.get_var("RecLeft").unwrap().as_string(), "0");
+        assert_eq!(ctx.get_var("RecTotal").unwrap().as_string(), "1001");
+        assert_eq!(ctx.scope_depth(), 0);
+
+        // Without frame reuse the walk is 1000 calls deep
+        let ctx = run(RuntimeConfig::builder().max_call_depth(50).build());
+        assert_eq!(ctx.err.as_ref().map(|e| e.number), Some(28));
+        assert_eq!(ctx.get_var("RecTotal").unwrap().as_string(), "49");
+    }
+
+    #[test]
+    fn test_def_types_and_type_suffixes() {
+        use crate::ast::{split_type_suffix, AssignmentTarget, Expression, Statement};
+        let assign = |name: &str, rvalue: Expression| Statement::Assignment {
+            lvalue: AssignmentTarget::Identifier(name.into()),
+            rvalue,
+            rvalue_compiled: Default::default(),
+        };
+        let mut ctx = Context::new();
+        crate::interpreter::execute_statement_list(
+            &[
+                Statement::DefType { type_name: "Integer".into(), ranges: vec![('Q', 'R')] },
+                Statement::DefType { type_name: "String".into(), ranges: vec![('Z', 'Z')] },
+                Statement::Dim { names: vec![("QDefCount".into(), None), ("ZDefLabel".into(), None)] },
+                assign("ZDefLabel", Expression::Integer(5)),
+                assign("RDefImplicit", Expression::Double(7.0)),
+                assign("SuffixDefTotal&", Expression::Double(9.0)),
+                assign("OtherDefValue", Expression::Double(1.5)),
+            ],
+            &mut ctx,
+        );
+
+        assert!(matches!(ctx.get_var("QDefCount"), Some(Value::Integer(0))));
+        assert!(matches!(ctx.get_var("ZDefLabel"), Some(Value::String(s)) if s == "5"));
+        assert!(matches!(ctx.get_var("RDefImplicit"), Some(Value::Integer(7))));
+        assert!(matches!(ctx.get_var("SuffixDefTotal"), Some(Value::Long(9))));
+        assert!(matches!(ctx.get_var("OtherDefValue"), Some(Value::Double(_))));
+        assert_eq!(split_type_suffix("Left$"), ("Left", Some("String")));
+    }
+
+    #[test]
+    fn test_long_ptr_follows_win64() {
+        use crate::ast::{split_type_suffix, Expression, Statement};
+        let dims = |ctx: &mut Context| {
+            crate::interpreter::execute_statement_list(
+                &[
+                    Statement::DefType { type_name: "LongPtr".into(), ranges: vec![('H', 'H')] },
+                    Statement::Dim { names: vec![("PtrHandle".into(), Some("LongPtr".into()))] },
+                ],
+                ctx,
+            );
+        };
+        let clngptr = |ctx: &mut Context, n: Expression| {
+            let call = Expression::FunctionCall { function: Box::new(Expression::Identifier("CLngPtr".into())), args: vec![n] };
+            crate::interpreter::evaluate_expression(&call, ctx)
+        };
+
+        let mut ctx = Context::new();
+        dims(&mut ctx);
+        assert!(matches!(ctx.get_var("PtrHandle"), Some(Value::LongLong(0))));
+        assert_eq!(ctx.def_type("HWnd"), DeclaredType::LongLong);
+        assert!(matches!(clngptr(&mut ctx, Expression::Double(5e9)).unwrap(), Value::LongLong(5_000_000_000)));
+
+        // 32-bit Office
+        let config = RuntimeConfig::builder().compiler_constant("Win64", Value::Boolean(false)).build();
+        let mut ctx = Context::with_config(config);
+        dims(&mut ctx);
+        assert!(matches!(ctx.get_var("PtrHandle"), Some(Value::Long(0))));
+        assert_eq!(ctx.def_type("HWnd"), DeclaredType::Long);
+        let err = clngptr(&mut ctx, Expression::Double(5e9)).unwrap_err();
+        assert_eq!(crate::host::excel::error_number(&err), Some(6));
+
+        assert_eq!(split_type_suffix("5^"), ("5", Some("LongLong")));
+        let ptr_safe = "Private Declare PtrSafe Function A Lib \"k\" () As LongPtr\n\
+                        Declare Function B Lib \"k\" () As Long ' 32-bit only\n";
+        let missing = crate::preprocess::declares_without_ptr_safe(ptr_safe);
+        assert_eq!(missing.iter().map(|e| e.line).collect::<Vec<_>>(), vec![2]);
+    }
+
+    #[test]
+    fn test_embedder_globals() {
+        use crate::ast::{AssignmentTarget, Expression, Program, Statement};
+        let config = RuntimeConfig::builder()
+            .global("GlobalRate", Value::Integer(3))
+            .global("GlobalLimit", Value::String("7".into()))
+            .build();
+        let mut ctx = Context::with_config(config);
+        ctx.enable_option_explicit();
+        assert!(ctx.is_variable_declared("GlobalRate"));
+
+        // GlobalLimit is declared As Long by the program: the embedder value is converted
+        let total = Expression::BinaryOp {
+            left: Box::new(Expression::Identifier("GlobalRate".into())),
+            op: "*".into(),
+            right: Box::new(Expression::Identifier("GlobalLimit".into())),
+        };
+        let main = Statement::Subroutine {
+            visibility: None,
+            name: "GlobalMain".into(),
+            params: vec![],
+            body: vec![Statement::Assignment {
+                lvalue: AssignmentTarget::Identifier("GlobalTotal".into()),
+                rvalue: total,
+                rvalue_compiled: Default::default(),
+            }]
+            .into(),
+        };
+        let program = Program {
+            statements: vec![
+                Statement::Dim { names: vec![("GlobalLimit".into(), Some("Long".into())), ("GlobalTotal".into(), None)] },
+                main,
+            ],
+            diagnostics: vec![],
+        };
+        crate::vm::ProgramExecutor::new(program).execute_entrypoint(&mut ctx, "GlobalMain").unwrap();
+        assert!(ctx.err.is_none());
+        assert!(matches!(ctx.get_var("GlobalLimit"), Some(Value::Long(7))));
+        assert_eq!(ctx.get_var("GlobalTotal").unwrap().as_string(), "21");
+
+        ctx.define_global("GlobalLimit", Value::Integer(9)).unwrap();
+        assert!(matches!(ctx.get_var("GlobalLimit"), Some(Value::Long(9))));
+        assert!(ctx.define_global("GlobalLimit", Value::String("many".into())).is_err());
+    }
+
+    #[test]
+    fn test_implicit_variable_policies() {
+        use crate::ast::{AssignmentTarget, Expression, ForStatement, Program, Statement};
+        use crate::runtime_config::ImplicitVariablePolicy;
+        let assign = |name: &str, n: i64| Statement::Assignment {
+            lvalue: AssignmentTarget::Identifier(name.into()),
+            rvalue: Expression::Integer(n),
+            rvalue_compiled: Default::default(),
+        };
+        // Dim ImplicitKept: ImplicitKept = 1: ImplicitLoose = 2: ImplicitLoose = 3
+        // For ImplicitI = 1 To 2: Next
+        let body = vec![
+            Statement::Dim { names: vec![("ImplicitKept".into(), None)] },
+            assign("ImplicitKept", 1),
+            assign("ImplicitLoose", 2),
+            assign("ImplicitLoose", 3),
+            Statement::For(ForStatement {
+                counter: "ImplicitI".into(),
+                start: Expression::Integer(1),
+                end: Expression::Integer(2),
+                step: None,
+                body: Vec::new().into(),
+                next_counter: None,
+            }),
+        ];
+        let work = Statement::Subroutine { visibility: None, name: "ImplicitWork".into(), params: vec![], body: body.into() };
+        let main = Statement::Subroutine {
+            visibility: None,
+            name: "ImplicitMain".into(),
+            params: vec![],
+            body: vec![Statement::Call { function: "ImplicitWork".into(), args: vec![] }].into(),
+        };
+        let executor = crate::vm::ProgramExecutor::new(Program { statements: vec![work, main], diagnostics: vec![] });
+        let run = |policy: ImplicitVariablePolicy| {
+            let mut ctx = Context::with_config(RuntimeConfig::builder().implicit_variables(policy).build());
+            executor.execute_entrypoint(&mut ctx, "ImplicitMain").unwrap();
+            ctx
+        };
+
+        let ctx = run(ImplicitVariablePolicy::Allow);
+        assert!(ctx.err.is_none());
+        assert!(ctx.implicit_variables().is_empty());
+
+        let ctx = run(ImplicitVariablePolicy::Report);
+        assert!(ctx.err.is_none());
+        let names: Vec<&str> = ctx.implicit_variables().iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, ["ImplicitLoose", "ImplicitI"]);
+        assert_eq!(ctx.implicit_variables()[0].procedure.as_deref(), Some("ImplicitWork"));
+
+        let ctx = run(ImplicitVariablePolicy::Deny);
+        assert_eq!(ctx.err.as_ref().map(|e| e.number), Some(451));
+        assert!(ctx.implicit_variables().is_empty());
+    }
+
+    #[test]
+    fn test_local_static_and_module_lifetimes() {
+        use crate::ast::{AssignmentTarget, Expression, Program, Statement};
+        use crate::runtime_config::ExecutionEngine;
+        let id = |name: &str| Expression::Identifier(name.into());
+        let op = |left: Expression, op: &str, right: Expression| Expression::BinaryOp { left: Box::new(left), op: op.into(), right: Box::new(right) };
+        let assign = |name: &str, rvalue: Expression| Statement::Assignment {
+            lvalue: AssignmentTarget::Identifier(name.into()),
+            rvalue,
+            rvalue_compiled: Default::default(),
+        };
+        let bump = |name: &str| assign(name, op(id(name), "+", Expression::Integer(1)));
+        // Dim LifeLog
+        //
+        // Sub LifeCount()
+        //     Dim LifeLocal As Long
+        //     Static LifeKept As Long
+        //     LifeLocal = LifeLocal + 1: LifeKept = LifeKept + 1
+        //     LifeLog = LifeLog & LifeLocal & LifeKept & ","
+        // End Sub
+        //
+        // Sub LifeMain()
+        //     LifeLoose = 1
+        //     LifeCount: LifeCount: LifeCount
+        // End Sub
+        let count = vec![
+            Statement::Dim { names: vec![("LifeLocal".into(), Some("Long".into()))] },
+            Statement::Static { names: vec![("LifeKept".into(), Some("Long".into()))] },
+            bump("LifeLocal"),
+            bump("LifeKept"),
+            assign("LifeLog", op(op(op(id("LifeLog"), "&", id("LifeLocal")), "&", id("LifeKept")), "&", Expression::String(",".into()))),
+        ];
+        let call = Statement::Call { function: "LifeCount".into(), args: vec![] };
+        let main = vec![assign("LifeLoose", Expression::Integer(1)), call.clone(), call.clone(), call];
+        let sub = |name: &str, body: Vec<Statement>| Statement::Subroutine { visibility: None, name: name.into(), params: vec![], body: body.into() };
+        let program = Program {
+            statements: vec![Statement::Dim { names: vec![("LifeLog".into(), None)] }, sub("LifeCount", count), sub("LifeMain", main)],
+            diagnostics: vec![],
+        };
+        let executor = crate::vm::ProgramExecutor::new(program);
+
+        for engine in [ExecutionEngine::Vm, ExecutionEngine::Interpreter] {
+            let mut ctx = Context::with_config(RuntimeConfig::builder().engine(engine).build());
+            executor.execute_entrypoint(&mut ctx, "LifeMain").unwrap();
+            assert!(ctx.err.is_none());
+            assert_eq!(ctx.get_var("LifeLog").unwrap().as_string(), "11,12,13,", "{:?}", engine);
+            // Locals of the entry Sub and its callees are gone
+            assert!(ctx.get_var("LifeLoose").is_none());
+            assert!(ctx.get_var("LifeLocal").is_none());
+            assert!(ctx.get_var("LifeKept").is_none());
+            assert_eq!(ctx.scope_depth(), 0);
+
+            // Running again starts the module variables and Static locals over
+            executor.execute_entrypoint(&mut ctx, "LifeMain").unwrap();
+            assert_eq!(ctx.get_var("LifeLog").unwrap().as_string(), "11,12,13,", "{:?}", engine);
+        }
+    }
+}
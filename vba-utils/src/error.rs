@@ -0,0 +1,179 @@
+// vba-utils/src/error.rs
+
+//! Errors of the public API.
+//!
+//! Inside the crate errors are `anyhow::Error`s (a host error carries its
+//! VBA error number as an `ExcelError`) or plain `String`s. The entry
+//! points embedders call ([`crate::ProgramExecutor`], [`crate::VbaRuntime`],
+//! [`crate::ast::parse_source`], ...) return a [`VbaError`] instead, so a
+//! caller can tell source that did not parse from a run-time error of the
+//! program, a failure of the host application and a configured limit.
+//!
+//! A run-time error the program does not handle does not fail
+//! `ProgramExecutor::execute`: as in Office it ends the run and stays in
+//! `ctx.err`, where [`VbaError::from_err`] reads it.
+
+use std::fmt;
+
+use crate::ast::Span;
+use crate::context::ErrObject;
+use crate::vba_errors::VbaErr;
+
+/// A `RuntimeConfig` limit a run can hit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    /// `memory_limit`: error 7, "Out of memory"
+    Memory,
+    /// `max_call_depth`: error 28, "Out of stack space"
+    CallDepth,
+}
+
+impl Limit {
+    fn vba_err(self) -> VbaErr {
+        match self {
+            Limit::Memory => VbaErr::OutOfMemory,
+            Limit::CallDepth => VbaErr::OutOfStackSpace,
+        }
+    }
+
+    /// The VBA error number raised when the limit is hit
+    pub fn number(self) -> i32 {
+        self.vba_err().number()
+    }
+
+    /// The error the run gets when the limit is hit: the VBA error, marked
+    /// so the API reports it as [`VbaError::LimitExceeded`]
+    pub(crate) fn error(self) -> anyhow::Error {
+        let err = self.vba_err();
+        err.error("").context(LimitHit { limit: self, description: err.description() })
+    }
+
+    /// The limit that raised `e`, if one did
+    pub(crate) fn of(e: &anyhow::Error) -> Option<Limit> {
+        e.downcast_ref::<LimitHit>().map(|hit| hit.limit)
+    }
+}
+
+/// Marker [`Limit::error`] puts on its error; shows as the error itself
+#[derive(Debug)]
+struct LimitHit {
+    limit: Limit,
+    description: &'static str,
+}
+
+impl fmt::Display for LimitHit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VbaError {
+    /// Source (a module or an expression) that could not be parsed
+    ParseError { message: String, span: Option<Span> },
+    /// A VBA run-time error; `number` is what `Err.Number` reports. Only
+    /// the line is known of errors raised while running, so their span's
+    /// columns are 0.
+    RuntimeError { number: i32, description: String, span: Option<Span> },
+    /// The host application, its backend or the embedder's setup failed
+    HostError(String),
+    /// The run hit a limit set in `RuntimeConfig`
+    LimitExceeded { limit: Limit, description: String },
+}
+
+impl VbaError {
+    /// Run-time error `number`
+    pub fn runtime(number: i32, description: impl Into<String>, line: Option<usize>) -> Self {
+        let span = line.map(|line| Span { line, column: 0, end_line: line, end_column: 0 });
+        VbaError::RuntimeError { number, description: description.into(), span }
+    }
+
+    /// The error a run stopped on, as `ctx.err` holds it; a
+    /// [`VbaError::LimitExceeded`] when a limit raised it
+    pub fn from_err(err: &ErrObject) -> Self {
+        match err.limit {
+            Some(limit) => VbaError::LimitExceeded { limit, description: err.description.clone() },
+            None => Self::runtime(err.number, err.description.clone(), None),
+        }
+    }
+
+    /// An error evaluating VBA: its number, or `default` when it has none
+    pub(crate) fn from_anyhow(e: &anyhow::Error, default: i32) -> Self {
+        if let Some(limit) = Limit::of(e) {
+            return VbaError::LimitExceeded { limit, description: e.to_string() };
+        }
+        let number = crate::host::excel::error_number(e).unwrap_or(default);
+        Self::runtime(number, e.to_string(), None)
+    }
+
+    pub(crate) fn parse(message: impl Into<String>) -> Self {
+        VbaError::ParseError { message: message.into(), span: None }
+    }
+
+    /// The VBA error number, for run-time errors and limits
+    pub fn number(&self) -> Option<i32> {
+        match self {
+            VbaError::RuntimeError { number, .. } => Some(*number),
+            VbaError::LimitExceeded { limit, .. } => Some(limit.number()),
+            VbaError::ParseError { .. } | VbaError::HostError(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for VbaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VbaError::ParseError { message, span: Some(span) } => {
+                write!(f, "line {}:{}: {}", span.line, span.column, message)
+            }
+            VbaError::ParseError { message, span: None } | VbaError::HostError(message) => write!(f, "{}", message),
+            VbaError::RuntimeError { number, description, span } => {
+                write!(f, "Run-time error {}: {}", number, description)?;
+                match span {
+                    Some(span) => write!(f, " (line {})", span.line),
+                    None => Ok(()),
+                }
+            }
+            VbaError::LimitExceeded { limit, description } => {
+                write!(f, "Run-time error {}: {}", limit.number(), description)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VbaError {}
+
+/// The crate's `String` errors come from the host side: backends, the
+/// write journal, hibernation images
+impl From<String> for VbaError {
+    fn from(message: String) -> Self {
+        VbaError::HostError(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vba_error_kinds() {
+        let err = ErrObject { number: 28, description: "Out of stack space".into(), source: "VM".into(), limit: Some(Limit::CallDepth) };
+        let limit = VbaError::from_err(&err);
+        assert_eq!(limit, VbaError::LimitExceeded { limit: Limit::CallDepth, description: "Out of stack space".into() });
+        assert_eq!(limit.number(), Some(28));
+        let e = Limit::Memory.error();
+        assert_eq!((crate::host::excel::error_number(&e), e.to_string()), (Some(7), "Out of memory".to_string()));
+        assert!(matches!(VbaError::from_anyhow(&e, 13), VbaError::LimitExceeded { limit: Limit::Memory, .. }));
+
+        // `Err.Raise 7` or an error 28 no limit raised is a run-time error
+        let raised = ErrObject { number: 7, description: "Out of memory".into(), source: "VBA".into(), limit: None };
+        assert_eq!(VbaError::from_err(&raised), VbaError::runtime(7, "Out of memory", None));
+        assert_eq!(VbaError::from_anyhow(&VbaErr::OutOfStackSpace.error(""), 13).number(), Some(28));
+        assert!(matches!(VbaError::from_anyhow(&VbaErr::OutOfStackSpace.error(""), 13), VbaError::RuntimeError { .. }));
+
+        let runtime = VbaError::runtime(13, "Type mismatch", Some(4));
+        assert_eq!(runtime.to_string(), "Run-time error 13: Type mismatch (line 4)");
+        let host = VbaError::from("backend went away".to_string());
+        assert_eq!((host.number(), host.to_string()), (None, "backend went away".to_string()));
+    }
+}
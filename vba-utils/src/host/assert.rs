@@ -0,0 +1,164 @@
+// src/host/assert.rs
+// ============================================================================
+// ASSERT - The `Assert` object of VBA unit tests
+//
+// Registered as a global while `ProgramExecutor::run_tests` runs, with the
+// members of Rubberduck's AssertClass:
+//
+//     Assert.AreEqual expected, actual[, message]
+//     Assert.AreNotEqual expected, actual[, message]
+//     Assert.IsTrue condition[, message]    Assert.IsFalse condition[, message]
+//     Assert.IsNothing value[, message]     Assert.IsNotNothing value[, message]
+//     Assert.Fail [message]                 Assert.Inconclusive [message]
+//     Assert.Succeed
+//
+// A failed assertion is recorded with its source line and the test goes on,
+// as in Rubberduck; the test fails if any assertion did. Values are compared
+// with VBA's `=`, so `Assert.AreEqual 3, "3"` passes.
+// ============================================================================
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use anyhow::Result;
+
+use crate::context::{Context, Value};
+use crate::interpreter::{coerce, operations};
+
+/// An assertion that did not hold
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssertFailure {
+    /// Source line of the assertion (VM only)
+    pub line: Option<usize>,
+    /// "AreEqual failed: expected 3, actual 4. totals"
+    pub message: String,
+}
+
+/// What the assertions of the running test recorded
+#[derive(Debug, Default)]
+pub(crate) struct AssertLog {
+    pub failures: Vec<AssertFailure>,
+    /// Message of the last Assert.Inconclusive
+    pub inconclusive: Option<String>,
+    pub count: usize,
+}
+
+/// Shared between the registered `Assert` object and the test runner
+pub(crate) type SharedAssertLog = Rc<RefCell<AssertLog>>;
+
+pub(crate) struct AssertObject {
+    log: SharedAssertLog,
+}
+
+impl AssertObject {
+    pub(crate) fn new(log: SharedAssertLog) -> Self {
+        Self { log }
+    }
+
+    fn check(&self, method: &str, passed: bool, detail: String, message: Option<&Value>) {
+        let mut log = self.log.borrow_mut();
+        log.count += 1;
+        if passed {
+            return;
+        }
+        let mut text = format!("{} failed", method);
+        if !detail.is_empty() {
+            text.push_str(": ");
+            text.push_str(&detail);
+        }
+        if let Some(message) = message.map(Value::as_string).filter(|m| !m.is_empty()) {
+            text.push_str(". ");
+            text.push_str(&message);
+        }
+        let line = crate::host::excel::engine::source_line();
+        log.failures.push(AssertFailure { line, message: text });
+    }
+}
+
+/// `=` between two values; values VBA cannot compare are not equal (and
+/// leave no error behind in the test)
+fn equal(expected: &Value, actual: &Value, ctx: &mut Context) -> bool {
+    let err = ctx.err.take();
+    let result = operations::eval_binary(ctx, "=", expected.clone(), actual.clone());
+    ctx.err = err;
+    matches!(result, Ok(Value::Boolean(true)))
+}
+
+fn is_nothing(value: &Value) -> bool {
+    matches!(value, Value::Object(None))
+}
+
+fn shown(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("\"{}\"", s),
+        Value::Empty => "Empty".to_string(),
+        other => other.as_string(),
+    }
+}
+
+impl AssertObject {
+    fn are_equal(&mut self, ctx: &mut Context, expected: Value, actual: Value, message: Option<Value>) -> Result<()> {
+        let detail = format!("expected {}, actual {}", shown(&expected), shown(&actual));
+        self.check("AreEqual", equal(&expected, &actual, ctx), detail, message.as_ref());
+        Ok(())
+    }
+
+    fn are_not_equal(&mut self, ctx: &mut Context, expected: Value, actual: Value, message: Option<Value>) -> Result<()> {
+        let detail = format!("both {}", shown(&actual));
+        self.check("AreNotEqual", !equal(&expected, &actual, ctx), detail, message.as_ref());
+        Ok(())
+    }
+
+    fn is_true(&mut self, _ctx: &mut Context, condition: Value, message: Option<Value>) -> Result<()> {
+        let passed = coerce::to_bool(&condition).ok() == Some(true);
+        self.check("IsTrue", passed, format!("got {}", shown(&condition)), message.as_ref());
+        Ok(())
+    }
+
+    fn is_false(&mut self, _ctx: &mut Context, condition: Value, message: Option<Value>) -> Result<()> {
+        let passed = coerce::to_bool(&condition).ok() == Some(false);
+        self.check("IsFalse", passed, format!("got {}", shown(&condition)), message.as_ref());
+        Ok(())
+    }
+
+    fn is_nothing(&mut self, _ctx: &mut Context, value: Value, message: Option<Value>) -> Result<()> {
+        self.check("IsNothing", is_nothing(&value), format!("got {}", shown(&value)), message.as_ref());
+        Ok(())
+    }
+
+    fn is_not_nothing(&mut self, _ctx: &mut Context, value: Value, message: Option<Value>) -> Result<()> {
+        self.check("IsNotNothing", !is_nothing(&value), String::new(), message.as_ref());
+        Ok(())
+    }
+
+    fn fail(&mut self, _ctx: &mut Context, message: Option<Value>) -> Result<()> {
+        self.check("Fail", false, String::new(), message.as_ref());
+        Ok(())
+    }
+
+    fn succeed(&mut self, _ctx: &mut Context) -> Result<()> {
+        self.check("Succeed", true, String::new(), None);
+        Ok(())
+    }
+
+    fn inconclusive(&mut self, _ctx: &mut Context, message: Option<String>) -> Result<()> {
+        let mut log = self.log.borrow_mut();
+        log.count += 1;
+        log.inconclusive = Some(message.unwrap_or_default());
+        Ok(())
+    }
+}
+
+crate::com_object! {
+    impl ComObject for AssertObject as "AssertClass" {
+        method AreEqual(expected: Value, actual: Value, message: Option<Value>) => are_equal;
+        method AreNotEqual(expected: Value, actual: Value, message: Option<Value>) => are_not_equal;
+        method IsTrue(condition: Value, message: Option<Value>) => is_true;
+        method IsFalse(condition: Value, message: Option<Value>) => is_false;
+        method IsNothing(value: Value, message: Option<Value>) => is_nothing;
+        method IsNotNothing(value: Value, message: Option<Value>) => is_not_nothing;
+        method Fail(message: Option<Value>) => fail;
+        method Succeed() => succeed;
+        method Inconclusive(message: Option<String>) => inconclusive;
+    }
+}
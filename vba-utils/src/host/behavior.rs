@@ -0,0 +1,283 @@
+// src/host/behavior.rs
+// ============================================================================
+// BEHAVIOR REPORT - What a macro tried to do outside the document
+//
+// SendKeys, AppActivate, Beep, Application.Quit/Wait, GetObject and
+// CreateObject never reach the desktop. Every one goes through perform(),
+// which applies the InteractionPolicy: record the call as an Interaction
+// (keys sent, window targeted, object requested) and carry on, ignore it,
+// refuse SendKeys and AppActivate with error 70, or hand the call to the
+// HostUi. The report joins these with the late-bound COM calls (host::stub)
+// and Declare'd DLL calls (host::dll) recorded during the same run.
+// ============================================================================
+
+use std::sync::Mutex;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+
+use crate::context::{Context, Value};
+use crate::host::dll::DllCall;
+use crate::host::stub::StubCall;
+
+/// What SendKeys, AppActivate, Beep and Application.Quit/Wait do.
+/// GetObject and CreateObject are always recorded and allowed (see
+/// `UnknownObjectPolicy`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InteractionPolicy {
+    /// Record the call and carry on
+    #[default]
+    Record,
+    /// Record the call; SendKeys and AppActivate raise run-time error 70
+    /// (Permission denied)
+    Deny,
+    /// Carry on without recording anything
+    Ignore,
+    /// Record the call and let `HostUi::interact` perform or refuse it
+    /// (error 70)
+    Delegate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InteractionKind {
+    SendKeys,
+    AppActivate,
+    Beep,
+    /// Application.Quit
+    Quit,
+    /// Application.Wait
+    Wait,
+    GetObject,
+    CreateObject,
+}
+
+impl InteractionKind {
+    /// GetObject and CreateObject, which the policy does not govern
+    fn is_object(self) -> bool {
+        matches!(self, InteractionKind::GetObject | InteractionKind::CreateObject)
+    }
+}
+
+/// One recorded interaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interaction {
+    pub kind: InteractionKind,
+    /// Keys as written, window title, or path/ProgID
+    pub target: String,
+    /// Readable keys for SendKeys ("Ctrl+C, Enter"), the class for GetObject
+    pub detail: String,
+    /// False when the policy refused it
+    pub allowed: bool,
+}
+
+impl Interaction {
+    /// `SendKeys "^c{ENTER}" (Ctrl+C, Enter)`
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{:?}", self.kind);
+        if !self.target.is_empty() {
+            text.push_str(&format!(" {:?}", self.target));
+        }
+        if !self.detail.is_empty() {
+            text.push_str(&format!(" ({})", self.detail));
+        }
+        if !self.allowed {
+            text.push_str(" [denied]");
+        }
+        text
+    }
+}
+
+/// Every interaction, in call order
+static INTERACTIONS: Lazy<Mutex<Vec<Interaction>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Forget recorded interactions
+pub fn reset() {
+    INTERACTIONS.lock().unwrap().clear();
+}
+
+/// Recorded interactions, in call order
+pub fn interactions() -> Vec<Interaction> {
+    INTERACTIONS.lock().unwrap().clone()
+}
+
+/// Apply the policy to an interaction, recording it unless ignored;
+/// error 70 if it is refused
+pub(crate) fn perform(ctx: &mut Context, kind: InteractionKind, target: &str, detail: String) -> Result<()> {
+    let policy = ctx.runtime_config.interactions;
+    if policy == InteractionPolicy::Ignore && !kind.is_object() {
+        return Ok(());
+    }
+    let mut interaction = Interaction { kind, target: target.to_string(), detail, allowed: true };
+    interaction.allowed = match policy {
+        _ if kind.is_object() => true,
+        InteractionPolicy::Deny => !matches!(kind, InteractionKind::SendKeys | InteractionKind::AppActivate),
+        InteractionPolicy::Delegate => ctx.runtime_config.ui.0.clone().interact(&interaction),
+        InteractionPolicy::Record | InteractionPolicy::Ignore => true,
+    };
+    let name = format!("{:?}", kind);
+    let hook_args = [Value::String(target.to_string())];
+    if interaction.allowed {
+        interaction.allowed = crate::vm::hooks::host_call(ctx, crate::vm::HostCallKind::Interaction, &name, &hook_args).is_ok();
+    }
+    ctx.log(&format!("Behavior: {}", interaction.to_text()));
+    let allowed = interaction.allowed;
+    INTERACTIONS.lock().unwrap().push(interaction);
+    if !allowed {
+        return Err(crate::vba_errors::VbaErr::PermissionDenied.error(format!("{} {:?}", name, target)));
+    }
+    Ok(())
+}
+
+/// Spell out a SendKeys string: `+{TAB}%f` is "Shift+Tab, Alt+F"
+pub fn describe_keys(keys: &str) -> String {
+    let mut out: Vec<String> = Vec::new();
+    let mut modifiers = String::new();
+    let mut chars = keys.chars().peekable();
+    while let Some(c) = chars.next() {
+        let key = match c {
+            '+' => { modifiers.push_str("Shift+"); continue; }
+            '^' => { modifiers.push_str("Ctrl+"); continue; }
+            '%' => { modifiers.push_str("Alt+"); continue; }
+            '~' => "Enter".to_string(),
+            '{' => {
+                // {ENTER}, {TAB 3}, {{} for a literal brace
+                let mut name: String = chars.next().into_iter().collect();
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                    name.push(c);
+                }
+                key_name(&name)
+            }
+            '(' => {
+                // ^(ac): the modifier applies to every key in the group
+                let group: String = chars.by_ref().take_while(|&c| c != ')').collect();
+                group.chars().map(|c| c.to_ascii_uppercase().to_string()).collect::<Vec<_>>().join("")
+            }
+            c if modifiers.is_empty() => c.to_string(),
+            c => c.to_ascii_uppercase().to_string(),
+        };
+        out.push(format!("{}{}", std::mem::take(&mut modifiers), key));
+    }
+    out.join(", ")
+}
+
+/// `ENTER` -> `Enter`, `TAB 3` -> `Tab x3`
+fn key_name(braced: &str) -> String {
+    let (name, count) = match braced.rsplit_once(' ') {
+        Some((name, n)) if n.parse::<u32>().is_ok() => (name, Some(n)),
+        _ => (braced, None),
+    };
+    let mut spelled: String = name.chars().take(1).collect();
+    spelled.push_str(&name.chars().skip(1).collect::<String>().to_ascii_lowercase());
+    match count {
+        Some(n) => format!("{} x{}", spelled, n),
+        None => spelled,
+    }
+}
+
+/// Everything recorded since the run started
+#[derive(Debug, Clone, Default)]
+pub struct BehaviorReport {
+    pub interactions: Vec<Interaction>,
+    pub com_calls: Vec<StubCall>,
+    pub dll_calls: Vec<DllCall>,
+}
+
+impl BehaviorReport {
+    pub fn collect() -> Self {
+        Self {
+            interactions: interactions(),
+            com_calls: crate::host::stub::calls(),
+            dll_calls: crate::host::dll::calls(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.interactions.is_empty() && self.com_calls.is_empty() && self.dll_calls.is_empty()
+    }
+
+    /// One line per event, grouped by source
+    pub fn to_text(&self) -> String {
+        let mut lines: Vec<String> = self.interactions.iter().map(Interaction::to_text).collect();
+        lines.extend(self.com_calls.iter().map(|c| format!("COM {}", c.to_text())));
+        lines.extend(self.dll_calls.iter().map(|c| format!("DLL {} [{:?}]", c.to_text(), c.outcome)));
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{Expression, Statement};
+    use crate::runtime_config::RuntimeConfig;
+
+    #[test]
+    fn test_describe_keys() {
+        assert_eq!(describe_keys("^c{ENTER}"), "Ctrl+C, Enter");
+        assert_eq!(describe_keys("+{TAB 2}%f~"), "Shift+Tab x2, Alt+F, Enter");
+        assert_eq!(describe_keys("ab"), "a, b");
+    }
+
+    #[test]
+    fn test_interactions_recorded_and_denied() {
+        let call = |function: &str, arg: &str| Statement::Call {
+            function: function.into(),
+            args: vec![Expression::String(arg.into())],
+        };
+        let body = [call("SendKeys", "%{F4}"), call("AppActivate", "Behavior Test Window")];
+
+        let mut ctx = Context::new();
+        crate::interpreter::execute_statement_list(&body, &mut ctx);
+        assert!(ctx.err.is_none());
+
+        let mut ctx = Context::with_config(RuntimeConfig::builder().interactions(InteractionPolicy::Deny).build());
+        crate::interpreter::execute_statement_list(&body, &mut ctx);
+        assert_eq!(ctx.err.as_ref().map(|e| e.number), Some(70));
+
+        let mine: Vec<Interaction> = interactions()
+            .into_iter()
+            .filter(|i| i.target == "%{F4}" || i.target == "Behavior Test Window")
+            .collect();
+        assert!(mine.iter().any(|i| i.kind == InteractionKind::SendKeys && i.detail == "Alt+F4" && i.allowed));
+        assert!(mine.iter().any(|i| i.kind == InteractionKind::SendKeys && !i.allowed));
+        assert!(mine.iter().any(|i| i.kind == InteractionKind::AppActivate && i.allowed));
+        assert!(BehaviorReport::collect().to_text().contains("SendKeys \"%{F4}\" (Alt+F4) [denied]"));
+    }
+
+    #[test]
+    fn test_ignore_and_delegate_policies() {
+        /// Refuses Beep, performs everything else
+        struct Quiet;
+
+        impl crate::host::ui::HostUi for Quiet {
+            fn interact(&self, interaction: &Interaction) -> bool {
+                interaction.kind != InteractionKind::Beep
+            }
+        }
+
+        let activate = Statement::Call {
+            function: "AppActivate".into(),
+            args: vec![Expression::String("Policy Test Window".into())],
+        };
+        let beep = Statement::Call { function: "Beep".into(), args: vec![] };
+
+        let config = RuntimeConfig::builder().interactions(InteractionPolicy::Ignore).build();
+        let mut ctx = Context::with_config(config);
+        crate::interpreter::execute_statement_list(&[activate.clone()], &mut ctx);
+        assert!(ctx.err.is_none());
+        assert!(!interactions().iter().any(|i| i.target == "Policy Test Window"));
+
+        let config = RuntimeConfig::builder()
+            .interactions(InteractionPolicy::Delegate)
+            .ui(std::sync::Arc::new(Quiet))
+            .build();
+        let mut ctx = Context::with_config(config);
+        crate::interpreter::execute_statement_list(&[activate, beep], &mut ctx);
+        assert_eq!(ctx.err.as_ref().map(|e| e.number), Some(70));
+        let recorded = interactions();
+        assert!(recorded.iter().any(|i| i.target == "Policy Test Window" && i.allowed));
+        assert!(recorded.iter().any(|i| i.kind == InteractionKind::Beep && !i.allowed));
+    }
+}
@@ -0,0 +1,354 @@
+// src/host/com_object.rs
+// ============================================================================
+// COM_OBJECT! - Declare a host object's members instead of matching names
+//
+//     com_object! {
+//         impl ComObject for Timer as "Timer" {
+//             default Interval;
+//             prop Interval: i64 => interval;
+//             prop Enabled: bool => enabled, set_enabled;
+//             get Name: String => name;
+//             method Start(seconds: f64, label: Option<String>) => start;
+//             method Quit() => quit;
+//         }
+//     }
+//
+// writes the ComObject impl for Timer:
+//   prop    Timer.Interval reads and assigns the field `interval`;
+//           Timer.Enabled calls `self.enabled(ctx)` and
+//           `self.set_enabled(ctx, value)`, for state kept elsewhere
+//   get     Timer.Name reads the field `name`; assigning it is error 383
+//   method  Timer.Start 2 calls `self.start(ctx, 2.0, None)`, which returns
+//           Result<R> for any ComValue R (`()` for a Sub)
+//   default the member `x = timer` and `timer = 5` use
+// Names match case-insensitively. Arguments are converted with ComValue:
+// one that does not convert is error 13, a missing one error 449 unless
+// its type is an Option, and an extra one error 450. Members come
+// in the order above (default, prop, get, method), each kind optional.
+// ============================================================================
+
+use crate::context::Value;
+use crate::interpreter::coerce;
+use crate::vba_errors::VbaErr;
+
+/// Result of the members `com_object!` routes to
+pub type ComResult<T> = anyhow::Result<T>;
+
+/// A Rust type a host object member takes or returns
+pub trait ComValue: Sized {
+    /// Convert an argument or assigned value
+    fn from_value(value: &Value) -> ComResult<Self>;
+
+    fn into_value(self) -> Value;
+
+    /// The argument when the caller left it out; error 449 unless the type
+    /// can stand for a missing argument
+    fn missing() -> Option<Self> {
+        None
+    }
+}
+
+impl ComValue for bool {
+    fn from_value(value: &Value) -> ComResult<Self> {
+        coerce::to_bool(value)
+    }
+
+    fn into_value(self) -> Value {
+        Value::Boolean(self)
+    }
+}
+
+impl ComValue for i64 {
+    fn from_value(value: &Value) -> ComResult<Self> {
+        coerce::to_i64(value)
+    }
+
+    fn into_value(self) -> Value {
+        Value::Integer(self)
+    }
+}
+
+impl ComValue for i32 {
+    fn from_value(value: &Value) -> ComResult<Self> {
+        i32::try_from(coerce::to_i64(value)?).map_err(|_| VbaErr::Overflow.error(""))
+    }
+
+    fn into_value(self) -> Value {
+        Value::Long(self)
+    }
+}
+
+impl ComValue for f64 {
+    fn from_value(value: &Value) -> ComResult<Self> {
+        coerce::to_f64(value)
+    }
+
+    fn into_value(self) -> Value {
+        Value::Double(self)
+    }
+}
+
+impl ComValue for String {
+    fn from_value(value: &Value) -> ComResult<Self> {
+        Ok(coerce::to_string(value))
+    }
+
+    fn into_value(self) -> Value {
+        Value::String(self)
+    }
+}
+
+impl ComValue for Value {
+    fn from_value(value: &Value) -> ComResult<Self> {
+        Ok(value.clone())
+    }
+
+    fn into_value(self) -> Value {
+        self
+    }
+}
+
+impl ComValue for () {
+    fn from_value(_value: &Value) -> ComResult<Self> {
+        Ok(())
+    }
+
+    fn into_value(self) -> Value {
+        Value::Empty
+    }
+}
+
+/// An optional argument: None when left out or passed as Empty
+impl<T: ComValue> ComValue for Option<T> {
+    fn from_value(value: &Value) -> ComResult<Self> {
+        match value {
+            Value::Empty => Ok(None),
+            value => T::from_value(value).map(Some),
+        }
+    }
+
+    fn into_value(self) -> Value {
+        self.map_or(Value::Empty, T::into_value)
+    }
+
+    fn missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+/// Arguments of a `com_object!` method, taken in order
+pub struct ComArgs<'a> {
+    method: &'static str,
+    args: &'a [Value],
+    next: usize,
+}
+
+impl<'a> ComArgs<'a> {
+    /// Error 450 when more than `expected` arguments were passed
+    pub fn new(method: &'static str, args: &'a [Value], expected: usize) -> ComResult<Self> {
+        if args.len() > expected {
+            return Err(VbaErr::WrongNumberOfArguments.message(format!(
+                "Wrong number of arguments: {} takes {}, got {}",
+                method,
+                expected,
+                args.len()
+            )));
+        }
+        Ok(Self { method, args, next: 0 })
+    }
+
+    /// The next argument as a `T`
+    pub fn take<T: ComValue>(&mut self, name: &str) -> ComResult<T> {
+        let index = self.next;
+        self.next += 1;
+        match self.args.get(index) {
+            Some(value) => T::from_value(value)
+                .map_err(|e| VbaErr::TypeMismatch.error(format!("{} argument {}: {}", self.method, name, e))),
+            None => T::missing()
+                .ok_or_else(|| VbaErr::ArgumentNotOptional.error(format!("{} argument {}", self.method, name))),
+        }
+    }
+}
+
+/// Error 438 for a member the object does not have, suggesting the nearest
+/// of its declared `members`
+pub fn no_member(type_name: &str, kind: &str, member: &str, members: &[&str]) -> anyhow::Error {
+    crate::host::members::unknown_member(type_name, kind, member, members.iter().copied())
+}
+
+/// Error 383 for assigning a `get` property
+pub fn read_only(type_name: &str, member: &str) -> anyhow::Error {
+    VbaErr::ReadOnlyProperty.error(format!("{}.{}", type_name, member))
+}
+
+/// See the module documentation
+#[macro_export]
+macro_rules! com_object {
+    // A `prop` backed by a field, or by a getter and setter pair
+    (@get $this:ident, $ctx:ident, $field:ident) => {
+        $this.$field.clone()
+    };
+    (@get $this:ident, $ctx:ident, $getter:ident, $setter:ident) => {
+        $this.$getter($ctx)?
+    };
+    (@set $this:ident, $ctx:ident, $value:expr, $field:ident) => {
+        $this.$field = $value
+    };
+    (@set $this:ident, $ctx:ident, $value:expr, $getter:ident, $setter:ident) => {
+        $this.$setter($ctx, $value)?
+    };
+
+    (
+        impl ComObject for $ty:ty as $type_name:literal {
+            $(default $default:ident;)?
+            $(prop $prop:ident : $prop_ty:ty => $prop_field:ident $(, $prop_setter:ident)?;)*
+            $(get $get:ident : $get_ty:ty => $get_field:ident;)*
+            $(method $method:ident ( $($arg:ident : $arg_ty:ty),* $(,)? ) => $method_fn:ident;)*
+        }
+    ) => {
+        impl $ty {
+            const COM_MEMBERS: &'static [&'static str] =
+                &[$(stringify!($prop),)* $(stringify!($get),)* $(stringify!($method),)*];
+        }
+
+        impl $crate::host::ComObject for $ty {
+            fn get_property(
+                &self,
+                name: &str,
+                _ctx: &mut $crate::Context,
+            ) -> $crate::host::com_object::ComResult<$crate::context::Value> {
+                $(if name.eq_ignore_ascii_case(stringify!($prop)) {
+                    let value: $prop_ty = $crate::com_object!(@get self, _ctx, $prop_field $(, $prop_setter)?);
+                    return Ok($crate::host::com_object::ComValue::into_value(value));
+                })*
+                $(if name.eq_ignore_ascii_case(stringify!($get)) {
+                    let value: $get_ty = self.$get_field.clone();
+                    return Ok($crate::host::com_object::ComValue::into_value(value));
+                })*
+                Err($crate::host::com_object::no_member($type_name, "property", name, Self::COM_MEMBERS))
+            }
+
+            fn set_property(
+                &mut self,
+                name: &str,
+                value: $crate::context::Value,
+                _ctx: &mut $crate::Context,
+            ) -> $crate::host::com_object::ComResult<()> {
+                let _ = &value;
+                $(if name.eq_ignore_ascii_case(stringify!($prop)) {
+                    let mut args = $crate::host::com_object::ComArgs::new(stringify!($prop), std::slice::from_ref(&value), 1)?;
+                    let value = args.take::<$prop_ty>("value")?;
+                    $crate::com_object!(@set self, _ctx, value, $prop_field $(, $prop_setter)?);
+                    return Ok(());
+                })*
+                $(if name.eq_ignore_ascii_case(stringify!($get)) {
+                    return Err($crate::host::com_object::read_only($type_name, stringify!($get)));
+                })*
+                Err($crate::host::com_object::no_member($type_name, "property", name, Self::COM_MEMBERS))
+            }
+
+            fn call_method(
+                &mut self,
+                name: &str,
+                args: &[$crate::context::Value],
+                ctx: &mut $crate::Context,
+            ) -> $crate::host::com_object::ComResult<$crate::context::Value> {
+                let _ = (&args, &ctx);
+                $(if name.eq_ignore_ascii_case(stringify!($method)) {
+                    let expected = <[&str]>::len(&[$(stringify!($arg)),*]);
+                    let mut _args = $crate::host::com_object::ComArgs::new(stringify!($method), args, expected)?;
+                    $(let $arg: $arg_ty = _args.take(stringify!($arg))?;)*
+                    let result = self.$method_fn(ctx, $($arg),*)?;
+                    return Ok($crate::host::com_object::ComValue::into_value(result));
+                })*
+                // `obj.Prop()` reads the property
+                if args.is_empty() && !<[&str]>::is_empty(&[$(stringify!($prop),)* $(stringify!($get),)*]) {
+                    return self.get_property(name, ctx);
+                }
+                Err($crate::host::com_object::no_member($type_name, "method", name, Self::COM_MEMBERS))
+            }
+
+            fn type_name(&self) -> &str {
+                $type_name
+            }
+
+            fn default_member(&self) -> Option<&str> {
+                None$(.or(Some(stringify!($default))))?
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::Context;
+    use crate::host::ComObject;
+
+    struct Timer {
+        interval: i64,
+        name: String,
+        started: Vec<(f64, Option<String>)>,
+    }
+
+    impl Timer {
+        // Enabled while the interval is positive
+        fn enabled(&self, _ctx: &mut Context) -> ComResult<bool> {
+            Ok(self.interval > 0)
+        }
+
+        fn set_enabled(&mut self, _ctx: &mut Context, enabled: bool) -> ComResult<()> {
+            self.interval = if enabled { self.interval.abs().max(1) } else { -self.interval.abs() };
+            Ok(())
+        }
+
+        fn start(&mut self, _ctx: &mut Context, seconds: f64, label: Option<String>) -> ComResult<()> {
+            self.started.push((seconds, label));
+            Ok(())
+        }
+
+        fn elapsed(&mut self, _ctx: &mut Context) -> ComResult<f64> {
+            Ok(self.started.iter().map(|(seconds, _)| seconds).sum())
+        }
+    }
+
+    crate::com_object! {
+        impl ComObject for Timer as "Timer" {
+            default Interval;
+            prop Interval: i64 => interval;
+            prop Enabled: bool => enabled, set_enabled;
+            get Name: String => name;
+            method Start(seconds: f64, label: Option<String>) => start;
+            method Elapsed() => elapsed;
+        }
+    }
+
+    #[test]
+    fn test_com_object_routes_typed_members() {
+        let mut ctx = Context::new();
+        let mut timer = Timer { interval: 1000, name: "T1".into(), started: Vec::new() };
+        let number = |result: ComResult<Value>| result.err().and_then(|e| crate::host::excel::error_number(&e));
+
+        assert!(matches!(timer.get_property("interval", &mut ctx), Ok(Value::Integer(1000))));
+        timer.set_property("Interval", Value::String("250".into()), &mut ctx).unwrap();
+        assert_eq!(timer.interval, 250);
+        assert!(matches!(timer.call_method("NAME", &[], &mut ctx), Ok(Value::String(name)) if name == "T1"));
+        assert_eq!(number(timer.set_property("Name", Value::Empty, &mut ctx).map(|_| Value::Empty)), Some(383));
+        assert_eq!(number(timer.set_property("Interval", Value::String("soon".into()), &mut ctx).map(|_| Value::Empty)), Some(13));
+        timer.set_property("enabled", Value::Boolean(false), &mut ctx).unwrap();
+        assert_eq!(timer.interval, -250);
+        assert!(matches!(timer.get_property("Enabled", &mut ctx), Ok(Value::Boolean(false))));
+        timer.set_property("Enabled", Value::Integer(-1), &mut ctx).unwrap();
+        assert_eq!(timer.interval, 250);
+
+        timer.call_method("Start", &[Value::Double(1.5)], &mut ctx).unwrap();
+        timer.call_method("start", &[Value::Integer(2), Value::String("lap".into())], &mut ctx).unwrap();
+        assert_eq!(timer.started, [(1.5, None), (2.0, Some("lap".to_string()))]);
+        assert!(matches!(timer.call_method("Elapsed", &[], &mut ctx), Ok(Value::Double(total)) if total == 3.5));
+        assert_eq!(number(timer.call_method("Start", &[], &mut ctx)), Some(449));
+        assert_eq!(number(timer.call_method("Elapsed", &[Value::Integer(1)], &mut ctx)), Some(450));
+        let unknown = timer.call_method("Strat", &[Value::Integer(1)], &mut ctx).err().unwrap();
+        assert_eq!(unknown.to_string(), "Unknown Timer method 'Strat'; did you mean 'Start'?");
+        assert_eq!((timer.type_name(), timer.default_member()), ("Timer", Some("Interval")));
+    }
+}
@@ -0,0 +1,226 @@
+// src/host/dll.rs
+// ============================================================================
+// DECLARE - External DLL procedures
+//
+// `Declare Function URLDownloadToFile Lib "urlmon" ...` never loads a DLL.
+// Calls go to a Rust handler the embedder registered for the library and
+// entry point (RuntimeConfig::dll_function); without one, DllPolicy decides
+// between raising error 70 (Deny, the default) and returning the return
+// type's zero value (Stub). Every call is logged and recorded either way.
+// ============================================================================
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+
+use crate::ast::Expression;
+use crate::context::{Context, Value};
+
+/// Rust implementation of a DLL entry point; receives the evaluated arguments
+pub type DllHandler = Arc<dyn Fn(&[Value]) -> Result<Value> + Send + Sync>;
+
+/// Handlers keyed by library and entry point (case-insensitive, `.dll` optional)
+#[derive(Clone, Default)]
+pub struct DllRegistry {
+    handlers: HashMap<String, DllHandler>,
+}
+
+impl DllRegistry {
+    pub fn register(&mut self, library: &str, entry: &str, handler: DllHandler) {
+        self.handlers.insert(key(library, entry), handler);
+    }
+
+    pub fn get(&self, library: &str, entry: &str) -> Option<DllHandler> {
+        self.handlers.get(&key(library, entry)).cloned()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+}
+
+impl fmt::Debug for DllRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<&String> = self.handlers.keys().collect();
+        names.sort();
+        f.debug_struct("DllRegistry").field("handlers", &names).finish()
+    }
+}
+
+/// `kernel32.dll` + `Sleep` -> `kernel32!sleep`
+fn key(library: &str, entry: &str) -> String {
+    let library = library.trim().to_ascii_lowercase();
+    let library = library.strip_suffix(".dll").unwrap_or(&library);
+    format!("{}!{}", library, entry.to_ascii_lowercase())
+}
+
+/// What a Declare'd procedure with no registered handler does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DllPolicy {
+    /// Raise run-time error 70 (Permission denied)
+    #[default]
+    Deny,
+    /// Return the declared return type's zero value
+    Stub,
+}
+
+/// A `Declare` statement, registered on the Context by name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeclaredProcedure {
+    pub library: String,
+    /// Alias if given, otherwise the VBA name
+    pub entry: String,
+    pub is_function: bool,
+    pub return_type: Option<String>,
+}
+
+/// How a recorded call was answered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DllOutcome {
+    Handled,
+    Stubbed,
+    Denied,
+}
+
+/// One call to a Declare'd procedure
+#[derive(Debug, Clone, PartialEq)]
+pub struct DllCall {
+    pub library: String,
+    pub entry: String,
+    /// Arguments formatted as VBA literals
+    pub args: Vec<String>,
+    pub outcome: DllOutcome,
+}
+
+impl DllCall {
+    /// `urlmon!URLDownloadToFileA(0, "http://...", "C:\x", 0, 0)`
+    pub fn to_text(&self) -> String {
+        format!("{}!{}({})", self.library, self.entry, self.args.join(", "))
+    }
+}
+
+/// Every call to a Declare'd procedure, in call order
+static DLL_CALLS: Lazy<Mutex<Vec<DllCall>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Forget recorded calls
+pub fn reset() {
+    DLL_CALLS.lock().unwrap().clear();
+}
+
+/// Recorded calls, in call order
+pub fn calls() -> Vec<DllCall> {
+    DLL_CALLS.lock().unwrap().clone()
+}
+
+/// Run `name` if it is a Declare'd procedure: None when it is not, else
+/// the handler's result (errors carry a VBA error number).
+pub(crate) fn call_declared(name: &str, args: &[Expression], ctx: &mut Context) -> Option<Result<Value>> {
+    let procedure = ctx.get_declared(name)?.clone();
+    Some(call(&procedure, args, ctx))
+}
+
+fn call(procedure: &DeclaredProcedure, args: &[Expression], ctx: &mut Context) -> Result<Value> {
+    let values = args
+        .iter()
+        .map(|a| crate::interpreter::evaluate_expression(a, ctx))
+        .collect::<Result<Vec<_>>>()?;
+
+    let handler = ctx.runtime_config.dll_functions.get(&procedure.library, &procedure.entry);
+    let outcome = match (&handler, ctx.runtime_config.dll_policy) {
+        (Some(_), _) => DllOutcome::Handled,
+        (None, DllPolicy::Stub) => DllOutcome::Stubbed,
+        (None, DllPolicy::Deny) => DllOutcome::Denied,
+    };
+    let call = DllCall {
+        library: procedure.library.clone(),
+        entry: procedure.entry.clone(),
+        args: values.iter().map(crate::host::stub::literal).collect(),
+        outcome,
+    };
+    ctx.log(&format!("Declare {:?}: {}", outcome, call.to_text()));
+    DLL_CALLS.lock().unwrap().push(call.clone());
+    let name = format!("{}!{}", procedure.library, procedure.entry);
+    crate::vm::hooks::host_call(ctx, crate::vm::HostCallKind::Dll, &name, &values)?;
+
+    crate::vm::telemetry::host_span(ctx, crate::vm::HostCallKind::Dll, &name, |ctx| match handler {
+        Some(handler) => handler(&values),
+        None if outcome == DllOutcome::Stubbed && procedure.is_function => {
+            Ok(ctx.declared_type(procedure.return_type.as_deref()).default_value())
+        }
+        None if outcome == DllOutcome::Stubbed => Ok(Value::Empty),
+        None => Err(crate::vba_errors::VbaErr::PermissionDenied.error(format!("{} is not allowed", call.to_text()))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{Program, Statement};
+    use crate::runtime_config::RuntimeConfig;
+
+    fn declare(name: &str, alias: Option<&str>) -> Statement {
+        Statement::Declare {
+            visibility: Some("Private".into()),
+            ptr_safe: true,
+            name: name.into(),
+            is_function: true,
+            library: "kernel32".into(),
+            alias: alias.map(str::to_string),
+            params: vec![],
+            return_type: Some("Long".into()),
+        }
+    }
+
+    #[test]
+    fn test_handlers_stub_and_deny() {
+        let result = |name: &str| Statement::Assignment {
+            lvalue: crate::ast::AssignmentTarget::Identifier(name.into()),
+            rvalue: Expression::FunctionCall {
+                function: Box::new(Expression::Identifier("DllTestTicks".into())),
+                args: vec![Expression::Integer(7)],
+            },
+            rvalue_compiled: Default::default(),
+        };
+        let program = Program {
+            statements: vec![
+                declare("DllTestTicks", Some("GetTickCount")),
+                Statement::Dim { names: vec![("DllTestResult".into(), None)] },
+                Statement::Subroutine {
+                    visibility: None,
+                    name: "DllTestMain".into(),
+                    params: vec![],
+                    body: vec![result("DllTestResult")].into(),
+                },
+            ],
+            diagnostics: vec![],
+        };
+        let executor = crate::vm::ProgramExecutor::new(program);
+        let run = |config: RuntimeConfig| {
+            let mut ctx = Context::with_config(config);
+            executor.execute_entrypoint(&mut ctx, "DllTestMain").unwrap();
+            ctx
+        };
+
+        let handler: DllHandler = Arc::new(|args: &[Value]| Ok(Value::Long(args.len() as i32 + 40)));
+        let ctx = run(RuntimeConfig::builder().dll_function("Kernel32.dll", "gettickcount", handler).build());
+        assert_eq!(ctx.get_var("DllTestResult").unwrap().as_string(), "41");
+
+        let ctx = run(RuntimeConfig::builder().dll_policy(DllPolicy::Stub).build());
+        assert!(matches!(ctx.get_var("DllTestResult"), Some(Value::Long(0))));
+
+        let ctx = run(RuntimeConfig::default());
+        assert_eq!(ctx.err.as_ref().map(|e| e.number), Some(70));
+
+        let outcomes: Vec<DllOutcome> = calls()
+            .into_iter()
+            .filter(|c| c.entry == "GetTickCount" && c.args == ["7"])
+            .map(|c| c.outcome)
+            .collect();
+        assert!(outcomes.contains(&DllOutcome::Handled));
+        assert!(outcomes.contains(&DllOutcome::Stubbed));
+        assert!(outcomes.contains(&DllOutcome::Denied));
+    }
+}
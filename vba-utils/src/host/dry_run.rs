@@ -0,0 +1,349 @@
+// src/host/dry_run.rs
+// ============================================================================
+// DRY RUN - Plan a macro's writes instead of applying them
+//
+// With RuntimeConfig::dry_run on (or ProgramExecutor::execute_dry_run),
+// the run's Context gets a plan (Context::dry_run_plan) and
+// initialize_excel_host puts a DryRunBackend recording into it in front of
+// the context's backend: cell values, formulas, number formats and new sheets go into the
+// mutation plan and never reach the backend. The macro reads back what it
+// planned, so it runs as it would for real, except that a formula it wrote
+// reads back as its text instead of being calculated. Workbook saves and
+// file operations on late-bound objects (Scripting.FileSystemObject,
+// ADODB.Stream, ...), which are never applied in any mode, are listed too.
+//
+// The plan comes back in the order the macro made the writes, for approval
+// before the macro is let loose on real data. Plans belong to their
+// context, so dry runs and real runs of other contexts can go on at the
+// same time.
+// ============================================================================
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::context::Context;
+use crate::host::excel::backend::{ExcelBackend, SharedExcelBackend};
+use crate::host::excel::objects::indices_to_address;
+use crate::symbol::lower;
+
+/// One write the macro would have made
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mutation {
+    SetValue { sheet: String, row: i32, col: i32, value: String },
+    SetFormula { sheet: String, row: i32, col: i32, formula: String },
+    SetNumberFormat { sheet: String, row: i32, col: i32, format: String },
+    AddSheet { name: String },
+    /// Workbook.Save / SaveAs / SaveCopyAs / Close SaveChanges:=True
+    SaveWorkbook { workbook: String, path: Option<String> },
+    /// A call that writes files, as `StubCall::to_text` shows it
+    File { call: String },
+}
+
+impl Mutation {
+    /// `Sheet1!A1 = 42`, `Sheet1!B1 formula =A1*2`, `add sheet Report`, ...
+    pub fn to_text(&self) -> String {
+        let cell = |sheet: &str, row: i32, col: i32| format!("{}!{}", sheet, indices_to_address(row, col));
+        match self {
+            Self::SetValue { sheet, row, col, value } => format!("{} = {}", cell(sheet, *row, *col), value),
+            Self::SetFormula { sheet, row, col, formula } => format!("{} formula {}", cell(sheet, *row, *col), formula),
+            Self::SetNumberFormat { sheet, row, col, format } => {
+                format!("{} number format {}", cell(sheet, *row, *col), format)
+            }
+            Self::AddSheet { name } => format!("add sheet {}", name),
+            Self::SaveWorkbook { workbook, path: Some(path) } => format!("save {} as {}", workbook, path),
+            Self::SaveWorkbook { workbook, path: None } => format!("save {}", workbook),
+            Self::File { call } => format!("file {}", call),
+        }
+    }
+}
+
+/// Writes a dry run planned, in the order the macro made them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MutationPlan {
+    pub mutations: Vec<Mutation>,
+}
+
+impl MutationPlan {
+    pub fn is_empty(&self) -> bool {
+        self.mutations.is_empty()
+    }
+
+    /// One mutation per line
+    pub fn to_text(&self) -> String {
+        self.mutations.iter().map(|m| m.to_text() + "\n").collect()
+    }
+}
+
+/// The plan of a dry run in progress, shared by its context
+/// (`Context::dry_run_plan`) and the DryRunBackend recording into it
+#[derive(Clone, Default)]
+pub struct ActivePlan(Arc<Mutex<MutationPlan>>);
+
+impl ActivePlan {
+    fn record(&self, mutation: Mutation) {
+        self.0.lock().unwrap().mutations.push(mutation);
+    }
+
+    /// The writes planned so far
+    pub fn plan(&self) -> MutationPlan {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl fmt::Debug for ActivePlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let planned = self.0.lock().unwrap().mutations.len();
+        f.debug_tuple("ActivePlan").field(&planned).finish()
+    }
+}
+
+/// Start planning `ctx`'s writes; those made from now on are recorded
+/// instead of applied
+pub fn start(ctx: &mut Context) {
+    ctx.dry_run_plan = Some(ActivePlan::default());
+}
+
+/// Whether `ctx` is in a dry run
+pub fn is_active(ctx: &Context) -> bool {
+    ctx.dry_run_plan.is_some()
+}
+
+/// Add `mutation` to `ctx`'s plan; false when it is not in a dry run and
+/// the caller should apply it
+pub(crate) fn record(ctx: &Context, mutation: Mutation) -> bool {
+    match &ctx.dry_run_plan {
+        Some(plan) => {
+            plan.record(mutation);
+            true
+        }
+        None => false,
+    }
+}
+
+/// End `ctx`'s dry run and return its plan
+pub fn take(ctx: &mut Context) -> Option<MutationPlan> {
+    ctx.dry_run_plan.take().map(|plan| plan.plan())
+}
+
+/// Record a stub call that writes files (`fso.CreateTextFile`, `ts.WriteLine`,
+/// `stream.SaveToFile`, ...)
+pub(crate) fn record_file_call(ctx: &Context, member: &str, call: impl FnOnce() -> String) {
+    const FILE_MEMBERS: [&str; 14] = [
+        "createtextfile", "opentextfile", "write", "writeline", "writeblanklines", "copyfile", "movefile",
+        "deletefile", "createfolder", "deletefolder", "copyfolder", "movefolder", "savetofile", "writetext",
+    ];
+    if is_active(ctx) && FILE_MEMBERS.contains(&lower(member).as_ref()) {
+        record(ctx, Mutation::File { call: call() });
+    }
+}
+
+/// A cell the dry run wrote
+#[derive(Debug, Clone, Default)]
+struct PlannedCell {
+    /// Value, or the formula's text
+    value: Option<String>,
+    formula: Option<String>,
+    number_format: Option<String>,
+}
+
+/// Reads from the wrapped backend (nothing when there is none) with the
+/// planned writes on top; writes go to `plan`
+pub struct DryRunBackend {
+    inner: Option<SharedExcelBackend>,
+    plan: ActivePlan,
+    cells: HashMap<(String, i32, i32), PlannedCell>,
+    sheets: Vec<String>,
+}
+
+impl DryRunBackend {
+    pub fn new(inner: Option<SharedExcelBackend>, plan: ActivePlan) -> Self {
+        Self { inner, plan, cells: HashMap::new(), sheets: Vec::new() }
+    }
+
+    fn planned(&self, sheet: &str, row: i32, col: i32) -> Option<&PlannedCell> {
+        self.cells.get(&(sheet.to_string(), row, col))
+    }
+
+    fn plan(&mut self, sheet: &str, row: i32, col: i32) -> &mut PlannedCell {
+        self.cells.entry((sheet.to_string(), row, col)).or_default()
+    }
+
+    fn read<T: Default>(&self, read: impl FnOnce(&dyn ExcelBackend) -> Result<T, String>) -> Result<T, String> {
+        match &self.inner {
+            Some(inner) => read(&*inner.lock().unwrap()),
+            None => Ok(T::default()),
+        }
+    }
+}
+
+impl ExcelBackend for DryRunBackend {
+    fn name(&self) -> &str {
+        "dry-run"
+    }
+
+    fn workbook_id(&self) -> Option<String> {
+        self.inner.as_ref().and_then(|inner| inner.lock().unwrap().workbook_id())
+    }
+
+    fn sheet_names(&self) -> Vec<String> {
+        let mut sheets = self.inner.as_ref().map(|inner| inner.lock().unwrap().sheet_names()).unwrap_or_default();
+        sheets.extend(self.sheets.iter().cloned());
+        sheets
+    }
+
+    fn add_sheet(&mut self, name: &str) -> Result<(), String> {
+        if self.sheet_names().iter().any(|s| s.eq_ignore_ascii_case(name)) {
+            return Err(format!("A sheet named {} already exists", name));
+        }
+        self.sheets.push(name.to_string());
+        self.plan.record(Mutation::AddSheet { name: name.to_string() });
+        Ok(())
+    }
+
+    fn get_cell_value(&self, sheet: &str, row: i32, col: i32) -> Result<String, String> {
+        match self.planned(sheet, row, col).and_then(|cell| cell.value.clone()) {
+            Some(value) => Ok(value),
+            None => self.read(|inner| inner.get_cell_value(sheet, row, col)),
+        }
+    }
+
+    fn set_cell_value(&mut self, sheet: &str, row: i32, col: i32, value: &str) -> Result<(), String> {
+        let cell = self.plan(sheet, row, col);
+        cell.value = Some(value.to_string());
+        cell.formula = None;
+        self.plan.record(Mutation::SetValue { sheet: sheet.to_string(), row, col, value: value.to_string() });
+        Ok(())
+    }
+
+    fn get_cell_formula(&self, sheet: &str, row: i32, col: i32) -> Result<String, String> {
+        match self.planned(sheet, row, col).filter(|cell| cell.value.is_some()) {
+            Some(cell) => Ok(cell.formula.clone().or_else(|| cell.value.clone()).unwrap_or_default()),
+            None => self.read(|inner| inner.get_cell_formula(sheet, row, col)),
+        }
+    }
+
+    fn set_cell_formula(&mut self, sheet: &str, row: i32, col: i32, formula: &str) -> Result<(), String> {
+        if !formula.starts_with('=') {
+            return self.set_cell_value(sheet, row, col, formula);
+        }
+        let cell = self.plan(sheet, row, col);
+        cell.value = Some(formula.to_string());
+        cell.formula = Some(formula.to_string());
+        self.plan.record(Mutation::SetFormula { sheet: sheet.to_string(), row, col, formula: formula.to_string() });
+        Ok(())
+    }
+
+    fn get_number_format(&self, sheet: &str, row: i32, col: i32) -> Result<String, String> {
+        match self.planned(sheet, row, col).and_then(|cell| cell.number_format.clone()) {
+            Some(format) => Ok(format),
+            None if self.inner.is_none() => Ok("General".to_string()),
+            None => self.read(|inner| inner.get_number_format(sheet, row, col)),
+        }
+    }
+
+    fn set_number_format(&mut self, sheet: &str, row: i32, col: i32, format: &str) -> Result<(), String> {
+        self.plan(sheet, row, col).number_format = Some(format.to_string());
+        self.plan.record(Mutation::SetNumberFormat { sheet: sheet.to_string(), row, col, format: format.to_string() });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{AssignmentTarget, Expression, Program, Statement};
+    use crate::host::excel::fixture::{assert_cell_eq, WorkbookFixture};
+    use crate::runtime_config::RuntimeConfig;
+
+    #[test]
+    fn test_dry_run_plans_writes_without_applying_them() {
+        // Range("C1").Value = Range("A1").Value + 1
+        // Range("D1").Value = Range("C1").Value * 2
+        // Range("E1").Formula = "=A1*3"
+        // Set fso = CreateObject("Scripting.FileSystemObject")
+        // fso.CreateTextFile "C:\out.txt"
+        let range = |address: &str| Expression::FunctionCall {
+            function: Box::new(Expression::Identifier("Range".into())),
+            args: vec![Expression::String(address.into())],
+        };
+        let value = |address: &str| Expression::PropertyAccess { obj: Box::new(range(address)), property: "Value".into() };
+        let set = |address: &str, property: &str, rvalue: Expression| Statement::Assignment {
+            lvalue: AssignmentTarget::PropertyAccess { object: Box::new(range(address)), property: property.into() },
+            rvalue,
+            rvalue_compiled: Default::default(),
+        };
+        let binary = |left: Expression, op: &str, right: i64| Expression::BinaryOp {
+            left: Box::new(left),
+            op: op.into(),
+            right: Box::new(Expression::Integer(right)),
+        };
+        let body = vec![
+            set("C1", "Value", binary(value("A1"), "+", 1)),
+            set("D1", "Value", binary(value("C1"), "*", 2)),
+            set("E1", "Formula", Expression::String("=A1*3".into())),
+            Statement::Set {
+                target: "fso".into(),
+                expr: Expression::FunctionCall {
+                    function: Box::new(Expression::Identifier("CreateObject".into())),
+                    args: vec![Expression::String("Scripting.FileSystemObject".into())],
+                },
+            },
+            Statement::Expression(Expression::FunctionCall {
+                function: Box::new(Expression::PropertyAccess {
+                    obj: Box::new(Expression::Identifier("fso".into())),
+                    property: "CreateTextFile".into(),
+                }),
+                args: vec![Expression::String("C:\\out.txt".into())],
+            }),
+        ];
+        let program = Program {
+            statements: vec![Statement::Subroutine { visibility: None, name: "Main".into(), params: vec![], body: body.into() }],
+            diagnostics: vec![],
+        };
+
+        let mut ctx = WorkbookFixture::new().cell("A1", 41).context(RuntimeConfig::default()).unwrap();
+        let executor = crate::vm::ProgramExecutor::new(program);
+        let plan = executor.execute_dry_run(&mut ctx).unwrap();
+        assert!(ctx.err.is_none());
+        assert_eq!(
+            plan.to_text(),
+            "Sheet1!C1 = 42\nSheet1!D1 = 84\nSheet1!E1 formula =A1*3\nfile Scripting.FileSystemObject.CreateTextFile(\"C:\\\\out.txt\")\n"
+        );
+        // Nothing reached the workbook, and the next run is a real one
+        assert_cell_eq(&ctx, "C1", crate::host::excel::backend::CellValue::Empty);
+        assert!(!is_active(&ctx));
+        executor.execute(&mut ctx).unwrap();
+        assert_cell_eq(&ctx, "D1", 84);
+        executor.shutdown(&mut ctx).unwrap();
+    }
+
+    // The plan and the backend in front of it are the context's: another
+    // context running during a dry run writes for real
+    #[test]
+    fn test_dry_run_belongs_to_its_context() {
+        // Range("C1").Value = 1
+        let set = Statement::Assignment {
+            lvalue: AssignmentTarget::PropertyAccess {
+                object: Box::new(Expression::FunctionCall {
+                    function: Box::new(Expression::Identifier("Range".into())),
+                    args: vec![Expression::String("C1".into())],
+                }),
+                property: "Value".into(),
+            },
+            rvalue: Expression::Integer(1),
+            rvalue_compiled: Default::default(),
+        };
+        let main = Statement::Subroutine { visibility: None, name: "Main".into(), params: vec![], body: vec![set].into() };
+        let executor = crate::vm::ProgramExecutor::new(Program { statements: vec![main], diagnostics: vec![] });
+
+        let mut planning = WorkbookFixture::new().context(RuntimeConfig::default()).unwrap();
+        start(&mut planning);
+        let mut real = WorkbookFixture::new().context(RuntimeConfig::default()).unwrap();
+        executor.execute(&mut real).unwrap();
+        assert!(!is_active(&real));
+        assert_cell_eq(&real, "C1", 1);
+        assert_eq!(take(&mut planning), Some(MutationPlan::default()));
+        executor.shutdown(&mut real).unwrap();
+    }
+}
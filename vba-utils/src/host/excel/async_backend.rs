@@ -0,0 +1,713 @@
+// src/host/excel/async_backend.rs
+// ============================================================================
+// ASYNC EXCEL BACKEND - Cell stores behind network services
+//
+// An AsyncExcelBackend returns futures instead of values. The interpreter
+// itself is synchronous, so AsyncBackendAdapter plugs one in as an ordinary
+// ExcelBackend:
+//   - writes are started and left pending on the workbook's queue (each
+//     EngineSession has its own); they are polled at every statement
+//     boundary, so they progress while the macro keeps running
+//   - reads (and workbook/sheet queries) first wait for pending writes, then
+//     for their own result
+//   - at the end of a run the remaining writes are awaited
+// ProgramExecutor::execute_async runs the macro on a coroutine (see `drive`):
+// at every statement boundary it suspends so the caller's executor can run
+// other tasks, and a read suspends it until the caller has awaited the read.
+// Backend futures are then only ever polled by the caller's task, so they
+// may depend on its executor (a current-thread runtime, a LocalSet, ...).
+// ProgramExecutor::execute instead waits on the calling thread, parking it
+// until the future's waker fires; there the futures must make progress
+// without the caller's executor.
+// ============================================================================
+
+use std::any::Any;
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context as TaskContext, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use corosensei::stack::DefaultStack;
+use corosensei::{CoroutineResult, ScopedCoroutine, Yielder};
+
+use crate::context::Context;
+use crate::host::excel::backend::ExcelBackend;
+use crate::host::excel::static_engine::{self, StaticWorkbook};
+
+/// Result of an async backend call
+pub type BackendFuture<T> = Pin<Box<dyn Future<Output = Result<T, String>> + Send + 'static>>;
+
+/// A cell store whose calls complete asynchronously. Futures own their
+/// arguments so writes can stay in flight after the call returns.
+pub trait AsyncExcelBackend: Send + Sync {
+    /// Short name for diagnostics
+    fn name(&self) -> &str;
+
+    fn workbook_id(&self) -> BackendFuture<Option<String>>;
+
+    fn sheet_names(&self) -> BackendFuture<Vec<String>>;
+
+    fn add_sheet(&self, name: String) -> BackendFuture<()>;
+
+    fn get_cell_value(&self, sheet: String, row: i32, col: i32) -> BackendFuture<String>;
+
+    fn set_cell_value(&self, sheet: String, row: i32, col: i32, value: String) -> BackendFuture<()>;
+
+    /// Write a rectangle of values; defaults to one set_cell_value per cell,
+    /// awaited in order
+    fn set_range_values(&self, sheet: String, row: i32, col: i32, values: Vec<Vec<String>>) -> BackendFuture<()> {
+        let mut writes = Vec::new();
+        for (r, line) in values.into_iter().enumerate() {
+            for (c, value) in line.into_iter().enumerate() {
+                writes.push(self.set_cell_value(sheet.clone(), row + r as i32, col + c as i32, value));
+            }
+        }
+        Box::pin(async move {
+            for write in writes {
+                write.await?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Formula in A1 notation; defaults to the value, as for constant cells
+    fn get_cell_formula(&self, sheet: String, row: i32, col: i32) -> BackendFuture<String> {
+        self.get_cell_value(sheet, row, col)
+    }
+
+    /// Store a formula; defaults to storing the text as a value
+    fn set_cell_formula(&self, sheet: String, row: i32, col: i32, formula: String) -> BackendFuture<()> {
+        self.set_cell_value(sheet, row, col, formula)
+    }
+
+    fn get_number_format(&self, _sheet: String, _row: i32, _col: i32) -> BackendFuture<String> {
+        Box::pin(async { Ok("General".to_string()) })
+    }
+
+    fn set_number_format(&self, _sheet: String, _row: i32, _col: i32, _format: String) -> BackendFuture<()> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// An async backend shared between the runtime config and the engine
+pub type SharedAsyncExcelBackend = Arc<dyn AsyncExcelBackend>;
+
+// ============================================================================
+// Pending writes
+// ============================================================================
+
+/// Writes started but not yet complete, of the runs of one workbook (see
+/// `EngineSession`)
+#[derive(Default)]
+pub(crate) struct PendingWrites {
+    queue: Mutex<WriteQueue>,
+    /// Live AsyncBackendAdapters on this queue; with none, statement
+    /// boundaries cost one atomic load
+    adapters: AtomicUsize,
+}
+
+#[derive(Default)]
+struct WriteQueue {
+    /// Oldest first
+    futures: VecDeque<BackendFuture<()>>,
+    /// First write that failed, reported by the next wait
+    error: Option<String>,
+}
+
+impl PendingWrites {
+    /// Poll every pending write once, dropping the finished ones
+    fn poll(&self, cx: &mut TaskContext<'_>) {
+        let mut queue = self.queue.lock().unwrap();
+        let mut still_pending = VecDeque::with_capacity(queue.futures.len());
+        while let Some(mut future) = queue.futures.pop_front() {
+            match future.as_mut().poll(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(e)) => {
+                    queue.error.get_or_insert(e);
+                }
+                Poll::Pending => still_pending.push_back(future),
+            }
+        }
+        queue.futures = still_pending;
+    }
+
+    fn take_futures(&self) -> VecDeque<BackendFuture<()>> {
+        std::mem::take(&mut self.queue.lock().unwrap().futures)
+    }
+
+    fn record_error(&self, e: String) {
+        self.queue.lock().unwrap().error.get_or_insert(e);
+    }
+
+    fn take_error(&self) -> Result<(), String> {
+        self.queue.lock().unwrap().error.take().map_or(Ok(()), Err)
+    }
+
+    fn flush_blocking(&self) -> Result<(), String> {
+        for future in self.take_futures() {
+            if let Err(e) = block_on(future) {
+                self.record_error(e);
+            }
+        }
+        self.take_error()
+    }
+
+    async fn flush(&self) -> Result<(), String> {
+        for future in self.take_futures() {
+            if let Err(e) = future.await {
+                self.record_error(e);
+            }
+        }
+        self.take_error()
+    }
+}
+
+/// Pending writes of the workbook entered on this thread
+fn current_writes() -> Arc<PendingWrites> {
+    static_engine::stores().engine.async_writes.clone()
+}
+
+/// Wakes a thread parked in `block_on`
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Drive a future to completion on this thread
+fn block_on<F: Future + ?Sized>(mut future: Pin<Box<F>>) -> F::Output {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = TaskContext::from_waker(&waker);
+    loop {
+        if let Poll::Ready(value) = future.as_mut().poll(&mut cx) {
+            return value;
+        }
+        thread::park();
+    }
+}
+
+/// Called by the VM between statements. Under `drive` this suspends the run
+/// so the caller's task polls the pending writes and yields to its
+/// executor; otherwise the writes of `ctx`'s workbook are polled here.
+pub fn statement_boundary(ctx: &Context) {
+    if suspend(Suspend::Statement).is_some() {
+        return;
+    }
+    let pending = &ctx.static_workbook.engine().async_writes;
+    if pending.adapters.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    pending.poll(&mut TaskContext::from_waker(&waker));
+}
+
+/// Wait for every pending write of the entered workbook, blocking this thread
+pub fn flush_blocking() -> Result<(), String> {
+    current_writes().flush_blocking()
+}
+
+/// Await every pending write of the workbook entered when this is called
+pub fn flush() -> impl Future<Output = Result<(), String>> {
+    let pending = current_writes();
+    async move { pending.flush().await }
+}
+
+/// Number of writes of the entered workbook still in flight
+pub fn pending_writes() -> usize {
+    current_writes().queue.lock().unwrap().futures.len()
+}
+
+// ============================================================================
+// Driving a run
+// ============================================================================
+
+/// Stack of the coroutine a driven run executes on; as large as a main
+/// thread's, since VBA calls recurse on it
+const RUN_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Why a driven run suspended
+enum Suspend {
+    /// Between two statements
+    Statement,
+    /// A read: the driver awaits it and resumes the run with its result
+    Read(BackendFuture<Box<dyn Any + Send>>),
+}
+
+/// What the driver resumes a run with: the result of its read, or `()`
+type Resumed = Result<Box<dyn Any + Send>, String>;
+
+thread_local! {
+    /// Yielder of the driven run executing on this thread; null when none
+    /// is (including while a run is suspended)
+    static YIELDER: Cell<*const Yielder<Resumed, Suspend>> = const { Cell::new(std::ptr::null()) };
+}
+
+/// Suspend the driven run executing on this thread, if any
+fn suspend(reason: Suspend) -> Option<Resumed> {
+    let yielder = YIELDER.with(|current| current.replace(std::ptr::null()));
+    if yielder.is_null() {
+        return None;
+    }
+    // SAFETY: `drive` only sets the pointer while its coroutine runs on this
+    // thread, and we are on that coroutine's stack, so the yielder is alive
+    let resumed = unsafe { &*yielder }.suspend(reason);
+    YIELDER.with(|current| current.set(yielder));
+    Some(resumed)
+}
+
+/// Clears YIELDER when a driven run returns or unwinds
+struct DrivenRun;
+
+impl Drop for DrivenRun {
+    fn drop(&mut self) {
+        YIELDER.with(|current| current.set(std::ptr::null()));
+    }
+}
+
+/// Lets the executor run other tasks once
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// Run `run` with `workbook` entered, yielding to the caller's executor
+/// between statements and awaiting reads of async backends on it. The
+/// workbook is only entered while the run executes, never across an await.
+pub(crate) async fn drive<R>(workbook: &StaticWorkbook, run: impl FnOnce() -> R) -> R {
+    let stack = match DefaultStack::new(RUN_STACK_SIZE) {
+        Ok(stack) => stack,
+        Err(e) => {
+            eprintln!("⚠️ No stack for the async run ({}); running it blocking", e);
+            let _workbook = workbook.enter();
+            return run();
+        }
+    };
+    let mut coroutine = ScopedCoroutine::with_stack(stack, |yielder: &Yielder<Resumed, Suspend>, _: Resumed| {
+        YIELDER.with(|current| current.set(yielder));
+        let _driven = DrivenRun;
+        run()
+    });
+    let pending = workbook.engine().async_writes.clone();
+    let mut resumed: Resumed = Ok(Box::new(()));
+    loop {
+        let step = {
+            let _workbook = workbook.enter();
+            coroutine.resume(resumed)
+        };
+        resumed = match step {
+            CoroutineResult::Return(value) => return value,
+            CoroutineResult::Yield(Suspend::Statement) => {
+                poll_fn(|cx| {
+                    pending.poll(cx);
+                    Poll::Ready(())
+                })
+                .await;
+                YieldNow(false).await;
+                Ok(Box::new(()))
+            }
+            CoroutineResult::Yield(Suspend::Read(read)) => match pending.flush().await {
+                Ok(()) => read.await,
+                Err(e) => Err(e),
+            },
+        };
+    }
+}
+
+// ============================================================================
+// Adapter
+// ============================================================================
+
+/// Runs an AsyncExcelBackend behind the synchronous ExcelBackend interface
+pub struct AsyncBackendAdapter {
+    backend: SharedAsyncExcelBackend,
+    /// Queue of the workbook entered when the adapter was made
+    pending: Arc<PendingWrites>,
+}
+
+impl AsyncBackendAdapter {
+    /// Wrap `backend` for the workbook entered on this thread; its pending
+    /// writes from a previous adapter are discarded
+    pub fn new(backend: SharedAsyncExcelBackend) -> Self {
+        let pending = current_writes();
+        *pending.queue.lock().unwrap() = WriteQueue::default();
+        pending.adapters.fetch_add(1, Ordering::Relaxed);
+        Self { backend, pending }
+    }
+
+    fn write(&self, future: BackendFuture<()>) -> Result<(), String> {
+        self.pending.queue.lock().unwrap().futures.push_back(future);
+        Ok(())
+    }
+
+    /// Wait for pending writes, then for `future`: by handing both to the
+    /// driver of a driven run, else on this thread
+    fn read<T: Send + 'static>(&self, future: BackendFuture<T>) -> Result<T, String> {
+        if YIELDER.with(|current| current.get().is_null()) {
+            self.pending.flush_blocking()?;
+            return block_on(future);
+        }
+        let erased: BackendFuture<Box<dyn Any + Send>> =
+            Box::pin(async move { future.await.map(|value| Box::new(value) as Box<dyn Any + Send>) });
+        let resumed = suspend(Suspend::Read(erased)).expect("checked above");
+        resumed.map(|value| *value.downcast::<T>().expect("resumed with the result of this read"))
+    }
+}
+
+impl Drop for AsyncBackendAdapter {
+    fn drop(&mut self) {
+        self.pending.adapters.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl ExcelBackend for AsyncBackendAdapter {
+    fn name(&self) -> &str {
+        self.backend.name()
+    }
+
+    fn workbook_id(&self) -> Option<String> {
+        self.read(self.backend.workbook_id()).ok().flatten()
+    }
+
+    fn sheet_names(&self) -> Vec<String> {
+        self.read(self.backend.sheet_names()).unwrap_or_default()
+    }
+
+    fn add_sheet(&mut self, name: &str) -> Result<(), String> {
+        self.read(self.backend.add_sheet(name.to_string()))
+    }
+
+    fn get_cell_value(&self, sheet: &str, row: i32, col: i32) -> Result<String, String> {
+        self.read(self.backend.get_cell_value(sheet.to_string(), row, col))
+    }
+
+    fn set_cell_value(&mut self, sheet: &str, row: i32, col: i32, value: &str) -> Result<(), String> {
+        self.write(self.backend.set_cell_value(sheet.to_string(), row, col, value.to_string()))
+    }
+
+    fn set_range_values(&mut self, sheet: &str, row: i32, col: i32, values: &[Vec<String>]) -> Result<(), String> {
+        self.write(self.backend.set_range_values(sheet.to_string(), row, col, values.to_vec()))
+    }
+
+    fn get_cell_formula(&self, sheet: &str, row: i32, col: i32) -> Result<String, String> {
+        self.read(self.backend.get_cell_formula(sheet.to_string(), row, col))
+    }
+
+    fn set_cell_formula(&mut self, sheet: &str, row: i32, col: i32, formula: &str) -> Result<(), String> {
+        self.write(self.backend.set_cell_formula(sheet.to_string(), row, col, formula.to_string()))
+    }
+
+    fn get_number_format(&self, sheet: &str, row: i32, col: i32) -> Result<String, String> {
+        self.read(self.backend.get_number_format(sheet.to_string(), row, col))
+    }
+
+    fn set_number_format(&mut self, sheet: &str, row: i32, col: i32, format: &str) -> Result<(), String> {
+        self.write(self.backend.set_number_format(sheet.to_string(), row, col, format.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{AssignmentTarget, Expression, Program, Statement};
+    use crate::host::excel::backend::ExcelBackendKind;
+    use crate::runtime_config::RuntimeConfig;
+    use crate::vm::ProgramExecutor;
+    use std::collections::HashMap;
+
+    /// Completes on its second poll, like a response arriving later
+    struct Delayed<T>(Option<T>, bool);
+
+    impl<T: Unpin> Future for Delayed<T> {
+        type Output = T;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<T> {
+            if !self.1 {
+                self.1 = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            Poll::Ready(self.0.take().unwrap())
+        }
+    }
+
+    #[derive(Default)]
+    struct RemoteCells {
+        cells: Arc<Mutex<HashMap<(String, i32, i32), String>>>,
+    }
+
+    impl AsyncExcelBackend for RemoteCells {
+        fn name(&self) -> &str {
+            "remote"
+        }
+
+        fn workbook_id(&self) -> BackendFuture<Option<String>> {
+            Box::pin(async { Ok(Some("wb-remote".to_string())) })
+        }
+
+        fn sheet_names(&self) -> BackendFuture<Vec<String>> {
+            Box::pin(async { Ok(vec!["Sheet1".to_string()]) })
+        }
+
+        fn add_sheet(&self, name: String) -> BackendFuture<()> {
+            Box::pin(async move { Err(format!("cannot add {}", name)) })
+        }
+
+        fn get_cell_value(&self, sheet: String, row: i32, col: i32) -> BackendFuture<String> {
+            let cells = self.cells.clone();
+            Box::pin(async move {
+                let value = cells.lock().unwrap().get(&(sheet, row, col)).cloned().unwrap_or_default();
+                Ok(Delayed(Some(value), false).await)
+            })
+        }
+
+        fn set_cell_value(&self, sheet: String, row: i32, col: i32, value: String) -> BackendFuture<()> {
+            let cells = self.cells.clone();
+            Box::pin(async move {
+                Delayed(Some(()), false).await;
+                if value == "#FAIL" {
+                    return Err("write rejected".to_string());
+                }
+                cells.lock().unwrap().insert((sheet, row, col), value);
+                Ok(())
+            })
+        }
+    }
+
+    #[test]
+    fn test_writes_progress_between_statements() {
+        let ctx = Context::new();
+        let _workbook = ctx.static_workbook.enter();
+        let remote = RemoteCells::default();
+        let cells = remote.cells.clone();
+        let mut adapter = AsyncBackendAdapter::new(Arc::new(remote));
+        assert_eq!(adapter.name(), "remote");
+
+        adapter.set_cell_value("Sheet1", 0, 0, "1").unwrap();
+        adapter.set_cell_value("Sheet1", 0, 1, "2").unwrap();
+        assert_eq!(pending_writes(), 2);
+        statement_boundary(&ctx);
+        assert!(cells.lock().unwrap().is_empty());
+        statement_boundary(&ctx);
+        assert_eq!(pending_writes(), 0);
+        assert_eq!(cells.lock().unwrap().len(), 2);
+
+        // A read waits for earlier writes
+        adapter.set_cell_value("Sheet1", 1, 0, "3").unwrap();
+        assert_eq!(adapter.get_cell_value("Sheet1", 1, 0).unwrap(), "3");
+        assert_eq!(adapter.workbook_id().as_deref(), Some("wb-remote"));
+
+        // Failed writes surface when the run's writes are awaited
+        adapter.set_cell_value("Sheet1", 2, 0, "#FAIL").unwrap();
+        adapter.set_cell_value("Sheet1", 3, 0, "4").unwrap();
+        assert_eq!(block_on(Box::pin(flush())), Err("write rejected".to_string()));
+        assert_eq!(cells.lock().unwrap().get(&("Sheet1".to_string(), 3, 0)).map(String::as_str), Some("4"));
+        assert_eq!(flush_blocking(), Ok(()));
+    }
+
+    #[test]
+    fn test_pending_writes_belong_to_the_workbook() {
+        let (first, second) = (StaticWorkbook::new(), StaticWorkbook::new());
+        let mut adapter = {
+            let _workbook = first.enter();
+            AsyncBackendAdapter::new(Arc::new(RemoteCells::default()))
+        };
+        // A second adapter on another workbook leaves the first one's writes
+        let _other = {
+            let _workbook = second.enter();
+            AsyncBackendAdapter::new(Arc::new(RemoteCells::default()))
+        };
+        adapter.set_cell_value("Sheet1", 0, 0, "1").unwrap();
+        assert_eq!(second.engine().async_writes.queue.lock().unwrap().futures.len(), 0);
+        let _workbook = first.enter();
+        assert_eq!(pending_writes(), 1);
+        assert_eq!(flush_blocking(), Ok(()));
+    }
+
+    /// Cells behind a service task: calls only complete when the executor
+    /// running the macro also polls `serve`
+    #[derive(Clone, Default)]
+    struct Service(Arc<Mutex<ServiceState>>);
+
+    type Request = Box<dyn FnOnce(&mut HashMap<(String, i32, i32), String>) + Send>;
+
+    #[derive(Default)]
+    struct ServiceState {
+        cells: HashMap<(String, i32, i32), String>,
+        requests: VecDeque<Request>,
+        waker: Option<Waker>,
+        /// Times `serve` was polled
+        polls: usize,
+    }
+
+    /// Reply to one request, filled in by the service task
+    type Slot<T> = Arc<Mutex<(Option<T>, Option<Waker>)>>;
+
+    impl Service {
+        fn call<T: Send + 'static>(
+            &self,
+            request: impl FnOnce(&mut HashMap<(String, i32, i32), String>) -> T + Send + 'static,
+        ) -> BackendFuture<T> {
+            let slot: Slot<T> = Arc::default();
+            let reply = slot.clone();
+            let mut state = self.0.lock().unwrap();
+            state.requests.push_back(Box::new(move |cells| {
+                let mut reply = reply.lock().unwrap();
+                reply.0 = Some(request(cells));
+                if let Some(waker) = reply.1.take() {
+                    waker.wake();
+                }
+            }));
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+            Box::pin(poll_fn(move |cx| {
+                let mut slot = slot.lock().unwrap();
+                match slot.0.take() {
+                    Some(value) => Poll::Ready(Ok(value)),
+                    None => {
+                        slot.1 = Some(cx.waker().clone());
+                        Poll::Pending
+                    }
+                }
+            }))
+        }
+
+        /// The service task; never finishes
+        async fn serve(self) {
+            poll_fn(|cx| {
+                let mut state = self.0.lock().unwrap();
+                state.polls += 1;
+                state.waker = Some(cx.waker().clone());
+                while let Some(request) = state.requests.pop_front() {
+                    request(&mut state.cells);
+                }
+                Poll::<()>::Pending
+            })
+            .await
+        }
+    }
+
+    impl AsyncExcelBackend for Service {
+        fn name(&self) -> &str {
+            "service"
+        }
+
+        fn workbook_id(&self) -> BackendFuture<Option<String>> {
+            self.call(|_| Some("wb-service".to_string()))
+        }
+
+        fn sheet_names(&self) -> BackendFuture<Vec<String>> {
+            self.call(|_| vec!["Sheet1".to_string()])
+        }
+
+        fn add_sheet(&self, _name: String) -> BackendFuture<()> {
+            self.call(|_| ())
+        }
+
+        fn get_cell_value(&self, sheet: String, row: i32, col: i32) -> BackendFuture<String> {
+            self.call(move |cells| cells.get(&(sheet, row, col)).cloned().unwrap_or_default())
+        }
+
+        fn set_cell_value(&self, sheet: String, row: i32, col: i32, value: String) -> BackendFuture<()> {
+            self.call(move |cells| {
+                cells.insert((sheet, row, col), value);
+            })
+        }
+    }
+
+    /// A single-threaded executor running `main` and `task` together
+    fn run_local<T>(main: impl Future<Output = T>, task: impl Future<Output = ()>) -> T {
+        let (mut main, mut task) = (Box::pin(main), Box::pin(task));
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = TaskContext::from_waker(&waker);
+        loop {
+            if let Poll::Ready(value) = main.as_mut().poll(&mut cx) {
+                return value;
+            }
+            let _ = task.as_mut().poll(&mut cx);
+            thread::park();
+        }
+    }
+
+    fn range_value(address: &str) -> Expression {
+        Expression::PropertyAccess {
+            obj: Box::new(Expression::FunctionCall {
+                function: Box::new(Expression::Identifier("Range".into())),
+                args: vec![Expression::String(address.into())],
+            }),
+            property: "Value".into(),
+        }
+    }
+
+    fn main_program(body: Vec<Statement>) -> ProgramExecutor {
+        ProgramExecutor::new(Program {
+            statements: vec![Statement::Subroutine { visibility: None, name: "Main".into(), params: vec![], body: body.into() }],
+            diagnostics: vec![],
+        })
+    }
+
+    #[test]
+    fn test_execute_async_awaits_reads_on_the_callers_executor() {
+        // Range("B1").Value = Range("A1").Value + 1
+        let executor = main_program(vec![Statement::Assignment {
+            lvalue: AssignmentTarget::PropertyAccess {
+                object: Box::new(Expression::FunctionCall {
+                    function: Box::new(Expression::Identifier("Range".into())),
+                    args: vec![Expression::String("B1".into())],
+                }),
+                property: "Value".into(),
+            },
+            rvalue: Expression::BinaryOp {
+                left: Box::new(range_value("A1")),
+                op: "+".into(),
+                right: Box::new(Expression::Integer(1)),
+            },
+            rvalue_compiled: Default::default(),
+        }]);
+        let service = Service::default();
+        service.0.lock().unwrap().cells.insert(("Sheet1".to_string(), 0, 0), "41".to_string());
+        let config = RuntimeConfig::builder().excel_backend(ExcelBackendKind::asynchronous(service.clone())).build();
+        let mut ctx = Context::with_config(config);
+
+        // Blocking on the read here would never let `serve` answer it
+        run_local(executor.execute_async(&mut ctx), service.clone().serve()).unwrap();
+        assert_eq!(service.0.lock().unwrap().cells.get(&("Sheet1".to_string(), 0, 1)).map(String::as_str), Some("42"));
+        executor.shutdown(&mut ctx).unwrap();
+    }
+
+    #[test]
+    fn test_execute_async_yields_between_statements() {
+        // x = 1 ... x = 5, touching no cells
+        let body = (1..=5)
+            .map(|i| Statement::Assignment {
+                lvalue: AssignmentTarget::Identifier("x".into()),
+                rvalue: Expression::Integer(i),
+                rvalue_compiled: Default::default(),
+            })
+            .collect();
+        let executor = main_program(body);
+        let service = Service::default();
+        let config = RuntimeConfig::builder().excel_backend(ExcelBackendKind::asynchronous(service.clone())).build();
+        let mut ctx = Context::with_config(config);
+
+        run_local(executor.execute_async(&mut ctx), service.clone().serve()).unwrap();
+        assert!(service.0.lock().unwrap().polls >= 5);
+        executor.shutdown(&mut ctx).unwrap();
+    }
+}
@@ -0,0 +1,637 @@
+// src/host/excel/backend.rs
+// ============================================================================
+// EXCEL BACKEND - Pluggable cell store behind Range values and formulas
+//
+// `engine` reads and writes cells through the backend installed by
+// initialize_excel_host, chosen with RuntimeConfig::excel_backend:
+//   Native  - the NativeClientEngine library (cells are dropped when it
+//             fails to initialize, as before)
+//   Static  - the in-memory stores of `static_engine`
+//   Custom  - any ExcelBackend the embedder supplies (a database-backed
+//             sheet store, a test double, ...)
+//   Async   - an AsyncExcelBackend, run through AsyncBackendAdapter
+// Rows and columns are 0-based, as in the engine.
+// ============================================================================
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::context::Value;
+use crate::host::excel::async_backend::{AsyncExcelBackend, SharedAsyncExcelBackend};
+use crate::host::excel::locale::CellLocale;
+use crate::host::excel::{engine, formula, static_engine};
+
+/// A cell store the Excel host can run against
+pub trait ExcelBackend: Send {
+    /// Short name for diagnostics ("native", "static", ...)
+    fn name(&self) -> &str;
+
+    /// Identifier of the workbook the backend serves
+    fn workbook_id(&self) -> Option<String>;
+
+    /// Worksheet names, in tab order
+    fn sheet_names(&self) -> Vec<String>;
+
+    fn add_sheet(&mut self, name: &str) -> Result<(), String>;
+
+    /// Remove a sheet added with `add_sheet`, as a rolled-back run does
+    /// (see `host::journal`). Backends that cannot delete sheets fail.
+    fn delete_sheet(&mut self, name: &str) -> Result<(), String> {
+        Err(format!("The {} backend cannot delete sheet {}", self.name(), name))
+    }
+
+    fn get_cell_value(&self, sheet: &str, row: i32, col: i32) -> Result<String, String>;
+
+    /// Typed value of a cell; the text of a backend that stores only text
+    /// is read in the workbook's `locale`. Backends that know the type
+    /// should override.
+    fn get_typed_value(&self, sheet: &str, row: i32, col: i32, locale: &CellLocale) -> Result<CellValue, String> {
+        self.get_cell_value(sheet, row, col).map(|text| CellValue::parse_in(&text, locale))
+    }
+
+    fn set_cell_value(&mut self, sheet: &str, row: i32, col: i32, value: &str) -> Result<(), String>;
+
+    /// Write a rectangle of values (rows of equal length) starting at
+    /// (row, col). Batched writes arrive here; backends with a bulk call
+    /// should override the per-cell default.
+    fn set_range_values(&mut self, sheet: &str, row: i32, col: i32, values: &[Vec<String>]) -> Result<(), String> {
+        for (r, line) in values.iter().enumerate() {
+            for (c, value) in line.iter().enumerate() {
+                self.set_cell_value(sheet, row + r as i32, col + c as i32, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Formula in A1 notation; a backend without formulas reports the value,
+    /// as Excel does for constant cells
+    fn get_cell_formula(&self, sheet: &str, row: i32, col: i32) -> Result<String, String> {
+        self.get_cell_value(sheet, row, col)
+    }
+
+    /// Store a formula; a backend without formulas stores the text as a value
+    fn set_cell_formula(&mut self, sheet: &str, row: i32, col: i32, formula: &str) -> Result<(), String> {
+        self.set_cell_value(sheet, row, col, formula)
+    }
+
+    fn get_number_format(&self, _sheet: &str, _row: i32, _col: i32) -> Result<String, String> {
+        Ok("General".to_string())
+    }
+
+    /// Store a number format; ignored by backends without formats
+    fn set_number_format(&mut self, _sheet: &str, _row: i32, _col: i32, _format: &str) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// What a cell holds, as Range.Value sees it
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum CellValue {
+    #[default]
+    Empty,
+    Number(f64),
+    Text(String),
+    Boolean(bool),
+    /// `CVErr` number of an error cell (2007 for `#DIV/0!`)
+    Error(i32),
+    Date(NaiveDateTime),
+}
+
+impl CellValue {
+    /// Type a cell's text as this crate writes it (en-US): numbers,
+    /// TRUE/FALSE, error literals and dates; anything else is text
+    pub fn parse(text: &str) -> Self {
+        Self::parse_in(text, &CellLocale::default())
+    }
+
+    /// Type a cell's text spelled the way `locale` shows numbers and dates
+    pub fn parse_in(text: &str, locale: &CellLocale) -> Self {
+        let number = if locale.decimal_separator == '.' {
+            text.parse::<f64>().ok().filter(|n| n.is_finite())
+        } else {
+            None
+        };
+        if text.is_empty() {
+            CellValue::Empty
+        } else if let Some(n) = number.or_else(|| locale.parse_number(text)) {
+            CellValue::Number(n)
+        } else if text.eq_ignore_ascii_case("TRUE") {
+            CellValue::Boolean(true)
+        } else if text.eq_ignore_ascii_case("FALSE") {
+            CellValue::Boolean(false)
+        } else if let Some(number) = formula::cell_error(text) {
+            CellValue::Error(number)
+        } else if let Some(date) = locale.parse_date(text) {
+            CellValue::Date(date)
+        } else {
+            CellValue::Text(text.to_string())
+        }
+    }
+
+    /// The Variant Range.Value returns: whole numbers as Integer, dates as
+    /// Date (DateTime with a time part). `serial_dates` gives Value2's view,
+    /// where dates are Double serial numbers.
+    pub fn to_value(&self, serial_dates: bool) -> Value {
+        match self {
+            CellValue::Empty => Value::Empty,
+            CellValue::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => Value::Integer(*n as i64),
+            CellValue::Number(n) => Value::Double(*n),
+            CellValue::Text(s) => Value::String(s.clone()),
+            CellValue::Boolean(b) => Value::Boolean(*b),
+            CellValue::Error(number) => Value::Error(*number),
+            CellValue::Date(dt) if serial_dates => {
+                let epoch = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap().and_hms_opt(0, 0, 0).unwrap();
+                Value::Double((*dt - epoch).num_seconds() as f64 / 86400.0)
+            }
+            CellValue::Date(dt) if dt.time() == chrono::NaiveTime::MIN => Value::Date(dt.date()),
+            CellValue::Date(dt) => Value::DateTime(*dt),
+        }
+    }
+
+    /// The text this crate stores for the value, which `parse` reads back
+    /// as the same value
+    pub fn to_cell_text(&self) -> String {
+        match self {
+            CellValue::Empty => String::new(),
+            CellValue::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => format!("{}", *n as i64),
+            CellValue::Number(n) => n.to_string(),
+            CellValue::Text(s) => s.clone(),
+            CellValue::Boolean(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+            CellValue::Error(number) => formula::error_literal(*number).unwrap_or("#VALUE!").to_string(),
+            CellValue::Date(dt) if dt.time() == chrono::NaiveTime::MIN => dt.format("%m/%d/%Y").to_string(),
+            CellValue::Date(dt) => dt.format("%m/%d/%Y %H:%M:%S").to_string(),
+        }
+    }
+}
+
+impl From<i32> for CellValue {
+    fn from(n: i32) -> Self {
+        CellValue::Number(n.into())
+    }
+}
+
+impl From<i64> for CellValue {
+    fn from(n: i64) -> Self {
+        CellValue::Number(n as f64)
+    }
+}
+
+impl From<f64> for CellValue {
+    fn from(n: f64) -> Self {
+        CellValue::Number(n)
+    }
+}
+
+impl From<bool> for CellValue {
+    fn from(b: bool) -> Self {
+        CellValue::Boolean(b)
+    }
+}
+
+impl From<&str> for CellValue {
+    fn from(s: &str) -> Self {
+        CellValue::Text(s.to_string())
+    }
+}
+
+impl From<String> for CellValue {
+    fn from(s: String) -> Self {
+        CellValue::Text(s)
+    }
+}
+
+impl From<NaiveDate> for CellValue {
+    fn from(date: NaiveDate) -> Self {
+        CellValue::Date(date.and_time(chrono::NaiveTime::MIN))
+    }
+}
+
+impl From<NaiveDateTime> for CellValue {
+    fn from(dt: NaiveDateTime) -> Self {
+        CellValue::Date(dt)
+    }
+}
+
+/// A backend shared between the runtime config and the engine
+pub type SharedExcelBackend = Arc<Mutex<dyn ExcelBackend>>;
+
+/// Which cell store initialize_excel_host installs
+#[derive(Clone, Default)]
+pub enum ExcelBackendKind {
+    /// NativeClientEngine; without it cells are not stored
+    #[default]
+    Native,
+    /// In-memory `static_engine` stores
+    Static,
+    /// A backend supplied by the embedder
+    Custom(SharedExcelBackend),
+    /// An async backend supplied by the embedder (see `async_backend`)
+    Async(SharedAsyncExcelBackend),
+}
+
+impl ExcelBackendKind {
+    /// Wrap an embedder backend for RuntimeConfig
+    pub fn custom(backend: impl ExcelBackend + 'static) -> Self {
+        Self::Custom(Arc::new(Mutex::new(backend)))
+    }
+
+    /// Wrap an embedder async backend for RuntimeConfig
+    pub fn asynchronous(backend: impl AsyncExcelBackend + 'static) -> Self {
+        Self::Async(Arc::new(backend))
+    }
+}
+
+impl fmt::Debug for ExcelBackendKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Native => write!(f, "Native"),
+            Self::Static => write!(f, "Static"),
+            Self::Custom(backend) => match backend.try_lock() {
+                Ok(backend) => write!(f, "Custom({})", backend.name()),
+                Err(_) => write!(f, "Custom(..)"),
+            },
+            Self::Async(backend) => write!(f, "Async({})", backend.name()),
+        }
+    }
+}
+
+impl PartialEq for ExcelBackendKind {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Native, Self::Native) | (Self::Static, Self::Static) => true,
+            (Self::Custom(a), Self::Custom(b)) => Arc::ptr_eq(a, b),
+            (Self::Async(a), Self::Async(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+/// Cells in the NativeClientEngine workbook
+#[derive(Debug, Clone)]
+pub struct NativeBackend {
+    workbook_id: String,
+}
+
+impl NativeBackend {
+    /// Backend for a workbook opened by engine::initialize_engine
+    pub fn new(workbook_id: String) -> Self {
+        Self { workbook_id }
+    }
+}
+
+impl ExcelBackend for NativeBackend {
+    fn name(&self) -> &str {
+        "native"
+    }
+
+    fn workbook_id(&self) -> Option<String> {
+        Some(self.workbook_id.clone())
+    }
+
+    // The native interface cannot list sheets; report the one in use
+    fn sheet_names(&self) -> Vec<String> {
+        vec![engine::get_active_sheet()]
+    }
+
+    fn add_sheet(&mut self, name: &str) -> Result<(), String> {
+        Err(format!("The native engine cannot add sheet {}", name))
+    }
+
+    fn get_cell_value(&self, sheet: &str, row: i32, col: i32) -> Result<String, String> {
+        engine::native_get_cell_value(&self.workbook_id, sheet, row, col)
+    }
+
+    fn set_cell_value(&mut self, sheet: &str, row: i32, col: i32, value: &str) -> Result<(), String> {
+        engine::native_set_cell_value(&self.workbook_id, sheet, row, col, value)
+    }
+}
+
+/// Cells in the in-memory `static_engine` stores, with formulas calculated
+/// by `formula` and number formats kept per cell. Reads and writes go to the
+/// workbook of the run on the calling thread (`Context::static_workbook`).
+#[derive(Debug, Clone)]
+pub struct StaticBackend {
+    sheets: BTreeSet<String>,
+}
+
+impl Default for StaticBackend {
+    fn default() -> Self {
+        Self { sheets: BTreeSet::from(["Sheet1".to_string()]) }
+    }
+}
+
+impl ExcelBackend for StaticBackend {
+    fn name(&self) -> &str {
+        "static"
+    }
+
+    fn workbook_id(&self) -> Option<String> {
+        Some("static".to_string())
+    }
+
+    // Sheets added here and those the run's workbook was seeded with
+    fn sheet_names(&self) -> Vec<String> {
+        let mut sheets = self.sheets.clone();
+        sheets.extend(static_engine::static_sheet_names());
+        sheets.into_iter().collect()
+    }
+
+    fn add_sheet(&mut self, name: &str) -> Result<(), String> {
+        if static_engine::static_sheet_names().iter().any(|s| s == name) || !self.sheets.insert(name.to_string()) {
+            return Err(format!("A sheet named {} already exists", name));
+        }
+        Ok(())
+    }
+
+    fn delete_sheet(&mut self, name: &str) -> Result<(), String> {
+        match self.sheets.remove(name) {
+            true => Ok(()),
+            false => Err(format!("No sheet named {} was added", name)),
+        }
+    }
+
+    fn get_cell_value(&self, sheet: &str, row: i32, col: i32) -> Result<String, String> {
+        Ok(static_engine::static_get_cell_value(sheet, row, col))
+    }
+
+    // The stores hold text as this crate wrote it, whatever the locale
+    fn get_typed_value(&self, sheet: &str, row: i32, col: i32, _locale: &CellLocale) -> Result<CellValue, String> {
+        self.get_cell_value(sheet, row, col).map(|text| CellValue::parse(&text))
+    }
+
+    fn set_cell_value(&mut self, sheet: &str, row: i32, col: i32, value: &str) -> Result<(), String> {
+        self.sheets.insert(sheet.to_string());
+        static_engine::static_replace_cell_value(sheet, row, col, value);
+        Ok(())
+    }
+
+    fn get_cell_formula(&self, sheet: &str, row: i32, col: i32) -> Result<String, String> {
+        let formula = static_engine::static_get_cell_formula(sheet, row, col);
+        if formula.is_empty() {
+            return self.get_cell_value(sheet, row, col);
+        }
+        Ok(formula)
+    }
+
+    fn set_cell_formula(&mut self, sheet: &str, row: i32, col: i32, formula: &str) -> Result<(), String> {
+        if !formula.starts_with('=') {
+            return self.set_cell_value(sheet, row, col, formula);
+        }
+        self.sheets.insert(sheet.to_string());
+        static_engine::static_set_cell_formula(sheet, row, col, formula);
+        Ok(())
+    }
+
+    fn get_number_format(&self, sheet: &str, row: i32, col: i32) -> Result<String, String> {
+        Ok(static_engine::static_get_number_format(sheet, row, col))
+    }
+
+    fn set_number_format(&mut self, sheet: &str, row: i32, col: i32, format: &str) -> Result<(), String> {
+        static_engine::static_set_number_format(sheet, row, col, format);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Embedder-style backend: values only, kept in a map
+    #[derive(Default)]
+    struct MapBackend {
+        cells: HashMap<(String, i32, i32), String>,
+        range_writes: usize,
+    }
+
+    impl ExcelBackend for MapBackend {
+        fn name(&self) -> &str {
+            "map"
+        }
+
+        fn workbook_id(&self) -> Option<String> {
+            Some("wb-map".to_string())
+        }
+
+        fn sheet_names(&self) -> Vec<String> {
+            vec!["Sheet1".to_string(), "Data".to_string()]
+        }
+
+        fn add_sheet(&mut self, name: &str) -> Result<(), String> {
+            Err(format!("cannot add {}", name))
+        }
+
+        fn get_cell_value(&self, sheet: &str, row: i32, col: i32) -> Result<String, String> {
+            Ok(self.cells.get(&(sheet.to_string(), row, col)).cloned().unwrap_or_default())
+        }
+
+        fn set_cell_value(&mut self, sheet: &str, row: i32, col: i32, value: &str) -> Result<(), String> {
+            self.cells.insert((sheet.to_string(), row, col), value.to_string());
+            Ok(())
+        }
+
+        fn set_range_values(&mut self, sheet: &str, row: i32, col: i32, values: &[Vec<String>]) -> Result<(), String> {
+            self.range_writes += 1;
+            for (r, line) in values.iter().enumerate() {
+                for (c, value) in line.iter().enumerate() {
+                    self.set_cell_value(sheet, row + r as i32, col + c as i32, value)?;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    // Outside a run the engine uses the shared workbook; tests that
+    // install a backend there hold the lock
+    #[test]
+    fn test_custom_backend_receives_cell_access() {
+        let _engine = engine::tests::lock_engine();
+        let kind = ExcelBackendKind::custom(MapBackend::default());
+        let ExcelBackendKind::Custom(shared) = &kind else { unreachable!() };
+        assert_eq!(format!("{:?}", kind), "Custom(map)");
+
+        engine::install_backend(Some(shared.clone()));
+        assert_eq!(engine::backend_name(), "map");
+        assert_eq!(engine::get_workbook_id().as_deref(), Some("wb-map"));
+
+        engine::set_cell_value("B2:C3", "7").unwrap();
+        engine::set_cell_formula("D1", "=B2*2").unwrap();
+        let sheet = engine::get_active_sheet();
+        {
+            let backend = shared.lock().unwrap();
+            assert_eq!(backend.get_cell_value(&sheet, 2, 2).unwrap(), "7");
+            // No formula support: the formula text is stored as the value
+            assert_eq!(backend.get_cell_formula(&sheet, 0, 3).unwrap(), "=B2*2");
+        }
+        assert_eq!(engine::get_cell_value("C3").unwrap(), "7");
+        assert_eq!(engine::get_number_format("C3").unwrap(), "General");
+
+        // Batched: a column of writes reaches the backend as one block when read
+        engine::set_write_batching(true).unwrap();
+        for row in 1..=3 {
+            engine::set_cell_value(&format!("F{}", row), &row.to_string()).unwrap();
+        }
+        assert!(shared.lock().unwrap().get_cell_value(&sheet, 0, 5).unwrap().is_empty());
+        assert_eq!(engine::get_cell_value("F3").unwrap(), "3");
+        let stats = engine::write_batch_stats();
+        assert_eq!((stats.cells_written, stats.backend_writes, stats.round_trips_saved()), (3, 1, 2));
+
+        // Teardown sends what is still buffered, then forgets the backend
+        engine::set_cell_value("G1", "last").unwrap();
+        engine::select_range("G1").unwrap();
+        engine::shutdown_engine().unwrap();
+        assert_eq!(shared.lock().unwrap().get_cell_value(&sheet, 0, 6).unwrap(), "last");
+        assert_eq!(engine::backend_name(), "stub");
+        assert_eq!(engine::get_workbook_id(), None);
+        assert_eq!(engine::get_selection(), engine::Selection::default());
+        assert_eq!(engine::write_batch_stats().cells_written, 0);
+    }
+
+    // A run leaves its writes in the backend and nothing in the engine,
+    // whether the embedder shuts the host down or drops the guard
+    #[test]
+    fn test_shutdown_flushes_a_run_and_resets_the_engine() {
+        use crate::ast::{AssignmentTarget, Expression, Program, Statement};
+        use crate::host::excel::ExcelHostGuard;
+        use crate::runtime_config::RuntimeConfig;
+
+        let call = |function: &str, arg: &str| Expression::FunctionCall {
+            function: Box::new(Expression::Identifier(function.into())),
+            args: vec![Expression::String(arg.into())],
+        };
+        let method = |object: Expression, name: &str| Statement::Expression(Expression::PropertyAccess {
+            obj: Box::new(object),
+            property: name.into(),
+        });
+        let set = |object: Expression, property: &str, rvalue: Expression| Statement::Assignment {
+            lvalue: AssignmentTarget::PropertyAccess { object: Box::new(object), property: property.into() },
+            rvalue,
+            rvalue_compiled: Default::default(),
+        };
+        let application = || Expression::Identifier("Application".into());
+        // Worksheets("Data").Activate
+        // Range("A1:A2").Value = "kept"
+        // Range("B2").Select
+        // Application.ScreenUpdating = False
+        // Application.DisplayAlerts = False
+        let body = vec![
+            method(call("Worksheets", "Data"), "Activate"),
+            set(call("Range", "A1:A2"), "Value", Expression::String("kept".into())),
+            method(call("Range", "B2"), "Select"),
+            set(application(), "ScreenUpdating", Expression::Boolean(false)),
+            set(application(), "DisplayAlerts", Expression::Boolean(false)),
+        ];
+        let main = Statement::Subroutine { visibility: None, name: "Main".into(), params: vec![], body: body.into() };
+        let executor = crate::vm::ProgramExecutor::new(Program { statements: vec![main], diagnostics: vec![] });
+
+        // The engine state is the context's, so this needs no engine lock
+        for use_guard in [false, true] {
+            let kind = ExcelBackendKind::custom(MapBackend::default());
+            let ExcelBackendKind::Custom(shared) = &kind else { unreachable!() };
+            let config = RuntimeConfig::builder().excel_backend(kind.clone()).write_batching(true).build();
+            let mut ctx = crate::context::Context::with_config(config);
+            let guard = use_guard.then(|| ExcelHostGuard::new(&ctx));
+            executor.execute(&mut ctx).unwrap();
+            assert!(ctx.err.is_none(), "{:?}", ctx.err);
+
+            // The run's state is still in place, and a write made after it
+            // sits in the write buffer
+            let cell = |row| shared.lock().unwrap().get_cell_value("Data", row, 0).unwrap();
+            let workbook = ctx.static_workbook.clone();
+            let _workbook = workbook.enter();
+            engine::set_cell_value("A3", "late").unwrap();
+            assert_eq!(cell(2), "");
+            assert_eq!(engine::backend_name(), "map");
+            assert_eq!(engine::get_active_sheet(), "Data");
+            assert_eq!(engine::get_selection().active_cell, "B2");
+            assert!(!engine::screen_updating());
+
+            match guard {
+                Some(guard) => drop(guard),
+                None => executor.shutdown(&mut ctx).unwrap(),
+            }
+            assert_eq!([cell(0), cell(1), cell(2)], ["kept", "kept", "late"], "guard: {}", use_guard);
+            assert_eq!(engine::backend_name(), "stub");
+            assert_eq!(engine::get_active_sheet(), "Sheet1");
+            assert_eq!(engine::get_selection(), engine::Selection::default());
+            assert_eq!(engine::write_batch_stats().cells_written, 0);
+            assert!(engine::screen_updating() && engine::display_alerts());
+        }
+    }
+
+    #[test]
+    fn test_static_backend_sheets_and_formulas() {
+        let mut backend = StaticBackend::default();
+        assert!(backend.add_sheet("BackendData").is_ok());
+        assert!(backend.add_sheet("BackendData").is_err());
+        assert_eq!(backend.sheet_names(), vec!["BackendData".to_string(), "Sheet1".to_string()]);
+
+        backend.set_cell_value("BackendData", 0, 0, "4").unwrap();
+        backend.set_cell_formula("BackendData", 0, 1, "=A1*3").unwrap();
+        assert_eq!(backend.get_cell_value("BackendData", 0, 1).unwrap(), "12");
+        assert_eq!(backend.get_cell_formula("BackendData", 0, 1).unwrap(), "=A1*3");
+        assert_eq!(backend.get_cell_formula("BackendData", 0, 0).unwrap(), "4");
+        backend.set_number_format("BackendData", 0, 1, "0.00").unwrap();
+        assert_eq!(backend.get_number_format("BackendData", 0, 1).unwrap(), "0.00");
+    }
+
+    #[test]
+    fn test_text_cells_read_back_typed() {
+        let mut backend = MapBackend::default();
+        for (col, text) in ["12", "2.5", "TRUE", "#N/A", "03/15/2024", "03/15/2024 06:00:00", "12 apples"].iter().enumerate() {
+            backend.set_cell_value("Sheet1", 0, col as i32, text).unwrap();
+        }
+        let typed = |col| backend.get_typed_value("Sheet1", 0, col, &CellLocale::default()).unwrap();
+        assert_eq!(typed(0), CellValue::Number(12.0));
+        assert_eq!(typed(2), CellValue::Boolean(true));
+        assert_eq!(typed(3), CellValue::Error(2042));
+        assert_eq!(typed(6), CellValue::Text("12 apples".to_string()));
+        assert_eq!(typed(9), CellValue::Empty);
+
+        // Numbers compare as numbers, not text ("9" > "10" as strings)
+        assert!(matches!(typed(0).to_value(false), Value::Integer(12)));
+        assert!(matches!(typed(1).to_value(false), Value::Double(n) if n == 2.5));
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        assert!(matches!(typed(4).to_value(false), Value::Date(d) if d == date));
+        assert!(matches!(typed(5).to_value(false), Value::DateTime(dt) if dt == date.and_hms_opt(6, 0, 0).unwrap()));
+        // Value2 gives the serial number
+        assert!(matches!(typed(4).to_value(true), Value::Double(n) if n == 45366.0));
+        assert!(matches!(typed(5).to_value(true), Value::Double(n) if n == 45366.25));
+        assert!(matches!(CellValue::parse("NaN"), CellValue::Text(_)));
+    }
+
+    #[test]
+    fn test_cell_text_in_workbook_locale() {
+        let german = CellLocale::for_locale("de-DE").unwrap();
+        let mut backend = MapBackend::default();
+        for (col, text) in ["1.234,5", "-0,25", "1.5", "15.03.2024", "03/15/2024"].iter().enumerate() {
+            backend.set_cell_value("Sheet1", 0, col as i32, text).unwrap();
+        }
+        let typed = |col, locale: &CellLocale| backend.get_typed_value("Sheet1", 0, col, locale).unwrap();
+        assert_eq!(typed(0, &german), CellValue::Number(1234.5));
+        assert_eq!(typed(1, &german), CellValue::Number(-0.25));
+        // Not three digits after the thousands separator: text, not 15 or 1.5
+        assert_eq!(typed(2, &german), CellValue::Text("1.5".to_string()));
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        assert_eq!(typed(3, &german), CellValue::Date(date));
+        assert_eq!(typed(4, &german), CellValue::Text("03/15/2024".to_string()));
+
+        // The opt-out reads as en-US
+        let us = CellLocale::default();
+        assert_eq!(typed(2, &us), CellValue::Number(1.5));
+        assert_eq!(typed(4, &us), CellValue::Date(date));
+        assert_eq!(CellValue::parse_in("1,234.5", &us), CellValue::Number(1234.5));
+        assert_eq!(CellValue::parse_in("15/03/2024", &CellLocale::for_locale("en-GB").unwrap()), CellValue::Date(date));
+        assert_eq!(CellValue::parse_in("2024/03/15", &CellLocale::for_locale("ja-JP").unwrap()), CellValue::Date(date));
+        assert_eq!(CellLocale::for_locale("fr-FR").map(|l| l.thousands_separator), Some(' '));
+        assert_eq!(CellLocale::for_locale("german"), None);
+
+        // The static stores hold text this crate wrote, so they ignore the locale
+        let mut store = StaticBackend::default();
+        store.set_cell_value("LocaleData", 0, 0, "2.5").unwrap();
+        assert_eq!(store.get_typed_value("LocaleData", 0, 0, &german).unwrap(), CellValue::Number(2.5));
+    }
+}
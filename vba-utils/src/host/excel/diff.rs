@@ -0,0 +1,323 @@
+// src/host/excel/diff.rs
+// ============================================================================
+// WORKBOOK DIFF - What a macro changed in a workbook
+//
+//     let before = engine::snapshot()?;
+//     executor.execute(&mut ctx)?;
+//     let after = WorkbookSnapshot::of(&ctx.static_workbook);
+//     print!("{}", engine::diff(&before, &after).to_text());
+//
+// Snapshots copy the cells (value and formula), formats, names and sheets of
+// a StaticWorkbook, the only workbook the engine can list the contents of.
+// A cell that holds nothing and a cell never written are the same, and so
+// are a default format and none.
+// ============================================================================
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fmt::Write as _;
+
+use crate::host::excel::objects::indices_to_address;
+use crate::host::excel::static_engine::{self, CellFormat, CellKey, NamedRange, StaticWorkbook};
+
+/// What a cell holds
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellState {
+    pub value: String,
+    /// A1-style formula, when the value was calculated
+    pub formula: Option<String>,
+}
+
+impl fmt::Display for CellState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.formula {
+            Some(formula) => write!(f, "{} ({})", formula, self.value),
+            None => write!(f, "{}", self.value),
+        }
+    }
+}
+
+/// Cells, formats, names and sheets of a workbook at one point of a run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkbookSnapshot {
+    sheets: Vec<String>,
+    cells: BTreeMap<CellKey, CellState>,
+    formats: BTreeMap<CellKey, CellFormat>,
+    /// Keyed by lowercase name
+    names: BTreeMap<String, NamedRange>,
+}
+
+impl WorkbookSnapshot {
+    /// Snapshot of the workbook entered on this thread (see
+    /// [`StaticWorkbook::enter`]); `engine::snapshot` flushes buffered
+    /// writes first
+    pub fn capture() -> Self {
+        let contents = static_engine::static_contents();
+        let cells: BTreeMap<CellKey, CellState> = contents
+            .cells
+            .into_iter()
+            .filter(|(_, data)| !data.value.is_empty() || data.formula.is_some())
+            .map(|(key, data)| (key, CellState { value: data.value, formula: data.formula }))
+            .collect();
+        let formats = contents.formats.into_iter().filter(|(_, format)| *format != CellFormat::default()).collect();
+        let names = contents.names.into_iter().map(|n| (n.name.to_lowercase(), n)).collect();
+
+        // Declared sheets in tab order, then any other sheet holding cells
+        let mut sheets = contents.sheets;
+        for (sheet, _, _) in cells.keys() {
+            if !sheets.iter().any(|s| s.eq_ignore_ascii_case(sheet)) {
+                sheets.push(sheet.clone());
+            }
+        }
+        Self { sheets, cells, formats, names }
+    }
+
+    /// Snapshot of `workbook`
+    pub fn of(workbook: &StaticWorkbook) -> Self {
+        let _workbook = workbook.enter();
+        Self::capture()
+    }
+
+    pub fn sheets(&self) -> &[String] {
+        &self.sheets
+    }
+
+    /// The cell at 0-based `row` and `col`, if it holds anything
+    pub fn cell(&self, sheet: &str, row: i32, col: i32) -> Option<&CellState> {
+        self.cells.get(&(sheet.to_string(), row, col))
+    }
+}
+
+/// A cell whose value or formula changed; `None` is an empty cell
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellDiff {
+    pub sheet: String,
+    pub row: i32,
+    pub col: i32,
+    pub before: Option<CellState>,
+    pub after: Option<CellState>,
+}
+
+impl CellDiff {
+    /// "Sheet1!B2"
+    pub fn address(&self) -> String {
+        format!("{}!{}", self.sheet, indices_to_address(self.row, self.col))
+    }
+}
+
+/// A cell whose format changed; a cell never formatted has the default one
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatDiff {
+    pub sheet: String,
+    pub row: i32,
+    pub col: i32,
+    pub before: CellFormat,
+    pub after: CellFormat,
+}
+
+impl FormatDiff {
+    /// "Sheet1!B2"
+    pub fn address(&self) -> String {
+        format!("{}!{}", self.sheet, indices_to_address(self.row, self.col))
+    }
+
+    /// Range properties that differ ("NumberFormat", "Font", ...)
+    pub fn changed_properties(&self) -> Vec<&'static str> {
+        let (a, b) = (&self.before, &self.after);
+        [
+            ("NumberFormat", a.number_format != b.number_format),
+            ("HorizontalAlignment", a.horizontal_alignment != b.horizontal_alignment),
+            ("VerticalAlignment", a.vertical_alignment != b.vertical_alignment),
+            ("Orientation", a.orientation != b.orientation),
+            ("WrapText", a.wrap_text != b.wrap_text),
+            ("IndentLevel", (a.add_indent, a.indent_level) != (b.add_indent, b.indent_level)),
+            ("Locked", a.locked != b.locked),
+            ("FormulaHidden", a.hidden != b.hidden),
+            ("Font", a.font != b.font),
+            ("Interior", a.interior != b.interior),
+            ("Borders", a.borders != b.borders),
+        ]
+        .into_iter()
+        .filter(|(_, changed)| *changed)
+        .map(|(property, _)| property)
+        .collect()
+    }
+}
+
+/// A name added (`before` is None), deleted (`after` is None) or pointed
+/// elsewhere
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameDiff {
+    pub name: String,
+    pub before: Option<NamedRange>,
+    pub after: Option<NamedRange>,
+}
+
+/// What changed between two snapshots, cells and formats in sheet, row,
+/// column order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkbookDiff {
+    pub added_sheets: Vec<String>,
+    pub removed_sheets: Vec<String>,
+    pub cells: Vec<CellDiff>,
+    pub formats: Vec<FormatDiff>,
+    pub names: Vec<NameDiff>,
+}
+
+impl WorkbookDiff {
+    /// See `engine::diff`
+    pub fn between(before: &WorkbookSnapshot, after: &WorkbookSnapshot) -> Self {
+        let missing = |sheets: &[String], other: &[String]| -> Vec<String> {
+            sheets.iter().filter(|s| !other.iter().any(|o| o.eq_ignore_ascii_case(s))).cloned().collect()
+        };
+
+        let keys: BTreeSet<&CellKey> = before.cells.keys().chain(after.cells.keys()).collect();
+        let cells = keys
+            .into_iter()
+            .filter(|key| before.cells.get(*key) != after.cells.get(*key))
+            .map(|key| CellDiff {
+                sheet: key.0.clone(),
+                row: key.1,
+                col: key.2,
+                before: before.cells.get(key).cloned(),
+                after: after.cells.get(key).cloned(),
+            })
+            .collect();
+
+        let keys: BTreeSet<&CellKey> = before.formats.keys().chain(after.formats.keys()).collect();
+        let formats = keys
+            .into_iter()
+            .filter(|key| before.formats.get(*key) != after.formats.get(*key))
+            .map(|key| FormatDiff {
+                sheet: key.0.clone(),
+                row: key.1,
+                col: key.2,
+                before: before.formats.get(key).cloned().unwrap_or_default(),
+                after: after.formats.get(key).cloned().unwrap_or_default(),
+            })
+            .collect();
+
+        let keys: BTreeSet<&String> = before.names.keys().chain(after.names.keys()).collect();
+        let names = keys
+            .into_iter()
+            .filter(|key| before.names.get(*key) != after.names.get(*key))
+            .map(|key| {
+                let (was, now) = (before.names.get(key).cloned(), after.names.get(key).cloned());
+                let name = now.as_ref().or(was.as_ref()).map_or_else(|| key.clone(), |n| n.name.clone());
+                NameDiff { name, before: was, after: now }
+            })
+            .collect();
+
+        Self {
+            added_sheets: missing(&after.sheets, &before.sheets),
+            removed_sheets: missing(&before.sheets, &after.sheets),
+            cells,
+            formats,
+            names,
+        }
+    }
+
+    /// Nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.added_sheets.is_empty()
+            && self.removed_sheets.is_empty()
+            && self.cells.is_empty()
+            && self.formats.is_empty()
+            && self.names.is_empty()
+    }
+
+    /// One line per change: sheets, then cells, formats and names
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for sheet in &self.added_sheets {
+            let _ = writeln!(out, "+ sheet {}", sheet);
+        }
+        for sheet in &self.removed_sheets {
+            let _ = writeln!(out, "- sheet {}", sheet);
+        }
+        for cell in &self.cells {
+            let _ = writeln!(out, "  {}: {} -> {}", cell.address(), shown(&cell.before), shown(&cell.after));
+        }
+        for format in &self.formats {
+            let _ = writeln!(out, "  {} format: {}", format.address(), format.changed_properties().join(", "));
+        }
+        for name in &self.names {
+            let _ = match (&name.before, &name.after) {
+                (None, Some(now)) => writeln!(out, "+ name {} = {}", name.name, refers_to(now)),
+                (Some(_), None) => writeln!(out, "- name {}", name.name),
+                (Some(was), Some(now)) => writeln!(out, "  name {}: {} -> {}", name.name, refers_to(was), refers_to(now)),
+                (None, None) => Ok(()),
+            };
+        }
+        out
+    }
+}
+
+fn shown(cell: &Option<CellState>) -> String {
+    cell.as_ref().map_or_else(|| "(empty)".to_string(), CellState::to_string)
+}
+
+/// "Rates!A1" or "Rates!A1:B2"
+fn refers_to(name: &NamedRange) -> String {
+    let start = indices_to_address(name.start_row, name.start_col);
+    if (name.start_row, name.start_col) == (name.end_row, name.end_col) {
+        format!("{}!{}", name.sheet_name, start)
+    } else {
+        format!("{}!{}:{}", name.sheet_name, start, indices_to_address(name.end_row, name.end_col))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::excel::engine;
+    use crate::host::excel::fixture::WorkbookFixture;
+    use crate::runtime_config::RuntimeConfig;
+
+    #[test]
+    fn test_diff_lists_what_changed() {
+        let ctx = WorkbookFixture::new()
+            .cell("A1", 42)
+            .cell("A2", "keep")
+            .cell("A3", "gone")
+            .formula("B1", "=A1*2")
+            .name("Total", "B1")
+            .context(RuntimeConfig::default())
+            .unwrap();
+        let before = WorkbookSnapshot::of(&ctx.static_workbook);
+        assert_eq!(before.cell("Sheet1", 0, 1).map(|c| c.to_string()).as_deref(), Some("=A1*2 (84)"));
+
+        {
+            let _workbook = ctx.static_workbook.enter();
+            static_engine::static_set_cell_value("Sheet1", 0, 0, "50");
+            static_engine::static_clear_contents("Sheet1", 2, 0, 2, 0);
+            static_engine::static_set_number_format("Sheet1", 0, 1, "0.00");
+            static_engine::static_add_sheet("Report");
+            static_engine::static_set_cell_value("Report", 0, 0, "done");
+            static_engine::static_create_named_range("Total", "Sheet1", 0, 1, 1, 1);
+            static_engine::static_create_named_range("Source", "Sheet1", 0, 0, 0, 0);
+        }
+        let after = WorkbookSnapshot::of(&ctx.static_workbook);
+        let diff = engine::diff(&before, &after);
+
+        assert_eq!(diff.added_sheets, ["Report"]);
+        assert!(diff.removed_sheets.is_empty());
+        let cells: Vec<String> =
+            diff.cells.iter().map(|c| format!("{}: {} -> {}", c.address(), shown(&c.before), shown(&c.after))).collect();
+        assert_eq!(
+            cells,
+            [
+                "Report!A1: (empty) -> done",
+                "Sheet1!A1: 42 -> 50",
+                "Sheet1!B1: =A1*2 (84) -> =A1*2 (100)",
+                "Sheet1!A3: gone -> (empty)",
+            ]
+        );
+        assert_eq!(diff.formats.len(), 1);
+        assert_eq!(diff.formats[0].changed_properties(), ["NumberFormat"]);
+        assert!(diff.to_text().contains("+ name Source = Sheet1!A1\n  name Total: Sheet1!B1 -> Sheet1!B1:B2\n"));
+
+        assert!(engine::diff(&after, &after).is_empty());
+        assert_eq!(engine::diff(&after, &before).removed_sheets, ["Report"]);
+    }
+}
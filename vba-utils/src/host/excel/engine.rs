@@ -2,9 +2,19 @@
 // src/host/excel/engine.rs
 use std::ffi::{CStr, CString, c_char, c_int};
 use libc::free;
-use std::sync::Mutex;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicI32, Ordering};
 use once_cell::sync::Lazy;
 
+use crate::host::excel::backend::{CellValue, ExcelBackend, SharedExcelBackend};
+use crate::host::excel::diff::{WorkbookDiff, WorkbookSnapshot};
+use crate::host::excel::locale::CellLocale;
+use crate::host::excel::formula::{self, CellRef};
+use crate::host::excel::objects::indices_to_address;
+use crate::host::excel::static_engine::{self, MAX_COL, MAX_ROW};
+use crate::host::excel::write_buffer::{WriteBatchStats, WriteBlock, WriteBuffer};
+
 #[link(name = "NativeClientEngine")]
 unsafe extern "C" {
     fn EngineInterface_Initialize(resource_path: *const c_char, local_path: *const c_char) -> bool;
@@ -16,19 +26,114 @@ unsafe extern "C" {
 
 
 
-/// Global state for the Excel engine
-static ENGINE_STATE: Lazy<Mutex<EngineState>> = Lazy::new(|| {
-    Mutex::new(EngineState {
-        initialized: false,
-        workbook_id: None,
-        active_sheet: "Sheet1".to_string(),
-    })
-});
+/// Engine state of one workbook: the active sheet, selections and window,
+/// the installed backend, its write buffer and pending async writes, the
+/// cell locale and the Application flags. Every [`StaticWorkbook`] carries
+/// one, so runs in different contexts never see each other's; the functions
+/// of this module use the one of the workbook entered on the calling thread
+/// (see [`StaticWorkbook::enter`]).
+///
+/// [`StaticWorkbook`]: crate::host::excel::static_engine::StaticWorkbook
+/// [`StaticWorkbook::enter`]: crate::host::excel::static_engine::StaticWorkbook::enter
+pub(crate) struct EngineSession {
+    state: Mutex<EngineState>,
+    /// Cell store installed by initialize_excel_host; None is stub mode, where
+    /// writes are dropped and reads are empty
+    backend: Mutex<Option<SharedExcelBackend>>,
+    /// Writes held back for coalescing (see `write_buffer`)
+    write_buffer: Mutex<WriteBuffer>,
+    /// How cell text from the backend spells numbers and dates
+    /// (RuntimeConfig::locale)
+    cell_locale: Mutex<CellLocale>,
+    application: Mutex<ApplicationFlags>,
+    /// Application.ReferenceStyle: xlA1 (1) or xlR1C1 (-4150). Kept outside
+    /// `state` because address parsing reads it while that lock is held.
+    reference_style: AtomicI32,
+    /// Writes an async backend has in flight (see `async_backend`)
+    pub(crate) async_writes: Arc<super::async_backend::PendingWrites>,
+}
+
+impl Default for EngineSession {
+    fn default() -> Self {
+        Self {
+            state: Mutex::default(),
+            backend: Mutex::default(),
+            write_buffer: Mutex::default(),
+            cell_locale: Mutex::default(),
+            application: Mutex::default(),
+            reference_style: AtomicI32::new(formula::XL_A1),
+            async_writes: Arc::default(),
+        }
+    }
+}
+
+/// Engine state of the workbook entered on this thread
+fn session() -> Arc<EngineSession> {
+    static_engine::stores().engine.clone()
+}
 
 struct EngineState {
     initialized: bool,
     workbook_id: Option<String>,
     active_sheet: String,
+    /// Selection per sheet; a sheet that was never selected on has A1
+    selections: HashMap<String, Selection>,
+    window: WindowState,
+}
+
+impl Default for EngineState {
+    fn default() -> Self {
+        Self {
+            initialized: false,
+            workbook_id: None,
+            active_sheet: "Sheet1".to_string(),
+            selections: HashMap::new(),
+            window: WindowState::default(),
+        }
+    }
+}
+
+/// Selected range and active cell of a sheet (addresses like "B2:C5", "B2")
+#[derive(Clone, Debug, PartialEq)]
+pub struct Selection {
+    pub range: String,
+    pub active_cell: String,
+}
+
+impl Default for Selection {
+    fn default() -> Self {
+        Self { range: "A1".to_string(), active_cell: "A1".to_string() }
+    }
+}
+
+/// ActiveWindow view settings (nothing is rendered; macros read them back)
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowState {
+    pub zoom: f64,
+    pub scroll_row: i32,         // 1-based, as in Excel
+    pub scroll_column: i32,
+    pub split_row: i32,
+    pub split_column: i32,
+    pub freeze_panes: bool,
+    pub display_gridlines: bool,
+    pub display_headings: bool,
+    pub window_state: i32,       // xlNormal=-4143, xlMaximized=-4137, xlMinimized=-4140
+}
+
+impl Default for WindowState {
+    fn default() -> Self {
+        Self {
+            zoom: 100.0,
+            scroll_row: 1,
+            scroll_column: 1,
+            split_row: 0,
+            split_column: 0,
+            freeze_panes: false,
+            display_gridlines: true,
+            display_headings: true,
+            window_state: -4143,
+        }
+    }
 }
 
 /// Initialize the Excel engine
@@ -53,7 +158,8 @@ pub fn initialize_engine(resource_path: &str, local_path: &str) -> Result<(), St
             
             free(workbook_ptr as *mut libc::c_void);
             
-            let mut state = ENGINE_STATE.lock().unwrap();
+            let session = session();
+            let mut state = session.state.lock().unwrap();
             state.initialized = true;
             state.workbook_id = Some(workbook_id.clone());
             
@@ -65,29 +171,116 @@ pub fn initialize_engine(resource_path: &str, local_path: &str) -> Result<(), St
     }
 }
 
+/// Counterpart of [`initialize_engine`]: send buffered and in-flight writes,
+/// drop the installed backend and put the active sheet, selections, window,
+/// cell locale and Application flags of the entered workbook back to their
+/// start-up values, so its next run starts clean. Other workbooks, and the
+/// change subscribers and confirm handler, which belong to the embedder,
+/// are not touched.
+///
+/// The native library has no release call; its workbook is forgotten and the
+/// next `initialize_engine` asks for a new one. A failed flush is reported
+/// after the state has been reset anyway.
+pub fn shutdown_engine() -> Result<(), String> {
+    let flushed = flush_writes().and_then(|()| super::async_backend::flush_blocking());
+    set_screen_updating(true);
+    set_display_alerts(true);
+    set_reference_style(formula::XL_A1);
+    install_backend(None);
+    let session = session();
+    *session.cell_locale.lock().unwrap() = CellLocale::default();
+    *session.state.lock().unwrap() = EngineState::default();
+    flushed
+}
+
 /// Check if engine is initialized
 pub fn is_initialized() -> bool {
-    ENGINE_STATE.lock().unwrap().initialized
+    session().state.lock().unwrap().initialized
 }
 
-/// Get current workbook ID
+/// Get current workbook ID (from the installed backend, if any)
 pub fn get_workbook_id() -> Option<String> {
-    ENGINE_STATE.lock().unwrap().workbook_id.clone()
+    if let Some(backend) = backend() {
+        return backend.lock().unwrap().workbook_id();
+    }
+    session().state.lock().unwrap().workbook_id.clone()
 }
 
 /// Set active sheet
 pub fn set_active_sheet(sheet_name: String) {
-    ENGINE_STATE.lock().unwrap().active_sheet = sheet_name;
+    session().state.lock().unwrap().active_sheet = sheet_name;
 }
 
 /// Get active sheet
 pub fn get_active_sheet() -> String {
-    ENGINE_STATE.lock().unwrap().active_sheet.clone()
+    session().state.lock().unwrap().active_sheet.clone()
+}
+
+/// Sheets of the workbook in tab order (the active sheet in stub mode)
+pub fn sheet_names() -> Vec<String> {
+    let names = backend().map(|b| b.lock().unwrap().sheet_names()).unwrap_or_default();
+    if names.is_empty() { vec![get_active_sheet()] } else { names }
+}
+
+// ============================================================================
+// Selection and ActiveWindow
+// ============================================================================
+
+/// Selection of the active sheet (Selection / ActiveCell)
+pub fn get_selection() -> Selection {
+    let session = session();
+    let state = session.state.lock().unwrap();
+    state.selections.get(&state.active_sheet.to_lowercase()).cloned().unwrap_or_default()
+}
+
+/// Range.Select - the range becomes the selection, its top-left cell the active cell
+pub fn select_range(address: &str) -> Result<(), String> {
+    let (row, col, end_row, end_col) = address_to_area(address)?;
+    let range = if (row, col) == (end_row, end_col) {
+        indices_to_address(row, col)
+    } else {
+        format!("{}:{}", indices_to_address(row, col), indices_to_address(end_row, end_col))
+    };
+    let session = session();
+    let mut state = session.state.lock().unwrap();
+    let sheet = state.active_sheet.to_lowercase();
+    state.selections.insert(sheet, Selection { range, active_cell: indices_to_address(row, col) });
+    Ok(())
+}
+
+/// Range.Activate - moves the active cell inside the selection, or selects the
+/// cell when it lies outside it
+pub fn activate_cell(address: &str) -> Result<(), String> {
+    let (row, col, _, _) = address_to_area(address)?;
+    let selection = get_selection();
+    let (start_row, start_col, end_row, end_col) = address_to_area(&selection.range)?;
+    if (start_row..=end_row).contains(&row) && (start_col..=end_col).contains(&col) {
+        let session = session();
+        let mut state = session.state.lock().unwrap();
+        let sheet = state.active_sheet.to_lowercase();
+        state.selections.insert(sheet, Selection { active_cell: indices_to_address(row, col), ..selection });
+        Ok(())
+    } else {
+        select_range(&indices_to_address(row, col))
+    }
+}
+
+/// ActiveWindow settings
+pub fn window_state() -> WindowState {
+    session().state.lock().unwrap().window.clone()
+}
+
+/// Modify ActiveWindow settings
+pub fn update_window(update: impl FnOnce(&mut WindowState)) {
+    update(&mut session().state.lock().unwrap().window);
 }
 
 /// Convert Excel address like "A1" to (row, col) indices
 pub fn address_to_indices(address: &str) -> Result<(i32, i32), String> {
     let address = address.trim().to_uppercase();
+    if let Some(cell) = r1c1_address(&address) {
+        return Ok((cell.row, cell.col));
+    }
     
     // Split into column letters and row number
     let col_end = address.chars().take_while(|c| c.is_alphabetic()).count();
@@ -113,69 +306,298 @@ pub fn address_to_indices(address: &str) -> Result<(i32, i32), String> {
     Ok((row_idx, col_idx))
 }
 
-/// Set cell value in the native engine
-/// Supports both single cell (A1) and range (A1:A12) addresses
-pub fn set_cell_value(address: &str, value: &str) -> Result<(), String> {
-    let state = ENGINE_STATE.lock().unwrap();
-    
-    if !state.initialized {
-        // Engine not initialized - stub mode
-        // Just log, don't store anything - cells are engine's responsibility
-        // eprintln!("⚠️  [Stub] Set {}!{} = '{}' (engine not initialized)", state.active_sheet, address, value);
+/// An absolute R1C1 cell address ("R2C3") while ReferenceStyle is xlR1C1.
+/// Relative forms (R[1]C) are only meaningful inside formulas.
+fn r1c1_address(address: &str) -> Option<CellRef> {
+    if reference_style() != formula::XL_R1C1 {
+        return None;
+    }
+    CellRef::parse_r1c1(address, (0, 0)).filter(|cell| cell.row_absolute && cell.col_absolute)
+}
+
+/// Convert "A1", "A1:C3", "2:4" (entire rows) or "C:D" (entire columns) to
+/// 0-based (start_row, start_col, end_row, end_col). `$` markers are ignored.
+pub fn address_to_area(address: &str) -> Result<(i32, i32, i32, i32), String> {
+    let address = address.replace('$', "");
+    let (first, last) = address.split_once(':').unwrap_or((&address, &address));
+    if reference_style() == formula::XL_R1C1 {
+        // "R2:R4" (entire rows) / "C3" (entire column) in R1C1 mode
+        let line = |part: &str, axis: char| {
+            let part = part.trim().to_uppercase();
+            part.strip_prefix(axis)?.parse::<i32>().ok().filter(|n| *n >= 1).map(|n| n - 1)
+        };
+        if let (Some(start), Some(end)) = (line(first, 'R'), line(last, 'R')) {
+            return Ok((start.min(end), 0, start.max(end), MAX_COL));
+        }
+        if let (Some(start), Some(end)) = (line(first, 'C'), line(last, 'C')) {
+            return Ok((0, start.min(end), MAX_ROW, start.max(end)));
+        }
+    }
+    let is_row = |part: &str| !part.is_empty() && part.trim().chars().all(|c| c.is_ascii_digit());
+    let is_col = |part: &str| !part.is_empty() && part.trim().chars().all(|c| c.is_ascii_alphabetic());
+
+    let area = if is_row(first) && is_row(last) {
+        let (start, _) = address_to_indices(&format!("A{}", first.trim()))?;
+        let (end, _) = address_to_indices(&format!("A{}", last.trim()))?;
+        (start, 0, end, MAX_COL)
+    } else if is_col(first) && is_col(last) {
+        let (_, start) = address_to_indices(&format!("{}1", first.trim()))?;
+        let (_, end) = address_to_indices(&format!("{}1", last.trim()))?;
+        (0, start, MAX_ROW, end)
+    } else {
+        let (start_row, start_col) = address_to_indices(first)?;
+        let (end_row, end_col) = address_to_indices(last)?;
+        (start_row, start_col, end_row, end_col)
+    };
+    Ok((area.0.min(area.2), area.1.min(area.3), area.0.max(area.2), area.1.max(area.3)))
+}
+
+/// Cells covered by "A1" or "A1:B2", as (row, col) indices
+fn address_cells(address: &str) -> Result<Vec<(i32, i32)>, String> {
+    let (first, last) = address.split_once(':').unwrap_or((address, address));
+    let (start_row, start_col) = address_to_indices(first)?;
+    let (end_row, end_col) = address_to_indices(last)?;
+    let mut cells = Vec::new();
+    for row in start_row.min(end_row)..=start_row.max(end_row) {
+        for col in start_col.min(end_col)..=start_col.max(end_col) {
+            cells.push((row, col));
+        }
+    }
+    Ok(cells)
+}
+
+// ============================================================================
+// Cell access through the installed backend
+// ============================================================================
+
+/// Install the backend cell reads and writes go to (None for stub mode).
+/// Unsent buffered writes are dropped and batching is turned off.
+pub fn install_backend(backend: Option<SharedExcelBackend>) {
+    let session = session();
+    *session.write_buffer.lock().unwrap() = WriteBuffer::default();
+    *session.backend.lock().unwrap() = backend;
+}
+
+/// Coalesce value writes into block writes (RuntimeConfig::write_batching)
+pub fn set_write_batching(enabled: bool) -> Result<(), String> {
+    if !enabled {
+        flush_writes()?;
+    }
+    session().write_buffer.lock().unwrap().enabled = enabled;
+    Ok(())
+}
+
+/// Read cell text in `locale` (RuntimeConfig::locale)
+pub fn set_cell_locale(locale: CellLocale) {
+    *session().cell_locale.lock().unwrap() = locale;
+}
+
+/// Write counters since the backend was installed
+pub fn write_batch_stats() -> WriteBatchStats {
+    session().write_buffer.lock().unwrap().stats
+}
+
+/// Send buffered writes to the backend
+pub fn flush_writes() -> Result<(), String> {
+    let Some(block) = session().write_buffer.lock().unwrap().take() else {
         return Ok(());
+    };
+    match backend() {
+        Some(backend) => write_block(&mut *backend.lock().unwrap(), block),
+        None => Ok(()),
     }
-    
-    let workbook_id = state.workbook_id.as_ref()
-        .ok_or("No workbook ID")?;
-    let sheet_name = &state.active_sheet;
-    
-    // Check if this is a range (contains :)
-    if address.contains(':') {
-        // It's a range - expand and set all cells
-        let parts: Vec<&str> = address.split(':').collect();
-        if parts.len() != 2 {
-            return Err(format!("Invalid range: {}", address));
+}
+
+fn write_block(backend: &mut dyn ExcelBackend, block: WriteBlock) -> Result<(), String> {
+    let writes = block.into_writes();
+    {
+        let session = session();
+        let mut buffer = session.write_buffer.lock().unwrap();
+        buffer.stats.flushes += 1;
+        buffer.stats.backend_writes += writes.len() as u64;
+    }
+    for write in writes {
+        backend.set_range_values(&write.sheet, write.row, write.col, &write.rows)?;
+    }
+    Ok(())
+}
+
+/// set_cell_value with batching on: cells go to the write buffer, and a
+/// block they do not continue is sent first
+fn buffer_cells(backend: &SharedExcelBackend, address: &str, value: &str) -> Result<(), String> {
+    let sheet_name = get_active_sheet();
+    let cells = address_cells(address)?;
+    let notify = has_change_subscribers();
+    let session = session();
+    let mut changes = Vec::new();
+    {
+        let mut backend = backend.lock().unwrap();
+        for (row, col) in cells {
+            let old_value = if notify {
+                let buffered = session.write_buffer.lock().unwrap().get(&sheet_name, row, col).map(str::to_string);
+                buffered.unwrap_or_else(|| backend.get_cell_value(&sheet_name, row, col).unwrap_or_default())
+            } else {
+                String::new()
+            };
+            let full = session.write_buffer.lock().unwrap().push(&sheet_name, row, col, value);
+            if let Some(block) = full {
+                write_block(&mut *backend, block)?;
+            }
+            changes.push((row, col, old_value));
         }
-        
-        let (start_row, start_col) = address_to_indices(parts[0])?;
-        let (end_row, end_col) = address_to_indices(parts[1])?;
-        
-        // Make sure we handle ranges in any direction
-        let min_row = start_row.min(end_row);
-        let max_row = start_row.max(end_row);
-        let min_col = start_col.min(end_col);
-        let max_col = start_col.max(end_col);
-        
-        for row in min_row..=max_row {
-            for col in min_col..=max_col {
-                unsafe {
-                    let wb_cstr = CString::new(workbook_id.as_str()).map_err(|e| e.to_string())?;
-                    let sheet_cstr = CString::new(sheet_name.as_str()).map_err(|e| e.to_string())?;
-                    let value_cstr = CString::new(value).map_err(|e| e.to_string())?;
-                    
-                    let success = EngineInterface_SetCellValue(
-                        wb_cstr.as_ptr(),
-                        sheet_cstr.as_ptr(),
-                        row,
-                        col,
-                        value_cstr.as_ptr()
-                    );
-                    
-                    if !success {
-                        return Err(format!("Failed to set cell ({}, {})", row, col));
-                    }
-                }
+    }
+    for (row, col, old_value) in changes {
+        notify_cell_changed(&sheet_name, row, col, &old_value, value);
+    }
+    Ok(())
+}
+
+/// The installed backend, if any
+pub fn backend() -> Option<SharedExcelBackend> {
+    session().backend.lock().unwrap().clone()
+}
+
+/// Name of the installed backend ("stub" when none is)
+pub fn backend_name() -> String {
+    backend().map_or_else(|| "stub".to_string(), |b| b.lock().unwrap().name().to_string())
+}
+
+/// Write every cell of "A1" or "A1:B2" on the active sheet with `write`,
+/// reporting each change to subscribers once the backend is released
+fn write_cells(
+    address: &str,
+    new_value: &str,
+    mut write: impl FnMut(&mut dyn ExcelBackend, &str, i32, i32) -> Result<(), String>,
+) -> Result<(), String> {
+    let sheet_name = get_active_sheet();
+    let cells = address_cells(address)?;
+    let notify = has_change_subscribers();
+    let mut changes = Vec::new();
+    match backend() {
+        Some(backend) => {
+            let mut backend = backend.lock().unwrap();
+            for (row, col) in cells {
+                let old_value = if notify {
+                    backend.get_cell_value(&sheet_name, row, col).unwrap_or_default()
+                } else {
+                    String::new()
+                };
+                write(&mut *backend, &sheet_name, row, col)?;
+                changes.push((row, col, old_value));
             }
         }
-        return Ok(());
+        // Stub mode - nothing is stored, subscribers still see the write
+        None => changes.extend(cells.into_iter().map(|(row, col)| (row, col, String::new()))),
     }
-    
-    // Single cell
+    if notify {
+        for (row, col, old_value) in changes {
+            notify_cell_changed(&sheet_name, row, col, &old_value, new_value);
+        }
+    }
+    Ok(())
+}
+
+/// Read one cell of the active sheet with `read` ("" in stub mode)
+fn read_cell<T: Default>(
+    address: &str,
+    read: impl FnOnce(&dyn ExcelBackend, &str, i32, i32) -> Result<T, String>,
+) -> Result<T, String> {
+    flush_writes()?;
+    let sheet_name = get_active_sheet();
+    let Some(backend) = backend() else {
+        // Engine not initialized - stub mode
+        eprintln!("⚠️  [Stub] Get {}!{} = '' (engine not initialized)", sheet_name, address);
+        return Ok(T::default());
+    };
     let (row, col) = address_to_indices(address)?;
-    
+    let backend = backend.lock().unwrap();
+    read(&*backend, &sheet_name, row, col)
+}
+
+/// Set cell value on the active sheet
+/// Supports both single cell (A1) and range (A1:A12) addresses
+pub fn set_cell_value(address: &str, value: &str) -> Result<(), String> {
+    if let Some(backend) = backend().filter(|_| session().write_buffer.lock().unwrap().enabled) {
+        return buffer_cells(&backend, address, value);
+    }
+    write_cells(address, value, |backend, sheet, row, col| backend.set_cell_value(sheet, row, col, value))
+}
+
+/// Get cell value from the active sheet
+pub fn get_cell_value(address: &str) -> Result<String, String> {
+    read_cell(address, |backend, sheet, row, col| backend.get_cell_value(sheet, row, col))
+}
+
+/// Typed value of a cell on the active sheet (Empty in stub mode)
+pub fn get_typed_value(address: &str) -> Result<CellValue, String> {
+    let locale = *session().cell_locale.lock().unwrap();
+    read_cell(address, |backend, sheet, row, col| backend.get_typed_value(sheet, row, col, &locale))
+}
+
+/// Value of a cell on any sheet by 0-based indices ("" in stub mode)
+pub fn get_sheet_cell_value(sheet: &str, row: i32, col: i32) -> Result<String, String> {
+    flush_writes()?;
+    match backend() {
+        Some(backend) => backend.lock().unwrap().get_cell_value(sheet, row, col),
+        None => Ok(String::new()),
+    }
+}
+
+/// Set a formula (A1 notation) in every cell of the address
+pub fn set_cell_formula(address: &str, formula: &str) -> Result<(), String> {
+    flush_writes()?;
+    write_cells(address, formula, |backend, sheet, row, col| backend.set_cell_formula(sheet, row, col, formula))
+}
+
+/// Formula of a cell, or its value when it holds a constant
+pub fn get_cell_formula(address: &str) -> Result<String, String> {
+    read_cell(address, |backend, sheet, row, col| backend.get_cell_formula(sheet, row, col))
+}
+
+/// Set the number format of every cell of the address
+pub fn set_number_format(address: &str, format: &str) -> Result<(), String> {
+    flush_writes()?;
+    let sheet_name = get_active_sheet();
+    let Some(backend) = backend() else {
+        return Ok(());
+    };
+    let mut backend = backend.lock().unwrap();
+    for (row, col) in address_cells(address)? {
+        backend.set_number_format(&sheet_name, row, col, format)?;
+    }
+    Ok(())
+}
+
+/// Number format of a cell ("General" in stub mode)
+pub fn get_number_format(address: &str) -> Result<String, String> {
+    if backend().is_none() {
+        return Ok("General".to_string());
+    }
+    read_cell(address, |backend, sheet, row, col| backend.get_number_format(sheet, row, col))
+}
+
+/// Cells, formats, names and sheets of the static workbook entered on this
+/// thread, once buffered writes are in (other backends cannot list their
+/// contents)
+pub fn snapshot() -> Result<WorkbookSnapshot, String> {
+    flush_writes()?;
+    Ok(WorkbookSnapshot::capture())
+}
+
+/// What changed from `before` to `after`: cells whose value or formula
+/// changed, formats, sheets and names
+pub fn diff(before: &WorkbookSnapshot, after: &WorkbookSnapshot) -> WorkbookDiff {
+    WorkbookDiff::between(before, after)
+}
+
+// ============================================================================
+// NativeClientEngine cell access (used by NativeBackend)
+// ============================================================================
+
+pub(crate) fn native_set_cell_value(workbook_id: &str, sheet_name: &str, row: i32, col: i32, value: &str) -> Result<(), String> {
     unsafe {
-        let wb_cstr = CString::new(workbook_id.as_str()).map_err(|e| e.to_string())?;
-        let sheet_cstr = CString::new(sheet_name.as_str()).map_err(|e| e.to_string())?;
+        let wb_cstr = CString::new(workbook_id).map_err(|e| e.to_string())?;
+        let sheet_cstr = CString::new(sheet_name).map_err(|e| e.to_string())?;
         let value_cstr = CString::new(value).map_err(|e| e.to_string())?;
         
         let success = EngineInterface_SetCellValue(
@@ -187,34 +609,17 @@ pub fn set_cell_value(address: &str, value: &str) -> Result<(), String> {
         );
         
         if success {
-            // eprintln!("✅ Set {}!{} = '{}'", sheet_name, address, value);
             Ok(())
         } else {
-            Err(format!("Failed to set cell {}", address))
+            Err(format!("Failed to set cell {}", indices_to_address(row, col)))
         }
     }
 }
 
-/// Get cell value from the native engine
-pub fn get_cell_value(address: &str) -> Result<String, String> {
-    let state = ENGINE_STATE.lock().unwrap();
-    let sheet_name = &state.active_sheet;
-    
-    if !state.initialized {
-        // Engine not initialized - stub mode 
-        // Return empty string - cells are engine's responsibility
-        eprintln!("⚠️  [Stub] Get {}!{} = '' (engine not initialized)", sheet_name, address);
-        return Ok(String::new());
-    }
-    
-    let workbook_id = state.workbook_id.as_ref()
-        .ok_or("No workbook ID")?;
-    
-    let (row, col) = address_to_indices(address)?;
-    
+pub(crate) fn native_get_cell_value(workbook_id: &str, sheet_name: &str, row: i32, col: i32) -> Result<String, String> {
     unsafe {
-        let wb_cstr = CString::new(workbook_id.as_str()).map_err(|e| e.to_string())?;
-        let sheet_cstr = CString::new(sheet_name.as_str()).map_err(|e| e.to_string())?;
+        let wb_cstr = CString::new(workbook_id).map_err(|e| e.to_string())?;
+        let sheet_cstr = CString::new(sheet_name).map_err(|e| e.to_string())?;
         
         let value_ptr = EngineInterface_GetCellValue(
             wb_cstr.as_ptr(),
@@ -234,14 +639,228 @@ pub fn get_cell_value(address: &str) -> Result<String, String> {
         
         free(value_ptr as *mut libc::c_void);
         
-        // eprintln!("📖 Get {}!{} = '{}'", sheet_name, address, value);
         Ok(value)
     }
 }
 
+/// A cell written by VBA, as reported to change subscribers
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellChange {
+    pub sheet: String,
+    /// A1-style address of the cell ("B3")
+    pub address: String,
+    /// 0-based row
+    pub row: i32,
+    /// 0-based column
+    pub col: i32,
+    pub old_value: String,
+    pub new_value: String,
+    /// Source line of the statement that wrote the cell (VM only)
+    pub line: Option<usize>,
+}
+
+/// Handle returned by [`subscribe_changes`], used to unsubscribe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChangeSubscription(u64);
+
+type ChangeListener = Arc<dyn Fn(&[CellChange]) + Send + Sync>;
+type ConfirmHandler = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Embedder callbacks. They are registered outside of any run and serve
+/// the runs of every workbook, so unlike the Application flags that gate
+/// them they are kept process-wide.
+static HOST_CALLBACKS: Lazy<Mutex<HostCallbacks>> = Lazy::new(|| Mutex::new(HostCallbacks::default()));
+
+#[derive(Default)]
+struct HostCallbacks {
+    subscribers: Vec<(ChangeSubscription, ChangeListener)>,
+    next_subscription: u64,
+    confirm_handler: Option<ConfirmHandler>,
+}
+
+/// Application flags of one workbook's runs
+struct ApplicationFlags {
+    display_alerts: bool,
+    screen_updating: bool,
+    /// Changes held back while ScreenUpdating is False
+    pending_changes: Vec<CellChange>,
+}
+
+impl Default for ApplicationFlags {
+    fn default() -> Self {
+        Self { display_alerts: true, screen_updating: true, pending_changes: Vec::new() }
+    }
+}
+
+thread_local! {
+    /// Line of the statement the VM is executing on this thread
+    static SOURCE_LINE: std::cell::Cell<Option<usize>> = const { std::cell::Cell::new(None) };
+}
+
+/// Record the statement being executed, attached to the changes it causes
+pub(crate) fn set_source_line(line: Option<usize>) {
+    SOURCE_LINE.with(|l| l.set(line));
+}
+
+/// Line of the statement being executed, when the VM knows it
+pub(crate) fn source_line() -> Option<usize> {
+    SOURCE_LINE.with(|l| l.get())
+}
+
+/// Subscribe to cell writes. The callback receives one change at a time
+/// normally, and one batch when ScreenUpdating is turned back on. It runs
+/// on the thread executing VBA and must not block.
+pub fn subscribe_changes(callback: impl Fn(&[CellChange]) + Send + Sync + 'static) -> ChangeSubscription {
+    let mut host = HOST_CALLBACKS.lock().unwrap();
+    host.next_subscription += 1;
+    let id = ChangeSubscription(host.next_subscription);
+    host.subscribers.push((id, Arc::new(callback)));
+    id
+}
+
+/// Subscribe through a channel instead of a callback
+pub fn subscribe_changes_channel() -> (ChangeSubscription, std::sync::mpsc::Receiver<CellChange>) {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let id = subscribe_changes(move |changes| {
+        for change in changes {
+            let _ = tx.send(change.clone());
+        }
+    });
+    (id, rx)
+}
+
+/// Stop delivering changes to a subscriber. Returns false if it was unknown.
+pub fn unsubscribe_changes(id: ChangeSubscription) -> bool {
+    let mut host = HOST_CALLBACKS.lock().unwrap();
+    let before = host.subscribers.len();
+    host.subscribers.retain(|(sub, _)| *sub != id);
+    host.subscribers.len() != before
+}
+
+/// Whether anyone is listening (lets writers skip reading old values)
+pub fn has_change_subscribers() -> bool {
+    !HOST_CALLBACKS.lock().unwrap().subscribers.is_empty()
+}
+
+/// Register the embedder's confirmation dialog (prompt → user said yes)
+pub fn set_confirm_handler(handler: impl Fn(&str) -> bool + Send + Sync + 'static) {
+    HOST_CALLBACKS.lock().unwrap().confirm_handler = Some(Arc::new(handler));
+}
+
+/// Remove every callback, and restore the entered workbook's
+/// DisplayAlerts/ScreenUpdating to True and ReferenceStyle to xlA1
+pub fn clear_host_callbacks() {
+    let session = session();
+    session.reference_style.store(formula::XL_A1, Ordering::Relaxed);
+    *session.application.lock().unwrap() = ApplicationFlags::default();
+    *HOST_CALLBACKS.lock().unwrap() = HostCallbacks::default();
+}
+
+/// Application.DisplayAlerts
+pub fn display_alerts() -> bool {
+    session().application.lock().unwrap().display_alerts
+}
+
+pub fn set_display_alerts(enabled: bool) {
+    session().application.lock().unwrap().display_alerts = enabled;
+}
+
+/// Application.ReferenceStyle: xlA1 (1) or xlR1C1 (-4150)
+pub fn reference_style() -> i32 {
+    session().reference_style.load(Ordering::Relaxed)
+}
+
+pub fn set_reference_style(style: i32) {
+    session().reference_style.store(style, Ordering::Relaxed);
+}
+
+/// Application.ScreenUpdating
+pub fn screen_updating() -> bool {
+    session().application.lock().unwrap().screen_updating
+}
+
+/// Turning ScreenUpdating back on delivers everything held back as one batch
+pub fn set_screen_updating(enabled: bool) {
+    session().application.lock().unwrap().screen_updating = enabled;
+    if enabled {
+        flush_cell_changes();
+    }
+}
+
+/// Ask the embedder to confirm an action. With DisplayAlerts = False (or no
+/// handler registered) the host is not asked and `default` is used, as Excel
+/// picks the default button.
+pub fn confirm(prompt: &str, default: bool) -> bool {
+    if !display_alerts() {
+        return default;
+    }
+    let handler = HOST_CALLBACKS.lock().unwrap().confirm_handler.clone();
+    handler.map_or(default, |h| h(prompt))
+}
+
+/// Report a cell write to subscribers (or hold it while ScreenUpdating is
+/// False)
+pub fn notify_cell_changed(sheet: &str, row: i32, col: i32, old_value: &str, new_value: &str) {
+    let Some(listeners) = listeners() else {
+        return;
+    };
+    let change = CellChange {
+        sheet: sheet.to_string(),
+        address: indices_to_address(row, col),
+        row,
+        col,
+        old_value: old_value.to_string(),
+        new_value: new_value.to_string(),
+        line: SOURCE_LINE.with(|l| l.get()),
+    };
+    {
+        let session = session();
+        let mut application = session.application.lock().unwrap();
+        application.pending_changes.push(change);
+        if !application.screen_updating {
+            return;
+        }
+    }
+    deliver(listeners);
+}
+
+/// Deliver held-back changes now, e.g. when a run ends with ScreenUpdating
+/// still False
+pub fn flush_cell_changes() {
+    deliver(listeners().unwrap_or_default());
+}
+
+/// The subscribers' callbacks, None when there are none
+fn listeners() -> Option<Vec<ChangeListener>> {
+    let host = HOST_CALLBACKS.lock().unwrap();
+    (!host.subscribers.is_empty()).then(|| host.subscribers.iter().map(|(_, l)| l.clone()).collect())
+}
+
+/// Hand the pending changes to `listeners` outside the lock, so callbacks
+/// may call back into the engine
+fn deliver(listeners: Vec<ChangeListener>) {
+    let batch = std::mem::take(&mut session().application.lock().unwrap().pending_changes);
+    if batch.is_empty() {
+        return;
+    }
+    for listener in listeners {
+        listener(&batch);
+    }
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
+    use crate::host::excel::static_engine::StaticWorkbook;
+
+    /// Held by tests that change or reset the shared workbook's engine state
+    /// (backend, selections, Application flags) or the process-wide change
+    /// subscribers, which run in parallel
+    static ENGINE_LOCK: Mutex<()> = Mutex::new(());
+
+    pub(crate) fn lock_engine() -> std::sync::MutexGuard<'static, ()> {
+        ENGINE_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
 
     #[test]
     fn test_address_parsing() {
@@ -252,5 +871,279 @@ mod tests {
         assert_eq!(address_to_indices("A10").unwrap(), (9, 0));
         assert_eq!(address_to_indices("C5").unwrap(), (4, 2));
     }
-    
-}
\ No newline at end of file
+
+    #[test]
+    fn test_area_parsing() {
+        assert_eq!(address_to_area("B2").unwrap(), (1, 1, 1, 1));
+        assert_eq!(address_to_area("$C$3:A1").unwrap(), (0, 0, 2, 2));
+        assert_eq!(address_to_area("2:4").unwrap(), (1, 0, 3, MAX_COL));
+        assert_eq!(address_to_area("C:C").unwrap(), (0, 2, MAX_ROW, 2));
+    }
+
+    // One test: the callbacks are global to the process
+    #[test]
+    fn test_display_alerts_and_screen_updating() {
+        let _engine = lock_engine();
+        set_confirm_handler(|_| false);
+        assert!(!confirm("Delete?", true));
+        set_display_alerts(false);
+        assert!(confirm("Delete?", true));
+        assert!(!confirm("Save?", false));
+        set_display_alerts(true);
+
+        let batches: Arc<Mutex<Vec<usize>>> = Arc::new(Mutex::new(Vec::new()));
+        let seen = batches.clone();
+        let id = subscribe_changes(move |changes| {
+            let mine = changes.iter().filter(|c| c.sheet == "NotifyTest").count();
+            if mine > 0 {
+                seen.lock().unwrap().push(mine);
+            }
+        });
+        let (channel, rx) = subscribe_changes_channel();
+        set_source_line(Some(7));
+        notify_cell_changed("NotifyTest", 0, 0, "", "1");
+        set_screen_updating(false);
+        notify_cell_changed("NotifyTest", 1, 0, "", "2");
+        notify_cell_changed("NotifyTest", 1, 0, "2", "3");
+        assert_eq!(*batches.lock().unwrap(), vec![1]);
+        set_screen_updating(true);
+        assert_eq!(*batches.lock().unwrap(), vec![1, 2]);
+
+        let mine: Vec<CellChange> = rx.try_iter().filter(|c| c.sheet == "NotifyTest").collect();
+        assert_eq!(mine.len(), 3);
+        assert_eq!(mine[2].address, "A2");
+        assert_eq!((mine[2].old_value.as_str(), mine[2].new_value.as_str()), ("2", "3"));
+        assert_eq!(mine[2].line, Some(7));
+
+        assert!(unsubscribe_changes(id));
+        assert!(!unsubscribe_changes(id));
+        assert!(unsubscribe_changes(channel));
+        clear_host_callbacks();
+    }
+
+    // Writes from a run reach subscribers with their sheet, address, values
+    // and the line of the statement that made them
+    #[test]
+    fn test_run_reports_direct_shifted_and_batched_writes() {
+        use crate::ast::{AssignmentTarget, Body, Expression, Program, Statement};
+        use crate::host::excel::fixture::WorkbookFixture;
+        use crate::runtime_config::RuntimeConfig;
+
+        let call = |function: &str, arg: Expression| Expression::FunctionCall {
+            function: Box::new(Expression::Identifier(function.into())),
+            args: vec![arg],
+        };
+        let member = |object: Expression, name: &str| Statement::Expression(Expression::PropertyAccess {
+            obj: Box::new(object),
+            property: name.into(),
+        });
+        let set = |object: Expression, property: &str, rvalue: Expression| Statement::Assignment {
+            lvalue: AssignmentTarget::PropertyAccess { object: Box::new(object), property: property.into() },
+            rvalue,
+            rvalue_compiled: Default::default(),
+        };
+        let range = |address: &str| call("Range", Expression::String(address.into()));
+        let screen_updating = |on| set(Expression::Identifier("Application".into()), "ScreenUpdating", Expression::Boolean(on));
+        let run = |body: Vec<(usize, Statement)>, fixture: WorkbookFixture, batching: bool| {
+            let (lines, stmts) = body.into_iter().unzip();
+            let main = Statement::Subroutine { visibility: None, name: "Main".into(), params: vec![], body: Body::new(stmts, lines) };
+            let executor = crate::vm::ProgramExecutor::new(Program { statements: vec![main], diagnostics: vec![] });
+            let config = RuntimeConfig::builder().write_batching(batching).build();
+            let mut ctx = fixture.context(config).unwrap();
+            executor.execute(&mut ctx).unwrap();
+            assert!(ctx.err.is_none(), "{:?}", ctx.err);
+            executor.shutdown(&mut ctx).unwrap();
+        };
+
+        let _engine = lock_engine();
+        let batches: Arc<Mutex<Vec<Vec<CellChange>>>> = Arc::new(Mutex::new(Vec::new()));
+        let seen = batches.clone();
+        let id = subscribe_changes(move |changes| {
+            let mine: Vec<CellChange> = changes.iter().filter(|c| c.sheet == "ChangeEvents").cloned().collect();
+            if !mine.is_empty() {
+                seen.lock().unwrap().push(mine);
+            }
+        });
+        let activate = member(call("Worksheets", Expression::String("ChangeEvents".into())), "Activate");
+        let change = |address: &str, old_value: &str, new_value: &str, line| {
+            let (row, col) = address_to_indices(address).unwrap();
+            CellChange {
+                sheet: "ChangeEvents".into(),
+                address: address.into(),
+                row,
+                col,
+                old_value: old_value.into(),
+                new_value: new_value.into(),
+                line: Some(line),
+            }
+        };
+
+        // Worksheets("ChangeEvents").Activate
+        // Range("A1").Value = 5
+        // Rows(1).Insert
+        // Application.ScreenUpdating = False
+        // Range("B1:C1").Value = 7
+        // Application.ScreenUpdating = True
+        run(vec![
+            (1, activate.clone()),
+            (2, set(range("A1"), "Value", Expression::Integer(5))),
+            (3, member(call("Rows", Expression::Integer(1)), "Insert")),
+            (4, screen_updating(false)),
+            (5, set(range("B1:C1"), "Value", Expression::Integer(7))),
+            (6, screen_updating(true)),
+        ], WorkbookFixture::new().sheet("ChangeEvents"), false);
+        {
+            let mut batches = batches.lock().unwrap();
+            assert_eq!(batches.len(), 4, "{:?}", batches);
+            assert_eq!(batches[0], vec![change("A1", "", "5", 2)]);
+            // The insert reports each cell whose value moved, in no fixed order
+            let mut shifted: Vec<CellChange> = batches[1..3].concat();
+            shifted.sort_by_key(|c| c.row);
+            assert_eq!(shifted, vec![change("A1", "5", "", 3), change("A2", "", "5", 3)]);
+            // Held back until ScreenUpdating comes back on, then one batch
+            assert_eq!(batches[3], vec![change("B1", "", "7", 5), change("C1", "", "7", 5)]);
+            batches.clear();
+        }
+
+        // With write batching the old value is read before the cell is buffered
+        // Range("D1:D2").Value = "x"
+        run(vec![(1, activate), (2, set(range("D1:D2"), "Value", Expression::String("x".into())))],
+            WorkbookFixture::new().sheet("ChangeEvents").cell("D1", "old"), true);
+        assert_eq!(*batches.lock().unwrap(), vec![vec![change("D1", "old", "x", 2)], vec![change("D2", "", "x", 2)]]);
+
+        assert!(unsubscribe_changes(id));
+        clear_host_callbacks();
+    }
+
+    // Each context carries its own engine state, so runs on two threads need
+    // no engine lock, and tearing one down leaves the other's alone
+    #[test]
+    fn test_contexts_on_two_threads_keep_separate_engines() {
+        use crate::ast::{AssignmentTarget, Expression, Program, Statement};
+        use crate::host::excel::fixture::WorkbookFixture;
+        use crate::runtime_config::RuntimeConfig;
+        use std::sync::Barrier;
+
+        fn worker(sheet: &'static str, tear_down: bool, barrier: Arc<Barrier>) -> std::thread::JoinHandle<()> {
+            std::thread::spawn(move || {
+                let call = |function: &str, arg: &str| Expression::FunctionCall {
+                    function: Box::new(Expression::Identifier(function.into())),
+                    args: vec![Expression::String(arg.into())],
+                };
+                let method = |object: Expression, name: &str| Statement::Expression(Expression::PropertyAccess {
+                    obj: Box::new(object),
+                    property: name.into(),
+                });
+                let set = |object: Expression, property: &str, rvalue: Expression| Statement::Assignment {
+                    lvalue: AssignmentTarget::PropertyAccess { object: Box::new(object), property: property.into() },
+                    rvalue,
+                    rvalue_compiled: Default::default(),
+                };
+                // Worksheets(sheet).Activate
+                // Range("A1").Value = sheet
+                // Range("B2").Select
+                // Application.DisplayAlerts = tear_down
+                let body = vec![
+                    method(call("Worksheets", sheet), "Activate"),
+                    set(call("Range", "A1"), "Value", Expression::String(sheet.into())),
+                    method(call("Range", "B2"), "Select"),
+                    set(Expression::Identifier("Application".into()), "DisplayAlerts", Expression::Boolean(tear_down)),
+                ];
+                let main = Statement::Subroutine { visibility: None, name: "Main".into(), params: vec![], body: body.into() };
+                let executor = crate::vm::ProgramExecutor::new(Program { statements: vec![main], diagnostics: vec![] });
+                let mut ctx = WorkbookFixture::new().sheet(sheet).context(RuntimeConfig::default()).unwrap();
+
+                // Both runs have finished before either is torn down
+                let ran = executor.execute(&mut ctx);
+                barrier.wait();
+                let shut_down = if tear_down { executor.shutdown(&mut ctx) } else { Ok(()) };
+                barrier.wait();
+                ran.unwrap();
+                shut_down.unwrap();
+                assert!(ctx.err.is_none(), "{:?}", ctx.err);
+
+                let _workbook = ctx.static_workbook.enter();
+                if tear_down {
+                    assert_eq!(backend_name(), "stub");
+                    assert_eq!(get_active_sheet(), "Sheet1");
+                    assert!(display_alerts());
+                } else {
+                    assert_eq!(backend_name(), "static");
+                    assert_eq!(get_active_sheet(), sheet);
+                    assert_eq!(get_selection().active_cell, "B2");
+                    assert_eq!(get_cell_value("A1").unwrap(), sheet);
+                    assert!(!display_alerts());
+                }
+            })
+        }
+
+        let barrier = Arc::new(Barrier::new(2));
+        let first = worker("First", true, barrier.clone());
+        let second = worker("Second", false, barrier);
+        first.join().unwrap();
+        second.join().unwrap();
+    }
+
+    // Each selection test enters its own workbook, whose engine state
+    // starts on Sheet1 with nothing selected
+
+    #[test]
+    fn test_selection_starts_at_a1() {
+        let _workbook = StaticWorkbook::new().enter();
+        assert_eq!(get_selection(), Selection { range: "A1".into(), active_cell: "A1".into() });
+    }
+
+    #[test]
+    fn test_select_range() {
+        let _workbook = StaticWorkbook::new().enter();
+        select_range("b2:c5").unwrap();
+        assert_eq!(get_selection(), Selection { range: "B2:C5".into(), active_cell: "B2".into() });
+        // A single-cell area is stored as a cell address
+        select_range("D4:D4").unwrap();
+        assert_eq!(get_selection(), Selection { range: "D4".into(), active_cell: "D4".into() });
+    }
+
+    #[test]
+    fn test_select_range_rejects_bad_addresses() {
+        let _workbook = StaticWorkbook::new().enter();
+        select_range("B2").unwrap();
+        assert!(select_range("not an address").is_err());
+        assert_eq!(get_selection().range, "B2");
+    }
+
+    #[test]
+    fn test_activate_cell_inside_the_selection() {
+        let _workbook = StaticWorkbook::new().enter();
+        select_range("B2:C5").unwrap();
+        activate_cell("C3").unwrap();
+        assert_eq!(get_selection(), Selection { range: "B2:C5".into(), active_cell: "C3".into() });
+    }
+
+    #[test]
+    fn test_activate_cell_outside_the_selection() {
+        let _workbook = StaticWorkbook::new().enter();
+        select_range("B2:C5").unwrap();
+        activate_cell("E1").unwrap();
+        assert_eq!(get_selection(), Selection { range: "E1".into(), active_cell: "E1".into() });
+        assert!(activate_cell("not an address").is_err());
+    }
+
+    #[test]
+    fn test_selection_is_kept_per_sheet() {
+        let _workbook = StaticWorkbook::new().enter();
+        select_range("B2").unwrap();
+        set_active_sheet("Data".into());
+        assert_eq!(get_selection().range, "A1");
+        select_range("C3").unwrap();
+        set_active_sheet("SHEET1".into());
+        assert_eq!(get_selection().range, "B2");
+    }
+
+    #[test]
+    fn test_update_window() {
+        let _workbook = StaticWorkbook::new().enter();
+        assert_eq!(window_state(), WindowState::default());
+        update_window(|w| w.zoom = 85.0);
+        assert_eq!(window_state().zoom, 85.0);
+    }
+}
@@ -0,0 +1,236 @@
+// src/host/excel/fixture.rs
+// ============================================================================
+// WORKBOOK FIXTURE - Seed a context's workbook before running a macro
+//
+//     let fixture = WorkbookFixture::new()
+//         .sheet("Sheet1")
+//         .cell("A1", 42)
+//         .formula("B1", "=A1*2");
+//     let mut ctx = fixture.context(RuntimeConfig::default())?;
+//     executor.execute(&mut ctx)?;
+//     assert_cell_eq(&ctx, "B1", 84);
+//
+// Cells go into the context's StaticWorkbook and the context is switched to
+// the Static backend, so the macro reads them through Range like any other
+// workbook. Addresses may name their sheet ("Data!A1"); without one they
+// refer to the sheet last passed to `sheet` (Sheet1 before any).
+// ============================================================================
+
+use crate::context::Context;
+use crate::host::excel::backend::{CellValue, ExcelBackendKind};
+use crate::host::excel::static_engine::{self, Area};
+use crate::host::excel::engine;
+use crate::runtime_config::RuntimeConfig;
+
+/// Sheet cells go to when neither the fixture nor the address names one
+const DEFAULT_SHEET: &str = "Sheet1";
+
+#[derive(Debug, Clone)]
+enum Seed {
+    Value(String, String, CellValue),
+    Formula(String, String, String),
+    NumberFormat(String, String, String),
+    Name(String, String, String),
+}
+
+/// Sheets, cells, formulas, number formats and names to start a run with
+#[derive(Debug, Clone, Default)]
+pub struct WorkbookFixture {
+    sheets: Vec<String>,
+    seeds: Vec<Seed>,
+}
+
+impl WorkbookFixture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a sheet (once) and send the following cells to it
+    pub fn sheet(mut self, name: &str) -> Self {
+        self.sheets.retain(|s| !s.eq_ignore_ascii_case(name));
+        self.sheets.push(name.to_string());
+        self
+    }
+
+    /// Put `value` in every cell of `address` ("A1", "A1:B3", "Data!C2")
+    pub fn cell(mut self, address: &str, value: impl Into<CellValue>) -> Self {
+        self.seeds.push(Seed::Value(self.current_sheet(), address.to_string(), value.into()));
+        self
+    }
+
+    /// Put an A1-style formula ("=A1*2") in the cell; it is calculated when
+    /// the fixture is seeded
+    pub fn formula(mut self, address: &str, formula: &str) -> Self {
+        self.seeds.push(Seed::Formula(self.current_sheet(), address.to_string(), formula.to_string()));
+        self
+    }
+
+    pub fn number_format(mut self, address: &str, format: &str) -> Self {
+        self.seeds.push(Seed::NumberFormat(self.current_sheet(), address.to_string(), format.to_string()));
+        self
+    }
+
+    /// Define a workbook-level name referring to `address`
+    pub fn name(mut self, name: &str, address: &str) -> Self {
+        self.seeds.push(Seed::Name(self.current_sheet(), address.to_string(), name.to_string()));
+        self
+    }
+
+    /// Write the fixture into `ctx`'s workbook and select the Static backend
+    /// so the run reads it. Fails on an address that does not parse.
+    pub fn seed(&self, ctx: &mut Context) -> Result<(), String> {
+        let _workbook = ctx.static_workbook.enter();
+        for sheet in &self.sheets {
+            static_engine::static_add_sheet(sheet);
+        }
+        for seed in &self.seeds {
+            match seed {
+                Seed::Value(sheet, address, value) => {
+                    let (sheet, area) = locate(sheet, address)?;
+                    for (row, col) in cells(area) {
+                        static_engine::static_replace_cell_value(&sheet, row, col, &value.to_cell_text());
+                    }
+                }
+                Seed::Formula(sheet, address, formula) => {
+                    let (sheet, area) = locate(sheet, address)?;
+                    for (row, col) in cells(area) {
+                        static_engine::static_set_cell_formula(&sheet, row, col, formula);
+                    }
+                }
+                Seed::NumberFormat(sheet, address, format) => {
+                    let (sheet, area) = locate(sheet, address)?;
+                    for (row, col) in cells(area) {
+                        static_engine::static_set_number_format(&sheet, row, col, format);
+                    }
+                }
+                Seed::Name(sheet, address, name) => {
+                    let (sheet, (row, col, end_row, end_col)) = locate(sheet, address)?;
+                    static_engine::static_create_named_range(name, &sheet, row, col, end_row, end_col);
+                }
+            }
+        }
+        ctx.runtime_config.excel_backend = ExcelBackendKind::Static;
+        Ok(())
+    }
+
+    /// A context with `config` whose workbook holds the fixture
+    pub fn context(&self, config: RuntimeConfig) -> Result<Context, String> {
+        let mut ctx = Context::with_config(config);
+        self.seed(&mut ctx)?;
+        Ok(ctx)
+    }
+
+    fn current_sheet(&self) -> String {
+        self.sheets.last().map_or(DEFAULT_SHEET, String::as_str).to_string()
+    }
+}
+
+/// The value of a cell in `ctx`'s workbook ("B1", "Data!B1"; Sheet1 when no
+/// sheet is named), typed as Range.Value reads it
+pub fn cell_value(ctx: &Context, address: &str) -> Result<CellValue, String> {
+    let (sheet, (row, col, _, _)) = locate(DEFAULT_SHEET, address)?;
+    let _workbook = ctx.static_workbook.enter();
+    Ok(CellValue::parse(&static_engine::static_get_cell_value(&sheet, row, col)))
+}
+
+/// Panic unless the cell at `address` in `ctx`'s workbook holds `expected`.
+/// Numbers compare by value, so `84`, `84.0` and a cell holding "84" match.
+#[track_caller]
+pub fn assert_cell_eq(ctx: &Context, address: &str, expected: impl Into<CellValue>) {
+    let expected = expected.into();
+    match cell_value(ctx, address) {
+        Ok(actual) if actual == expected => {}
+        Ok(actual) => panic!("cell {}: expected {:?}, found {:?}", address, expected, actual),
+        Err(e) => panic!("cell {}: {}", address, e),
+    }
+}
+
+/// Sheet and 0-based area of `address`, on `sheet` unless it names one
+fn locate(sheet: &str, address: &str) -> Result<(String, Area), String> {
+    let (sheet, address) = match address.rsplit_once('!') {
+        Some((named, address)) => (named.trim_matches('\'').to_string(), address),
+        None => (sheet.to_string(), address),
+    };
+    let area = engine::address_to_area(address).map_err(|e| format!("{}: {}", address, e))?;
+    Ok((sheet, area))
+}
+
+fn cells((row, col, end_row, end_col): Area) -> impl Iterator<Item = (i32, i32)> {
+    (row..=end_row).flat_map(move |r| (col..=end_col).map(move |c| (r, c)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{AssignmentTarget, Expression, Program, Statement};
+    use crate::host::excel::backend::ExcelBackend;
+
+    #[test]
+    fn test_fixture_seeds_context_workbook() {
+        let fixture = WorkbookFixture::new()
+            .sheet("Sheet1")
+            .cell("A1", 42)
+            .formula("B1", "=A1*2")
+            .cell("A2:A3", "x")
+            .number_format("B1", "0.00")
+            .sheet("Rates")
+            .cell("A1", 0.25)
+            .cell("Sheet1!C1", true)
+            .name("Rate", "A1");
+        let ctx = fixture.context(RuntimeConfig::default()).unwrap();
+        assert!(matches!(ctx.runtime_config.excel_backend, ExcelBackendKind::Static));
+
+        assert_cell_eq(&ctx, "B1", 84);
+        assert_cell_eq(&ctx, "Sheet1!A3", "x");
+        assert_cell_eq(&ctx, "Rates!A1", 0.25);
+        assert_cell_eq(&ctx, "C1", true);
+        assert_cell_eq(&ctx, "D1", CellValue::Empty);
+        assert!(!fixture.context(RuntimeConfig::default()).unwrap().static_workbook.ptr_eq(&ctx.static_workbook));
+
+        let _workbook = ctx.static_workbook.enter();
+        assert_eq!(static_engine::static_get_number_format("Sheet1", 0, 1), "0.00");
+        assert_eq!(static_engine::static_get_named_range("rate").map(|n| n.sheet_name), Some("Rates".to_string()));
+        let backend = crate::host::excel::backend::StaticBackend::default();
+        assert_eq!(backend.sheet_names(), vec!["Rates".to_string(), "Sheet1".to_string()]);
+
+        assert!(WorkbookFixture::new().cell("1A", 1).context(RuntimeConfig::default()).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "cell B1: expected Number(85.0), found Number(84.0)")]
+    fn test_assert_cell_eq_reports_mismatch() {
+        let ctx = WorkbookFixture::new().cell("A1", 42).formula("B1", "=A1*2").context(RuntimeConfig::default()).unwrap();
+        assert_cell_eq(&ctx, "B1", 85);
+    }
+
+    #[test]
+    fn test_macro_reads_and_writes_fixture() {
+        // Range("C1").Value = Range("B1").Value + 1
+        let range = |address: &str| Expression::FunctionCall {
+            function: Box::new(Expression::Identifier("Range".into())),
+            args: vec![Expression::String(address.into())],
+        };
+        let assign = Statement::Assignment {
+            lvalue: AssignmentTarget::PropertyAccess { object: Box::new(range("C1")), property: "Value".into() },
+            rvalue: Expression::BinaryOp {
+                left: Box::new(Expression::PropertyAccess { obj: Box::new(range("B1")), property: "Value".into() }),
+                op: "+".into(),
+                right: Box::new(Expression::Integer(1)),
+            },
+            rvalue_compiled: Default::default(),
+        };
+        let program = Program {
+            statements: vec![Statement::Subroutine { visibility: None, name: "FixtureMain".into(), params: vec![], body: vec![assign].into() }],
+            diagnostics: vec![],
+        };
+
+        let _engine = engine::tests::lock_engine();
+        let config = RuntimeConfig::builder().auto_run(crate::AutoRunPolicy::Named("FixtureMain".into())).build();
+        let mut ctx = WorkbookFixture::new().cell("A1", 42).formula("B1", "=A1*2").context(config).unwrap();
+        let executor = crate::vm::ProgramExecutor::new(program);
+        executor.execute(&mut ctx).unwrap();
+        assert_cell_eq(&ctx, "C1", 85);
+        executor.shutdown(&mut ctx).unwrap();
+        assert_cell_eq(&ctx, "C1", CellValue::Empty);
+    }
+}
@@ -0,0 +1,944 @@
+// src/host/excel/formula.rs
+// ============================================================================
+// Worksheet formula parser and evaluator for the static engine.
+//
+// Supports:
+//   - Numbers, "strings", TRUE/FALSE
+//   - Cell and area references: A1, $B$2, A1:C3, Sheet2!A1, 'My Sheet'!A1:B2
+//   - Operators: + - * / ^ & = <> < <= > >= and unary minus
+//   - Functions: SUM, AVERAGE, MIN, MAX, COUNT, COUNTA, PRODUCT, IF, AND,
+//     OR, NOT, ABS, ROUND, INT, MOD, SQRT, LEN, UPPER, LOWER, TRIM, LEFT,
+//     RIGHT, CONCATENATE, NOW, TODAY, RAND
+//
+// NOW, TODAY and RAND are volatile: they are recalculated by every
+// calculation pass, not only when a precedent changes.
+// ============================================================================
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::{Local, NaiveDate};
+
+use crate::host::excel::objects::column_index_to_letter;
+use crate::host::excel::static_engine::{MAX_COL, MAX_ROW};
+
+/// Functions whose result changes without any precedent changing
+const VOLATILE_FUNCTIONS: [&str; 3] = ["NOW", "TODAY", "RAND"];
+
+/// Result of evaluating a formula.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormulaValue {
+    Number(f64),
+    Text(String),
+    Bool(bool),
+    /// Excel error literal, e.g. `#DIV/0!`
+    Error(&'static str),
+    Blank,
+}
+
+impl FormulaValue {
+    /// Parse a stored cell value the way Excel reads a typed entry.
+    pub fn from_cell(text: &str) -> Self {
+        if text.is_empty() {
+            return FormulaValue::Blank;
+        }
+        if let Ok(n) = text.trim().parse::<f64>() {
+            return FormulaValue::Number(n);
+        }
+        match text.to_ascii_uppercase().as_str() {
+            "TRUE" => FormulaValue::Bool(true),
+            "FALSE" => FormulaValue::Bool(false),
+            _ => FormulaValue::Text(text.to_string()),
+        }
+    }
+
+    /// Text stored back into the cell.
+    pub fn to_cell_string(&self) -> String {
+        match self {
+            FormulaValue::Number(n) => format_number(*n),
+            FormulaValue::Text(s) => s.clone(),
+            FormulaValue::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+            FormulaValue::Error(e) => e.to_string(),
+            FormulaValue::Blank => String::new(),
+        }
+    }
+
+    fn as_number(&self) -> Result<f64, &'static str> {
+        match self {
+            FormulaValue::Number(n) => Ok(*n),
+            FormulaValue::Bool(b) => Ok(if *b { 1.0 } else { 0.0 }),
+            FormulaValue::Blank => Ok(0.0),
+            FormulaValue::Text(s) => s.trim().parse().map_err(|_| "#VALUE!"),
+            FormulaValue::Error(e) => Err(e),
+        }
+    }
+
+    fn as_text(&self) -> Result<String, &'static str> {
+        match self {
+            FormulaValue::Error(e) => Err(e),
+            other => Ok(other.to_cell_string()),
+        }
+    }
+
+    fn as_bool(&self) -> Result<bool, &'static str> {
+        match self {
+            FormulaValue::Bool(b) => Ok(*b),
+            FormulaValue::Text(s) if s.eq_ignore_ascii_case("true") => Ok(true),
+            FormulaValue::Text(s) if s.eq_ignore_ascii_case("false") => Ok(false),
+            FormulaValue::Text(_) => Err("#VALUE!"),
+            other => other.as_number().map(|n| n != 0.0),
+        }
+    }
+}
+
+/// Cell error literals and their `CVErr` numbers (xlErrNull, xlErrDiv0, ...)
+const CELL_ERRORS: [(&str, i32); 13] = [
+    ("#NULL!", 2000),
+    ("#DIV/0!", 2007),
+    ("#VALUE!", 2015),
+    ("#REF!", 2023),
+    ("#NAME?", 2029),
+    ("#NUM!", 2036),
+    ("#N/A", 2042),
+    ("#GETTING_DATA", 2043),
+    ("#SPILL!", 2045),
+    ("#CONNECT!", 2046),
+    ("#BLOCKED!", 2047),
+    ("#UNKNOWN!", 2048),
+    ("#CALC!", 2050),
+];
+
+/// The `CVErr` number of an error literal (`#DIV/0!` → 2007, xlErrDiv0)
+pub fn error_number(literal: &str) -> i32 {
+    cell_error(literal).unwrap_or(2042)
+}
+
+/// The `CVErr` number when a cell's text is an error literal
+pub fn cell_error(text: &str) -> Option<i32> {
+    CELL_ERRORS.iter().find(|(literal, _)| literal.eq_ignore_ascii_case(text)).map(|(_, n)| *n)
+}
+
+/// The literal a cell shows for a `CVErr` number (2042 → `#N/A`)
+pub fn error_literal(number: i32) -> Option<&'static str> {
+    CELL_ERRORS.iter().find(|(_, n)| *n == number).map(|(literal, _)| *literal)
+}
+
+fn format_number(n: f64) -> String {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        format!("{}", n as i64)
+    } else {
+        format!("{}", n)
+    }
+}
+
+/// A rectangular reference (single cells have `start == end`). Rows and
+/// columns are 0-based, like the static engine's storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AreaRef {
+    /// `None` means the formula's own sheet
+    pub sheet: Option<String>,
+    pub start: (i32, i32),
+    pub end: (i32, i32),
+}
+
+impl AreaRef {
+    pub fn contains(&self, sheet: &str, own_sheet: &str, row: i32, col: i32) -> bool {
+        let area_sheet = self.sheet.as_deref().unwrap_or(own_sheet);
+        area_sheet.eq_ignore_ascii_case(sheet)
+            && (self.start.0..=self.end.0).contains(&row)
+            && (self.start.1..=self.end.1).contains(&col)
+    }
+
+    /// Every cell in the area, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        (self.start.0..=self.end.0).flat_map(move |r| (self.start.1..=self.end.1).map(move |c| (r, c)))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Number(f64),
+    Text(String),
+    Bool(bool),
+    Ref(AreaRef),
+    Neg(Box<Expr>),
+    Binary(String, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+    /// Unknown name or unparsable text
+    Error(&'static str),
+}
+
+/// A parsed formula.
+#[derive(Debug, Clone)]
+pub struct Formula {
+    pub text: String,
+    expr: Expr,
+}
+
+impl Formula {
+    /// Parse `text` (with or without the leading `=`). Syntax errors
+    /// evaluate to `#NAME?`.
+    pub fn parse(text: &str) -> Self {
+        let body = text.strip_prefix('=').unwrap_or(text);
+        let expr = Parser::new(body).parse().unwrap_or(Expr::Error("#NAME?"));
+        Formula { text: text.to_string(), expr }
+    }
+
+    /// Cells and areas this formula reads.
+    pub fn references(&self) -> Vec<AreaRef> {
+        let mut out = Vec::new();
+        collect_refs(&self.expr, &mut out);
+        out
+    }
+
+    /// True when the formula calls NOW, TODAY or RAND.
+    pub fn is_volatile(&self) -> bool {
+        fn walk(e: &Expr) -> bool {
+            match e {
+                Expr::Call(name, args) => VOLATILE_FUNCTIONS.contains(&name.as_str()) || args.iter().any(walk),
+                Expr::Neg(inner) => walk(inner),
+                Expr::Binary(_, l, r) => walk(l) || walk(r),
+                _ => false,
+            }
+        }
+        walk(&self.expr)
+    }
+
+    /// Evaluate on `sheet`; `read(sheet, row, col)` returns a stored cell value.
+    pub fn evaluate(&self, sheet: &str, read: &mut dyn FnMut(&str, i32, i32) -> String) -> FormulaValue {
+        let mut eval = Evaluator { sheet, read };
+        match eval.scalar(&self.expr) {
+            Ok(v) => v,
+            Err(e) => FormulaValue::Error(e),
+        }
+    }
+}
+
+fn collect_refs(expr: &Expr, out: &mut Vec<AreaRef>) {
+    match expr {
+        Expr::Ref(area) => out.push(area.clone()),
+        Expr::Neg(inner) => collect_refs(inner, out),
+        Expr::Binary(_, l, r) => {
+            collect_refs(l, out);
+            collect_refs(r, out);
+        }
+        Expr::Call(_, args) => args.iter().for_each(|a| collect_refs(a, out)),
+        _ => {}
+    }
+}
+
+// ============================================================================
+// PARSER
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+    /// Function name, sheet-qualified reference or bare name
+    Word(String),
+    Op(String),
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn tokenize(src: &str) -> Option<Vec<Token>> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            ' ' | '\t' => i += 1,
+            '(' => { tokens.push(Token::LParen); i += 1; }
+            ')' => { tokens.push(Token::RParen); i += 1; }
+            ',' | ';' => { tokens.push(Token::Comma); i += 1; }
+            '"' => {
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i)? {
+                        '"' if chars.get(i + 1) == Some(&'"') => { s.push('"'); i += 2; }
+                        '"' => { i += 1; break; }
+                        ch => { s.push(*ch); i += 1; }
+                    }
+                }
+                tokens.push(Token::Text(s));
+            }
+            '0'..='9' | '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                if i < chars.len() && (chars[i] == 'E' || chars[i] == 'e') {
+                    i += 1;
+                    if i < chars.len() && (chars[i] == '+' || chars[i] == '-') {
+                        i += 1;
+                    }
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+                let text: String = chars[start..i].iter().collect();
+                tokens.push(Token::Number(text.parse().ok()?));
+            }
+            '<' | '>' => {
+                let mut op = c.to_string();
+                if let Some(&next) = chars.get(i + 1) {
+                    if next == '=' || (c == '<' && next == '>') {
+                        op.push(next);
+                        i += 1;
+                    }
+                }
+                tokens.push(Token::Op(op));
+                i += 1;
+            }
+            '+' | '-' | '*' | '/' | '^' | '&' | '=' | ':' => {
+                tokens.push(Token::Op(c.to_string()));
+                i += 1;
+            }
+            '\'' => {
+                // 'Quoted Sheet'!A1
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i)? {
+                        '\'' if chars.get(i + 1) == Some(&'\'') => { s.push('\''); i += 2; }
+                        '\'' => { i += 1; break; }
+                        ch => { s.push(*ch); i += 1; }
+                    }
+                }
+                if chars.get(i) != Some(&'!') {
+                    return None;
+                }
+                i += 1;
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '$') {
+                    i += 1;
+                }
+                let cell: String = chars[start..i].iter().collect();
+                tokens.push(Token::Word(format!("{}!{}", s, cell)));
+            }
+            c if c.is_alphabetic() || c == '$' || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || matches!(chars[i], '$' | '_' | '.' | '!')) {
+                    i += 1;
+                }
+                tokens.push(Token::Word(chars[start..i].iter().collect()));
+            }
+            _ => return None,
+        }
+    }
+    Some(tokens)
+}
+
+/// `$AB$12` → (11, 27)
+fn parse_cell(text: &str) -> Option<(i32, i32)> {
+    let text = text.replace('$', "");
+    let split = text.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = text.split_at(split);
+    if letters.is_empty() || letters.len() > 3 || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let row: i32 = digits.parse().ok()?;
+    let col = letters
+        .to_ascii_uppercase()
+        .bytes()
+        .fold(0i32, |acc, b| acc * 26 + (b - b'A' + 1) as i32);
+    (row >= 1).then_some((row - 1, col - 1))
+}
+
+/// Split `Sheet1!A1` into its sheet and cell parts.
+fn split_sheet(word: &str) -> (Option<String>, &str) {
+    match word.rsplit_once('!') {
+        Some((sheet, cell)) => (Some(sheet.to_string()), cell),
+        None => (None, word),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    valid: bool,
+}
+
+impl Parser {
+    fn new(src: &str) -> Self {
+        match tokenize(src) {
+            Some(tokens) => Parser { tokens, pos: 0, valid: true },
+            None => Parser { tokens: Vec::new(), pos: 0, valid: false },
+        }
+    }
+
+    fn parse(mut self) -> Option<Expr> {
+        if !self.valid {
+            return None;
+        }
+        let expr = self.comparison()?;
+        (self.pos == self.tokens.len()).then_some(expr)
+    }
+
+    fn peek_op(&self, ops: &[&str]) -> Option<String> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) if ops.contains(&op.as_str()) => Some(op.clone()),
+            _ => None,
+        }
+    }
+
+    fn binary(&mut self, ops: &[&str], next: fn(&mut Self) -> Option<Expr>) -> Option<Expr> {
+        let mut left = next(self)?;
+        while let Some(op) = self.peek_op(ops) {
+            self.pos += 1;
+            let right = next(self)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Some(left)
+    }
+
+    fn comparison(&mut self) -> Option<Expr> {
+        self.binary(&["=", "<>", "<", "<=", ">", ">="], Self::concat)
+    }
+
+    fn concat(&mut self) -> Option<Expr> {
+        self.binary(&["&"], Self::additive)
+    }
+
+    fn additive(&mut self) -> Option<Expr> {
+        self.binary(&["+", "-"], Self::term)
+    }
+
+    fn term(&mut self) -> Option<Expr> {
+        self.binary(&["*", "/"], Self::power)
+    }
+
+    fn power(&mut self) -> Option<Expr> {
+        self.binary(&["^"], Self::unary)
+    }
+
+    fn unary(&mut self) -> Option<Expr> {
+        match self.peek_op(&["-", "+"]) {
+            Some(op) => {
+                self.pos += 1;
+                let inner = self.unary()?;
+                Some(if op == "-" { Expr::Neg(Box::new(inner)) } else { inner })
+            }
+            None => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Option<Expr> {
+        let token = self.tokens.get(self.pos)?.clone();
+        self.pos += 1;
+        match token {
+            Token::Number(n) => Some(Expr::Number(n)),
+            Token::Text(s) => Some(Expr::Text(s)),
+            Token::LParen => {
+                let inner = self.comparison()?;
+                if self.tokens.get(self.pos) != Some(&Token::RParen) {
+                    return None;
+                }
+                self.pos += 1;
+                Some(inner)
+            }
+            Token::Word(word) => {
+                if self.tokens.get(self.pos) == Some(&Token::LParen) {
+                    self.pos += 1;
+                    return self.call(word.to_ascii_uppercase());
+                }
+                match word.to_ascii_uppercase().as_str() {
+                    "TRUE" => return Some(Expr::Bool(true)),
+                    "FALSE" => return Some(Expr::Bool(false)),
+                    _ => {}
+                }
+                let (sheet, cell) = split_sheet(&word);
+                let Some(start) = parse_cell(cell) else {
+                    return Some(Expr::Error("#NAME?"));
+                };
+                let mut end = start;
+                if self.peek_op(&[":"]).is_some() {
+                    self.pos += 1;
+                    let Some(Token::Word(other)) = self.tokens.get(self.pos).cloned() else { return None };
+                    self.pos += 1;
+                    end = parse_cell(split_sheet(&other).1)?;
+                }
+                let (start, end) = ((start.0.min(end.0), start.1.min(end.1)), (start.0.max(end.0), start.1.max(end.1)));
+                Some(Expr::Ref(AreaRef { sheet, start, end }))
+            }
+            _ => None,
+        }
+    }
+
+    fn call(&mut self, name: String) -> Option<Expr> {
+        let mut args = Vec::new();
+        if self.tokens.get(self.pos) == Some(&Token::RParen) {
+            self.pos += 1;
+            return Some(Expr::Call(name, args));
+        }
+        loop {
+            args.push(self.comparison()?);
+            match self.tokens.get(self.pos) {
+                Some(Token::Comma) => self.pos += 1,
+                Some(Token::RParen) => {
+                    self.pos += 1;
+                    return Some(Expr::Call(name, args));
+                }
+                _ => return None,
+            }
+        }
+    }
+}
+
+// ============================================================================
+// EVALUATOR
+// ============================================================================
+
+struct Evaluator<'a> {
+    sheet: &'a str,
+    read: &'a mut dyn FnMut(&str, i32, i32) -> String,
+}
+
+type Eval<T> = Result<T, &'static str>;
+
+impl Evaluator<'_> {
+    fn cell(&mut self, area: &AreaRef, row: i32, col: i32) -> FormulaValue {
+        let sheet = area.sheet.clone().unwrap_or_else(|| self.sheet.to_string());
+        FormulaValue::from_cell(&(self.read)(&sheet, row, col))
+    }
+
+    /// Values of an argument; areas expand to every cell
+    fn values(&mut self, expr: &Expr) -> Eval<Vec<FormulaValue>> {
+        match expr {
+            Expr::Ref(area) => Ok(area.cells().map(|(r, c)| self.cell(area, r, c)).collect()),
+            other => Ok(vec![self.scalar(other)?]),
+        }
+    }
+
+    /// Numbers among the arguments: cells holding text or blanks are
+    /// skipped, literal arguments must convert
+    fn numbers(&mut self, args: &[Expr]) -> Eval<Vec<f64>> {
+        let mut out = Vec::new();
+        for arg in args {
+            let from_ref = matches!(arg, Expr::Ref(_));
+            for v in self.values(arg)? {
+                match v {
+                    FormulaValue::Number(n) => out.push(n),
+                    FormulaValue::Error(e) => return Err(e),
+                    _ if from_ref => {}
+                    other => out.push(other.as_number()?),
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    fn scalar(&mut self, expr: &Expr) -> Eval<FormulaValue> {
+        match expr {
+            Expr::Number(n) => Ok(FormulaValue::Number(*n)),
+            Expr::Text(s) => Ok(FormulaValue::Text(s.clone())),
+            Expr::Bool(b) => Ok(FormulaValue::Bool(*b)),
+            Expr::Error(e) => Err(e),
+            Expr::Ref(area) if area.start == area.end => Ok(self.cell(area, area.start.0, area.start.1)),
+            Expr::Ref(_) => Err("#VALUE!"),
+            Expr::Neg(inner) => Ok(FormulaValue::Number(-self.scalar(inner)?.as_number()?)),
+            Expr::Binary(op, l, r) => {
+                let l = self.scalar(l)?;
+                let r = self.scalar(r)?;
+                binary(op, &l, &r)
+            }
+            Expr::Call(name, args) => self.call(name, args),
+        }
+    }
+
+    fn call(&mut self, name: &str, args: &[Expr]) -> Eval<FormulaValue> {
+        let num = |v: f64| Ok(FormulaValue::Number(v));
+        let arity = |min: usize, max: usize| {
+            if (min..=max).contains(&args.len()) { Ok(()) } else { Err("#VALUE!") }
+        };
+        match name {
+            "SUM" => num(self.numbers(args)?.iter().sum()),
+            "PRODUCT" => num(self.numbers(args)?.iter().product()),
+            "AVERAGE" => {
+                let n = self.numbers(args)?;
+                if n.is_empty() { Err("#DIV/0!") } else { num(n.iter().sum::<f64>() / n.len() as f64) }
+            }
+            "MIN" => num(self.numbers(args)?.into_iter().reduce(f64::min).unwrap_or(0.0)),
+            "MAX" => num(self.numbers(args)?.into_iter().reduce(f64::max).unwrap_or(0.0)),
+            "COUNT" => {
+                let mut count = 0;
+                for arg in args {
+                    count += self.values(arg)?.iter().filter(|v| matches!(v, FormulaValue::Number(_))).count();
+                }
+                num(count as f64)
+            }
+            "COUNTA" => {
+                let mut count = 0;
+                for arg in args {
+                    count += self.values(arg)?.iter().filter(|v| !matches!(v, FormulaValue::Blank)).count();
+                }
+                num(count as f64)
+            }
+            "IF" => {
+                arity(2, 3)?;
+                if self.scalar(&args[0])?.as_bool()? {
+                    self.scalar(&args[1])
+                } else if let Some(other) = args.get(2) {
+                    self.scalar(other)
+                } else {
+                    Ok(FormulaValue::Bool(false))
+                }
+            }
+            "AND" | "OR" => {
+                let mut results = Vec::new();
+                for arg in args {
+                    for v in self.values(arg)? {
+                        if !matches!(v, FormulaValue::Blank) {
+                            results.push(v.as_bool()?);
+                        }
+                    }
+                }
+                let result = if name == "AND" { results.iter().all(|b| *b) } else { results.iter().any(|b| *b) };
+                Ok(FormulaValue::Bool(result))
+            }
+            "NOT" => {
+                arity(1, 1)?;
+                Ok(FormulaValue::Bool(!self.scalar(&args[0])?.as_bool()?))
+            }
+            "ABS" | "INT" | "SQRT" => {
+                arity(1, 1)?;
+                let n = self.scalar(&args[0])?.as_number()?;
+                match name {
+                    "ABS" => num(n.abs()),
+                    "INT" => num(n.floor()),
+                    _ if n < 0.0 => Err("#NUM!"),
+                    _ => num(n.sqrt()),
+                }
+            }
+            "ROUND" => {
+                arity(2, 2)?;
+                let n = self.scalar(&args[0])?.as_number()?;
+                let digits = self.scalar(&args[1])?.as_number()? as i32;
+                let scale = 10f64.powi(digits);
+                num((n * scale).round() / scale)
+            }
+            "MOD" => {
+                arity(2, 2)?;
+                let n = self.scalar(&args[0])?.as_number()?;
+                let d = self.scalar(&args[1])?.as_number()?;
+                // Excel's MOD takes the sign of the divisor
+                if d == 0.0 { Err("#DIV/0!") } else { num(n - d * (n / d).floor()) }
+            }
+            "LEN" | "UPPER" | "LOWER" | "TRIM" => {
+                arity(1, 1)?;
+                let s = self.scalar(&args[0])?.as_text()?;
+                Ok(match name {
+                    "LEN" => FormulaValue::Number(s.chars().count() as f64),
+                    "UPPER" => FormulaValue::Text(s.to_uppercase()),
+                    "LOWER" => FormulaValue::Text(s.to_lowercase()),
+                    _ => FormulaValue::Text(s.split_whitespace().collect::<Vec<_>>().join(" ")),
+                })
+            }
+            "LEFT" | "RIGHT" => {
+                arity(1, 2)?;
+                let s = self.scalar(&args[0])?.as_text()?;
+                let n = match args.get(1) {
+                    Some(a) => self.scalar(a)?.as_number()?,
+                    None => 1.0,
+                };
+                if n < 0.0 {
+                    return Err("#VALUE!");
+                }
+                let len = s.chars().count();
+                let n = (n as usize).min(len);
+                let text = if name == "LEFT" { s.chars().take(n).collect() } else { s.chars().skip(len - n).collect() };
+                Ok(FormulaValue::Text(text))
+            }
+            "CONCATENATE" => {
+                let mut out = String::new();
+                for arg in args {
+                    out.push_str(&self.scalar(arg)?.as_text()?);
+                }
+                Ok(FormulaValue::Text(out))
+            }
+            "NOW" | "TODAY" => {
+                arity(0, 0)?;
+                let now = Local::now().naive_local();
+                let epoch = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap().and_hms_opt(0, 0, 0).unwrap();
+                let serial = (now - epoch).num_seconds() as f64 / 86400.0;
+                num(if name == "TODAY" { serial.floor() } else { serial })
+            }
+            "RAND" => {
+                arity(0, 0)?;
+                num(next_random())
+            }
+            _ => Err("#NAME?"),
+        }
+    }
+}
+
+fn binary(op: &str, l: &FormulaValue, r: &FormulaValue) -> Eval<FormulaValue> {
+    if op == "&" {
+        return Ok(FormulaValue::Text(l.as_text()? + &r.as_text()?));
+    }
+    if matches!(op, "=" | "<>" | "<" | "<=" | ">" | ">=") {
+        let ordering = match (l, r) {
+            (FormulaValue::Error(e), _) | (_, FormulaValue::Error(e)) => return Err(e),
+            (FormulaValue::Text(_), _) | (_, FormulaValue::Text(_)) => {
+                l.as_text()?.to_lowercase().cmp(&r.as_text()?.to_lowercase())
+            }
+            _ => l.as_number()?.partial_cmp(&r.as_number()?).ok_or("#NUM!")?,
+        };
+        let result = match op {
+            "=" => ordering.is_eq(),
+            "<>" => ordering.is_ne(),
+            "<" => ordering.is_lt(),
+            "<=" => ordering.is_le(),
+            ">" => ordering.is_gt(),
+            _ => ordering.is_ge(),
+        };
+        return Ok(FormulaValue::Bool(result));
+    }
+    let (a, b) = (l.as_number()?, r.as_number()?);
+    let n = match op {
+        "+" => a + b,
+        "-" => a - b,
+        "*" => a * b,
+        "/" if b == 0.0 => return Err("#DIV/0!"),
+        "/" => a / b,
+        _ => a.powf(b),
+    };
+    if n.is_finite() { Ok(FormulaValue::Number(n)) } else { Err("#NUM!") }
+}
+
+/// xorshift64*, seeded from the clock on first use
+fn next_random() -> f64 {
+    static STATE: AtomicU64 = AtomicU64::new(0);
+    let mut x = STATE.load(Ordering::Relaxed);
+    if x == 0 {
+        x = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0x9E37_79B9_7F4A_7C15)
+            | 1;
+    }
+    x ^= x >> 12;
+    x ^= x << 25;
+    x ^= x >> 27;
+    STATE.store(x, Ordering::Relaxed);
+    (x.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+// ============================================================================
+// REFERENCE STYLE CONVERSION
+// ============================================================================
+
+/// XlReferenceStyle: xlA1 = 1, xlR1C1 = -4150
+pub const XL_A1: i32 = 1;
+pub const XL_R1C1: i32 = -4150;
+
+/// One cell reference with its `$` (A1) / bracket-free (R1C1) markers.
+/// Rows and columns are 0-based and absolute, whatever the notation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellRef {
+    pub row: i32,
+    pub col: i32,
+    pub row_absolute: bool,
+    pub col_absolute: bool,
+}
+
+impl CellRef {
+    /// `B$3` → row 2 (absolute), column 1 (relative)
+    pub fn parse_a1(text: &str) -> Option<Self> {
+        let col_absolute = text.starts_with('$');
+        let rest = text.trim_start_matches('$');
+        let split = rest.find(|c: char| c == '$' || c.is_ascii_digit())?;
+        let row_absolute = rest[split..].starts_with('$');
+        let (row, col) = parse_cell(rest)?;
+        let valid = row <= MAX_ROW && col <= MAX_COL && rest[split..].trim_start_matches('$').chars().all(|c| c.is_ascii_digit());
+        valid.then_some(Self { row, col, row_absolute, col_absolute })
+    }
+
+    /// `R2C[-1]` relative to `base` (0-based row, col); a bare `R` or `C`
+    /// part is the base row/column
+    pub fn parse_r1c1(text: &str, base: (i32, i32)) -> Option<Self> {
+        let upper = text.to_ascii_uppercase();
+        let rest = upper.strip_prefix('R')?;
+        let c_at = rest.find('C')?;
+        let (row, row_absolute) = r1c1_part(&rest[..c_at], base.0)?;
+        let (col, col_absolute) = r1c1_part(&rest[c_at + 1..], base.1)?;
+        let valid = (0..=MAX_ROW).contains(&row) && (0..=MAX_COL).contains(&col);
+        valid.then_some(Self { row, col, row_absolute, col_absolute })
+    }
+
+    pub fn to_a1(self) -> String {
+        format!(
+            "{}{}{}{}",
+            if self.col_absolute { "$" } else { "" },
+            column_index_to_letter(self.col),
+            if self.row_absolute { "$" } else { "" },
+            self.row + 1
+        )
+    }
+
+    pub fn to_r1c1(self, base: (i32, i32)) -> String {
+        let part = |axis: char, value: i32, absolute: bool, base: i32| match (absolute, value - base) {
+            (true, _) => format!("{}{}", axis, value + 1),
+            (false, 0) => axis.to_string(),
+            (false, offset) => format!("{}[{}]", axis, offset),
+        };
+        part('R', self.row, self.row_absolute, base.0) + &part('C', self.col, self.col_absolute, base.1)
+    }
+
+    /// Apply XlReferenceType: xlAbsolute(1), xlAbsRowRelColumn(2),
+    /// xlRelRowAbsColumn(3), xlRelative(4)
+    pub fn with_reference_type(self, reference_type: i32) -> Self {
+        let (row_absolute, col_absolute) = match reference_type {
+            1 => (true, true),
+            2 => (true, false),
+            3 => (false, true),
+            4 => (false, false),
+            _ => (self.row_absolute, self.col_absolute),
+        };
+        Self { row_absolute, col_absolute, ..self }
+    }
+}
+
+/// `2` → (1, true), `[-1]` → (base - 1, false), `` → (base, false)
+fn r1c1_part(text: &str, base: i32) -> Option<(i32, bool)> {
+    if text.is_empty() {
+        Some((base, false))
+    } else if let Some(offset) = text.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        Some((base + offset.parse::<i32>().ok()?, false))
+    } else {
+        let n: i32 = text.parse().ok()?;
+        (n >= 1).then_some((n - 1, true))
+    }
+}
+
+/// Application.ConvertFormula: rewrite every cell reference in `formula`
+/// from one reference style to the other (or the same style, to change
+/// `$` markers with `reference_type`). Relative references are resolved
+/// against `base`, the RelativeTo cell. String literals and function names
+/// are left alone. Returns None if a reference can't be converted.
+pub fn convert_formula(formula: &str, from: i32, to: i32, reference_type: Option<i32>, base: (i32, i32)) -> Option<String> {
+    let chars: Vec<char> = formula.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '"' || c == '\'' {
+            // Copy "text" and 'Sheet Name' verbatim (doubled quotes included)
+            let end = (i + 1..chars.len()).find(|&j| chars[j] == c && chars.get(j + 1) != Some(&c)).unwrap_or(chars.len() - 1);
+            out.extend(&chars[i..=end]);
+            i = end + 1;
+            continue;
+        }
+        if !(c.is_ascii_alphabetic() || c == '$') {
+            out.push(c);
+            i += 1;
+            continue;
+        }
+        // A word: letters, digits, $, _, . and R1C1 [offsets]
+        let start = i;
+        while i < chars.len() {
+            if chars[i] == '[' {
+                match (i..chars.len()).find(|&j| chars[j] == ']') {
+                    Some(close) => i = close + 1,
+                    None => return None,
+                }
+            } else if chars[i].is_ascii_alphanumeric() || matches!(chars[i], '$' | '_' | '.') {
+                i += 1;
+            } else {
+                break;
+            }
+        }
+        let word: String = chars[start..i].iter().collect();
+        let is_function = chars[i..].iter().find(|c| !c.is_whitespace()) == Some(&'(');
+        let is_sheet = chars.get(i) == Some(&'!');
+        let cell = match from {
+            _ if is_function || is_sheet => None,
+            XL_R1C1 => CellRef::parse_r1c1(&word, base),
+            _ => CellRef::parse_a1(&word),
+        };
+        match cell {
+            Some(cell) => {
+                let cell = cell.with_reference_type(reference_type.unwrap_or(0));
+                out.push_str(&if to == XL_R1C1 { cell.to_r1c1(base) } else { cell.to_a1() });
+            }
+            None => out.push_str(&word),
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(formula: &str, cells: &[(&str, i32, i32, &str)]) -> FormulaValue {
+        let mut read = |sheet: &str, row: i32, col: i32| {
+            cells
+                .iter()
+                .find(|(s, r, c, _)| *s == sheet && *r == row && *c == col)
+                .map(|(_, _, _, v)| v.to_string())
+                .unwrap_or_default()
+        };
+        Formula::parse(formula).evaluate("Sheet1", &mut read)
+    }
+
+    const CELLS: [(&str, i32, i32, &str); 4] = [("Sheet1", 0, 0, "2"), ("Sheet1", 1, 0, "3"), ("Sheet1", 2, 0, "x"), ("Data", 0, 1, "10")];
+
+    #[test]
+    fn test_operator_precedence() {
+        assert_eq!(eval("=A1+A2*2^2", &CELLS), FormulaValue::Number(14.0));
+        assert_eq!(eval("=-A1+ROUND(2.567, 1)*10", &CELLS).to_cell_string(), "24");
+    }
+
+    #[test]
+    fn test_sum_over_ranges_and_other_sheets() {
+        // The text in A3 is skipped, as in Excel
+        assert_eq!(eval("=SUM(A1:A3, Data!B1)", &CELLS), FormulaValue::Number(15.0));
+    }
+
+    #[test]
+    fn test_if_and_concatenation() {
+        assert_eq!(eval("=IF(A2>A1,\"up\",\"down\")&\"!\"", &CELLS), FormulaValue::Text("up!".into()));
+    }
+
+    #[test]
+    fn test_division_by_zero() {
+        assert_eq!(eval("=A1/0", &CELLS), FormulaValue::Error("#DIV/0!"));
+    }
+
+    #[test]
+    fn test_arithmetic_on_text() {
+        assert_eq!(eval("=A3+1", &CELLS), FormulaValue::Error("#VALUE!"));
+    }
+
+    #[test]
+    fn test_unknown_function() {
+        assert_eq!(eval("=NOPE(1)", &CELLS), FormulaValue::Error("#NAME?"));
+    }
+
+    #[test]
+    fn test_references() {
+        let refs = Formula::parse("=SUM($A$1:B2)+'My Sheet'!C3").references();
+        assert_eq!(refs[0], AreaRef { sheet: None, start: (0, 0), end: (1, 1) });
+        assert_eq!(refs[1], AreaRef { sheet: Some("My Sheet".into()), start: (2, 2), end: (2, 2) });
+    }
+
+    #[test]
+    fn test_volatility() {
+        assert!(!Formula::parse("=SUM(A1:B2)").is_volatile());
+        assert!(Formula::parse("=A1*RAND()").is_volatile());
+    }
+
+    #[test]
+    fn test_convert_formula() {
+        // Relative to C3 (row 2, column 2)
+        let base = (2, 2);
+        assert_eq!(convert_formula("=A1+$B$2*C$3", XL_A1, XL_R1C1, None, base).unwrap(), "=R[-2]C[-2]+R2C2*R3C");
+        assert_eq!(convert_formula("=SUM(R[-2]C:RC[1])&\"R1C1\"", XL_R1C1, XL_A1, None, base).unwrap(), "=SUM(C1:D3)&\"R1C1\"");
+        assert_eq!(convert_formula("=LOG10(A1)+'My Sheet'!B2", XL_A1, XL_A1, Some(1), base).unwrap(), "=LOG10($A$1)+'My Sheet'!$B$2");
+        assert_eq!(CellRef::parse_r1c1("R[-5]C", base), None);
+    }
+}
@@ -0,0 +1,132 @@
+// src/host/excel/locale.rs
+// ============================================================================
+// CELL LOCALE - How a backend's cell text spells numbers and dates
+//
+// A backend that only hands back text shows numbers and dates the way the
+// workbook's locale displays them: "1.234,5" and "15.03.2024" in de-DE,
+// "1,234.5" and "03/15/2024" in en-US. CellValue::parse_in reads them with
+// the CellLocale chosen from RuntimeConfig::locale (see
+// RuntimeConfig::locale_cell_parsing to read every backend as en-US).
+// ============================================================================
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+/// Order of day, month and year in a short date
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateOrder {
+    /// 03/15/2024
+    MonthDayYear,
+    /// 15/03/2024, 15.03.2024
+    DayMonthYear,
+    /// 2024/03/15
+    YearMonthDay,
+}
+
+/// Number and date spelling of a locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellLocale {
+    pub decimal_separator: char,
+    pub thousands_separator: char,
+    pub date_order: DateOrder,
+}
+
+impl Default for CellLocale {
+    /// en-US, the spelling this crate writes cells in
+    fn default() -> Self {
+        Self { decimal_separator: '.', thousands_separator: ',', date_order: DateOrder::MonthDayYear }
+    }
+}
+
+/// Languages that write 1.234,5 (or 1 234,5)
+const DECIMAL_COMMA_LANGUAGES: &[&str] = &[
+    "da", "de", "es", "fi", "fr", "id", "it", "nb", "nl", "pl", "pt", "ru", "sv", "tr", "uk",
+];
+
+/// Languages whose short dates start with the year
+const YEAR_FIRST_LANGUAGES: &[&str] = &["ja", "ko", "zh", "hu", "lt"];
+
+impl CellLocale {
+    /// Spelling for a locale name ("de-DE", "en_GB", "fr"); None for a
+    /// name that is not language[-REGION]
+    pub fn for_locale(name: &str) -> Option<Self> {
+        let mut parts = name.split(['-', '_']);
+        let language = parts.next()?.to_ascii_lowercase();
+        let region = parts.next().map(str::to_ascii_uppercase);
+        if !(2..=3).contains(&language.len()) || !language.chars().all(|c| c.is_ascii_lowercase()) {
+            return None;
+        }
+        if region.as_deref().is_some_and(|r| r.len() != 2 || !r.chars().all(|c| c.is_ascii_uppercase())) {
+            return None;
+        }
+        if parts.next().is_some() {
+            return None;
+        }
+
+        let mut locale = Self::default();
+        if DECIMAL_COMMA_LANGUAGES.contains(&language.as_str()) && region.as_deref() != Some("CH") {
+            locale.decimal_separator = ',';
+            locale.thousands_separator = if language == "fr" || language == "ru" || language == "sv" { ' ' } else { '.' };
+        }
+        if region.as_deref() == Some("CH") {
+            locale.thousands_separator = '\'';
+        }
+        locale.date_order = if YEAR_FIRST_LANGUAGES.contains(&language.as_str()) {
+            DateOrder::YearMonthDay
+        } else if language == "en" && matches!(region.as_deref(), None | Some("US") | Some("PH")) {
+            DateOrder::MonthDayYear
+        } else {
+            DateOrder::DayMonthYear
+        };
+        Some(locale)
+    }
+
+    /// A number written in this locale: "1.234,5" in de-DE. Thousands
+    /// separators must fall every three digits, so "1.5" is not a number
+    /// there.
+    pub fn parse_number(&self, text: &str) -> Option<f64> {
+        let (sign, digits) = match text.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", text),
+        };
+        let (whole, fraction) = match digits.split_once(self.decimal_separator) {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (digits, None),
+        };
+        let groups: Vec<&str> = whole.split(self.thousands_separator).collect();
+        let grouped = groups.len() > 1;
+        if grouped && (groups[0].is_empty() || groups[0].len() > 3 || groups[1..].iter().any(|g| g.len() != 3)) {
+            return None;
+        }
+        let whole = groups.concat();
+        let plain = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+        if !plain(&whole) || fraction.is_some_and(|f| !plain(f)) || (whole.is_empty() && fraction.is_none_or(str::is_empty)) {
+            return None;
+        }
+        format!("{}{}.{}", sign, whole, fraction.unwrap_or("0")).parse().ok()
+    }
+
+    /// A short date, optionally followed by h:mm[:ss], in this locale's
+    /// order; ISO yyyy-mm-dd is read everywhere
+    pub fn parse_date(&self, text: &str) -> Option<NaiveDateTime> {
+        let day_first = match self.date_order {
+            DateOrder::MonthDayYear => ["%m/%d/%Y", "%m-%d-%Y"],
+            DateOrder::DayMonthYear => ["%d/%m/%Y", "%d.%m.%Y"],
+            DateOrder::YearMonthDay => ["%Y/%m/%d", "%Y.%m.%d"],
+        };
+        let (date, time) = match text.split_once(' ') {
+            Some((date, time)) => (date, Some(time.trim())),
+            None => (text, None),
+        };
+        let date = day_first
+            .iter()
+            .chain(&["%Y-%m-%d"])
+            .find_map(|format| NaiveDate::parse_from_str(date, format).ok())?;
+        match time {
+            None => date.and_hms_opt(0, 0, 0),
+            Some(time) => ["%H:%M:%S", "%H:%M"]
+                .iter()
+                .find_map(|format| chrono::NaiveTime::parse_from_str(time, format).ok())
+                .map(|time| date.and_time(time)),
+        }
+    }
+}
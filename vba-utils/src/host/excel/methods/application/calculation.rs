@@ -1,18 +1,471 @@
 // Calculation and formula evaluation methods
+//
+// Formulas stored in the static engine are tracked in a dependency graph
+// (formula cell → the areas it reads). Writing a value or formula either
+// recalculates the affected cells right away (xlCalculationAutomatic) or
+// marks them dirty until Calculate (xlCalculationManual).
+//
+// - Calculate: dirty cells, their dependents and volatile formulas
+// - CalculateFull: every formula, in dependency order
+// - Volatile: accepted; static-engine formulas cannot call VBA functions,
+//   so only NOW/TODAY/RAND make a formula volatile
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use anyhow::Result;
+
 use crate::context::Value;
+use crate::host::excel::formula::{AreaRef, Formula};
+use crate::host::excel::{engine, static_engine};
+use crate::interpreter::builtins::constants;
+use crate::symbol::lower;
 
 pub fn call_method(method: &str, _args: &[Value]) -> Result<Value> {
-    match method.to_lowercase().as_str() {
+    // Recalculation reads cells the write buffer may still hold
+    engine::flush_writes().map_err(|e| anyhow::anyhow!("{}", e))?;
+    match &*lower(method) {
         "calculate" => {
             eprintln!("🧮 Application.Calculate() - recalculating all open workbooks");
+            calculate();
             Ok(Value::Empty)
         }
         "calculatefull" => {
             eprintln!("🧮 Application.CalculateFull() - full recalculation (forces rebuild)");
+            calculate_full();
+            Ok(Value::Empty)
+        }
+        "volatile" => {
+            eprintln!("🧮 Application.Volatile() - no effect outside worksheet UDFs");
             Ok(Value::Empty)
         }
         _ => Err(anyhow::anyhow!("Unknown calculation method: {}", method)),
     }
 }
+
+// ============================================================================
+// CALCULATION MODE
+// ============================================================================
+
+/// Application.Calculation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CalculationMode {
+    #[default]
+    Automatic,
+    Manual,
+    /// Treated like Automatic (the static engine has no data tables)
+    SemiAutomatic,
+}
+
+impl CalculationMode {
+    /// Accepts the xlCalculation* constants or their names.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let code = match value {
+            Value::Integer(n) | Value::LongLong(n) => *n,
+            Value::Long(n) => *n as i64,
+            Value::Double(n) => *n as i64,
+            Value::String(s) => {
+                let name = s.to_ascii_lowercase();
+                return match name.trim_start_matches("xlcalculation") {
+                    "automatic" => Some(CalculationMode::Automatic),
+                    "manual" => Some(CalculationMode::Manual),
+                    "semiautomatic" => Some(CalculationMode::SemiAutomatic),
+                    _ => None,
+                };
+            }
+            _ => return None,
+        };
+        match code {
+            constants::xlCalculationAutomatic => Some(CalculationMode::Automatic),
+            constants::xlCalculationManual => Some(CalculationMode::Manual),
+            constants::xlCalculationSemiautomatic => Some(CalculationMode::SemiAutomatic),
+            _ => None,
+        }
+    }
+
+    /// The xlCalculation* constant
+    pub fn code(self) -> i64 {
+        match self {
+            CalculationMode::Automatic => constants::xlCalculationAutomatic,
+            CalculationMode::Manual => constants::xlCalculationManual,
+            CalculationMode::SemiAutomatic => constants::xlCalculationSemiautomatic,
+        }
+    }
+}
+
+/// (sheet, 0-based row, 0-based column)
+pub type CellKey = (String, i32, i32);
+
+/// Kept per workbook in `static_engine`
+#[derive(Default)]
+pub(crate) struct CalcState {
+    mode: CalculationMode,
+    /// Formula cells, keyed by location
+    formulas: BTreeMap<CellKey, Formula>,
+    /// Cells changed since the last calculation (Manual mode)
+    dirty: BTreeSet<CellKey>,
+}
+
+impl CalcState {
+    /// Formula cells that read `cell` directly.
+    fn direct_dependents(&self, cell: &CellKey) -> Vec<CellKey> {
+        self.formulas
+            .iter()
+            .filter(|(key, f)| f.references().iter().any(|a| a.contains(&cell.0, &key.0, cell.1, cell.2)))
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    /// Formula cells `cell` reads directly.
+    fn direct_precedents(&self, cell: &CellKey) -> Vec<CellKey> {
+        let Some(formula) = self.formulas.get(cell) else { return Vec::new() };
+        let mut out = Vec::new();
+        for area in formula.references() {
+            let sheet = area.sheet.clone().unwrap_or_else(|| cell.0.clone());
+            out.extend(area.cells().map(|(r, c)| (sheet.clone(), r, c)));
+        }
+        out
+    }
+
+    /// `seeds` that hold formulas, plus everything depending on any seed.
+    fn affected(&self, seeds: impl IntoIterator<Item = CellKey>) -> BTreeSet<CellKey> {
+        let mut out = BTreeSet::new();
+        let mut queue: Vec<CellKey> = seeds.into_iter().collect();
+        let mut seen: BTreeSet<CellKey> = queue.iter().cloned().collect();
+        while let Some(cell) = queue.pop() {
+            if self.formulas.contains_key(&cell) {
+                out.insert(cell.clone());
+            }
+            for dep in self.direct_dependents(&cell) {
+                if seen.insert(dep.clone()) {
+                    queue.push(dep);
+                }
+            }
+        }
+        out
+    }
+
+    /// `cells` ordered so every formula comes after the formulas it reads.
+    /// Cells on a circular reference come last, in address order.
+    fn order(&self, cells: &BTreeSet<CellKey>) -> Vec<CellKey> {
+        let mut pending: HashMap<&CellKey, usize> = HashMap::new();
+        let mut readers: HashMap<CellKey, Vec<&CellKey>> = HashMap::new();
+        for cell in cells {
+            let inputs: BTreeSet<CellKey> =
+                self.direct_precedents(cell).into_iter().filter(|p| cells.contains(p) && p != cell).collect();
+            pending.insert(cell, inputs.len());
+            for input in inputs {
+                readers.entry(input).or_default().push(cell);
+            }
+        }
+
+        let mut ready: Vec<&CellKey> = cells.iter().filter(|c| pending[c] == 0).collect();
+        ready.reverse();
+        let mut order = Vec::with_capacity(cells.len());
+        while let Some(cell) = ready.pop() {
+            order.push(cell.clone());
+            for reader in readers.get(cell).into_iter().flatten() {
+                let count = pending.get_mut(reader).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    ready.push(reader);
+                }
+            }
+        }
+
+        if order.len() < cells.len() {
+            let circular: Vec<CellKey> = cells.iter().filter(|c| !order.contains(c)).cloned().collect();
+            eprintln!("⚠️  Circular reference involving {} cell(s)", circular.len());
+            order.extend(circular);
+        }
+        order
+    }
+
+    fn volatile_cells(&self) -> impl Iterator<Item = CellKey> + '_ {
+        self.formulas.iter().filter(|(_, f)| f.is_volatile()).map(|(k, _)| k.clone())
+    }
+}
+
+pub fn calculation_mode() -> CalculationMode {
+    static_engine::stores().calc.lock().unwrap().mode
+}
+
+/// Switching back to Automatic recalculates whatever changed while Manual.
+pub fn set_calculation_mode(mode: CalculationMode) {
+    let previous = std::mem::replace(&mut static_engine::stores().calc.lock().unwrap().mode, mode);
+    if previous == CalculationMode::Manual && mode != CalculationMode::Manual {
+        calculate();
+    }
+}
+
+/// Forget every formula and reset the mode to Automatic.
+pub fn reset() {
+    let workbook = static_engine::stores();
+    let mut state = workbook.calc.lock().unwrap();
+    state.mode = CalculationMode::Automatic;
+    state.formulas.clear();
+    state.dirty.clear();
+}
+
+// ============================================================================
+// CHANGE NOTIFICATIONS (from the static engine)
+// ============================================================================
+
+/// A cell's formula was set (`Some`) or replaced by a plain value (`None`).
+pub fn formula_changed(sheet: &str, row: i32, col: i32, formula: Option<&str>) {
+    let key = (sheet.to_string(), row, col);
+    {
+        let workbook = static_engine::stores();
+        let mut state = workbook.calc.lock().unwrap();
+        match formula {
+            Some(text) => state.formulas.insert(key.clone(), Formula::parse(text)),
+            None => state.formulas.remove(&key),
+        };
+    }
+    changed(key);
+}
+
+/// A plain value was written to a cell.
+pub fn value_changed(sheet: &str, row: i32, col: i32) {
+    let key = (sheet.to_string(), row, col);
+    let has_dependents = {
+        let workbook = static_engine::stores();
+        let state = workbook.calc.lock().unwrap();
+        state.formulas.contains_key(&key) || !state.direct_dependents(&key).is_empty()
+    };
+    if has_dependents {
+        formula_changed(sheet, row, col, None);
+    }
+}
+
+fn changed(key: CellKey) {
+    let order = {
+        let workbook = static_engine::stores();
+        let mut state = workbook.calc.lock().unwrap();
+        if state.mode == CalculationMode::Manual {
+            state.dirty.insert(key);
+            return;
+        }
+        let seeds: Vec<CellKey> = std::iter::once(key).chain(state.volatile_cells()).collect();
+        let affected = state.affected(seeds);
+        state.order(&affected)
+    };
+    recalculate(order);
+}
+
+/// Rows or columns were inserted/deleted: move formula cells to their new
+/// positions (`None` = deleted). References inside formulas keep their text.
+pub fn cells_moved(sheet: &str, relocate: &dyn Fn(i32, i32) -> Option<(i32, i32)>) {
+    let relocate_key = |(s, row, col): CellKey| -> Option<CellKey> {
+        if s != sheet {
+            return Some((s, row, col));
+        }
+        relocate(row, col).map(|(row, col)| (s, row, col))
+    };
+    let workbook = static_engine::stores();
+    let mut state = workbook.calc.lock().unwrap();
+    let formulas = std::mem::take(&mut state.formulas);
+    state.formulas = formulas.into_iter().filter_map(|(k, f)| Some((relocate_key(k)?, f))).collect();
+    let dirty = std::mem::take(&mut state.dirty);
+    state.dirty = dirty.into_iter().filter_map(relocate_key).collect();
+}
+
+// ============================================================================
+// RECALCULATION
+// ============================================================================
+
+/// Recalculate dirty cells, their dependents and volatile formulas.
+pub fn calculate() {
+    let order = {
+        let workbook = static_engine::stores();
+        let mut state = workbook.calc.lock().unwrap();
+        let dirty = std::mem::take(&mut state.dirty);
+        let seeds: Vec<CellKey> = dirty.into_iter().chain(state.volatile_cells()).collect();
+        let affected = state.affected(seeds);
+        state.order(&affected)
+    };
+    recalculate(order);
+}
+
+/// Recalculate every formula.
+pub fn calculate_full() {
+    let order = {
+        let workbook = static_engine::stores();
+        let mut state = workbook.calc.lock().unwrap();
+        state.dirty.clear();
+        let all: BTreeSet<CellKey> = state.formulas.keys().cloned().collect();
+        state.order(&all)
+    };
+    recalculate(order);
+}
+
+/// Recalculate the formulas inside an area (Range.Calculate), regardless
+/// of the calculation mode. Dependents outside the area are not touched.
+pub fn calculate_range(sheet: &str, start_row: i32, start_col: i32, end_row: i32, end_col: i32) {
+    let area = AreaRef { sheet: None, start: (start_row, start_col), end: (end_row, end_col) };
+    let order = {
+        let workbook = static_engine::stores();
+        let state = workbook.calc.lock().unwrap();
+        let inside: BTreeSet<CellKey> = state
+            .formulas
+            .keys()
+            .filter(|k| area.contains(&k.0, sheet, k.1, k.2))
+            .cloned()
+            .collect();
+        state.order(&inside)
+    };
+    recalculate(order);
+}
+
+/// Evaluate `order` front to back, storing each result before the next
+/// formula reads it.
+fn recalculate(order: Vec<CellKey>) {
+    for key in order {
+        let formula = static_engine::stores().calc.lock().unwrap().formulas.get(&key).cloned();
+        let Some(formula) = formula else { continue };
+        let mut read = |sheet: &str, row: i32, col: i32| static_engine::static_get_cell_value(sheet, row, col);
+        let value = formula.evaluate(&key.0, &mut read);
+        static_engine::static_store_calculated_value(&key.0, key.1, key.2, &value.to_cell_string());
+    }
+}
+
+// ============================================================================
+// DEPENDENCY QUERIES
+// ============================================================================
+
+/// Formula cells that read this cell directly.
+pub fn direct_dependents(sheet: &str, row: i32, col: i32) -> Vec<CellKey> {
+    static_engine::stores().calc.lock().unwrap().direct_dependents(&(sheet.to_string(), row, col))
+}
+
+/// Cells this formula reads directly.
+pub fn direct_precedents(sheet: &str, row: i32, col: i32) -> Vec<CellKey> {
+    static_engine::stores().calc.lock().unwrap().direct_precedents(&(sheet.to_string(), row, col))
+}
+
+/// Every formula cell that depends on this cell, directly or not.
+pub fn dependents(sheet: &str, row: i32, col: i32) -> Vec<CellKey> {
+    let key = (sheet.to_string(), row, col);
+    let mut all = static_engine::stores().calc.lock().unwrap().affected([key.clone()]);
+    all.remove(&key);
+    all.into_iter().collect()
+}
+
+/// Every cell this formula reads, directly or through other formulas.
+pub fn precedents(sheet: &str, row: i32, col: i32) -> Vec<CellKey> {
+    let workbook = static_engine::stores();
+    let state = workbook.calc.lock().unwrap();
+    let start = (sheet.to_string(), row, col);
+    let mut seen = BTreeSet::new();
+    let mut queue = vec![start.clone()];
+    while let Some(cell) = queue.pop() {
+        for p in state.direct_precedents(&cell) {
+            if seen.insert(p.clone()) {
+                queue.push(p);
+            }
+        }
+    }
+    seen.remove(&start);
+    seen.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::excel::static_engine::{
+        static_get_cell_value, static_set_cell_formula, static_set_cell_value, StaticWorkbook, WorkbookScope,
+    };
+
+    const S: &str = "CalcTest";
+
+    fn cell(row: i32, col: i32) -> CellKey {
+        (S.to_string(), row, col)
+    }
+
+    /// A fresh workbook holding A1 = 2, B1 = A1*10 and C1 = B1+A1
+    fn chain() -> WorkbookScope {
+        let workbook = StaticWorkbook::new().enter();
+        static_set_cell_value(S, 0, 0, "2");
+        static_set_cell_formula(S, 0, 1, "=A1*10");
+        static_set_cell_formula(S, 0, 2, "=B1+A1");
+        workbook
+    }
+
+    #[test]
+    fn test_automatic_mode_recalculates_dependents() {
+        let _workbook = chain();
+        assert_eq!(static_get_cell_value(S, 0, 2), "22");
+        static_set_cell_value(S, 0, 0, "3");
+        assert_eq!(static_get_cell_value(S, 0, 2), "33");
+    }
+
+    #[test]
+    fn test_dependency_queries() {
+        let _workbook = chain();
+        assert_eq!(direct_dependents(S, 0, 0), vec![cell(0, 1), cell(0, 2)]);
+        assert_eq!(dependents(S, 0, 0), vec![cell(0, 1), cell(0, 2)]);
+        assert_eq!(direct_precedents(S, 0, 2), vec![cell(0, 1), cell(0, 0)]);
+        assert_eq!(precedents(S, 0, 2), vec![cell(0, 0), cell(0, 1)]);
+        assert!(direct_precedents(S, 0, 0).is_empty());
+    }
+
+    #[test]
+    fn test_manual_mode_waits_for_calculate() {
+        let _workbook = chain();
+        set_calculation_mode(CalculationMode::Manual);
+        static_set_cell_value(S, 0, 0, "4");
+        assert_eq!(static_get_cell_value(S, 0, 2), "22");
+        calculate();
+        assert_eq!(static_get_cell_value(S, 0, 2), "44");
+    }
+
+    #[test]
+    fn test_back_to_automatic_recalculates_changes() {
+        let _workbook = chain();
+        set_calculation_mode(CalculationMode::Manual);
+        static_set_cell_value(S, 0, 0, "5");
+        set_calculation_mode(CalculationMode::Automatic);
+        assert_eq!(static_get_cell_value(S, 0, 2), "55");
+    }
+
+    #[test]
+    fn test_calculate_range_ignores_the_mode_and_outside_cells() {
+        let _workbook = chain();
+        set_calculation_mode(CalculationMode::Manual);
+        static_set_cell_value(S, 0, 0, "3");
+        calculate_range(S, 0, 1, 0, 1);
+        assert_eq!((static_get_cell_value(S, 0, 1), static_get_cell_value(S, 0, 2)), ("30".to_string(), "22".to_string()));
+        calculate_full();
+        assert_eq!(static_get_cell_value(S, 0, 2), "33");
+    }
+
+    #[test]
+    fn test_value_over_a_formula_leaves_the_graph() {
+        let _workbook = chain();
+        static_set_cell_value(S, 0, 1, "1");
+        assert_eq!(static_get_cell_value(S, 0, 2), "3");
+        assert_eq!(direct_dependents(S, 0, 0), vec![cell(0, 2)]);
+    }
+
+    #[test]
+    fn test_circular_references_still_calculate() {
+        let _workbook = StaticWorkbook::new().enter();
+        static_set_cell_formula(S, 0, 0, "=B1+1");
+        static_set_cell_formula(S, 0, 1, "=A1+1");
+        calculate_full();
+        assert!(!static_get_cell_value(S, 0, 0).is_empty());
+    }
+
+    #[test]
+    fn test_calculation_mode_from_value() {
+        assert_eq!(CalculationMode::from_value(&Value::Long(-4135)), Some(CalculationMode::Manual));
+        assert_eq!(CalculationMode::from_value(&Value::String("xlCalculationAutomatic".into())), Some(CalculationMode::Automatic));
+        assert_eq!(CalculationMode::from_value(&Value::Integer(7)), None);
+        assert_eq!(CalculationMode::Manual.code(), -4135);
+    }
+
+    #[test]
+    fn test_unknown_calculation_method() {
+        let _workbook = StaticWorkbook::new().enter();
+        assert!(call_method("Recalc", &[]).is_err());
+    }
+}
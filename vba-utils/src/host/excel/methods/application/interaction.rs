@@ -1,26 +1,123 @@
 // User interaction methods (dialogs, input, etc.)
 
 use anyhow::Result;
-use crate::context::Value;
+use crate::context::{Context, Value};
+use crate::host::behavior::{self, InteractionKind};
+use crate::host::excel::{engine, objects};
+use crate::host::ui::{self, InputBoxRequest};
+use crate::symbol::lower;
 
-pub fn call_method(method: &str, _args: &[Value]) -> Result<Value> {
-    match method.to_lowercase().as_str() {
+/// Answers Application.InputBox takes before giving up on a Type it
+/// cannot accept (Excel would keep asking)
+const MAX_INPUT_ATTEMPTS: usize = 3;
+
+/// Application.Quit, Application.Wait and Application.SendKeys: nothing
+/// leaves the sandbox, the interaction policy decides whether they are
+/// recorded or refused (see `host::behavior`)
+pub fn call_method(method: &str, args: &[Value], ctx: &mut Context) -> Result<Value> {
+    let target = args.first().map(Value::as_string).unwrap_or_default();
+    match &*lower(method) {
         "quit" => {
-            eprintln!("🚪 Application.Quit() - closing Excel");
+            behavior::perform(ctx, InteractionKind::Quit, "", String::new())?;
             Ok(Value::Empty)
         }
         "wait" => {
-            eprintln!("⏱️ Application.Wait() - pausing execution");
-            Ok(Value::Empty)
-        }
-        "inputbox" => {
-            eprintln!("📝 Application.InputBox() - showing input dialog");
-            Ok(Value::String(String::new()))
+            behavior::perform(ctx, InteractionKind::Wait, &target, String::new())?;
+            Ok(Value::Boolean(true))
         }
-        "ontime" => {
-            eprintln!("⏰ Application.OnTime() - scheduling procedure");
+        "sendkeys" => {
+            let described = behavior::describe_keys(&target);
+            behavior::perform(ctx, InteractionKind::SendKeys, &target, described)?;
             Ok(Value::Empty)
         }
         _ => Err(anyhow::anyhow!("Unknown interaction method: {}", method)),
     }
 }
+
+/// Application.InputBox(Prompt, [Title], [Default], [Left], [Top],
+/// [HelpFile], [HelpContextID], [Type]): the answer converted to `Type`
+/// (text by default), or False for Cancel. An answer `Type` does not
+/// accept is asked again, like Excel's "not valid" prompt.
+pub fn input_box(args: &[Value], ctx: &mut Context) -> Result<Value> {
+    let text = |index: usize| args.get(index).filter(|v| !matches!(v, Value::Empty)).map(Value::as_string);
+    let number = |index: usize| args.get(index).and_then(Value::as_integer);
+    let request = InputBoxRequest {
+        prompt: text(0).unwrap_or_default(),
+        title: text(1),
+        default: text(2),
+        xpos: number(3),
+        ypos: number(4),
+        input_type: Some(number(7).unwrap_or(2)),
+    };
+    let input_type = request.input_type.unwrap_or(2);
+
+    for _ in 0..MAX_INPUT_ATTEMPTS {
+        let Some(answer) = ui::input_box(ctx, &request) else {
+            return Ok(Value::Boolean(false));
+        };
+        match typed_answer(&answer, input_type) {
+            Some(value) => return Ok(value),
+            None => ctx.log(&format!("Application.InputBox: {:?} is not valid for Type {}", answer, input_type)),
+        }
+    }
+    Ok(Value::Boolean(false))
+}
+
+/// `answer` as the first of `input_type`'s kinds that accepts it: a range
+/// reference (8), a number (1), a boolean (4), then formula (0) or text (2)
+fn typed_answer(answer: &str, input_type: i64) -> Option<Value> {
+    let trimmed = answer.trim();
+    if input_type & 8 != 0 && engine::address_to_area(trimmed).is_ok() {
+        return Some(objects::range_object(&trimmed.replace('$', "").to_ascii_uppercase()));
+    }
+    if input_type & 1 != 0 {
+        if let Ok(n) = trimmed.parse::<f64>() {
+            return Some(Value::Double(n));
+        }
+    }
+    if input_type & 4 != 0 {
+        if trimmed.eq_ignore_ascii_case("true") {
+            return Some(Value::Boolean(true));
+        }
+        if trimmed.eq_ignore_ascii_case("false") {
+            return Some(Value::Boolean(false));
+        }
+    }
+    (input_type == 0 || input_type & 2 != 0).then(|| Value::String(answer.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime_config::RuntimeConfig;
+
+    fn ask(ctx: &mut Context, input_type: i64) -> Value {
+        let mut args = vec![Value::String("Value?".into())];
+        args.resize(7, Value::Empty);
+        args.push(Value::Integer(input_type));
+        input_box(&args, ctx).unwrap()
+    }
+
+    #[test]
+    fn test_input_box_types_and_scripted_answers() {
+        let config = RuntimeConfig::builder()
+            .input_answer("12.5")
+            .input_answer("abc")
+            .input_answer("$b$2:c3")
+            .input_answer("not a number")
+            .input_answer("7")
+            .input_cancel()
+            .build();
+        let mut ctx = Context::with_config(config);
+
+        assert!(matches!(ask(&mut ctx, 1), Value::Double(n) if n == 12.5));
+        assert!(matches!(ask(&mut ctx, 2), Value::String(s) if s == "abc"));
+        assert_eq!(objects::object_ref(&ask(&mut ctx, 8)), Some(("range".into(), "B2:C3".into())));
+        // The invalid answer is skipped, as if the user typed again
+        assert!(matches!(ask(&mut ctx, 1), Value::Double(n) if n == 7.0));
+        assert!(matches!(ask(&mut ctx, 2), Value::Boolean(false)));
+        // Out of scripted answers: the headless UI accepts the (empty) default,
+        // which is not a number
+        assert!(matches!(ask(&mut ctx, 1), Value::Boolean(false)));
+    }
+}
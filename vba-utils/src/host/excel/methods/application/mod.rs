@@ -5,25 +5,45 @@ pub mod utility;
 pub mod calculation;
 pub mod navigation;
 pub mod interaction;
+pub mod ranges;
 
 use anyhow::Result;
 use crate::context::{Context, Value};
+use crate::symbol::lower;
+
+/// Methods `call_method` routes, as VBA spells them
+pub const METHODS: &[&str] = &[
+    "Calculate", "CalculateFull", "Volatile", "Goto", "ActivatePrevious", "ActivateNext", "OnTime", "InputBox",
+    "Quit", "Wait", "SendKeys", "Union", "Intersect", "CentimetersToPoints", "InchesToPoints", "ConvertFormula",
+    "Evaluate",
+];
 
 /// Route method calls to specialized handlers
-pub fn call_method(method: &str, args: &[Value], _ctx: &mut Context) -> Result<Value> {
-    match method.to_lowercase().as_str() {
+pub fn call_method(method: &str, args: &[Value], ctx: &mut Context) -> Result<Value> {
+    match &*lower(method) {
+        // Server deployments persist the run instead of blocking on Wait
+        "wait" if ctx.runtime_config.hibernate_on_wait => {
+            ctx.request_hibernation();
+            Ok(Value::Boolean(true))
+        }
+        
         // Calculation methods
-        "calculate" | "calculatefull" => calculation::call_method(method, args),
+        "calculate" | "calculatefull" | "volatile" => calculation::call_method(method, args),
         
         // Navigation methods
         "goto" | "activateprevious" | "activatenext" => navigation::call_method(method, args),
         
         // Interaction methods
-        "quit" | "wait" | "inputbox" | "ontime" => interaction::call_method(method, args),
+        "ontime" => crate::host::ontime::on_time(args, ctx),
+        "inputbox" => interaction::input_box(args, ctx),
+        "quit" | "wait" | "sendkeys" => interaction::call_method(method, args, ctx),
+        
+        // Range set operations
+        "union" | "intersect" => ranges::call_method(method, args),
         
         // Utility methods
-        "centimeterstopo" | "inchestopoint" | "convertformula" | "evaluate" => utility::call_method(method, args),
+        "centimeterstopoints" | "inchestopoints" | "convertformula" | "evaluate" => utility::call_method(method, args),
         
-        _ => Err(anyhow::anyhow!("Unknown Application method: {}", method)),
+        _ => Err(crate::host::excel::properties::application::unknown_member("method", method)),
     }
 }
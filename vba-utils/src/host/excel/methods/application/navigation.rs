@@ -2,11 +2,25 @@
 
 use anyhow::Result;
 use crate::context::Value;
+use crate::host::excel::{engine, objects};
+use crate::symbol::lower;
 
-pub fn call_method(method: &str, _args: &[Value]) -> Result<Value> {
-    match method.to_lowercase().as_str() {
+pub fn call_method(method: &str, args: &[Value]) -> Result<Value> {
+    match &*lower(method) {
         "goto" => {
-            eprintln!("🔍 Application.Goto() - navigate to cell/range");
+            // Goto([Reference], [Scroll]) - selects the range; Scroll puts it at the window's top-left
+            let Some((_, address)) = args.first().and_then(objects::object_ref).filter(|(t, _)| t == "range") else {
+                eprintln!("🔍 Application.Goto() - navigate to cell/range");
+                return Ok(Value::Empty);
+            };
+            engine::select_range(&address).map_err(|e| anyhow::anyhow!("Application.Goto: {}", e))?;
+            if matches!(args.get(1), Some(Value::Boolean(true))) {
+                let (row, col, _, _) = engine::address_to_area(&address).map_err(|e| anyhow::anyhow!("{}", e))?;
+                engine::update_window(|w| {
+                    w.scroll_row = row + 1;
+                    w.scroll_column = col + 1;
+                });
+            }
             Ok(Value::Empty)
         }
         "activateprevious" => {
@@ -0,0 +1,39 @@
+// Range set operations (Union, Intersect)
+
+use anyhow::{Result, bail};
+use crate::context::Value;
+use crate::host::excel::objects::{self, range};
+use crate::host::excel::static_engine::Area;
+use crate::symbol::lower;
+
+pub fn call_method(method: &str, args: &[Value]) -> Result<Value> {
+    if args.len() < 2 {
+        bail!("Application.{} requires at least two ranges", method);
+    }
+    let ranges = args.iter()
+        .map(|arg| match objects::object_ref(arg) {
+            Some((object_type, address)) if object_type == "range" => range::address_areas(&address),
+            _ => bail!("Application.{}: argument is not a Range", method),
+        })
+        .collect::<Result<Vec<Vec<Area>>>>()?;
+
+    match &*lower(method) {
+        "union" => {
+            // Union(Arg1, Arg2, ...) - every cell in any of the ranges
+            let areas = range::union_areas(ranges.concat());
+            Ok(objects::range_object(&range::areas_address(&areas)))
+        }
+        "intersect" => {
+            // Intersect(Arg1, Arg2, ...) - cells in all of the ranges; Nothing if none
+            let mut areas = ranges[0].clone();
+            for other in &ranges[1..] {
+                areas = range::intersect_areas(&areas, other);
+            }
+            if areas.is_empty() {
+                return Ok(Value::Object(None));
+            }
+            Ok(objects::range_object(&range::areas_address(&range::union_areas(areas))))
+        }
+        _ => Err(anyhow::anyhow!("Unknown range method: {}", method)),
+    }
+}
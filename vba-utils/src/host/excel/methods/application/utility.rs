@@ -2,10 +2,13 @@
 
 use anyhow::Result;
 use crate::context::Value;
+use crate::host::excel::{engine, objects};
+use crate::host::excel::formula::{self, Formula, FormulaValue, XL_A1};
+use crate::symbol::lower;
 
 pub fn call_method(method: &str, args: &[Value]) -> Result<Value> {
-    match method.to_lowercase().as_str() {
-        "centimeterstopo" => {
+    match &*lower(method) {
+        "centimeterstopoints" => {
             // Convert centimeters to points
             if let Some(Value::Double(cm)) = args.first() {
                 let points = cm * 28.346456693; // 1 cm = 28.346... points
@@ -15,7 +18,7 @@ pub fn call_method(method: &str, args: &[Value]) -> Result<Value> {
                 Err(anyhow::anyhow!("CentimetersToPoints requires a numeric argument"))
             }
         }
-        "inchestopoint" => {
+        "inchestopoints" => {
             // Convert inches to points
             if let Some(Value::Double(inches)) = args.first() {
                 let points = inches * 72.0; // 1 inch = 72 points
@@ -26,13 +29,80 @@ pub fn call_method(method: &str, args: &[Value]) -> Result<Value> {
             }
         }
         "convertformula" => {
-            eprintln!("🔄 Application.ConvertFormula() - converting formula between styles");
-            Ok(Value::String(String::new()))
+            // ConvertFormula(Formula, FromReferenceStyle, [ToReferenceStyle], [ToAbsolute], [RelativeTo])
+            // Relative references are resolved against RelativeTo (default: the active cell)
+            let text = args.first().map(Value::as_string).unwrap_or_default();
+            let style = |i: usize| args.get(i).filter(|v| !matches!(v, Value::Empty)).map(|v| v.as_string().parse::<i32>().unwrap_or(XL_A1));
+            let from = style(1).unwrap_or(XL_A1);
+            let to = style(2).unwrap_or(from);
+            let relative_to = match args.get(4).and_then(objects::object_ref) {
+                Some((object_type, address)) if object_type == "range" => address,
+                _ => engine::get_selection().active_cell,
+            };
+            let (base_row, base_col, _, _) = engine::address_to_area(relative_to.split(',').next().unwrap_or("A1"))
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+            match formula::convert_formula(&text, from, to, style(3), (base_row, base_col)) {
+                Some(converted) => {
+                    eprintln!("🔄 Application.ConvertFormula({}) = {}", text, converted);
+                    Ok(Value::String(converted))
+                }
+                None => Ok(Value::Error(2015)), // #VALUE!
+            }
         }
         "evaluate" => {
-            eprintln!("🔢 Application.Evaluate() - evaluating expression");
-            Ok(Value::Empty)
+            let text = args.first().map(Value::as_string).unwrap_or_default();
+            evaluate(&text)
         }
         _ => Err(anyhow::anyhow!("Unknown utility method: {}", method)),
     }
 }
+
+/// Application.Evaluate / `[...]`: a reference on the active sheet gives
+/// the Range itself, anything else is worked out as a worksheet formula.
+/// Formula errors come back as error Values (`CVErr(xlErrDiv0)`, ...).
+fn evaluate(text: &str) -> Result<Value> {
+    let text = text.trim();
+    let text = text.strip_prefix('=').unwrap_or(text).trim();
+    if is_reference(text) {
+        return Ok(objects::range_object(&text.replace('$', "")));
+    }
+    let sheet = engine::get_active_sheet();
+    let mut read = |sheet: &str, row: i32, col: i32| engine::get_sheet_cell_value(sheet, row, col).unwrap_or_default();
+    let value = Formula::parse(text).evaluate(&sheet, &mut read);
+    eprintln!("🔢 Application.Evaluate({}) = {}", text, value.to_cell_string());
+    Ok(match value {
+        FormulaValue::Number(n) => Value::Double(n),
+        FormulaValue::Text(s) => Value::String(s),
+        FormulaValue::Bool(b) => Value::Boolean(b),
+        FormulaValue::Error(e) => Value::Error(formula::error_number(e)),
+        FormulaValue::Blank => Value::Empty,
+    })
+}
+
+/// `A1`, `$B$2:C3` (not `Sheet2!A1`, which evaluates to the cell's value)
+fn is_reference(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '$')
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '$' || c == ':')
+        && engine::address_to_area(text).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_formulas_and_references() {
+        let evaluate = |text: &str| call_method("Evaluate", &[Value::String(text.into())]).unwrap();
+        assert!(matches!(evaluate("SUM(1,2,3)"), Value::Double(n) if n == 6.0));
+        assert!(matches!(evaluate("=2^10"), Value::Double(n) if n == 1024.0));
+        assert!(matches!(evaluate("AND(TRUE,1>2)"), Value::Boolean(false)));
+        assert_eq!(evaluate("UPPER(\"abc\")").as_string(), "ABC");
+
+        // Formula errors are CVErr values
+        assert!(matches!(evaluate("1/0"), Value::Error(2007)));
+        assert!(matches!(evaluate("NOSUCHFUNCTION(1)"), Value::Error(2029)));
+
+        // A bare reference is the Range itself
+        assert_eq!(objects::object_ref(&evaluate("$A$1:B2")), Some(("range".into(), "A1:B2".into())));
+    }
+}
@@ -0,0 +1,27 @@
+// src/host/excel/methods/areas_methods.rs
+// Method handlers for Areas collection (Range.Areas)
+
+use anyhow::Result;
+use crate::context::Value;
+use crate::host::excel::objects::{self, range};
+use crate::symbol::lower;
+use crate::vba_errors::VbaErr;
+
+/// Call method on Areas collection
+/// Data format: "A1:A5,C1:C5" (the address of the range)
+pub fn call_areas_method(data: &str, method: &str, args: &[Value]) -> Result<Value> {
+    let areas = range::address_areas(data)?;
+
+    match &*lower(method) {
+        "item" => {
+            // Item(Index) - 1-based, in address order
+            let index = args.first().and_then(|v| v.as_string().parse::<usize>().ok()).unwrap_or(0);
+            match index.checked_sub(1).and_then(|i| areas.get(i)) {
+                Some(area) => Ok(objects::range_object(&range::areas_address(&[*area]))),
+                None => Err(VbaErr::SubscriptOutOfRange.error(format!("Areas.Item({})", index))),
+            }
+        }
+        "count" => Ok(Value::Integer(areas.len() as i64)),
+        _ => Err(anyhow::anyhow!("Unknown Areas method: {}", method)),
+    }
+}
@@ -4,11 +4,12 @@
 
 use anyhow::Result;
 use crate::context::Value;
+use crate::symbol::lower;
 
 /// Call method on AutoFilter object
 /// Data format: "worksheet_name:workbook_id"
 pub fn call_autofilter_method(_data: &str, method: &str, _args: &[Value]) -> Result<Value> {
-    match method.to_lowercase().as_str() {
+    match &*lower(method) {
         "applyfilter" => {
             eprintln!("Applying AutoFilter with criteria - NOT YET IMPLEMENTED");
             Ok(Value::Empty)
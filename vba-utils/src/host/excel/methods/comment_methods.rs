@@ -0,0 +1,45 @@
+// src/host/excel/methods/comment_methods.rs
+// Method handlers for Comment object
+// Comment is accessed via Range.Comment / returned by Range.AddComment
+
+use anyhow::Result;
+use crate::context::Value;
+use crate::host::excel::{engine, static_engine};
+use crate::symbol::lower;
+
+/// Call method on Comment object
+/// Data format: cell address ("B2") on the active sheet
+pub fn call_comment_method(data: &str, method: &str, args: &[Value]) -> Result<Value> {
+    let sheet = engine::get_active_sheet();
+    let (row, col) = engine::address_to_indices(data).map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    match &*lower(method) {
+        "text" => {
+            // Text([Text], [Start], [Overwrite]) - sets the text when given, returns it.
+            // Without Start the old text is replaced; with it, Text is inserted there.
+            let current = static_engine::static_get_comment(&sheet, row, col).unwrap_or_default();
+            let Some(text) = args.first().filter(|v| !matches!(v, Value::Empty)) else {
+                return Ok(Value::String(current));
+            };
+            let text = text.as_string();
+            let start = args.get(1).map(|v| v.as_string().parse::<usize>().unwrap_or(1)).unwrap_or(1);
+            let overwrite = matches!(args.get(2), Some(Value::Boolean(true)));
+            let updated = if args.len() < 2 {
+                text
+            } else {
+                // Insert (or overwrite) at the 1-based character position
+                let chars: Vec<char> = current.chars().collect();
+                let at = start.saturating_sub(1).min(chars.len());
+                let tail_from = if overwrite { (at + text.chars().count()).min(chars.len()) } else { at };
+                chars[..at].iter().collect::<String>() + &text + &chars[tail_from..].iter().collect::<String>()
+            };
+            static_engine::static_add_comment(&sheet, row, col, &updated);
+            Ok(Value::String(updated))
+        }
+        "delete" => {
+            static_engine::static_clear_comment(&sheet, row, col);
+            Ok(Value::Empty)
+        }
+        _ => Err(anyhow::anyhow!("Unknown Comment method: {}", method)),
+    }
+}
@@ -0,0 +1,112 @@
+// src/host/excel/methods/hyperlink_methods.rs
+// Method handlers for Hyperlinks collection and Hyperlink object
+// Hyperlinks is accessed via Worksheet.Hyperlinks or Range.Hyperlinks
+
+use anyhow::{Result, bail};
+use crate::context::Value;
+use crate::host::excel::{engine, objects, static_engine};
+use crate::host::excel::objects::indices_to_address;
+use crate::host::excel::static_engine::HyperlinkInfo;
+use crate::symbol::lower;
+use crate::vba_errors::VbaErr;
+
+/// Call method on Hyperlinks collection
+/// Data format: "Sheet1" (whole sheet) or "Sheet1!A1:B10" (Range.Hyperlinks)
+pub fn call_hyperlinks_method(data: &str, method: &str, args: &[Value]) -> Result<Value> {
+    let (sheet, _) = parse_scope(data)?;
+
+    match &*lower(method) {
+        "add" => {
+            // Add(Anchor, Address, [SubAddress], [ScreenTip], [TextToDisplay])
+            let Some(anchor) = args.first() else {
+                bail!("Hyperlinks.Add requires Anchor");
+            };
+            let anchor = match objects::object_ref(anchor) {
+                Some((object_type, address)) if object_type == "range" => address,
+                _ => anchor.as_string(),
+            };
+            let (row, col) = engine::address_to_indices(&anchor).map_err(|e| anyhow::anyhow!("{}", e))?;
+            let text = |i: usize| args.get(i).map(|v| v.as_string()).unwrap_or_default();
+            let link = HyperlinkInfo {
+                address: text(1),
+                sub_address: text(2),
+                screen_tip: text(3),
+                text_to_display: text(4),
+            };
+            eprintln!("Hyperlinks.Add {}!{} -> {}", sheet, indices_to_address(row, col), link.address);
+            static_engine::static_add_hyperlink(&sheet, row, col, link);
+            Ok(Value::String(format!("Hyperlink:{}!{}", sheet, indices_to_address(row, col))))
+        }
+        "item" => {
+            // Item(Index) - 1-based, in row-major order
+            let index = args.first().and_then(|v| v.as_string().parse::<usize>().ok()).unwrap_or(0);
+            let links = hyperlinks_in_scope(data)?;
+            match index.checked_sub(1).and_then(|i| links.get(i)) {
+                Some((sheet, row, col, _)) => Ok(Value::String(format!("Hyperlink:{}!{}", sheet, indices_to_address(*row, *col)))),
+                None => Err(VbaErr::SubscriptOutOfRange.error(format!("Hyperlinks.Item({})", index))),
+            }
+        }
+        "count" => Ok(Value::Integer(hyperlinks_in_scope(data)?.len() as i64)),
+        "delete" => {
+            for (sheet, row, col, _) in hyperlinks_in_scope(data)? {
+                static_engine::static_delete_hyperlink(&sheet, row, col);
+            }
+            Ok(Value::Empty)
+        }
+        _ => Err(anyhow::anyhow!("Unknown Hyperlinks method: {}", method)),
+    }
+}
+
+/// Call method on Hyperlink object
+/// Data format: "Sheet1!A1"
+pub fn call_hyperlink_method(data: &str, method: &str, _args: &[Value]) -> Result<Value> {
+    let (sheet, row, col) = parse_cell(data)?;
+
+    match &*lower(method) {
+        "delete" => {
+            static_engine::static_delete_hyperlink(&sheet, row, col);
+            Ok(Value::Empty)
+        }
+        "follow" => {
+            // Opening the target is out of scope; the request is only logged
+            let link = static_engine::static_get_hyperlink(&sheet, row, col)
+                .ok_or_else(|| anyhow::anyhow!("Hyperlink at {} was deleted", data))?;
+            eprintln!("   [STUB] Hyperlink({}).Follow -> {}", data, link.address);
+            Ok(Value::Empty)
+        }
+        _ => Err(anyhow::anyhow!("Unknown Hyperlink method: {}", method)),
+    }
+}
+
+/// (start_row, start_col, end_row, end_col), 0-based
+type Area = (i32, i32, i32, i32);
+
+/// Split a Hyperlinks scope into its sheet and optional area
+pub(crate) fn parse_scope(data: &str) -> Result<(String, Option<Area>)> {
+    match data.rsplit_once('!') {
+        Some((sheet, address)) => {
+            let area = engine::address_to_area(address).map_err(|e| anyhow::anyhow!("{}", e))?;
+            Ok((sheet.to_string(), Some(area)))
+        }
+        None if data.is_empty() => Ok((engine::get_active_sheet(), None)),
+        None => Ok((data.to_string(), None)),
+    }
+}
+
+/// Hyperlinks inside a Hyperlinks scope, in collection order
+pub(crate) fn hyperlinks_in_scope(data: &str) -> Result<Vec<(String, i32, i32, HyperlinkInfo)>> {
+    let (sheet, area) = parse_scope(data)?;
+    let mut links = static_engine::static_list_hyperlinks(Some(&sheet));
+    if let Some((start_row, start_col, end_row, end_col)) = area {
+        links.retain(|(_, row, col, _)| (start_row..=end_row).contains(row) && (start_col..=end_col).contains(col));
+    }
+    Ok(links)
+}
+
+/// Split a Hyperlink reference ("Sheet1!A1") into sheet, row and column
+pub(crate) fn parse_cell(data: &str) -> Result<(String, i32, i32)> {
+    let (sheet, address) = data.rsplit_once('!').unwrap_or(("", data));
+    let sheet = if sheet.is_empty() { engine::get_active_sheet() } else { sheet.to_string() };
+    let (row, col) = engine::address_to_indices(address).map_err(|e| anyhow::anyhow!("{}", e))?;
+    Ok((sheet, row, col))
+}
@@ -3,11 +3,20 @@
 
 pub mod range_methods;
 pub mod worksheet_methods;
+pub mod workbook_methods;
 pub mod autofilter_methods;
+pub mod areas_methods;
+pub mod comment_methods;
+pub mod hyperlink_methods;
+pub mod pivot_methods;
+pub mod shape_methods;
+pub mod validation_methods;
+pub mod window_methods;
 pub mod application;
 
 use anyhow::Result;
-use crate::context::Value;
+use crate::context::{Context, Value};
+use crate::symbol::lower;
 
 /// Call method on any Excel object
 pub fn call_method(
@@ -15,13 +24,31 @@ pub fn call_method(
     object_data: &str, // e.g., "A1" for Range
     method: &str,
     args: &[Value],
+    ctx: &mut Context,
 ) -> Result<Value> {
-    match object_type.to_lowercase().as_str() {
+    match &*lower(object_type) {
         "range" => range_methods::call_range_method(object_data, method, args),
+        "rows" | "columns" => range_methods::call_rows_columns_method(object_type, object_data, method, args),
         "worksheet" => worksheet_methods::call_worksheet_method(object_data, method, args),
-        "workbook" => Err(anyhow::anyhow!("Workbook methods not yet implemented")),
-        "application" => application::call_method(method, args, &mut crate::context::Context::default()),
+        "workbook" => workbook_methods::call_workbook_method(object_data, method, args, ctx),
+        "worksheets" => worksheet_methods::call_worksheets_method(object_data, method, args),
+        "workbooks" => workbook_methods::call_workbooks_method(object_data, method, args),
+        "application" => application::call_method(method, args, ctx),
         "autofilter" => autofilter_methods::call_autofilter_method(object_data, method, args),
+        "areas" => areas_methods::call_areas_method(object_data, method, args),
+        "comment" => comment_methods::call_comment_method(object_data, method, args),
+        "validation" => validation_methods::call_validation_method(object_data, method, args),
+        "hyperlinks" => hyperlink_methods::call_hyperlinks_method(object_data, method, args),
+        "hyperlink" => hyperlink_methods::call_hyperlink_method(object_data, method, args),
+        "shapes" => shape_methods::call_shapes_method(object_data, method, args),
+        "shape" => shape_methods::call_shape_method(object_data, method, args),
+        "textframe" => shape_methods::call_textframe_method(object_data, method, args),
+        "pivotcaches" => pivot_methods::call_pivotcaches_method(object_data, method, args),
+        "pivotcache" => pivot_methods::call_pivotcache_method(object_data, method, args),
+        "pivottables" => pivot_methods::call_pivottables_method(object_data, method, args),
+        "pivottable" => pivot_methods::call_pivottable_method(object_data, method, args),
+        "pivotfield" => pivot_methods::call_pivotfield_method(object_data, method, args),
+        "window" => window_methods::call_window_method(object_data, method, args),
         _ => Err(anyhow::anyhow!("Unknown object type: {}", object_type)),
     }
 }
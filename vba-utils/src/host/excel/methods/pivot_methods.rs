@@ -0,0 +1,195 @@
+// src/host/excel/methods/pivot_methods.rs
+// Method handlers for PivotCaches, PivotCache, PivotTables, PivotTable and PivotField
+// Pivot tables are not computed: creation calls and field layout are recorded
+// in the static engine as a declarative description
+
+use anyhow::{Result, bail};
+use crate::context::Value;
+use crate::host::excel::{engine, objects, static_engine};
+use crate::symbol::lower;
+use crate::vba_errors::VbaErr;
+
+/// Call method on PivotCaches collection (Workbook.PivotCaches)
+pub fn call_pivotcaches_method(_data: &str, method: &str, args: &[Value]) -> Result<Value> {
+    match &*lower(method) {
+        "create" | "add" => {
+            // Create(SourceType, [SourceData], [Version])
+            let source_type = args.first().and_then(|v| v.as_string().parse::<i32>().ok()).unwrap_or(1);
+            let source_data = match args.get(1) {
+                Some(source) => match objects::object_ref(source) {
+                    Some((object_type, address)) if object_type == "range" => format!("{}!{}", engine::get_active_sheet(), address),
+                    _ => source.as_string(),
+                },
+                None => String::new(),
+            };
+            let index = static_engine::static_create_pivot_cache(source_type, &source_data);
+            eprintln!("PivotCaches.Create(SourceData:='{}') -> PivotCache {}", source_data, index);
+            Ok(Value::String(format!("PivotCache:{}", index)))
+        }
+        "item" => {
+            let index = args.first().and_then(|v| v.as_string().parse::<usize>().ok()).unwrap_or(0);
+            match static_engine::static_get_pivot_cache(index) {
+                Some(_) => Ok(Value::String(format!("PivotCache:{}", index))),
+                None => Err(VbaErr::SubscriptOutOfRange.error(format!("PivotCaches.Item({})", index))),
+            }
+        }
+        "count" => Ok(Value::Integer(static_engine::static_pivot_cache_count() as i64)),
+        _ => Err(anyhow::anyhow!("Unknown PivotCaches method: {}", method)),
+    }
+}
+
+/// Call method on PivotCache object
+/// Data format: 1-based cache index ("1")
+pub fn call_pivotcache_method(data: &str, method: &str, args: &[Value]) -> Result<Value> {
+    let index = cache_index(data)?;
+    match &*lower(method) {
+        "createpivottable" => {
+            // CreatePivotTable(TableDestination, [TableName], [ReadData], [DefaultVersion])
+            create_pivot_table(index, args.first(), args.get(1))
+        }
+        "refresh" => Ok(Value::Empty),
+        _ => Err(anyhow::anyhow!("Unknown PivotCache method: {}", method)),
+    }
+}
+
+/// Call method on PivotTables collection (Worksheet.PivotTables)
+/// Data format: sheet name ("Sheet1")
+pub fn call_pivottables_method(data: &str, method: &str, args: &[Value]) -> Result<Value> {
+    match &*lower(method) {
+        "add" => {
+            // Add(PivotCache, TableDestination, [TableName], ...)
+            let cache = args.first().and_then(objects::object_ref).filter(|(t, _)| t == "pivotcache");
+            let Some((_, cache)) = cache else {
+                bail!("PivotTables.Add requires a PivotCache");
+            };
+            create_pivot_table(cache_index(&cache)?, args.get(1), args.get(2))
+        }
+        "item" => {
+            // Item(Index) - 1-based creation order, or table name
+            let key = args.first().cloned().unwrap_or(Value::Empty);
+            let tables = static_engine::static_list_pivot_tables(Some(data));
+            let table = match &key {
+                Value::String(name) => tables.into_iter().find(|t| t.name.eq_ignore_ascii_case(name)),
+                other => other.as_string().parse::<usize>().ok()
+                    .and_then(|i| i.checked_sub(1))
+                    .and_then(|i| tables.into_iter().nth(i)),
+            };
+            match table {
+                Some(table) => Ok(Value::String(format!("PivotTable:{}", table.name))),
+                None => bail!("PivotTables.Item({}): unable to get the PivotTables property", key.as_string()),
+            }
+        }
+        "count" => Ok(Value::Integer(static_engine::static_list_pivot_tables(Some(data)).len() as i64)),
+        _ => Err(anyhow::anyhow!("Unknown PivotTables method: {}", method)),
+    }
+}
+
+/// Call method on PivotTable object
+/// Data format: table name ("PivotTable1")
+pub fn call_pivottable_method(data: &str, method: &str, args: &[Value]) -> Result<Value> {
+    if static_engine::static_get_pivot_table(data).is_none() {
+        bail!("PivotTable '{}' does not exist", data);
+    }
+    let text = |i: usize| args.get(i).map(|v| v.as_string()).filter(|s| !s.is_empty());
+
+    match &*lower(method) {
+        "pivotfields" => {
+            // PivotFields(Index) - fields are added to the layout on first use
+            let Some(name) = text(0) else {
+                bail!("PivotTable.PivotFields requires a field name");
+            };
+            static_engine::static_update_pivot_table(data, |t| {
+                t.field_mut(&name);
+            });
+            Ok(field_ref(data, &name))
+        }
+        "adddatafield" => {
+            // AddDataField(Field, [Caption], [Function])
+            let field = match args.first().and_then(objects::object_ref) {
+                Some((object_type, field)) if object_type == "pivotfield" => split_field(&field)?.1.to_string(),
+                _ => text(0).ok_or_else(|| anyhow::anyhow!("PivotTable.AddDataField requires a field"))?,
+            };
+            let function = args.get(2).and_then(|v| v.as_string().parse::<i32>().ok()).unwrap_or(-4157);
+            let caption = text(1);
+            static_engine::static_update_pivot_table(data, |t| {
+                t.add_data_field(&field, caption.as_deref(), function);
+            });
+            Ok(field_ref(data, &field))
+        }
+        "addfields" => {
+            // AddFields(RowFields, [ColumnFields], [PageFields]) - one field name each
+            static_engine::static_update_pivot_table(data, |t| {
+                for (i, orientation) in [1, 2, 3].into_iter().enumerate() {
+                    if let Some(name) = text(i) {
+                        t.set_orientation(&name, orientation);
+                    }
+                }
+            });
+            Ok(Value::Empty)
+        }
+        "cleartable" => {
+            static_engine::static_update_pivot_table(data, |t| {
+                t.fields.clear();
+                t.data_fields.clear();
+            });
+            Ok(Value::Empty)
+        }
+        "refreshtable" => Ok(Value::Boolean(true)),
+        "pivotcache" => Ok(Value::String(format!("PivotCache:{}", static_engine::static_get_pivot_table(data).map_or(0, |t| t.cache_index)))),
+        _ => Err(anyhow::anyhow!("Unknown PivotTable method: {}", method)),
+    }
+}
+
+/// Call method on PivotField object
+/// Data format: "PivotTable1|FieldName"
+pub fn call_pivotfield_method(data: &str, method: &str, _args: &[Value]) -> Result<Value> {
+    let (table, field) = split_field(data)?;
+    match &*lower(method) {
+        "clearallfilters" | "clearmanualfilter" => Ok(Value::Empty),
+        "delete" => {
+            // Removing a field from the layout hides it
+            static_engine::static_update_pivot_table(table, |t| t.set_orientation(field, 0));
+            Ok(Value::Empty)
+        }
+        _ => Err(anyhow::anyhow!("Unknown PivotField method: {}", method)),
+    }
+}
+
+/// Create a pivot table from a cache at TableDestination
+fn create_pivot_table(cache_index: usize, destination: Option<&Value>, name: Option<&Value>) -> Result<Value> {
+    let Some(destination) = destination else {
+        bail!("CreatePivotTable requires TableDestination");
+    };
+    let (sheet, destination) = match objects::object_ref(destination) {
+        Some((object_type, address)) if object_type == "range" => (engine::get_active_sheet(), address),
+        _ => {
+            // "Sheet2!R3C1", "'My Sheet'!A3" or a bare address on the active sheet
+            let text = destination.as_string();
+            match text.rsplit_once('!') {
+                Some((sheet, address)) => (sheet.trim_matches('\'').to_string(), address.to_string()),
+                None => (engine::get_active_sheet(), text),
+            }
+        }
+    };
+    let name = name.map(|v| v.as_string()).filter(|s| !s.is_empty());
+    match static_engine::static_create_pivot_table(cache_index, &sheet, &destination, name.as_deref()) {
+        Some(table) => {
+            eprintln!("CreatePivotTable -> {} at {}!{}", table.name, sheet, destination);
+            Ok(Value::String(format!("PivotTable:{}", table.name)))
+        }
+        None => bail!("Cannot create pivot table '{}': the PivotCache is invalid or the name is in use", name.unwrap_or_default()),
+    }
+}
+
+fn cache_index(data: &str) -> Result<usize> {
+    data.parse().map_err(|_| anyhow::anyhow!("Invalid PivotCache reference: {}", data))
+}
+
+fn field_ref(table: &str, field: &str) -> Value {
+    Value::String(format!("PivotField:{}|{}", table, field))
+}
+
+/// Split a PivotField reference into table and field names
+pub(crate) fn split_field(data: &str) -> Result<(&str, &str)> {
+    data.split_once('|').ok_or_else(|| anyhow::anyhow!("Invalid PivotField reference: {}", data))
+}
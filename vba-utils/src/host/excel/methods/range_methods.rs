@@ -19,7 +19,14 @@
 
 use anyhow::{Result, bail};
 use crate::context::Value;
-use crate::host::excel::engine;
+use crate::host::excel::{engine, formula};
+use crate::host::excel::formula::CellRef;
+use crate::host::excel::objects::{self, range};
+use crate::host::excel::properties::range_properties::check_writable;
+use crate::host::excel::static_engine::{self, MAX_COL, MAX_ROW};
+use crate::interpreter::builtins::constants;
+use crate::symbol::lower;
+use crate::vba_errors::VbaErr;
 
 // ============================================================================
 // CALL METHOD
@@ -36,7 +43,9 @@ use crate::host::excel::engine;
 /// * `Ok(Value)` - The method return value (often Value::Empty for void methods)
 /// * `Err` - If method is unknown or engine call fails
 pub fn call_range_method(address: &str, method: &str, args: &[Value]) -> Result<Value> {
-    match method.to_lowercase().as_str() {
+    // Methods read the sheet directly, so buffered writes must land first
+    engine::flush_writes().map_err(|e| anyhow::anyhow!("{}", e))?;
+    match &*lower(method) {
         
         // ====================================================================
         // SELECTION & ACTIVATION
@@ -44,15 +53,13 @@ pub fn call_range_method(address: &str, method: &str, args: &[Value]) -> Result<
         
         "select" => {
             // Selects the range (makes it the current selection)
-            // TODO: ENGINE CALL - engine::select_range(address)
-            eprintln!("   [STUB] Range({}).Select()", address);
+            engine::select_range(address).map_err(|e| anyhow::anyhow!("Range({}).Select: {}", address, e))?;
             Ok(Value::Empty)
         }
         
         "activate" => {
             // Activates a single cell within a selection
-            // TODO: ENGINE CALL - engine::activate_cell(address)
-            eprintln!("   [STUB] Range({}).Activate()", address);
+            engine::activate_cell(address).map_err(|e| anyhow::anyhow!("Range({}).Activate: {}", address, e))?;
             Ok(Value::Empty)
         }
         
@@ -91,8 +98,8 @@ pub fn call_range_method(address: &str, method: &str, args: &[Value]) -> Result<
             // Pastes from clipboard with special options
             // Paste: xlPasteAll(-4104), xlPasteValues(-4163), xlPasteFormulas(-4123), etc.
             // TODO: ENGINE CALL - engine::paste_special(address, paste_type, operation, skip_blanks, transpose)
-            let paste_type = args.get(0).map(value_to_int).unwrap_or(-4104); // xlPasteAll
-            let operation = args.get(1).map(value_to_int).unwrap_or(-4142);  // xlNone
+            let paste_type = args.get(0).map(value_to_int).unwrap_or(constants::xlPasteAll);
+            let operation = args.get(1).map(value_to_int).unwrap_or(constants::xlNone);
             let skip_blanks = args.get(2).map(value_to_bool).unwrap_or(false);
             let transpose = args.get(3).map(value_to_bool).unwrap_or(false);
             eprintln!("   [STUB] Range({}).PasteSpecial(Paste:={}, Operation:={}, SkipBlanks:={}, Transpose:={})", 
@@ -120,8 +127,11 @@ pub fn call_range_method(address: &str, method: &str, args: &[Value]) -> Result<
             // Clears everything (values, formats, comments, etc.)
             // TODO: ENGINE CALL - engine::clear_range(address)
             eprintln!("   [STUB] Range({}).Clear()", address);
-            engine::set_cell_value(address, "")
-                .map_err(|e| anyhow::anyhow!("Failed to clear: {}", e))?;
+            check_writable(address)?;
+            for area in address.split(',') {
+                engine::set_cell_value(area.trim(), "")
+                    .map_err(|e| anyhow::anyhow!("Failed to clear: {}", e))?;
+            }
             Ok(Value::Empty)
         }
         
@@ -129,8 +139,11 @@ pub fn call_range_method(address: &str, method: &str, args: &[Value]) -> Result<
             // Clears only values and formulas (keeps formatting)
             // TODO: ENGINE CALL - engine::clear_contents(address)
             eprintln!("   [STUB] Range({}).ClearContents()", address);
-            engine::set_cell_value(address, "")
-                .map_err(|e| anyhow::anyhow!("Failed to clear contents: {}", e))?;
+            check_writable(address)?;
+            for area in address.split(',') {
+                engine::set_cell_value(area.trim(), "")
+                    .map_err(|e| anyhow::anyhow!("Failed to clear contents: {}", e))?;
+            }
             Ok(Value::Empty)
         }
         
@@ -143,8 +156,8 @@ pub fn call_range_method(address: &str, method: &str, args: &[Value]) -> Result<
         
         "clearcomments" => {
             // Clears only comments
-            // TODO: ENGINE CALL - engine::clear_comments(address)
-            eprintln!("   [STUB] Range({}).ClearComments()", address);
+            let ((start_row, start_col), (end_row, end_col)) = get_range_bounds(address)?;
+            static_engine::static_clear_comments(&engine::get_active_sheet(), start_row, start_col, end_row, end_col);
             Ok(Value::Empty)
         }
         
@@ -197,9 +210,20 @@ pub fn call_range_method(address: &str, method: &str, args: &[Value]) -> Result<
             // Inserts cells, shifting existing cells
             // Shift: xlShiftDown(-4121), xlShiftToRight(-4161)
             // CopyOrigin: xlFormatFromLeftOrAbove(0), xlFormatFromRightOrBelow(1)
-            // TODO: ENGINE CALL - engine::insert_cells(address, shift, copy_origin)
-            let shift = args.get(0).map(value_to_int).unwrap_or(-4121); // xlShiftDown
-            eprintln!("   [STUB] Range({}).Insert(Shift:={})", address, shift);
+            // Entire rows/columns (Rows(2), Columns("C")) ignore Shift
+            let shift = args.get(0).map(value_to_int).unwrap_or(constants::xlShiftDown) as i32;
+            let sheet = engine::get_active_sheet();
+            let ((start_row, start_col), (end_row, end_col)) = get_range_bounds(address)?;
+            static_engine::static_check_structure_change(&sheet, (start_row, start_col, end_row, end_col), true)
+                .map_err(|e| VbaErr::ApplicationDefined.message(e))?;
+            if start_col == 0 && end_col == MAX_COL {
+                static_engine::static_insert_rows(&sheet, start_row, end_row - start_row + 1);
+            } else if start_row == 0 && end_row == MAX_ROW {
+                static_engine::static_insert_columns(&sheet, start_col, end_col - start_col + 1);
+            } else {
+                static_engine::static_insert_cells(&sheet, start_row, start_col, end_row, end_col, shift);
+            }
+            eprintln!("   Range({}).Insert(Shift:={})", address, shift);
             Ok(Value::Empty)
         }
         
@@ -207,9 +231,20 @@ pub fn call_range_method(address: &str, method: &str, args: &[Value]) -> Result<
             // Delete([Shift])
             // Deletes cells, shifting remaining cells
             // Shift: xlShiftUp(-4162), xlShiftToLeft(-4159)
-            // TODO: ENGINE CALL - engine::delete_cells(address, shift)
-            let shift = args.get(0).map(value_to_int).unwrap_or(-4162); // xlShiftUp
-            eprintln!("   [STUB] Range({}).Delete(Shift:={})", address, shift);
+            // Entire rows/columns (Rows(2), Columns("C")) ignore Shift
+            let shift = args.get(0).map(value_to_int).unwrap_or(-4162) as i32; // xlShiftUp
+            let sheet = engine::get_active_sheet();
+            let ((start_row, start_col), (end_row, end_col)) = get_range_bounds(address)?;
+            static_engine::static_check_structure_change(&sheet, (start_row, start_col, end_row, end_col), false)
+                .map_err(|e| VbaErr::ApplicationDefined.message(e))?;
+            if start_col == 0 && end_col == MAX_COL {
+                static_engine::static_delete_rows(&sheet, start_row, end_row - start_row + 1);
+            } else if start_row == 0 && end_row == MAX_ROW {
+                static_engine::static_delete_columns(&sheet, start_col, end_col - start_col + 1);
+            } else {
+                static_engine::static_delete_cells(&sheet, start_row, start_col, end_row, end_col, shift);
+            }
+            eprintln!("   Range({}).Delete(Shift:={})", address, shift);
             Ok(Value::Empty)
         }
         
@@ -305,16 +340,18 @@ pub fn call_range_method(address: &str, method: &str, args: &[Value]) -> Result<
             // Merge([Across])
             // Merges cells into one merged cell
             // Across: If True, merges each row separately
-            // TODO: ENGINE CALL - engine::merge_cells(address, across)
             let across = args.get(0).map(value_to_bool).unwrap_or(false);
-            eprintln!("   [STUB] Range({}).Merge(Across:={})", address, across);
+            let ((start_row, start_col), (end_row, end_col)) = get_range_bounds(address)?;
+            static_engine::static_merge_cells(&engine::get_active_sheet(), start_row, start_col, end_row, end_col, across);
+            eprintln!("   Range({}).Merge(Across:={})", address, across);
             Ok(Value::Empty)
         }
         
         "unmerge" => {
             // Unmerges merged cells back to individual cells
-            // TODO: ENGINE CALL - engine::unmerge_cells(address)
-            eprintln!("   [STUB] Range({}).UnMerge()", address);
+            let ((start_row, start_col), (end_row, end_col)) = get_range_bounds(address)?;
+            static_engine::static_unmerge_cells(&engine::get_active_sheet(), start_row, start_col, end_row, end_col);
+            eprintln!("   Range({}).UnMerge()", address);
             Ok(Value::Empty)
         }
         
@@ -391,18 +428,22 @@ pub fn call_range_method(address: &str, method: &str, args: &[Value]) -> Result<
         "addcomment" => {
             // AddComment([Text])
             // Adds a comment to the cell
-            // Returns the Comment object
-            // TODO: ENGINE CALL - engine::add_comment(address, text)
+            // Returns the Comment object; fails if the cell already has one
             let text = args.get(0).map(value_to_string).unwrap_or_default();
-            eprintln!("   [STUB] Range({}).AddComment(Text:='{}')", address, text);
+            let sheet = engine::get_active_sheet();
+            let ((row, col), _) = get_range_bounds(address)?;
+            if static_engine::static_get_comment(&sheet, row, col).is_some() {
+                bail!("Range({}).AddComment: the cell already has a comment", address);
+            }
+            static_engine::static_add_comment(&sheet, row, col, &text);
             // Return reference to Comment object
-            Ok(Value::String(format!("Comment:{}", address)))
+            Ok(Value::String(format!("Comment:{}", indices_to_address(row, col))))
         }
         
         "clearcomment" => {
             // Clears the comment (alias for ClearComments for single cell)
-            // TODO: ENGINE CALL - engine::clear_comment(address)
-            eprintln!("   [STUB] Range({}).ClearComment()", address);
+            let ((row, col), _) = get_range_bounds(address)?;
+            static_engine::static_clear_comment(&engine::get_active_sheet(), row, col);
             Ok(Value::Empty)
         }
         
@@ -415,12 +456,22 @@ pub fn call_range_method(address: &str, method: &str, args: &[Value]) -> Result<
             // Returns cells matching special criteria
             // Type: xlCellTypeConstants(2), xlCellTypeFormulas(-4123), xlCellTypeBlanks(4), etc.
             // Value: xlNumbers(1), xlTextValues(2), xlLogical(4), xlErrors(16)
-            // TODO: ENGINE CALL - engine::get_special_cells(address, type, value)
-            let cell_type = args.get(0).map(value_to_int).unwrap_or(2); // xlCellTypeConstants
-            let value_type = args.get(1).map(value_to_int);
-            eprintln!("   [STUB] Range({}).SpecialCells(Type:={}, Value:={:?})", address, cell_type, value_type);
-            // Return self for now
-            Ok(Value::String(format!("Range:{}", address)))
+            // A single cell searches the whole sheet, as in Excel
+            let cell_type = args.get(0).map(value_to_int).unwrap_or(2) as i32; // xlCellTypeConstants
+            let value_type = args.get(1).map(|v| value_to_int(v) as i32);
+            let ((start_row, start_col), (end_row, end_col)) = match get_range_bounds(address)? {
+                (start, end) if start == end => ((0, 0), (MAX_ROW, MAX_COL)),
+                bounds => bounds,
+            };
+            let cells = static_engine::static_get_special_cells(
+                &engine::get_active_sheet(), start_row, start_col, end_row, end_col, cell_type, value_type,
+            );
+            if cells.is_empty() {
+                return Err(VbaErr::ApplicationDefined.message("No cells were found."));
+            }
+            let areas = static_engine::static_cells_to_areas(&cells);
+            eprintln!("   Range({}).SpecialCells(Type:={}, Value:={:?}) -> {} area(s)", address, cell_type, value_type, areas.len());
+            Ok(Value::String(format!("Range:{}", range::areas_address(&areas))))
         }
         
         // ====================================================================
@@ -492,6 +543,61 @@ pub fn call_range_method(address: &str, method: &str, args: &[Value]) -> Result<
             Ok(Value::String(format!("Range:{}", new_address)))
         }
         
+        "address" => {
+            // Address([RowAbsolute], [ColumnAbsolute], [ReferenceStyle], [External], [RelativeTo])
+            // R1C1 relative references are measured from RelativeTo (default A1)
+            let flag = |i: usize| args.get(i).filter(|v| !matches!(v, Value::Empty)).map(value_to_bool).unwrap_or(true);
+            let style = args.get(2).map(value_to_int).unwrap_or(formula::XL_A1 as i64) as i32;
+            let base = match args.get(4).and_then(objects::object_ref) {
+                Some((object_type, relative_to)) if object_type == "range" => get_range_bounds(&relative_to)?.0,
+                _ => (0, 0),
+            };
+            let format = |row: i32, col: i32| {
+                let cell = CellRef { row, col, row_absolute: flag(0), col_absolute: flag(1) };
+                if style == formula::XL_R1C1 { cell.to_r1c1(base) } else { cell.to_a1() }
+            };
+            let text = range::address_areas(address)?.iter()
+                .map(|&(start_row, start_col, end_row, end_col)| {
+                    if (start_row, start_col) == (end_row, end_col) {
+                        format(start_row, start_col)
+                    } else {
+                        format!("{}:{}", format(start_row, start_col), format(end_row, end_col))
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            Ok(Value::String(text))
+        }
+        
+        "end" => {
+            // End(Direction) - the cell Ctrl+Arrow reaches from the top-left cell
+            // Direction: xlUp(-4162), xlDown(-4121), xlToLeft(-4159), xlToRight(-4161)
+            let Some(direction) = args.first().map(value_to_int) else {
+                bail!("Range.End requires a Direction");
+            };
+            let ((row, col), _) = get_range_bounds(address)?;
+            let (end_row, end_col) = static_engine::static_get_end(&engine::get_active_sheet(), row, col, direction as i32);
+            Ok(Value::String(format!("Range:{}", indices_to_address(end_row, end_col))))
+        }
+        
+        "cells" | "item" => {
+            // Cells(RowIndex, [ColumnIndex]) - relative to the top-left cell;
+            // a single index counts across the rows of the range
+            let ((start_row, start_col), (_, end_col)) = get_range_bounds(address)?;
+            let (row, col) = match (args.first().map(value_to_int), args.get(1).map(value_to_int)) {
+                (Some(row), Some(col)) => (start_row + row as i32 - 1, start_col + col as i32 - 1),
+                (Some(index), None) if index >= 1 => {
+                    let width = (end_col - start_col + 1) as i64;
+                    (start_row + ((index - 1) / width) as i32, start_col + ((index - 1) % width) as i32)
+                }
+                _ => bail!("Range.Cells requires a row and column index"),
+            };
+            if row < 0 || col < 0 || row > MAX_ROW || col > MAX_COL {
+                bail!("Range.Cells({:?}) is outside the sheet", args);
+            }
+            Ok(Value::String(format!("Range:{}", indices_to_address(row, col))))
+        }
+        
         // ====================================================================
         // AUTOFIT
         // ====================================================================
@@ -518,22 +624,13 @@ pub fn call_range_method(address: &str, method: &str, args: &[Value]) -> Result<
 
 /// Get the start and end indices of a range
 /// Returns ((start_row, start_col), (end_row, end_col))
+/// Entire rows ("2:2") and columns ("C:C") span the whole sheet; a
+/// multi-area range ("A1:A5,C1:C5") uses its first area
 fn get_range_bounds(address: &str) -> Result<((i32, i32), (i32, i32))> {
-    if let Some(colon_pos) = address.find(':') {
-        let start = &address[..colon_pos];
-        let end = &address[colon_pos + 1..];
-        
-        let start_pos = engine::address_to_indices(start)
-            .map_err(|e| anyhow::anyhow!("{}", e))?;
-        let end_pos = engine::address_to_indices(end)
-            .map_err(|e| anyhow::anyhow!("{}", e))?;
-        
-        Ok((start_pos, end_pos))
-    } else {
-        let pos = engine::address_to_indices(address)
-            .map_err(|e| anyhow::anyhow!("{}", e))?;
-        Ok((pos, pos))
-    }
+    let first = address.split(',').next().unwrap_or(address);
+    let (start_row, start_col, end_row, end_col) = engine::address_to_area(first)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    Ok(((start_row, start_col), (end_row, end_col)))
 }
 
 /// Convert (row, col) to Excel address
@@ -612,3 +709,23 @@ mod tests {
         assert_eq!(value_to_string(&Value::Integer(42)), "42");
     }
 }
+
+/// Call method on a Rows or Columns collection (Range.Rows, Worksheet.Columns)
+/// Data format: the range address; other methods act on the whole range
+pub fn call_rows_columns_method(kind: &str, address: &str, method: &str, args: &[Value]) -> Result<Value> {
+    if !matches!(&*lower(method), "item") {
+        return call_range_method(address, method, args);
+    }
+    // Item(Index) - the Index-th row/column of the range, 1-based
+    let index = args.first().map(value_to_int).unwrap_or(0) as i32;
+    let ((start_row, start_col), (end_row, end_col)) = get_range_bounds(address)?;
+    let area = if kind.eq_ignore_ascii_case("rows") {
+        (start_row + index - 1, start_col, start_row + index - 1, end_col)
+    } else {
+        (start_row, start_col + index - 1, end_row, start_col + index - 1)
+    };
+    if index < 1 || area.2 > MAX_ROW || area.3 > MAX_COL {
+        return Err(VbaErr::SubscriptOutOfRange.error(format!("{}.Item({})", kind, index)));
+    }
+    Ok(objects::range_object(&range::areas_address(&[area])))
+}
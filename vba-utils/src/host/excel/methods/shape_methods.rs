@@ -0,0 +1,93 @@
+// src/host/excel/methods/shape_methods.rs
+// Method handlers for Shapes collection, Shape and TextFrame objects
+// Shapes is accessed via Worksheet.Shapes; shapes are kept in the static
+// engine so their names, positions and text persist (nothing is rendered)
+
+use anyhow::{Result, bail};
+use crate::context::Value;
+use crate::host::excel::{engine, static_engine};
+use crate::host::excel::static_engine::ShapeInfo;
+use crate::symbol::lower;
+
+/// Call method on Shapes collection
+/// Data format: sheet name ("Sheet1")
+pub fn call_shapes_method(data: &str, method: &str, args: &[Value]) -> Result<Value> {
+    let sheet = if data.is_empty() { engine::get_active_sheet() } else { data.to_string() };
+
+    match &*lower(method) {
+        "addshape" | "addtextbox" => {
+            // AddShape(Type, Left, Top, Width, Height)
+            // AddTextbox(Orientation, Left, Top, Width, Height)
+            let number = |i: usize| args.get(i).and_then(|v| v.as_string().parse::<f64>().ok());
+            let (Some(left), Some(top), Some(width), Some(height)) = (number(1), number(2), number(3), number(4)) else {
+                bail!("Shapes.{} requires Left, Top, Width and Height", method);
+            };
+            let shape = if lower(method) == "addtextbox" {
+                // msoTextBox, drawn as a rectangle
+                static_engine::static_add_shape(&sheet, 17, 1, left, top, width, height)
+            } else {
+                let auto_shape_type = number(0).ok_or_else(|| anyhow::anyhow!("Shapes.AddShape requires Type"))? as i32;
+                static_engine::static_add_shape(&sheet, 1, auto_shape_type, left, top, width, height)
+            };
+            eprintln!("Shapes.{} -> {} on {}", method, shape.name, sheet);
+            Ok(shape_ref(&shape))
+        }
+        "item" => {
+            // Item(Index) - 1-based z-order position, or shape name
+            let key = args.first().cloned().unwrap_or(Value::Empty);
+            let shape = match &key {
+                Value::String(name) => static_engine::static_find_shape(&sheet, name),
+                other => other.as_string().parse::<usize>().ok()
+                    .and_then(|i| i.checked_sub(1))
+                    .and_then(|i| static_engine::static_list_shapes(&sheet).into_iter().nth(i)),
+            };
+            match shape {
+                Some(shape) => Ok(shape_ref(&shape)),
+                None => bail!("Shapes.Item({}): the item with the specified name wasn't found", key.as_string()),
+            }
+        }
+        "count" => Ok(Value::Integer(static_engine::static_list_shapes(&sheet).len() as i64)),
+        _ => Err(anyhow::anyhow!("Unknown Shapes method: {}", method)),
+    }
+}
+
+/// Call method on Shape object
+/// Data format: "Sheet1!<id>"
+pub fn call_shape_method(data: &str, method: &str, args: &[Value]) -> Result<Value> {
+    let (sheet, id) = parse_shape(data)?;
+    let amount = args.first().and_then(|v| v.as_string().parse::<f64>().ok()).unwrap_or(0.0);
+
+    let found = match &*lower(method) {
+        "delete" => static_engine::static_delete_shape(&sheet, id),
+        "select" => static_engine::static_get_shape(&sheet, id).is_some(),
+        "incrementleft" => static_engine::static_update_shape(&sheet, id, |s| s.left += amount),
+        "incrementtop" => static_engine::static_update_shape(&sheet, id, |s| s.top += amount),
+        _ => return Err(anyhow::anyhow!("Unknown Shape method: {}", method)),
+    };
+    if !found {
+        bail!("Shape {} was deleted", data);
+    }
+    Ok(Value::Empty)
+}
+
+/// Call method on TextFrame object
+/// Data format: "Sheet1!<id>" of the owning shape
+pub fn call_textframe_method(data: &str, method: &str, _args: &[Value]) -> Result<Value> {
+    match &*lower(method) {
+        // Characters([Start], [Length]) - the whole text is used
+        "characters" => Ok(Value::String(format!("TextFrame:{}", data))),
+        _ => Err(anyhow::anyhow!("Unknown TextFrame method: {}", method)),
+    }
+}
+
+/// Object reference for a stored shape
+pub(crate) fn shape_ref(shape: &ShapeInfo) -> Value {
+    Value::String(format!("Shape:{}!{}", shape.sheet_name, shape.id))
+}
+
+/// Split a Shape reference ("Sheet1!3") into sheet and id
+pub(crate) fn parse_shape(data: &str) -> Result<(String, i32)> {
+    let (sheet, id) = data.rsplit_once('!').ok_or_else(|| anyhow::anyhow!("Invalid Shape reference: {}", data))?;
+    let id = id.parse().map_err(|_| anyhow::anyhow!("Invalid Shape reference: {}", data))?;
+    Ok((sheet.to_string(), id))
+}
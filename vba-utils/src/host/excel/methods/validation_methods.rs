@@ -0,0 +1,57 @@
+// src/host/excel/methods/validation_methods.rs
+// Method handlers for Validation object
+// Validation is accessed via Range.Validation and applies to every cell of the range
+
+use anyhow::{Result, bail};
+use crate::context::Value;
+use crate::host::excel::{engine, static_engine};
+use crate::symbol::lower;
+
+/// Call method on Validation object
+/// Data format: range address ("A2:A100") on the active sheet
+pub fn call_validation_method(data: &str, method: &str, args: &[Value]) -> Result<Value> {
+    let sheet = engine::get_active_sheet();
+    let (start_row, start_col, end_row, end_col) = engine::address_to_area(data).map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    match &*lower(method) {
+        "add" | "modify" => {
+            // Add(Type, [AlertStyle], [Operator], [Formula1], [Formula2])
+            // Type: xlValidateList(3), xlValidateWholeNumber(1), xlValidateDecimal(2), ...
+            let is_add = lower(method) == "add";
+            let arg = |i: usize| args.get(i).filter(|v| !matches!(v, Value::Empty));
+            let int = |i: usize, default: i32| arg(i).and_then(|v| v.as_string().parse::<f64>().ok()).map_or(default, |n| n as i32);
+            let existing = static_engine::static_get_validation(&sheet, start_row, start_col);
+            if is_add && existing.is_some() {
+                bail!("Range({}) already has data validation (use Modify or Delete first)", data);
+            }
+            let previous = existing.unwrap_or_default();
+            let validation_type = if is_add {
+                arg(0).map(|_| int(0, 0)).ok_or_else(|| anyhow::anyhow!("Validation.Add requires Type"))?
+            } else {
+                int(0, previous.validation_type)
+            };
+            let alert_style = int(1, previous.alert_style);
+            let operator = int(2, previous.operator);
+            let formula1 = arg(3).map_or(previous.formula1.clone(), |v| v.as_string());
+            let formula2 = arg(4).map(|v| v.as_string()).or(previous.formula2.clone());
+
+            for row in start_row..=end_row {
+                for col in start_col..=end_col {
+                    static_engine::static_set_validation(&sheet, row, col, validation_type, &formula1, formula2.as_deref(), operator);
+                    static_engine::static_update_validation(&sheet, row, col, |v| v.alert_style = alert_style);
+                }
+            }
+            eprintln!("Validation({}).{}(Type:={}, Formula1:='{}')", data, method, validation_type, formula1);
+            Ok(Value::Empty)
+        }
+        "delete" => {
+            for row in start_row..=end_row {
+                for col in start_col..=end_col {
+                    static_engine::static_delete_validation(&sheet, row, col);
+                }
+            }
+            Ok(Value::Empty)
+        }
+        _ => Err(anyhow::anyhow!("Unknown Validation method: {}", method)),
+    }
+}
@@ -0,0 +1,48 @@
+// src/host/excel/methods/window_methods.rs
+// Method handlers for Window object (ActiveWindow)
+
+use anyhow::Result;
+use crate::context::Value;
+use crate::host::excel::engine;
+use crate::symbol::lower;
+
+/// Call method on Window object
+/// Data format: window index ("1")
+pub fn call_window_method(_data: &str, method: &str, args: &[Value]) -> Result<Value> {
+    let count = |i: usize| args.get(i).and_then(|v| v.as_string().parse::<f64>().ok()).unwrap_or(0.0) as i32;
+
+    match &*lower(method) {
+        "smallscroll" => {
+            // SmallScroll([Down], [Up], [ToRight], [ToLeft]) - in rows/columns
+            let (down, right) = (count(0) - count(1), count(2) - count(3));
+            engine::update_window(|w| {
+                w.scroll_row = (w.scroll_row + down).max(1);
+                w.scroll_column = (w.scroll_column + right).max(1);
+            });
+            Ok(Value::Empty)
+        }
+        "activate" => Ok(Value::Empty),
+        _ => Err(anyhow::anyhow!("Unknown Window method: {}", method)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::excel::static_engine::StaticWorkbook;
+
+    #[test]
+    fn test_small_scroll() {
+        let _workbook = StaticWorkbook::new().enter();
+        call_window_method("1", "SmallScroll", &[Value::Integer(5), Value::Empty, Value::Integer(2)]).unwrap();
+        // Scrolling up past the first row stops there
+        call_window_method("1", "SmallScroll", &[Value::Empty, Value::Integer(9)]).unwrap();
+        let window = engine::window_state();
+        assert_eq!((window.scroll_row, window.scroll_column), (1, 3));
+    }
+
+    #[test]
+    fn test_unknown_window_method() {
+        assert!(call_window_method("1", "LargeScroll", &[]).is_err());
+    }
+}
@@ -0,0 +1,69 @@
+// src/host/excel/methods/workbook_methods.rs
+// Method handlers for Workbook object
+
+use anyhow::Result;
+use crate::context::{Context, Value};
+use crate::host::dry_run::{self, Mutation};
+use crate::host::excel::engine;
+use crate::symbol::lower;
+use crate::vba_errors::VbaErr;
+
+/// Call method on Workbook object
+pub fn call_workbook_method(data: &str, method: &str, args: &[Value], ctx: &mut Context) -> Result<Value> {
+    let name = data.split(':').next().filter(|n| !n.is_empty()).unwrap_or("Book1");
+
+    match &*lower(method) {
+        "close" => {
+            // Close([SaveChanges], [Filename], [RouteWorkbook])
+            // Without SaveChanges Excel asks; DisplayAlerts = False closes without saving
+            let save = match args.first() {
+                Some(Value::Boolean(b)) => *b,
+                Some(Value::Empty) | None => {
+                    engine::confirm(&format!("Do you want to save the changes you made to '{}'?", name), false)
+                }
+                Some(other) => other.as_string().parse::<f64>().map(|n| n != 0.0).unwrap_or(false),
+            };
+            if save {
+                save_workbook(ctx, name, args.get(1).filter(|a| !matches!(a, Value::Empty)).map(Value::as_string));
+            }
+            eprintln!("Closing workbook: {}", name);
+            Ok(Value::Empty)
+        }
+        "save" => {
+            save_workbook(ctx, name, None);
+            Ok(Value::Empty)
+        }
+        "saveas" | "savecopyas" => {
+            // SaveAs([Filename], ...) / SaveCopyAs(Filename)
+            save_workbook(ctx, name, args.first().filter(|a| !matches!(a, Value::Empty)).map(Value::as_string));
+            Ok(Value::Empty)
+        }
+        "activate" => {
+            eprintln!("Activated workbook: {}", name);
+            Ok(Value::Empty)
+        }
+        _ => Err(anyhow::anyhow!("Unknown Workbook method: {}", method)),
+    }
+}
+
+/// Planned in a dry run (see `host::dry_run`); nothing is written otherwise
+fn save_workbook(ctx: &Context, name: &str, path: Option<String>) {
+    let save = Mutation::SaveWorkbook { workbook: name.to_string(), path: path.clone() };
+    if !dry_run::record(ctx, save) {
+        eprintln!("Saving workbook: {} {}- NOT YET IMPLEMENTED", name, path.map(|p| format!("as {} ", p)).unwrap_or_default());
+    }
+}
+
+/// Call method on the Workbooks collection; the open workbook is the only
+/// member
+pub fn call_workbooks_method(_data: &str, method: &str, args: &[Value]) -> Result<Value> {
+    match &*lower(method) {
+        "item" => match args.first() {
+            Some(Value::String(_)) => Ok(Value::String("Workbook:".into())),
+            Some(index) if index.as_integer() == Some(1) => Ok(Value::String("Workbook:".into())),
+            index => Err(VbaErr::SubscriptOutOfRange.error(format!("Workbooks({:?})", index))),
+        },
+        "count" => Ok(Value::Integer(1)),
+        _ => Err(anyhow::anyhow!("Unknown Workbooks method: {}", method)),
+    }
+}
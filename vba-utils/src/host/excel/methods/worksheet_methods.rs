@@ -3,20 +3,35 @@
 
 use anyhow::Result;
 use crate::context::Value;
+use crate::host::excel::{engine, objects};
+use crate::host::excel::methods::range_methods;
+use crate::host::excel::objects::range;
+use crate::host::excel::static_engine::{self, SheetProtection, MAX_COL, MAX_ROW};
+use crate::symbol::lower;
+use crate::vba_errors::VbaErr;
 
 /// Call method on Worksheet object
-pub fn call_worksheet_method(data: &str, method: &str, _args: &[Value]) -> Result<Value> {
+pub fn call_worksheet_method(data: &str, method: &str, args: &[Value]) -> Result<Value> {
     let parts: Vec<&str> = data.split(':').collect();
     let name = parts.get(0).copied().unwrap_or("Sheet");
     
-    match method.to_lowercase().as_str() {
-        "activate" => {
-            eprintln!("Activated worksheet: {}", name);
+    match &*lower(method) {
+        "activate" | "select" => {
+            if !name.is_empty() {
+                engine::set_active_sheet(name.to_string());
+            }
+            eprintln!("Activated worksheet: {}", engine::get_active_sheet());
             Ok(Value::Empty)
         }
         "delete" => {
+            // Excel asks before deleting a sheet; DisplayAlerts = False skips the prompt
+            let prompt = format!("Microsoft Excel will permanently delete sheet '{}'. Continue?", name);
+            if !engine::confirm(&prompt, true) {
+                eprintln!("Delete of worksheet {} cancelled", name);
+                return Ok(Value::Boolean(false));
+            }
             eprintln!("Deleting worksheet: {} - NOT YET IMPLEMENTED", name);
-            Ok(Value::Empty)
+            Ok(Value::Boolean(true))
         }
         "copy" => {
             eprintln!("Copying worksheet: {} - NOT YET IMPLEMENTED", name);
@@ -26,10 +41,96 @@ pub fn call_worksheet_method(data: &str, method: &str, _args: &[Value]) -> Resul
             eprintln!("Moving worksheet: {} - NOT YET IMPLEMENTED", name);
             Ok(Value::Empty)
         }
-        "select" => {
-            eprintln!("Selecting worksheet: {}", name);
+        "protect" => {
+            // Protect([Password], [DrawingObjects], [Contents], [Scenarios], [UserInterfaceOnly],
+            //         [AllowFormattingCells], [AllowFormattingColumns], [AllowFormattingRows],
+            //         [AllowInsertingColumns], [AllowInsertingRows], [AllowInsertingHyperlinks],
+            //         [AllowDeletingColumns], [AllowDeletingRows], ...)
+            let sheet = sheet_name(name);
+            let protection = SheetProtection {
+                password: args.first().map(Value::as_string).unwrap_or_default(),
+                contents: bool_arg(args, 2, true),
+                user_interface_only: bool_arg(args, 4, false),
+                allow_inserting_columns: bool_arg(args, 8, false),
+                allow_inserting_rows: bool_arg(args, 9, false),
+                allow_deleting_columns: bool_arg(args, 11, false),
+                allow_deleting_rows: bool_arg(args, 12, false),
+            };
+            static_engine::static_protect_sheet(&sheet, protection)
+                .map_err(|e| VbaErr::ApplicationDefined.message(e))?;
+            eprintln!("Protected worksheet: {}", sheet);
+            Ok(Value::Empty)
+        }
+        "range" => {
+            // Range(Cell1, [Cell2]) - addresses are on the active sheet
+            let address = |arg: &Value| match objects::object_ref(arg) {
+                Some((object_type, address)) if object_type == "range" => address,
+                _ => arg.as_string(),
+            };
+            match (args.first(), args.get(1)) {
+                (Some(cell1), Some(cell2)) => Ok(objects::range_object(&format!("{}:{}", address(cell1), address(cell2)))),
+                (Some(cell1), None) => Ok(objects::range_object(&address(cell1))),
+                _ => Err(VbaErr::WrongNumberOfArguments.error("Worksheet.Range requires an address")),
+            }
+        }
+        "cells" => {
+            // Cells(RowIndex, [ColumnIndex]) on the whole sheet
+            let whole_sheet = range::areas_address(&[(0, 0, MAX_ROW, MAX_COL)]);
+            let cell = range_methods::call_range_method(&whole_sheet, "Cells", args)?;
+            Ok(objects::object_ref(&cell).map(|(_, address)| objects::range_object(&address)).unwrap_or(cell))
+        }
+        "unprotect" => {
+            // Unprotect([Password])
+            let sheet = sheet_name(name);
+            let password = args.first().map(Value::as_string).unwrap_or_default();
+            static_engine::static_unprotect_sheet(&sheet, &password)
+                .map_err(|e| VbaErr::ApplicationDefined.message(e))?;
+            eprintln!("Unprotected worksheet: {}", sheet);
             Ok(Value::Empty)
         }
         _ => Err(anyhow::anyhow!("Unknown Worksheet method: {}", method)),
     }
 }
+
+/// Call method on the Worksheets (or Sheets) collection of the workbook
+pub fn call_worksheets_method(_data: &str, method: &str, args: &[Value]) -> Result<Value> {
+    match &*lower(method) {
+        "item" => {
+            // Item(Index) - 1-based position or sheet name
+            let names = engine::sheet_names();
+            let name = match args.first() {
+                Some(Value::String(wanted)) => names.iter().find(|n| n.eq_ignore_ascii_case(wanted)),
+                Some(index) => index.as_integer().and_then(|i| usize::try_from(i).ok()?.checked_sub(1)).and_then(|i| names.get(i)),
+                None => None,
+            };
+            match name {
+                Some(name) => Ok(Value::String(format!("Worksheet:{}::", name))),
+                None => Err(VbaErr::SubscriptOutOfRange.error(format!("Worksheets({:?})", args.first()))),
+            }
+        }
+        "count" => Ok(Value::Integer(engine::sheet_names().len() as i64)),
+        _ => Err(anyhow::anyhow!("Unknown Worksheets method: {}", method)),
+    }
+}
+
+/// Sheet name from the worksheet data; empty data is the active sheet
+fn sheet_name(name: &str) -> String {
+    if name.is_empty() {
+        engine::get_active_sheet()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Optional Boolean argument at `index`; missing or Empty uses `default`
+fn bool_arg(args: &[Value], index: usize, default: bool) -> bool {
+    match args.get(index) {
+        None | Some(Value::Empty) => default,
+        Some(Value::Boolean(b)) => *b,
+        Some(Value::Integer(i)) => *i != 0,
+        Some(Value::Long(i)) => *i != 0,
+        Some(Value::Double(d)) => *d != 0.0,
+        Some(Value::String(s)) => s.eq_ignore_ascii_case("true") || s == "1",
+        Some(_) => default,
+    }
+}
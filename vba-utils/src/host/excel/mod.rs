@@ -1,13 +1,22 @@
 // src/host/excel/mod.rs
 
+pub mod async_backend;
+pub mod backend;
+pub mod diff;
 pub mod engine;
+pub mod fixture;
 pub mod static_engine;
+pub mod write_buffer;
+pub mod formula;
+pub mod locale;
 pub mod properties;
 pub mod methods;
 pub mod objects;
 
 use std::cell::RefCell;
+use std::fmt;
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
 
 use crate::context::Context;
 use crate::host::ComObjectHandle;
@@ -15,16 +24,57 @@ use crate::host::ComObjectHandle;
 use self::objects::application::ExcelApplication;
 
 /// Initialize the Excel host environment and register default COM objects.
+/// The backend and settings go to the engine state of the context's
+/// workbook, so they only apply while it is entered.
 pub fn initialize_excel_host(ctx: &mut Context) {
+    let _workbook = ctx.static_workbook.enter();
     // Initialize the Excel engine
     // Paths to resource files and app cache
     let resource_path = "/Users/poornema-13898/Downloads/SamplePOCMacro/resources";
     let local_path = "/Users/poornema-13898/Downloads/SamplePOCMacro/AppLocal";
     
-    match engine::initialize_engine(resource_path, local_path) {
-        Ok(_) => eprintln!("✅ Excel engine initialized"),
-        Err(e) => eprintln!("⚠️  Failed to initialize Excel engine: {}", e),
+    let backend: Option<backend::SharedExcelBackend> = match &ctx.runtime_config.excel_backend {
+        backend::ExcelBackendKind::Native => match engine::initialize_engine(resource_path, local_path) {
+            Ok(_) => {
+                eprintln!("✅ Excel engine initialized");
+                engine::get_workbook_id().map(|id| {
+                    Arc::new(Mutex::new(backend::NativeBackend::new(id))) as backend::SharedExcelBackend
+                })
+            }
+            Err(e) => {
+                eprintln!("⚠️  Failed to initialize Excel engine: {}", e);
+                None
+            }
+        },
+        backend::ExcelBackendKind::Static => Some(Arc::new(Mutex::new(backend::StaticBackend::default()))),
+        backend::ExcelBackendKind::Custom(backend) => Some(backend.clone()),
+        backend::ExcelBackendKind::Async(backend) => {
+            Some(Arc::new(Mutex::new(async_backend::AsyncBackendAdapter::new(backend.clone()))))
+        }
+    };
+    // A journaled run records what its writes replace, and a dry run plans
+    // them in front of that
+    let backend = match ctx.active_journal.clone() {
+        Some(journal) => backend.map(|b| {
+            Arc::new(Mutex::new(crate::host::journal::JournalBackend::new(b, journal))) as backend::SharedExcelBackend
+        }),
+        None => backend,
+    };
+    let backend = match ctx.dry_run_plan.clone() {
+        Some(plan) => {
+            Some(Arc::new(Mutex::new(crate::host::dry_run::DryRunBackend::new(backend, plan))) as backend::SharedExcelBackend)
+        }
+        None => backend,
+    };
+    engine::install_backend(backend);
+    if let Err(e) = engine::set_write_batching(ctx.runtime_config.write_batching) {
+        eprintln!("⚠️  Failed to configure write batching: {}", e);
     }
+    let cell_locale = match ctx.runtime_config.locale_cell_parsing {
+        true => locale::CellLocale::for_locale(&ctx.runtime_config.locale).unwrap_or_default(),
+        false => locale::CellLocale::default(),
+    };
+    engine::set_cell_locale(cell_locale);
     
     // Register global Excel.Application
     let app: ComObjectHandle = Rc::new(RefCell::new(ExcelApplication::new()));
@@ -32,4 +82,73 @@ pub fn initialize_excel_host(ctx: &mut Context) {
 
     // If you later want aliases like "Excel.Application", you can register them here
     // using ctx.com_registry.get_global("Application") and re-inserting.
-}
\ No newline at end of file
+}
+
+/// Counterpart of [`initialize_excel_host`]: flushes pending writes, releases
+/// the backend and resets the engine (see `engine::shutdown_engine`), empties
+/// the context's in-memory workbook and unregisters `Application`. Call it
+/// between runs that share a process; a failed flush is returned after the
+/// teardown has finished.
+pub fn shutdown_excel_host(ctx: &mut Context) -> Result<(), String> {
+    ctx.com_registry.unregister_global("Application");
+    let _workbook = ctx.static_workbook.enter();
+    teardown()
+}
+
+fn teardown() -> Result<(), String> {
+    let result = engine::shutdown_engine();
+    static_engine::static_reset();
+    result
+}
+
+/// Tears the Excel host down when dropped, so an embedder's run that returns
+/// early or panics still leaves nothing behind for the next one. Only the
+/// context's workbook is emptied; the context itself is not touched.
+#[must_use = "the Excel host is torn down when the guard is dropped"]
+#[derive(Debug)]
+pub struct ExcelHostGuard {
+    workbook: static_engine::StaticWorkbook,
+}
+
+impl ExcelHostGuard {
+    pub fn new(ctx: &Context) -> Self {
+        Self { workbook: ctx.static_workbook.clone() }
+    }
+}
+
+impl Drop for ExcelHostGuard {
+    fn drop(&mut self) {
+        let _workbook = self.workbook.enter();
+        if let Err(e) = teardown() {
+            eprintln!("⚠️  Failed to flush Excel writes at shutdown: {}", e);
+        }
+    }
+}
+
+/// A runtime error with a specific VBA error number (e.g. 1004 for a write
+/// to a protected cell). Other host errors are reported with the caller's
+/// default number.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExcelError {
+    pub number: i32,
+    pub description: String,
+}
+
+impl ExcelError {
+    pub fn raise(number: i32, description: impl Into<String>) -> anyhow::Error {
+        anyhow::Error::new(Self { number, description: description.into() })
+    }
+}
+
+impl fmt::Display for ExcelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+impl std::error::Error for ExcelError {}
+
+/// The VBA error number carried by a host error, if it has one
+pub fn error_number(e: &anyhow::Error) -> Option<i32> {
+    e.downcast_ref::<ExcelError>().map(|e| e.number)
+}
@@ -85,4 +85,8 @@ impl ComObject for ExcelApplication {
     fn type_name(&self) -> &str {
         "Application"
     }
+
+    fn default_member(&self) -> Option<&str> {
+        Some("Name")
+    }
 }
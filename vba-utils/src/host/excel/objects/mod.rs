@@ -37,6 +37,67 @@ pub fn dispatch_method_call(
     object_data: &str,
     method: &str,
     args: &[Value],
+    ctx: &mut Context,
 ) -> Result<Value> {
-    super::methods::call_method(object_type, object_data, method, args)
+    let name = format!("{}.{}", object_type, method);
+    crate::vm::hooks::host_call(ctx, crate::vm::HostCallKind::Method, &name, args)?;
+    super::methods::call_method(object_type, object_data, method, args, ctx)
+}
+
+/// Objects returned as plain "Type:data" strings by host properties and
+/// methods (Range.Offset, Range.Comment, Range.Validation, Range.Areas,
+/// Range.Rows, Hyperlinks, Shapes, pivots, ActiveWindow, the Workbooks and
+/// Worksheets collections) that have their own dispatch handlers
+const SUB_OBJECT_TYPES: &[&str] = &[
+    "Workbooks", "Workbook", "Worksheets", "Worksheet", "Range", "Rows", "Columns", "Comment", "Validation", "Areas", "Hyperlinks", "Hyperlink", "Shapes", "Shape", "TextFrame",
+    "PivotCaches", "PivotCache", "PivotTables", "PivotTable", "PivotField", "Window",
+];
+
+/// Default member of an Excel object type: Value for a range, Item when a
+/// range, row/column set or collection is indexed (`rng(2, 1)`,
+/// `Worksheets(1)`). None for the rest.
+pub fn default_member(object_type: &str, indexed: bool) -> Option<&'static str> {
+    match &*crate::symbol::lower(object_type) {
+        "range" if !indexed => Some("Value"),
+        "range" | "rows" | "columns" | "areas" | "hyperlinks" | "shapes" | "worksheets" | "workbooks" | "pivotcaches"
+        | "pivottables"
+            if indexed =>
+        {
+            Some("Item")
+        }
+        _ => None,
+    }
+}
+
+/// Range object for an address on the active sheet, as returned by Range("A1")
+pub fn range_object(address: &str) -> Value {
+    Value::Object(Some(Box::new(Value::String(format!("Range:{}", address)))))
+}
+
+/// Split an Excel object reference into its dispatch type and data
+/// ("Range:A1" objects, "Comment:B2" / "Validation:A2:A10" sub-objects, and
+/// the ActiveSheet / ActiveWorkbook / Application globals).
+/// Returns None for values that are not Excel object references.
+pub fn object_ref(value: &Value) -> Option<(String, String)> {
+    let (reference, known): (&str, &[&str]) = match value {
+        Value::Object(Some(inner)) => match inner.as_ref() {
+            Value::String(s) => {
+                let global = [("ActiveSheet", "worksheet"), ("ActiveWorkbook", "workbook"), ("Application", "application")]
+                    .into_iter()
+                    .find(|(name, _)| s.eq_ignore_ascii_case(name));
+                if let Some((_, object_type)) = global {
+                    return Some((object_type.to_string(), String::new()));
+                }
+                (s, &["Range"])
+            }
+            _ => return None,
+        },
+        Value::String(s) => (s, SUB_OBJECT_TYPES),
+        _ => return None,
+    };
+    let (object_type, data) = reference.split_once(':')?;
+    known
+        .iter()
+        .any(|t| t.eq_ignore_ascii_case(object_type))
+        .then(|| (object_type.to_ascii_lowercase(), data.to_string()))
 }
@@ -23,6 +23,7 @@ use anyhow::Result;
 use crate::context::{Context, Value};
 use crate::host::ComObject;
 use crate::host::excel::{engine, properties, methods};
+use crate::host::excel::static_engine::{Area, MAX_COL, MAX_ROW};
 
 /// Excel Range Object
 /// 
@@ -231,6 +232,104 @@ pub fn column_index_to_letter(col: i32) -> String {
     result
 }
 
+/// Address of `Rows(index)` / `Columns(index)`: Rows(2) → "2:2",
+/// Columns("C") or Columns(3) → "C:C"; "2:4" and "B:D" pass through.
+pub fn rows_columns_address(collection: &str, index: &Value) -> Option<String> {
+    let text = match index {
+        Value::String(s) => s.trim().to_string(),
+        Value::Integer(n) | Value::LongLong(n) => n.to_string(),
+        Value::Long(n) => n.to_string(),
+        Value::Double(n) => (n.round() as i64).to_string(),
+        _ => return None,
+    };
+    if text.contains(':') {
+        return Some(text);
+    }
+    if collection.eq_ignore_ascii_case("Rows") {
+        let row: i32 = text.parse().ok().filter(|r| *r >= 1)?;
+        Some(format!("{}:{}", row, row))
+    } else {
+        let letters = match text.parse::<i32>() {
+            Ok(col) if col >= 1 => column_index_to_letter(col - 1),
+            Ok(_) => return None,
+            Err(_) => text.to_uppercase(),
+        };
+        Some(format!("{}:{}", letters, letters))
+    }
+}
+
+/// Areas of a possibly discontiguous address: "A1:A5,C1:C5" →
+/// [(0, 0, 4, 0), (0, 2, 4, 2)]
+pub fn address_areas(address: &str) -> Result<Vec<Area>> {
+    address.split(',')
+        .map(|part| engine::address_to_area(part.trim()).map_err(|e| anyhow::anyhow!("{}", e)))
+        .collect()
+}
+
+/// Address of one or more areas, e.g. "A1:B5", "A1:A5,C1:C5" or "2:4"
+pub fn areas_address(areas: &[Area]) -> String {
+    areas.iter()
+        .map(|&(start_row, start_col, end_row, end_col)| {
+            if start_col == 0 && end_col == MAX_COL {
+                format!("{}:{}", start_row + 1, end_row + 1)
+            } else if start_row == 0 && end_row == MAX_ROW {
+                format!("{}:{}", column_index_to_letter(start_col), column_index_to_letter(end_col))
+            } else if (start_row, start_col) == (end_row, end_col) {
+                indices_to_address(start_row, start_col)
+            } else {
+                format!("{}:{}", indices_to_address(start_row, start_col), indices_to_address(end_row, end_col))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Application.Union: all areas, dropping areas inside another one and
+/// joining neighbours that line up into a single rectangle
+pub fn union_areas(mut areas: Vec<Area>) -> Vec<Area> {
+    let contains = |outer: &Area, inner: &Area| {
+        outer.0 <= inner.0 && outer.1 <= inner.1 && outer.2 >= inner.2 && outer.3 >= inner.3
+    };
+    loop {
+        let mut joined = None;
+        'search: for i in 0..areas.len() {
+            for j in 0..areas.len() {
+                let (a, b) = (areas[i], areas[j]);
+                if i == j {
+                    continue;
+                }
+                if contains(&a, &b) {
+                    joined = Some((i, j, a));
+                } else if a.1 == b.1 && a.3 == b.3 && a.2 + 1 == b.0 {
+                    joined = Some((i, j, (a.0, a.1, b.2, a.3)));
+                } else if a.0 == b.0 && a.2 == b.2 && a.3 + 1 == b.1 {
+                    joined = Some((i, j, (a.0, a.1, a.2, b.3)));
+                }
+                if joined.is_some() {
+                    break 'search;
+                }
+            }
+        }
+        let Some((keep, remove, area)) = joined else { return areas };
+        areas[keep] = area;
+        areas.remove(remove);
+    }
+}
+
+/// Application.Intersect of two ranges; empty when they don't overlap
+pub fn intersect_areas(first: &[Area], second: &[Area]) -> Vec<Area> {
+    let mut result = Vec::new();
+    for a in first {
+        for b in second {
+            let area = (a.0.max(b.0), a.1.max(b.1), a.2.min(b.2), a.3.min(b.3));
+            if area.0 <= area.2 && area.1 <= area.3 && !result.contains(&area) {
+                result.push(area);
+            }
+        }
+    }
+    result
+}
+
 /// Implement ComObject trait for Range
 impl ComObject for ExcelRange {
     fn get_property(&self, name: &str, ctx: &mut Context) -> Result<Value> {
@@ -248,6 +347,10 @@ impl ComObject for ExcelRange {
     fn type_name(&self) -> &str {
         "Range"
     }
+
+    fn default_member(&self) -> Option<&str> {
+        Some("Value")
+    }
 }
 
 // ============================================================================
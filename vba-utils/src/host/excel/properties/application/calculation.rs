@@ -2,30 +2,27 @@
 
 use anyhow::Result;
 use crate::context::Value;
+use crate::host::excel::methods::application::calculation::{self, CalculationMode};
+use crate::symbol::lower;
 
 pub fn get_property(property: &str) -> Result<Value> {
-    match property.to_lowercase().as_str() {
+    match &*lower(property) {
         "calculation" => {
-            // Values: Automatic, Manual, SemiAutomatic
-            Ok(Value::String("Automatic".to_string()))
+            // xlCalculationAutomatic (-4105), xlCalculationManual (-4135), xlCalculationSemiautomatic (2)
+            Ok(Value::Integer(calculation::calculation_mode().code()))
         }
         _ => Err(anyhow::anyhow!("Unknown calculation property: {}", property)),
     }
 }
 
 pub fn set_property(property: &str, value: Value) -> Result<()> {
-    match property.to_lowercase().as_str() {
+    match &*lower(property) {
         "calculation" => {
-            match value {
-                Value::String(s) => {
-                    eprintln!("⚙️ Application.Calculation = {}", s);
-                    match s.to_lowercase().as_str() {
-                        "automatic" | "manual" | "semiautomatic" => Ok(()),
-                        _ => Err(anyhow::anyhow!("Invalid calculation mode: {}", s)),
-                    }
-                }
-                _ => Err(anyhow::anyhow!("Calculation must be String")),
-            }
+            let mode = CalculationMode::from_value(&value)
+                .ok_or_else(|| anyhow::anyhow!("Invalid calculation mode: {}", value.as_string()))?;
+            eprintln!("⚙️ Application.Calculation = {:?}", mode);
+            calculation::set_calculation_mode(mode);
+            Ok(())
         }
         _ => Err(anyhow::anyhow!("Cannot set calculation property: {}", property)),
     }
@@ -2,9 +2,10 @@
 
 use anyhow::Result;
 use crate::context::Value;
+use crate::symbol::lower;
 
 pub fn get_property(property: &str) -> Result<Value> {
-    match property.to_lowercase().as_str() {
+    match &*lower(property) {
         "oncalculate" | "ondata" | "ondoubleclick" | "onentry" 
         | "onsheetactivate" | "onsheetdeactivate" => {
             Ok(Value::String(String::new()))
@@ -14,7 +15,7 @@ pub fn get_property(property: &str) -> Result<Value> {
 }
 
 pub fn set_property(property: &str, value: Value) -> Result<()> {
-    match property.to_lowercase().as_str() {
+    match &*lower(property) {
         "oncalculate" | "ondata" | "ondoubleclick" | "onentry" 
         | "onsheetactivate" | "onsheetdeactivate" => {
             match value {
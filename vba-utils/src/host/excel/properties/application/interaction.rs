@@ -1,53 +1,75 @@
 // Display, alerts, and event handling properties
 
 use anyhow::Result;
-use crate::context::Value;
-
-pub fn get_property(property: &str) -> Result<Value> {
-    match property.to_lowercase().as_str() {
-        "displayalerts" => {
-            // Get from engine if available
-            Ok(Value::Boolean(true))
-        }
-        "screenupdating" => {
-            Ok(Value::Boolean(true))
-        }
-        "enableevents" => {
-            Ok(Value::Boolean(true))
-        }
-        _ => Err(anyhow::anyhow!("Unknown interaction property: {}", property)),
+use crate::context::{Context, Value};
+use crate::host::com_object::ComResult;
+use crate::host::excel::engine;
+use crate::host::ComObject;
+
+/// Application's display and event switches; the state lives in the engine
+pub struct Interaction;
+
+impl Interaction {
+    fn display_alerts(&self, _ctx: &mut Context) -> ComResult<bool> {
+        Ok(engine::display_alerts())
+    }
+
+    // False: confirmations take their default answer without asking the host
+    fn set_display_alerts(&mut self, _ctx: &mut Context, on: bool) -> ComResult<()> {
+        engine::set_display_alerts(on);
+        Ok(())
+    }
+
+    fn screen_updating(&self, _ctx: &mut Context) -> ComResult<bool> {
+        Ok(engine::screen_updating())
     }
+
+    // False: cell changes are batched until it is turned back on
+    fn set_screen_updating(&mut self, _ctx: &mut Context, on: bool) -> ComResult<()> {
+        engine::set_screen_updating(on);
+        Ok(())
+    }
+
+    fn enable_events(&self, _ctx: &mut Context) -> ComResult<bool> {
+        Ok(true)
+    }
+
+    fn set_enable_events(&mut self, _ctx: &mut Context, _on: bool) -> ComResult<()> {
+        Ok(())
+    }
+}
+
+crate::com_object! {
+    impl ComObject for Interaction as "Application" {
+        prop DisplayAlerts: bool => display_alerts, set_display_alerts;
+        prop ScreenUpdating: bool => screen_updating, set_screen_updating;
+        prop EnableEvents: bool => enable_events, set_enable_events;
+    }
+}
+
+pub fn get_property(property: &str, ctx: &mut Context) -> Result<Value> {
+    Interaction.get_property(property, ctx)
+}
+
+pub fn set_property(property: &str, value: Value, ctx: &mut Context) -> Result<()> {
+    Interaction.set_property(property, value, ctx)
 }
 
-pub fn set_property(property: &str, value: Value) -> Result<()> {
-    match property.to_lowercase().as_str() {
-        "displayalerts" => {
-            match value {
-                Value::Boolean(b) => {
-                    eprintln!("📢 Application.DisplayAlerts = {}", b);
-                    Ok(())
-                }
-                _ => Err(anyhow::anyhow!("DisplayAlerts must be Boolean")),
-            }
-        }
-        "screenupdating" => {
-            match value {
-                Value::Boolean(b) => {
-                    eprintln!("📊 Application.ScreenUpdating = {}", b);
-                    Ok(())
-                }
-                _ => Err(anyhow::anyhow!("ScreenUpdating must be Boolean")),
-            }
-        }
-        "enableevents" => {
-            match value {
-                Value::Boolean(b) => {
-                    eprintln!("⚡ Application.EnableEvents = {}", b);
-                    Ok(())
-                }
-                _ => Err(anyhow::anyhow!("EnableEvents must be Boolean")),
-            }
-        }
-        _ => Err(anyhow::anyhow!("Cannot set interaction property: {}", property)),
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_switches_convert_like_booleans() {
+        let _engine = engine::tests::lock_engine();
+        let mut ctx = Context::new();
+        set_property("displayalerts", Value::Integer(0), &mut ctx).unwrap();
+        assert!(matches!(get_property("DisplayAlerts", &mut ctx), Ok(Value::Boolean(false))));
+        set_property("DisplayAlerts", Value::Boolean(true), &mut ctx).unwrap();
+        assert!(engine::display_alerts());
+
+        let err = set_property("ScreenUpdating", Value::String("often".into()), &mut ctx).unwrap_err();
+        assert_eq!(crate::host::excel::error_number(&err), Some(13));
+        assert!(engine::screen_updating());
     }
 }
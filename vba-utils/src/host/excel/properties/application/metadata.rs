@@ -2,9 +2,11 @@
 
 use anyhow::Result;
 use crate::context::Value;
+use crate::symbol::lower;
 
 pub fn get_property(property: &str) -> Result<Value> {
-    match property.to_lowercase().as_str() {
+    match &*lower(property) {
+        "name" => Ok(Value::String("Microsoft Excel".to_string())),
         "username" => Ok(Value::String("User".to_string())),
         "useremailid" => Ok(Value::String(String::new())),
         "creatorname" => Ok(Value::String(String::new())),
@@ -14,7 +16,7 @@ pub fn get_property(property: &str) -> Result<Value> {
 }
 
 pub fn set_property(property: &str, value: Value) -> Result<()> {
-    match property.to_lowercase().as_str() {
+    match &*lower(property) {
         "username" | "useremailid" | "creatorname" | "creatoremailid" => {
             match value {
                 Value::String(s) => {
@@ -6,23 +6,39 @@ pub mod calculation;
 pub mod metadata;
 pub mod events;
 pub mod references;
+pub mod selection;
 
 use anyhow::Result;
 use crate::context::{Context, Value};
+use crate::host::members;
+use crate::symbol::lower;
+
+/// Properties `get_property` routes, as VBA spells them
+pub const PROPERTIES: &[&str] = &[
+    "DisplayAlerts", "ScreenUpdating", "EnableEvents", "Calculation", "Name", "UserName", "UserEmailId",
+    "CreatorName", "CreatorEmailId", "OnCalculate", "OnData", "OnDoubleClick", "OnEntry", "OnSheetActivate",
+    "OnSheetDeactivate", "ReferenceStyle", "CutCopyMode", "ActiveSheet", "ActiveWorkbook", "ThisWorkbook",
+    "ActiveCell", "Selection", "ActiveWindow", "Workbooks", "Worksheets", "Sheets",
+];
+
+/// Error 438 for an Application member that is neither a property nor a
+/// method, suggesting the nearest one
+pub(crate) fn unknown_member(kind: &str, name: &str) -> anyhow::Error {
+    let methods = crate::host::excel::methods::application::METHODS;
+    members::unknown_member("Application", kind, name, PROPERTIES.iter().chain(methods).copied())
+}
 
 /// Route property get requests to specialized handlers
-pub fn get_property(property: &str, _ctx: &mut Context) -> Result<Value> {
-    match property.to_lowercase().as_str() {
+pub fn get_property(property: &str, ctx: &mut Context) -> Result<Value> {
+    match &*lower(property) {
         // Interaction properties
-        "displayalerts" => interaction::get_property(property),
-        "screenupdating" => interaction::get_property(property),
-        "enableevents" => interaction::get_property(property),
+        "displayalerts" | "screenupdating" | "enableevents" => interaction::get_property(property, ctx),
         
         // Calculation properties
         "calculation" => calculation::get_property(property),
         
         // Metadata properties
-        "username" | "useremailid" | "creatorname" | "creatoremailid" => metadata::get_property(property),
+        "name" | "username" | "useremailid" | "creatorname" | "creatoremailid" => metadata::get_property(property),
         
         // Event handlers
         "oncalculate" | "ondata" | "ondoubleclick" | "onentry" | "onsheetactivate" | "onsheetdeactivate" => events::get_property(property),
@@ -30,20 +46,26 @@ pub fn get_property(property: &str, _ctx: &mut Context) -> Result<Value> {
         // Reference properties
         "referencestyle" | "cutcopymode" => references::get_property(property),
         
-        _ => Err(anyhow::anyhow!("Unknown Application property: {}", property)),
+        // Active objects and selection
+        "activesheet" | "activeworkbook" | "thisworkbook" | "activecell" | "selection" | "activewindow" | "workbooks" | "worksheets"
+        | "sheets" => selection::get_property(property),
+        
+        _ => Err(unknown_member("property", property)),
     }
 }
 
 /// Route property set requests to specialized handlers
-pub fn set_property(property: &str, value: Value, _ctx: &mut Context) -> Result<()> {
-    match property.to_lowercase().as_str() {
-        "displayalerts" => interaction::set_property(property, value),
-        "screenupdating" => interaction::set_property(property, value),
-        "enableevents" => interaction::set_property(property, value),
+pub fn set_property(property: &str, value: Value, ctx: &mut Context) -> Result<()> {
+    match &*lower(property) {
+        "displayalerts" | "screenupdating" | "enableevents" => interaction::set_property(property, value, ctx),
         "calculation" => calculation::set_property(property, value),
         "username" | "useremailid" | "creatorname" | "creatoremailid" => metadata::set_property(property, value),
         "oncalculate" | "ondata" | "ondoubleclick" | "onentry" | "onsheetactivate" | "onsheetdeactivate" => events::set_property(property, value),
         "referencestyle" | "cutcopymode" => references::set_property(property, value),
-        _ => Err(anyhow::anyhow!("Cannot set Application property: {}", property)),
+        "activesheet" | "activeworkbook" | "thisworkbook" | "activecell" | "selection" | "activewindow" => selection::set_property(property, value),
+        _ if PROPERTIES.iter().any(|p| p.eq_ignore_ascii_case(property)) => {
+            Err(anyhow::anyhow!("Cannot set Application property: {}", property))
+        }
+        _ => Err(unknown_member("property", property)),
     }
 }
@@ -2,12 +2,15 @@
 
 use anyhow::Result;
 use crate::context::Value;
+use crate::host::excel::engine;
+use crate::host::excel::formula::{XL_A1, XL_R1C1};
+use crate::symbol::lower;
 
 pub fn get_property(property: &str) -> Result<Value> {
-    match property.to_lowercase().as_str() {
+    match &*lower(property) {
         "referencestyle" => {
-            // 1 = A1 style, 2 = R1C1 style
-            Ok(Value::Integer(1))
+            // xlA1 (1) or xlR1C1 (-4150)
+            Ok(Value::Integer(engine::reference_style() as i64))
         }
         "cutcopymode" => {
             // 0 = none, 1 = cut, 2 = copy
@@ -18,15 +21,21 @@ pub fn get_property(property: &str) -> Result<Value> {
 }
 
 pub fn set_property(property: &str, value: Value) -> Result<()> {
-    match property.to_lowercase().as_str() {
+    match &*lower(property) {
         "referencestyle" => {
-            match value {
-                Value::Integer(i) if i == 1 || i == 2 => {
-                    let style = if i == 1 { "A1" } else { "R1C1" };
-                    eprintln!("📋 Application.ReferenceStyle = {} ({})", i, style);
+            let style = match value {
+                Value::Integer(i) | Value::LongLong(i) => i,
+                Value::Long(i) => i as i64,
+                _ => 0,
+            };
+            match style as i32 {
+                style @ (XL_A1 | XL_R1C1) => {
+                    let name = if style == XL_A1 { "A1" } else { "R1C1" };
+                    eprintln!("📋 Application.ReferenceStyle = {} ({})", style, name);
+                    engine::set_reference_style(style);
                     Ok(())
                 }
-                _ => Err(anyhow::anyhow!("ReferenceStyle must be 1 or 2")),
+                _ => Err(anyhow::anyhow!("ReferenceStyle must be xlA1 (1) or xlR1C1 (-4150)")),
             }
         }
         "cutcopymode" => {
@@ -0,0 +1,23 @@
+// Active object and selection properties
+
+use anyhow::Result;
+use crate::context::Value;
+use crate::host::excel::{engine, objects};
+use crate::symbol::lower;
+
+pub fn get_property(property: &str) -> Result<Value> {
+    match &*lower(property) {
+        "activesheet" => Ok(Value::Object(Some(Box::new(Value::String("ActiveSheet".into()))))),
+        "activeworkbook" | "thisworkbook" => Ok(Value::Object(Some(Box::new(Value::String("ActiveWorkbook".into()))))),
+        "activecell" => Ok(objects::range_object(&engine::get_selection().active_cell)),
+        "selection" => Ok(objects::range_object(&engine::get_selection().range)),
+        "activewindow" => Ok(Value::String("Window:1".into())),
+        "workbooks" => Ok(Value::String("Workbooks:".into())),
+        "worksheets" | "sheets" => Ok(Value::String("Worksheets:".into())),
+        _ => Err(anyhow::anyhow!("Unknown selection property: {}", property)),
+    }
+}
+
+pub fn set_property(property: &str, _value: Value) -> Result<()> {
+    Err(anyhow::anyhow!("Application.{} is read-only", property))
+}
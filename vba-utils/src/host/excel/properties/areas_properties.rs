@@ -0,0 +1,17 @@
+// src/host/excel/properties/areas_properties.rs
+// Property handlers for Areas collection (Range.Areas)
+
+use anyhow::{Result, bail};
+use crate::context::Value;
+use crate::host::excel::objects::{self, range};
+use crate::symbol::lower;
+
+/// Get Areas collection property by name
+/// Data format: "A1:A5,C1:C5" (the address of the range)
+pub fn get_areas_property(data: &str, property: &str) -> Result<Value> {
+    match &*lower(property) {
+        "count" => Ok(Value::Integer(range::address_areas(data)?.len() as i64)),
+        "parent" => Ok(objects::range_object(data)),
+        _ => bail!("Unknown Areas property: {}", property),
+    }
+}
@@ -4,11 +4,12 @@
 
 use anyhow::{Result, bail};
 use crate::context::Value;
+use crate::symbol::lower;
 
 /// Get AutoFilter property by name
 /// Data format: "worksheet_name:workbook_id"
 pub fn get_autofilter_property(_data: &str, property: &str) -> Result<Value> {
-    match property.to_lowercase().as_str() {
+    match &*lower(property) {
         "enabled" => Ok(Value::Boolean(true)),
         "range" => {
             // AutoFilter.Range returns the range the filter is applied to
@@ -20,7 +21,7 @@ pub fn get_autofilter_property(_data: &str, property: &str) -> Result<Value> {
 
 /// Set AutoFilter property by name
 pub fn set_autofilter_property(_data: &str, property: &str, _value: Value) -> Result<()> {
-    match property.to_lowercase().as_str() {
+    match &*lower(property) {
         "enabled" => {
             eprintln!("Setting AutoFilter.Enabled not yet implemented");
             Ok(())
@@ -0,0 +1,36 @@
+// src/host/excel/properties/comment_properties.rs
+// Property handlers for Comment object
+// Comment is accessed via Range.Comment / returned by Range.AddComment
+
+use anyhow::{Result, bail};
+use crate::context::Value;
+use crate::host::excel::{engine, static_engine};
+use crate::symbol::lower;
+
+/// Get Comment property by name
+/// Data format: cell address ("B2") on the active sheet
+pub fn get_comment_property(data: &str, property: &str) -> Result<Value> {
+    let (row, col) = engine::address_to_indices(data).map_err(|e| anyhow::anyhow!("{}", e))?;
+    let Some(text) = static_engine::static_get_comment(&engine::get_active_sheet(), row, col) else {
+        bail!("Comment on {} was deleted", data);
+    };
+    match &*lower(property) {
+        "text" => Ok(Value::String(text)),
+        "author" => Ok(Value::String(String::new())),
+        "visible" => Ok(Value::Boolean(false)),
+        "parent" => Ok(Value::String(format!("Range:{}", data))),
+        _ => bail!("Unknown Comment property: {}", property),
+    }
+}
+
+/// Set Comment property by name
+pub fn set_comment_property(data: &str, property: &str, value: Value) -> Result<()> {
+    match &*lower(property) {
+        "visible" => {
+            eprintln!("Comment({}).Visible = {} - display only, ignored", data, value.as_string());
+            Ok(())
+        }
+        "text" | "author" | "parent" => bail!("Comment.{} is read-only", property),
+        _ => bail!("Cannot set Comment property: {}", property),
+    }
+}
@@ -0,0 +1,64 @@
+// src/host/excel/properties/hyperlink_properties.rs
+// Property handlers for Hyperlinks collection and Hyperlink object
+
+use anyhow::{Result, bail};
+use crate::context::Value;
+use crate::host::excel::static_engine;
+use crate::host::excel::methods::hyperlink_methods::{hyperlinks_in_scope, parse_cell, parse_scope};
+use crate::host::excel::objects::indices_to_address;
+use crate::symbol::lower;
+
+/// Get Hyperlinks collection property by name
+/// Data format: "Sheet1" (whole sheet) or "Sheet1!A1:B10" (Range.Hyperlinks)
+pub fn get_hyperlinks_property(data: &str, property: &str) -> Result<Value> {
+    match &*lower(property) {
+        "count" => Ok(Value::Integer(hyperlinks_in_scope(data)?.len() as i64)),
+        "parent" => Ok(Value::String(format!("Worksheet:{}", parse_scope(data)?.0))),
+        _ => bail!("Unknown Hyperlinks property: {}", property),
+    }
+}
+
+/// Set Hyperlinks collection property by name (all are read-only)
+pub fn set_hyperlinks_property(_data: &str, property: &str, _value: Value) -> Result<()> {
+    bail!("Cannot set Hyperlinks property: {}", property)
+}
+
+/// Get Hyperlink property by name
+/// Data format: "Sheet1!A1"
+pub fn get_hyperlink_property(data: &str, property: &str) -> Result<Value> {
+    let (sheet, row, col) = parse_cell(data)?;
+    let Some(link) = static_engine::static_get_hyperlink(&sheet, row, col) else {
+        bail!("Hyperlink at {} was deleted", data);
+    };
+    match &*lower(property) {
+        "address" => Ok(Value::String(link.address)),
+        "subaddress" => Ok(Value::String(link.sub_address)),
+        "screentip" => Ok(Value::String(link.screen_tip)),
+        "texttodisplay" => Ok(Value::String(link.text_to_display)),
+        "name" => Ok(Value::String(if link.text_to_display.is_empty() { link.address } else { link.text_to_display })),
+        "type" => Ok(Value::Integer(0)), // msoHyperlinkRange
+        "range" => Ok(Value::Object(Some(Box::new(Value::String(format!("Range:{}", indices_to_address(row, col))))))),
+        _ => bail!("Unknown Hyperlink property: {}", property),
+    }
+}
+
+/// Set Hyperlink property by name
+pub fn set_hyperlink_property(data: &str, property: &str, value: Value) -> Result<()> {
+    let (sheet, row, col) = parse_cell(data)?;
+    let text = value.as_string();
+    let updated = match &*lower(property) {
+        "address" => static_engine::static_update_hyperlink(&sheet, row, col, |l| l.address = text),
+        "subaddress" => static_engine::static_update_hyperlink(&sheet, row, col, |l| l.sub_address = text),
+        "screentip" => static_engine::static_update_hyperlink(&sheet, row, col, |l| l.screen_tip = text),
+        "texttodisplay" => {
+            // The displayed text is the cell's value
+            static_engine::static_set_cell_value(&sheet, row, col, &text);
+            static_engine::static_update_hyperlink(&sheet, row, col, |l| l.text_to_display = text)
+        }
+        _ => bail!("Cannot set Hyperlink property: {}", property),
+    };
+    if !updated {
+        bail!("Hyperlink at {} was deleted", data);
+    }
+    Ok(())
+}
@@ -3,11 +3,20 @@
 
 pub mod range_properties;
 pub mod worksheet_properties;
+pub mod workbook_properties;
 pub mod autofilter_properties;
+pub mod areas_properties;
+pub mod comment_properties;
+pub mod hyperlink_properties;
+pub mod pivot_properties;
+pub mod shape_properties;
+pub mod validation_properties;
+pub mod window_properties;
 pub mod application;
 
 use anyhow::Result;
 use crate::context::{Context, Value};
+use crate::symbol::lower;
 
 /// Get property from any Excel object by name
 pub fn get_property(
@@ -16,12 +25,29 @@ pub fn get_property(
     property: &str,
     ctx: &mut Context,
 ) -> Result<Value> {
-    match object_type.to_lowercase().as_str() {
+    match &*lower(object_type) {
         "range" => range_properties::get_range_property(object_data, property),
+        "rows" | "columns" => range_properties::get_rows_columns_property(object_type, object_data, property),
         "worksheet" => worksheet_properties::get_worksheet_property(object_data, property),
-        "workbook" => Err(anyhow::anyhow!("Workbook properties not yet implemented")),
+        "workbook" => workbook_properties::get_workbook_property(object_data, property),
+        "worksheets" => worksheet_properties::get_worksheets_property(object_data, property),
+        "workbooks" => workbook_properties::get_workbooks_property(object_data, property),
         "application" => application::get_property(property, ctx),
         "autofilter" => autofilter_properties::get_autofilter_property(object_data, property),
+        "areas" => areas_properties::get_areas_property(object_data, property),
+        "comment" => comment_properties::get_comment_property(object_data, property),
+        "validation" => validation_properties::get_validation_property(object_data, property),
+        "hyperlinks" => hyperlink_properties::get_hyperlinks_property(object_data, property),
+        "hyperlink" => hyperlink_properties::get_hyperlink_property(object_data, property),
+        "shapes" => shape_properties::get_shapes_property(object_data, property),
+        "shape" => shape_properties::get_shape_property(object_data, property),
+        "textframe" => shape_properties::get_textframe_property(object_data, property),
+        "pivotcaches" => pivot_properties::get_pivotcaches_property(object_data, property),
+        "pivotcache" => pivot_properties::get_pivotcache_property(object_data, property),
+        "pivottables" => pivot_properties::get_pivottables_property(object_data, property),
+        "pivottable" => pivot_properties::get_pivottable_property(object_data, property),
+        "pivotfield" => pivot_properties::get_pivotfield_property(object_data, property),
+        "window" => window_properties::get_window_property(object_data, property),
         _ => Err(anyhow::anyhow!("Unknown object type: {}", object_type)),
     }
 }
@@ -34,12 +60,23 @@ pub fn set_property(
     value: Value,
     ctx: &mut Context,
 ) -> Result<()> {
-    match object_type.to_lowercase().as_str() {
-        "range" => range_properties::set_range_property(object_data, property, value),
+    match &*lower(object_type) {
+        "range" | "rows" | "columns" => range_properties::set_range_property(object_data, property, value),
         "worksheet" => worksheet_properties::set_worksheet_property(object_data, property, value),
         "workbook" => Err(anyhow::anyhow!("Workbook properties not yet implemented")),
         "application" => application::set_property(property, value, ctx),
         "autofilter" => autofilter_properties::set_autofilter_property(object_data, property, value),
+        "comment" => comment_properties::set_comment_property(object_data, property, value),
+        "validation" => validation_properties::set_validation_property(object_data, property, value),
+        "hyperlinks" => hyperlink_properties::set_hyperlinks_property(object_data, property, value),
+        "hyperlink" => hyperlink_properties::set_hyperlink_property(object_data, property, value),
+        "shapes" => shape_properties::set_shapes_property(object_data, property, value),
+        "shape" => shape_properties::set_shape_property(object_data, property, value),
+        "textframe" => shape_properties::set_textframe_property(object_data, property, value),
+        "areas" | "pivotcaches" | "pivotcache" | "pivottables" => Err(anyhow::anyhow!("Cannot set {} property: {}", object_type, property)),
+        "pivottable" => pivot_properties::set_pivottable_property(object_data, property, value),
+        "pivotfield" => pivot_properties::set_pivotfield_property(object_data, property, value),
+        "window" => window_properties::set_window_property(object_data, property, value),
         _ => Err(anyhow::anyhow!("Unknown object type: {}", object_type)),
     }
 }
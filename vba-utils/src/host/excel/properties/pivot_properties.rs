@@ -0,0 +1,105 @@
+// src/host/excel/properties/pivot_properties.rs
+// Property handlers for PivotCaches, PivotCache, PivotTables, PivotTable and PivotField
+
+use anyhow::{Result, bail};
+use crate::context::Value;
+use crate::host::excel::static_engine;
+use crate::host::excel::methods::pivot_methods::split_field;
+use crate::symbol::lower;
+
+/// Get PivotCaches collection property by name
+pub fn get_pivotcaches_property(_data: &str, property: &str) -> Result<Value> {
+    match &*lower(property) {
+        "count" => Ok(Value::Integer(static_engine::static_pivot_cache_count() as i64)),
+        _ => bail!("Unknown PivotCaches property: {}", property),
+    }
+}
+
+/// Get PivotCache property by name
+/// Data format: 1-based cache index ("1")
+pub fn get_pivotcache_property(data: &str, property: &str) -> Result<Value> {
+    let cache = data.parse().ok()
+        .and_then(static_engine::static_get_pivot_cache)
+        .ok_or_else(|| anyhow::anyhow!("PivotCache {} does not exist", data))?;
+    match &*lower(property) {
+        "index" => Ok(Value::Integer(data.parse().unwrap_or(0))),
+        "sourcedata" => Ok(Value::String(cache.source_data)),
+        "sourcetype" => Ok(Value::Integer(cache.source_type as i64)),
+        _ => bail!("Unknown PivotCache property: {}", property),
+    }
+}
+
+/// Get PivotTables collection property by name
+/// Data format: sheet name ("Sheet1")
+pub fn get_pivottables_property(data: &str, property: &str) -> Result<Value> {
+    match &*lower(property) {
+        "count" => Ok(Value::Integer(static_engine::static_list_pivot_tables(Some(data)).len() as i64)),
+        _ => bail!("Unknown PivotTables property: {}", property),
+    }
+}
+
+/// Get PivotTable property by name
+/// Data format: table name ("PivotTable1")
+pub fn get_pivottable_property(data: &str, property: &str) -> Result<Value> {
+    let table = static_engine::static_get_pivot_table(data)
+        .ok_or_else(|| anyhow::anyhow!("PivotTable '{}' does not exist", data))?;
+    match &*lower(property) {
+        "name" => Ok(Value::String(table.name)),
+        "sourcedata" => Ok(Value::String(
+            static_engine::static_get_pivot_cache(table.cache_index).map(|c| c.source_data).unwrap_or_default(),
+        )),
+        "pivotcache" => Ok(Value::String(format!("PivotCache:{}", table.cache_index))),
+        "parent" => Ok(Value::String(format!("Worksheet:{}", table.sheet_name))),
+        _ => bail!("Unknown PivotTable property: {}", property),
+    }
+}
+
+/// Set PivotTable property by name
+pub fn set_pivottable_property(data: &str, property: &str, value: Value) -> Result<()> {
+    match &*lower(property) {
+        "name" => {
+            let name = value.as_string();
+            if static_engine::static_get_pivot_table(&name).is_some() {
+                bail!("A PivotTable named '{}' already exists", name);
+            }
+            if !static_engine::static_update_pivot_table(data, |t| t.name = name) {
+                bail!("PivotTable '{}' does not exist", data);
+            }
+            Ok(())
+        }
+        _ => bail!("Cannot set PivotTable property: {}", property),
+    }
+}
+
+/// Get PivotField property by name
+/// Data format: "PivotTable1|FieldName"
+pub fn get_pivotfield_property(data: &str, property: &str) -> Result<Value> {
+    let (table, name) = split_field(data)?;
+    let field = static_engine::static_get_pivot_table(table)
+        .and_then(|t| t.fields.into_iter().find(|f| f.name.eq_ignore_ascii_case(name)))
+        .ok_or_else(|| anyhow::anyhow!("PivotField '{}' does not exist", data))?;
+    match &*lower(property) {
+        "name" | "caption" | "sourcename" => Ok(Value::String(field.name)),
+        "orientation" => Ok(Value::Integer(field.orientation as i64)),
+        "position" => Ok(Value::Integer(field.position as i64)),
+        "numberformat" => Ok(Value::String(field.number_format)),
+        _ => bail!("Unknown PivotField property: {}", property),
+    }
+}
+
+/// Set PivotField property by name
+pub fn set_pivotfield_property(data: &str, property: &str, value: Value) -> Result<()> {
+    let (table, name) = split_field(data)?;
+    let number = || value.as_string().parse::<i32>().map_err(|_| anyhow::anyhow!("PivotField.{} expects a number", property));
+    let updated = match &*lower(property) {
+        // Orientation = xlRowField / xlColumnField / xlPageField / xlDataField / xlHidden
+        "orientation" => { let n = number()?; static_engine::static_update_pivot_table(table, |t| t.set_orientation(name, n)) }
+        "position" => { let n = number()?; static_engine::static_update_pivot_table(table, |t| t.set_position(name, n)) }
+        "numberformat" => static_engine::static_update_pivot_table(table, |t| t.field_mut(name).number_format = value.as_string()),
+        _ => bail!("Cannot set PivotField property: {}", property),
+    };
+    if !updated {
+        bail!("PivotTable '{}' does not exist", table);
+    }
+    Ok(())
+}
@@ -20,7 +20,11 @@
 
 use anyhow::{Result, bail};
 use crate::context::Value;
-use crate::host::excel::engine;
+use crate::host::excel::{engine, formula};
+use crate::host::excel::objects::range;
+use crate::host::excel::static_engine::{self, MAX_ROW};
+use crate::symbol::lower;
+use crate::vba_errors::VbaErr;
 
 // ============================================================================
 // GET PROPERTIES
@@ -36,7 +40,9 @@ use crate::host::excel::engine;
 /// * `Ok(Value)` - The property value
 /// * `Err` - If property is unknown or engine call fails
 pub fn get_range_property(address: &str, property: &str) -> Result<Value> {
-    match property.to_lowercase().as_str() {
+    // Properties may read the sheet directly, so buffered writes land first
+    engine::flush_writes().map_err(|e| anyhow::anyhow!("{}", e))?;
+    match &*lower(property) {
         
         // ====================================================================
         // CONTENT & VALUES
@@ -45,37 +51,16 @@ pub fn get_range_property(address: &str, property: &str) -> Result<Value> {
         "value" => {
             // Returns the value of the cell(s)
             // TODO: For multi-cell ranges, return 2D array
-            match engine::get_cell_value(address) {
-                Ok(val) => {
-                    if val.is_empty() {
-                        Ok(Value::Empty)
-                    } else if let Ok(n) = val.parse::<i64>() {
-                        Ok(Value::Integer(n))
-                    } else if let Ok(n) = val.parse::<f64>() {
-                        Ok(Value::Double(n))
-                    } else {
-                        Ok(Value::String(val))
-                    }
-                }
+            match engine::get_typed_value(address) {
+                Ok(val) => Ok(val.to_value(false)),
                 Err(e) => bail!("Failed to get cell value: {}", e),
             }
         }
         
         "value2" => {
             // Same as Value but dates are returned as serial numbers
-            // TODO: ENGINE CALL - engine::get_cell_value_raw(address)
-            match engine::get_cell_value(address) {
-                Ok(val) => {
-                    if val.is_empty() {
-                        Ok(Value::Empty)
-                    } else if let Ok(n) = val.parse::<i64>() {
-                        Ok(Value::Integer(n))
-                    } else if let Ok(n) = val.parse::<f64>() {
-                        Ok(Value::Double(n))
-                    } else {
-                        Ok(Value::String(val))
-                    }
-                }
+            match engine::get_typed_value(address) {
+                Ok(val) => Ok(val.to_value(true)),
                 Err(e) => bail!("Failed to get cell value: {}", e),
             }
         }
@@ -91,17 +76,21 @@ pub fn get_range_property(address: &str, property: &str) -> Result<Value> {
         }
         
         "formula" => {
-            // Returns the formula in A1 notation (e.g., "=A1+B1")
-            // TODO: ENGINE CALL - engine::get_cell_formula(address)
-            eprintln!("   [STUB] Range({}).Formula - returning empty", address);
-            Ok(Value::String(String::new()))
+            // Returns the formula in A1 notation (e.g., "=A1+B1") of the
+            // top-left cell, or its value when it holds a constant
+            match engine::get_cell_formula(top_left(address)) {
+                Ok(formula) => Ok(Value::String(formula)),
+                Err(e) => bail!("Failed to get formula: {}", e),
+            }
         }
         
         "formular1c1" => {
-            // Returns the formula in R1C1 notation (e.g., "=R[-1]C+R[-1]C[1]")
-            // TODO: ENGINE CALL - engine::get_cell_formula_r1c1(address)
-            eprintln!("   [STUB] Range({}).FormulaR1C1 - returning empty", address);
-            Ok(Value::String(String::new()))
+            // Returns the formula in R1C1 notation (e.g., "=R[-1]C+R[-1]C[1]"),
+            // relative to the top-left cell
+            let a1 = get_range_property(address, "Formula")?.as_string();
+            let ((row, col), _) = get_range_bounds(address)?;
+            let r1c1 = formula::convert_formula(&a1, formula::XL_A1, formula::XL_R1C1, None, (row, col)).unwrap_or(a1);
+            Ok(Value::String(r1c1))
         }
         
         "formulaarray" => {
@@ -124,36 +113,44 @@ pub fn get_range_property(address: &str, property: &str) -> Result<Value> {
         
         "address" => {
             // Returns the absolute address as string (e.g., "$A$1")
-            // Format: $A$1 (absolute by default)
-            Ok(Value::String(format!("${}", address.to_uppercase())))
+            // Format: $A$1 (absolute by default), areas joined by ","
+            let relative = range::areas_address(&range::address_areas(address)?);
+            let mut absolute = String::new();
+            let mut previous = ':';
+            for c in relative.chars() {
+                // A "$" before each run of column letters and of row digits
+                if (c.is_ascii_alphabetic() && !previous.is_ascii_alphabetic())
+                    || (c.is_ascii_digit() && !previous.is_ascii_digit()) {
+                    absolute.push('$');
+                }
+                absolute.push(c);
+                previous = c;
+            }
+            Ok(Value::String(absolute))
         }
         
         "row" => {
-            // Returns the row number (1-based)
-            let (row, _) = engine::address_to_indices(address)
-                .map_err(|e| anyhow::anyhow!("{}", e))?;
+            // Returns the row number (1-based) of the first area
+            let ((row, _), _) = get_range_bounds(address)?;
             Ok(Value::Integer((row + 1) as i64))
         }
         
         "column" => {
-            // Returns the column number (1-based)
-            let (_, col) = engine::address_to_indices(address)
-                .map_err(|e| anyhow::anyhow!("{}", e))?;
+            // Returns the column number (1-based) of the first area
+            let ((_, col), _) = get_range_bounds(address)?;
             Ok(Value::Integer((col + 1) as i64))
         }
         
         "rows" => {
-            // Returns a Range representing all rows in the range
+            // Returns the Rows collection of the range
             // In VBA, Range.Rows.Count returns the number of rows
-            // For now we return the range itself (Rows collection)
-            // The Count property will be handled when accessed on this
-            Ok(Value::String(format!("Range:{}", address)))
+            Ok(Value::String(format!("Rows:{}", address)))
         }
         
         "columns" => {
-            // Returns a Range representing all columns in the range
+            // Returns the Columns collection of the range
             // In VBA, Range.Columns.Count returns the number of columns
-            Ok(Value::String(format!("Range:{}", address)))
+            Ok(Value::String(format!("Columns:{}", address)))
         }
         
         "cells" => {
@@ -186,15 +183,13 @@ pub fn get_range_property(address: &str, property: &str) -> Result<Value> {
         
         "currentregion" => {
             // Returns the current region (bounded by empty rows/columns)
-            // TODO: ENGINE CALL - engine::get_current_region(address)
-            eprintln!("   [STUB] Range({}).CurrentRegion - returning self", address);
-            Ok(Value::String(format!("Range:{}", address)))
+            let ((row, col), _) = get_range_bounds(address)?;
+            let region = static_engine::static_get_current_region(&engine::get_active_sheet(), row, col);
+            Ok(Value::String(format!("Range:{}", range::areas_address(&[region]))))
         }
         
         "areas" => {
             // Returns an Areas collection for non-contiguous ranges
-            // TODO: ENGINE CALL - engine::get_range_areas(address)
-            eprintln!("   [STUB] Range({}).Areas - returning self", address);
             Ok(Value::String(format!("Areas:{}", address)))
         }
         
@@ -203,16 +198,18 @@ pub fn get_range_property(address: &str, property: &str) -> Result<Value> {
         // ====================================================================
         
         "count" => {
-            // Returns the number of cells in the range (as Long)
-            let (rows, cols) = get_range_dimensions(address)?;
-            let count = rows as i64 * cols as i64;
+            // Returns the number of cells in the range (as Long), across all areas
+            let count: i64 = range::address_areas(address)?.iter()
+                .map(|a| (a.2 - a.0 + 1) as i64 * (a.3 - a.1 + 1) as i64)
+                .sum();
             Ok(Value::Integer(count))
         }
         
         "countlarge" => {
             // Returns the number of cells (as Double, for large ranges)
-            let (rows, cols) = get_range_dimensions(address)?;
-            let count = rows as f64 * cols as f64;
+            let count: f64 = range::address_areas(address)?.iter()
+                .map(|a| (a.2 - a.0 + 1) as f64 * (a.3 - a.1 + 1) as f64)
+                .sum();
             Ok(Value::Double(count))
         }
         
@@ -222,9 +219,10 @@ pub fn get_range_property(address: &str, property: &str) -> Result<Value> {
         
         "numberformat" => {
             // Returns the number format code (e.g., "0.00", "@", "General")
-            // TODO: ENGINE CALL - engine::get_cell_number_format(address)
-            eprintln!("   [STUB] Range({}).NumberFormat - returning 'General'", address);
-            Ok(Value::String("General".to_string()))
+            match engine::get_number_format(top_left(address)) {
+                Ok(format) => Ok(Value::String(format)),
+                Err(e) => bail!("Failed to get number format: {}", e),
+            }
         }
         
         // ====================================================================
@@ -314,24 +312,35 @@ pub fn get_range_property(address: &str, property: &str) -> Result<Value> {
         // ====================================================================
         
         "locked" => {
-            // Returns True if cells are locked
-            // TODO: ENGINE CALL - engine::get_cell_locked(address)
-            eprintln!("   [STUB] Range({}).Locked - returning True", address);
-            Ok(Value::Boolean(true)) // Default is locked
+            // Returns True if cells are locked, False if none are, Null if mixed
+            let sheet = engine::get_active_sheet();
+            let ((start_row, start_col), (end_row, end_col)) = get_range_bounds(address)?;
+            let area = (start_row, start_col, end_row, end_col);
+            if static_engine::static_all_locked(&sheet, area) {
+                Ok(Value::Boolean(true))
+            } else if static_engine::static_any_locked(&sheet, area) {
+                Ok(Value::Null)
+            } else {
+                Ok(Value::Boolean(false))
+            }
         }
         
         "hidden" => {
-            // Returns True if rows/columns containing range are hidden
-            // TODO: ENGINE CALL - engine::get_range_hidden(address)
-            eprintln!("   [STUB] Range({}).Hidden - returning False", address);
-            Ok(Value::Boolean(false))
+            // Returns True if every row (or, for Columns("C"), every column) is hidden
+            let sheet = engine::get_active_sheet();
+            let ((start_row, start_col), (end_row, end_col)) = get_range_bounds(address)?;
+            let hidden = if start_row == 0 && end_row == MAX_ROW {
+                (start_col..=end_col).all(|col| static_engine::static_is_column_hidden(&sheet, col))
+            } else {
+                (start_row..=end_row).all(|row| static_engine::static_is_row_hidden(&sheet, row))
+            };
+            Ok(Value::Boolean(hidden))
         }
         
         "mergecells" => {
             // Returns True if range is part of a merged cell
-            // TODO: ENGINE CALL - engine::get_merge_cells(address)
-            eprintln!("   [STUB] Range({}).MergeCells - returning False", address);
-            Ok(Value::Boolean(false))
+            let ((row, col), _) = get_range_bounds(address)?;
+            Ok(Value::Boolean(static_engine::static_is_merged(&engine::get_active_sheet(), row, col)))
         }
         
         // ====================================================================
@@ -397,10 +406,12 @@ pub fn get_range_property(address: &str, property: &str) -> Result<Value> {
         // ====================================================================
         
         "comment" => {
-            // Returns the Comment object (if any)
-            // TODO: ENGINE CALL - engine::get_cell_comment(address)
-            eprintln!("   [STUB] Range({}).Comment - returning Nothing", address);
-            Ok(Value::Empty)
+            // Returns the Comment object of the top-left cell, or Nothing
+            let ((row, col), _) = get_range_bounds(address)?;
+            match static_engine::static_get_comment(&engine::get_active_sheet(), row, col) {
+                Some(_) => Ok(Value::String(format!("Comment:{}", indices_to_address(row, col)))),
+                None => Ok(Value::Object(None)),
+            }
         }
         
         // ====================================================================
@@ -409,15 +420,11 @@ pub fn get_range_property(address: &str, property: &str) -> Result<Value> {
         
         "hyperlinks" => {
             // Returns the Hyperlinks collection for the range
-            // TODO: ENGINE CALL - engine::get_hyperlinks(address)
-            eprintln!("   [STUB] Range({}).Hyperlinks - returning Hyperlinks object reference", address);
-            Ok(Value::String(format!("Hyperlinks:{}", address)))
+            Ok(Value::String(format!("Hyperlinks:{}!{}", engine::get_active_sheet(), address)))
         }
         
         "validation" => {
             // Returns the Validation object (data validation settings)
-            // TODO: ENGINE CALL - engine::get_validation(address)
-            eprintln!("   [STUB] Range({}).Validation - returning Validation object reference", address);
             Ok(Value::String(format!("Validation:{}", address)))
         }
         
@@ -460,7 +467,11 @@ pub fn get_range_property(address: &str, property: &str) -> Result<Value> {
 /// * `Ok(())` - Property was set successfully
 /// * `Err` - If property is read-only, unknown, or engine call fails
 pub fn set_range_property(address: &str, property: &str, value: Value) -> Result<()> {
-    match property.to_lowercase().as_str() {
+    // Only Value writes are buffered; anything else sees them applied
+    if !matches!(&*lower(property), "value" | "value2") {
+        engine::flush_writes().map_err(|e| anyhow::anyhow!("{}", e))?;
+    }
+    match &*lower(property) {
         
         // ====================================================================
         // CONTENT & VALUES
@@ -468,31 +479,39 @@ pub fn set_range_property(address: &str, property: &str, value: Value) -> Result
         
         "value" | "value2" => {
             // Set the value of the cell(s)
+            check_writable(address)?;
             let value_str = value_to_string(&value);
-            engine::set_cell_value(address, &value_str)
-                .map_err(|e| anyhow::anyhow!("Failed to set cell value: {}", e))
+            for area in address.split(',') {
+                engine::set_cell_value(area.trim(), &value_str)
+                    .map_err(|e| anyhow::anyhow!("Failed to set cell value: {}", e))?;
+            }
+            Ok(())
         }
         
         "formula" => {
-            // Set formula in A1 notation
-            // TODO: ENGINE CALL - engine::set_cell_formula(address, formula)
+            // Set formula in A1 notation; backends without formulas store
+            // the text as a value
+            check_writable(address)?;
             let formula = value_to_string(&value);
-            eprintln!("   [STUB] Range({}).Formula = '{}' - storing as value", address, formula);
-            engine::set_cell_value(address, &formula)
+            engine::set_cell_formula(address, &formula)
                 .map_err(|e| anyhow::anyhow!("Failed to set formula: {}", e))
         }
         
         "formular1c1" => {
-            // Set formula in R1C1 notation
-            // TODO: ENGINE CALL - engine::set_cell_formula_r1c1(address, formula)
-            let formula = value_to_string(&value);
-            eprintln!("   [STUB] Range({}).FormulaR1C1 = '{}' - NOT IMPLEMENTED", address, formula);
-            Ok(())
+            // Set formula in R1C1 notation: stored as its A1 form relative to
+            // the top-left cell
+            let r1c1 = value_to_string(&value);
+            let ((row, col), _) = get_range_bounds(address)?;
+            let Some(a1) = formula::convert_formula(&r1c1, formula::XL_R1C1, formula::XL_A1, None, (row, col)) else {
+                return Err(VbaErr::ApplicationDefined.message(format!("Invalid R1C1 formula: {}", r1c1)));
+            };
+            set_range_property(address, "Formula", Value::String(a1))
         }
         
         "formulaarray" => {
             // Set array formula
             // TODO: ENGINE CALL - engine::set_array_formula(address, formula)
+            check_writable(address)?;
             let formula = value_to_string(&value);
             eprintln!("   [STUB] Range({}).FormulaArray = '{}' - NOT IMPLEMENTED", address, formula);
             Ok(())
@@ -504,9 +523,11 @@ pub fn set_range_property(address: &str, property: &str, value: Value) -> Result
         
         "numberformat" => {
             // Set number format code
-            // TODO: ENGINE CALL - engine::set_cell_number_format(address, format)
             let format = value_to_string(&value);
-            eprintln!("   [STUB] Range({}).NumberFormat = '{}' - NOT IMPLEMENTED", address, format);
+            for area in address.split(',') {
+                engine::set_number_format(area.trim(), &format)
+                    .map_err(|e| anyhow::anyhow!("Failed to set number format: {}", e))?;
+            }
             Ok(())
         }
         
@@ -564,26 +585,44 @@ pub fn set_range_property(address: &str, property: &str, value: Value) -> Result
         // ====================================================================
         
         "locked" => {
-            // Set locked state
-            // TODO: ENGINE CALL - engine::set_cell_locked(address, locked)
+            // Set locked state (only takes effect once the sheet is protected)
             let locked = value_to_bool(&value);
-            eprintln!("   [STUB] Range({}).Locked = {} - NOT IMPLEMENTED", address, locked);
+            let sheet = engine::get_active_sheet();
+            if static_engine::static_get_protection(&sheet).is_some_and(|p| !p.user_interface_only) {
+                return Err(VbaErr::ApplicationDefined.message("Unable to set the Locked property of the Range class"));
+            }
+            let ((start_row, start_col), (end_row, end_col)) = get_range_bounds(address)?;
+            static_engine::static_set_locked_area(&sheet, (start_row, start_col, end_row, end_col), locked);
             Ok(())
         }
         
         "hidden" => {
-            // Set hidden state (for rows/columns)
-            // TODO: ENGINE CALL - engine::set_range_hidden(address, hidden)
+            // Hide/show the rows of the range, or the columns for Columns("C")
             let hidden = value_to_bool(&value);
-            eprintln!("   [STUB] Range({}).Hidden = {} - NOT IMPLEMENTED", address, hidden);
+            let sheet = engine::get_active_sheet();
+            let ((start_row, start_col), (end_row, end_col)) = get_range_bounds(address)?;
+            if start_row == 0 && end_row == MAX_ROW {
+                for col in start_col..=end_col {
+                    static_engine::static_set_column_hidden(&sheet, col, hidden);
+                }
+            } else {
+                for row in start_row..=end_row {
+                    static_engine::static_set_row_hidden(&sheet, row, hidden);
+                }
+            }
             Ok(())
         }
         
         "mergecells" => {
             // Set merge state (True to merge, False to unmerge)
-            // TODO: ENGINE CALL - engine::set_merge_cells(address, merge)
             let merge = value_to_bool(&value);
-            eprintln!("   [STUB] Range({}).MergeCells = {} - NOT IMPLEMENTED", address, merge);
+            let sheet = engine::get_active_sheet();
+            let ((start_row, start_col), (end_row, end_col)) = get_range_bounds(address)?;
+            if merge {
+                static_engine::static_merge_cells(&sheet, start_row, start_col, end_row, end_col, false);
+            } else {
+                static_engine::static_unmerge_cells(&sheet, start_row, start_col, end_row, end_col);
+            }
             Ok(())
         }
         
@@ -629,51 +668,44 @@ pub fn set_range_property(address: &str, property: &str, value: Value) -> Result
     }
 }
 
+/// Get a property of a Rows or Columns collection (Range.Rows, Worksheet.Columns)
+/// Data format: the range address; properties other than Count are the range's
+pub fn get_rows_columns_property(kind: &str, address: &str, property: &str) -> Result<Value> {
+    if lower(property) != "count" {
+        return get_range_property(address, property);
+    }
+    let ((start_row, start_col), (end_row, end_col)) = get_range_bounds(address)?;
+    let count = if kind.eq_ignore_ascii_case("rows") { end_row - start_row + 1 } else { end_col - start_col + 1 };
+    Ok(Value::Integer(count as i64))
+}
+
 // ============================================================================
 // HELPER FUNCTIONS
 // ============================================================================
 
-/// Parse a range address and return (row_count, col_count)
-/// Handles both single cell (e.g., "A1") and range (e.g., "A1:C5")
-fn get_range_dimensions(address: &str) -> Result<(i32, i32)> {
-    if let Some(colon_pos) = address.find(':') {
-        // Range like "A1:C5"
-        let start = &address[..colon_pos];
-        let end = &address[colon_pos + 1..];
-        
-        let (start_row, start_col) = engine::address_to_indices(start)
-            .map_err(|e| anyhow::anyhow!("{}", e))?;
-        let (end_row, end_col) = engine::address_to_indices(end)
-            .map_err(|e| anyhow::anyhow!("{}", e))?;
-        
-        let rows = (end_row - start_row + 1).abs();
-        let cols = (end_col - start_col + 1).abs();
-        
-        Ok((rows, cols))
-    } else {
-        // Single cell like "A1"
-        Ok((1, 1))
-    }
-}
-
 /// Get the start and end indices of a range
 /// Returns ((start_row, start_col), (end_row, end_col))
+/// Entire rows ("2:2") and columns ("C:C") span the whole sheet; a
+/// multi-area range ("A1:A5,C1:C5") uses its first area
 fn get_range_bounds(address: &str) -> Result<((i32, i32), (i32, i32))> {
-    if let Some(colon_pos) = address.find(':') {
-        let start = &address[..colon_pos];
-        let end = &address[colon_pos + 1..];
-        
-        let start_pos = engine::address_to_indices(start)
-            .map_err(|e| anyhow::anyhow!("{}", e))?;
-        let end_pos = engine::address_to_indices(end)
-            .map_err(|e| anyhow::anyhow!("{}", e))?;
-        
-        Ok((start_pos, end_pos))
-    } else {
-        let pos = engine::address_to_indices(address)
-            .map_err(|e| anyhow::anyhow!("{}", e))?;
-        Ok((pos, pos))
+    let first = address.split(',').next().unwrap_or(address);
+    let (start_row, start_col, end_row, end_col) = engine::address_to_area(first)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    Ok(((start_row, start_col), (end_row, end_col)))
+}
+
+/// Top-left cell of a range ("B2" for "B2:C5,E1")
+fn top_left(address: &str) -> &str {
+    address.split([',', ':']).next().unwrap_or(address).trim()
+}
+
+/// Fail with error 1004 if the range has locked cells on a protected sheet
+pub(crate) fn check_writable(address: &str) -> Result<()> {
+    let sheet = engine::get_active_sheet();
+    for area in range::address_areas(address)? {
+        static_engine::static_check_writable(&sheet, area).map_err(|e| VbaErr::ApplicationDefined.message(e))?;
     }
+    Ok(())
 }
 
 /// Convert (row, col) to Excel address
@@ -693,9 +725,9 @@ fn column_index_to_letter(col: i32) -> String {
     result
 }
 
-/// Convert Value to String representation
 fn value_to_string(value: &Value) -> String {
     match value {
+        Value::Error(e) => formula::error_literal(*e).map_or_else(|| format!("Error {}", e), str::to_string),
         Value::String(s) => s.clone(),
         Value::Integer(i) => i.to_string(),
         Value::Double(d) => d.to_string(),
@@ -754,4 +786,33 @@ mod tests {
         assert_eq!(value_to_string(&Value::Boolean(true)), "TRUE");
         assert_eq!(value_to_string(&Value::Empty), "");
     }
+
+    #[test]
+    fn test_cell_error_values() {
+        use crate::ast::Expression;
+        use crate::context::Context;
+
+        let cell_to_value = |text: &str| crate::host::excel::backend::CellValue::parse(text).to_value(false);
+        assert!(matches!(cell_to_value("#N/A"), Value::Error(2042)));
+        assert!(matches!(cell_to_value("#DIV/0!"), Value::Error(2007)));
+        assert!(matches!(cell_to_value("#VALUE!"), Value::Error(2015)));
+        assert_eq!(value_to_string(&Value::Error(2007)), "#DIV/0!");
+
+        let call = |name: &str, arg: Expression| Expression::FunctionCall {
+            function: Box::new(Expression::Identifier(name.into())),
+            args: vec![arg],
+        };
+        let na = call("CVErr", Expression::Identifier("xlErrNA".into()));
+        let mut ctx = Context::new();
+        let eval = |expr: &Expression, ctx: &mut Context| crate::interpreter::evaluate_expression(expr, ctx);
+        assert!(matches!(eval(&na, &mut ctx).unwrap(), Value::Error(2042)));
+        assert!(matches!(eval(&call("IsError", na.clone()), &mut ctx).unwrap(), Value::Boolean(true)));
+
+        // Math on an error value is a type mismatch; errors still compare
+        let plus_one = Expression::BinaryOp { left: Box::new(na.clone()), op: "+".into(), right: Box::new(Expression::Integer(1)) };
+        let err = eval(&plus_one, &mut ctx).unwrap_err();
+        assert_eq!(crate::host::excel::error_number(&err), Some(13));
+        let same = Expression::BinaryOp { left: Box::new(na.clone()), op: "=".into(), right: Box::new(na) };
+        assert!(matches!(eval(&same, &mut ctx).unwrap(), Value::Boolean(true)));
+    }
 }
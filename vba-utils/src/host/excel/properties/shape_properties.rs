@@ -0,0 +1,101 @@
+// src/host/excel/properties/shape_properties.rs
+// Property handlers for Shapes collection, Shape and TextFrame objects
+
+use anyhow::{Result, bail};
+use crate::context::Value;
+use crate::host::excel::{engine, static_engine};
+use crate::host::excel::methods::shape_methods::parse_shape;
+use crate::host::excel::static_engine::ShapeInfo;
+use crate::symbol::lower;
+
+/// Get Shapes collection property by name
+/// Data format: sheet name ("Sheet1")
+pub fn get_shapes_property(data: &str, property: &str) -> Result<Value> {
+    let sheet = if data.is_empty() { engine::get_active_sheet() } else { data.to_string() };
+    match &*lower(property) {
+        "count" => Ok(Value::Integer(static_engine::static_list_shapes(&sheet).len() as i64)),
+        "parent" => Ok(Value::String(format!("Worksheet:{}", sheet))),
+        _ => bail!("Unknown Shapes property: {}", property),
+    }
+}
+
+/// Set Shapes collection property by name (all are read-only)
+pub fn set_shapes_property(_data: &str, property: &str, _value: Value) -> Result<()> {
+    bail!("Cannot set Shapes property: {}", property)
+}
+
+/// Get Shape property by name
+/// Data format: "Sheet1!<id>"
+pub fn get_shape_property(data: &str, property: &str) -> Result<Value> {
+    let shape = get_shape(data)?;
+    match &*lower(property) {
+        "name" => Ok(Value::String(shape.name)),
+        "id" => Ok(Value::Integer(shape.id as i64)),
+        "type" => Ok(Value::Integer(shape.shape_type as i64)),
+        "autoshapetype" => Ok(Value::Integer(shape.auto_shape_type as i64)),
+        "left" => Ok(Value::Double(shape.left)),
+        "top" => Ok(Value::Double(shape.top)),
+        "width" => Ok(Value::Double(shape.width)),
+        "height" => Ok(Value::Double(shape.height)),
+        "visible" => Ok(Value::Boolean(shape.visible)),
+        "onaction" => Ok(Value::String(shape.on_action)),
+        "textframe" | "textframe2" => Ok(Value::String(format!("TextFrame:{}", data))),
+        "parent" => Ok(Value::String(format!("Worksheet:{}", shape.sheet_name))),
+        _ => bail!("Unknown Shape property: {}", property),
+    }
+}
+
+/// Set Shape property by name
+pub fn set_shape_property(data: &str, property: &str, value: Value) -> Result<()> {
+    let (sheet, id) = parse_shape(data)?;
+    let text = value.as_string();
+    let number = || text.parse::<f64>().map_err(|_| anyhow::anyhow!("Shape.{} expects a number, got '{}'", property, text));
+    let updated = match &*lower(property) {
+        "name" => static_engine::static_update_shape(&sheet, id, |s| s.name = text.clone()),
+        "left" => { let n = number()?; static_engine::static_update_shape(&sheet, id, |s| s.left = n) }
+        "top" => { let n = number()?; static_engine::static_update_shape(&sheet, id, |s| s.top = n) }
+        "width" => { let n = number()?; static_engine::static_update_shape(&sheet, id, |s| s.width = n) }
+        "height" => { let n = number()?; static_engine::static_update_shape(&sheet, id, |s| s.height = n) }
+        "autoshapetype" => { let n = number()? as i32; static_engine::static_update_shape(&sheet, id, |s| s.auto_shape_type = n) }
+        "visible" => {
+            let visible = matches!(value, Value::Boolean(true)) || text == "-1" || text == "1" || text.eq_ignore_ascii_case("true");
+            static_engine::static_update_shape(&sheet, id, |s| s.visible = visible)
+        }
+        "onaction" => static_engine::static_update_shape(&sheet, id, |s| s.on_action = text.clone()),
+        _ => bail!("Cannot set Shape property: {}", property),
+    };
+    if !updated {
+        bail!("Shape {} was deleted", data);
+    }
+    Ok(())
+}
+
+/// Get TextFrame property by name
+/// Data format: "Sheet1!<id>" of the owning shape
+pub fn get_textframe_property(data: &str, property: &str) -> Result<Value> {
+    match &*lower(property) {
+        // TextFrame.Characters.Text / TextFrame2.TextRange.Text
+        "characters" | "textrange" => Ok(Value::String(format!("TextFrame:{}", data))),
+        "text" | "caption" => Ok(Value::String(get_shape(data)?.text)),
+        _ => bail!("Unknown TextFrame property: {}", property),
+    }
+}
+
+/// Set TextFrame property by name
+pub fn set_textframe_property(data: &str, property: &str, value: Value) -> Result<()> {
+    match &*lower(property) {
+        "text" | "caption" => {
+            let (sheet, id) = parse_shape(data)?;
+            if !static_engine::static_update_shape(&sheet, id, |s| s.text = value.as_string()) {
+                bail!("Shape {} was deleted", data);
+            }
+            Ok(())
+        }
+        _ => bail!("Cannot set TextFrame property: {}", property),
+    }
+}
+
+fn get_shape(data: &str) -> Result<ShapeInfo> {
+    let (sheet, id) = parse_shape(data)?;
+    static_engine::static_get_shape(&sheet, id).ok_or_else(|| anyhow::anyhow!("Shape {} was deleted", data))
+}
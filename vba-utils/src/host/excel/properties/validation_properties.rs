@@ -0,0 +1,111 @@
+// src/host/excel/properties/validation_properties.rs
+// Property handlers for Validation object
+// Validation is accessed via Range.Validation; the rule of the top-left
+// cell is reported, and setters apply to every cell of the range
+
+use anyhow::{Result, bail};
+use crate::context::Value;
+use crate::host::excel::{engine, static_engine};
+use crate::host::excel::static_engine::ValidationInfo;
+use crate::symbol::lower;
+
+/// Get Validation property by name
+/// Data format: range address ("A2:A100") on the active sheet
+pub fn get_validation_property(data: &str, property: &str) -> Result<Value> {
+    let sheet = engine::get_active_sheet();
+    let (row, col, _, _) = engine::address_to_area(data).map_err(|e| anyhow::anyhow!("{}", e))?;
+    let Some(info) = static_engine::static_get_validation(&sheet, row, col) else {
+        // Excel raises error 1004 when reading a rule that does not exist
+        bail!("Range({}) has no data validation", data);
+    };
+    match &*lower(property) {
+        "type" => Ok(Value::Integer(info.validation_type as i64)),
+        "formula1" => Ok(Value::String(info.formula1)),
+        "formula2" => Ok(Value::String(info.formula2.unwrap_or_default())),
+        "operator" => Ok(Value::Integer(info.operator as i64)),
+        "alertstyle" => Ok(Value::Integer(info.alert_style as i64)),
+        "inputtitle" => Ok(Value::String(info.input_title)),
+        "inputmessage" => Ok(Value::String(info.input_message)),
+        "errortitle" => Ok(Value::String(info.error_title)),
+        "errormessage" => Ok(Value::String(info.error_message)),
+        "ignoreblank" => Ok(Value::Boolean(info.ignore_blank)),
+        "incelldropdown" => Ok(Value::Boolean(info.in_cell_dropdown)),
+        "showinput" => Ok(Value::Boolean(info.show_input)),
+        "showerror" => Ok(Value::Boolean(info.show_error)),
+        "value" => {
+            // True if the current cell value satisfies the rule
+            let value = static_engine::static_get_cell_value(&sheet, row, col);
+            Ok(Value::Boolean(is_valid(&info, &value)))
+        }
+        _ => bail!("Unknown Validation property: {}", property),
+    }
+}
+
+/// Set Validation property by name
+pub fn set_validation_property(data: &str, property: &str, value: Value) -> Result<()> {
+    let text = value.as_string();
+    let flag = matches!(value, Value::Boolean(true)) || text == "1" || text.eq_ignore_ascii_case("true");
+    let update: Box<dyn Fn(&mut ValidationInfo)> = match &*lower(property) {
+        "inputtitle" => Box::new(move |v| v.input_title = text.clone()),
+        "inputmessage" => Box::new(move |v| v.input_message = text.clone()),
+        "errortitle" => Box::new(move |v| v.error_title = text.clone()),
+        "errormessage" => Box::new(move |v| v.error_message = text.clone()),
+        "ignoreblank" => Box::new(move |v| v.ignore_blank = flag),
+        "incelldropdown" => Box::new(move |v| v.in_cell_dropdown = flag),
+        "showinput" => Box::new(move |v| v.show_input = flag),
+        "showerror" => Box::new(move |v| v.show_error = flag),
+        "type" | "formula1" | "formula2" | "operator" | "alertstyle" | "value" => {
+            bail!("Validation.{} is read-only (use Validation.Modify)", property)
+        }
+        _ => bail!("Cannot set Validation property: {}", property),
+    };
+
+    let sheet = engine::get_active_sheet();
+    let (start_row, start_col, end_row, end_col) = engine::address_to_area(data).map_err(|e| anyhow::anyhow!("{}", e))?;
+    for row in start_row..=end_row {
+        for col in start_col..=end_col {
+            if !static_engine::static_update_validation(&sheet, row, col, |v| update(v)) {
+                bail!("Range({}) has no data validation", data);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check a cell value against a rule (list membership, or the numeric
+/// comparison for whole number/decimal/text length rules)
+fn is_valid(info: &ValidationInfo, value: &str) -> bool {
+    if value.is_empty() {
+        return info.ignore_blank;
+    }
+    match info.validation_type {
+        // xlValidateList: Formula1 is "a,b,c" (a range reference is not resolved)
+        3 => info.formula1.trim_start_matches('=').split(',').any(|item| item.trim().eq_ignore_ascii_case(value.trim())),
+        // xlValidateWholeNumber / xlValidateDecimal / xlValidateTextLength
+        1 | 2 | 6 => {
+            let n = if info.validation_type == 6 {
+                value.chars().count() as f64
+            } else {
+                match value.trim().parse::<f64>() {
+                    Ok(n) if info.validation_type != 1 || n.fract() == 0.0 => n,
+                    _ => return false,
+                }
+            };
+            let bound = |f: &str| f.trim_start_matches('=').trim().parse::<f64>().ok();
+            let (Some(a), b) = (bound(&info.formula1), info.formula2.as_deref().and_then(bound)) else { return true };
+            match info.operator {
+                1 => n >= a && b.is_none_or(|b| n <= b), // xlBetween
+                2 => n < a || b.is_some_and(|b| n > b),   // xlNotBetween
+                3 => n == a,                             // xlEqual
+                4 => n != a,                             // xlNotEqual
+                5 => n > a,                              // xlGreater
+                6 => n < a,                              // xlLess
+                7 => n >= a,                             // xlGreaterEqual
+                8 => n <= a,                             // xlLessEqual
+                _ => true,
+            }
+        }
+        // Any value / dates / times / custom formulas are not evaluated
+        _ => true,
+    }
+}
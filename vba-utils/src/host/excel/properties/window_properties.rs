@@ -0,0 +1,104 @@
+// src/host/excel/properties/window_properties.rs
+// Property handlers for Window object (ActiveWindow)
+// View settings are kept in the engine so macros can read back what they set
+
+use anyhow::{Result, bail};
+use crate::context::Value;
+use crate::host::excel::{engine, objects};
+use crate::symbol::lower;
+
+/// Get Window property by name
+/// Data format: window index ("1"); there is a single window
+pub fn get_window_property(_data: &str, property: &str) -> Result<Value> {
+    let window = engine::window_state();
+    match &*lower(property) {
+        "zoom" => Ok(Value::Double(window.zoom)),
+        "scrollrow" => Ok(Value::Integer(window.scroll_row as i64)),
+        "scrollcolumn" => Ok(Value::Integer(window.scroll_column as i64)),
+        "splitrow" => Ok(Value::Integer(window.split_row as i64)),
+        "splitcolumn" => Ok(Value::Integer(window.split_column as i64)),
+        "freezepanes" => Ok(Value::Boolean(window.freeze_panes)),
+        "displaygridlines" => Ok(Value::Boolean(window.display_gridlines)),
+        "displayheadings" => Ok(Value::Boolean(window.display_headings)),
+        "windowstate" => Ok(Value::Integer(window.window_state as i64)),
+        "activecell" => Ok(objects::range_object(&engine::get_selection().active_cell)),
+        "selection" | "rangeselection" => Ok(objects::range_object(&engine::get_selection().range)),
+        "activesheet" => Ok(Value::Object(Some(Box::new(Value::String("ActiveSheet".into()))))),
+        _ => bail!("Unknown Window property: {}", property),
+    }
+}
+
+/// Set Window property by name
+pub fn set_window_property(_data: &str, property: &str, value: Value) -> Result<()> {
+    let text = value.as_string();
+    let flag = matches!(value, Value::Boolean(true)) || text == "-1" || text.eq_ignore_ascii_case("true");
+    let number = || text.parse::<f64>().map_err(|_| anyhow::anyhow!("Window.{} expects a number, got '{}'", property, text));
+
+    match &*lower(property) {
+        "zoom" => {
+            let zoom = number()?;
+            if !(10.0..=400.0).contains(&zoom) {
+                bail!("Window.Zoom must be between 10 and 400");
+            }
+            engine::update_window(|w| w.zoom = zoom);
+        }
+        "scrollrow" => { let n = number()? as i32; engine::update_window(|w| w.scroll_row = n.max(1)) }
+        "scrollcolumn" => { let n = number()? as i32; engine::update_window(|w| w.scroll_column = n.max(1)) }
+        "splitrow" => { let n = number()? as i32; engine::update_window(|w| w.split_row = n.max(0)) }
+        "splitcolumn" => { let n = number()? as i32; engine::update_window(|w| w.split_column = n.max(0)) }
+        "freezepanes" => {
+            // Without an existing split, Excel freezes above and left of the active cell
+            let (row, col) = engine::address_to_indices(&engine::get_selection().active_cell).unwrap_or((0, 0));
+            engine::update_window(|w| {
+                if flag && w.split_row == 0 && w.split_column == 0 {
+                    w.split_row = (row + 1 - w.scroll_row).max(0);
+                    w.split_column = (col + 1 - w.scroll_column).max(0);
+                }
+                w.freeze_panes = flag;
+            });
+        }
+        "displaygridlines" => engine::update_window(|w| w.display_gridlines = flag),
+        "displayheadings" => engine::update_window(|w| w.display_headings = flag),
+        "windowstate" => { let n = number()? as i32; engine::update_window(|w| w.window_state = n) }
+        _ => bail!("Cannot set Window property: {}", property),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::excel::static_engine::StaticWorkbook;
+
+    #[test]
+    fn test_zoom_must_be_between_10_and_400() {
+        let _workbook = StaticWorkbook::new().enter();
+        set_window_property("1", "Zoom", Value::Integer(150)).unwrap();
+        assert!(set_window_property("1", "Zoom", Value::Integer(5)).is_err());
+        assert!(set_window_property("1", "Zoom", Value::String("big".into())).is_err());
+        assert_eq!(get_window_property("1", "Zoom").unwrap().as_string(), "150");
+    }
+
+    #[test]
+    fn test_freeze_panes_splits_at_the_active_cell() {
+        let _workbook = StaticWorkbook::new().enter();
+        engine::select_range("C4").unwrap();
+        set_window_property("1", "FreezePanes", Value::Boolean(true)).unwrap();
+        let window = engine::window_state();
+        assert_eq!((window.split_row, window.split_column, window.freeze_panes), (3, 2, true));
+    }
+
+    #[test]
+    fn test_scroll_positions_stay_on_the_sheet() {
+        let _workbook = StaticWorkbook::new().enter();
+        set_window_property("1", "ScrollRow", Value::Integer(-3)).unwrap();
+        assert_eq!(get_window_property("1", "ScrollRow").unwrap().as_string(), "1");
+    }
+
+    #[test]
+    fn test_unknown_window_properties() {
+        let _workbook = StaticWorkbook::new().enter();
+        assert!(get_window_property("1", "Caption2").is_err());
+        assert!(set_window_property("1", "ActiveCell", Value::String("A1".into())).is_err());
+    }
+}
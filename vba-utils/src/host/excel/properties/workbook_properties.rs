@@ -0,0 +1,26 @@
+// src/host/excel/properties/workbook_properties.rs
+// Property handlers for Workbook object
+
+use anyhow::{Result, bail};
+use crate::context::Value;
+use crate::host::excel::engine;
+use crate::symbol::lower;
+
+/// Get Workbook property by name
+/// Data format: empty (ActiveWorkbook)
+pub fn get_workbook_property(_data: &str, property: &str) -> Result<Value> {
+    match &*lower(property) {
+        "pivotcaches" => Ok(Value::String("PivotCaches:".into())),
+        "worksheets" | "sheets" => Ok(Value::String("Worksheets:".into())),
+        "activesheet" => Ok(Value::String(format!("Worksheet:{}::", engine::get_active_sheet()))),
+        _ => bail!("Workbook property not yet implemented: {}", property),
+    }
+}
+
+/// Get Workbooks collection property by name
+pub fn get_workbooks_property(_data: &str, property: &str) -> Result<Value> {
+    match &*lower(property) {
+        "count" => Ok(Value::Integer(1)),
+        _ => bail!("Unknown Workbooks property: {}", property),
+    }
+}
@@ -3,6 +3,9 @@
 
 use anyhow::{Result, bail};
 use crate::context::Value;
+use crate::host::excel::objects::{self, range};
+use crate::host::excel::static_engine::{self, MAX_COL, MAX_ROW};
+use crate::symbol::lower;
 
 /// Get Worksheet property by name
 pub fn get_worksheet_property(data: &str, property: &str) -> Result<Value> {
@@ -19,8 +22,9 @@ pub fn get_worksheet_property(data: &str, property: &str) -> Result<Value> {
     };
     
     let index: i32 = 1; // TODO: get from engine if available
+    let whole_sheet = range::areas_address(&[(0, 0, MAX_ROW, MAX_COL)]);
     
-    match property.to_lowercase().as_str() {
+    match &*lower(property) {
         "name" => Ok(Value::String(name.to_string())),
         "index" => Ok(Value::Integer(index as i64)),
         "visible" => Ok(Value::Boolean(true)), // TODO: get from engine
@@ -31,13 +35,39 @@ pub fn get_worksheet_property(data: &str, property: &str) -> Result<Value> {
             // Return a reference to the AutoFilter object (as an Object value)
             Ok(Value::Object(Some(Box::new(Value::String("AutoFilter".into())))))
         }
+        // Cells/Rows/Columns/UsedRange are addressed on the active sheet
+        "cells" => Ok(objects::range_object(&whole_sheet)),
+        "rows" => Ok(Value::String(format!("Rows:{}", whole_sheet))),
+        "columns" => Ok(Value::String(format!("Columns:{}", whole_sheet))),
+        "usedrange" => {
+            // Bounds of every filled cell; an empty sheet reports A1
+            let used = static_engine::static_get_used_area(&name).unwrap_or((0, 0, 0, 0));
+            Ok(objects::range_object(&range::areas_address(&[used])))
+        }
+        "hyperlinks" => Ok(Value::String(format!("Hyperlinks:{}", name))),
+        "shapes" => Ok(Value::String(format!("Shapes:{}", name))),
+        "pivottables" => Ok(Value::String(format!("PivotTables:{}", name))),
+        "protectcontents" => Ok(Value::Boolean(
+            static_engine::static_get_protection(&name).is_some_and(|p| p.contents),
+        )),
+        "protectionmode" => Ok(Value::Boolean(
+            static_engine::static_get_protection(&name).is_some_and(|p| p.user_interface_only),
+        )),
         _ => bail!("Unknown Worksheet property: {}", property),
     }
 }
 
+/// Get Worksheets (or Sheets) collection property by name
+pub fn get_worksheets_property(_data: &str, property: &str) -> Result<Value> {
+    match &*lower(property) {
+        "count" => Ok(Value::Integer(crate::host::excel::engine::sheet_names().len() as i64)),
+        _ => bail!("Unknown Worksheets property: {}", property),
+    }
+}
+
 /// Set Worksheet property by name
 pub fn set_worksheet_property(_data: &str, property: &str, _value: Value) -> Result<()> {
-    match property.to_lowercase().as_str() {
+    match &*lower(property) {
         "name" => {
             eprintln!("Setting Worksheet.Name not yet implemented");
             Ok(())
@@ -18,37 +18,144 @@
 //
 // ============================================================================
 
-use std::collections::HashMap;
-use std::sync::Mutex;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
 use once_cell::sync::Lazy;
 
+use crate::host::excel::engine;
+use crate::host::excel::methods::application::calculation;
+use crate::interpreter::builtins::constants;
+
 // ============================================================================
 // IN-MEMORY STORAGE (for testing/stub mode)
 // ============================================================================
 
-/// In-memory cell storage for stub mode
-/// Key: "SheetName!Row:Col" (0-based indices)
-static CELL_STORAGE: Lazy<Mutex<HashMap<String, CellData>>> = Lazy::new(|| {
-    Mutex::new(HashMap::new())
-});
+/// The in-memory stores of one workbook
+#[derive(Default)]
+pub(crate) struct Stores {
+    /// Cell storage; key: "SheetName!Row:Col" (0-based indices)
+    cells: Mutex<HashMap<String, CellData>>,
+    formats: Mutex<HashMap<String, CellFormat>>,
+    comments: Mutex<HashMap<String, String>>,
+    /// Merge regions (stores top-left cell of merge region)
+    merges: Mutex<HashMap<String, String>>,
+    validations: Mutex<HashMap<String, ValidationInfo>>,
+    hyperlinks: Mutex<HashMap<String, HyperlinkInfo>>,
+    /// Drawing objects in z-order (oldest first)
+    shapes: Mutex<Vec<ShapeInfo>>,
+    /// Pivot caches (Workbook.PivotCaches, 1-based index = position + 1)
+    pivot_caches: Mutex<Vec<PivotCacheInfo>>,
+    /// Pivot table definitions in creation order
+    pivot_tables: Mutex<Vec<PivotTableInfo>>,
+    /// Locked state set on areas (Range.Locked), later entries win; cells
+    /// outside every area are locked, as in Excel
+    locked_areas: Mutex<Vec<(String, Area, bool)>>,
+    /// Sheet protection, keyed by lowercase sheet name
+    protection: Mutex<HashMap<String, SheetProtection>>,
+    /// Hidden rows and columns: (sheet, 0-based index)
+    hidden_rows: Mutex<HashSet<(String, i32)>>,
+    hidden_columns: Mutex<HashSet<(String, i32)>>,
+    /// Named ranges, keyed by lowercase name
+    names: Mutex<HashMap<String, NamedRange>>,
+    /// Sheets the workbook was seeded with (see `fixture`), in order
+    sheets: Mutex<Vec<String>>,
+    /// Formula dependency graph and calculation mode
+    pub(crate) calc: Mutex<calculation::CalcState>,
+    /// Active sheet, backend, Application flags and the rest of the state
+    /// the `engine` functions keep for this workbook's runs
+    pub(crate) engine: Arc<super::engine::EngineSession>,
+}
+
+/// One workbook's cells, formats, comments, shapes, names and formula
+/// graph, and the engine state its runs work with (active sheet, backend,
+/// buffered writes, Application flags). Every `Context` gets its own, so two
+/// runs in one process never see each other's cells or settings; clones
+/// share the same stores.
+///
+/// The `static_*` and `engine` functions work on the workbook entered on the
+/// calling thread (`ProgramExecutor` enters the context's for the length of
+/// a run) and on [`StaticWorkbook::shared`] outside of one.
+#[derive(Clone, Default)]
+pub struct StaticWorkbook(Arc<Stores>);
+
+/// Used by the `static_*` functions when no workbook is entered
+static SHARED_WORKBOOK: Lazy<StaticWorkbook> = Lazy::new(StaticWorkbook::default);
+
+thread_local! {
+    static CURRENT_WORKBOOK: RefCell<Option<StaticWorkbook>> = const { RefCell::new(None) };
+}
+
+impl StaticWorkbook {
+    /// An empty workbook
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The process-wide workbook, as the `static_*` functions used before
+    /// workbooks were per context
+    pub fn shared() -> Self {
+        SHARED_WORKBOOK.clone()
+    }
+
+    /// The workbook the `static_*` functions use on this thread
+    pub fn current() -> Self {
+        CURRENT_WORKBOOK.with(|current| current.borrow().clone()).unwrap_or_else(Self::shared)
+    }
 
-/// In-memory format storage
-static FORMAT_STORAGE: Lazy<Mutex<HashMap<String, CellFormat>>> = Lazy::new(|| {
-    Mutex::new(HashMap::new())
-});
+    /// Send this thread's `static_*` calls to this workbook until the
+    /// returned scope is dropped (scopes nest)
+    pub fn enter(&self) -> WorkbookScope {
+        let previous = CURRENT_WORKBOOK.with(|current| current.replace(Some(self.clone())));
+        WorkbookScope { previous, _thread: PhantomData }
+    }
 
-/// In-memory comment storage
-static COMMENT_STORAGE: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(|| {
-    Mutex::new(HashMap::new())
-});
+    /// Engine state of this workbook
+    pub(crate) fn engine(&self) -> &Arc<super::engine::EngineSession> {
+        &self.0.engine
+    }
 
-/// In-memory merge storage (stores top-left cell of merge region)
-static MERGE_STORAGE: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(|| {
-    Mutex::new(HashMap::new())
-});
+    /// Whether both handles share the same stores
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for StaticWorkbook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells = self.0.cells.lock().unwrap().len();
+        f.debug_struct("StaticWorkbook").field("cells", &cells).finish()
+    }
+}
+
+/// Restores the previously entered workbook when dropped (see
+/// [`StaticWorkbook::enter`])
+#[must_use = "the workbook is only entered while the scope is alive"]
+pub struct WorkbookScope {
+    previous: Option<StaticWorkbook>,
+    /// Entered on one thread, so it must be left there
+    _thread: PhantomData<*const ()>,
+}
+
+impl Drop for WorkbookScope {
+    fn drop(&mut self) {
+        CURRENT_WORKBOOK.with(|current| *current.borrow_mut() = self.previous.take());
+    }
+}
+
+/// Stores of the workbook entered on this thread
+pub(crate) fn stores() -> Arc<Stores> {
+    StaticWorkbook::current().0
+}
+
+/// Last row / column index on a sheet (0-based), as in Excel 2007+
+pub const MAX_ROW: i32 = 1_048_575;
+pub const MAX_COL: i32 = 16_383;
 
 /// Cell data structure
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct CellData {
     pub value: String,
     pub formula: Option<String>,
@@ -57,7 +164,7 @@ pub struct CellData {
 }
 
 /// Cell format structure  
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct CellFormat {
     pub number_format: String,
     pub horizontal_alignment: i32,  // xlGeneral=-4105, xlLeft=-4131, xlCenter=-4108, xlRight=-4152
@@ -93,7 +200,7 @@ impl Default for CellFormat {
 }
 
 /// Font format structure
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct FontFormat {
     pub name: String,
     pub size: f64,
@@ -121,7 +228,7 @@ impl Default for FontFormat {
 }
 
 /// Interior (fill) format structure
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct InteriorFormat {
     pub color: i64,            // RGB color as Long
     pub color_index: i32,      // xlColorIndexNone=-4142
@@ -143,7 +250,7 @@ impl Default for InteriorFormat {
 }
 
 /// Borders format structure
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct BordersFormat {
     pub left: BorderFormat,
     pub right: BorderFormat,
@@ -154,7 +261,7 @@ pub struct BordersFormat {
 }
 
 /// Single border format
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct BorderFormat {
     pub line_style: i32,       // xlLineStyleNone=-4142, xlContinuous=1, etc.
     pub weight: i32,           // xlThin=2, xlMedium=-4138, xlThick=4
@@ -173,6 +280,80 @@ impl Default for BorderFormat {
     }
 }
 
+// ============================================================================
+// STORAGE RESET
+// ============================================================================
+
+/// Clear every in-memory store of the current workbook (values, formats,
+/// comments, merges, validation, hidden rows/columns, names, sheets), giving the
+/// next run an empty workbook.
+pub fn static_reset() {
+    stores().cells.lock().unwrap().clear();
+    stores().formats.lock().unwrap().clear();
+    stores().comments.lock().unwrap().clear();
+    stores().merges.lock().unwrap().clear();
+    stores().validations.lock().unwrap().clear();
+    stores().hyperlinks.lock().unwrap().clear();
+    stores().shapes.lock().unwrap().clear();
+    stores().pivot_caches.lock().unwrap().clear();
+    stores().pivot_tables.lock().unwrap().clear();
+    stores().locked_areas.lock().unwrap().clear();
+    stores().protection.lock().unwrap().clear();
+    stores().hidden_rows.lock().unwrap().clear();
+    stores().hidden_columns.lock().unwrap().clear();
+    stores().names.lock().unwrap().clear();
+    stores().sheets.lock().unwrap().clear();
+    calculation::reset();
+}
+
+/// Declare a sheet in the current workbook, for backends that list sheets
+/// (`StaticBackend`); cells can be written to any sheet without this
+pub fn static_add_sheet(sheet_name: &str) -> bool {
+    let workbook = stores();
+    let mut sheets = workbook.sheets.lock().unwrap();
+    if sheets.iter().any(|s| s.eq_ignore_ascii_case(sheet_name)) {
+        return false;
+    }
+    sheets.push(sheet_name.to_string());
+    true
+}
+
+/// Sheets declared with `static_add_sheet`
+pub fn static_sheet_names() -> Vec<String> {
+    stores().sheets.lock().unwrap().clone()
+}
+
+/// Sheet and 0-based row and column of a stored cell
+pub(crate) type CellKey = (String, i32, i32);
+
+/// What `diff::WorkbookSnapshot` keeps of a workbook
+pub(crate) struct Contents {
+    pub cells: Vec<(CellKey, CellData)>,
+    pub formats: Vec<(CellKey, CellFormat)>,
+    pub names: Vec<NamedRange>,
+    pub sheets: Vec<String>,
+}
+
+/// Cells, formats, names and declared sheets of the current workbook
+pub(crate) fn static_contents() -> Contents {
+    fn entries<V: Clone>(storage: &HashMap<String, V>) -> Vec<(CellKey, V)> {
+        storage
+            .iter()
+            .filter_map(|(key, value)| {
+                let (sheet, cell) = key.rsplit_once('!')?;
+                let (row, col) = parse_row_col(cell)?;
+                Some(((sheet.to_string(), row, col), value.clone()))
+            })
+            .collect()
+    }
+    let workbook = stores();
+    let cells = entries(&workbook.cells.lock().unwrap());
+    let formats = entries(&workbook.formats.lock().unwrap());
+    let names = workbook.names.lock().unwrap().values().cloned().collect();
+    let sheets = workbook.sheets.lock().unwrap().clone();
+    Contents { cells, formats, names, sheets }
+}
+
 // ============================================================================
 // CELL VALUE FUNCTIONS
 // ============================================================================
@@ -188,7 +369,8 @@ impl Default for BorderFormat {
 /// - String - Cell value as string
 pub fn static_get_cell_value(sheet_name: &str, row: i32, col: i32) -> String {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let storage = CELL_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let storage = workbook.cells.lock().unwrap();
     storage.get(&key)
         .map(|d| d.value.clone())
         .unwrap_or_default()
@@ -205,13 +387,39 @@ pub fn static_get_cell_value(sheet_name: &str, row: i32, col: i32) -> String {
 /// # Returns
 /// - bool - Success
 pub fn static_set_cell_value(sheet_name: &str, row: i32, col: i32, value: &str) -> bool {
-    let key = format!("{}!{}:{}", sheet_name, row, col);
-    let mut storage = CELL_STORAGE.lock().unwrap();
-    let entry = storage.entry(key).or_insert_with(CellData::default);
-    entry.value = value.to_string();
+    let old_value = static_replace_cell_value(sheet_name, row, col, value);
+    engine::notify_cell_changed(sheet_name, row, col, &old_value, value);
     true
 }
 
+/// Store a value and recalculate dependents without notifying change
+/// subscribers, returning the old value (used by `StaticBackend`, whose
+/// writes `engine` reports itself)
+pub(crate) fn static_replace_cell_value(sheet_name: &str, row: i32, col: i32, value: &str) -> String {
+    let key = format!("{}!{}:{}", sheet_name, row, col);
+    let old_value = {
+        let workbook = stores();
+        let mut storage = workbook.cells.lock().unwrap();
+        let entry = storage.entry(key).or_insert_with(CellData::default);
+        let old_value = std::mem::replace(&mut entry.value, value.to_string());
+        // A plain value replaces any formula in the cell
+        entry.formula = None;
+        entry.formula_r1c1 = None;
+        old_value
+    };
+    calculation::value_changed(sheet_name, row, col);
+    old_value
+}
+
+/// Store a formula result without touching the formula or notifying the
+/// calculation chain (used by recalculation itself)
+pub(crate) fn static_store_calculated_value(sheet_name: &str, row: i32, col: i32, value: &str) {
+    let key = format!("{}!{}:{}", sheet_name, row, col);
+    let workbook = stores();
+    let mut storage = workbook.cells.lock().unwrap();
+    storage.entry(key).or_default().value = value.to_string();
+}
+
 /// Get cell formatted text (as displayed)
 /// 
 /// # Parameters
@@ -242,7 +450,8 @@ pub fn static_get_cell_text(sheet_name: &str, row: i32, col: i32) -> String {
 /// - String - Formula (empty if no formula)
 pub fn static_get_cell_formula(sheet_name: &str, row: i32, col: i32) -> String {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let storage = CELL_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let storage = workbook.cells.lock().unwrap();
     storage.get(&key)
         .and_then(|d| d.formula.clone())
         .unwrap_or_default()
@@ -260,10 +469,14 @@ pub fn static_get_cell_formula(sheet_name: &str, row: i32, col: i32) -> String {
 /// - bool - Success
 pub fn static_set_cell_formula(sheet_name: &str, row: i32, col: i32, formula: &str) -> bool {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let mut storage = CELL_STORAGE.lock().unwrap();
-    let entry = storage.entry(key).or_insert_with(CellData::default);
-    entry.formula = Some(formula.to_string());
-    // In real engine, this would trigger recalculation
+    {
+        let workbook = stores();
+        let mut storage = workbook.cells.lock().unwrap();
+        let entry = storage.entry(key).or_insert_with(CellData::default);
+        entry.formula = Some(formula.to_string());
+    }
+    // Recalculates now (Automatic) or marks the cell dirty (Manual)
+    calculation::formula_changed(sheet_name, row, col, Some(formula));
     true
 }
 
@@ -278,7 +491,8 @@ pub fn static_set_cell_formula(sheet_name: &str, row: i32, col: i32, formula: &s
 /// - String - Formula in R1C1 notation
 pub fn static_get_cell_formula_r1c1(sheet_name: &str, row: i32, col: i32) -> String {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let storage = CELL_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let storage = workbook.cells.lock().unwrap();
     storage.get(&key)
         .and_then(|d| d.formula_r1c1.clone())
         .unwrap_or_default()
@@ -296,7 +510,8 @@ pub fn static_get_cell_formula_r1c1(sheet_name: &str, row: i32, col: i32) -> Str
 /// - bool - Success
 pub fn static_set_cell_formula_r1c1(sheet_name: &str, row: i32, col: i32, formula: &str) -> bool {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let mut storage = CELL_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let mut storage = workbook.cells.lock().unwrap();
     let entry = storage.entry(key).or_insert_with(CellData::default);
     entry.formula_r1c1 = Some(formula.to_string());
     true
@@ -335,7 +550,8 @@ pub fn static_set_array_formula(sheet_name: &str, start_row: i32, start_col: i32
     for row in start_row..=end_row {
         for col in start_col..=end_col {
             let key = format!("{}!{}:{}", sheet_name, row, col);
-            let mut storage = CELL_STORAGE.lock().unwrap();
+            let workbook = stores();
+            let mut storage = workbook.cells.lock().unwrap();
             let entry = storage.entry(key).or_insert_with(CellData::default);
             entry.is_array_formula = true;
             if row == start_row && col == start_col {
@@ -343,6 +559,7 @@ pub fn static_set_array_formula(sheet_name: &str, start_row: i32, start_col: i32
             }
         }
     }
+    calculation::formula_changed(sheet_name, start_row, start_col, Some(formula));
     true
 }
 
@@ -357,7 +574,8 @@ pub fn static_set_array_formula(sheet_name: &str, start_row: i32, start_col: i32
 /// - bool - True if part of array formula
 pub fn static_has_array_formula(sheet_name: &str, row: i32, col: i32) -> bool {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let storage = CELL_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let storage = workbook.cells.lock().unwrap();
     storage.get(&key)
         .map(|d| d.is_array_formula)
         .unwrap_or(false)
@@ -378,7 +596,8 @@ pub fn static_has_array_formula(sheet_name: &str, row: i32, col: i32) -> bool {
 /// - String - Number format code (e.g., "General", "0.00", "@")
 pub fn static_get_number_format(sheet_name: &str, row: i32, col: i32) -> String {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let storage = FORMAT_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let storage = workbook.formats.lock().unwrap();
     storage.get(&key)
         .map(|f| f.number_format.clone())
         .unwrap_or_else(|| "General".to_string())
@@ -396,7 +615,8 @@ pub fn static_get_number_format(sheet_name: &str, row: i32, col: i32) -> String
 /// - bool - Success
 pub fn static_set_number_format(sheet_name: &str, row: i32, col: i32, format: &str) -> bool {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let mut storage = FORMAT_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let mut storage = workbook.formats.lock().unwrap();
     let entry = storage.entry(key).or_insert_with(CellFormat::default);
     entry.number_format = format.to_string();
     true
@@ -417,7 +637,8 @@ pub fn static_set_number_format(sheet_name: &str, row: i32, col: i32, format: &s
 /// - i32 - Alignment constant (xlGeneral=-4105, xlLeft=-4131, xlCenter=-4108, xlRight=-4152)
 pub fn static_get_horizontal_alignment(sheet_name: &str, row: i32, col: i32) -> i32 {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let storage = FORMAT_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let storage = workbook.formats.lock().unwrap();
     storage.get(&key)
         .map(|f| f.horizontal_alignment)
         .unwrap_or(-4105) // xlGeneral
@@ -426,7 +647,8 @@ pub fn static_get_horizontal_alignment(sheet_name: &str, row: i32, col: i32) ->
 /// Set horizontal alignment
 pub fn static_set_horizontal_alignment(sheet_name: &str, row: i32, col: i32, alignment: i32) -> bool {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let mut storage = FORMAT_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let mut storage = workbook.formats.lock().unwrap();
     let entry = storage.entry(key).or_insert_with(CellFormat::default);
     entry.horizontal_alignment = alignment;
     true
@@ -435,7 +657,8 @@ pub fn static_set_horizontal_alignment(sheet_name: &str, row: i32, col: i32, ali
 /// Get vertical alignment
 pub fn static_get_vertical_alignment(sheet_name: &str, row: i32, col: i32) -> i32 {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let storage = FORMAT_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let storage = workbook.formats.lock().unwrap();
     storage.get(&key)
         .map(|f| f.vertical_alignment)
         .unwrap_or(-4107) // xlBottom
@@ -444,7 +667,8 @@ pub fn static_get_vertical_alignment(sheet_name: &str, row: i32, col: i32) -> i3
 /// Set vertical alignment
 pub fn static_set_vertical_alignment(sheet_name: &str, row: i32, col: i32, alignment: i32) -> bool {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let mut storage = FORMAT_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let mut storage = workbook.formats.lock().unwrap();
     let entry = storage.entry(key).or_insert_with(CellFormat::default);
     entry.vertical_alignment = alignment;
     true
@@ -453,7 +677,8 @@ pub fn static_set_vertical_alignment(sheet_name: &str, row: i32, col: i32, align
 /// Get text orientation (-90 to 90 degrees)
 pub fn static_get_orientation(sheet_name: &str, row: i32, col: i32) -> i32 {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let storage = FORMAT_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let storage = workbook.formats.lock().unwrap();
     storage.get(&key)
         .map(|f| f.orientation)
         .unwrap_or(0)
@@ -462,7 +687,8 @@ pub fn static_get_orientation(sheet_name: &str, row: i32, col: i32) -> i32 {
 /// Set text orientation
 pub fn static_set_orientation(sheet_name: &str, row: i32, col: i32, degrees: i32) -> bool {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let mut storage = FORMAT_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let mut storage = workbook.formats.lock().unwrap();
     let entry = storage.entry(key).or_insert_with(CellFormat::default);
     entry.orientation = degrees.clamp(-90, 90);
     true
@@ -471,7 +697,8 @@ pub fn static_set_orientation(sheet_name: &str, row: i32, col: i32, degrees: i32
 /// Get wrap text setting
 pub fn static_get_wrap_text(sheet_name: &str, row: i32, col: i32) -> bool {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let storage = FORMAT_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let storage = workbook.formats.lock().unwrap();
     storage.get(&key)
         .map(|f| f.wrap_text)
         .unwrap_or(false)
@@ -480,7 +707,8 @@ pub fn static_get_wrap_text(sheet_name: &str, row: i32, col: i32) -> bool {
 /// Set wrap text setting
 pub fn static_set_wrap_text(sheet_name: &str, row: i32, col: i32, wrap: bool) -> bool {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let mut storage = FORMAT_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let mut storage = workbook.formats.lock().unwrap();
     let entry = storage.entry(key).or_insert_with(CellFormat::default);
     entry.wrap_text = wrap;
     true
@@ -489,7 +717,8 @@ pub fn static_set_wrap_text(sheet_name: &str, row: i32, col: i32, wrap: bool) ->
 /// Get indent level (0-15)
 pub fn static_get_indent_level(sheet_name: &str, row: i32, col: i32) -> i32 {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let storage = FORMAT_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let storage = workbook.formats.lock().unwrap();
     storage.get(&key)
         .map(|f| f.indent_level)
         .unwrap_or(0)
@@ -498,7 +727,8 @@ pub fn static_get_indent_level(sheet_name: &str, row: i32, col: i32) -> i32 {
 /// Set indent level
 pub fn static_set_indent_level(sheet_name: &str, row: i32, col: i32, level: i32) -> bool {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let mut storage = FORMAT_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let mut storage = workbook.formats.lock().unwrap();
     let entry = storage.entry(key).or_insert_with(CellFormat::default);
     entry.indent_level = level.clamp(0, 15);
     true
@@ -510,26 +740,67 @@ pub fn static_set_indent_level(sheet_name: &str, row: i32, col: i32, level: i32)
 
 /// Get locked state
 pub fn static_get_locked(sheet_name: &str, row: i32, col: i32) -> bool {
-    let key = format!("{}!{}:{}", sheet_name, row, col);
-    let storage = FORMAT_STORAGE.lock().unwrap();
-    storage.get(&key)
-        .map(|f| f.locked)
+    stores().locked_areas.lock().unwrap().iter().rev()
+        .find(|(sheet, area, _)| sheet.eq_ignore_ascii_case(sheet_name) && area_contains(*area, row, col))
+        .map(|(_, _, locked)| *locked)
         .unwrap_or(true) // Default is locked
 }
 
 /// Set locked state
 pub fn static_set_locked(sheet_name: &str, row: i32, col: i32, locked: bool) -> bool {
-    let key = format!("{}!{}:{}", sheet_name, row, col);
-    let mut storage = FORMAT_STORAGE.lock().unwrap();
-    let entry = storage.entry(key).or_insert_with(CellFormat::default);
-    entry.locked = locked;
+    static_set_locked_area(sheet_name, (row, col, row, col), locked)
+}
+
+/// Set locked state of an area (Cells.Locked = False covers the whole sheet)
+pub fn static_set_locked_area(sheet_name: &str, area: Area, locked: bool) -> bool {
+    let workbook = stores();
+    let mut areas = workbook.locked_areas.lock().unwrap();
+    // Entries inside the new area no longer decide anything
+    areas.retain(|(sheet, old, _)| {
+        !(sheet.eq_ignore_ascii_case(sheet_name) && old.0 >= area.0 && old.1 >= area.1 && old.2 <= area.2 && old.3 <= area.3)
+    });
+    areas.push((sheet_name.to_string(), area, locked));
     true
 }
 
+/// Whether any cell of an area is locked
+pub fn static_any_locked(sheet_name: &str, area: Area) -> bool {
+    locked_states(sheet_name, area).into_iter().any(|locked| locked)
+}
+
+/// Whether every cell of an area is locked
+pub fn static_all_locked(sheet_name: &str, area: Area) -> bool {
+    locked_states(sheet_name, area).into_iter().all(|locked| locked)
+}
+
+/// Locked state of one cell per block of an area with the same state.
+/// The state only changes at the edges of `locked_areas` entries, so checking
+/// the cells where those edges cross inside `area` covers every cell.
+fn locked_states(sheet_name: &str, area: Area) -> Vec<bool> {
+    let edges: Vec<Area> = stores().locked_areas.lock().unwrap().iter()
+        .filter(|(sheet, _, _)| sheet.eq_ignore_ascii_case(sheet_name))
+        .map(|(_, a, _)| *a)
+        .collect();
+    let cuts = |start: i32, end: i32, bounds: &dyn Fn(&Area) -> [i32; 2]| {
+        let mut cuts: Vec<i32> = edges.iter().flat_map(bounds).filter(|i| (start..=end).contains(i)).collect();
+        cuts.push(start);
+        cuts.sort_unstable();
+        cuts.dedup();
+        cuts
+    };
+    let rows = cuts(area.0, area.2, &|a| [a.0, a.2 + 1]);
+    let cols = cuts(area.1, area.3, &|a| [a.1, a.3 + 1]);
+    rows.iter()
+        .flat_map(|&row| cols.iter().map(move |&col| (row, col)))
+        .map(|(row, col)| static_get_locked(sheet_name, row, col))
+        .collect()
+}
+
 /// Get hidden state
 pub fn static_get_hidden(sheet_name: &str, row: i32, col: i32) -> bool {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let storage = FORMAT_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let storage = workbook.formats.lock().unwrap();
     storage.get(&key)
         .map(|f| f.hidden)
         .unwrap_or(false)
@@ -538,12 +809,47 @@ pub fn static_get_hidden(sheet_name: &str, row: i32, col: i32) -> bool {
 /// Set hidden state
 pub fn static_set_hidden(sheet_name: &str, row: i32, col: i32, hidden: bool) -> bool {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let mut storage = FORMAT_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let mut storage = workbook.formats.lock().unwrap();
     let entry = storage.entry(key).or_insert_with(CellFormat::default);
     entry.hidden = hidden;
     true
 }
 
+/// Check if a row is hidden (Rows(n).Hidden)
+pub fn static_is_row_hidden(sheet_name: &str, row: i32) -> bool {
+    stores().hidden_rows.lock().unwrap().contains(&(sheet_name.to_string(), row))
+}
+
+/// Hide or show a row
+pub fn static_set_row_hidden(sheet_name: &str, row: i32, hidden: bool) -> bool {
+    let workbook = stores();
+    let mut rows = workbook.hidden_rows.lock().unwrap();
+    if hidden {
+        rows.insert((sheet_name.to_string(), row));
+    } else {
+        rows.remove(&(sheet_name.to_string(), row));
+    }
+    true
+}
+
+/// Check if a column is hidden (Columns("C").Hidden)
+pub fn static_is_column_hidden(sheet_name: &str, col: i32) -> bool {
+    stores().hidden_columns.lock().unwrap().contains(&(sheet_name.to_string(), col))
+}
+
+/// Hide or show a column
+pub fn static_set_column_hidden(sheet_name: &str, col: i32, hidden: bool) -> bool {
+    let workbook = stores();
+    let mut cols = workbook.hidden_columns.lock().unwrap();
+    if hidden {
+        cols.insert((sheet_name.to_string(), col));
+    } else {
+        cols.remove(&(sheet_name.to_string(), col));
+    }
+    true
+}
+
 // ============================================================================
 // MERGE CELL FUNCTIONS
 // ============================================================================
@@ -559,7 +865,8 @@ pub fn static_set_hidden(sheet_name: &str, row: i32, col: i32, hidden: bool) ->
 /// - bool - True if merged
 pub fn static_is_merged(sheet_name: &str, row: i32, col: i32) -> bool {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let storage = MERGE_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let storage = workbook.merges.lock().unwrap();
     storage.contains_key(&key)
 }
 
@@ -576,7 +883,8 @@ pub fn static_is_merged(sheet_name: &str, row: i32, col: i32) -> bool {
 /// # Returns
 /// - bool - Success
 pub fn static_merge_cells(sheet_name: &str, start_row: i32, start_col: i32, end_row: i32, end_col: i32, across: bool) -> bool {
-    let mut storage = MERGE_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let mut storage = workbook.merges.lock().unwrap();
     let top_left = format!("{}:{}", start_row, start_col);
     
     if across {
@@ -601,7 +909,8 @@ pub fn static_merge_cells(sheet_name: &str, start_row: i32, start_col: i32, end_
 
 /// Unmerge cells
 pub fn static_unmerge_cells(sheet_name: &str, start_row: i32, start_col: i32, end_row: i32, end_col: i32) -> bool {
-    let mut storage = MERGE_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let mut storage = workbook.merges.lock().unwrap();
     for row in start_row..=end_row {
         for col in start_col..=end_col {
             let key = format!("{}!{}:{}", sheet_name, row, col);
@@ -611,6 +920,17 @@ pub fn static_unmerge_cells(sheet_name: &str, start_row: i32, start_col: i32, en
     true
 }
 
+/// Top-left cell of the merged area containing a cell
+/// 
+/// # Returns
+/// - Option<(i32, i32)> - (row, col) of the merge anchor, None if not merged
+pub fn static_get_merge_anchor(sheet_name: &str, row: i32, col: i32) -> Option<(i32, i32)> {
+    let key = format!("{}!{}:{}", sheet_name, row, col);
+    let workbook = stores();
+    let storage = workbook.merges.lock().unwrap();
+    storage.get(&key).and_then(|anchor| parse_row_col(anchor))
+}
+
 // ============================================================================
 // COMMENT FUNCTIONS
 // ============================================================================
@@ -618,14 +938,16 @@ pub fn static_unmerge_cells(sheet_name: &str, start_row: i32, start_col: i32, en
 /// Get cell comment
 pub fn static_get_comment(sheet_name: &str, row: i32, col: i32) -> Option<String> {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let storage = COMMENT_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let storage = workbook.comments.lock().unwrap();
     storage.get(&key).cloned()
 }
 
 /// Add cell comment
 pub fn static_add_comment(sheet_name: &str, row: i32, col: i32, text: &str) -> bool {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let mut storage = COMMENT_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let mut storage = workbook.comments.lock().unwrap();
     storage.insert(key, text.to_string());
     true
 }
@@ -633,7 +955,8 @@ pub fn static_add_comment(sheet_name: &str, row: i32, col: i32, text: &str) -> b
 /// Clear cell comment
 pub fn static_clear_comment(sheet_name: &str, row: i32, col: i32) -> bool {
     let key = format!("{}!{}:{}", sheet_name, row, col);
-    let mut storage = COMMENT_STORAGE.lock().unwrap();
+    let workbook = stores();
+    let mut storage = workbook.comments.lock().unwrap();
     storage.remove(&key);
     true
 }
@@ -749,8 +1072,8 @@ pub fn static_clear_range(sheet_name: &str, start_row: i32, start_col: i32, end_
         for col in start_col..=end_col {
             static_set_cell_value(sheet_name, row, col, "");
             let key = format!("{}!{}:{}", sheet_name, row, col);
-            FORMAT_STORAGE.lock().unwrap().remove(&key);
-            COMMENT_STORAGE.lock().unwrap().remove(&key);
+            stores().formats.lock().unwrap().remove(&key);
+            stores().comments.lock().unwrap().remove(&key);
         }
     }
     true
@@ -771,7 +1094,7 @@ pub fn static_clear_formats(sheet_name: &str, start_row: i32, start_col: i32, en
     for row in start_row..=end_row {
         for col in start_col..=end_col {
             let key = format!("{}!{}:{}", sheet_name, row, col);
-            FORMAT_STORAGE.lock().unwrap().remove(&key);
+            stores().formats.lock().unwrap().remove(&key);
         }
     }
     true
@@ -779,11 +1102,12 @@ pub fn static_clear_formats(sheet_name: &str, start_row: i32, start_col: i32, en
 
 /// Clear comments only
 pub fn static_clear_comments(sheet_name: &str, start_row: i32, start_col: i32, end_row: i32, end_col: i32) -> bool {
-    for row in start_row..=end_row {
-        for col in start_col..=end_col {
-            static_clear_comment(sheet_name, row, col);
-        }
-    }
+    // Walk the stored comments rather than the area (Columns("A") is a million cells)
+    let prefix = format!("{}!", sheet_name);
+    stores().comments.lock().unwrap().retain(|key, _| {
+        let Some((row, col)) = key.strip_prefix(&prefix).and_then(parse_row_col) else { return true };
+        !(start_row..=end_row).contains(&row) || !(start_col..=end_col).contains(&col)
+    });
     true
 }
 
@@ -899,22 +1223,265 @@ pub fn static_replace_in_range(
 /// # Returns
 /// - bool - Success
 pub fn static_insert_cells(
-    _sheet_name: &str, _start_row: i32, _start_col: i32, _end_row: i32, _end_col: i32,
-    _shift: i32
+    sheet_name: &str, start_row: i32, start_col: i32, end_row: i32, end_col: i32,
+    shift: i32
 ) -> bool {
-    // Complex operation requiring shifting all cells
-    // Would be implemented in native engine
+    let edit = if shift == -4161 {
+        Shift::columns(start_row, end_row, start_col, end_col - start_col + 1)
+    } else {
+        Shift::rows(start_col, end_col, start_row, end_row - start_row + 1)
+    };
+    apply_shift(sheet_name, &edit);
     true
 }
 
 /// Delete cells
+/// 
+/// # Parameters
+/// - `shift`: i32 - xlShiftUp(-4162) or xlShiftToLeft(-4159)
 pub fn static_delete_cells(
-    _sheet_name: &str, _start_row: i32, _start_col: i32, _end_row: i32, _end_col: i32,
-    _shift: i32
+    sheet_name: &str, start_row: i32, start_col: i32, end_row: i32, end_col: i32,
+    shift: i32
 ) -> bool {
+    let edit = if shift == -4159 {
+        Shift::columns(start_row, end_row, start_col, -(end_col - start_col + 1))
+    } else {
+        Shift::rows(start_col, end_col, start_row, -(end_row - start_row + 1))
+    };
+    apply_shift(sheet_name, &edit);
+    true
+}
+
+/// Insert entire rows (Rows(2).Insert)
+pub fn static_insert_rows(sheet_name: &str, start_row: i32, count: i32) -> bool {
+    apply_shift(sheet_name, &Shift::rows(0, MAX_COL, start_row, count));
+    true
+}
+
+/// Delete entire rows (Rows(2).Delete)
+pub fn static_delete_rows(sheet_name: &str, start_row: i32, count: i32) -> bool {
+    apply_shift(sheet_name, &Shift::rows(0, MAX_COL, start_row, -count));
+    true
+}
+
+/// Insert entire columns (Columns("C").Insert)
+pub fn static_insert_columns(sheet_name: &str, start_col: i32, count: i32) -> bool {
+    apply_shift(sheet_name, &Shift::columns(0, MAX_ROW, start_col, count));
+    true
+}
+
+/// Delete entire columns (Columns("C").Delete)
+pub fn static_delete_columns(sheet_name: &str, start_col: i32, count: i32) -> bool {
+    apply_shift(sheet_name, &Shift::columns(0, MAX_ROW, start_col, -count));
     true
 }
 
+/// A rectangle of cells: (start_row, start_col, end_row, end_col), 0-based
+pub type Area = (i32, i32, i32, i32);
+
+fn area_contains(area: Area, row: i32, col: i32) -> bool {
+    (area.0..=area.2).contains(&row) && (area.1..=area.3).contains(&col)
+}
+
+/// An insert (count > 0) or delete (count < 0) of `count` rows or columns
+/// at `at`, limited to the rows/columns `across_start..=across_end` on the
+/// other axis
+struct Shift {
+    rows: bool,
+    across_start: i32,
+    across_end: i32,
+    at: i32,
+    count: i32,
+}
+
+impl Shift {
+    fn rows(start_col: i32, end_col: i32, at: i32, count: i32) -> Self {
+        Shift { rows: true, across_start: start_col, across_end: end_col, at, count }
+    }
+
+    fn columns(start_row: i32, end_row: i32, at: i32, count: i32) -> Self {
+        Shift { rows: false, across_start: start_row, across_end: end_row, at, count }
+    }
+
+    /// Whether the edit spans the whole sheet (moves hidden flags too)
+    fn is_entire(&self) -> bool {
+        self.across_start == 0 && self.across_end >= if self.rows { MAX_COL } else { MAX_ROW }
+    }
+
+    /// New index along the shifted axis; None if deleted
+    fn index(&self, along: i32) -> Option<i32> {
+        if self.count >= 0 {
+            Some(if along >= self.at { along + self.count } else { along })
+        } else if along < self.at {
+            Some(along)
+        } else if along < self.at - self.count {
+            None
+        } else {
+            Some(along + self.count)
+        }
+    }
+
+    /// Where a cell ends up; None if it was deleted
+    fn cell(&self, row: i32, col: i32) -> Option<(i32, i32)> {
+        let (along, across) = if self.rows { (row, col) } else { (col, row) };
+        if across < self.across_start || across > self.across_end {
+            return Some((row, col));
+        }
+        let along = self.index(along)?;
+        Some(if self.rows { (along, col) } else { (row, along) })
+    }
+
+    /// New bounds of a block (start..=end along the axis); None if all of it
+    /// was deleted. Inserting inside a block grows it.
+    fn span(&self, start: i32, end: i32) -> Option<(i32, i32)> {
+        if self.count >= 0 {
+            let grow = |i: i32| if i >= self.at { i + self.count } else { i };
+            return Some((grow(start), grow(end)));
+        }
+        let deleted_end = self.at - self.count - 1;
+        let new_start = if start > deleted_end { start + self.count } else { start.min(self.at) };
+        let new_end = if end > deleted_end { end + self.count } else if end >= self.at { self.at - 1 } else { end };
+        (new_end >= new_start).then_some((new_start, new_end))
+    }
+
+    /// New bounds of an area that covers the edited rows/columns across the
+    /// other axis; areas only partly across it are unchanged. None if all of
+    /// it was deleted.
+    fn area(&self, area: Area) -> Option<Area> {
+        let (start_row, start_col, end_row, end_col) = area;
+        let (across_start, across_end) = if self.rows { (start_col, end_col) } else { (start_row, end_row) };
+        if across_start < self.across_start || across_end > self.across_end {
+            return Some(area);
+        }
+        if self.rows {
+            let (start, end) = self.span(start_row, end_row)?;
+            Some((start, start_col, end, end_col))
+        } else {
+            let (start, end) = self.span(start_col, end_col)?;
+            Some((start_row, start, end_row, end))
+        }
+    }
+}
+
+/// Parse "row:col" (the tail of a storage key, or a merge anchor)
+fn parse_row_col(text: &str) -> Option<(i32, i32)> {
+    let (row, col) = text.split_once(':')?;
+    Some((row.parse().ok()?, col.parse().ok()?))
+}
+
+/// Move every "Sheet!row:col" entry of `storage` on `sheet_name`. Returns
+/// the positions that were vacated or filled.
+fn shift_keys<V>(storage: &mut HashMap<String, V>, sheet_name: &str, edit: &Shift) -> Vec<(i32, i32)> {
+    let prefix = format!("{}!", sheet_name);
+    let keys: Vec<String> = storage.keys().filter(|k| k.starts_with(&prefix)).cloned().collect();
+    let mut touched = Vec::new();
+    let mut moving = Vec::new();
+    for key in keys {
+        let Some((row, col)) = parse_row_col(&key[prefix.len()..]) else { continue };
+        let target = edit.cell(row, col);
+        if target == Some((row, col)) {
+            continue;
+        }
+        let value = storage.remove(&key).unwrap();
+        touched.push((row, col));
+        if let Some(target) = target {
+            touched.push(target);
+            moving.push((target, value));
+        }
+    }
+    // Re-insert only after every entry has been lifted, so nothing is overwritten
+    for ((row, col), value) in moving {
+        storage.insert(format!("{}{}:{}", prefix, row, col), value);
+    }
+    touched
+}
+
+/// Apply an insert/delete to every store: values, formats, comments,
+/// validation, merges, hidden rows/columns, named ranges and the
+/// calculation chain.
+/// Formula text is not rewritten.
+fn apply_shift(sheet_name: &str, edit: &Shift) {
+    let before: HashMap<String, String> = {
+        let prefix = format!("{}!", sheet_name);
+        let workbook = stores();
+        let storage = workbook.cells.lock().unwrap();
+        storage.iter().filter(|(k, _)| k.starts_with(&prefix)).map(|(k, d)| (k.clone(), d.value.clone())).collect()
+    };
+    let touched = shift_keys(&mut stores().cells.lock().unwrap(), sheet_name, edit);
+    shift_keys(&mut stores().formats.lock().unwrap(), sheet_name, edit);
+    shift_keys(&mut stores().comments.lock().unwrap(), sheet_name, edit);
+    shift_keys(&mut stores().validations.lock().unwrap(), sheet_name, edit);
+    shift_keys(&mut stores().hyperlinks.lock().unwrap(), sheet_name, edit);
+
+    {
+        let workbook = stores();
+        let mut merges = workbook.merges.lock().unwrap();
+        shift_keys(&mut merges, sheet_name, edit);
+        let prefix = format!("{}!", sheet_name);
+        merges.retain(|key, anchor| {
+            if !key.starts_with(&prefix) {
+                return true;
+            }
+            // An area that lost its anchor is no longer merged
+            match parse_row_col(anchor).and_then(|(r, c)| edit.cell(r, c)) {
+                Some((r, c)) => {
+                    *anchor = format!("{}:{}", r, c);
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+
+    if edit.is_entire() {
+        let workbook = stores();
+        let hidden = if edit.rows { &workbook.hidden_rows } else { &workbook.hidden_columns };
+        let mut hidden = hidden.lock().unwrap();
+        let on_sheet: Vec<(String, i32)> = hidden.iter().filter(|(s, _)| s == sheet_name).cloned().collect();
+        for entry in on_sheet {
+            hidden.remove(&entry);
+            if let Some(index) = edit.index(entry.1) {
+                hidden.insert((entry.0, index));
+            }
+        }
+    }
+
+    stores().names.lock().unwrap().retain(|_, name| {
+        if !name.sheet_name.eq_ignore_ascii_case(sheet_name) {
+            return true;
+        }
+        // A name whose cells were all deleted would refer to #REF!
+        let Some(area) = edit.area((name.start_row, name.start_col, name.end_row, name.end_col)) else { return false };
+        (name.start_row, name.start_col, name.end_row, name.end_col) = area;
+        true
+    });
+
+    stores().locked_areas.lock().unwrap().retain_mut(|(sheet, area, _)| {
+        if !sheet.eq_ignore_ascii_case(sheet_name) {
+            return true;
+        }
+        match edit.area(*area) {
+            Some(moved) => {
+                *area = moved;
+                true
+            }
+            None => false,
+        }
+    });
+
+    calculation::cells_moved(sheet_name, &|row, col| edit.cell(row, col));
+
+    // Every position whose value changed reports as a cell change
+    let touched: HashSet<(i32, i32)> = touched.into_iter().collect();
+    for (row, col) in touched {
+        let old_value = before.get(&format!("{}!{}:{}", sheet_name, row, col)).cloned().unwrap_or_default();
+        let new_value = static_get_cell_value(sheet_name, row, col);
+        if old_value != new_value {
+            engine::notify_cell_changed(sheet_name, row, col, &old_value, &new_value);
+        }
+    }
+}
+
 // ============================================================================
 // FILL FUNCTIONS
 // ============================================================================
@@ -1007,8 +1574,8 @@ pub fn static_remove_duplicates(
 // ============================================================================
 
 /// Calculate range
-pub fn static_calculate_range(_sheet_name: &str, _start_row: i32, _start_col: i32, _end_row: i32, _end_col: i32) -> bool {
-    // Would trigger formula recalculation
+pub fn static_calculate_range(sheet_name: &str, start_row: i32, start_col: i32, end_row: i32, end_col: i32) -> bool {
+    calculation::calculate_range(sheet_name, start_row, start_col, end_row, end_col);
     true
 }
 
@@ -1017,30 +1584,39 @@ pub fn static_calculate_range(_sheet_name: &str, _start_row: i32, _start_col: i3
 // ============================================================================
 
 /// Get direct dependents (cells that reference this cell)
-pub fn static_get_direct_dependents(_sheet_name: &str, _row: i32, _col: i32) -> Vec<(i32, i32)> {
-    Vec::new()
+pub fn static_get_direct_dependents(sheet_name: &str, row: i32, col: i32) -> Vec<(i32, i32)> {
+    on_sheet(sheet_name, calculation::direct_dependents(sheet_name, row, col))
 }
 
 /// Get direct precedents (cells that this cell references)
-pub fn static_get_direct_precedents(_sheet_name: &str, _row: i32, _col: i32) -> Vec<(i32, i32)> {
-    Vec::new()
+pub fn static_get_direct_precedents(sheet_name: &str, row: i32, col: i32) -> Vec<(i32, i32)> {
+    on_sheet(sheet_name, calculation::direct_precedents(sheet_name, row, col))
 }
 
 /// Get all dependents (recursive)
-pub fn static_get_dependents(_sheet_name: &str, _row: i32, _col: i32) -> Vec<(i32, i32)> {
-    Vec::new()
+pub fn static_get_dependents(sheet_name: &str, row: i32, col: i32) -> Vec<(i32, i32)> {
+    on_sheet(sheet_name, calculation::dependents(sheet_name, row, col))
 }
 
 /// Get all precedents (recursive)
-pub fn static_get_precedents(_sheet_name: &str, _row: i32, _col: i32) -> Vec<(i32, i32)> {
-    Vec::new()
+pub fn static_get_precedents(sheet_name: &str, row: i32, col: i32) -> Vec<(i32, i32)> {
+    on_sheet(sheet_name, calculation::precedents(sheet_name, row, col))
+}
+
+/// Keep the cells on `sheet_name` (Range.Dependents never crosses sheets)
+fn on_sheet(sheet_name: &str, cells: Vec<calculation::CellKey>) -> Vec<(i32, i32)> {
+    cells.into_iter()
+        .filter(|(sheet, _, _)| sheet == sheet_name)
+        .map(|(_, row, col)| (row, col))
+        .collect()
 }
 
 // ============================================================================
 // SPECIAL CELLS FUNCTION
 // ============================================================================
 
-/// Get special cells matching criteria
+/// Get special cells matching criteria. Like Excel, only cells inside the
+/// used range are considered.
 /// 
 /// # Parameters
 /// - `sheet_name`: &str - Sheet name
@@ -1048,26 +1624,40 @@ pub fn static_get_precedents(_sheet_name: &str, _row: i32, _col: i32) -> Vec<(i3
 /// - `start_col`: i32 - Start column
 /// - `end_row`: i32 - End row
 /// - `end_col`: i32 - End column
-/// - `cell_type`: i32 - xlCellTypeConstants(2), xlCellTypeFormulas(-4123), xlCellTypeBlanks(4), etc.
-/// - `value_type`: Option<i32> - xlNumbers(1), xlTextValues(2), xlLogical(4), xlErrors(16)
+/// - `cell_type`: i32 - xlCellTypeConstants(2), xlCellTypeFormulas(-4123), xlCellTypeBlanks(4),
+///   xlCellTypeVisible(12), xlCellTypeLastCell(11), xlCellTypeComments(-4144)
+/// - `value_type`: Option<i32> - Sum of xlNumbers(1), xlTextValues(2), xlLogical(4), xlErrors(16)
 /// 
 /// # Returns
-/// - Vec<(i32, i32)> - List of matching cell coordinates
+/// - Vec<(i32, i32)> - List of matching cell coordinates, row by row
 pub fn static_get_special_cells(
     sheet_name: &str, start_row: i32, start_col: i32, end_row: i32, end_col: i32,
-    cell_type: i32, _value_type: Option<i32>
+    cell_type: i32, value_type: Option<i32>
 ) -> Vec<(i32, i32)> {
+    let Some(used) = static_get_used_area(sheet_name) else { return Vec::new() };
+    if cell_type == 11 {
+        // xlCellTypeLastCell ignores the range it is called on
+        return vec![(used.2, used.3)];
+    }
+    let (start_row, start_col) = (start_row.max(used.0), start_col.max(used.1));
+    let (end_row, end_col) = (end_row.min(used.2), end_col.min(used.3));
+    let value_type = value_type.unwrap_or(1 | 2 | 4 | 16);
     let mut results = Vec::new();
     
     for row in start_row..=end_row {
+        if cell_type == 12 && static_is_row_hidden(sheet_name, row) {
+            continue;
+        }
         for col in start_col..=end_col {
             let value = static_get_cell_value(sheet_name, row, col);
             let formula = static_get_cell_formula(sheet_name, row, col);
             
             let matches = match cell_type {
                 4 => value.is_empty() && formula.is_empty(), // xlCellTypeBlanks
-                2 => !value.is_empty() && formula.is_empty(), // xlCellTypeConstants
-                -4123 => !formula.is_empty(), // xlCellTypeFormulas
+                2 => !value.is_empty() && formula.is_empty() && value_type & special_value_type(&value) != 0, // xlCellTypeConstants
+                -4123 => !formula.is_empty() && value_type & special_value_type(&value) != 0, // xlCellTypeFormulas
+                12 => !static_is_column_hidden(sheet_name, col), // xlCellTypeVisible
+                -4144 => static_get_comment(sheet_name, row, col).is_some(), // xlCellTypeComments
                 _ => false,
             };
             
@@ -1079,19 +1669,127 @@ pub fn static_get_special_cells(
     results
 }
 
+/// Classify a cell value for SpecialCells: xlNumbers(1), xlTextValues(2),
+/// xlLogical(4) or xlErrors(16)
+fn special_value_type(value: &str) -> i32 {
+    if value.parse::<f64>().is_ok() {
+        1
+    } else if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+        4
+    } else if value.starts_with('#') && (value.ends_with('!') || value.ends_with('?') || value == "#N/A") {
+        16
+    } else {
+        2
+    }
+}
+
+/// Group cells (as returned by static_get_special_cells) into rectangles:
+/// runs of adjacent cells in a row, merged with identical runs in the rows
+/// below
+pub fn static_cells_to_areas(cells: &[(i32, i32)]) -> Vec<Area> {
+    let mut cells = cells.to_vec();
+    cells.sort_unstable();
+    cells.dedup();
+
+    let mut runs: Vec<Area> = Vec::new();
+    for (row, col) in cells {
+        match runs.last_mut() {
+            Some(run) if run.0 == row && run.3 + 1 == col => run.3 = col,
+            _ => runs.push((row, col, row, col)),
+        }
+    }
+
+    let mut areas: Vec<Area> = Vec::new();
+    for run in runs {
+        // Extend an area that ends on the row above with the same columns
+        match areas.iter_mut().find(|a| a.2 + 1 == run.0 && a.1 == run.1 && a.3 == run.3) {
+            Some(area) => area.2 = run.2,
+            None => areas.push(run),
+        }
+    }
+    areas
+}
+
 // ============================================================================
 // CURRENT REGION FUNCTION
 // ============================================================================
 
+/// Get the used range: bounds of every cell holding a value or formula
+pub fn static_get_used_area(sheet_name: &str) -> Option<Area> {
+    filled_cells(sheet_name).into_iter().fold(None, |used, (row, col)| {
+        Some(match used {
+            None => (row, col, row, col),
+            Some((r1, c1, r2, c2)) => (r1.min(row), c1.min(col), r2.max(row), c2.max(col)),
+        })
+    })
+}
+
 /// Get current region (contiguous non-empty cells)
 /// 
+/// The region grows from the cell while any filled cell touches its edge,
+/// diagonals included, the same as Ctrl+* in Excel.
+/// 
 /// # Returns
 /// - (start_row, start_col, end_row, end_col) bounds of current region
 pub fn static_get_current_region(sheet_name: &str, row: i32, col: i32) -> (i32, i32, i32, i32) {
-    // Simplified: just return the cell itself
-    // Real implementation would expand to find contiguous data
-    let _ = (sheet_name, row, col);
-    (row, col, row, col)
+    let filled = filled_cells(sheet_name);
+    let mut region = (row, col, row, col);
+    loop {
+        let touching = filled.iter().find(|&&(r, c)| {
+            !area_contains(region, r, c) && area_contains((region.0 - 1, region.1 - 1, region.2 + 1, region.3 + 1), r, c)
+        });
+        match touching {
+            Some(&(r, c)) => region = (region.0.min(r), region.1.min(c), region.2.max(r), region.3.max(c)),
+            None => return region,
+        }
+    }
+}
+
+/// Range.End: where Ctrl+Arrow lands from a cell
+/// 
+/// From a filled cell with a filled neighbour, the last filled cell of that
+/// block; otherwise the next filled cell, or the sheet edge if there is none.
+/// 
+/// # Parameters
+/// - `direction`: i32 - xlUp(-4162), xlDown(-4121), xlToLeft(-4159), xlToRight(-4161)
+pub fn static_get_end(sheet_name: &str, row: i32, col: i32, direction: i32) -> (i32, i32) {
+    let (vertical, step) = match i64::from(direction) {
+        constants::xlUp => (true, -1),
+        constants::xlDown => (true, 1),
+        constants::xlToLeft => (false, -1),
+        _ => (false, 1), // xlToRight
+    };
+    let (start, last) = if vertical { (row, MAX_ROW) } else { (col, MAX_COL) };
+    let line: HashSet<i32> = filled_cells(sheet_name).into_iter()
+        .filter(|&(r, c)| if vertical { c == col } else { r == row })
+        .map(|(r, c)| if vertical { r } else { c })
+        .collect();
+    let inside = |i: i32| (0..=last).contains(&i);
+    let next = start + step;
+
+    let end = if !inside(next) {
+        start
+    } else if line.contains(&start) && line.contains(&next) {
+        let mut end = next;
+        while inside(end + step) && line.contains(&(end + step)) {
+            end += step;
+        }
+        end
+    } else {
+        let ahead = line.iter().copied().filter(|&i| (i - start) * step > 0);
+        let found = if step > 0 { ahead.min() } else { ahead.max() };
+        found.unwrap_or(if step > 0 { last } else { 0 })
+    };
+    if vertical { (end, col) } else { (row, end) }
+}
+
+/// Positions of the cells on a sheet holding a value or formula
+fn filled_cells(sheet_name: &str) -> Vec<(i32, i32)> {
+    let prefix = format!("{}!", sheet_name);
+    stores().cells.lock().unwrap().iter()
+        .filter(|(key, data)| key.starts_with(&prefix) && (!data.value.is_empty() || data.formula.is_some()))
+        .filter_map(|(key, _)| parse_row_col(&key[prefix.len()..]))
+        .collect()
 }
 
 // ============================================================================
@@ -1112,60 +1810,612 @@ pub fn static_set_style(_sheet_name: &str, _row: i32, _col: i32, _style_name: &s
 // NAMED RANGE FUNCTIONS
 // ============================================================================
 
+/// A workbook-level name (Names.Add / Range.Name)
+#[derive(Clone, Debug, PartialEq)]
+pub struct NamedRange {
+    pub name: String,
+    pub sheet_name: String,
+    pub start_row: i32,
+    pub start_col: i32,
+    pub end_row: i32,
+    pub end_col: i32,
+}
+
 /// Get name for range
-pub fn static_get_range_name(_sheet_name: &str, _start_row: i32, _start_col: i32, _end_row: i32, _end_col: i32) -> Option<String> {
-    None
+pub fn static_get_range_name(sheet_name: &str, start_row: i32, start_col: i32, end_row: i32, end_col: i32) -> Option<String> {
+    let workbook = stores();
+    let storage = workbook.names.lock().unwrap();
+    storage.values()
+        .find(|n| n.sheet_name.eq_ignore_ascii_case(sheet_name)
+            && (n.start_row, n.start_col, n.end_row, n.end_col) == (start_row, start_col, end_row, end_col))
+        .map(|n| n.name.clone())
 }
 
 /// Create named range
 pub fn static_create_named_range(
-    _name: &str, _sheet_name: &str, 
-    _start_row: i32, _start_col: i32, _end_row: i32, _end_col: i32
+    name: &str, sheet_name: &str, 
+    start_row: i32, start_col: i32, end_row: i32, end_col: i32
 ) -> bool {
+    let named = NamedRange {
+        name: name.to_string(),
+        sheet_name: sheet_name.to_string(),
+        start_row, start_col, end_row, end_col,
+    };
+    stores().names.lock().unwrap().insert(name.to_lowercase(), named);
     true
 }
 
+/// Look up a named range (case-insensitive); its bounds follow inserted
+/// and deleted rows/columns
+pub fn static_get_named_range(name: &str) -> Option<NamedRange> {
+    stores().names.lock().unwrap().get(&name.to_lowercase()).cloned()
+}
+
 // ============================================================================
 // HYPERLINK FUNCTIONS
 // ============================================================================
 
-/// Get hyperlink from cell
-pub fn static_get_hyperlink(_sheet_name: &str, _row: i32, _col: i32) -> Option<String> {
-    None
+/// Hyperlink info structure
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HyperlinkInfo {
+    pub address: String,         // External target ("https://...", "mailto:...", file path)
+    pub sub_address: String,     // Location inside the document ("Sheet2!A1")
+    pub screen_tip: String,
+    pub text_to_display: String,
 }
 
-/// Add hyperlink to cell
-pub fn static_add_hyperlink(_sheet_name: &str, _row: i32, _col: i32, _address: &str, _text: &str) -> bool {
+/// Get hyperlink from cell
+pub fn static_get_hyperlink(sheet_name: &str, row: i32, col: i32) -> Option<HyperlinkInfo> {
+    let key = format!("{}!{}:{}", sheet_name, row, col);
+    stores().hyperlinks.lock().unwrap().get(&key).cloned()
+}
+
+/// Add hyperlink to cell, replacing any existing one
+///
+/// Like Excel, the cell shows TextToDisplay when given, or the address
+/// when the cell is empty.
+pub fn static_add_hyperlink(sheet_name: &str, row: i32, col: i32, link: HyperlinkInfo) -> bool {
+    if !link.text_to_display.is_empty() {
+        static_set_cell_value(sheet_name, row, col, &link.text_to_display);
+    } else if static_get_cell_value(sheet_name, row, col).is_empty() {
+        let shown = if link.address.is_empty() { &link.sub_address } else { &link.address };
+        static_set_cell_value(sheet_name, row, col, shown);
+    }
+    let key = format!("{}!{}:{}", sheet_name, row, col);
+    stores().hyperlinks.lock().unwrap().insert(key, link);
     true
 }
 
+/// Modify an existing hyperlink in place
+///
+/// # Returns
+/// - `bool`: false if the cell has no hyperlink
+pub fn static_update_hyperlink(sheet_name: &str, row: i32, col: i32, update: impl FnOnce(&mut HyperlinkInfo)) -> bool {
+    let key = format!("{}!{}:{}", sheet_name, row, col);
+    match stores().hyperlinks.lock().unwrap().get_mut(&key) {
+        Some(link) => {
+            update(link);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Delete hyperlink from cell (the cell value is kept, as in Excel)
+pub fn static_delete_hyperlink(sheet_name: &str, row: i32, col: i32) -> bool {
+    let key = format!("{}!{}:{}", sheet_name, row, col);
+    stores().hyperlinks.lock().unwrap().remove(&key).is_some()
+}
+
+/// List hyperlinks as (sheet, row, col, link), ordered by sheet then
+/// row-major position
+///
+/// # Parameters
+/// - `sheet_name`: Option<&str> - Only this sheet, or every sheet when None
+pub fn static_list_hyperlinks(sheet_name: Option<&str>) -> Vec<(String, i32, i32, HyperlinkInfo)> {
+    let workbook = stores();
+    let storage = workbook.hyperlinks.lock().unwrap();
+    let mut links: Vec<(String, i32, i32, HyperlinkInfo)> = storage
+        .iter()
+        .filter_map(|(key, link)| {
+            let (sheet, cell) = key.rsplit_once('!')?;
+            if sheet_name.is_some_and(|s| !s.eq_ignore_ascii_case(sheet)) {
+                return None;
+            }
+            let (row, col) = parse_row_col(cell)?;
+            Some((sheet.to_string(), row, col, link.clone()))
+        })
+        .collect();
+    links.sort_by(|a, b| (&a.0, a.1, a.2).cmp(&(&b.0, b.1, b.2)));
+    links
+}
+
+// ============================================================================
+// SHAPE FUNCTIONS
+// ============================================================================
+
+/// Shape info structure (position and size in points)
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShapeInfo {
+    pub sheet_name: String,
+    pub id: i32,                // Unique per sheet
+    pub name: String,
+    pub shape_type: i32,        // MsoShapeType: msoAutoShape=1, msoTextBox=17, ...
+    pub auto_shape_type: i32,   // MsoAutoShapeType: msoShapeRectangle=1, msoShapeOval=9, ...
+    pub left: f64,
+    pub top: f64,
+    pub width: f64,
+    pub height: f64,
+    pub text: String,
+    pub visible: bool,
+    pub on_action: String,      // Macro run when the shape is clicked
+}
+
+/// Add a shape to a sheet, naming it like Excel does ("Rectangle 3")
+///
+/// # Returns
+/// - `ShapeInfo`: the stored shape, with its id and default name
+pub fn static_add_shape(
+    sheet_name: &str, shape_type: i32, auto_shape_type: i32,
+    left: f64, top: f64, width: f64, height: f64,
+) -> ShapeInfo {
+    let workbook = stores();
+    let mut shapes = workbook.shapes.lock().unwrap();
+    let id = shapes.iter()
+        .filter(|s| s.sheet_name.eq_ignore_ascii_case(sheet_name))
+        .map(|s| s.id)
+        .max()
+        .unwrap_or(0) + 1;
+    let kind = match (shape_type, auto_shape_type) {
+        (17, _) => "TextBox",
+        (_, 1) => "Rectangle",
+        (_, 5) => "Rounded Rectangle",
+        (_, 9) => "Oval",
+        (_, 33) => "Right Arrow",
+        _ => "AutoShape",
+    };
+    let shape = ShapeInfo {
+        sheet_name: sheet_name.to_string(),
+        id,
+        name: format!("{} {}", kind, id),
+        shape_type,
+        auto_shape_type,
+        left,
+        top,
+        width,
+        height,
+        text: String::new(),
+        visible: true,
+        on_action: String::new(),
+    };
+    shapes.push(shape.clone());
+    shape
+}
+
+/// Get shape by id
+pub fn static_get_shape(sheet_name: &str, id: i32) -> Option<ShapeInfo> {
+    stores().shapes.lock().unwrap().iter()
+        .find(|s| s.id == id && s.sheet_name.eq_ignore_ascii_case(sheet_name))
+        .cloned()
+}
+
+/// Find shape by name (case-insensitive)
+pub fn static_find_shape(sheet_name: &str, name: &str) -> Option<ShapeInfo> {
+    stores().shapes.lock().unwrap().iter()
+        .find(|s| s.name.eq_ignore_ascii_case(name) && s.sheet_name.eq_ignore_ascii_case(sheet_name))
+        .cloned()
+}
+
+/// List shapes on a sheet in z-order
+pub fn static_list_shapes(sheet_name: &str) -> Vec<ShapeInfo> {
+    stores().shapes.lock().unwrap().iter()
+        .filter(|s| s.sheet_name.eq_ignore_ascii_case(sheet_name))
+        .cloned()
+        .collect()
+}
+
+/// Modify a shape in place
+///
+/// # Returns
+/// - `bool`: false if the shape was deleted
+pub fn static_update_shape(sheet_name: &str, id: i32, update: impl FnOnce(&mut ShapeInfo)) -> bool {
+    let workbook = stores();
+    let mut shapes = workbook.shapes.lock().unwrap();
+    match shapes.iter_mut().find(|s| s.id == id && s.sheet_name.eq_ignore_ascii_case(sheet_name)) {
+        Some(shape) => {
+            update(shape);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Delete shape by id
+pub fn static_delete_shape(sheet_name: &str, id: i32) -> bool {
+    let workbook = stores();
+    let mut shapes = workbook.shapes.lock().unwrap();
+    let before = shapes.len();
+    shapes.retain(|s| !(s.id == id && s.sheet_name.eq_ignore_ascii_case(sheet_name)));
+    shapes.len() != before
+}
+
+// ============================================================================
+// PIVOT TABLE FUNCTIONS
+// ============================================================================
+// Pivot tables are not computed; the engine keeps a declarative description
+// (source, destination, field layout) for downstream consumers.
+
+/// Pivot cache info structure
+#[derive(Clone, Debug, PartialEq)]
+pub struct PivotCacheInfo {
+    pub source_type: i32,       // xlDatabase=1, xlExternal=2, xlConsolidation=3
+    pub source_data: String,    // "Sheet1!A1:D100" / "Sheet1!R1C1:R100C4"
+}
+
+/// Pivot field layout (PivotTable.PivotFields(name))
+#[derive(Clone, Debug, PartialEq)]
+pub struct PivotFieldInfo {
+    pub name: String,
+    pub orientation: i32,       // xlHidden=0, xlRowField=1, xlColumnField=2, xlPageField=3
+    pub position: i32,          // 1-based within its orientation, 0 when hidden
+    pub number_format: String,
+}
+
+/// Data (values) field: an aggregate of a source field
+#[derive(Clone, Debug, PartialEq)]
+pub struct PivotDataFieldInfo {
+    pub source_field: String,
+    pub caption: String,
+    pub function: i32,          // xlSum=-4157, xlCount=-4112, xlAverage=-4106, ...
+}
+
+/// Pivot table info structure
+#[derive(Clone, Debug, PartialEq)]
+pub struct PivotTableInfo {
+    pub name: String,
+    pub sheet_name: String,
+    pub destination: String,    // TableDestination as written ("R3C1", "A3")
+    pub cache_index: usize,
+    pub fields: Vec<PivotFieldInfo>,
+    pub data_fields: Vec<PivotDataFieldInfo>,
+}
+
+impl PivotTableInfo {
+    /// Get a field, adding it (hidden) on first use
+    pub fn field_mut(&mut self, name: &str) -> &mut PivotFieldInfo {
+        let index = match self.fields.iter().position(|f| f.name.eq_ignore_ascii_case(name)) {
+            Some(index) => index,
+            None => {
+                self.fields.push(PivotFieldInfo { name: name.to_string(), orientation: 0, position: 0, number_format: String::new() });
+                self.fields.len() - 1
+            }
+        };
+        &mut self.fields[index]
+    }
+
+    /// Move a field to an orientation; xlDataField (4) adds a "Sum of" data field
+    pub fn set_orientation(&mut self, name: &str, orientation: i32) {
+        if orientation == 4 {
+            self.add_data_field(name, None, -4157);
+            return;
+        }
+        let old = self.field_mut(name).orientation;
+        let position = if orientation == 0 { 0 } else { self.fields.iter().filter(|f| f.orientation == orientation).count() as i32 + 1 };
+        if old != orientation {
+            let field = self.field_mut(name);
+            let old_position = field.position;
+            field.orientation = orientation;
+            field.position = position;
+            // Close the gap left in the old orientation
+            for f in self.fields.iter_mut().filter(|f| old != 0 && f.orientation == old && f.position > old_position) {
+                f.position -= 1;
+            }
+        }
+    }
+
+    /// Move a row/column/page field to a 1-based position within its orientation
+    pub fn set_position(&mut self, name: &str, position: i32) {
+        let field = self.field_mut(name);
+        let (orientation, old_position) = (field.orientation, field.position);
+        if orientation == 0 {
+            return;
+        }
+        let count = self.fields.iter().filter(|f| f.orientation == orientation).count() as i32;
+        let position = position.clamp(1, count);
+        for f in self.fields.iter_mut().filter(|f| f.orientation == orientation) {
+            if f.name.eq_ignore_ascii_case(name) {
+                f.position = position;
+            } else if position < old_position && (position..old_position).contains(&f.position) {
+                f.position += 1;
+            } else if position > old_position && (old_position + 1..=position).contains(&f.position) {
+                f.position -= 1;
+            }
+        }
+    }
+
+    /// Add a data field, captioned like Excel ("Sum of Sales") when no caption is given
+    pub fn add_data_field(&mut self, source_field: &str, caption: Option<&str>, function: i32) -> &PivotDataFieldInfo {
+        self.field_mut(source_field);
+        let caption = caption.map(str::to_string).unwrap_or_else(|| {
+            let summary = match function {
+                -4112 => "Count",
+                -4106 => "Average",
+                -4136 => "Max",
+                -4139 => "Min",
+                -4149 => "Product",
+                _ => "Sum",
+            };
+            format!("{} of {}", summary, source_field)
+        });
+        self.data_fields.push(PivotDataFieldInfo { source_field: source_field.to_string(), caption, function });
+        self.data_fields.last().unwrap()
+    }
+}
+
+/// Add a pivot cache
+///
+/// # Returns
+/// - `usize`: 1-based index of the new cache
+pub fn static_create_pivot_cache(source_type: i32, source_data: &str) -> usize {
+    let workbook = stores();
+    let mut caches = workbook.pivot_caches.lock().unwrap();
+    caches.push(PivotCacheInfo { source_type, source_data: source_data.to_string() });
+    caches.len()
+}
+
+/// Get pivot cache by 1-based index
+pub fn static_get_pivot_cache(index: usize) -> Option<PivotCacheInfo> {
+    stores().pivot_caches.lock().unwrap().get(index.checked_sub(1)?).cloned()
+}
+
+/// Number of pivot caches in the workbook
+pub fn static_pivot_cache_count() -> usize {
+    stores().pivot_caches.lock().unwrap().len()
+}
+
+/// Create a pivot table from a cache
+///
+/// # Parameters
+/// - `name`: Option<&str> - TableName, or "PivotTable<n>" when None
+///
+/// # Returns
+/// - `Option<PivotTableInfo>`: None if the cache does not exist or the name is taken
+pub fn static_create_pivot_table(cache_index: usize, sheet_name: &str, destination: &str, name: Option<&str>) -> Option<PivotTableInfo> {
+    static_get_pivot_cache(cache_index)?;
+    let workbook = stores();
+    let mut tables = workbook.pivot_tables.lock().unwrap();
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => (1..).map(|n| format!("PivotTable{}", n)).find(|n| !tables.iter().any(|t| t.name.eq_ignore_ascii_case(n)))?,
+    };
+    if tables.iter().any(|t| t.name.eq_ignore_ascii_case(&name)) {
+        return None;
+    }
+    let table = PivotTableInfo {
+        name,
+        sheet_name: sheet_name.to_string(),
+        destination: destination.to_string(),
+        cache_index,
+        fields: Vec::new(),
+        data_fields: Vec::new(),
+    };
+    tables.push(table.clone());
+    Some(table)
+}
+
+/// Get pivot table by name (case-insensitive)
+pub fn static_get_pivot_table(name: &str) -> Option<PivotTableInfo> {
+    stores().pivot_tables.lock().unwrap().iter().find(|t| t.name.eq_ignore_ascii_case(name)).cloned()
+}
+
+/// List pivot tables in creation order
+///
+/// # Parameters
+/// - `sheet_name`: Option<&str> - Only tables placed on this sheet, or all when None
+pub fn static_list_pivot_tables(sheet_name: Option<&str>) -> Vec<PivotTableInfo> {
+    stores().pivot_tables.lock().unwrap().iter()
+        .filter(|t| sheet_name.is_none_or(|s| t.sheet_name.eq_ignore_ascii_case(s)))
+        .cloned()
+        .collect()
+}
+
+/// Modify a pivot table in place
+///
+/// # Returns
+/// - `bool`: false if no table has this name
+pub fn static_update_pivot_table(name: &str, update: impl FnOnce(&mut PivotTableInfo)) -> bool {
+    let workbook = stores();
+    let mut tables = workbook.pivot_tables.lock().unwrap();
+    match tables.iter_mut().find(|t| t.name.eq_ignore_ascii_case(name)) {
+        Some(table) => {
+            update(table);
+            true
+        }
+        None => false,
+    }
+}
+
+// ============================================================================
+// PROTECTION FUNCTIONS
+// ============================================================================
+
+const PROTECTED_CELL_ERROR: &str = "The cell or chart you're trying to change is on a protected sheet.";
+const WRONG_PASSWORD_ERROR: &str = "The password you supplied is not correct. Verify that the CAPS LOCK key is off and be sure to use the correct capitalization.";
+
+/// Worksheet.Protect settings
+#[derive(Clone, Debug, PartialEq)]
+pub struct SheetProtection {
+    pub password: String,
+    pub contents: bool,                 // Locked cells reject writes
+    pub user_interface_only: bool,      // Macros may still change everything
+    pub allow_inserting_columns: bool,
+    pub allow_inserting_rows: bool,
+    pub allow_deleting_columns: bool,
+    pub allow_deleting_rows: bool,
+}
+
+impl Default for SheetProtection {
+    fn default() -> Self {
+        Self {
+            password: String::new(),
+            contents: true,
+            user_interface_only: false,
+            allow_inserting_columns: false,
+            allow_inserting_rows: false,
+            allow_deleting_columns: false,
+            allow_deleting_rows: false,
+        }
+    }
+}
+
+/// Get sheet protection; None if the sheet is not protected
+pub fn static_get_protection(sheet_name: &str) -> Option<SheetProtection> {
+    stores().protection.lock().unwrap().get(&sheet_name.to_lowercase()).cloned()
+}
+
+/// Protect a sheet. Protecting an already protected sheet (to change its
+/// options) needs the same password.
+pub fn static_protect_sheet(sheet_name: &str, protection: SheetProtection) -> Result<(), String> {
+    let workbook = stores();
+    let mut storage = workbook.protection.lock().unwrap();
+    let key = sheet_name.to_lowercase();
+    if storage.get(&key).is_some_and(|p| p.password != protection.password) {
+        return Err(WRONG_PASSWORD_ERROR.to_string());
+    }
+    storage.insert(key, protection);
+    Ok(())
+}
+
+/// Unprotect a sheet (unprotecting an unprotected sheet does nothing)
+pub fn static_unprotect_sheet(sheet_name: &str, password: &str) -> Result<(), String> {
+    let workbook = stores();
+    let mut storage = workbook.protection.lock().unwrap();
+    let key = sheet_name.to_lowercase();
+    if storage.get(&key).is_some_and(|p| p.password != password) {
+        return Err(WRONG_PASSWORD_ERROR.to_string());
+    }
+    storage.remove(&key);
+    Ok(())
+}
+
+/// Check that a macro may write to an area: a protected sheet rejects writes
+/// to locked cells unless it was protected with UserInterfaceOnly
+pub fn static_check_writable(sheet_name: &str, area: Area) -> Result<(), String> {
+    match static_get_protection(sheet_name) {
+        Some(p) if p.contents && !p.user_interface_only && static_any_locked(sheet_name, area) => {
+            Err(PROTECTED_CELL_ERROR.to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Check that a macro may insert or delete cells in an area. Whole rows and
+/// columns follow the AllowInserting/AllowDeleting options; shifting cells is
+/// never allowed on a protected sheet.
+pub fn static_check_structure_change(sheet_name: &str, area: Area, insert: bool) -> Result<(), String> {
+    let Some(p) = static_get_protection(sheet_name).filter(|p| !p.user_interface_only) else {
+        return Ok(());
+    };
+    let allowed = match (area.1 == 0 && area.3 == MAX_COL, area.0 == 0 && area.2 == MAX_ROW) {
+        (true, _) => if insert { p.allow_inserting_rows } else { p.allow_deleting_rows },
+        (_, true) => if insert { p.allow_inserting_columns } else { p.allow_deleting_columns },
+        _ => false,
+    };
+    if !allowed {
+        return Err("You can't use this command on a protected sheet.".to_string());
+    }
+    // Even when allowed, rows/columns holding locked cells can't be deleted
+    if !insert && p.contents && static_any_locked(sheet_name, area) {
+        return Err(PROTECTED_CELL_ERROR.to_string());
+    }
+    Ok(())
+}
+
 // ============================================================================
 // VALIDATION FUNCTIONS
 // ============================================================================
 
 /// Get data validation for cell
-pub fn static_get_validation(_sheet_name: &str, _row: i32, _col: i32) -> Option<ValidationInfo> {
-    None
+pub fn static_get_validation(sheet_name: &str, row: i32, col: i32) -> Option<ValidationInfo> {
+    let key = format!("{}!{}:{}", sheet_name, row, col);
+    stores().validations.lock().unwrap().get(&key).cloned()
 }
 
 /// Validation info structure
+#[derive(Clone, Debug)]
 pub struct ValidationInfo {
-    pub validation_type: i32,
+    pub validation_type: i32,   // xlValidateInputOnly=0, xlValidateWholeNumber=1, xlValidateDecimal=2, xlValidateList=3, ...
     pub formula1: String,
     pub formula2: Option<String>,
-    pub operator: i32,
-    pub alert_style: i32,
+    pub operator: i32,          // xlBetween=1, xlNotBetween=2, xlEqual=3, ...
+    pub alert_style: i32,       // xlValidAlertStop=1, xlValidAlertWarning=2, xlValidAlertInformation=3
     pub input_title: String,
     pub input_message: String,
     pub error_title: String,
     pub error_message: String,
+    pub ignore_blank: bool,
+    pub in_cell_dropdown: bool,
+    pub show_input: bool,
+    pub show_error: bool,
+}
+
+impl Default for ValidationInfo {
+    fn default() -> Self {
+        Self {
+            validation_type: 0,
+            formula1: String::new(),
+            formula2: None,
+            operator: 1,
+            alert_style: 1,
+            input_title: String::new(),
+            input_message: String::new(),
+            error_title: String::new(),
+            error_message: String::new(),
+            ignore_blank: true,
+            in_cell_dropdown: true,
+            show_input: true,
+            show_error: true,
+        }
+    }
 }
 
 /// Set data validation
 pub fn static_set_validation(
-    _sheet_name: &str, _row: i32, _col: i32,
-    _validation_type: i32, _formula1: &str, _formula2: Option<&str>, _operator: i32
+    sheet_name: &str, row: i32, col: i32,
+    validation_type: i32, formula1: &str, formula2: Option<&str>, operator: i32
 ) -> bool {
+    let key = format!("{}!{}:{}", sheet_name, row, col);
+    let workbook = stores();
+    let mut storage = workbook.validations.lock().unwrap();
+    let entry = storage.entry(key).or_default();
+    entry.validation_type = validation_type;
+    entry.formula1 = formula1.to_string();
+    entry.formula2 = formula2.map(str::to_string);
+    entry.operator = operator;
+    true
+}
+
+/// Update the messages and flags of an existing validation rule
+/// 
+/// # Returns
+/// - bool - False if the cell has no validation
+pub fn static_update_validation(sheet_name: &str, row: i32, col: i32, update: impl FnOnce(&mut ValidationInfo)) -> bool {
+    let key = format!("{}!{}:{}", sheet_name, row, col);
+    let workbook = stores();
+    let mut storage = workbook.validations.lock().unwrap();
+    match storage.get_mut(&key) {
+        Some(info) => {
+            update(info);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Remove data validation (Validation.Delete)
+pub fn static_delete_validation(sheet_name: &str, row: i32, col: i32) -> bool {
+    let key = format!("{}!{}:{}", sheet_name, row, col);
+    stores().validations.lock().unwrap().remove(&key);
     true
 }
 
@@ -1224,4 +2474,503 @@ mod tests {
         assert_eq!(static_get_cell_value("Sheet1", 1, 0), "Test");
         assert_eq!(static_get_cell_value("Sheet1", 2, 0), "Test");
     }
+
+    #[test]
+    fn test_insert_delete_rows_and_columns() {
+        let s = "ShiftTest";
+        static_set_cell_value(s, 0, 0, "a1");
+        static_set_cell_value(s, 1, 0, "a2");
+        static_set_cell_value(s, 1, 2, "c2");
+        static_set_number_format(s, 1, 0, "0.00");
+        static_set_row_hidden(s, 3, true);
+        static_merge_cells(s, 1, 1, 2, 2, false);
+        static_create_named_range("ShiftData", s, 0, 0, 2, 0);
+
+        // Rows(2).Insert
+        static_insert_rows(s, 1, 1);
+        assert_eq!(static_get_cell_value(s, 1, 0), "");
+        assert_eq!(static_get_cell_value(s, 2, 0), "a2");
+        assert_eq!(static_get_number_format(s, 2, 0), "0.00");
+        assert!(static_is_row_hidden(s, 4) && !static_is_row_hidden(s, 3));
+        assert_eq!(static_get_merge_anchor(s, 3, 2), Some((2, 1)));
+        let name = static_get_named_range("shiftdata").unwrap();
+        assert_eq!((name.start_row, name.end_row), (0, 3));
+
+        // Columns("B").Delete: C2 moves left, the merged area loses its anchor
+        static_delete_columns(s, 1, 1);
+        assert_eq!(static_get_cell_value(s, 2, 1), "c2");
+        assert!(!static_is_merged(s, 2, 1));
+
+        // Rows("1:2").Delete shrinks the name; deleting all of it removes it
+        static_delete_rows(s, 0, 2);
+        assert_eq!(static_get_cell_value(s, 0, 0), "a2");
+        let name = static_get_named_range("ShiftData").unwrap();
+        assert_eq!((name.start_row, name.end_row), (0, 1));
+        static_delete_rows(s, 0, 2);
+        assert!(static_get_named_range("ShiftData").is_none());
+    }
+
+    #[test]
+    fn test_comments_and_validation() {
+        let s = "CommentTest";
+        assert!(static_add_comment(s, 0, 0, "Review"));
+        assert_eq!(static_get_comment(s, 0, 0).as_deref(), Some("Review"));
+        static_clear_comments(s, 0, 0, 5, 5);
+        assert!(static_get_comment(s, 0, 0).is_none());
+
+        // Validation.Add xlValidateList, Formula1:="Yes,No", then Rows(1).Insert moves it
+        static_set_validation(s, 1, 0, 3, "Yes,No", None, 1);
+        assert!(static_update_validation(s, 1, 0, |v| v.input_message = "Pick one".into()));
+        static_insert_rows(s, 0, 1);
+        assert!(static_get_validation(s, 1, 0).is_none());
+        let info = static_get_validation(s, 2, 0).unwrap();
+        assert_eq!((info.validation_type, info.formula1.as_str(), info.input_message.as_str()), (3, "Yes,No", "Pick one"));
+        assert!(static_delete_validation(s, 2, 0));
+        assert!(!static_update_validation(s, 2, 0, |v| v.show_error = false));
+    }
+
+    fn link_to(address: &str) -> HyperlinkInfo {
+        HyperlinkInfo { address: address.into(), ..Default::default() }
+    }
+
+    #[test]
+    fn test_add_hyperlink_shows_its_target_in_an_empty_cell() {
+        let s = "LinkShownTest";
+        assert!(static_add_hyperlink(s, 0, 0, link_to("https://example.com")));
+        assert_eq!(static_get_cell_value(s, 0, 0), "https://example.com");
+        static_add_hyperlink(s, 1, 0, HyperlinkInfo { sub_address: "Data!A1".into(), ..Default::default() });
+        assert_eq!(static_get_cell_value(s, 1, 0), "Data!A1");
+        // A filled cell keeps its value
+        static_set_cell_value(s, 2, 0, "Report");
+        static_add_hyperlink(s, 2, 0, link_to("https://example.com"));
+        assert_eq!(static_get_cell_value(s, 2, 0), "Report");
+    }
+
+    #[test]
+    fn test_add_hyperlink_shows_text_to_display() {
+        let s = "LinkTextTest";
+        static_set_cell_value(s, 0, 0, "Report");
+        static_add_hyperlink(s, 0, 0, HyperlinkInfo { text_to_display: "Docs".into(), ..link_to("https://example.com") });
+        assert_eq!(static_get_cell_value(s, 0, 0), "Docs");
+    }
+
+    #[test]
+    fn test_add_hyperlink_replaces_the_cells_link() {
+        let s = "LinkReplaceTest";
+        static_add_hyperlink(s, 0, 0, link_to("https://old.example.com"));
+        static_add_hyperlink(s, 0, 0, link_to("https://new.example.com"));
+        assert_eq!(static_get_hyperlink(s, 0, 0), Some(link_to("https://new.example.com")));
+        assert_eq!(static_list_hyperlinks(Some(s)).len(), 1);
+    }
+
+    #[test]
+    fn test_update_hyperlink() {
+        let s = "LinkUpdateTest";
+        static_add_hyperlink(s, 0, 0, link_to("https://example.com"));
+        assert!(static_update_hyperlink(s, 0, 0, |l| l.screen_tip = "Open".into()));
+        assert_eq!(static_get_hyperlink(s, 0, 0).unwrap().screen_tip, "Open");
+        assert!(!static_update_hyperlink(s, 5, 5, |l| l.screen_tip = "None".into()));
+    }
+
+    #[test]
+    fn test_delete_hyperlink_keeps_the_cell_value() {
+        let s = "LinkDeleteTest";
+        static_add_hyperlink(s, 0, 0, link_to("https://example.com"));
+        assert!(static_delete_hyperlink(s, 0, 0));
+        assert!(static_get_hyperlink(s, 0, 0).is_none());
+        assert_eq!(static_get_cell_value(s, 0, 0), "https://example.com");
+        assert!(!static_delete_hyperlink(s, 0, 0));
+    }
+
+    #[test]
+    fn test_list_hyperlinks_by_sheet_then_position() {
+        let _workbook = StaticWorkbook::new().enter();
+        for (sheet, row, col) in [("B", 0, 0), ("A", 2, 0), ("A", 0, 3), ("a", 0, 1)] {
+            static_add_hyperlink(sheet, row, col, link_to("https://example.com"));
+        }
+        let cells = |sheet| static_list_hyperlinks(sheet).into_iter().map(|l| (l.0, l.1, l.2)).collect::<Vec<_>>();
+        assert_eq!(cells(Some("A")), vec![("A".to_string(), 0, 3), ("A".to_string(), 2, 0), ("a".to_string(), 0, 1)]);
+        assert_eq!(cells(None).len(), 4);
+    }
+
+    #[test]
+    fn test_hyperlinks_move_with_deleted_rows() {
+        let s = "LinkShiftTest";
+        static_add_hyperlink(s, 2, 0, link_to("https://example.com"));
+        static_delete_rows(s, 0, 1);
+        let links = static_list_hyperlinks(Some(s));
+        assert_eq!(links.iter().map(|l| (l.1, l.2)).collect::<Vec<_>>(), vec![(1, 0)]);
+        assert_eq!(links[0].3, link_to("https://example.com"));
+    }
+
+    #[test]
+    fn test_add_shape_names_it_like_excel() {
+        let s = "ShapeNameTest";
+        let rect = static_add_shape(s, 1, 1, 10.0, 20.0, 100.0, 50.0);
+        let oval = static_add_shape(s, 1, 9, 0.0, 0.0, 30.0, 30.0);
+        let text_box = static_add_shape(s, 17, 1, 0.0, 0.0, 1.0, 1.0);
+        assert_eq!((rect.name.as_str(), oval.name.as_str(), text_box.name.as_str()), ("Rectangle 1", "Oval 2", "TextBox 3"));
+        assert_eq!((rect.left, rect.top, rect.width, rect.height), (10.0, 20.0, 100.0, 50.0));
+        assert!(rect.visible && rect.text.is_empty());
+        // Ids are per sheet
+        assert_eq!(static_add_shape("ShapeNameOther", 1, 33, 0.0, 0.0, 1.0, 1.0).name, "Right Arrow 1");
+    }
+
+    #[test]
+    fn test_find_shape_by_name_or_id() {
+        let s = "ShapeFindTest";
+        let rect = static_add_shape(s, 1, 1, 0.0, 0.0, 10.0, 10.0);
+        assert_eq!(static_find_shape(s, "RECTANGLE 1"), Some(rect.clone()));
+        assert_eq!(static_get_shape(s, rect.id), Some(rect));
+        assert!(static_find_shape(s, "Oval 1").is_none());
+        assert!(static_get_shape("ShapeFindElsewhere", 1).is_none());
+    }
+
+    #[test]
+    fn test_update_shape() {
+        let s = "ShapeUpdateTest";
+        let rect = static_add_shape(s, 1, 1, 0.0, 0.0, 10.0, 10.0);
+        assert!(static_update_shape(s, rect.id, |sh| {
+            sh.name = "Button".into();
+            sh.on_action = "RunReport".into();
+        }));
+        let button = static_find_shape(s, "button").unwrap();
+        assert_eq!((button.id, button.on_action.as_str()), (rect.id, "RunReport"));
+    }
+
+    #[test]
+    fn test_update_deleted_shape() {
+        let s = "ShapeUpdateDeletedTest";
+        let rect = static_add_shape(s, 1, 1, 0.0, 0.0, 10.0, 10.0);
+        assert!(static_delete_shape(s, rect.id));
+        assert!(!static_update_shape(s, rect.id, |sh| sh.text = "gone".into()));
+    }
+
+    #[test]
+    fn test_delete_shape() {
+        let s = "ShapeDeleteTest";
+        let rect = static_add_shape(s, 1, 1, 0.0, 0.0, 10.0, 10.0);
+        static_add_shape(s, 1, 9, 0.0, 0.0, 10.0, 10.0);
+        assert!(static_delete_shape(s, rect.id));
+        assert!(static_get_shape(s, rect.id).is_none());
+        assert!(!static_delete_shape(s, rect.id));
+        // Numbering continues from the highest id left on the sheet
+        assert_eq!(static_add_shape(s, 17, 1, 0.0, 0.0, 1.0, 1.0).name, "TextBox 3");
+    }
+
+    #[test]
+    fn test_list_shapes_in_z_order() {
+        let s = "ShapeListTest";
+        for auto_shape_type in [9, 1, 5] {
+            static_add_shape(s, 1, auto_shape_type, 0.0, 0.0, 1.0, 1.0);
+        }
+        static_add_shape("ShapeListOther", 1, 1, 0.0, 0.0, 1.0, 1.0);
+        let names: Vec<String> = static_list_shapes(s).into_iter().map(|sh| sh.name).collect();
+        assert_eq!(names, vec!["Oval 1", "Rectangle 2", "Rounded Rectangle 3"]);
+    }
+
+    #[test]
+    fn test_pivot_caches_are_numbered_from_one() {
+        let _workbook = StaticWorkbook::new().enter();
+        assert_eq!(static_create_pivot_cache(1, "PivotSrc!A1:C20"), 1);
+        assert_eq!(static_create_pivot_cache(3, "Q1,Q2"), 2);
+        assert_eq!(static_pivot_cache_count(), 2);
+        let cache = static_get_pivot_cache(2).unwrap();
+        assert_eq!((cache.source_type, cache.source_data.as_str()), (3, "Q1,Q2"));
+        assert!(static_get_pivot_cache(0).is_none());
+        assert!(static_get_pivot_cache(3).is_none());
+    }
+
+    #[test]
+    fn test_create_pivot_table() {
+        let _workbook = StaticWorkbook::new().enter();
+        let cache = static_create_pivot_cache(1, "PivotSrc!A1:C20");
+        let table = static_create_pivot_table(cache, "PivotDst", "R3C1", Some("SalesPivot")).unwrap();
+        assert_eq!((table.name.as_str(), table.destination.as_str(), table.cache_index), ("SalesPivot", "R3C1", cache));
+        assert_eq!(static_get_pivot_table("salespivot"), Some(table));
+    }
+
+    #[test]
+    fn test_create_pivot_table_default_names_skip_taken_ones() {
+        let _workbook = StaticWorkbook::new().enter();
+        let cache = static_create_pivot_cache(1, "PivotSrc!A1:C20");
+        static_create_pivot_table(cache, "PivotDst", "A1", Some("PivotTable1")).unwrap();
+        assert_eq!(static_create_pivot_table(cache, "PivotDst", "H1", None).unwrap().name, "PivotTable2");
+    }
+
+    #[test]
+    fn test_create_pivot_table_rejects_a_taken_name() {
+        let _workbook = StaticWorkbook::new().enter();
+        let cache = static_create_pivot_cache(1, "PivotSrc!A1:C20");
+        static_create_pivot_table(cache, "PivotDst", "R3C1", Some("SalesPivot")).unwrap();
+        assert!(static_create_pivot_table(cache, "PivotDst", "R3C8", Some("salespivot")).is_none());
+        assert_eq!(static_list_pivot_tables(None).len(), 1);
+    }
+
+    #[test]
+    fn test_create_pivot_table_needs_an_existing_cache() {
+        let _workbook = StaticWorkbook::new().enter();
+        assert!(static_create_pivot_table(1, "PivotDst", "A1", Some("NoCache")).is_none());
+        assert!(static_get_pivot_table("NoCache").is_none());
+    }
+
+    #[test]
+    fn test_list_pivot_tables_by_sheet() {
+        let _workbook = StaticWorkbook::new().enter();
+        let cache = static_create_pivot_cache(1, "PivotSrc!A1:C20");
+        static_create_pivot_table(cache, "North", "A1", Some("First")).unwrap();
+        static_create_pivot_table(cache, "South", "A1", Some("Second")).unwrap();
+        static_create_pivot_table(cache, "north", "H1", Some("Third")).unwrap();
+        let names = |sheet| static_list_pivot_tables(sheet).into_iter().map(|t| t.name).collect::<Vec<_>>();
+        assert_eq!(names(Some("NORTH")), vec!["First", "Third"]);
+        assert_eq!(names(None), vec!["First", "Second", "Third"]);
+    }
+
+    #[test]
+    fn test_update_pivot_table_of_unknown_name() {
+        let _workbook = StaticWorkbook::new().enter();
+        assert!(!static_update_pivot_table("Missing", |t| t.set_orientation("Region", 1)));
+    }
+
+    fn empty_pivot_table() -> PivotTableInfo {
+        PivotTableInfo {
+            name: "PivotTable1".into(),
+            sheet_name: "PivotDst".into(),
+            destination: "A3".into(),
+            cache_index: 1,
+            fields: Vec::new(),
+            data_fields: Vec::new(),
+        }
+    }
+
+    fn layout(table: &PivotTableInfo) -> Vec<(&str, i32, i32)> {
+        table.fields.iter().map(|f| (f.name.as_str(), f.orientation, f.position)).collect()
+    }
+
+    #[test]
+    fn test_pivot_field_orientation_appends_and_closes_gaps() {
+        let mut table = empty_pivot_table();
+        table.set_orientation("Region", 1);
+        table.set_orientation("Product", 1);
+        table.set_orientation("Year", 2);
+        assert_eq!(layout(&table), vec![("Region", 1, 1), ("Product", 1, 2), ("Year", 2, 1)]);
+
+        // Moving Region to the columns moves Product up to first row field
+        table.set_orientation("Region", 2);
+        assert_eq!(layout(&table), vec![("Region", 2, 2), ("Product", 1, 1), ("Year", 2, 1)]);
+        table.set_orientation("Year", 0);
+        assert_eq!(layout(&table), vec![("Region", 2, 1), ("Product", 1, 1), ("Year", 0, 0)]);
+    }
+
+    #[test]
+    fn test_pivot_field_position_reorders_within_its_orientation() {
+        let mut table = empty_pivot_table();
+        for name in ["Region", "Product", "Year"] {
+            table.set_orientation(name, 1);
+        }
+        table.set_position("Year", 1);
+        assert_eq!(layout(&table), vec![("Region", 1, 2), ("Product", 1, 3), ("Year", 1, 1)]);
+        // Out-of-range positions are clamped
+        table.set_position("Year", 9);
+        assert_eq!(layout(&table), vec![("Region", 1, 1), ("Product", 1, 2), ("Year", 1, 3)]);
+    }
+
+    #[test]
+    fn test_pivot_field_position_of_hidden_field_is_ignored() {
+        let mut table = empty_pivot_table();
+        table.set_position("Hidden", 2);
+        assert_eq!(layout(&table), vec![("Hidden", 0, 0)]);
+    }
+
+    #[test]
+    fn test_pivot_data_fields_are_captioned_like_excel() {
+        let mut table = empty_pivot_table();
+        table.set_orientation("Sales", 4);
+        table.add_data_field("Units", None, -4112);
+        table.add_data_field("Price", Some("Mean price"), -4106);
+        let captions: Vec<&str> = table.data_fields.iter().map(|d| d.caption.as_str()).collect();
+        assert_eq!(captions, vec!["Sum of Sales", "Count of Units", "Mean price"]);
+        // Data fields keep their source fields hidden in the layout
+        assert_eq!(layout(&table), vec![("Sales", 0, 0), ("Units", 0, 0), ("Price", 0, 0)]);
+    }
+
+    /// A sheet whose cells are unlocked except the header A1:D1
+    fn header_locked(s: &str) {
+        static_set_locked_area(s, (0, 0, MAX_ROW, MAX_COL), false);
+        static_set_locked_area(s, (0, 0, 0, 3), true);
+    }
+
+    fn protect(s: &str, protection: SheetProtection) -> Result<(), String> {
+        static_protect_sheet(s, SheetProtection { password: "pw".into(), ..protection })
+    }
+
+    #[test]
+    fn test_cells_are_locked_until_unlocked() {
+        let s = "LockedTest";
+        assert!(static_get_locked(s, 5, 5));
+        header_locked(s);
+        assert!(static_get_locked(s, 0, 3) && !static_get_locked(s, 0, 4) && !static_get_locked(s, 1, 0));
+        assert!(static_any_locked(s, (0, 3, 2, 5)) && !static_any_locked(s, (1, 0, 9, 9)));
+    }
+
+    #[test]
+    fn test_unprotected_sheet_accepts_writes() {
+        let s = "UnprotectedTest";
+        header_locked(s);
+        assert!(static_check_writable(s, (0, 0, 0, 0)).is_ok());
+        assert!(static_check_structure_change(s, (2, 0, 2, 0), true).is_ok());
+    }
+
+    #[test]
+    fn test_protected_sheet_rejects_writes_to_locked_cells() {
+        let s = "ProtectedWriteTest";
+        header_locked(s);
+        protect(s, SheetProtection::default()).unwrap();
+        assert_eq!(static_get_protection(s).map(|p| p.password), Some("pw".to_string()));
+        assert!(static_check_writable(s, (5, 0, 9, 9)).is_ok());
+        assert!(static_check_writable(s, (0, 4, 2, 4)).is_ok());
+        assert_eq!(static_check_writable(s, (0, 3, 0, 3)), Err(PROTECTED_CELL_ERROR.to_string()));
+    }
+
+    #[test]
+    fn test_user_interface_only_lets_macros_write() {
+        let s = "UserInterfaceOnlyTest";
+        header_locked(s);
+        protect(s, SheetProtection { user_interface_only: true, ..Default::default() }).unwrap();
+        assert!(static_check_writable(s, (0, 0, 0, 0)).is_ok());
+        assert!(static_check_structure_change(s, (0, 0, 0, MAX_COL), false).is_ok());
+    }
+
+    #[test]
+    fn test_protect_again_needs_the_same_password() {
+        let s = "ReprotectTest";
+        protect(s, SheetProtection::default()).unwrap();
+        let options = SheetProtection { password: "other".into(), allow_inserting_rows: true, ..Default::default() };
+        assert_eq!(static_protect_sheet(s, options), Err(WRONG_PASSWORD_ERROR.to_string()));
+        protect(s, SheetProtection { allow_inserting_rows: true, ..Default::default() }).unwrap();
+        assert!(static_get_protection(s).unwrap().allow_inserting_rows);
+    }
+
+    #[test]
+    fn test_unprotect() {
+        let s = "UnprotectTest";
+        protect(s, SheetProtection::default()).unwrap();
+        assert_eq!(static_unprotect_sheet(s, "wrong"), Err(WRONG_PASSWORD_ERROR.to_string()));
+        assert!(static_get_protection(s).is_some());
+        static_unprotect_sheet(s, "pw").unwrap();
+        assert!(static_get_protection(s).is_none());
+        // Unprotecting an unprotected sheet does nothing
+        assert!(static_unprotect_sheet(s, "anything").is_ok());
+    }
+
+    #[test]
+    fn test_structure_changes_follow_the_allow_options() {
+        let s = "StructureTest";
+        header_locked(s);
+        protect(s, SheetProtection { allow_inserting_rows: true, allow_deleting_rows: true, ..Default::default() }).unwrap();
+        assert!(static_check_structure_change(s, (2, 0, 2, MAX_COL), true).is_ok());
+        assert!(static_check_structure_change(s, (2, 0, 2, MAX_COL), false).is_ok());
+        assert!(static_check_structure_change(s, (0, 2, MAX_ROW, 2), true).is_err());
+        // Shifting cells is never allowed
+        assert!(static_check_structure_change(s, (2, 0, 2, 0), true).is_err());
+    }
+
+    #[test]
+    fn test_deleting_rows_with_locked_cells() {
+        let s = "DeleteLockedTest";
+        header_locked(s);
+        protect(s, SheetProtection { allow_deleting_rows: true, ..Default::default() }).unwrap();
+        assert_eq!(static_check_structure_change(s, (0, 0, 0, MAX_COL), false), Err(PROTECTED_CELL_ERROR.to_string()));
+    }
+
+    #[test]
+    fn test_locked_cells_move_with_inserted_rows() {
+        let s = "LockedShiftTest";
+        header_locked(s);
+        // Rows(1).Insert moves the locked header down
+        static_insert_rows(s, 0, 1);
+        assert!(static_get_locked(s, 1, 0) && !static_get_locked(s, 1, 4) && !static_get_locked(s, 2, 0));
+    }
+
+    #[test]
+    fn test_special_cells_and_current_region() {
+        let s = "SpecialCellsTest";
+        for (row, value) in [(0, "Name"), (1, "1"), (3, "x"), (4, "TRUE")] {
+            static_set_cell_value(s, row, 0, value);
+        }
+        static_set_cell_value(s, 0, 1, "Qty");
+        static_set_cell_value(s, 6, 3, "far");
+
+        assert_eq!(static_get_used_area(s), Some((0, 0, 6, 3)));
+        assert_eq!(static_get_current_region(s, 1, 0), (0, 0, 1, 1));
+        assert_eq!(static_get_current_region(s, 4, 0), (3, 0, 4, 0));
+        assert_eq!(static_get_current_region(s, 6, 3), (6, 3, 6, 3));
+
+        let blanks = static_get_special_cells(s, 0, 0, 4, 0, 4, None);
+        assert_eq!(blanks, vec![(2, 0)]);
+        let numbers = static_get_special_cells(s, 0, 0, MAX_ROW, 0, 2, Some(1));
+        assert_eq!(numbers, vec![(1, 0)]);
+        let text = static_get_special_cells(s, 0, 0, MAX_ROW, 1, 2, Some(2));
+        assert_eq!(static_cells_to_areas(&text), vec![(0, 0, 0, 1), (3, 0, 3, 0)]);
+
+        static_set_row_hidden(s, 1, true);
+        let visible = static_get_special_cells(s, 0, 0, 2, 1, 12, None);
+        assert_eq!(static_cells_to_areas(&visible), vec![(0, 0, 0, 1), (2, 0, 2, 1)]);
+        assert_eq!(static_get_special_cells(s, 0, 0, 0, 0, 11, None), vec![(6, 3)]);
+    }
+
+    #[test]
+    fn test_end_navigation() {
+        let s = "EndTest";
+        for row in 0..5 {
+            static_set_cell_value(s, row, 0, "x");
+        }
+        static_set_cell_value(s, 9, 0, "x");
+        static_set_cell_value(s, 0, 3, "x");
+
+        // Cells(Rows.Count, 1).End(xlUp) finds the last filled row
+        assert_eq!(static_get_end(s, MAX_ROW, 0, -4162), (9, 0));
+        assert_eq!(static_get_end(s, 0, 0, -4121), (4, 0));
+        assert_eq!(static_get_end(s, 4, 0, -4121), (9, 0));
+        assert_eq!(static_get_end(s, 9, 0, -4121), (MAX_ROW, 0));
+        assert_eq!(static_get_end(s, 0, 0, -4161), (0, 3));
+        assert_eq!(static_get_end(s, 0, 3, -4161), (0, MAX_COL));
+        assert_eq!(static_get_end(s, 0, 3, -4159), (0, 0));
+        assert_eq!(static_get_end(s, MAX_ROW, 5, -4162), (0, 5));
+    }
+
+    #[test]
+    fn test_workbooks_are_isolated() {
+        let first = StaticWorkbook::new();
+        let second = StaticWorkbook::new();
+        {
+            let _first = first.enter();
+            static_set_cell_value("Isolated", 0, 0, "4");
+            static_set_cell_formula("Isolated", 0, 1, "=A1*2");
+            {
+                let _second = second.enter();
+                assert_eq!(static_get_cell_value("Isolated", 0, 0), "");
+                static_set_cell_value("Isolated", 0, 0, "5");
+            }
+            // The other workbook's write did not recalculate this one
+            assert_eq!(static_get_cell_value("Isolated", 0, 1), "8");
+        }
+        assert!(StaticWorkbook::current().ptr_eq(&StaticWorkbook::shared()));
+        assert_eq!(static_get_cell_value("Isolated", 0, 0), "");
+
+        // One run per thread, each with its own workbook
+        let runs: Vec<_> = (0..4)
+            .map(|run| {
+                std::thread::spawn(move || {
+                    let workbook = StaticWorkbook::new();
+                    let _scope = workbook.enter();
+                    for row in 0..50 {
+                        static_set_cell_value("Threads", row, 0, &run.to_string());
+                    }
+                    (0..50).all(|row| static_get_cell_value("Threads", row, 0) == run.to_string())
+                })
+            })
+            .collect();
+        assert!(runs.into_iter().all(|run| run.join().unwrap()));
+    }
 }
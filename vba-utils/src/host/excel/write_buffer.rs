@@ -0,0 +1,182 @@
+// src/host/excel/write_buffer.rs
+// ============================================================================
+// WRITE BUFFER - Coalesces consecutive cell writes into block writes
+//
+// With RuntimeConfig::write_batching on, engine::set_cell_value does not
+// call the backend per cell. Writes that continue the current block (the
+// next cell of its row, the first cell of the row below, or a cell already
+// in it) are kept here; anything else first sends the block to the backend
+// with one ExcelBackend::set_range_values call. `engine` also flushes
+// before reads, other writes, Calculate and at the end of a run, so the
+// macro never observes the delay.
+// ============================================================================
+
+/// Largest block kept before it is sent to the backend
+pub const MAX_BLOCK_CELLS: usize = 4096;
+
+/// Counters for the buffer since the backend was installed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WriteBatchStats {
+    /// Cell writes the macro made
+    pub cells_written: u64,
+    /// set_range_values calls sent to the backend
+    pub backend_writes: u64,
+    /// Times buffered writes were sent
+    pub flushes: u64,
+}
+
+impl WriteBatchStats {
+    /// Backend calls avoided compared to one call per cell
+    pub fn round_trips_saved(&self) -> u64 {
+        self.cells_written.saturating_sub(self.backend_writes)
+    }
+}
+
+/// A rectangle of buffered values, row-major; only the last row may be
+/// shorter than the first
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteBlock {
+    pub sheet: String,
+    /// 0-based top-left cell
+    pub row: i32,
+    pub col: i32,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl WriteBlock {
+    fn new(sheet: &str, row: i32, col: i32, value: &str) -> Self {
+        Self { sheet: sheet.to_string(), row, col, rows: vec![vec![value.to_string()]] }
+    }
+
+    fn width(&self) -> usize {
+        self.rows[0].len()
+    }
+
+    /// Number of buffered cells
+    pub fn cells(&self) -> usize {
+        self.width() * (self.rows.len() - 1) + self.rows.last().map_or(0, Vec::len)
+    }
+
+    fn position(&self, sheet: &str, row: i32, col: i32) -> Option<(usize, usize)> {
+        if !self.sheet.eq_ignore_ascii_case(sheet) || row < self.row || col < self.col {
+            return None;
+        }
+        let (r, c) = ((row - self.row) as usize, (col - self.col) as usize);
+        (c < self.rows.get(r)?.len()).then_some((r, c))
+    }
+
+    /// Buffered value of a cell, if the block holds it
+    pub fn get(&self, sheet: &str, row: i32, col: i32) -> Option<&str> {
+        let (r, c) = self.position(sheet, row, col)?;
+        Some(&self.rows[r][c])
+    }
+
+    /// Take a write that overwrites a buffered cell or continues the block;
+    /// false when the write does not fit
+    pub fn push(&mut self, sheet: &str, row: i32, col: i32, value: &str) -> bool {
+        if let Some((r, c)) = self.position(sheet, row, col) {
+            self.rows[r][c] = value.to_string();
+            return true;
+        }
+        if !self.sheet.eq_ignore_ascii_case(sheet) || self.cells() >= MAX_BLOCK_CELLS {
+            return false;
+        }
+        let last = self.rows.len() - 1;
+        let last_len = self.rows[last].len();
+        let next_in_row = row == self.row + last as i32 && col == self.col + last_len as i32;
+        if next_in_row && (last == 0 || last_len < self.width()) {
+            self.rows[last].push(value.to_string());
+            return true;
+        }
+        if row == self.row + self.rows.len() as i32 && col == self.col && last_len == self.width() {
+            self.rows.push(vec![value.to_string()]);
+            return true;
+        }
+        false
+    }
+
+    /// Rectangular writes for the backend: the full rows, then the shorter
+    /// last row if there is one
+    pub fn into_writes(mut self) -> Vec<WriteBlock> {
+        let width = self.width();
+        if self.rows.len() < 2 || self.rows.last().map_or(0, Vec::len) == width {
+            return vec![self];
+        }
+        let partial = self.rows.pop().unwrap_or_default();
+        let tail = WriteBlock {
+            sheet: self.sheet.clone(),
+            row: self.row + self.rows.len() as i32,
+            col: self.col,
+            rows: vec![partial],
+        };
+        vec![self, tail]
+    }
+}
+
+/// The pending block and the counters
+#[derive(Debug, Default)]
+pub struct WriteBuffer {
+    pub enabled: bool,
+    block: Option<WriteBlock>,
+    pub stats: WriteBatchStats,
+}
+
+impl WriteBuffer {
+    /// Buffered value of a cell
+    pub fn get(&self, sheet: &str, row: i32, col: i32) -> Option<&str> {
+        self.block.as_ref()?.get(sheet, row, col)
+    }
+
+    /// Buffer a write. Returns the previous block when the write starts a
+    /// new one; the caller sends it to the backend.
+    pub fn push(&mut self, sheet: &str, row: i32, col: i32, value: &str) -> Option<WriteBlock> {
+        self.stats.cells_written += 1;
+        if let Some(block) = self.block.as_mut() {
+            if block.push(sheet, row, col, value) {
+                return None;
+            }
+        }
+        self.block.replace(WriteBlock::new(sheet, row, col, value))
+    }
+
+    /// Remove the pending block, if any
+    pub fn take(&mut self) -> Option<WriteBlock> {
+        self.block.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_all(buffer: &mut WriteBuffer, cells: &[(i32, i32)]) -> Vec<WriteBlock> {
+        cells
+            .iter()
+            .filter_map(|&(row, col)| buffer.push("Sheet1", row, col, &format!("{}:{}", row, col)))
+            .collect()
+    }
+
+    #[test]
+    fn test_coalesces_columns_rows_and_rectangles() {
+        // A column written top to bottom is one block
+        let mut buffer = WriteBuffer::default();
+        assert!(push_all(&mut buffer, &[(0, 0), (1, 0), (2, 0)]).is_empty());
+        let column = buffer.take().unwrap();
+        assert_eq!(column.rows, vec![vec!["0:0"], vec!["1:0"], vec!["2:0"]]);
+
+        // Row-major fill of B2:C3, ending one cell short of a third row
+        assert!(push_all(&mut buffer, &[(1, 1), (1, 2), (2, 1), (2, 2), (3, 1)]).is_empty());
+        assert_eq!(buffer.get("sheet1", 2, 2), Some("2:2"));
+        assert_eq!(buffer.get("Sheet1", 3, 2), None);
+        let writes = buffer.take().unwrap().into_writes();
+        assert_eq!(writes.len(), 2);
+        assert_eq!((writes[1].row, writes[1].col, writes[1].rows.len()), (3, 1, 1));
+
+        // Overwrites stay in the block; a jump starts a new one
+        assert!(push_all(&mut buffer, &[(0, 0), (0, 1), (0, 0)]).is_empty());
+        let sent = push_all(&mut buffer, &[(5, 5)]);
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].cells(), 2);
+        assert_eq!(buffer.stats.cells_written, 12);
+    }
+}
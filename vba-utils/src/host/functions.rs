@@ -0,0 +1,163 @@
+// src/host/functions.rs
+// ============================================================================
+// HOST FUNCTIONS - VBA functions implemented by the embedder in Rust
+//
+// `x = GetCustomerName(42)` looks here before the builtin table, so a host
+// application can expose domain functions (and replace builtins) without
+// touching the macro. Registration is by name, case-insensitive, through
+// RuntimeConfig::function or VbaRuntime::register_function.
+//
+// Before the handler runs the argument count is checked (error 450) and, if
+// the function declares parameter types, each argument is converted to its
+// type (error 13 when it cannot be). Errors the handler raises with a VBA
+// number reach Err unchanged; any other error becomes error 5 with the
+// handler's message as Err.Description.
+// ============================================================================
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::ast::Expression;
+use crate::context::{Context, DeclaredType, Value};
+use crate::host::excel::error_number;
+use crate::vba_errors::VbaErr;
+
+/// Rust body of a host function; receives the evaluated, converted arguments
+pub type HostHandler = Arc<dyn Fn(&[Value], &mut Context) -> Result<Value> + Send + Sync>;
+
+/// A host function and the arguments it accepts
+#[derive(Clone)]
+pub struct HostFunction {
+    handler: HostHandler,
+    min_args: usize,
+    /// None: any number of arguments
+    max_args: Option<usize>,
+    /// Types of the leading arguments; Variant leaves one as passed
+    param_types: Vec<DeclaredType>,
+}
+
+impl HostFunction {
+    /// Any number of arguments of any type
+    pub fn new(handler: impl Fn(&[Value], &mut Context) -> Result<Value> + Send + Sync + 'static) -> Self {
+        Self { handler: Arc::new(handler), min_args: 0, max_args: None, param_types: Vec::new() }
+    }
+
+    /// Accept between `min` and `max` arguments
+    pub fn args(mut self, min: usize, max: usize) -> Self {
+        self.min_args = min;
+        self.max_args = Some(max.max(min));
+        self
+    }
+
+    /// Declare the parameters' types; sets the arity to exactly this many
+    /// unless `args` is called afterwards
+    pub fn params(mut self, types: &[DeclaredType]) -> Self {
+        self.param_types = types.to_vec();
+        self.min_args = types.len();
+        self.max_args = Some(types.len());
+        self
+    }
+
+    /// Check and convert `args`, then run the handler
+    pub fn call(&self, name: &str, args: Vec<Value>, ctx: &mut Context) -> Result<Value> {
+        if args.len() < self.min_args || self.max_args.is_some_and(|max| args.len() > max) {
+            return Err(VbaErr::WrongNumberOfArguments.error(""));
+        }
+        let args = args
+            .into_iter()
+            .enumerate()
+            .map(|(i, arg)| match self.param_types.get(i) {
+                Some(ty) if *ty != DeclaredType::Variant => crate::interpreter::coerce::coerce_to_declared(arg, *ty)
+                    .map_err(|e| match error_number(&e) {
+                        Some(_) => e,
+                        None => VbaErr::TypeMismatch.error(""),
+                    }),
+                _ => Ok(arg),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        crate::vm::hooks::host_call(ctx, crate::vm::HostCallKind::HostFunction, name, &args)?;
+        crate::vm::telemetry::host_span(ctx, crate::vm::HostCallKind::HostFunction, name, |ctx| {
+            (self.handler)(&args, ctx).map_err(|e| match error_number(&e) {
+                Some(_) => e,
+                None => VbaErr::InvalidProcedureCall.error(format!("{}: {}", name, e)),
+            })
+        })
+    }
+}
+
+impl fmt::Debug for HostFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HostFunction")
+            .field("min_args", &self.min_args)
+            .field("max_args", &self.max_args)
+            .field("param_types", &self.param_types)
+            .finish()
+    }
+}
+
+/// Host functions by lowercase name
+#[derive(Debug, Clone, Default)]
+pub struct HostFunctionRegistry {
+    functions: HashMap<String, HostFunction>,
+}
+
+impl HostFunctionRegistry {
+    pub fn register(&mut self, name: &str, function: HostFunction) {
+        self.functions.insert(name.to_ascii_lowercase(), function);
+    }
+
+    pub fn get(&self, name: &str) -> Option<HostFunction> {
+        self.functions.get(&name.to_ascii_lowercase()).cloned()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+}
+
+/// Run `name` if the embedder registered it; None lets the builtins have it
+pub(crate) fn call_host_function(name: &str, args: &[Expression], ctx: &mut Context) -> Option<Result<Value>> {
+    let function = ctx.runtime_config.host_functions.get(name)?;
+    let values = args
+        .iter()
+        .map(|a| crate::interpreter::evaluate_expression(a, ctx))
+        .collect::<Result<Vec<_>>>();
+    Some(values.and_then(|values| function.call(name, values, ctx)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime_config::RuntimeConfig;
+
+    fn call(name: &str, args: Vec<Expression>) -> Expression {
+        Expression::FunctionCall { function: Box::new(Expression::Identifier(name.into())), args }
+    }
+
+    #[test]
+    fn test_host_functions() {
+        let customer = HostFunction::new(|args, _ctx| match args[0] {
+            Value::Long(42) => Ok(Value::String("Contoso".into())),
+            _ => anyhow::bail!("no such customer"),
+        })
+        .params(&[DeclaredType::Long]);
+        // Replaces the builtin Len
+        let len = HostFunction::new(|_, _| Ok(Value::Integer(-1)));
+        let config = RuntimeConfig::builder().function("GetCustomerName", customer).function("Len", len).build();
+        let mut ctx = Context::with_config(config);
+        let mut eval = |expr: Expression| crate::interpreter::evaluate_expression(&expr, &mut ctx);
+
+        assert_eq!(eval(call("getcustomername", vec![Expression::String("42".into())])).unwrap().as_string(), "Contoso");
+        assert_eq!(eval(call("Len", vec![Expression::String("abc".into())])).unwrap().as_string(), "-1");
+
+        let number = |r: Result<Value>| r.err().and_then(|e| error_number(&e));
+        assert_eq!(number(eval(call("GetCustomerName", vec![]))), Some(450));
+        assert_eq!(number(eval(call("GetCustomerName", vec![Expression::String("x".into())]))), Some(13));
+        let missing = eval(call("GetCustomerName", vec![Expression::Integer(7)])).unwrap_err();
+        assert_eq!(error_number(&missing), Some(5));
+        assert!(missing.to_string().contains("no such customer"));
+    }
+}
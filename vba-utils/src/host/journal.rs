@@ -0,0 +1,493 @@
+// src/host/journal.rs
+// ============================================================================
+// WRITE JOURNAL - Transactional runs over the static and custom backends
+//
+// With RuntimeConfig::journal or rollback_on_error set, initialize_excel_host
+// puts a JournalBackend in front of the configured backend, which records
+// what each cell held before the macro wrote its value, formula or number
+// format. When the run ends:
+//
+//   - it failed (an error stopped it, or execute returned one) and
+//     rollback_on_error is on: every write is undone, newest first
+//   - the run's journal is filed in the WriteJournal under the run ID
+//     (RuntimeConfig::run_id), and executing again with the ID of a
+//     committed run does nothing, so a service can retry requests safely
+//
+// Rolling back writes to the backend the run wrote through and deletes the
+// sheets it added there (backends that cannot delete sheets fail the
+// rollback). Only changes that go through the backend are journaled:
+// comments, merges and other formatting the static engine keeps outside the
+// backend interface stay.
+// ============================================================================
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::host::excel::backend::{CellValue, ExcelBackend, SharedExcelBackend};
+use crate::host::excel::locale::CellLocale;
+
+/// What a journaled write changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalWrite {
+    /// Value or formula (`before` is the formula, or the value of a
+    /// constant cell)
+    Contents,
+    NumberFormat,
+}
+
+/// One write, with what it replaced
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalEntry {
+    pub sheet: String,
+    pub row: i32,
+    pub col: i32,
+    pub write: JournalWrite,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// Finished without an error; its writes stand
+    Committed,
+    /// Failed and its writes were undone
+    RolledBack,
+    /// Failed with rollback_on_error off; its writes stand
+    Failed,
+}
+
+/// The writes of one run, in order
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunJournal {
+    pub run_id: String,
+    pub entries: Vec<JournalEntry>,
+    pub added_sheets: Vec<String>,
+    pub outcome: RunOutcome,
+}
+
+/// Journals of finished runs by run ID, shared by the RuntimeConfigs of
+/// the runs a service makes
+#[derive(Clone, Default)]
+pub struct WriteJournal(Arc<Mutex<HashMap<String, RunJournal>>>);
+
+impl WriteJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Journal of the run with this ID (its last execution)
+    pub fn get(&self, run_id: &str) -> Option<RunJournal> {
+        self.0.lock().unwrap().get(run_id).cloned()
+    }
+
+    /// Whether a run with this ID committed, so it must not run again
+    pub fn is_committed(&self, run_id: &str) -> bool {
+        self.get(run_id).is_some_and(|run| run.outcome == RunOutcome::Committed)
+    }
+
+    pub(crate) fn file(&self, run: RunJournal) {
+        self.0.lock().unwrap().insert(run.run_id.clone(), run);
+    }
+}
+
+impl fmt::Debug for WriteJournal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WriteJournal").field("runs", &self.0.lock().unwrap().len()).finish()
+    }
+}
+
+/// Journal of a run in progress, held by the run's Context
+/// (`Context::active_journal`) and the JournalBackend in front of its
+/// backend, so concurrent runs each journal their own writes and roll back
+/// only those
+#[derive(Clone, Default)]
+pub struct ActiveJournal {
+    run: Arc<Mutex<Option<RunJournal>>>,
+    /// The backend behind the run's JournalBackend, which undo writes to
+    target: Arc<Mutex<Option<SharedExcelBackend>>>,
+}
+
+impl ActiveJournal {
+    /// Whether writes are still being journaled (the run has not finished)
+    pub fn is_active(&self) -> bool {
+        self.run.lock().unwrap().is_some()
+    }
+
+    fn record(&self, update: impl FnOnce(&mut RunJournal)) {
+        if let Some(run) = self.run.lock().unwrap().as_mut() {
+            update(run);
+        }
+    }
+
+    /// Stop journaling: undo the run's writes when it `failed` and
+    /// `rollback` is on, and return its journal. Buffered writes must have
+    /// been flushed.
+    pub(crate) fn finish(&self, failed: bool, rollback: bool) -> Result<Option<RunJournal>, String> {
+        let Some(mut run) = self.run.lock().unwrap().take() else {
+            return Ok(None);
+        };
+        run.outcome = match (failed, rollback) {
+            (false, _) => RunOutcome::Committed,
+            (true, false) => RunOutcome::Failed,
+            (true, true) => {
+                if let Some(target) = self.target.lock().unwrap().clone() {
+                    undo(&run, &mut *target.lock().unwrap())?;
+                }
+                RunOutcome::RolledBack
+            }
+        };
+        Ok(Some(run))
+    }
+}
+
+impl fmt::Debug for ActiveJournal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let run_id = self.run.lock().unwrap().as_ref().map(|run| run.run_id.clone());
+        f.debug_tuple("ActiveJournal").field(&run_id).finish()
+    }
+}
+
+/// Start journaling the writes of run `run_id`
+pub(crate) fn begin(run_id: &str) -> ActiveJournal {
+    let run = RunJournal {
+        run_id: run_id.to_string(),
+        entries: Vec::new(),
+        added_sheets: Vec::new(),
+        outcome: RunOutcome::Committed,
+    };
+    ActiveJournal { run: Arc::new(Mutex::new(Some(run))), target: Arc::default() }
+}
+
+/// Put back what each write replaced, newest first, then delete the sheets
+/// the run added
+fn undo(run: &RunJournal, backend: &mut dyn ExcelBackend) -> Result<(), String> {
+    for entry in run.entries.iter().rev() {
+        match entry.write {
+            JournalWrite::Contents => backend.set_cell_formula(&entry.sheet, entry.row, entry.col, &entry.before)?,
+            JournalWrite::NumberFormat => backend.set_number_format(&entry.sheet, entry.row, entry.col, &entry.before)?,
+        }
+    }
+    for sheet in run.added_sheets.iter().rev() {
+        backend.delete_sheet(sheet)?;
+    }
+    eprintln!(
+        "↩️  Rolled back {} write(s) and {} added sheet(s) of run {}",
+        run.entries.len(),
+        run.added_sheets.len(),
+        run.run_id
+    );
+    Ok(())
+}
+
+/// Applies writes to the wrapped backend, journaling what they replaced
+/// into its run's journal
+pub struct JournalBackend {
+    inner: SharedExcelBackend,
+    journal: ActiveJournal,
+}
+
+impl JournalBackend {
+    pub fn new(inner: SharedExcelBackend, journal: ActiveJournal) -> Self {
+        *journal.target.lock().unwrap() = Some(inner.clone());
+        Self { inner, journal }
+    }
+
+    fn contents(&self, sheet: &str, row: i32, col: i32, after: &str) -> Option<JournalEntry> {
+        if !self.journal.is_active() {
+            return None;
+        }
+        let before = self.inner.lock().unwrap().get_cell_formula(sheet, row, col).unwrap_or_default();
+        Some(JournalEntry { sheet: sheet.to_string(), row, col, write: JournalWrite::Contents, before, after: after.to_string() })
+    }
+
+    /// Journal `entries` once the write they describe succeeded
+    fn applied(&self, entries: Vec<JournalEntry>, result: Result<(), String>) -> Result<(), String> {
+        if result.is_ok() {
+            self.journal.record(|run| run.entries.extend(entries));
+        }
+        result
+    }
+}
+
+impl ExcelBackend for JournalBackend {
+    fn name(&self) -> &str {
+        "journal"
+    }
+
+    fn workbook_id(&self) -> Option<String> {
+        self.inner.lock().unwrap().workbook_id()
+    }
+
+    fn sheet_names(&self) -> Vec<String> {
+        self.inner.lock().unwrap().sheet_names()
+    }
+
+    fn add_sheet(&mut self, name: &str) -> Result<(), String> {
+        self.inner.lock().unwrap().add_sheet(name)?;
+        self.journal.record(|run| run.added_sheets.push(name.to_string()));
+        Ok(())
+    }
+
+    fn delete_sheet(&mut self, name: &str) -> Result<(), String> {
+        self.inner.lock().unwrap().delete_sheet(name)
+    }
+
+    fn get_cell_value(&self, sheet: &str, row: i32, col: i32) -> Result<String, String> {
+        self.inner.lock().unwrap().get_cell_value(sheet, row, col)
+    }
+
+    fn get_typed_value(&self, sheet: &str, row: i32, col: i32, locale: &CellLocale) -> Result<CellValue, String> {
+        self.inner.lock().unwrap().get_typed_value(sheet, row, col, locale)
+    }
+
+    fn set_cell_value(&mut self, sheet: &str, row: i32, col: i32, value: &str) -> Result<(), String> {
+        let entry = self.contents(sheet, row, col, value);
+        let result = self.inner.lock().unwrap().set_cell_value(sheet, row, col, value);
+        self.applied(entry.into_iter().collect(), result)
+    }
+
+    fn set_range_values(&mut self, sheet: &str, row: i32, col: i32, values: &[Vec<String>]) -> Result<(), String> {
+        let mut entries = Vec::new();
+        for (r, line) in values.iter().enumerate() {
+            for (c, value) in line.iter().enumerate() {
+                entries.extend(self.contents(sheet, row + r as i32, col + c as i32, value));
+            }
+        }
+        let result = self.inner.lock().unwrap().set_range_values(sheet, row, col, values);
+        self.applied(entries, result)
+    }
+
+    fn get_cell_formula(&self, sheet: &str, row: i32, col: i32) -> Result<String, String> {
+        self.inner.lock().unwrap().get_cell_formula(sheet, row, col)
+    }
+
+    fn set_cell_formula(&mut self, sheet: &str, row: i32, col: i32, formula: &str) -> Result<(), String> {
+        let entry = self.contents(sheet, row, col, formula);
+        let result = self.inner.lock().unwrap().set_cell_formula(sheet, row, col, formula);
+        self.applied(entry.into_iter().collect(), result)
+    }
+
+    fn get_number_format(&self, sheet: &str, row: i32, col: i32) -> Result<String, String> {
+        self.inner.lock().unwrap().get_number_format(sheet, row, col)
+    }
+
+    fn set_number_format(&mut self, sheet: &str, row: i32, col: i32, format: &str) -> Result<(), String> {
+        let entry = self.journal.is_active().then(|| JournalEntry {
+            sheet: sheet.to_string(),
+            row,
+            col,
+            write: JournalWrite::NumberFormat,
+            before: self.inner.lock().unwrap().get_number_format(sheet, row, col).unwrap_or_default(),
+            after: format.to_string(),
+        });
+        let result = self.inner.lock().unwrap().set_number_format(sheet, row, col, format);
+        self.applied(entry.into_iter().collect(), result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{AssignmentTarget, Expression, Program, Statement};
+    use crate::host::excel::engine;
+    use crate::host::excel::fixture::{assert_cell_eq, WorkbookFixture};
+    use crate::runtime_config::RuntimeConfig;
+
+    #[test]
+    fn test_failed_run_rolls_back_and_committed_run_is_not_repeated() {
+        // Range("A1").Value = Range("A1").Value + 1
+        // Range("B1").Formula = "=A1*2"
+        // Range("C1").NumberFormat = "0.00"
+        // x = 1 / Divisor
+        let range = |address: &str| Expression::FunctionCall {
+            function: Box::new(Expression::Identifier("Range".into())),
+            args: vec![Expression::String(address.into())],
+        };
+        let set = |address: &str, property: &str, rvalue: Expression| Statement::Assignment {
+            lvalue: AssignmentTarget::PropertyAccess { object: Box::new(range(address)), property: property.into() },
+            rvalue,
+            rvalue_compiled: Default::default(),
+        };
+        let body = vec![
+            set("A1", "Value", Expression::BinaryOp {
+                left: Box::new(Expression::PropertyAccess { obj: Box::new(range("A1")), property: "Value".into() }),
+                op: "+".into(),
+                right: Box::new(Expression::Integer(1)),
+            }),
+            set("B1", "Formula", Expression::String("=A1*2".into())),
+            set("C1", "NumberFormat", Expression::String("0.00".into())),
+            Statement::Assignment {
+                lvalue: AssignmentTarget::Identifier("x".into()),
+                rvalue: Expression::BinaryOp {
+                    left: Box::new(Expression::Integer(1)),
+                    op: "/".into(),
+                    right: Box::new(Expression::Identifier("Divisor".into())),
+                },
+                rvalue_compiled: Default::default(),
+            },
+        ];
+        let program = Program {
+            statements: vec![Statement::Subroutine { visibility: None, name: "Main".into(), params: vec![], body: body.into() }],
+            diagnostics: vec![],
+        };
+        let executor = crate::vm::ProgramExecutor::new(program);
+        let journal = WriteJournal::new();
+        let fixture = WorkbookFixture::new().cell("A1", 41).formula("B1", "=A1");
+        let run = |divisor: i64| {
+            let config = RuntimeConfig::builder()
+                .run_id("request-7")
+                .journal(journal.clone())
+                .rollback_on_error(true)
+                .global("Divisor", crate::context::Value::Integer(divisor))
+                .build();
+            let mut ctx = fixture.context(config).unwrap();
+            executor.execute(&mut ctx).unwrap();
+            ctx
+        };
+
+        let _engine = engine::tests::lock_engine();
+        let failed = run(0);
+        assert_cell_eq(&failed, "A1", 41);
+        assert_cell_eq(&failed, "B1", 41);
+        let _workbook = failed.static_workbook.enter();
+        assert_eq!(crate::host::excel::static_engine::static_get_cell_formula("Sheet1", 0, 1), "=A1");
+        assert_eq!(crate::host::excel::static_engine::static_get_number_format("Sheet1", 0, 2), "General");
+        drop(_workbook);
+        let rolled_back = journal.get("request-7").unwrap();
+        assert_eq!(rolled_back.outcome, RunOutcome::RolledBack);
+        assert_eq!(rolled_back.entries.len(), 3);
+        assert_eq!((rolled_back.entries[0].before.as_str(), rolled_back.entries[0].after.as_str()), ("41", "42"));
+
+        let committed = run(1);
+        assert_cell_eq(&committed, "B1", 84);
+        assert!(journal.is_committed("request-7"));
+
+        // The same request again is not executed
+        let mut repeated = run(1);
+        assert_cell_eq(&repeated, "A1", 41);
+        assert_cell_eq(&repeated, "C1", crate::host::excel::backend::CellValue::Empty);
+        assert!(repeated.active_journal.is_none());
+        executor.shutdown(&mut repeated).unwrap();
+    }
+
+    #[test]
+    fn test_handled_error_does_not_roll_back() {
+        // On Error Resume Next
+        // Range("A1").Value = 7
+        // x = 1 / 0
+        let body = vec![
+            Statement::OnError(crate::ast::OnErrorKind::ResumeNext),
+            Statement::Assignment {
+                lvalue: AssignmentTarget::PropertyAccess {
+                    object: Box::new(Expression::FunctionCall {
+                        function: Box::new(Expression::Identifier("Range".into())),
+                        args: vec![Expression::String("A1".into())],
+                    }),
+                    property: "Value".into(),
+                },
+                rvalue: Expression::Integer(7),
+                rvalue_compiled: Default::default(),
+            },
+            Statement::Assignment {
+                lvalue: AssignmentTarget::Identifier("x".into()),
+                rvalue: Expression::BinaryOp {
+                    left: Box::new(Expression::Integer(1)),
+                    op: "/".into(),
+                    right: Box::new(Expression::Integer(0)),
+                },
+                rvalue_compiled: Default::default(),
+            },
+        ];
+        let program = Program {
+            statements: vec![Statement::Subroutine { visibility: None, name: "Main".into(), params: vec![], body: body.into() }],
+            diagnostics: vec![],
+        };
+        let executor = crate::vm::ProgramExecutor::new(program);
+        let journal = WriteJournal::new();
+        let config = RuntimeConfig::builder().run_id("request-8").journal(journal.clone()).rollback_on_error(true).build();
+
+        let _engine = engine::tests::lock_engine();
+        let mut ctx = WorkbookFixture::new().cell("A1", 1).context(config).unwrap();
+        executor.execute(&mut ctx).unwrap();
+        // The macro handled the error: Err still reports it, the run stands
+        assert_eq!(ctx.err.as_ref().map(|e| e.number), Some(11));
+        assert_cell_eq(&ctx, "A1", 7);
+        assert!(journal.is_committed("request-8"));
+        executor.shutdown(&mut ctx).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_runs_keep_separate_journals() {
+        let workbook = crate::host::excel::static_engine::StaticWorkbook::default();
+        let _workbook = workbook.enter();
+        let backend = || -> SharedExcelBackend { Arc::new(Mutex::new(crate::host::excel::backend::StaticBackend::default())) };
+        let (first, second) = (begin("run-a"), begin("run-b"));
+        let mut a = JournalBackend::new(backend(), first.clone());
+        let mut b = JournalBackend::new(backend(), second.clone());
+        a.set_cell_value("Sheet1", 0, 0, "1").unwrap();
+        b.set_cell_value("Sheet1", 0, 1, "2").unwrap();
+        b.set_cell_value("Sheet1", 0, 1, "3").unwrap();
+
+        let run_a = first.finish(false, false).unwrap().unwrap();
+        assert_eq!((run_a.run_id.as_str(), run_a.entries.len()), ("run-a", 1));
+        assert!(!first.is_active() && second.is_active());
+
+        // The finished run's backend stops journaling, the other keeps going
+        a.set_cell_value("Sheet1", 0, 0, "4").unwrap();
+        b.set_cell_value("Sheet1", 0, 2, "5").unwrap();
+        let run_b = second.finish(false, false).unwrap().unwrap();
+        assert_eq!((run_b.run_id.as_str(), run_b.entries.len()), ("run-b", 3));
+        assert_eq!(run_b.entries[1].before, "2");
+    }
+
+    // Rollback goes to the backend the run wrote through, not the one the
+    // engine has installed, and deletes the sheets the run added
+    #[test]
+    fn test_rollback_undoes_writes_and_added_sheets_on_its_own_backend() {
+        let workbook = crate::host::excel::static_engine::StaticWorkbook::default();
+        let _workbook = workbook.enter();
+        let backend = || -> SharedExcelBackend { Arc::new(Mutex::new(crate::host::excel::backend::StaticBackend::default())) };
+        let (failed_target, other_target) = (backend(), backend());
+        let (failed, other) = (begin("run-c"), begin("run-d"));
+        let mut a = JournalBackend::new(failed_target.clone(), failed.clone());
+        let mut b = JournalBackend::new(other_target.clone(), other.clone());
+        a.add_sheet("Report").unwrap();
+        a.set_cell_value("Report", 0, 0, "total").unwrap();
+        b.add_sheet("Summary").unwrap();
+
+        let rolled_back = failed.finish(true, true).unwrap().unwrap();
+        assert_eq!(rolled_back.outcome, RunOutcome::RolledBack);
+        assert_eq!(rolled_back.added_sheets, vec!["Report".to_string()]);
+        assert!(!failed_target.lock().unwrap().sheet_names().contains(&"Report".to_string()));
+        assert_eq!(failed_target.lock().unwrap().get_cell_value("Report", 0, 0).unwrap(), "");
+        assert!(other_target.lock().unwrap().sheet_names().contains(&"Summary".to_string()));
+
+        // A backend that cannot delete sheets fails the rollback
+        #[derive(Default)]
+        struct NoDelete(crate::host::excel::backend::StaticBackend);
+        impl ExcelBackend for NoDelete {
+            fn name(&self) -> &str {
+                "no-delete"
+            }
+            fn workbook_id(&self) -> Option<String> {
+                None
+            }
+            fn sheet_names(&self) -> Vec<String> {
+                self.0.sheet_names()
+            }
+            fn add_sheet(&mut self, name: &str) -> Result<(), String> {
+                self.0.add_sheet(name)
+            }
+            fn get_cell_value(&self, sheet: &str, row: i32, col: i32) -> Result<String, String> {
+                self.0.get_cell_value(sheet, row, col)
+            }
+            fn set_cell_value(&mut self, sheet: &str, row: i32, col: i32, value: &str) -> Result<(), String> {
+                self.0.set_cell_value(sheet, row, col, value)
+            }
+        }
+        let mut c = JournalBackend::new(Arc::new(Mutex::new(NoDelete::default())), other.clone());
+        c.add_sheet("Extra").unwrap();
+        assert_eq!(other.finish(true, true).unwrap_err(), "The no-delete backend cannot delete sheet Extra");
+    }
+}
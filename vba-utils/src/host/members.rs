@@ -0,0 +1,107 @@
+// src/host/members.rs
+// ============================================================================
+// MEMBERS - "Did you mean" for members a host object does not have
+//
+// Host objects dispatch member names case-insensitively; a name that matches
+// nothing is error 438 with the nearest member of the object's member table,
+// when one is close enough to be a typo:
+//
+//     Unknown Application property 'ScreenUpdatng'; did you mean 'ScreenUpdating'?
+//
+// Distance is the optimal string alignment distance on case-folded names
+// (an insertion, deletion, substitution or swap of two neighbouring
+// letters counts one), and a suggestion may be at most a third of the
+// name's length away, and never more than 3.
+// ============================================================================
+
+use crate::host::excel::error_number;
+use crate::vba_errors::VbaErr;
+
+/// Edits turning `a` into `b`, ignoring case
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().flat_map(char::to_lowercase).collect();
+    let b: Vec<char> = b.chars().flat_map(char::to_lowercase).collect();
+    // Rows i-2, i-1 and i of the distance table
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1).min(current[j - 1] + 1).min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// The member of `members` nearest to `name`, if close enough to be a typo
+/// of it; the first of equally near ones
+pub fn nearest<'a>(name: &str, members: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let limit = (name.chars().count() / 3).clamp(1, 3);
+    members
+        .into_iter()
+        .map(|member| (edit_distance(name, member), member))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, member)| member)
+}
+
+/// Error 438 for `object`'s `kind` ("property", "method") `name`, suggesting
+/// the nearest of `members`
+pub fn unknown_member<'a>(object: &str, kind: &str, name: &str, members: impl IntoIterator<Item = &'a str>) -> anyhow::Error {
+    let mut message = format!("Unknown {} {} '{}'", object, kind, name);
+    if let Some(member) = nearest(name, members) {
+        message.push_str(&format!("; did you mean '{}'?", member));
+    }
+    VbaErr::ObjectDoesntSupport.message(message)
+}
+
+/// Whether `e` is error 438: the object has no such member
+pub fn is_unknown_member(e: &anyhow::Error) -> bool {
+    error_number(e) == Some(VbaErr::ObjectDoesntSupport.number())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_member_suggestion() {
+        assert_eq!(edit_distance("ScreenUpdatng", "screenupdating"), 1);
+        assert_eq!(edit_distance("Caluclate", "Calculate"), 1);
+        assert_eq!(edit_distance("", "Quit"), 4);
+
+        let members = ["DisplayAlerts", "ScreenUpdating", "EnableEvents", "Calculate", "Quit"];
+        assert_eq!(nearest("SCREENUPDATNG", members), Some("ScreenUpdating"));
+        assert_eq!(nearest("Quti", members), Some("Quit"));
+        assert_eq!(nearest("Volume", members), None);
+
+        let e = unknown_member("Application", "property", "ScreenUpdatng", members);
+        assert_eq!(e.to_string(), "Unknown Application property 'ScreenUpdatng'; did you mean 'ScreenUpdating'?");
+        assert!(is_unknown_member(&e));
+        assert_eq!(unknown_member("Application", "method", "Frobnicate", members).to_string(), "Unknown Application method 'Frobnicate'");
+    }
+
+    #[test]
+    fn test_application_member_table_matches_dispatch() {
+        use crate::host::excel::properties::application::{get_property, PROPERTIES};
+
+        let _engine = crate::host::excel::engine::tests::lock_engine();
+        let mut ctx = crate::context::Context::new();
+        for property in PROPERTIES {
+            if let Err(e) = get_property(property, &mut ctx) {
+                assert!(!is_unknown_member(&e), "{} is in the table but not routed", property);
+            }
+        }
+        let e = get_property("ScreenUpdatng", &mut ctx).err().unwrap();
+        assert_eq!(e.to_string(), "Unknown Application property 'ScreenUpdatng'; did you mean 'ScreenUpdating'?");
+        let e = crate::host::excel::methods::application::call_method("Calcualte", &[], &mut ctx).err().unwrap();
+        assert_eq!(e.to_string(), "Unknown Application method 'Calcualte'; did you mean 'Calculate'?");
+    }
+}
@@ -1,6 +1,21 @@
 // src/host/mod.rs
 
+pub mod assert;
+pub mod behavior;
+pub mod com_object;
+pub mod dll;
+pub mod dry_run;
 pub mod excel;
+pub mod functions;
+pub mod journal;
+pub mod members;
+pub mod ontime;
+pub mod outlook;
+pub mod stub;
+pub mod ui;
+pub mod userform;
+pub mod vb;
+pub mod word;
 
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -9,6 +24,7 @@ use std::rc::Rc;
 use anyhow::{anyhow, Result};
 
 use crate::context::{Context, Value};
+use crate::symbol::Symbol;
 
 /// Trait implemented by all COM-style host objects.
 pub trait ComObject {
@@ -16,13 +32,89 @@ pub trait ComObject {
     fn set_property(&mut self, name: &str, value: Value, ctx: &mut Context) -> Result<()>;
     fn call_method(&mut self, name: &str, args: &[Value], ctx: &mut Context) -> Result<Value>;
     fn type_name(&self) -> &str;
+
+    /// The member used when the object itself is read, assigned or indexed:
+    /// `x = obj` reads it, `obj = 5` sets it, `obj(1)` calls it with the
+    /// arguments. None when the object has no default member (error 438).
+    fn default_member(&self) -> Option<&str> {
+        None
+    }
 }
 
 pub type ComObjectHandle = Rc<RefCell<dyn ComObject>>;
 
+/// Reference to a Word, Outlook, UserForm, VB global or recording-stub
+/// object, carried in `Value::Object` as "<Application>.<Type>:<data>" (e.g.
+/// "Word.Document:1", "Outlook.MailItem:3", "UserForm.Form:UserForm1",
+/// "VB.Clipboard:", "Stub.Object:2"). Excel objects use
+/// `excel::objects::object_ref`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectRef {
+    /// Lowercase application: "word", "outlook", "userform", "vb" or "stub"
+    pub application: String,
+    /// Lowercase object type within the application
+    pub object_type: String,
+    pub data: String,
+}
+
+impl ObjectRef {
+    /// Split a host object value; None for anything else
+    pub fn parse(value: &Value) -> Option<Self> {
+        let Value::Object(Some(inner)) = value else {
+            return None;
+        };
+        let Value::String(s) = inner.as_ref() else {
+            return None;
+        };
+        let (application, rest) = s.split_once('.')?;
+        let application = application.to_ascii_lowercase();
+        if !matches!(application.as_str(), "word" | "outlook" | "userform" | "vb" | "stub") {
+            return None;
+        }
+        let (object_type, data) = rest.split_once(':')?;
+        Some(Self { application, object_type: object_type.to_ascii_lowercase(), data: data.to_string() })
+    }
+
+    fn stub(&self) -> Result<stub::RecordingStub> {
+        stub::RecordingStub::from_data(&self.data).ok_or_else(|| anyhow!("Invalid stub object: {}", self.data))
+    }
+
+    pub fn get_property(&self, property: &str, ctx: &mut Context) -> Result<Value> {
+        match self.application.as_str() {
+            "word" => word::objects::get_property(&self.object_type, &self.data, property),
+            "outlook" => outlook::objects::get_property(&self.object_type, &self.data, property),
+            "userform" => userform::get_property(&self.object_type, &self.data, property, ctx),
+            "vb" => vb::get_property(&self.object_type, property),
+            _ => self.stub()?.get_property(property, ctx),
+        }
+    }
+
+    pub fn set_property(&self, property: &str, value: Value, ctx: &mut Context) -> Result<()> {
+        match self.application.as_str() {
+            "word" => word::objects::set_property(&self.object_type, &self.data, property, value),
+            "outlook" => outlook::objects::set_property(&self.object_type, &self.data, property, value),
+            "userform" => userform::set_property(&self.object_type, &self.data, property, value, ctx),
+            "vb" => vb::set_property(&self.object_type, property, value, ctx),
+            _ => self.stub()?.set_property(property, value, ctx),
+        }
+    }
+
+    pub fn call_method(&self, method: &str, args: &[Value], ctx: &mut Context) -> Result<Value> {
+        let name = format!("{}.{}", self.object_type, method);
+        crate::vm::hooks::host_call(ctx, crate::vm::HostCallKind::Method, &name, args)?;
+        crate::vm::telemetry::host_span(ctx, crate::vm::HostCallKind::Method, &name, |ctx| match self.application.as_str() {
+            "word" => word::objects::call_method(&self.object_type, &self.data, method, args),
+            "outlook" => outlook::objects::call_method(&self.object_type, &self.data, method, args),
+            "userform" => userform::call_method(&self.object_type, &self.data, method, args, ctx),
+            "vb" => vb::call_method(&self.object_type, method, args, ctx),
+            _ => self.stub()?.call_method(method, args, ctx),
+        })
+    }
+}
+
 /// Registry of COM objects (Application, Range, Workbook, etc.)
 pub struct ComRegistry {
-    globals: HashMap<String, ComObjectHandle>,
+    globals: HashMap<Symbol, ComObjectHandle>,
 }
 
 impl ComRegistry {
@@ -40,13 +132,18 @@ impl ComRegistry {
     }
 
     /// Register a named global COM object (e.g. "Application").
-    pub fn register_global(&mut self, name: impl Into<String>, obj: ComObjectHandle) {
+    pub fn register_global(&mut self, name: impl Into<Symbol>, obj: ComObjectHandle) {
         self.globals.insert(name.into(), obj);
     }
 
-    /// Look up a previously registered global object.
-    pub fn get_global(&self, name: &str) -> Option<ComObjectHandle> {
-        self.globals.get(name).cloned()
+    /// Look up a previously registered global object (case-insensitive).
+    pub fn get_global(&self, name: impl Into<Symbol>) -> Option<ComObjectHandle> {
+        self.globals.get(&name.into()).cloned()
+    }
+
+    /// Drop a registered global object, returning it if there was one.
+    pub fn unregister_global(&mut self, name: impl Into<Symbol>) -> Option<ComObjectHandle> {
+        self.globals.remove(&name.into())
     }
 }
 
@@ -107,3 +204,129 @@ pub fn dispatch_com_call(
         borrowed.get_property(property_or_method, ctx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{AssignmentTarget, Expression, Statement};
+    use crate::runtime_config::{ExecutionEngine, RuntimeConfig};
+
+    /// A list whose default member, Item, appends on assignment, returns the
+    /// last value when read and the nth value when indexed
+    struct Bag(Vec<Value>);
+
+    impl ComObject for Bag {
+        fn get_property(&self, name: &str, _ctx: &mut Context) -> Result<Value> {
+            match name {
+                "Item" => Ok(self.0.last().cloned().unwrap_or(Value::Empty)),
+                _ => Err(anyhow!("no property {}", name)),
+            }
+        }
+
+        fn set_property(&mut self, name: &str, value: Value, _ctx: &mut Context) -> Result<()> {
+            match name {
+                "Item" => Ok(self.0.push(value)),
+                _ => Err(anyhow!("no property {}", name)),
+            }
+        }
+
+        fn call_method(&mut self, name: &str, args: &[Value], _ctx: &mut Context) -> Result<Value> {
+            let index = args.first().and_then(Value::as_integer).unwrap_or(0);
+            match name {
+                "Item" => usize::try_from(index - 1).ok().and_then(|i| self.0.get(i).cloned()).ok_or_else(|| anyhow!("no item {}", index)),
+                _ => Err(anyhow!("no method {}", name)),
+            }
+        }
+
+        fn type_name(&self) -> &str {
+            "Bag"
+        }
+
+        fn default_member(&self) -> Option<&str> {
+            Some("Item")
+        }
+    }
+
+    #[test]
+    fn test_default_members_for_assignment_reads_and_indexing() {
+        let let_ = |lvalue: AssignmentTarget, rvalue: Expression| Statement::Assignment { lvalue, rvalue, rvalue_compiled: Default::default() };
+        let var = |name: &str| AssignmentTarget::Identifier(name.into());
+        let call = |name: &str, args: Vec<Expression>| Expression::FunctionCall { function: Box::new(Expression::Identifier(name.into())), args };
+        // Set Items = DefaultBag: Items = "a": Items = "b"
+        // First = Items(1): Last = Items
+        // Range("C5") = 5: Cell = Range("C5")
+        let body = vec![
+            Statement::Set { target: "Items".into(), expr: Expression::Identifier("DefaultBag".into()) },
+            let_(var("Items"), Expression::String("a".into())),
+            let_(var("Items"), Expression::String("b".into())),
+            let_(var("First"), call("Items", vec![Expression::Integer(1)])),
+            let_(var("Last"), Expression::Identifier("Items".into())),
+            let_(AssignmentTarget::DefaultMember { object: Box::new(call("Range", vec![Expression::String("C5".into())])) }, Expression::Integer(5)),
+            let_(var("Cell"), call("Range", vec![Expression::String("C5".into())])),
+        ];
+        // 3 = 1: a number has no default member
+        let no_default = vec![let_(AssignmentTarget::DefaultMember { object: Box::new(Expression::Integer(3)) }, Expression::Integer(1))];
+
+        for engine in [ExecutionEngine::Vm, ExecutionEngine::Interpreter] {
+            let bag = Rc::new(RefCell::new(Bag(Vec::new())));
+            let mut ctx = Context::with_config(RuntimeConfig::builder().engine(engine).build());
+            ctx.com_registry.register_global("DefaultBag", bag.clone());
+            let run = |body: &[Statement], ctx: &mut Context| match engine {
+                ExecutionEngine::Vm => crate::vm::runtime::run_statement_list_vm(body.to_vec(), ctx, 0),
+                ExecutionEngine::Interpreter => crate::interpreter::execute_statement_list(body, ctx),
+            };
+            run(&body, &mut ctx);
+
+            assert!(ctx.err.is_none(), "{:?}: {:?}", engine, ctx.err);
+            assert_eq!(bag.borrow().0.len(), 2);
+            assert_eq!(ctx.get_var("First").unwrap().as_string(), "a");
+            assert_eq!(ctx.get_var("Last").unwrap().as_string(), "b");
+            assert!(!matches!(ctx.get_var("Cell"), Some(Value::Object(_))), "{:?}", ctx.get_var("Cell"));
+
+            run(&no_default, &mut ctx);
+            assert_eq!(ctx.err.as_ref().map(|e| e.number), Some(438));
+        }
+    }
+
+    #[test]
+    fn test_objects_used_as_values_read_their_default_member() {
+        let let_ = |name: &str, rvalue: Expression| Statement::Assignment { lvalue: AssignmentTarget::Identifier(name.into()), rvalue, rvalue_compiled: Default::default() };
+        let a1 = || Expression::FunctionCall { function: Box::new(Expression::Identifier("Range".into())), args: vec![Expression::String("A1".into())] };
+        let binary = |op: &str, right: Expression| Expression::BinaryOp { left: Box::new(a1()), op: op.into(), right: Box::new(right) };
+        // Range("A1") = 5
+        // y = Range("A1") + 1: Same = Range("A1") = 5: Neg = -Range("A1")
+        // MsgBox y: MsgBox Same: MsgBox Neg: MsgBox Range("A1")
+        let msg_box = |expr: Expression| Statement::MsgBox { expr, buttons: None, title: None };
+        let body = vec![
+            Statement::Assignment {
+                lvalue: AssignmentTarget::DefaultMember { object: Box::new(a1()) },
+                rvalue: Expression::Integer(5),
+                rvalue_compiled: Default::default(),
+            },
+            let_("y", binary("+", Expression::Integer(1))),
+            let_("Same", binary("=", Expression::Integer(5))),
+            let_("Neg", Expression::UnaryOp { op: "-".into(), expr: Box::new(a1()) }),
+            msg_box(Expression::Identifier("y".into())),
+            msg_box(Expression::Identifier("Same".into())),
+            msg_box(Expression::Identifier("Neg".into())),
+            msg_box(a1()),
+        ];
+
+        let program = crate::ast::Program {
+            statements: vec![Statement::Subroutine { visibility: None, name: "Main".into(), params: vec![], body: body.into() }],
+            diagnostics: vec![],
+        };
+        let executor = crate::vm::ProgramExecutor::new(program);
+
+        let _engine = crate::host::excel::engine::tests::lock_engine();
+        for engine in [ExecutionEngine::Vm, ExecutionEngine::Interpreter] {
+            let config = RuntimeConfig::builder().engine(engine).build();
+            let mut ctx = crate::host::excel::fixture::WorkbookFixture::new().context(config).unwrap();
+            executor.execute(&mut ctx).unwrap();
+
+            assert!(ctx.err.is_none(), "{:?}: {:?}", engine, ctx.err);
+            assert_eq!(ctx.output, ["6", "True", "-5", "5"], "{:?}", engine);
+            executor.shutdown(&mut ctx).unwrap();
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// src/host/ontime.rs
+// ============================================================================
+// ONTIME - Application.OnTime on a clock the embedder controls
+//
+// Nothing runs in the background. `Application.OnTime when, "Proc"` records
+// the procedure on the Context and tells the embedder's scheduler hook
+// (RuntimeConfig::on_schedule), if any. The procedures then run when the
+// embedder calls ProgramExecutor::run_due from its own timer, or when a test
+// moves the virtual clock forward with ProgramExecutor::advance_clock.
+// With a virtual clock (RuntimeConfig::virtual_clock) Now, Date, Time and
+// Timer read that clock instead of the system time.
+// `Schedule:=False` (the fourth argument) cancels a pending call.
+// ============================================================================
+
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+
+use crate::context::{Context, Value};
+use crate::symbol::lower;
+use crate::vba_errors::VbaErr;
+
+/// Told about every OnTime call; `schedule` is false for a cancellation
+pub type ScheduleHook = Arc<dyn Fn(&ScheduledProcedure, bool) + Send + Sync>;
+
+/// A procedure waiting for its time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledProcedure {
+    /// When it becomes due
+    pub at: NaiveDateTime,
+    /// Sub to run, as the macro named it
+    pub procedure: String,
+    /// Dropped instead of run if it cannot start by then (`LatestTime`)
+    pub latest: Option<NaiveDateTime>,
+}
+
+impl ScheduledProcedure {
+    /// Whether it would still start at `now`
+    pub fn can_run_at(&self, now: NaiveDateTime) -> bool {
+        self.latest.is_none_or(|latest| now <= latest)
+    }
+}
+
+/// How OnTime is clocked and who hears about it
+#[derive(Clone, Default)]
+pub struct ScheduleConfig {
+    /// Start of the virtual clock; None reads the system time
+    pub clock_start: Option<NaiveDateTime>,
+    pub hook: Option<ScheduleHook>,
+}
+
+impl fmt::Debug for ScheduleConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScheduleConfig")
+            .field("clock_start", &self.clock_start)
+            .field("hook", &self.hook.is_some())
+            .finish()
+    }
+}
+
+/// `Application.OnTime EarliestTime, Procedure, [LatestTime], [Schedule]`
+pub fn on_time(args: &[Value], ctx: &mut Context) -> Result<Value> {
+    if args.len() < 2 {
+        return Err(VbaErr::ArgumentNotOptional.error(""));
+    }
+    let at = to_datetime(&args[0], ctx)?;
+    let procedure = args[1].as_string();
+    let latest = match args.get(2) {
+        None | Some(Value::Empty) => None,
+        Some(value) => Some(to_datetime(value, ctx)?),
+    };
+    let schedule = match args.get(3) {
+        None | Some(Value::Empty) => true,
+        Some(value) => crate::interpreter::coerce::to_bool(value)?,
+    };
+    let entry = ScheduledProcedure { at, procedure, latest };
+
+    if schedule {
+        ctx.log(&format!("Application.OnTime {} at {}", entry.procedure, entry.at));
+        ctx.schedule_procedure(entry.clone());
+    } else {
+        // Excel cancels only an exact match of time and procedure
+        let found = ctx.cancel_scheduled(entry.at, &entry.procedure);
+        if !found {
+            return Err(VbaErr::ApplicationDefined.message("Method 'OnTime' of object '_Application' failed"));
+        }
+        ctx.log(&format!("Application.OnTime {} at {} cancelled", entry.procedure, entry.at));
+    }
+    if let Some(hook) = ctx.runtime_config.schedule.hook.clone() {
+        hook(&entry, schedule);
+    }
+    Ok(Value::Empty)
+}
+
+/// Whether two scheduled names refer to the same Sub (`"Module1.Tick"` = `"tick"`)
+pub(crate) fn same_procedure(a: &str, b: &str) -> bool {
+    lower(procedure_name(a)) == lower(procedure_name(b))
+}
+
+/// The Sub to call for a scheduled name, without module or quotes
+pub(crate) fn procedure_name(name: &str) -> &str {
+    let name = name.trim().trim_matches('\'');
+    name.rsplit_once('.').map_or(name, |(_, sub)| sub)
+}
+
+/// A time-only value means today; a number is an OLE date serial
+fn to_datetime(value: &Value, ctx: &Context) -> Result<NaiveDateTime> {
+    let at = match value {
+        Value::DateTime(dt) => *dt,
+        Value::Date(d) => d.and_hms_opt(0, 0, 0).unwrap(),
+        Value::Time(t) => ctx.now().date().and_time(*t),
+        Value::Object(Some(inner)) => return to_datetime(inner, ctx),
+        other => {
+            let serial = crate::interpreter::coerce::to_f64(other)
+                .map_err(|_| VbaErr::TypeMismatch.error(""))?;
+            let epoch = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap().and_hms_opt(0, 0, 0).unwrap();
+            let at = epoch + Duration::milliseconds((serial * 86_400_000.0).round() as i64);
+            if serial < 1.0 {
+                ctx.now().date().and_time(at.time())
+            } else {
+                at
+            }
+        }
+    };
+    Ok(at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{Expression, Program, Statement};
+    use crate::runtime_config::RuntimeConfig;
+    use crate::vm::ProgramExecutor;
+    use std::sync::Mutex;
+
+    fn on_time_call(args: Vec<Expression>) -> Statement {
+        let function = Expression::PropertyAccess {
+            obj: Box::new(Expression::Identifier("Application".into())),
+            property: "OnTime".into(),
+        };
+        Statement::Expression(Expression::FunctionCall { function: Box::new(function), args })
+    }
+
+    fn in_seconds(seconds: &str) -> Expression {
+        Expression::BinaryOp {
+            left: Box::new(Expression::FunctionCall { function: Box::new(Expression::Identifier("Now".into())), args: vec![] }),
+            op: "+".into(),
+            right: Box::new(Expression::FunctionCall {
+                function: Box::new(Expression::Identifier("TimeValue".into())),
+                args: vec![Expression::String(format!("00:00:{}", seconds))],
+            }),
+        }
+    }
+
+    #[test]
+    fn test_on_time_with_virtual_clock() {
+        let start = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap().and_hms_opt(9, 0, 0).unwrap();
+        let heard = Arc::new(Mutex::new(Vec::new()));
+        let log = heard.clone();
+        let hook: ScheduleHook = Arc::new(move |entry: &ScheduledProcedure, schedule: bool| {
+            log.lock().unwrap().push((entry.procedure.clone(), schedule));
+        });
+        let config = RuntimeConfig::builder().virtual_clock(start).on_schedule(hook).build();
+        let mut ctx = Context::with_config(config);
+        let executor = ProgramExecutor::new(Program { statements: vec![], diagnostics: vec![] });
+
+        // Tick schedules itself again, Report is cancelled before it runs
+        let tick = vec![on_time_call(vec![in_seconds("10"), Expression::String("Tick".into())])];
+        ctx.define_sub("Tick", vec![], tick.clone());
+        ctx.define_sub("Report", vec![], vec![]);
+        crate::interpreter::execute_statement_list(&tick, &mut ctx);
+        let report = vec![in_seconds("15"), Expression::String("Report".into()), in_seconds("59")];
+        crate::interpreter::execute_statement_list(&[on_time_call(report.clone())], &mut ctx);
+        let mut cancel = report;
+        cancel.push(Expression::Boolean(false));
+        crate::interpreter::execute_statement_list(&[on_time_call(cancel.clone())], &mut ctx);
+        assert!(ctx.err.is_none());
+        assert_eq!(ctx.scheduled().len(), 1);
+
+        let fired = executor.advance_clock(&mut ctx, Duration::seconds(25));
+        assert_eq!(fired, vec!["Tick", "Tick"]);
+        assert_eq!(ctx.now(), start + Duration::seconds(25));
+        assert_eq!(ctx.scheduled()[0].at, start + Duration::seconds(30));
+
+        // Nothing left to cancel: error 1004
+        crate::interpreter::execute_statement_list(&[on_time_call(cancel)], &mut ctx);
+        assert_eq!(ctx.err.as_ref().map(|e| e.number), Some(1004));
+
+        let heard = heard.lock().unwrap();
+        assert_eq!(heard.iter().filter(|(p, s)| p == "Tick" && *s).count(), 3);
+        assert_eq!(heard.iter().filter(|(p, s)| p == "Report" && !*s).count(), 1);
+    }
+}
@@ -0,0 +1,57 @@
+// src/host/outlook/mail.rs
+// ============================================================================
+// MAIL CAPTURE - MailItems created by the macro
+//
+// Items are kept for the whole run, including ones that were sent or
+// discarded, so the report shows everything the macro composed.
+// ============================================================================
+
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+/// A MailItem as composed by the macro
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MailItem {
+    pub id: usize,
+    pub to: String,
+    pub cc: String,
+    pub bcc: String,
+    pub subject: String,
+    pub body: String,
+    pub html_body: String,
+    /// Attachment sources (file paths) in the order they were added
+    pub attachments: Vec<String>,
+    /// MailItem.Send was called (nothing is actually sent)
+    pub sent: bool,
+    /// MailItem.Display was called
+    pub displayed: bool,
+}
+
+/// Mail items in creation order (id = position + 1)
+static MAIL_ITEMS: Lazy<Mutex<Vec<MailItem>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Forget all captured mail
+pub fn reset() {
+    MAIL_ITEMS.lock().unwrap().clear();
+}
+
+/// Application.CreateItem(olMailItem)
+pub fn create_mail_item() -> usize {
+    let mut items = MAIL_ITEMS.lock().unwrap();
+    let id = items.len() + 1;
+    items.push(MailItem { id, ..Default::default() });
+    id
+}
+
+pub fn mail_item(id: usize) -> Option<MailItem> {
+    MAIL_ITEMS.lock().unwrap().get(id.checked_sub(1)?).cloned()
+}
+
+/// Every captured item, in creation order
+pub fn mail_items() -> Vec<MailItem> {
+    MAIL_ITEMS.lock().unwrap().clone()
+}
+
+pub fn update_mail_item<T>(id: usize, f: impl FnOnce(&mut MailItem) -> T) -> Option<T> {
+    MAIL_ITEMS.lock().unwrap().get_mut(id.checked_sub(1)?).map(f)
+}
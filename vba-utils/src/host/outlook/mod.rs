@@ -0,0 +1,46 @@
+// src/host/outlook/mod.rs
+//
+// Outlook host: Application.CreateItem and MailItem objects, reached through
+// CreateObject("Outlook.Application") from either Excel or Word macros.
+// Mail is never sent; every item the macro creates is captured in `mail`
+// with its recipients, subject, body, attachments and whether Send was
+// called, so runs can report what a macro tried to mail.
+
+pub mod mail;
+pub mod objects;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{Context, Value};
+    use crate::host::ObjectRef;
+
+    #[test]
+    fn test_mail_item_capture() {
+        let mut ctx = Context::new();
+        let app = objects::application_object();
+        let app = ObjectRef::parse(&app).unwrap();
+        assert_eq!(app.get_property("Name", &mut ctx).unwrap().as_string(), "Outlook");
+
+        let item = ObjectRef::parse(&app.call_method("CreateItem", &[Value::Integer(0)], &mut ctx).unwrap()).unwrap();
+        item.set_property("To", Value::String("a@example.com; b@example.com".into()), &mut ctx).unwrap();
+        item.set_property("Subject", Value::String("Invoice".into()), &mut ctx).unwrap();
+        item.set_property("Body", Value::String("See attached".into()), &mut ctx).unwrap();
+        let attachments = ObjectRef::parse(&item.get_property("Attachments", &mut ctx).unwrap()).unwrap();
+        attachments.call_method("Add", &[Value::String("C:\\Temp\\invoice.xlsm".into())], &mut ctx).unwrap();
+        assert_eq!(attachments.get_property("Count", &mut ctx).unwrap().as_string(), "1");
+        item.call_method("Send", &[], &mut ctx).unwrap();
+
+        let id: usize = item.data.parse().unwrap();
+        let captured = mail::mail_items().into_iter().find(|m| m.id == id).unwrap();
+        assert_eq!(captured.to, "a@example.com; b@example.com");
+        assert_eq!(captured.subject, "Invoice");
+        assert_eq!(captured.attachments, vec!["C:\\Temp\\invoice.xlsm".to_string()]);
+        assert!(captured.sent);
+
+        // A sent item can no longer be changed
+        assert!(item.set_property("Subject", Value::String("Again".into()), &mut ctx).is_err());
+        // Only mail items can be created
+        assert!(app.call_method("CreateItem", &[Value::Integer(1)], &mut ctx).is_err());
+    }
+}
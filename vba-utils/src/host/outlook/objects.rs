@@ -0,0 +1,183 @@
+// src/host/outlook/objects.rs
+// Outlook object references and their property/method dispatch.
+//
+// Objects are "Outlook.<Type>:<data>" strings inside Value::Object:
+//   Outlook.Application:        Outlook.MailItem:<id>
+//   Outlook.Attachments:<id>    Outlook.Attachment:<id>:<index>
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::context::Value;
+use crate::host::excel::ExcelError;
+use crate::symbol::lower;
+use crate::vba_errors::VbaErr;
+
+use super::mail::{self, MailItem};
+
+/// OlItemType.olMailItem, the only item type CreateItem supports
+const OL_MAIL_ITEM: i64 = 0;
+
+fn object(reference: String) -> Value {
+    Value::Object(Some(Box::new(Value::String(reference))))
+}
+
+pub fn application_object() -> Value {
+    object("Outlook.Application:".into())
+}
+
+pub fn mail_item_object(id: usize) -> Value {
+    object(format!("Outlook.MailItem:{}", id))
+}
+
+fn attachments_object(id: usize) -> Value {
+    object(format!("Outlook.Attachments:{}", id))
+}
+
+fn attachment_object(id: usize, index: usize) -> Value {
+    object(format!("Outlook.Attachment:{}:{}", id, index))
+}
+
+fn mail_item(data: &str) -> Result<MailItem> {
+    data.parse()
+        .ok()
+        .and_then(mail::mail_item)
+        .ok_or_else(|| anyhow!("Invalid mail item reference: {}", data))
+}
+
+/// Apply a change to an unsent item; Outlook rejects changes after Send
+fn update_unsent(data: &str, f: impl FnOnce(&mut MailItem)) -> Result<()> {
+    let item = mail_item(data)?;
+    if item.sent {
+        return Err(ExcelError::raise(-2147221238, "The item has been moved or deleted."));
+    }
+    mail::update_mail_item(item.id, f);
+    Ok(())
+}
+
+// ============================================================================
+// PROPERTIES
+// ============================================================================
+
+/// Get a property of an Outlook object
+pub fn get_property(object_type: &str, data: &str, property: &str) -> Result<Value> {
+    match &*lower(object_type) {
+        "application" => match &*lower(property) {
+            "name" => Ok(Value::String("Outlook".into())),
+            "version" => Ok(Value::String("16.0".into())),
+            "application" => Ok(application_object()),
+            _ => bail!("Unknown Outlook Application property: {}", property),
+        },
+        "mailitem" => get_mail_item_property(data, property),
+        "attachments" => match &*lower(property) {
+            "count" => Ok(Value::Integer(mail_item(data)?.attachments.len() as i64)),
+            _ => bail!("Unknown Attachments property: {}", property),
+        },
+        "attachment" => {
+            let (id, index) = data.split_once(':').unwrap_or((data, "1"));
+            let item = mail_item(id)?;
+            let source = index
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| item.attachments.get(i.checked_sub(1)?))
+                .ok_or_else(|| anyhow!("Invalid attachment reference: {}", data))?;
+            match &*lower(property) {
+                "filename" | "displayname" => {
+                    let name = source.rsplit(['\\', '/']).next().unwrap_or(source);
+                    Ok(Value::String(name.to_string()))
+                }
+                "pathname" => Ok(Value::String(source.clone())),
+                _ => bail!("Unknown Attachment property: {}", property),
+            }
+        }
+        _ => bail!("Unknown Outlook object type: {}", object_type),
+    }
+}
+
+fn get_mail_item_property(data: &str, property: &str) -> Result<Value> {
+    let item = mail_item(data)?;
+    Ok(match &*lower(property) {
+        "to" => Value::String(item.to),
+        "cc" => Value::String(item.cc),
+        "bcc" => Value::String(item.bcc),
+        "subject" => Value::String(item.subject),
+        "body" => Value::String(item.body),
+        "htmlbody" => Value::String(item.html_body),
+        "sent" => Value::Boolean(item.sent),
+        "attachments" => attachments_object(item.id),
+        "application" => application_object(),
+        _ => bail!("Unknown MailItem property: {}", property),
+    })
+}
+
+/// Set a property of an Outlook object
+pub fn set_property(object_type: &str, data: &str, property: &str, value: Value) -> Result<()> {
+    if lower(object_type) != "mailitem" {
+        bail!("Cannot set {}.{}", object_type, property);
+    }
+    let text = value.as_string();
+    match &*lower(property) {
+        "to" => update_unsent(data, |m| m.to = text),
+        "cc" => update_unsent(data, |m| m.cc = text),
+        "bcc" => update_unsent(data, |m| m.bcc = text),
+        "subject" => update_unsent(data, |m| m.subject = text),
+        "body" => update_unsent(data, |m| m.body = text),
+        "htmlbody" => update_unsent(data, |m| m.html_body = text),
+        _ => bail!("Cannot set MailItem.{}", property),
+    }
+}
+
+// ============================================================================
+// METHODS
+// ============================================================================
+
+/// Call a method of an Outlook object
+pub fn call_method(object_type: &str, data: &str, method: &str, args: &[Value]) -> Result<Value> {
+    match &*lower(object_type) {
+        "application" => match &*lower(method) {
+            "createitem" => {
+                let item_type = args.first().and_then(Value::as_integer).unwrap_or(OL_MAIL_ITEM);
+                if item_type != OL_MAIL_ITEM {
+                    bail!("CreateItem: only olMailItem is supported, got {}", item_type);
+                }
+                Ok(mail_item_object(mail::create_mail_item()))
+            }
+            "quit" => Ok(Value::Empty),
+            _ => get_property(object_type, data, method),
+        },
+        "mailitem" => match &*lower(method) {
+            // Captured, never delivered
+            "send" => update_unsent(data, |m| m.sent = true).map(|_| Value::Empty),
+            "display" => {
+                let item = mail_item(data)?;
+                mail::update_mail_item(item.id, |m| m.displayed = true);
+                Ok(Value::Empty)
+            }
+            "save" | "close" => mail_item(data).map(|_| Value::Empty),
+            _ => get_mail_item_property(data, method),
+        },
+        "attachments" => match &*lower(method) {
+            "add" => {
+                let source = args
+                    .first()
+                    .map(Value::as_string)
+                    .ok_or_else(|| anyhow!("Attachments.Add requires a Source argument"))?;
+                let mut index = 0;
+                update_unsent(data, |m| {
+                    m.attachments.push(source);
+                    index = m.attachments.len();
+                })?;
+                Ok(attachment_object(mail_item(data)?.id, index))
+            }
+            "item" => {
+                let item = mail_item(data)?;
+                let index = args.first().and_then(Value::as_integer).unwrap_or(0);
+                match usize::try_from(index) {
+                    Ok(i) if (1..=item.attachments.len()).contains(&i) => Ok(attachment_object(item.id, i)),
+                    _ => Err(VbaErr::AutomationError.message("Array index out of bounds.")),
+                }
+            }
+            _ => get_property(object_type, data, method),
+        },
+        _ => get_property(object_type, data, method),
+    }
+}
@@ -0,0 +1,169 @@
+// src/host/stub.rs
+// ============================================================================
+// RECORDING STUB - Late-bound objects no host implements
+//
+// CreateObject/GetObject for an unknown ProgID (WScript.Shell,
+// Scripting.FileSystemObject, winmgmts:...) returns a stub that accepts any
+// property or method, records the access with its arguments and returns a
+// child stub, so chains like fso.CreateTextFile(p).WriteLine(s) keep
+// running. Stubs are "Stub.Object:<id>" references; the id maps to the
+// member path that produced the object.
+// ============================================================================
+
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+use anyhow::Result;
+
+use crate::context::{Context, Value};
+use crate::host::ComObject;
+
+/// How a stub member was accessed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StubAccess {
+    Get,
+    Let,
+    Call,
+}
+
+/// One recorded access on a stub object
+#[derive(Debug, Clone, PartialEq)]
+pub struct StubCall {
+    /// Path of the object the member was accessed on, e.g. "WScript.Shell"
+    pub object: String,
+    pub member: String,
+    pub access: StubAccess,
+    /// Arguments (or the assigned value for Let), formatted as VBA literals
+    pub args: Vec<String>,
+}
+
+impl StubCall {
+    /// `WScript.Shell.Run("calc", 0)` / `obj.Visible = True` / `obj.Name`
+    pub fn to_text(&self) -> String {
+        match self.access {
+            StubAccess::Get => format!("{}.{}", self.object, self.member),
+            StubAccess::Let => format!("{}.{} = {}", self.object, self.member, self.args.join(", ")),
+            StubAccess::Call => format!("{}.{}({})", self.object, self.member, self.args.join(", ")),
+        }
+    }
+}
+
+/// Member path of each stub object (id = position + 1)
+static STUB_OBJECTS: Lazy<Mutex<Vec<String>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Every member access on a stub, in call order
+static STUB_CALLS: Lazy<Mutex<Vec<StubCall>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Forget all stub objects and recorded calls
+pub fn reset() {
+    STUB_OBJECTS.lock().unwrap().clear();
+    STUB_CALLS.lock().unwrap().clear();
+}
+
+/// Root stub for a ProgID or moniker
+pub fn create(prog_id: &str) -> Value {
+    let mut objects = STUB_OBJECTS.lock().unwrap();
+    objects.push(prog_id.to_string());
+    Value::Object(Some(Box::new(Value::String(format!("Stub.Object:{}", objects.len())))))
+}
+
+/// Recorded accesses, in call order
+pub fn calls() -> Vec<StubCall> {
+    STUB_CALLS.lock().unwrap().clone()
+}
+
+pub(crate) fn literal(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("{:?}", s),
+        Value::Boolean(true) => "True".into(),
+        Value::Boolean(false) => "False".into(),
+        Value::Object(None) => "Nothing".into(),
+        Value::Object(Some(_)) => match crate::host::ObjectRef::parse(value) {
+            Some(object) if object.application == "stub" => RecordingStub::from_data(&object.data)
+                .map(|stub| stub.path())
+                .unwrap_or_else(|| "Object".into()),
+            _ => "Object".into(),
+        },
+        other => other.as_string(),
+    }
+}
+
+/// A stub object from the store, dispatched like any other COM object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordingStub {
+    pub id: usize,
+}
+
+impl RecordingStub {
+    /// Stub for the data part of a "Stub.Object:<id>" reference
+    pub fn from_data(data: &str) -> Option<Self> {
+        let id: usize = data.parse().ok()?;
+        (1..=STUB_OBJECTS.lock().unwrap().len()).contains(&id).then_some(Self { id })
+    }
+
+    /// Member path that produced this object, e.g. `WScript.Shell.Environment("Process")`
+    pub fn path(&self) -> String {
+        STUB_OBJECTS.lock().unwrap()[self.id - 1].clone()
+    }
+
+    fn record(&self, member: &str, access: StubAccess, args: &[Value], ctx: &mut Context) -> StubCall {
+        let call = StubCall {
+            object: self.path(),
+            member: member.to_string(),
+            access,
+            args: args.iter().map(literal).collect(),
+        };
+        ctx.log(&format!("COM stub: {}", call.to_text()));
+        STUB_CALLS.lock().unwrap().push(call.clone());
+        crate::host::dry_run::record_file_call(ctx, member, || call.to_text());
+        call
+    }
+}
+
+impl ComObject for RecordingStub {
+    fn get_property(&self, name: &str, ctx: &mut Context) -> Result<Value> {
+        let call = self.record(name, StubAccess::Get, &[], ctx);
+        Ok(create(&call.to_text()))
+    }
+
+    fn set_property(&mut self, name: &str, value: Value, ctx: &mut Context) -> Result<()> {
+        self.record(name, StubAccess::Let, &[value], ctx);
+        Ok(())
+    }
+
+    fn call_method(&mut self, name: &str, args: &[Value], ctx: &mut Context) -> Result<Value> {
+        let call = self.record(name, StubAccess::Call, args, ctx);
+        Ok(create(&call.to_text()))
+    }
+
+    fn type_name(&self) -> &str {
+        "Object"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::ObjectRef;
+
+    // The stub store is global, so the test only looks at its own objects
+    #[test]
+    fn test_records_call_chain() {
+        let mut ctx = Context::new();
+        let shell = ObjectRef::parse(&create("WScript.Shell")).unwrap();
+        let env = shell.call_method("Environment", &[Value::String("Process".into())], &mut ctx).unwrap();
+        let env = ObjectRef::parse(&env).unwrap();
+        env.set_property("Item", Value::Integer(1), &mut ctx).unwrap();
+        shell.call_method("Run", &[Value::String("calc".into()), Value::Integer(0), Value::Boolean(true)], &mut ctx).unwrap();
+
+        let texts: Vec<String> = calls()
+            .iter()
+            .filter(|c| c.object.starts_with("WScript.Shell"))
+            .map(StubCall::to_text)
+            .collect();
+        assert!(texts.contains(&"WScript.Shell.Environment(\"Process\")".to_string()));
+        assert!(texts.contains(&"WScript.Shell.Environment(\"Process\").Item = 1".to_string()));
+        assert!(texts.contains(&"WScript.Shell.Run(\"calc\", 0, True)".to_string()));
+        assert!(ctx.output.iter().any(|line| line == "COM stub: WScript.Shell.Run(\"calc\", 0, True)"));
+    }
+}
@@ -0,0 +1,215 @@
+// src/host/ui.rs
+// ============================================================================
+// HOST UI - Dialogs the macro shows the user
+//
+// Nobody is at the keyboard, so MsgBox and InputBox never block: the dialog
+// is handed to the HostUi on the RuntimeConfig, which picks the button or
+// types the answer. The default HeadlessUi presses the dialog's default
+// button (vbDefaultButton1..4), so `If MsgBox("Continue?", vbYesNo) = vbYes
+// Then` takes the Yes branch, and accepts InputBox's Default. Answers
+// scripted with RuntimeConfigBuilder::input_answer are used first, in
+// order. An embedder can pass its own HostUi to show real dialogs.
+// ============================================================================
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::context::Context;
+use crate::host::behavior::Interaction;
+use crate::interpreter::builtins::constants::{self, vbAbort, vbCancel, vbIgnore, vbNo, vbOK, vbRetry, vbYes};
+
+/// A MsgBox call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MsgBoxRequest {
+    pub prompt: String,
+    /// VbMsgBoxStyle: buttons + icon + default button + modality
+    pub buttons: i64,
+    pub title: Option<String>,
+}
+
+impl MsgBoxRequest {
+    /// vbOKOnly, vbOKCancel, ..., vbRetryCancel
+    pub fn button_set(&self) -> i64 {
+        self.buttons & 0x0F
+    }
+
+    /// vbCritical, vbQuestion, vbExclamation, vbInformation or 0
+    pub fn icon(&self) -> i64 {
+        self.buttons & 0x70
+    }
+
+    /// VbMsgBoxResult of each button shown, left to right
+    pub fn choices(&self) -> &'static [i64] {
+        match self.button_set() {
+            constants::vbOKCancel => &[vbOK, vbCancel],
+            constants::vbAbortRetryIgnore => &[vbAbort, vbRetry, vbIgnore],
+            constants::vbYesNoCancel => &[vbYes, vbNo, vbCancel],
+            constants::vbYesNo => &[vbYes, vbNo],
+            constants::vbRetryCancel => &[vbRetry, vbCancel],
+            _ => &[vbOK],
+        }
+    }
+
+    /// Result of the default button; vbDefaultButton2 on a two-button
+    /// dialog is the second, a default past the last button is the first
+    pub fn default_result(&self) -> i64 {
+        let choices = self.choices();
+        let index = ((self.buttons >> 8) & 0x03) as usize;
+        choices.get(index).copied().unwrap_or(choices[0])
+    }
+}
+
+/// An InputBox or Application.InputBox call
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct InputBoxRequest {
+    pub prompt: String,
+    pub title: Option<String>,
+    pub default: Option<String>,
+    /// Left edge, in twips (InputBox) or points (Application.InputBox)
+    pub xpos: Option<i64>,
+    /// Top edge, in the same unit as `xpos`
+    pub ypos: Option<i64>,
+    /// Application.InputBox `Type`: sum of 0 formula, 1 number, 2 text,
+    /// 4 boolean, 8 range, 16 error value, 64 array; None for the VBA
+    /// InputBox function, which always returns text
+    pub input_type: Option<i64>,
+}
+
+/// Answers the dialogs a macro shows
+pub trait HostUi: Send + Sync {
+    /// The VbMsgBoxResult of the button pressed; answers that are not one
+    /// of `request.choices()` are replaced by the default button
+    fn msg_box(&self, request: &MsgBoxRequest) -> i64 {
+        request.default_result()
+    }
+
+    /// The text entered, or None for Cancel
+    fn input_box(&self, request: &InputBoxRequest) -> Option<String> {
+        Some(request.default.clone().unwrap_or_default())
+    }
+
+    /// Carry out SendKeys, AppActivate, Beep or Application.Quit/Wait
+    /// under `InteractionPolicy::Delegate`; false refuses it (error 70)
+    fn interact(&self, _interaction: &Interaction) -> bool {
+        true
+    }
+}
+
+/// Presses the default button of every dialog
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeadlessUi;
+
+impl HostUi for HeadlessUi {}
+
+/// The HostUi a run uses, kept on the RuntimeConfig
+#[derive(Clone)]
+pub struct UiHandle(pub Arc<dyn HostUi>);
+
+impl Default for UiHandle {
+    fn default() -> Self {
+        UiHandle(Arc::new(HeadlessUi))
+    }
+}
+
+impl fmt::Debug for UiHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HostUi")
+    }
+}
+
+/// Show a MsgBox through the configured HostUi; returns the button pressed
+pub(crate) fn msg_box(ctx: &mut Context, request: MsgBoxRequest) -> i64 {
+    let ui = ctx.runtime_config.ui.0.clone();
+    let answer = ui.msg_box(&request);
+    if request.choices().contains(&answer) { answer } else { request.default_result() }
+}
+
+/// Show an InputBox: the next scripted answer, else the configured
+/// HostUi's; None for Cancel
+pub(crate) fn input_box(ctx: &mut Context, request: &InputBoxRequest) -> Option<String> {
+    if let Some(answer) = ctx.next_input_answer() {
+        return answer;
+    }
+    let ui = ctx.runtime_config.ui.0.clone();
+    ui.input_box(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{AssignmentTarget, Expression, Statement};
+    use crate::context::Value;
+    use crate::interpreter::builtins::constants::{vbDefaultButton2, vbInformation, vbOKCancel, vbQuestion, vbRetryCancel, vbYesNo, vbYesNoCancel};
+    use crate::runtime_config::RuntimeConfig;
+    use std::sync::Mutex;
+
+    /// Answers No to everything
+    struct SaysNo;
+
+    impl HostUi for SaysNo {
+        fn msg_box(&self, _request: &MsgBoxRequest) -> i64 {
+            vbNo
+        }
+    }
+
+    fn request(buttons: i64) -> MsgBoxRequest {
+        MsgBoxRequest { prompt: "Continue?".into(), buttons, title: None }
+    }
+
+    #[test]
+    fn test_default_button() {
+        assert_eq!(request(vbYesNoCancel + vbQuestion).default_result(), vbYes);
+        assert_eq!(request(vbYesNoCancel + vbQuestion).icon(), vbQuestion);
+        assert_eq!(request(vbYesNo + vbDefaultButton2).default_result(), vbNo);
+        assert_eq!(request(vbRetryCancel + 3 * vbDefaultButton2).default_result(), vbRetry);
+        assert_eq!(request(0).choices(), &[vbOK]);
+    }
+
+    #[test]
+    fn test_msgbox_function_asks_host_ui() {
+        let ask = |buttons: i64| Statement::Assignment {
+            lvalue: AssignmentTarget::Identifier("Answer".into()),
+            rvalue: Expression::FunctionCall {
+                function: Box::new(Expression::Identifier("MsgBox".into())),
+                args: vec![Expression::String("Continue?".into()), Expression::Integer(buttons), Expression::String("Title".into())],
+            },
+            rvalue_compiled: Default::default(),
+        };
+
+        let mut ctx = Context::new();
+        crate::interpreter::execute_statement_list(&[ask(vbYesNoCancel + vbQuestion)], &mut ctx);
+        assert!(matches!(ctx.get_var("Answer"), Some(Value::Integer(n)) if n == vbYes));
+
+        let config = RuntimeConfig::builder().ui(Arc::new(SaysNo)).build();
+        let mut ctx = Context::with_config(config);
+        crate::interpreter::execute_statement_list(&[ask(vbYesNo)], &mut ctx);
+        assert!(matches!(ctx.get_var("Answer"), Some(Value::Integer(n)) if n == vbNo));
+        // No is not a button of vbOKCancel
+        crate::interpreter::execute_statement_list(&[ask(vbOKCancel)], &mut ctx);
+        assert!(matches!(ctx.get_var("Answer"), Some(Value::Integer(n)) if n == vbOK));
+    }
+
+    #[test]
+    fn test_msgbox_statement_passes_buttons_and_title() {
+        struct Recorder(Mutex<Vec<MsgBoxRequest>>);
+
+        impl HostUi for Recorder {
+            fn msg_box(&self, request: &MsgBoxRequest) -> i64 {
+                self.0.lock().unwrap().push(request.clone());
+                vbOK
+            }
+        }
+
+        let statement = Statement::MsgBox {
+            expr: Expression::String("Done".into()),
+            buttons: Some(Expression::Integer(vbInformation)),
+            title: Some(Expression::String("Report".into())),
+        };
+        let ui = Arc::new(Recorder(Mutex::new(Vec::new())));
+        let mut ctx = Context::with_config(RuntimeConfig::builder().ui(ui.clone()).build());
+        crate::interpreter::execute_statement_list(&[statement], &mut ctx);
+
+        let seen = ui.0.lock().unwrap();
+        assert_eq!(*seen, vec![MsgBoxRequest { prompt: "Done".into(), buttons: vbInformation, title: Some("Report".into()) }]);
+    }
+}
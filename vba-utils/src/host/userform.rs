@@ -0,0 +1,292 @@
+// src/host/userform.rs
+// ============================================================================
+// USERFORMS - Headless forms with embedder-supplied control values
+//
+// Nothing is drawn. The embedder registers each form the macro uses
+// (RuntimeConfig::user_form) with the control values a user would have
+// entered, and optionally a handler that runs when the macro calls .Show
+// and fills the form in at that point (RuntimeConfig::on_show). Forms are
+// "UserForm.Form:<name>" references and controls
+// "UserForm.Control:<form>!<control>"; a control the embedder did not list
+// starts out Empty. Control values live for one run and can be read back
+// with forms() afterwards.
+// ============================================================================
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{bail, Result};
+use once_cell::sync::Lazy;
+
+use crate::ast::Expression;
+use crate::context::{Context, Value};
+use crate::symbol::lower;
+
+/// Runs when the macro shows a form; fills in controls like a user would
+pub type ShowHandler = Arc<dyn Fn(&mut UserForm) -> Result<()> + Send + Sync>;
+
+/// A form and its control values
+#[derive(Debug, Clone, Default)]
+pub struct UserForm {
+    pub name: String,
+    pub caption: String,
+    /// Property values by control, both keyed lowercase
+    controls: HashMap<String, HashMap<String, Value>>,
+    /// Times .Show was called this run
+    pub shown: usize,
+}
+
+impl UserForm {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), caption: name.to_string(), ..Self::default() }
+    }
+
+    /// Pre-populate `control.Value`
+    pub fn value(mut self, control: &str, value: Value) -> Self {
+        self.set(control, "Value", value);
+        self
+    }
+
+    /// Set any control property (Value, Text, Caption, ListIndex, ...)
+    pub fn set(&mut self, control: &str, property: &str, value: Value) {
+        let properties = self.controls.entry(lower(control).to_string()).or_default();
+        // A TextBox's Text and Value are the same string
+        if matches!(&*lower(property), "value" | "text") {
+            properties.insert("value".into(), value.clone());
+            properties.insert("text".into(), value);
+        } else {
+            properties.insert(lower(property).to_string(), value);
+        }
+    }
+
+    /// A control property; Empty if nothing set it
+    pub fn get(&self, control: &str, property: &str) -> Value {
+        let value = self.controls.get(&*lower(control)).and_then(|p| p.get(&*lower(property)));
+        match (value, &*lower(property)) {
+            (Some(value), _) => value.clone(),
+            (None, "enabled" | "visible") => Value::Boolean(true),
+            (None, "name") => Value::String(control.to_string()),
+            (None, _) => Value::Empty,
+        }
+    }
+}
+
+/// Forms the embedder provides, keyed by name (case-insensitive)
+#[derive(Clone, Default)]
+pub struct UserFormRegistry {
+    forms: HashMap<String, UserForm>,
+    on_show: HashMap<String, ShowHandler>,
+}
+
+impl UserFormRegistry {
+    pub fn register(&mut self, form: UserForm) {
+        self.forms.insert(lower(&form.name).to_string(), form);
+    }
+
+    /// Run `handler` whenever the macro shows `form` (registering it if needed)
+    pub fn on_show(&mut self, form: &str, handler: ShowHandler) {
+        self.forms.entry(lower(form).to_string()).or_insert_with(|| UserForm::new(form));
+        self.on_show.insert(lower(form).to_string(), handler);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&UserForm> {
+        self.forms.get(&*lower(name))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.forms.is_empty()
+    }
+}
+
+impl fmt::Debug for UserFormRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<&String> = self.forms.keys().collect();
+        names.sort();
+        f.debug_struct("UserFormRegistry").field("forms", &names).finish()
+    }
+}
+
+/// Forms the current run has touched, keyed lowercase
+static FORMS: Lazy<Mutex<HashMap<String, UserForm>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Forget control values from the last run
+pub fn reset() {
+    FORMS.lock().unwrap().clear();
+}
+
+/// Forms as the run left them, sorted by name
+pub fn forms() -> Vec<UserForm> {
+    let mut forms: Vec<UserForm> = FORMS.lock().unwrap().values().cloned().collect();
+    forms.sort_by(|a, b| a.name.cmp(&b.name));
+    forms
+}
+
+/// The run's copy of a registered form, loaded from the RuntimeConfig on first use
+fn with_form<T>(ctx: &Context, name: &str, f: impl FnOnce(&mut UserForm) -> T) -> Option<T> {
+    let mut forms = FORMS.lock().unwrap();
+    if !forms.contains_key(&*lower(name)) {
+        let form = ctx.runtime_config.user_forms.get(name)?.clone();
+        forms.insert(lower(name).to_string(), form);
+    }
+    forms.get_mut(&*lower(name)).map(f)
+}
+
+/// `UserForm1` when the embedder registered it
+pub fn global_object(ctx: &Context, name: &str) -> Option<Value> {
+    ctx.runtime_config.user_forms.get(name)?;
+    Some(form_object(name))
+}
+
+/// Whether `UserForm1.TextBox1...` starts at a registered form
+pub fn is_form_expression(ctx: &Context, expr: &Expression) -> bool {
+    match expr {
+        Expression::Identifier(name) => ctx.get_var(name).is_none() && global_object(ctx, name).is_some(),
+        Expression::PropertyAccess { obj, .. } => is_form_expression(ctx, obj),
+        Expression::FunctionCall { function, .. } => is_form_expression(ctx, function),
+        _ => false,
+    }
+}
+
+fn form_object(name: &str) -> Value {
+    Value::Object(Some(Box::new(Value::String(format!("UserForm.Form:{}", name)))))
+}
+
+fn control_object(form: &str, control: &str) -> Value {
+    Value::Object(Some(Box::new(Value::String(format!("UserForm.Control:{}!{}", form, control)))))
+}
+
+fn form_name<'a>(object_type: &str, data: &'a str) -> &'a str {
+    match &*lower(object_type) {
+        "control" => data.split_once('!').map_or(data, |(form, _)| form),
+        _ => data,
+    }
+}
+
+fn control_name(data: &str) -> &str {
+    data.split_once('!').map_or("", |(_, control)| control)
+}
+
+pub fn get_property(object_type: &str, data: &str, property: &str, ctx: &mut Context) -> Result<Value> {
+    let form = form_name(object_type, data);
+    let value = with_form(ctx, form, |f| match (&*lower(object_type), &*lower(property)) {
+        ("control", _) => Ok(f.get(control_name(data), property)),
+        ("form", "caption") => Ok(Value::String(f.caption.clone())),
+        ("form", "name") => Ok(Value::String(f.name.clone())),
+        ("form", "visible") => Ok(Value::Boolean(false)),
+        // Methods: let the caller fall back to call_method
+        ("form", "show" | "hide" | "repaint" | "setfocus" | "controls") => {
+            bail!("{} is a method of {}", property, form)
+        }
+        ("form", _) => Ok(control_object(form, property)),
+        _ => bail!("Unknown UserForm object type: {}", object_type),
+    });
+    value.unwrap_or_else(|| bail!("UserForm {} is not registered", form))
+}
+
+pub fn set_property(object_type: &str, data: &str, property: &str, value: Value, ctx: &mut Context) -> Result<()> {
+    let form = form_name(object_type, data);
+    ctx.log(&format!("{}.{} = {}", data.replace('!', "."), property, value.as_string()));
+    let result = with_form(ctx, form, |f| match &*lower(object_type) {
+        "control" => {
+            f.set(control_name(data), property, value);
+            Ok(())
+        }
+        _ if lower(property) == "caption" => {
+            f.caption = value.as_string();
+            Ok(())
+        }
+        _ => bail!("Cannot set {}.{}", form, property),
+    });
+    result.unwrap_or_else(|| bail!("UserForm {} is not registered", form))
+}
+
+pub fn call_method(object_type: &str, data: &str, method: &str, args: &[Value], ctx: &mut Context) -> Result<Value> {
+    let form = form_name(object_type, data);
+    match (&*lower(object_type), &*lower(method)) {
+        ("form", "show") => show(form, ctx),
+        ("form", "hide" | "repaint") | ("control", "setfocus") => {
+            ctx.log(&format!("{}.{}", data.replace('!', "."), method));
+            Ok(Value::Empty)
+        }
+        ("form", "controls") => {
+            let control = args.first().map(Value::as_string).unwrap_or_default();
+            Ok(control_object(form, &control))
+        }
+        _ => bail!("Unknown UserForm method: {}", method),
+    }
+}
+
+/// Modal .Show: the embedder's handler plays the user, then the form closes
+fn show(form: &str, ctx: &mut Context) -> Result<Value> {
+    let handler = ctx.runtime_config.user_forms.on_show.get(&*lower(form)).cloned();
+    let mut snapshot = with_form(ctx, form, |f| {
+        f.shown += 1;
+        f.clone()
+    })
+    .ok_or_else(|| anyhow::anyhow!("UserForm {} is not registered", form))?;
+    ctx.log(&format!("{}.Show ({})", form, if handler.is_some() { "handler" } else { "pre-populated" }));
+    if let Some(handler) = handler {
+        handler(&mut snapshot)?;
+        FORMS.lock().unwrap().insert(lower(form).to_string(), snapshot);
+    }
+    Ok(Value::Empty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{AssignmentTarget, Statement};
+    use crate::runtime_config::RuntimeConfig;
+
+    fn member(path: &[&str]) -> Expression {
+        let mut expr = Expression::Identifier(path[0].into());
+        for property in &path[1..] {
+            expr = Expression::PropertyAccess { obj: Box::new(expr), property: property.to_string() };
+        }
+        expr
+    }
+
+    fn assign(name: &str, rvalue: Expression) -> Statement {
+        Statement::Assignment {
+            lvalue: AssignmentTarget::Identifier(name.into()),
+            rvalue,
+            rvalue_compiled: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_prepopulated_values_and_show_handler() {
+        let body = [
+            assign("FormTestName", member(&["FormTestDialog", "txtName", "Value"])),
+            Statement::Expression(member(&["FormTestDialog", "Show"])),
+            assign("FormTestAge", member(&["FormTestDialog", "txtAge", "Text"])),
+            Statement::Assignment {
+                lvalue: AssignmentTarget::PropertyAccess {
+                    object: Box::new(member(&["FormTestDialog", "lblStatus"])),
+                    property: "Caption".into(),
+                },
+                rvalue: Expression::String("Saved".into()),
+                rvalue_compiled: Default::default(),
+            },
+        ];
+        let handler: ShowHandler = Arc::new(|form: &mut UserForm| {
+            form.set("txtAge", "Value", Value::String("42".into()));
+            Ok(())
+        });
+        let config = RuntimeConfig::builder()
+            .user_form(UserForm::new("FormTestDialog").value("txtName", Value::String("Ada".into())))
+            .on_show("FormTestDialog", handler)
+            .build();
+        let mut ctx = Context::with_config(config);
+        crate::interpreter::execute_statement_list(&body, &mut ctx);
+        assert!(ctx.err.is_none());
+        assert_eq!(ctx.get_var("FormTestName").unwrap().as_string(), "Ada");
+        assert_eq!(ctx.get_var("FormTestAge").unwrap().as_string(), "42");
+
+        let form = forms().into_iter().find(|f| f.name == "FormTestDialog").unwrap();
+        assert_eq!(form.shown, 1);
+        assert_eq!(form.get("lblStatus", "Caption").as_string(), "Saved");
+        assert_eq!(form.get("txtName", "Text").as_string(), "Ada");
+    }
+}
@@ -0,0 +1,189 @@
+// src/host/vb.rs
+// ============================================================================
+// VB GLOBALS - Clipboard, Printer and Screen
+//
+// Ported VB6-style code touches these objects without creating them. The
+// clipboard is a text buffer shared with the embedder (clipboard_text /
+// set_clipboard_text) and is not cleared between runs, so it can be primed
+// before a macro starts. Printer output is logged and dropped; Screen
+// reports a fixed 1920x1080 display. Properties assigned on Printer and
+// Screen read back until the next run.
+// ============================================================================
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use anyhow::{bail, Result};
+use once_cell::sync::Lazy;
+
+use crate::context::{Context, Value};
+use crate::symbol::lower;
+
+/// vbCFText
+const CF_TEXT: i64 = 1;
+
+/// Twips per pixel at 96 DPI
+const TWIPS_PER_PIXEL: i64 = 15;
+
+/// Text on the emulated clipboard
+static CLIPBOARD: Lazy<Mutex<String>> = Lazy::new(|| Mutex::new(String::new()));
+
+/// Printer and Screen properties assigned by the macro, keyed "printer.copies"
+static ASSIGNED: Lazy<Mutex<HashMap<String, Value>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Text the macro last put on the clipboard (or the embedder primed it with)
+pub fn clipboard_text() -> String {
+    CLIPBOARD.lock().unwrap().clone()
+}
+
+/// Prime the clipboard before a run
+pub fn set_clipboard_text(text: impl Into<String>) {
+    *CLIPBOARD.lock().unwrap() = text.into();
+}
+
+/// Forget Printer and Screen properties assigned by the last run
+pub fn reset() {
+    ASSIGNED.lock().unwrap().clear();
+}
+
+/// `Clipboard`, `Printer` and `Screen`; None for any other name
+pub fn global_object(name: &str) -> Option<Value> {
+    let object_type = match &*lower(name) {
+        "clipboard" => "Clipboard",
+        "printer" => "Printer",
+        "screen" => "Screen",
+        _ => return None,
+    };
+    Some(Value::Object(Some(Box::new(Value::String(format!("VB.{}:", object_type))))))
+}
+
+pub fn get_property(object_type: &str, property: &str) -> Result<Value> {
+    let key = format!("{}.{}", lower(object_type), lower(property));
+    if let Some(value) = ASSIGNED.lock().unwrap().get(&key) {
+        return Ok(value.clone());
+    }
+    let value = match (&*lower(object_type), &*lower(property)) {
+        ("clipboard", "gettext") => Value::String(clipboard_text()),
+        ("printer", "devicename") => Value::String("Null Printer".into()),
+        ("printer", "drivername") => Value::String("winspool".into()),
+        ("printer", "port") => Value::String("nul:".into()),
+        ("printer", "copies") | ("printer", "orientation") | ("printer", "page") => Value::Integer(1),
+        ("printer", "width") => Value::Integer(12240),
+        ("printer", "height") => Value::Integer(15840),
+        ("printer", "scalewidth") | ("printer", "scaleheight") | ("printer", "currentx") | ("printer", "currenty") => {
+            Value::Integer(0)
+        }
+        ("printer", "fontname") => Value::String("Arial".into()),
+        ("printer", "fontsize") => Value::Integer(10),
+        ("screen", "width") => Value::Integer(1920 * TWIPS_PER_PIXEL),
+        ("screen", "height") => Value::Integer(1080 * TWIPS_PER_PIXEL),
+        ("screen", "twipsperpixelx") | ("screen", "twipsperpixely") => Value::Integer(TWIPS_PER_PIXEL),
+        ("screen", "mousepointer") => Value::Integer(0),
+        ("screen", "fontcount") => Value::Integer(1),
+        ("screen", "activeform") | ("screen", "activecontrol") => Value::Object(None),
+        _ => bail!("Unknown {} property: {}", object_type, property),
+    };
+    Ok(value)
+}
+
+pub fn set_property(object_type: &str, property: &str, value: Value, ctx: &mut Context) -> Result<()> {
+    let writable = match &*lower(object_type) {
+        "printer" => true,
+        "screen" => lower(property) == "mousepointer",
+        _ => false,
+    };
+    if !writable {
+        bail!("Cannot set {}.{}", object_type, property);
+    }
+    ctx.log(&format!("{}.{} = {}", object_type, property, value.as_string()));
+    ASSIGNED.lock().unwrap().insert(format!("{}.{}", lower(object_type), lower(property)), value);
+    Ok(())
+}
+
+pub fn call_method(object_type: &str, method: &str, args: &[Value], ctx: &mut Context) -> Result<Value> {
+    match (&*lower(object_type), &*lower(method)) {
+        ("clipboard", "settext") => {
+            let text = args.first().map(Value::as_string).unwrap_or_default();
+            set_clipboard_text(text);
+            Ok(Value::Empty)
+        }
+        ("clipboard", "gettext") => Ok(Value::String(clipboard_text())),
+        ("clipboard", "clear") => {
+            set_clipboard_text("");
+            Ok(Value::Empty)
+        }
+        ("clipboard", "getformat") => {
+            let format = args.first().map(|v| v.as_string().parse::<i64>().unwrap_or(0)).unwrap_or(CF_TEXT);
+            Ok(Value::Boolean(format == CF_TEXT && !clipboard_text().is_empty()))
+        }
+        ("printer", "print") => {
+            let line: Vec<String> = args.iter().map(Value::as_string).collect();
+            ctx.log(&format!("Printer.Print {}", line.join(" ")));
+            Ok(Value::Empty)
+        }
+        ("printer", "newpage" | "enddoc" | "killdoc" | "line" | "circle" | "pset" | "paintpicture") => {
+            ctx.log(&format!("Printer.{} ignored", method));
+            Ok(Value::Empty)
+        }
+        ("printer", "textwidth") => {
+            let text = args.first().map(Value::as_string).unwrap_or_default();
+            Ok(Value::Integer(text.chars().count() as i64 * 120))
+        }
+        ("printer", "textheight") => Ok(Value::Integer(240)),
+        _ => bail!("Unknown {} method: {}", object_type, method),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{AssignmentTarget, Expression, Statement};
+
+    fn member(object: &str, name: &str) -> Expression {
+        Expression::PropertyAccess { obj: Box::new(Expression::Identifier(object.into())), property: name.into() }
+    }
+
+    fn call(object: &str, method: &str, args: Vec<Expression>) -> Expression {
+        Expression::FunctionCall { function: Box::new(member(object, method)), args }
+    }
+
+    fn assign(name: &str, rvalue: Expression) -> Statement {
+        Statement::Assignment {
+            lvalue: AssignmentTarget::Identifier(name.into()),
+            rvalue,
+            rvalue_compiled: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_clipboard_printer_and_screen() {
+        let body = [
+            Statement::Expression(call("Clipboard", "SetText", vec![Expression::String("copied".into())])),
+            assign("VbTestPasted", call("Clipboard", "GetText", vec![])),
+            Statement::Expression(call("Printer", "Print", vec![Expression::String("page one".into())])),
+            Statement::Expression(call("Printer", "EndDoc", vec![])),
+            assign("VbTestWidth", member("Screen", "Width")),
+            assign("VbTestTwips", member("Screen", "TwipsPerPixelX")),
+            Statement::Assignment {
+                lvalue: AssignmentTarget::PropertyAccess {
+                    object: Box::new(Expression::Identifier("Screen".into())),
+                    property: "MousePointer".into(),
+                },
+                rvalue: Expression::Integer(11),
+                rvalue_compiled: Default::default(),
+            },
+            assign("VbTestPointer", member("Screen", "MousePointer")),
+        ];
+        let mut ctx = Context::new();
+        crate::interpreter::execute_statement_list(&body, &mut ctx);
+        assert!(ctx.err.is_none());
+        assert_eq!(ctx.get_var("VbTestPasted").unwrap().as_string(), "copied");
+        assert_eq!(ctx.get_var("VbTestWidth").unwrap().as_string(), "28800");
+        assert_eq!(ctx.get_var("VbTestTwips").unwrap().as_string(), "15");
+        assert_eq!(ctx.get_var("VbTestPointer").unwrap().as_string(), "11");
+        assert_eq!(clipboard_text(), "copied");
+
+        set_clipboard_text("from embedder");
+        assert_eq!(get_property("Clipboard", "GetText").unwrap().as_string(), "from embedder");
+    }
+}
@@ -0,0 +1,250 @@
+// src/host/word/document.rs
+// ============================================================================
+// DOCUMENT STORE - In-memory Word documents
+//
+// Documents keep their text as a plain string; positions (Range.Start,
+// Range.End, Selection) are character offsets into it, as in Word. There is
+// no trailing paragraph mark, so a new document's Content.Text is "".
+// Documents are identified by a stable id so "Word.Document:<id>" references
+// stay valid when other documents are closed.
+// ============================================================================
+
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+/// An open (or closed, for reporting) Word document
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordDocument {
+    pub id: usize,
+    pub name: String,
+    /// Folder the document was opened from or saved to ("" for new documents)
+    pub path: String,
+    pub text: String,
+    pub saved: bool,
+    /// Selection as (start, end) character offsets
+    pub selection: (usize, usize),
+    pub closed: bool,
+}
+
+impl WordDocument {
+    /// Path and name, or just the name for unsaved documents
+    pub fn full_name(&self) -> String {
+        if self.path.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}\\{}", self.path, self.name)
+        }
+    }
+
+    /// Number of characters in the document
+    pub fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+}
+
+/// Application-level Word settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordSettings {
+    pub visible: bool,
+    pub display_alerts: bool,
+    pub screen_updating: bool,
+}
+
+impl Default for WordSettings {
+    fn default() -> Self {
+        Self { visible: true, display_alerts: true, screen_updating: true }
+    }
+}
+
+#[derive(Debug, Default)]
+struct WordState {
+    /// Every document created in this session, in creation order
+    documents: Vec<WordDocument>,
+    active: Option<usize>,
+    /// Document holding the running macro (ThisDocument)
+    this_document: Option<usize>,
+    /// Counter for "Document<n>" names
+    untitled: usize,
+    settings: WordSettings,
+}
+
+static WORD_STATE: Lazy<Mutex<WordState>> = Lazy::new(|| Mutex::new(WordState::default()));
+
+/// Clear all documents and settings
+pub fn reset() {
+    *WORD_STATE.lock().unwrap() = WordState::default();
+}
+
+fn new_document(state: &mut WordState, name: String, path: String) -> usize {
+    let id = state.documents.len() + 1;
+    state.documents.push(WordDocument {
+        id,
+        name,
+        path,
+        text: String::new(),
+        saved: true,
+        selection: (0, 0),
+        closed: false,
+    });
+    state.active = Some(id);
+    id
+}
+
+/// Documents.Add: a new blank document named "Document<n>", made active
+pub fn add_document() -> usize {
+    let mut state = WORD_STATE.lock().unwrap();
+    state.untitled += 1;
+    let name = format!("Document{}", state.untitled);
+    new_document(&mut state, name, String::new())
+}
+
+/// Documents.Open: the file is not read, the document starts empty
+pub fn open_document(file_name: &str) -> usize {
+    let (path, name) = match file_name.rfind(['\\', '/']) {
+        Some(i) => (&file_name[..i], &file_name[i + 1..]),
+        None => ("", file_name),
+    };
+    let mut state = WORD_STATE.lock().unwrap();
+    new_document(&mut state, name.to_string(), path.to_string())
+}
+
+/// Create the document the macro runs in and make it ThisDocument
+pub fn init_this_document() -> usize {
+    let id = add_document();
+    WORD_STATE.lock().unwrap().this_document = Some(id);
+    id
+}
+
+pub fn this_document() -> Option<usize> {
+    WORD_STATE.lock().unwrap().this_document
+}
+
+/// Ids of open documents, in Documents collection order
+pub fn open_documents() -> Vec<usize> {
+    let state = WORD_STATE.lock().unwrap();
+    state.documents.iter().filter(|d| !d.closed).map(|d| d.id).collect()
+}
+
+/// Every document of the session, including closed ones
+pub fn all_documents() -> Vec<WordDocument> {
+    WORD_STATE.lock().unwrap().documents.clone()
+}
+
+/// Look up an open document by name (case-insensitive)
+pub fn find_document(name: &str) -> Option<usize> {
+    let state = WORD_STATE.lock().unwrap();
+    state
+        .documents
+        .iter()
+        .find(|d| !d.closed && (d.name.eq_ignore_ascii_case(name) || d.full_name().eq_ignore_ascii_case(name)))
+        .map(|d| d.id)
+}
+
+pub fn document(id: usize) -> Option<WordDocument> {
+    let state = WORD_STATE.lock().unwrap();
+    state.documents.get(id.checked_sub(1)?).filter(|d| !d.closed).cloned()
+}
+
+pub fn active_document() -> Option<usize> {
+    WORD_STATE.lock().unwrap().active
+}
+
+pub fn activate_document(id: usize) {
+    let mut state = WORD_STATE.lock().unwrap();
+    if state.documents.get(id.wrapping_sub(1)).is_some_and(|d| !d.closed) {
+        state.active = Some(id);
+    }
+}
+
+/// Close a document; the most recently opened remaining one becomes active
+pub fn close_document(id: usize) {
+    let mut state = WORD_STATE.lock().unwrap();
+    if let Some(doc) = state.documents.get_mut(id.wrapping_sub(1)) {
+        doc.closed = true;
+    }
+    if state.active == Some(id) {
+        state.active = state.documents.iter().rev().find(|d| !d.closed).map(|d| d.id);
+    }
+}
+
+fn with_document<T>(id: usize, f: impl FnOnce(&mut WordDocument) -> T) -> Option<T> {
+    let mut state = WORD_STATE.lock().unwrap();
+    state.documents.get_mut(id.checked_sub(1)?).filter(|d| !d.closed).map(f)
+}
+
+/// Byte offset of a character position, clamped to the text
+fn byte_offset(text: &str, chars: usize) -> usize {
+    text.char_indices().nth(chars).map(|(i, _)| i).unwrap_or(text.len())
+}
+
+/// Text between two character positions
+pub fn get_text(id: usize, start: usize, end: usize) -> Option<String> {
+    with_document(id, |doc| {
+        let (s, e) = (byte_offset(&doc.text, start), byte_offset(&doc.text, end.max(start)));
+        doc.text[s..e].to_string()
+    })
+}
+
+/// Replace the characters between `start` and `end` with `text`.
+/// Returns the (start, end) of the inserted text.
+pub fn replace_text(id: usize, start: usize, end: usize, text: &str) -> Option<(usize, usize)> {
+    with_document(id, |doc| {
+        let len = doc.len();
+        let (start, end) = (start.min(len), end.clamp(start.min(len), len));
+        let (s, e) = (byte_offset(&doc.text, start), byte_offset(&doc.text, end));
+        doc.text.replace_range(s..e, text);
+        doc.saved = false;
+        let inserted = text.chars().count();
+        // Keep the selection on the same text where possible
+        let removed = end - start;
+        let shift = |pos: usize| {
+            if pos >= end {
+                pos + inserted - removed
+            } else {
+                pos.min(start)
+            }
+        };
+        doc.selection = (shift(doc.selection.0), shift(doc.selection.1));
+        (start, start + inserted)
+    })
+}
+
+pub fn selection(id: usize) -> Option<(usize, usize)> {
+    with_document(id, |doc| doc.selection)
+}
+
+pub fn set_selection(id: usize, start: usize, end: usize) {
+    with_document(id, |doc| {
+        let len = doc.len();
+        doc.selection = (start.min(len), end.clamp(start.min(len), len));
+    });
+}
+
+pub fn set_saved(id: usize, saved: bool) {
+    with_document(id, |doc| doc.saved = saved);
+}
+
+/// Document.SaveAs: rename the document to the new file
+pub fn save_as(id: usize, file_name: &str) {
+    let (path, name) = match file_name.rfind(['\\', '/']) {
+        Some(i) => (&file_name[..i], &file_name[i + 1..]),
+        None => ("", file_name),
+    };
+    with_document(id, |doc| {
+        doc.path = path.to_string();
+        doc.name = name.to_string();
+        doc.saved = true;
+    });
+}
+
+pub fn settings() -> WordSettings {
+    WORD_STATE.lock().unwrap().settings.clone()
+}
+
+pub fn update_settings(f: impl FnOnce(&mut WordSettings)) {
+    f(&mut WORD_STATE.lock().unwrap().settings);
+}
@@ -0,0 +1,210 @@
+// src/host/word/mod.rs
+//
+// Word host: Application, Documents, Document, Selection and Range objects
+// over an in-memory document store, so Word macros (AutoOpen,
+// Document_Open) can run without Word.
+
+pub mod document;
+pub mod objects;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use anyhow::Result;
+
+use crate::context::{Context, Value};
+use crate::host::{ComObject, ComObjectHandle};
+use crate::runtime_config::HostApplication;
+
+/// Word.Application registered as the "Application" global
+#[derive(Debug, Default)]
+pub struct WordApplication;
+
+impl ComObject for WordApplication {
+    fn get_property(&self, name: &str, _ctx: &mut Context) -> Result<Value> {
+        objects::get_property("application", "", name)
+    }
+
+    fn set_property(&mut self, name: &str, value: Value, _ctx: &mut Context) -> Result<()> {
+        objects::set_property("application", "", name, value)
+    }
+
+    fn call_method(&mut self, name: &str, args: &[Value], _ctx: &mut Context) -> Result<Value> {
+        objects::call_method("application", "", name, args)
+    }
+
+    fn type_name(&self) -> &str {
+        "Word.Application"
+    }
+
+    fn default_member(&self) -> Option<&str> {
+        Some("Name")
+    }
+}
+
+/// Initialize the Word host: a fresh document store holding the macro's own
+/// document (ThisDocument, also the active document) and the Application global.
+pub fn initialize_word_host(ctx: &mut Context) {
+    document::reset();
+    document::init_this_document();
+
+    let app: ComObjectHandle = Rc::new(RefCell::new(WordApplication));
+    ctx.com_registry.register_global("Application", app);
+}
+
+/// Counterpart of [`initialize_word_host`]: closes every document and
+/// unregisters `Application`
+pub fn shutdown_word_host(ctx: &mut Context) {
+    document::reset();
+    ctx.com_registry.unregister_global("Application");
+}
+
+/// Whether this context runs against the Word host
+pub fn is_active(ctx: &Context) -> bool {
+    ctx.runtime_config.host == HostApplication::Word
+}
+
+/// Word globals that evaluate to host objects: Application, Documents,
+/// ActiveDocument, ThisDocument and Selection. None outside the Word host.
+pub fn global_object(ctx: &Context, name: &str) -> Option<Value> {
+    if !is_active(ctx) {
+        return None;
+    }
+    let object = match &*crate::symbol::lower(name) {
+        "application" => objects::application_object(),
+        "documents" => objects::documents_object(),
+        "selection" => objects::selection_object(),
+        "activedocument" => document::active_document().map(objects::document_object).unwrap_or(Value::Object(None)),
+        "thisdocument" => document::this_document().map(objects::document_object).unwrap_or(Value::Object(None)),
+        _ => return None,
+    };
+    Some(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::ObjectRef;
+    use std::sync::{Mutex, MutexGuard};
+
+    // The document store is global: tests only use documents they create
+    // and never reset the store. Tests that go through the active document
+    // (Selection) hold DOCUMENTS_LOCK.
+    static DOCUMENTS_LOCK: Mutex<()> = Mutex::new(());
+
+    fn lock_documents() -> MutexGuard<'static, ()> {
+        DOCUMENTS_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Add a document, returning its object data
+    fn add_document() -> String {
+        let doc = objects::call_method("documents", "", "Add", &[]).unwrap();
+        ObjectRef::parse(&doc).unwrap().data
+    }
+
+    fn text(object_type: &str, data: &str) -> String {
+        objects::get_property(object_type, data, "Text").unwrap().as_string()
+    }
+
+    /// Document.Range(start, end)
+    fn range(id: &str, start: i64, end: i64) -> String {
+        let range = objects::call_method("document", id, "Range", &[Value::Integer(start), Value::Integer(end)]).unwrap();
+        ObjectRef::parse(&range).unwrap().data
+    }
+
+    fn content(id: &str) -> String {
+        ObjectRef::parse(&objects::get_property("document", id, "Content").unwrap()).unwrap().data
+    }
+
+    fn type_text(text: &str) {
+        objects::call_method("selection", "", "TypeText", &[Value::String(text.into())]).unwrap();
+    }
+
+    #[test]
+    fn test_selection_typing() {
+        let _documents = lock_documents();
+        let id = add_document();
+        objects::call_method("document", &id, "Activate", &[]).unwrap();
+        type_text("Hello");
+        objects::call_method("selection", "", "TypeParagraph", &[]).unwrap();
+        type_text("World");
+        assert_eq!(text("range", &content(&id)), "Hello\rWorld");
+        // The insertion point follows the typed text
+        assert_eq!(objects::get_property("selection", "", "Start").unwrap().as_string(), "11");
+    }
+
+    #[test]
+    fn test_selection_text_stays_selected() {
+        let _documents = lock_documents();
+        let id = add_document();
+        objects::call_method("document", &id, "Activate", &[]).unwrap();
+        type_text("Hello World");
+        objects::call_method("selection", "", "WholeStory", &[]).unwrap();
+        objects::set_property("selection", "", "Text", Value::String("New".into())).unwrap();
+        assert_eq!(text("selection", ""), "New");
+        assert_eq!(objects::get_property("selection", "", "End").unwrap().as_string(), "3");
+    }
+
+    #[test]
+    fn test_content_follows_edits() {
+        let id = add_document();
+        let content = content(&id);
+        objects::call_method("range", &content, "InsertAfter", &[Value::String("Hello".into())]).unwrap();
+        objects::call_method("range", &content, "InsertAfter", &[Value::String("!".into())]).unwrap();
+        objects::call_method("range", &content, "InsertBefore", &[Value::String(">".into())]).unwrap();
+        assert_eq!(text("range", &content), ">Hello!");
+    }
+
+    #[test]
+    fn test_fixed_range_text() {
+        let id = add_document();
+        objects::call_method("range", &content(&id), "InsertAfter", &[Value::String("Hello World".into())]).unwrap();
+        let first = range(&id, 0, 5);
+        assert_eq!(text("range", &first), "Hello");
+        objects::set_property("range", &first, "Text", Value::String("Bye".into())).unwrap();
+        assert_eq!(text("range", &content(&id)), "Bye World");
+    }
+
+    #[test]
+    fn test_documents_item_by_name() {
+        let id = add_document();
+        let name = objects::get_property("document", &id, "Name").unwrap();
+        let by_name = objects::call_method("documents", "", "Item", &[name]).unwrap();
+        assert_eq!(ObjectRef::parse(&by_name).unwrap().data, id);
+    }
+
+    #[test]
+    fn test_documents_item_of_missing_document() {
+        let missing = objects::call_method("documents", "", "Item", &[Value::String("Missing.docx".into())]).unwrap_err();
+        assert_eq!(crate::host::excel::error_number(&missing), Some(5941));
+        assert!(objects::call_method("documents", "", "Item", &[Value::Integer(0)]).is_err());
+    }
+
+    #[test]
+    fn test_save_as() {
+        let id = add_document();
+        objects::call_method("range", &content(&id), "InsertAfter", &[Value::String("Draft".into())]).unwrap();
+        assert_eq!(objects::get_property("document", &id, "Saved").unwrap().as_string(), "false");
+        objects::call_method("document", &id, "SaveAs", &[Value::String("C:\\Temp\\out.docm".into())]).unwrap();
+        assert_eq!(objects::get_property("document", &id, "FullName").unwrap().as_string(), "C:\\Temp\\out.docm");
+        assert_eq!(objects::get_property("document", &id, "Saved").unwrap().as_string(), "true");
+    }
+
+    #[test]
+    fn test_closed_document_keeps_its_text_but_not_its_object() {
+        let id = add_document();
+        objects::call_method("range", &content(&id), "InsertAfter", &[Value::String("Kept".into())]).unwrap();
+        objects::call_method("document", &id, "Close", &[]).unwrap();
+        assert!(objects::get_property("document", &id, "Name").is_err());
+        let closed = document::all_documents().into_iter().find(|d| d.id.to_string() == id).unwrap();
+        assert_eq!(closed.text, "Kept");
+    }
+
+    #[test]
+    fn test_unknown_members() {
+        let id = add_document();
+        assert!(objects::get_property("document", &id, "Paragraphz").is_err());
+        assert!(objects::set_property("document", &id, "Name", Value::String("x".into())).is_err());
+        assert!(objects::get_property("table", "", "Count").is_err());
+    }
+}
@@ -0,0 +1,379 @@
+// src/host/word/objects.rs
+// Word object references and their property/method dispatch.
+//
+// Objects are "Word.<Type>:<data>" strings inside Value::Object:
+//   Word.Application:           Word.Documents:
+//   Word.Document:<id>          Word.Selection:  (of the active document)
+//   Word.Range:<id>:<start>:<end>  (empty <end> = end of document, so
+//                                   Content/Range() follow edits)
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::context::Value;
+use crate::host::excel::ExcelError;
+use crate::symbol::lower;
+use crate::vba_errors::VbaErr;
+
+use super::document;
+
+const NO_DOCUMENT_ERROR: &str = "This command is not available because no document is open.";
+
+fn object(reference: String) -> Value {
+    Value::Object(Some(Box::new(Value::String(reference))))
+}
+
+pub fn application_object() -> Value {
+    object("Word.Application:".into())
+}
+
+pub fn documents_object() -> Value {
+    object("Word.Documents:".into())
+}
+
+pub fn document_object(id: usize) -> Value {
+    object(format!("Word.Document:{}", id))
+}
+
+pub fn selection_object() -> Value {
+    object("Word.Selection:".into())
+}
+
+/// Range from `start` to `end`, or to the end of the document when `end` is None
+pub fn range_object(id: usize, start: usize, end: Option<usize>) -> Value {
+    let end = end.map(|e| e.to_string()).unwrap_or_default();
+    object(format!("Word.Range:{}:{}:{}", id, start, end))
+}
+
+fn active_document() -> Result<usize> {
+    document::active_document().ok_or_else(|| ExcelError::raise(4248, NO_DOCUMENT_ERROR))
+}
+
+fn document_id(data: &str) -> Result<usize> {
+    let id = data.parse().map_err(|_| anyhow!("Invalid document reference: {}", data))?;
+    document::document(id).map(|_| id).ok_or_else(|| ExcelError::raise(5825, "Object has been deleted."))
+}
+
+/// (document, start, end) of a range reference, with the open end resolved
+fn range_bounds(data: &str) -> Result<(usize, usize, usize)> {
+    let mut parts = data.splitn(3, ':');
+    let id = document_id(parts.next().unwrap_or(""))?;
+    let start = parts.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+    let len = document::document(id).map(|d| d.len()).unwrap_or(0);
+    let end = match parts.next() {
+        Some(e) if !e.is_empty() => e.parse::<usize>().map_err(|_| anyhow!("Invalid range reference: {}", data))?,
+        _ => len,
+    };
+    Ok((id, start.min(len), end.clamp(start.min(len), len)))
+}
+
+fn text_arg(args: &[Value], index: usize, method: &str) -> Result<String> {
+    args.get(index)
+        .map(Value::as_string)
+        .ok_or_else(|| anyhow!("{} requires a Text argument", method))
+}
+
+/// Optional character position argument; missing or Empty uses `default`
+fn position_arg(args: &[Value], index: usize, default: usize) -> Result<usize> {
+    match args.get(index) {
+        None | Some(Value::Empty) => Ok(default),
+        Some(v) => Ok(integer(v)?.max(0) as usize),
+    }
+}
+
+fn integer(value: &Value) -> Result<i64> {
+    value.as_integer().ok_or_else(|| VbaErr::TypeMismatch.error(""))
+}
+
+fn boolean(value: &Value) -> Result<bool> {
+    match value {
+        Value::Boolean(b) => Ok(*b),
+        Value::String(s) if s.eq_ignore_ascii_case("true") => Ok(true),
+        Value::String(s) if s.eq_ignore_ascii_case("false") => Ok(false),
+        other => integer(other).map(|i| i != 0),
+    }
+}
+
+// ============================================================================
+// PROPERTIES
+// ============================================================================
+
+/// Get a property of a Word object
+pub fn get_property(object_type: &str, data: &str, property: &str) -> Result<Value> {
+    match &*lower(object_type) {
+        "application" => get_application_property(property),
+        "documents" => match &*lower(property) {
+            "count" => Ok(Value::Integer(document::open_documents().len() as i64)),
+            "application" => Ok(application_object()),
+            _ => bail!("Unknown Documents property: {}", property),
+        },
+        "document" => get_document_property(data, property),
+        "selection" => get_selection_property(property),
+        "range" => get_range_property(data, property),
+        _ => bail!("Unknown Word object type: {}", object_type),
+    }
+}
+
+fn get_application_property(property: &str) -> Result<Value> {
+    let settings = document::settings();
+    Ok(match &*lower(property) {
+        "name" => Value::String("Microsoft Word".into()),
+        "version" => Value::String("16.0".into()),
+        "username" => Value::String("User".into()),
+        "documents" => documents_object(),
+        "activedocument" => document_object(active_document()?),
+        "selection" => selection_object(),
+        "visible" => Value::Boolean(settings.visible),
+        "displayalerts" => Value::Boolean(settings.display_alerts),
+        "screenupdating" => Value::Boolean(settings.screen_updating),
+        "application" => application_object(),
+        _ => bail!("Unknown Word Application property: {}", property),
+    })
+}
+
+fn get_document_property(data: &str, property: &str) -> Result<Value> {
+    let id = document_id(data)?;
+    let doc = document::document(id).ok_or_else(|| anyhow!("Document {} is closed", id))?;
+    Ok(match &*lower(property) {
+        "name" => Value::String(doc.name.clone()),
+        "fullname" => Value::String(doc.full_name()),
+        "path" => Value::String(doc.path.clone()),
+        "saved" => Value::Boolean(doc.saved),
+        "content" | "range" => range_object(id, 0, None),
+        "application" => application_object(),
+        _ => bail!("Unknown Document property: {}", property),
+    })
+}
+
+fn get_selection_property(property: &str) -> Result<Value> {
+    let id = active_document()?;
+    let (start, end) = document::selection(id).unwrap_or((0, 0));
+    Ok(match &*lower(property) {
+        "text" => Value::String(document::get_text(id, start, end).unwrap_or_default()),
+        "start" => Value::Integer(start as i64),
+        "end" => Value::Integer(end as i64),
+        "range" => range_object(id, start, Some(end)),
+        "document" => document_object(id),
+        "application" => application_object(),
+        _ => bail!("Unknown Selection property: {}", property),
+    })
+}
+
+fn get_range_property(data: &str, property: &str) -> Result<Value> {
+    let (id, start, end) = range_bounds(data)?;
+    Ok(match &*lower(property) {
+        "text" => Value::String(document::get_text(id, start, end).unwrap_or_default()),
+        "start" => Value::Integer(start as i64),
+        "end" => Value::Integer(end as i64),
+        "document" => document_object(id),
+        "application" => application_object(),
+        _ => bail!("Unknown Range property: {}", property),
+    })
+}
+
+/// Set a property of a Word object
+pub fn set_property(object_type: &str, data: &str, property: &str, value: Value) -> Result<()> {
+    match (&*lower(object_type), &*lower(property)) {
+        ("application", "visible") => {
+            let visible = boolean(&value)?;
+            document::update_settings(|s| s.visible = visible);
+        }
+        ("application", "displayalerts") => {
+            let display_alerts = boolean(&value)?;
+            document::update_settings(|s| s.display_alerts = display_alerts);
+        }
+        ("application", "screenupdating") => {
+            let screen_updating = boolean(&value)?;
+            document::update_settings(|s| s.screen_updating = screen_updating);
+        }
+        ("document", "saved") => document::set_saved(document_id(data)?, boolean(&value)?),
+        ("selection", "text") => {
+            // The new text stays selected
+            let id = active_document()?;
+            let (start, end) = document::selection(id).unwrap_or((0, 0));
+            if let Some((s, e)) = document::replace_text(id, start, end, &value.as_string()) {
+                document::set_selection(id, s, e);
+            }
+        }
+        ("selection", "start") => {
+            let id = active_document()?;
+            let (_, end) = document::selection(id).unwrap_or((0, 0));
+            let start = integer(&value)?.max(0) as usize;
+            document::set_selection(id, start, end.max(start));
+        }
+        ("selection", "end") => {
+            let id = active_document()?;
+            let (start, _) = document::selection(id).unwrap_or((0, 0));
+            let end = integer(&value)?.max(0) as usize;
+            document::set_selection(id, start.min(end), end);
+        }
+        ("range", "text") => {
+            let (id, start, end) = range_bounds(data)?;
+            document::replace_text(id, start, end, &value.as_string());
+        }
+        _ => bail!("Cannot set {}.{}", object_type, property),
+    }
+    Ok(())
+}
+
+// ============================================================================
+// METHODS
+// ============================================================================
+
+/// Call a method of a Word object
+pub fn call_method(object_type: &str, data: &str, method: &str, args: &[Value]) -> Result<Value> {
+    match &*lower(object_type) {
+        "application" => match &*lower(method) {
+            "quit" => {
+                for id in document::open_documents() {
+                    document::close_document(id);
+                }
+                Ok(Value::Empty)
+            }
+            _ => get_application_property(method),
+        },
+        "documents" => call_documents_method(method, args),
+        "document" => call_document_method(data, method, args),
+        "selection" => call_selection_method(method, args),
+        "range" => call_range_method(data, method, args),
+        _ => bail!("Unknown Word object type: {}", object_type),
+    }
+}
+
+fn call_documents_method(method: &str, args: &[Value]) -> Result<Value> {
+    match &*lower(method) {
+        "add" => Ok(document_object(document::add_document())),
+        "open" => Ok(document_object(document::open_document(&text_arg(args, 0, "Documents.Open")?))),
+        "item" => {
+            let id = match args.first() {
+                Some(Value::String(name)) => document::find_document(name),
+                Some(index) => {
+                    let index = integer(index)?;
+                    usize::try_from(index - 1).ok().and_then(|i| document::open_documents().get(i).copied())
+                }
+                None => None,
+            };
+            id.map(document_object)
+                .ok_or_else(|| ExcelError::raise(5941, "The requested member of the collection does not exist."))
+        }
+        "count" => get_property("documents", "", method),
+        _ => bail!("Unknown Documents method: {}", method),
+    }
+}
+
+fn call_document_method(data: &str, method: &str, args: &[Value]) -> Result<Value> {
+    let id = document_id(data)?;
+    match &*lower(method) {
+        "range" => {
+            // Range() is the whole document; Range(start) runs to the end
+            let start = position_arg(args, 0, 0)?;
+            let end = match args.get(1) {
+                None | Some(Value::Empty) => None,
+                Some(_) => Some(position_arg(args, 1, 0)?),
+            };
+            Ok(range_object(id, start, end))
+        }
+        "activate" => {
+            document::activate_document(id);
+            Ok(Value::Empty)
+        }
+        "select" => {
+            document::activate_document(id);
+            let len = document::document(id).map(|d| d.len()).unwrap_or(0);
+            document::set_selection(id, 0, len);
+            Ok(Value::Empty)
+        }
+        "save" => {
+            document::set_saved(id, true);
+            Ok(Value::Empty)
+        }
+        "saveas" | "saveas2" => {
+            document::save_as(id, &text_arg(args, 0, "SaveAs")?);
+            Ok(Value::Empty)
+        }
+        "close" => {
+            document::close_document(id);
+            Ok(Value::Empty)
+        }
+        _ => get_document_property(data, method),
+    }
+}
+
+fn call_selection_method(method: &str, args: &[Value]) -> Result<Value> {
+    let id = active_document()?;
+    let (start, end) = document::selection(id).unwrap_or((0, 0));
+    let len = document::document(id).map(|d| d.len()).unwrap_or(0);
+    match &*lower(method) {
+        // Typing replaces the selection and leaves the insertion point after it
+        "typetext" | "typeparagraph" => {
+            let text = if lower(method) == "typeparagraph" {
+                "\r".to_string()
+            } else {
+                text_arg(args, 0, "TypeText")?
+            };
+            if let Some((_, e)) = document::replace_text(id, start, end, &text) {
+                document::set_selection(id, e, e);
+            }
+        }
+        "typebackspace" => {
+            let from = if start == end { start.saturating_sub(1) } else { start };
+            document::replace_text(id, from, end, "");
+            document::set_selection(id, from, from);
+        }
+        "delete" => {
+            let to = if start == end { (end + 1).min(len) } else { end };
+            document::replace_text(id, start, to, "");
+            document::set_selection(id, start, start);
+        }
+        // InsertAfter/InsertBefore extend the selection over the new text
+        "insertafter" => {
+            let text = text_arg(args, 0, "InsertAfter")?;
+            if let Some((_, e)) = document::replace_text(id, end, end, &text) {
+                document::set_selection(id, start, e);
+            }
+        }
+        "insertbefore" => {
+            let text = text_arg(args, 0, "InsertBefore")?;
+            if let Some((_, e)) = document::replace_text(id, start, start, &text) {
+                document::set_selection(id, start, e + (end - start));
+            }
+        }
+        "wholestory" => document::set_selection(id, 0, len),
+        // Only wdStory (6) is supported as the unit
+        "homekey" => document::set_selection(id, 0, 0),
+        "endkey" => document::set_selection(id, len, len),
+        "collapse" => {
+            // wdCollapseStart (1) is the default, wdCollapseEnd is 0
+            let pos = if position_arg(args, 0, 1)? == 0 { end } else { start };
+            document::set_selection(id, pos, pos);
+        }
+        _ => return get_selection_property(method),
+    }
+    Ok(Value::Empty)
+}
+
+fn call_range_method(data: &str, method: &str, args: &[Value]) -> Result<Value> {
+    let (id, start, end) = range_bounds(data)?;
+    match &*lower(method) {
+        "insertafter" => {
+            document::replace_text(id, end, end, &text_arg(args, 0, "InsertAfter")?);
+        }
+        "insertbefore" => {
+            document::replace_text(id, start, start, &text_arg(args, 0, "InsertBefore")?);
+        }
+        "insertparagraphafter" => {
+            document::replace_text(id, end, end, "\r");
+        }
+        "insertparagraphbefore" => {
+            document::replace_text(id, start, start, "\r");
+        }
+        "delete" => {
+            document::replace_text(id, start, end, "");
+        }
+        "select" => {
+            document::activate_document(id);
+            document::set_selection(id, start, end);
+        }
+        _ => return get_range_property(data, method),
+    }
+    Ok(Value::Empty)
+}
@@ -67,6 +67,18 @@ pub(crate) fn value_to_string(val: &Value) -> String {
     }
 }
 
+/// Result of a builtin whose first argument is Null. The Variant forms
+/// that propagate it (`Len`, `Trim`, `Year`, `Abs`, ...) return Null; `$`
+/// forms and functions that need a value (`CStr`, `Chr`, `Sqr`) raise
+/// error 94 "Invalid use of Null".
+pub(crate) fn null_argument(function: &str, propagates: bool) -> Result<Option<Value>> {
+    if propagates && !function.ends_with('$') {
+        Ok(Some(Value::Null))
+    } else {
+        Err(crate::vba_errors::VbaErr::InvalidUseOfNull.error(""))
+    }
+}
+
 /// Convert a Value to a boolean for conditional evaluation
 pub(crate) fn value_to_bool(val: &Value) -> bool {
     match val {
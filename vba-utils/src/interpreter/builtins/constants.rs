@@ -1,549 +1,794 @@
-use crate::context::Value;
+// vba-utils/src/interpreter/builtins/constants.rs
+
+//! Builtin constants: the VBA library enums (`Vb*`, colors, key codes),
+//! the string constants (`vbCrLf`, `vbNullString`, ...) and the Excel,
+//! Office and Outlook enums macros use.
+//!
+//! The table is generated by `constant_table!`: every entry is also a Rust
+//! `const` with its VBA name, so host code can match on `xlShiftDown`
+//! instead of `-4121`. [`lookup`] resolves a name case-insensitively, as
+//! VBA does; [`TABLE`] lists every constant with the enum it belongs to.
+
+#![allow(non_upper_case_globals)]
+
+use std::collections::HashMap;
+
 use chrono::Local;
+use once_cell::sync::Lazy;
+
+use crate::context::Value;
+
+/// Value of a builtin constant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstantValue {
+    Number(i64),
+    Text(&'static str),
+}
+
+impl ConstantValue {
+    pub fn to_value(self) -> Value {
+        match self {
+            ConstantValue::Number(n) => Value::Integer(n),
+            ConstantValue::Text(s) => Value::String(s.to_string()),
+        }
+    }
+}
+
+/// One entry of [`TABLE`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuiltinConstant {
+    /// Name as written in the VBA object browser (`vbYesNo`)
+    pub name: &'static str,
+    /// Enum the constant belongs to (`VbMsgBoxStyle`)
+    pub group: &'static str,
+    pub value: ConstantValue,
+}
+
+macro_rules! constant_table {
+    (@type number) => { i64 };
+    (@type text) => { &str };
+    (@value number $name:ident) => { ConstantValue::Number($name) };
+    (@value text $name:ident) => { ConstantValue::Text($name) };
+    ($( $group:literal: $ty:tt { $($name:ident = $value:expr),* $(,)? } )*) => {
+        $($(
+            pub const $name: constant_table!(@type $ty) = $value;
+        )*)*
+
+        /// Every builtin constant, grouped by enum
+        pub static TABLE: &[BuiltinConstant] = &[$($(
+            BuiltinConstant { name: stringify!($name), group: $group, value: constant_table!(@value $ty $name) },
+        )*)*];
+    };
+}
+
+constant_table! {
+    // ====================================================================
+    // VBA LIBRARY
+    // ====================================================================
+
+    "Constants": text {
+        vbCrLf = "\r\n",
+        vbCr = "\r",
+        vbLf = "\n",
+        vbNewLine = "\n",        // same as vbLf in many contexts
+        vbNullChar = "\0",
+        vbNullString = "",
+        vbTab = "\t",
+        vbBack = "\x08",
+        vbFormFeed = "\x0C",
+        vbVerticalTab = "\x0B",
+    }
+
+    "Constants": number {
+        vbObjectError = crate::vba_errors::VB_OBJECT_ERROR as i64, // base for errors raised by classes
+    }
+
+    "VbCalendar": number {
+        vbCalGreg = 0,
+        vbCalHijri = 1,
+    }
+
+    "VbCallType": number {
+        vbMethod = 1,
+        vbGet = 2,
+        vbLet = 4,
+        vbSet = 8,
+    }
+
+    "ColorConstants": number {
+        vbBlack = 0,
+        vbRed = 255,
+        vbGreen = 65280,
+        vbYellow = 65535,
+        vbBlue = 16711680,
+        vbMagenta = 16711935,
+        vbCyan = 16776960,
+        vbWhite = 16777215,
+    }
+
+    "VbCompareMethod": number {
+        vbUseCompareOption = -1,
+        vbBinaryCompare = 0,
+        vbTextCompare = 1,
+        vbDatabaseCompare = 2,
+    }
+
+    "VbDayOfWeek": number {
+        vbUseSystemDayOfWeek = 0,
+        vbSunday = 1,
+        vbMonday = 2,
+        vbTuesday = 3,
+        vbWednesday = 4,
+        vbThursday = 5,
+        vbFriday = 6,
+        vbSaturday = 7,
+    }
+
+    "VbFirstWeekOfYear": number {
+        vbUseSystem = 0,
+        vbFirstJan1 = 1,
+        vbFirstFourDays = 2,
+        vbFirstFullWeek = 3,
+    }
+
+    "VbDateTimeFormat": number {
+        vbGeneralDate = 0,
+        vbLongDate = 1,
+        vbShortDate = 2,
+        vbLongTime = 3,
+        vbShortTime = 4,
+    }
+
+    "KeyCodeConstants": number {
+        // Mouse buttons
+        vbKeyLButton = 0x1,
+        vbKeyRButton = 0x2,
+        vbKeyCancel = 0x3,
+        vbKeyMButton = 0x4,
+        // Special keys
+        vbKeyBack = 0x8,
+        vbKeyTab = 0x9,
+        vbKeyClear = 0xC,
+        vbKeyReturn = 0xD,
+        vbKeyShift = 0x10,
+        vbKeyControl = 0x11,
+        vbKeyMenu = 0x12,
+        vbKeyPause = 0x13,
+        vbKeyCapital = 0x14,
+        vbKeyEscape = 0x1B,
+        vbKeySpace = 0x20,
+        // Navigation keys
+        vbKeyPageUp = 0x21,
+        vbKeyPageDown = 0x22,
+        vbKeyEnd = 0x23,
+        vbKeyHome = 0x24,
+        vbKeyLeft = 0x25,
+        vbKeyUp = 0x26,
+        vbKeyRight = 0x27,
+        vbKeyDown = 0x28,
+        vbKeySelect = 0x29,
+        vbKeyPrint = 0x2A,
+        vbKeyExecute = 0x2B,
+        vbKeySnapshot = 0x2C,
+        vbKeyInsert = 0x2D,
+        vbKeyDelete = 0x2E,
+        vbKeyHelp = 0x2F,
+        vbKeyNumlock = 0x90,
+        vbKeyScrollLock = 0x91,
+        // Letters (ASCII)
+        vbKeyA = 65, vbKeyB = 66, vbKeyC = 67, vbKeyD = 68, vbKeyE = 69, vbKeyF = 70,
+        vbKeyG = 71, vbKeyH = 72, vbKeyI = 73, vbKeyJ = 74, vbKeyK = 75, vbKeyL = 76,
+        vbKeyM = 77, vbKeyN = 78, vbKeyO = 79, vbKeyP = 80, vbKeyQ = 81, vbKeyR = 82,
+        vbKeyS = 83, vbKeyT = 84, vbKeyU = 85, vbKeyV = 86, vbKeyW = 87, vbKeyX = 88,
+        vbKeyY = 89, vbKeyZ = 90,
+        // Digits (ASCII)
+        vbKey0 = 48, vbKey1 = 49, vbKey2 = 50, vbKey3 = 51, vbKey4 = 52,
+        vbKey5 = 53, vbKey6 = 54, vbKey7 = 55, vbKey8 = 56, vbKey9 = 57,
+        // Numeric keypad
+        vbKeyNumpad0 = 96, vbKeyNumpad1 = 97, vbKeyNumpad2 = 98, vbKeyNumpad3 = 99,
+        vbKeyNumpad4 = 100, vbKeyNumpad5 = 101, vbKeyNumpad6 = 102, vbKeyNumpad7 = 103,
+        vbKeyNumpad8 = 104, vbKeyNumpad9 = 105,
+        vbKeyMultiply = 106,
+        vbKeyAdd = 107,
+        vbKeySeparator = 108,
+        vbKeySubtract = 109,
+        vbKeyDecimal = 110,
+        vbKeyDivide = 111,
+        // Function keys
+        vbKeyF1 = 112, vbKeyF2 = 113, vbKeyF3 = 114, vbKeyF4 = 115,
+        vbKeyF5 = 116, vbKeyF6 = 117, vbKeyF7 = 118, vbKeyF8 = 119,
+        vbKeyF9 = 120, vbKeyF10 = 121, vbKeyF11 = 122, vbKeyF12 = 123,
+        vbKeyF13 = 124, vbKeyF14 = 125, vbKeyF15 = 126, vbKeyF16 = 127,
+    }
+
+    "VbMsgBoxStyle": number {
+        // Buttons
+        vbOKOnly = 0,
+        vbOKCancel = 1,
+        vbAbortRetryIgnore = 2,
+        vbYesNoCancel = 3,
+        vbYesNo = 4,
+        vbRetryCancel = 5,
+        // Icons
+        vbCritical = 16,
+        vbQuestion = 32,
+        vbExclamation = 48,
+        vbInformation = 64,
+        // Default button
+        vbDefaultButton1 = 0,
+        vbDefaultButton2 = 256,
+        vbDefaultButton3 = 512,
+        vbDefaultButton4 = 768,
+        // Modality and options
+        vbApplicationModal = 0,
+        vbSystemModal = 4096,
+        vbMsgBoxHelpButton = 16384,
+        vbMsgBoxSetForeground = 65536,
+        vbMsgBoxRight = 524288,
+        vbMsgBoxRtlReading = 1048576,
+    }
+
+    "VbMsgBoxResult": number {
+        vbOK = 1,
+        vbCancel = 2,
+        vbAbort = 3,
+        vbRetry = 4,
+        vbIgnore = 5,
+        vbYes = 6,
+        vbNo = 7,
+    }
+
+    "VbAppWinStyle": number {
+        vbHide = 0,
+        vbNormalFocus = 1,
+        vbMinimizedFocus = 2,
+        vbMaximizedFocus = 3,
+        vbNormalNoFocus = 4,
+        vbMinimizedNoFocus = 6,
+    }
+
+    "VbFileAttribute": number {
+        vbNormal = 0,
+        vbReadOnly = 1,
+        vbHidden = 2,
+        vbSystem = 4,
+        vbVolume = 8,
+        vbDirectory = 16,
+        vbArchive = 32,
+        vbAlias = 64,
+    }
+
+    "VbStrConv": number {
+        vbUpperCase = 1,
+        vbLowerCase = 2,
+        vbProperCase = 3,
+        vbWide = 4,
+        vbNarrow = 8,
+        vbKatakana = 16,
+        vbHiragana = 32,
+        vbUnicode = 64,
+        vbFromUnicode = 128,
+        vbSimplifiedChinese = 256,
+        vbTraditionalChinese = 512,
+        vbLinguisticCasing = 1024,
+    }
+
+    "VbTriState": number {
+        vbTrue = -1,
+        vbFalse = 0,
+        vbUseDefault = -2,
+    }
+
+    "VbVarType": number {
+        vbEmpty = 0,
+        vbNull = 1,
+        vbInteger = 2,
+        vbLong = 3,
+        vbSingle = 4,
+        vbDouble = 5,
+        vbCurrency = 6,
+        vbDate = 7,
+        vbString = 8,
+        vbObject = 9,
+        vbError = 10,
+        vbBoolean = 11,
+        vbVariant = 12,
+        vbDataObject = 13,
+        vbDecimal = 14,
+        vbByte = 17,
+        vbLongLong = 20,
+        vbUserDefinedType = 36,
+        vbArray = 8192,
+    }
+
+    // ====================================================================
+    // EXCEL CONSTANTS (xl*)
+    // ====================================================================
+
+    "XlHAlign": number {
+        xlHAlignCenter = -4108,
+        xlHAlignCenterAcrossSelection = 7,
+        xlHAlignDistributed = -4117,
+        xlHAlignFill = 5,
+        xlHAlignGeneral = 1,
+        xlHAlignJustify = -4130,
+        xlHAlignLeft = -4131,
+        xlHAlignRight = -4152,
+    }
+
+    "XlVAlign": number {
+        xlVAlignBottom = -4107,
+        xlVAlignCenter = -4108,
+        xlVAlignDistributed = -4117,
+        xlVAlignJustify = -4130,
+        xlVAlignTop = -4160,
+    }
+
+    // Alignment shortcuts from the Constants enum
+    "Constants": number {
+        xlLeft = -4131,
+        xlCenter = -4108,
+        xlRight = -4152,
+        xlTop = -4160,
+        xlBottom = -4107,
+        xlGeneral = 1,
+        xlJustify = -4130,
+        xlDistributed = -4117,
+    }
+
+    "XlBordersIndex": number {
+        xlDiagonalDown = 5,
+        xlDiagonalUp = 6,
+        xlEdgeBottom = 9,
+        xlEdgeLeft = 7,
+        xlEdgeRight = 10,
+        xlEdgeTop = 8,
+        xlInsideHorizontal = 12,
+        xlInsideVertical = 11,
+    }
+
+    "XlLineStyle": number {
+        xlContinuous = 1,
+        xlDash = -4115,
+        xlDashDot = 4,
+        xlDashDotDot = 5,
+        xlDot = -4118,
+        xlDouble = -4119,
+        xlLineStyleNone = -4142,
+        xlSlantDashDot = 13,
+    }
+
+    "XlBorderWeight": number {
+        xlHairline = 1,
+        xlMedium = -4138,
+        xlThick = 4,
+        xlThin = 2,
+    }
+
+    "XlColorIndex": number {
+        xlColorIndexAutomatic = -4105,
+        xlColorIndexNone = -4142,
+    }
+
+    "XlPattern": number {
+        xlPatternAutomatic = -4105,
+        xlPatternChecker = 9,
+        xlPatternCrissCross = 16,
+        xlPatternDown = -4121,
+        xlPatternGray16 = 17,
+        xlPatternGray25 = -4124,
+        xlPatternGray50 = -4125,
+        xlPatternGray75 = -4126,
+        xlPatternGray8 = 18,
+        xlPatternGrid = 15,
+        xlPatternHorizontal = -4128,
+        xlPatternLightDown = 13,
+        xlPatternLightHorizontal = 11,
+        xlPatternLightUp = 14,
+        xlPatternLightVertical = 12,
+        xlPatternNone = -4142,
+        xlPatternSemiGray75 = 10,
+        xlPatternSolid = 1,
+        xlPatternUp = -4162,
+        xlPatternVertical = -4166,
+    }
+
+    "XlPasteType": number {
+        xlPasteAll = -4104,
+        xlPasteAllExceptBorders = 7,
+        xlPasteAllMergingConditionalFormats = 14,
+        xlPasteAllUsingSourceTheme = 13,
+        xlPasteColumnWidths = 8,
+        xlPasteComments = -4144,
+        xlPasteFormats = -4122,
+        xlPasteFormulas = -4123,
+        xlPasteFormulasAndNumberFormats = 11,
+        xlPasteValidation = 6,
+        xlPasteValues = -4163,
+        xlPasteValuesAndNumberFormats = 12,
+    }
+
+    "XlPasteSpecialOperation": number {
+        xlPasteSpecialOperationAdd = 2,
+        xlPasteSpecialOperationDivide = 5,
+        xlPasteSpecialOperationMultiply = 4,
+        xlPasteSpecialOperationNone = -4142,
+        xlPasteSpecialOperationSubtract = 3,
+    }
+
+    "XlInsertShiftDirection": number {
+        xlShiftDown = -4121,
+        xlShiftToRight = -4161,
+    }
+
+    "XlDeleteShiftDirection": number {
+        xlShiftToLeft = -4159,
+        xlShiftUp = -4162,
+    }
+
+    "XlDirection": number {
+        xlDown = -4121,
+        xlToLeft = -4159,
+        xlToRight = -4161,
+        xlUp = -4162,
+    }
+
+    "XlCellType": number {
+        xlCellTypeAllFormatConditions = -4172,
+        xlCellTypeAllValidation = -4174,
+        xlCellTypeBlanks = 4,
+        xlCellTypeComments = -4144,
+        xlCellTypeConstants = 2,
+        xlCellTypeFormulas = -4123,
+        xlCellTypeLastCell = 11,
+        xlCellTypeSameFormatConditions = -4173,
+        xlCellTypeSameValidation = -4175,
+        xlCellTypeVisible = 12,
+    }
+
+    "XlCVError": number {
+        xlErrBlocked = 2047,
+        xlErrCalc = 2050,
+        xlErrConnect = 2046,
+        xlErrDiv0 = 2007,
+        xlErrField = 2049,
+        xlErrGettingData = 2043,
+        xlErrNA = 2042,
+        xlErrName = 2029,
+        xlErrNull = 2000,
+        xlErrNum = 2036,
+        xlErrRef = 2023,
+        xlErrSpill = 2045,
+        xlErrUnknown = 2048,
+        xlErrValue = 2015,
+    }
+
+    "XlSpecialCellsValue": number {
+        xlErrors = 16,
+        xlLogical = 4,
+        xlNumbers = 1,
+        xlTextValues = 2,
+    }
+
+    "XlFindLookIn": number {
+        xlComments = -4144,
+        xlFormulas = -4123,
+        xlValues = -4163,
+    }
+
+    "XlAutoFillType": number {
+        xlFillCopy = 1,
+        xlFillDays = 5,
+        xlFillDefault = 0,
+        xlFillFormats = 3,
+        xlFillMonths = 7,
+        xlFillSeries = 2,
+        xlFillValues = 4,
+        xlFillWeekdays = 6,
+        xlFillYears = 8,
+        xlGrowthTrend = 10,
+        xlLinearTrend = 9,
+    }
+
+    "XlSortOrder": number {
+        xlAscending = 1,
+        xlDescending = 2,
+    }
+
+    "XlSortOrientation": number {
+        xlSortColumns = 1,
+        xlSortRows = 2,
+    }
+
+    "XlYesNoGuess": number {
+        xlGuess = 0,
+        xlNo = 2,
+        xlYes = 1,
+    }
+
+    "XlAutoFilterOperator": number {
+        xlAnd = 1,
+        xlBottom10Items = 4,
+        xlBottom10Percent = 6,
+        xlFilterCellColor = 8,
+        xlFilterDynamic = 11,
+        xlFilterFontColor = 9,
+        xlFilterIcon = 10,
+        xlFilterValues = 7,
+        xlOr = 2,
+        xlTop10Items = 3,
+        xlTop10Percent = 5,
+    }
+
+    "XlCalculation": number {
+        xlCalculationAutomatic = -4105,
+        xlCalculationManual = -4135,
+        xlCalculationSemiautomatic = 2,
+    }
+
+    "XlLookAt": number {
+        xlPart = 2,
+        xlWhole = 1,
+    }
+
+    "XlSearchOrder": number {
+        xlByColumns = 2,
+        xlByRows = 1,
+    }
+
+    "XlSearchDirection": number {
+        xlNext = 1,
+        xlPrevious = 2,
+    }
+
+    "XlOrientation": number {
+        xlDownward = -4170,
+        xlHorizontal = -4128,
+        xlUpward = -4171,
+        xlVertical = -4166,
+    }
+
+    "XlUnderlineStyle": number {
+        xlUnderlineStyleDouble = -4119,
+        xlUnderlineStyleDoubleAccounting = 5,
+        xlUnderlineStyleNone = -4142,
+        xlUnderlineStyleSingle = 2,
+        xlUnderlineStyleSingleAccounting = 4,
+    }
+
+    "XlFileFormat": number {
+        xlAddIn = 18,
+        xlCSV = 6,
+        xlCSVUTF8 = 62,
+        xlCurrentPlatformText = -4158,
+        xlExcel8 = 56,
+        xlExcel12 = 50,
+        xlHtml = 44,
+        xlOpenXMLAddIn = 55,
+        xlOpenXMLWorkbook = 51,
+        xlOpenXMLWorkbookMacroEnabled = 52,
+        xlTemplate = 17,
+        xlTextWindows = 20,
+        xlWorkbookDefault = 51,
+        xlWorkbookNormal = -4143,
+    }
+
+    "XlSheetType": number {
+        xlChart = -4109,
+        xlDialogSheet = -4116,
+        xlExcel4IntlMacroSheet = 4,
+        xlExcel4MacroSheet = 3,
+        xlWorksheet = -4167,
+    }
+
+    "XlSheetVisibility": number {
+        xlSheetVisible = -1,
+        xlSheetHidden = 0,
+        xlSheetVeryHidden = 2,
+    }
+
+    "XlWindowState": number {
+        xlMaximized = -4137,
+        xlMinimized = -4140,
+        xlNormal = -4143,
+    }
+
+    "XlPageOrientation": number {
+        xlLandscape = 2,
+        xlPortrait = 1,
+    }
+
+    "XlPaperSize": number {
+        xlPaperA4 = 9,
+        xlPaperLetter = 1,
+        xlPaperLegal = 5,
+    }
+
+    "XlReferenceStyle": number {
+        xlA1 = 1,
+        xlR1C1 = -4150,
+    }
+
+    "XlCopyPictureFormat": number {
+        xlBitmap = 2,
+        xlPicture = -4147,
+    }
+
+    "XlPictureAppearance": number {
+        xlPrinter = 2,
+        xlScreen = 1,
+    }
+
+    "XlFormatConditionType": number {
+        xlAboveAverageCondition = 12,
+        xlBlanksCondition = 10,
+        xlCellValue = 1,
+        xlColorScale = 3,
+        xlDatabar = 4,
+        xlErrorsCondition = 16,
+        xlExpression = 2,
+        xlIconSet = 6,
+        xlNoBlanksCondition = 13,
+        xlNoErrorsCondition = 17,
+        xlTextString = 9,
+        xlTimePeriod = 11,
+        xlTop10 = 5,
+        xlUniqueValues = 8,
+    }
+
+    "XlFormatConditionOperator": number {
+        xlBetween = 1,
+        xlEqual = 3,
+        xlGreater = 5,
+        xlGreaterEqual = 7,
+        xlLess = 6,
+        xlLessEqual = 8,
+        xlNotBetween = 2,
+        xlNotEqual = 4,
+    }
+
+    "XlDVType": number {
+        xlValidateInputOnly = 0,
+        xlValidateWholeNumber = 1,
+        xlValidateDecimal = 2,
+        xlValidateList = 3,
+        xlValidateDate = 4,
+        xlValidateTime = 5,
+        xlValidateTextLength = 6,
+        xlValidateCustom = 7,
+    }
+
+    "XlDVAlertStyle": number {
+        xlValidAlertStop = 1,
+        xlValidAlertWarning = 2,
+        xlValidAlertInformation = 3,
+    }
+
+    "XlPivotTableSourceType": number {
+        xlDatabase = 1,
+        xlExternal = 2,
+        xlConsolidation = 3,
+    }
+
+    "XlPivotFieldOrientation": number {
+        xlHidden = 0,
+        xlRowField = 1,
+        xlColumnField = 2,
+        xlPageField = 3,
+        xlDataField = 4,
+    }
+
+    "XlConsolidationFunction": number {
+        xlSum = -4157,
+        xlCount = -4112,
+        xlAverage = -4106,
+        xlMax = -4136,
+        xlMin = -4139,
+        xlProduct = -4149,
+    }
+
+    "Constants": number {
+        xlNone = -4142,
+        xlAutomatic = -4105,
+        xlManual = -4135,
+    }
+
+    "XlCreator": number {
+        xlCreatorCode = 1480803660,
+    }
+
+    // ====================================================================
+    // OFFICE CONSTANTS (mso*)
+    // ====================================================================
+
+    "MsoShapeType": number {
+        msoAutoShape = 1,
+        msoPicture = 13,
+        msoTextBox = 17,
+    }
+
+    "MsoAutoShapeType": number {
+        msoShapeRectangle = 1,
+        msoShapeRoundedRectangle = 5,
+        msoShapeOval = 9,
+        msoShapeRightArrow = 33,
+    }
+
+    "MsoTextOrientation": number {
+        msoTextOrientationHorizontal = 1,
+    }
+
+    "MsoTriState": number {
+        msoTrue = -1,
+        msoFalse = 0,
+    }
+
+    // ====================================================================
+    // OUTLOOK CONSTANTS (ol*)
+    // ====================================================================
+
+    "OlItemType": number {
+        olMailItem = 0,
+        olAppointmentItem = 1,
+        olContactItem = 2,
+        olTaskItem = 3,
+        olJournalItem = 4,
+        olNoteItem = 5,
+        olPostItem = 6,
+        olDistributionListItem = 7,
+    }
+
+    "OlDefaultFolders": number {
+        olFolderDeletedItems = 3,
+        olFolderOutbox = 4,
+        olFolderSentMail = 5,
+        olFolderInbox = 6,
+        olFolderCalendar = 9,
+        olFolderContacts = 10,
+        olFolderDrafts = 16,
+    }
+
+    "OlBodyFormat": number {
+        olFormatPlain = 1,
+        olFormatHTML = 2,
+        olFormatRichText = 3,
+    }
+
+    "OlMailRecipientType": number {
+        olTo = 1,
+        olCC = 2,
+        olBCC = 3,
+    }
+
+    "OlAttachmentType": number {
+        olByValue = 1,
+    }
+}
+
+/// Lower-cased name to index in [`TABLE`]
+static BY_NAME: Lazy<HashMap<String, usize>> = Lazy::new(|| {
+    TABLE.iter().enumerate().map(|(i, c)| (c.name.to_ascii_lowercase(), i)).collect()
+});
+
+/// The builtin constant called `name`, any case
+pub fn constant(name: &str) -> Option<&'static BuiltinConstant> {
+    BY_NAME.get(&name.to_ascii_lowercase()).map(|&i| &TABLE[i])
+}
+
+/// Value of the builtin constant called `name`, any case
+pub fn lookup(name: &str) -> Option<Value> {
+    constant(name).map(|c| c.value.to_value())
+}
 
 pub(crate) fn resolve_builtin_identifier(name: &str) -> Option<Value> {
+    if let Some(value) = lookup(name) {
+        return Some(value);
+    }
     match name {
-        "vbCalGreg" => Some(Value::Integer( 0)),
-        "vbCalHijri" => Some(Value::Integer( 1)),
-
-        // CallType constants
-        "vbMethod" => Some(Value::Integer( -1)),
-        "vbGet" => Some(Value::Integer( -2)),
-        "vbLet" => Some(Value::Integer( -4)),
-        "vbSet" => Some(Value::Integer( -8)),
-
-         // Color constants
-        "vbBlack" => Some(Value::Integer( 0)),
-        "vbRed" => Some(Value::Integer( 255)),
-        "vbGreen" => Some(Value::Integer( 65280)),
-        "vbYellow" => Some(Value::Integer( 65535)),
-        "vbBlue" => Some(Value::Integer( 16711680)),
-        "vbMagenta" => Some(Value::Integer( 16711935)),
-        "vbCyan" => Some(Value::Integer( 16776960)),
-        "vbWhite" => Some(Value::Integer( 16777215)),
-
-         // Comparison constants
-        "vbUseCompareOption"=> Some(Value::Integer(-1)),
-        "vbBinaryCompare" => Some(Value::Integer( 0)),
-        "vbTextCompare" => Some(Value::Integer( 1)),
-        "vbDatabaseCompare" => Some(Value::Integer( 2)),
-
-         // Day of Week constants
-         "vbSunday" => Some(Value::Integer( 1)),
-         "vbMonday" => Some(Value::Integer( 2)),
-         "vbTuesday" => Some(Value::Integer( 3)),
-         "vbWednesday" => Some(Value::Integer( 4)),
-         "vbThursday" => Some(Value::Integer( 5)),
-         "vbFriday" => Some(Value::Integer( 6)),
-         "vbSaturday" => Some(Value::Integer( 7)),
-         "vbUseSystemDayOfWeek" => Some(Value::Integer( 0)),
-
-         // First Week of Year constants
-         "vbUseSystem" => Some(Value::Integer( 0)),
-         "vbFirstJan1" => Some(Value::Integer( 1)),
-         "vbFirstFourDays" => Some(Value::Integer( 2)),
-         "vbFirstFullWeek" => Some(Value::Integer( 3)),
-
-         // Date/Time format constants
-         "vbGeneralDate" => Some(Value::Integer( 0)),
-         "vbLongDate" => Some(Value::Integer( 1)),
-         "vbShortDate" => Some(Value::Integer( 2)),
-         "vbLongTime" => Some(Value::Integer( 3)),
-         "vbShortTime" => Some(Value::Integer( 4)),
-
-         // Key Code Constants - Mouse Buttons
-         "vbKeyLButton" => Some(Value::Integer( 1)),        // 0x1 - Left mouse button
-         "vbKeyRButton" => Some(Value::Integer( 2)),        // 0x2 - Right mouse button
-         "vbKeyCancel" => Some(Value::Integer( 3)),         // 0x3 - CANCEL key
-         "vbKeyMButton" => Some(Value::Integer( 4)),        // 0x4 - Middle mouse button
-         
-         // Key Code Constants - Special Keys
-         "vbKeyBack" => Some(Value::Integer( 8)),           // 0x8 - BACKSPACE key
-         "vbKeyTab" => Some(Value::Integer( 9)),            // 0x9 - TAB key
-         "vbKeyClear" => Some(Value::Integer( 12)),         // 0xC - CLEAR key
-         "vbKeyReturn" => Some(Value::Integer( 13)),        // 0xD - ENTER key
-         "vbKeyShift" => Some(Value::Integer( 16)),         // 0x10 - SHIFT key
-         "vbKeyControl" => Some(Value::Integer( 17)),       // 0x11 - CTRL key
-         "vbKeyMenu" => Some(Value::Integer( 18)),          // 0x12 - MENU key
-         "vbKeyPause" => Some(Value::Integer( 19)),         // 0x13 - PAUSE key
-         "vbKeyCapital" => Some(Value::Integer( 20)),       // 0x14 - CAPS LOCK key
-         "vbKeyEscape" => Some(Value::Integer( 27)),        // 0x1B - ESC key
-         "vbKeySpace" => Some(Value::Integer( 32)),         // 0x20 - SPACEBAR key
-         
-         // Key Code Constants - Navigation Keys
-         "vbKeyPageUp" => Some(Value::Integer( 33)),        // 0x21 - PAGE UP key
-         "vbKeyPageDown" => Some(Value::Integer( 34)),      // 0x22 - PAGE DOWN key
-         "vbKeyEnd" => Some(Value::Integer( 35)),           // 0x23 - END key
-         "vbKeyHome" => Some(Value::Integer( 36)),          // 0x24 - HOME key
-         "vbKeyLeft" => Some(Value::Integer( 37)),          // 0x25 - LEFT ARROW key
-         "vbKeyUp" => Some(Value::Integer( 38)),            // 0x26 - UP ARROW key
-         "vbKeyRight" => Some(Value::Integer( 39)),         // 0x27 - RIGHT ARROW key
-         "vbKeyDown" => Some(Value::Integer( 40)),          // 0x28 - DOWN ARROW key
-         "vbKeySelect" => Some(Value::Integer( 41)),        // 0x29 - SELECT key
-         "vbKeyPrint" => Some(Value::Integer( 42)),         // 0x2A - PRINT SCREEN key
-         "vbKeyExecute" => Some(Value::Integer( 43)),       // 0x2B - EXECUTE key
-         "vbKeySnapshot" => Some(Value::Integer( 44)),      // 0x2C - SNAPSHOT key
-         "vbKeyInsert" => Some(Value::Integer( 45)),        // 0x2D - INSERT key
-         "vbKeyDelete" => Some(Value::Integer( 46)),        // 0x2E - DELETE key
-         "vbKeyHelp" => Some(Value::Integer( 47)),          // 0x2F - HELP key
-         "vbKeyNumlock" => Some(Value::Integer( 144)),      // 0x90 - NUM LOCK key
-         
-         // Key Code Constants - Alphabetic Keys (A-Z)
-         "vbKeyA" => Some(Value::Integer( 65)),             // ASCII 'A'
-         "vbKeyB" => Some(Value::Integer( 66)),             // ASCII 'B'
-         "vbKeyC" => Some(Value::Integer( 67)),             // ASCII 'C'
-         "vbKeyD" => Some(Value::Integer( 68)),             // ASCII 'D'
-         "vbKeyE" => Some(Value::Integer( 69)),             // ASCII 'E'
-         "vbKeyF" => Some(Value::Integer( 70)),             // ASCII 'F'
-         "vbKeyG" => Some(Value::Integer( 71)),             // ASCII 'G'
-         "vbKeyH" => Some(Value::Integer( 72)),             // ASCII 'H'
-         "vbKeyI" => Some(Value::Integer( 73)),             // ASCII 'I'
-         "vbKeyJ" => Some(Value::Integer( 74)),             // ASCII 'J'
-         "vbKeyK" => Some(Value::Integer( 75)),             // ASCII 'K'
-         "vbKeyL" => Some(Value::Integer( 76)),             // ASCII 'L'
-         "vbKeyM" => Some(Value::Integer( 77)),             // ASCII 'M'
-         "vbKeyN" => Some(Value::Integer( 78)),             // ASCII 'N'
-         "vbKeyO" => Some(Value::Integer( 79)),             // ASCII 'O'
-         "vbKeyP" => Some(Value::Integer( 80)),             // ASCII 'P'
-         "vbKeyQ" => Some(Value::Integer( 81)),             // ASCII 'Q'
-         "vbKeyR" => Some(Value::Integer( 82)),             // ASCII 'R'
-         "vbKeyS" => Some(Value::Integer( 83)),             // ASCII 'S'
-         "vbKeyT" => Some(Value::Integer( 84)),             // ASCII 'T'
-         "vbKeyU" => Some(Value::Integer( 85)),             // ASCII 'U'
-         "vbKeyV" => Some(Value::Integer( 86)),             // ASCII 'V'
-         "vbKeyW" => Some(Value::Integer( 87)),             // ASCII 'W'
-         "vbKeyX" => Some(Value::Integer( 88)),             // ASCII 'X'
-         "vbKeyY" => Some(Value::Integer( 89)),             // ASCII 'Y'
-         "vbKeyZ" => Some(Value::Integer( 90)),             // ASCII 'Z'
-         
-         // Key Code Constants - Numeric Keys (0-9)
-         "vbKey0" => Some(Value::Integer( 48)),             // ASCII '0'
-         "vbKey1" => Some(Value::Integer( 49)),             // ASCII '1'
-         "vbKey2" => Some(Value::Integer( 50)),             // ASCII '2'
-         "vbKey3" => Some(Value::Integer( 51)),             // ASCII '3'
-         "vbKey4" => Some(Value::Integer( 52)),             // ASCII '4'
-         "vbKey5" => Some(Value::Integer( 53)),             // ASCII '5'
-         "vbKey6" => Some(Value::Integer( 54)),             // ASCII '6'
-         "vbKey7" => Some(Value::Integer( 55)),             // ASCII '7'
-         "vbKey8" => Some(Value::Integer( 56)),             // ASCII '8'
-         "vbKey9" => Some(Value::Integer( 57)),             // ASCII '9'
-         
-         // Key Code Constants - Numpad Keys
-         "vbKeyNumpad0" => Some(Value::Integer( 96)),       // Numpad 0
-         "vbKeyNumpad1" => Some(Value::Integer( 97)),       // Numpad 1
-         "vbKeyNumpad2" => Some(Value::Integer( 98)),       // Numpad 2
-         "vbKeyNumpad3" => Some(Value::Integer( 99)),       // Numpad 3
-         "vbKeyNumpad4" => Some(Value::Integer( 100)),      // Numpad 4
-         "vbKeyNumpad5" => Some(Value::Integer( 101)),      // Numpad 5
-         "vbKeyNumpad6" => Some(Value::Integer( 102)),      // Numpad 6
-         "vbKeyNumpad7" => Some(Value::Integer( 103)),      // Numpad 7
-         "vbKeyNumpad8" => Some(Value::Integer( 104)),      // Numpad 8
-         "vbKeyNumpad9" => Some(Value::Integer( 105)),      // Numpad 9
-         "vbKeyMultiply" => Some(Value::Integer( 106)),     // Numpad * (multiply)
-         "vbKeyAdd" => Some(Value::Integer( 107)),          // Numpad + (add)
-         "vbKeySeparator" => Some(Value::Integer( 108)),    // Numpad separator
-         "vbKeySubtract" => Some(Value::Integer( 109)),     // Numpad - (subtract)
-         "vbKeyDecimal" => Some(Value::Integer( 110)),      // Numpad . (decimal)
-         "vbKeyDivide" => Some(Value::Integer( 111)),       // Numpad / (divide)
-         
-         // Key Code Constants - Function Keys (F1-F16)
-         "vbKeyF1" => Some(Value::Integer( 112)),           // F1 key
-         "vbKeyF2" => Some(Value::Integer( 113)),           // F2 key
-         "vbKeyF3" => Some(Value::Integer( 114)),           // F3 key
-         "vbKeyF4" => Some(Value::Integer( 115)),           // F4 key
-         "vbKeyF5" => Some(Value::Integer( 116)),           // F5 key
-         "vbKeyF6" => Some(Value::Integer( 117)),           // F6 key
-         "vbKeyF7" => Some(Value::Integer( 118)),           // F7 key
-         "vbKeyF8" => Some(Value::Integer( 119)),           // F8 key
-         "vbKeyF9" => Some(Value::Integer( 120)),           // F9 key
-         "vbKeyF10" => Some(Value::Integer( 121)),          // F10 key
-         "vbKeyF11" => Some(Value::Integer( 122)),          // F11 key
-         "vbKeyF12" => Some(Value::Integer( 123)),          // F12 key
-         "vbKeyF13" => Some(Value::Integer( 124)),          // F13 key
-         "vbKeyF14" => Some(Value::Integer( 125)),          // F14 key
-         "vbKeyF15" => Some(Value::Integer( 126)),          // F15 key
-         "vbKeyF16" => Some(Value::Integer( 127)),          // F16 key
-
-         
-         // MsgBox constants
-         "vbOKOnly" => Some(Value::Integer( 0)),
-         "vbOKCancel" => Some(Value::Integer( 1)),
-         "vbAbortRetryIgnore" => Some(Value::Integer( 2)),
-         "vbYesNoCancel" => Some(Value::Integer( 3)),
-         "vbYesNo" => Some(Value::Integer( 4)),
-         "vbRetryCancel" => Some(Value::Integer( 5)),
-
-         // MsgBox icon constants
-         "vbCritical" => Some(Value::Integer( 16)),
-         "vbQuestion" => Some(Value::Integer( 32)),
-         "vbExclamation" => Some(Value::Integer( 48)),
-         "vbInformation" => Some(Value::Integer( 64)),
-
-         // MsgBox return Some(Values
-         "vbOK" => Some(Value::Integer( 1)),
-         "vbCancel" => Some(Value::Integer( 2)),
-         "vbAbort" => Some(Value::Integer( 3)),
-         "vbRetry" => Some(Value::Integer( 4)),
-         "vbIgnore" => Some(Value::Integer( 5)),
-         "vbYes" => Some(Value::Integer( 6)),
-         "vbNo" => Some(Value::Integer( 7)),
-
-          // Text case constants
-         "vbUpperCase"   => Some(Value::Integer( 1)),
-         "vbLowerCase"   => Some(Value::Integer( 2)),
-         "vbProperCase"  => Some(Value::Integer( 3)),
-
-         // String width and script constants
-         "vbWide"        => Some(Value::Integer( 4)),
-         "vbNarrow"      => Some(Value::Integer( 8)),
-         "vbKatakana"    => Some(Value::Integer( 16)),
-         "vbHiragana"    => Some(Value::Integer( 32)),
-
-         // Unicode constants
-         "vbUnicode"     => Some(Value::Integer( -1)),
-         "vbFromUnicode" => Some(Value::Integer( -2)),
-
-         "vbTrue" => Some(Value::Integer( -1)),
-         "vbFalse" => Some(Value::Integer( 0)),
-         "vbUseDefault" => Some(Value::Integer( 2)),
-
-        
-
-         "vbEmpty" => Some(Value::Integer( 0)),
-         "vbNull" => Some(Value::Integer( 1)),
-         "vbInteger" => Some(Value::Integer( 2)),
-         "vbLong" => Some(Value::Integer( 3)),
-         "vbSingle" => Some(Value::Integer( 4)),
-         "vbDouble" => Some(Value::Integer( 5)),
-         "vbCurrency" => Some(Value::Integer( 6)),
-         "vbDate" => Some(Value::Integer( 7)),
-         "vbString" => Some(Value::Integer( 8)),
-         "vbObject" => Some(Value::Integer( 9)),
-         "vbError" => Some(Value::Integer( 10)),
-         "vbBoolean" => Some(Value::Integer( 11)),
-         "vbVariant" => Some(Value::Integer( 12)),
-         "vbDataObject" => Some(Value::Integer( 13)),
-         "vbDecimal" => Some(Value::Integer( 14)),
-         "vbByte" => Some(Value::Integer( 17)),
-         "vbUserDefinedType" => Some(Value::Integer( 36)),
-         "vbArray" => Some(Value::Integer( 8192)),
-
-
-
-         "vbCrLf"       => Some(Value::String( "\r\n".to_string())),
-         "vbCr"         => Some(Value::String( "\r".to_string())),
-         "vbLf"         => Some(Value::String( "\n".to_string())),
-         "vbNewLine"    => Some(Value::String( "\n".to_string())),       // same as vbLf in many contexts
-         "vbNullChar"   => Some(Value::String( '\0'.to_string())),       // null character
-         "vbNullString" => Some(Value::String( "".to_string())),         // empty string
-         "vbObjectError"=> Some(Value::Integer( -2147221504)), // typical COM error base (example Some(Value)
-         "vbTab"        => Some(Value::String( "\t".to_string())),
-         "vbBack"       => Some(Value::String( '\x08'.to_string())),     // backspace character
-         "vbFormFeed"   => Some(Value::String( '\x0C'.to_string())),     // form feed character
-         "vbVerticalTab"=> Some(Value::String( '\x0B'.to_string())),     // vertical tab character
-
-        // ====================================================================
-        // EXCEL CONSTANTS (xl*)
-        // ====================================================================
-
-        // XlHAlign - Horizontal Alignment
-        "xlHAlignCenter" => Some(Value::Integer(-4108)),
-        "xlHAlignCenterAcrossSelection" => Some(Value::Integer(7)),
-        "xlHAlignDistributed" => Some(Value::Integer(-4117)),
-        "xlHAlignFill" => Some(Value::Integer(5)),
-        "xlHAlignGeneral" => Some(Value::Integer(1)),
-        "xlHAlignJustify" => Some(Value::Integer(-4130)),
-        "xlHAlignLeft" => Some(Value::Integer(-4131)),
-        "xlHAlignRight" => Some(Value::Integer(-4152)),
-        
-        // XlVAlign - Vertical Alignment
-        "xlVAlignBottom" => Some(Value::Integer(-4107)),
-        "xlVAlignCenter" => Some(Value::Integer(-4108)),
-        "xlVAlignDistributed" => Some(Value::Integer(-4117)),
-        "xlVAlignJustify" => Some(Value::Integer(-4130)),
-        "xlVAlignTop" => Some(Value::Integer(-4160)),
-        
-        // Common alignment shortcuts (used in VBA)
-        "xlLeft" => Some(Value::Integer(-4131)),
-        "xlCenter" => Some(Value::Integer(-4108)),
-        "xlRight" => Some(Value::Integer(-4152)),
-        "xlTop" => Some(Value::Integer(-4160)),
-        "xlBottom" => Some(Value::Integer(-4107)),
-        "xlGeneral" => Some(Value::Integer(1)),
-        "xlJustify" => Some(Value::Integer(-4130)),
-
-        // XlBordersIndex - Border edges
-        "xlDiagonalDown" => Some(Value::Integer(5)),
-        "xlDiagonalUp" => Some(Value::Integer(6)),
-        "xlEdgeBottom" => Some(Value::Integer(9)),
-        "xlEdgeLeft" => Some(Value::Integer(7)),
-        "xlEdgeRight" => Some(Value::Integer(10)),
-        "xlEdgeTop" => Some(Value::Integer(8)),
-        "xlInsideHorizontal" => Some(Value::Integer(12)),
-        "xlInsideVertical" => Some(Value::Integer(11)),
-
-        // XlLineStyle - Border line styles
-        "xlContinuous" => Some(Value::Integer(1)),
-        "xlDash" => Some(Value::Integer(-4115)),
-        "xlDashDot" => Some(Value::Integer(4)),
-        "xlDashDotDot" => Some(Value::Integer(5)),
-        "xlDot" => Some(Value::Integer(-4118)),
-        "xlDouble" => Some(Value::Integer(-4119)),
-        "xlLineStyleNone" => Some(Value::Integer(-4142)),
-        "xlSlantDashDot" => Some(Value::Integer(13)),
-
-        // XlBorderWeight - Border thickness
-        "xlHairline" => Some(Value::Integer(1)),
-        "xlMedium" => Some(Value::Integer(-4138)),
-        "xlThick" => Some(Value::Integer(4)),
-        "xlThin" => Some(Value::Integer(2)),
-
-        // XlPattern - Interior/fill patterns
-        "xlPatternAutomatic" => Some(Value::Integer(-4105)),
-        "xlPatternChecker" => Some(Value::Integer(9)),
-        "xlPatternCrissCross" => Some(Value::Integer(16)),
-        "xlPatternDown" => Some(Value::Integer(-4121)),
-        "xlPatternGray16" => Some(Value::Integer(17)),
-        "xlPatternGray25" => Some(Value::Integer(-4124)),
-        "xlPatternGray50" => Some(Value::Integer(-4125)),
-        "xlPatternGray75" => Some(Value::Integer(-4126)),
-        "xlPatternGray8" => Some(Value::Integer(18)),
-        "xlPatternGrid" => Some(Value::Integer(15)),
-        "xlPatternHorizontal" => Some(Value::Integer(-4128)),
-        "xlPatternLightDown" => Some(Value::Integer(13)),
-        "xlPatternLightHorizontal" => Some(Value::Integer(11)),
-        "xlPatternLightUp" => Some(Value::Integer(14)),
-        "xlPatternLightVertical" => Some(Value::Integer(12)),
-        "xlPatternNone" => Some(Value::Integer(-4142)),
-        "xlPatternSemiGray75" => Some(Value::Integer(10)),
-        "xlPatternSolid" => Some(Value::Integer(1)),
-        "xlPatternUp" => Some(Value::Integer(-4162)),
-        "xlPatternVertical" => Some(Value::Integer(-4166)),
-
-        // XlPasteType - Paste operations
-        "xlPasteAll" => Some(Value::Integer(-4104)),
-        "xlPasteAllExceptBorders" => Some(Value::Integer(7)),
-        "xlPasteAllMergingConditionalFormats" => Some(Value::Integer(14)),
-        "xlPasteAllUsingSourceTheme" => Some(Value::Integer(13)),
-        "xlPasteColumnWidths" => Some(Value::Integer(8)),
-        "xlPasteComments" => Some(Value::Integer(-4144)),
-        "xlPasteFormats" => Some(Value::Integer(-4122)),
-        "xlPasteFormulas" => Some(Value::Integer(-4123)),
-        "xlPasteFormulasAndNumberFormats" => Some(Value::Integer(11)),
-        "xlPasteValidation" => Some(Value::Integer(6)),
-        "xlPasteValues" => Some(Value::Integer(-4163)),
-        "xlPasteValuesAndNumberFormats" => Some(Value::Integer(12)),
-
-        // XlPasteSpecialOperation - Paste special math operations
-        "xlPasteSpecialOperationAdd" => Some(Value::Integer(2)),
-        "xlPasteSpecialOperationDivide" => Some(Value::Integer(5)),
-        "xlPasteSpecialOperationMultiply" => Some(Value::Integer(4)),
-        "xlPasteSpecialOperationNone" => Some(Value::Integer(-4142)),
-        "xlPasteSpecialOperationSubtract" => Some(Value::Integer(3)),
-
-        // XlInsertShiftDirection - Insert cells shift direction
-        "xlShiftDown" => Some(Value::Integer(-4121)),
-        "xlShiftToRight" => Some(Value::Integer(-4161)),
-
-        // XlDeleteShiftDirection - Delete cells shift direction  
-        "xlShiftToLeft" => Some(Value::Integer(-4159)),
-        "xlShiftUp" => Some(Value::Integer(-4162)),
-
-        // XlDirection - Navigation direction
-        "xlDown" => Some(Value::Integer(-4121)),
-        "xlToLeft" => Some(Value::Integer(-4159)),
-        "xlToRight" => Some(Value::Integer(-4161)),
-        "xlUp" => Some(Value::Integer(-4162)),
-
-        // XlCellType - SpecialCells types
-        "xlCellTypeAllFormatConditions" => Some(Value::Integer(-4172)),
-        "xlCellTypeAllValidation" => Some(Value::Integer(-4174)),
-        "xlCellTypeBlanks" => Some(Value::Integer(4)),
-        "xlCellTypeComments" => Some(Value::Integer(-4144)),
-        "xlCellTypeConstants" => Some(Value::Integer(2)),
-        "xlCellTypeFormulas" => Some(Value::Integer(-4123)),
-        "xlCellTypeLastCell" => Some(Value::Integer(11)),
-        "xlCellTypeSameFormatConditions" => Some(Value::Integer(-4173)),
-        "xlCellTypeSameValidation" => Some(Value::Integer(-4175)),
-        "xlCellTypeVisible" => Some(Value::Integer(12)),
-
-        // XlSpecialCellsValue - For SpecialCells with xlCellTypeConstants/xlCellTypeFormulas
-        "xlErrors" => Some(Value::Integer(16)),
-        "xlLogical" => Some(Value::Integer(4)),
-        "xlNumbers" => Some(Value::Integer(1)),
-        "xlTextValues" => Some(Value::Integer(2)),
-
-        // XlFillStyle - AutoFill types
-        "xlFillCopy" => Some(Value::Integer(1)),
-        "xlFillDays" => Some(Value::Integer(5)),
-        "xlFillDefault" => Some(Value::Integer(0)),
-        "xlFillFormats" => Some(Value::Integer(3)),
-        "xlFillMonths" => Some(Value::Integer(7)),
-        "xlFillSeries" => Some(Value::Integer(2)),
-        "xlFillValues" => Some(Value::Integer(4)),
-        "xlFillWeekdays" => Some(Value::Integer(6)),
-        "xlFillYears" => Some(Value::Integer(8)),
-        "xlGrowthTrend" => Some(Value::Integer(10)),
-        "xlLinearTrend" => Some(Value::Integer(9)),
-
-        // XlSortOrder - Sort order
-        "xlAscending" => Some(Value::Integer(1)),
-        "xlDescending" => Some(Value::Integer(2)),
-
-        // XlSortOrientation - Sort direction
-        "xlSortColumns" => Some(Value::Integer(1)),
-        "xlSortRows" => Some(Value::Integer(2)),
-
-        // XlYesNoGuess - Headers in sort/filter
-        "xlGuess" => Some(Value::Integer(0)),
-        "xlNo" => Some(Value::Integer(2)),
-        "xlYes" => Some(Value::Integer(1)),
-
-        // XlAutoFilterOperator - AutoFilter operators
-        "xlAnd" => Some(Value::Integer(1)),
-        "xlBottom10Items" => Some(Value::Integer(4)),
-        "xlBottom10Percent" => Some(Value::Integer(6)),
-        "xlFilterCellColor" => Some(Value::Integer(8)),
-        "xlFilterDynamic" => Some(Value::Integer(11)),
-        "xlFilterFontColor" => Some(Value::Integer(9)),
-        "xlFilterIcon" => Some(Value::Integer(10)),
-        "xlFilterValues" => Some(Value::Integer(7)),
-        "xlOr" => Some(Value::Integer(2)),
-        "xlTop10Items" => Some(Value::Integer(3)),
-        "xlTop10Percent" => Some(Value::Integer(5)),
-
-        // XlCalculation - Calculation modes
-        "xlCalculationAutomatic" => Some(Value::Integer(-4105)),
-        "xlCalculationManual" => Some(Value::Integer(-4135)),
-        "xlCalculationSemiautomatic" => Some(Value::Integer(2)),
-
-        // XlLookAt - Find/Replace match type
-        "xlPart" => Some(Value::Integer(2)),
-        "xlWhole" => Some(Value::Integer(1)),
-
-        // XlSearchOrder - Find/Replace search order
-        "xlByColumns" => Some(Value::Integer(2)),
-        "xlByRows" => Some(Value::Integer(1)),
-
-        // XlSearchDirection - Find direction
-        "xlNext" => Some(Value::Integer(1)),
-        "xlPrevious" => Some(Value::Integer(2)),
-
-        // XlOrientation - Text orientation
-        "xlDownward" => Some(Value::Integer(-4170)),
-        "xlHorizontal" => Some(Value::Integer(-4128)),
-        "xlUpward" => Some(Value::Integer(-4171)),
-        "xlVertical" => Some(Value::Integer(-4166)),
-
-        // XlUnderlineStyle - Font underline styles
-        "xlUnderlineStyleDouble" => Some(Value::Integer(-4119)),
-        "xlUnderlineStyleDoubleAccounting" => Some(Value::Integer(5)),
-        "xlUnderlineStyleNone" => Some(Value::Integer(-4142)),
-        "xlUnderlineStyleSingle" => Some(Value::Integer(2)),
-        "xlUnderlineStyleSingleAccounting" => Some(Value::Integer(4)),
-
-        // XlFileFormat - Workbook file formats (common ones)
-        "xlCSV" => Some(Value::Integer(6)),
-        "xlCurrentPlatformText" => Some(Value::Integer(-4158)),
-        "xlExcel8" => Some(Value::Integer(56)),
-        "xlHtml" => Some(Value::Integer(44)),
-        "xlOpenXMLWorkbook" => Some(Value::Integer(51)),
-        "xlOpenXMLWorkbookMacroEnabled" => Some(Value::Integer(52)),
-        "xlTextWindows" => Some(Value::Integer(20)),
-        "xlWorkbookDefault" => Some(Value::Integer(51)),
-        "xlWorkbookNormal" => Some(Value::Integer(-4143)),
-
-        // XlSheetType - Worksheet types
-        "xlChart" => Some(Value::Integer(-4109)),
-        "xlDialogSheet" => Some(Value::Integer(-4116)),
-        "xlExcel4IntlMacroSheet" => Some(Value::Integer(4)),
-        "xlExcel4MacroSheet" => Some(Value::Integer(3)),
-        "xlWorksheet" => Some(Value::Integer(-4167)),
-
-        // XlWindowState - Window state
-        "xlMaximized" => Some(Value::Integer(-4137)),
-        "xlMinimized" => Some(Value::Integer(-4140)),
-        "xlNormal" => Some(Value::Integer(-4143)),
-
-        // XlPageOrientation - Print orientation
-        "xlLandscape" => Some(Value::Integer(2)),
-        "xlPortrait" => Some(Value::Integer(1)),
-
-        // XlPaperSize - Common paper sizes
-        "xlPaperA4" => Some(Value::Integer(9)),
-        "xlPaperLetter" => Some(Value::Integer(1)),
-        "xlPaperLegal" => Some(Value::Integer(5)),
-
-        // XlReferenceStyle - Formula reference style
-        "xlA1" => Some(Value::Integer(1)),
-        "xlR1C1" => Some(Value::Integer(-4150)),
-
-        // XlCopyPictureFormat - CopyPicture format
-        "xlBitmap" => Some(Value::Integer(2)),
-        "xlPicture" => Some(Value::Integer(-4147)),
-
-        // XlPictureAppearance - CopyPicture appearance
-        "xlPrinter" => Some(Value::Integer(2)),
-        "xlScreen" => Some(Value::Integer(1)),
-
-        // XlFormatConditionType - Conditional formatting types
-        "xlAboveAverageCondition" => Some(Value::Integer(12)),
-        "xlBlanksCondition" => Some(Value::Integer(10)),
-        "xlCellValue" => Some(Value::Integer(1)),
-        "xlColorScale" => Some(Value::Integer(3)),
-        "xlDatabar" => Some(Value::Integer(4)),
-        "xlErrorsCondition" => Some(Value::Integer(16)),
-        "xlExpression" => Some(Value::Integer(2)),
-        "xlIconSet" => Some(Value::Integer(6)),
-        "xlNoBlanksCondition" => Some(Value::Integer(13)),
-        "xlNoErrorsCondition" => Some(Value::Integer(17)),
-        "xlTextString" => Some(Value::Integer(9)),
-        "xlTimePeriod" => Some(Value::Integer(11)),
-        "xlTop10" => Some(Value::Integer(5)),
-        "xlUniqueValues" => Some(Value::Integer(8)),
-
-        // XlFormatConditionOperator - Conditional formatting operators
-        "xlBetween" => Some(Value::Integer(1)),
-        "xlEqual" => Some(Value::Integer(3)),
-        "xlGreater" => Some(Value::Integer(5)),
-        "xlGreaterEqual" => Some(Value::Integer(7)),
-        "xlLess" => Some(Value::Integer(6)),
-        "xlLessEqual" => Some(Value::Integer(8)),
-        "xlNotBetween" => Some(Value::Integer(2)),
-        "xlNotEqual" => Some(Value::Integer(4)),
-
-        // Miscellaneous common constants
-        "xlNone" => Some(Value::Integer(-4142)),
-        "xlAutomatic" => Some(Value::Integer(-4105)),
-        "xlManual" => Some(Value::Integer(-4135)),
-
-        // Creator code (Excel's application signature)
-        "xlCreatorCode" => Some(Value::Integer(1480803660)),
-
-        // VarType constants - used by VarType() function
-        "vbEmpty" => Some(Value::Integer(0)),
-        "vbNull" => Some(Value::Integer(1)),
-        "vbInteger" => Some(Value::Integer(2)),
-        "vbLong" => Some(Value::Integer(3)),
-        "vbSingle" => Some(Value::Integer(4)),
-        "vbDouble" => Some(Value::Integer(5)),
-        "vbCurrency" => Some(Value::Integer(6)),
-        "vbDate" => Some(Value::Integer(7)),
-        "vbString" => Some(Value::Integer(8)),
-        "vbObject" => Some(Value::Integer(9)),
-        "vbError" => Some(Value::Integer(10)),
-        "vbBoolean" => Some(Value::Integer(11)),
-        "vbVariant" => Some(Value::Integer(12)),
-        "vbDataObject" => Some(Value::Integer(13)),
-        "vbDecimal" => Some(Value::Integer(14)),
-        "vbByte" => Some(Value::Integer(17)),
-        "vbLongLong" => Some(Value::Integer(20)),
-        "vbUserDefinedType" => Some(Value::Integer(36)),
-        "vbArray" => Some(Value::Integer(8192)),
- 
-        // Empty and Null - VBA builtin values
+        // Empty, Null and Nothing - VBA builtin values
         "Empty" => Some(Value::Empty),
         "Null" => Some(Value::Null),
+        "Nothing" => Some(Value::Object(None)),
 
         // Date - returns today's date as a Date value
         "Date" => {
@@ -551,9 +796,26 @@ pub(crate) fn resolve_builtin_identifier(name: &str) -> Option<Value> {
             Some(Value::Date(today))
         }
 
-        _ => {
-            //println!("⚠️ Unknown builtin constant: {}", name);
-            return None;
-        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_lookup() {
+        let mut names: Vec<String> = TABLE.iter().map(|c| c.name.to_ascii_lowercase()).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), TABLE.len(), "duplicate constant names");
+
+        assert_eq!(lookup("VBCRLF").map(|v| v.as_string()), Some("\r\n".to_string()));
+        assert!(matches!(lookup("xlshiftdown"), Some(Value::Integer(n)) if n == xlShiftDown));
+        assert_eq!(constant("vbYesNo").map(|c| c.group), Some("VbMsgBoxStyle"));
+        assert!(matches!(lookup("vbUseDefault"), Some(Value::Integer(-2))));
+        assert!(lookup("vbNotAConstant").is_none());
+        assert!(matches!(resolve_builtin_identifier("Null"), Some(Value::Null)));
     }
-}
\ No newline at end of file
+}
@@ -2,7 +2,7 @@
 //! 
 //! This module contains all VBA type conversion functions including:
 //! - CBool, CByte, CCur, CDate, CDbl, CDec
-//! - CInt, CLng, CLngLng, CSng, CStr, CVar
+//! - CInt, CLng, CLngLng, CLngPtr, CSng, CStr, CVar
 //! - CVDate, CVErr
 //! - Error, Error$
 //! - Str, Str$
@@ -12,7 +12,8 @@ use chrono::NaiveDate;
 use crate::ast::Expression;
 use crate::context::{Context, Value};
 use crate::interpreter::evaluate_expression;
-use super::common::value_to_string;
+use crate::vba_errors::VbaErr;
+use super::common::{null_argument, value_to_string};
 
 /// Handle type conversion builtin function calls
 pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ctx: &mut Context) -> Result<Option<Value>> {
@@ -27,6 +28,9 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
                 return Ok(Some(Value::String(String::new())));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
             Ok(Some(Value::String(value_to_string(&val))))
         }
 
@@ -40,6 +44,9 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
                 return Ok(Some(Value::Integer(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
             let f = value_to_f64(&val);
             // VBA rounds to nearest even (banker's rounding)
             Ok(Some(Value::Integer(f.round() as i64)))
@@ -51,8 +58,14 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
                 return Ok(Some(Value::Long(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
-            let f = value_to_f64(&val);
-            Ok(Some(Value::Long(f.round() as i32)))
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
+            let f = value_to_f64(&val).round();
+            if !(i32::MIN as f64..=i32::MAX as f64).contains(&f) {
+                return Err(VbaErr::Overflow.error(""));
+            }
+            Ok(Some(Value::Long(f as i32)))
         }
 
         // CLNGLNG — Convert to LongLong (64-bit)
@@ -61,8 +74,24 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
                 return Ok(Some(Value::LongLong(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
-            let f = value_to_f64(&val);
-            Ok(Some(Value::LongLong(f.round() as i64)))
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
+            let f = value_to_f64(&val).round();
+            // i64::MAX as f64 rounds up to 2^63, which no longer fits
+            if !(i64::MIN as f64..i64::MAX as f64).contains(&f) {
+                return Err(VbaErr::Overflow.error(""));
+            }
+            Ok(Some(Value::LongLong(f as i64)))
+        }
+
+        // CLNGPTR — Long or LongLong, whichever LongPtr is (see RuntimeConfig::pointer_type)
+        "clngptr" => {
+            let target = match ctx.runtime_config.pointer_type() {
+                crate::context::DeclaredType::LongLong => "clnglng",
+                _ => "clng",
+            };
+            handle_conversion_function(target, args, ctx)
         }
 
         // CBYTE — Convert to Byte (0-255)
@@ -71,6 +100,9 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
                 return Ok(Some(Value::Byte(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
             let f = value_to_f64(&val);
             let byte_val = f.round().clamp(0.0, 255.0) as u8;
             Ok(Some(Value::Byte(byte_val)))
@@ -86,6 +118,9 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
                 return Ok(Some(Value::Double(0.0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
             let f = value_to_f64(&val);
             Ok(Some(Value::Double(f)))
         }
@@ -96,6 +131,9 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
                 return Ok(Some(Value::Single(0.0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
             let f = value_to_f64(&val);
             Ok(Some(Value::Single(f as f32)))
         }
@@ -106,6 +144,9 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
                 return Ok(Some(Value::Decimal(0.0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
             let f = value_to_f64(&val);
             Ok(Some(Value::Decimal(f)))
         }
@@ -120,6 +161,9 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
                 return Ok(Some(Value::Currency(0.0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
             let f = value_to_f64(&val);
             // Currency has 4 decimal places
             let rounded = (f * 10000.0).round() / 10000.0;
@@ -132,6 +176,9 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
                 return Ok(Some(Value::Boolean(false)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
             let b = match val {
                 Value::Boolean(b) => b,
                 Value::Integer(i) => i != 0,
@@ -156,6 +203,9 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
                 return Ok(Some(Value::Empty));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
             match val {
                 Value::Date(d) => Ok(Some(Value::Date(d))),
                 Value::String(s) => {
@@ -177,7 +227,7 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
                 Value::Integer(i) => {
                     // VBA serial date (days since Dec 30, 1899)
                     let base = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap();
-                    if let Some(date) = base.checked_add_signed(chrono::Duration::days(i)) {
+                    if let Some(date) = chrono::Duration::try_days(i).and_then(|delta| base.checked_add_signed(delta)) {
                         Ok(Some(Value::Date(date)))
                     } else {
                         Ok(Some(Value::Empty))
@@ -185,7 +235,7 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
                 }
                 Value::Double(d) => {
                     let base = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap();
-                    if let Some(date) = base.checked_add_signed(chrono::Duration::days(d.trunc() as i64)) {
+                    if let Some(date) = chrono::Duration::try_days(d.trunc() as i64).and_then(|delta| base.checked_add_signed(delta)) {
                         Ok(Some(Value::Date(date)))
                     } else {
                         Ok(Some(Value::Empty))
@@ -234,7 +284,7 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
                 }
                 Value::Integer(i) => {
                     let base = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap();
-                    if let Some(date) = base.checked_add_signed(chrono::Duration::days(i)) {
+                    if let Some(date) = chrono::Duration::try_days(i).and_then(|delta| base.checked_add_signed(delta)) {
                         Ok(Some(Value::Date(date)))
                     } else {
                         Ok(Some(Value::Empty))
@@ -242,7 +292,7 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
                 }
                 Value::Double(d) => {
                     let base = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap();
-                    if let Some(date) = base.checked_add_signed(chrono::Duration::days(d.trunc() as i64)) {
+                    if let Some(date) = chrono::Duration::try_days(d.trunc() as i64).and_then(|delta| base.checked_add_signed(delta)) {
                         Ok(Some(Value::Date(date)))
                     } else {
                         Ok(Some(Value::Empty))
@@ -259,11 +309,15 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
             }
             let val = evaluate_expression(&args[0], ctx)?;
             let err_num = match val {
-                Value::Integer(i) => i as i32,
-                Value::Long(l) => l,
-                Value::Double(d) => d as i32,
-                _ => 0
+                Value::Error(e) => e,
+                Value::String(_) | Value::Empty | Value::Null | Value::Object(_) | Value::UserType { .. } => {
+                    return Err(VbaErr::TypeMismatch.error(""));
+                }
+                other => crate::interpreter::coerce::to_i64(&other)? as i32,
             };
+            if !(0..=65535).contains(&err_num) {
+                return Err(VbaErr::InvalidProcedureCall.error(""));
+            }
             Ok(Some(Value::Error(err_num)))
         }
 
@@ -271,21 +325,21 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
         // ERROR MESSAGE FUNCTIONS
         // ============================================================
 
-        // ERROR / ERROR$ — Returns error message for error number
+        // ERROR / ERROR$ — Error(13) = "Type mismatch"; with no argument,
+        // the description of the most recent run-time error
         "error" | "error$" => {
             if args.is_empty() {
-                // Return current error message (if any)
-                return Ok(Some(Value::String(String::new())));
+                let description = ctx.err.as_ref().map(|e| e.description.clone()).unwrap_or_default();
+                return Ok(Some(Value::String(description)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
-            let err_num = match val {
-                Value::Integer(i) => i as i32,
-                Value::Long(l) => l,
-                Value::Double(d) => d as i32,
-                _ => 0
-            };
-            let msg = get_error_message(err_num);
-            Ok(Some(Value::String(msg)))
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
+            let err_num = crate::interpreter::coerce::to_i64(&val)?;
+            let err_num = i32::try_from(err_num)
+                .map_err(|_| VbaErr::Overflow.error(""))?;
+            Ok(Some(Value::String(crate::vba_errors::description(err_num).to_string())))
         }
 
         // ============================================================
@@ -298,6 +352,9 @@ pub(crate) fn handle_conversion_function(function: &str, args: &[Expression], ct
                 return Ok(Some(Value::String(String::new())));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             let f = value_to_f64(&val);
             // VBA Str adds a leading space for positive numbers
             let result = if f >= 0.0 {
@@ -330,97 +387,3 @@ fn value_to_f64(val: &Value) -> f64 {
         _ => 0.0
     }
 }
-
-/// Get VBA error message for error number
-fn get_error_message(err_num: i32) -> String {
-    match err_num {
-        0 => String::new(),
-        3 => "Return without GoSub".to_string(),
-        5 => "Invalid procedure call or argument".to_string(),
-        6 => "Overflow".to_string(),
-        7 => "Out of memory".to_string(),
-        9 => "Subscript out of range".to_string(),
-        10 => "This array is fixed or temporarily locked".to_string(),
-        11 => "Division by zero".to_string(),
-        13 => "Type mismatch".to_string(),
-        14 => "Out of string space".to_string(),
-        16 => "Expression too complex".to_string(),
-        17 => "Can't perform requested operation".to_string(),
-        18 => "User interrupt occurred".to_string(),
-        20 => "Resume without error".to_string(),
-        28 => "Out of stack space".to_string(),
-        35 => "Sub or Function not defined".to_string(),
-        48 => "Error in loading DLL".to_string(),
-        49 => "Bad DLL calling convention".to_string(),
-        51 => "Internal error".to_string(),
-        52 => "Bad file name or number".to_string(),
-        53 => "File not found".to_string(),
-        54 => "Bad file mode".to_string(),
-        55 => "File already open".to_string(),
-        57 => "Device I/O error".to_string(),
-        58 => "File already exists".to_string(),
-        59 => "Bad record length".to_string(),
-        61 => "Disk full".to_string(),
-        62 => "Input past end of file".to_string(),
-        63 => "Bad record number".to_string(),
-        67 => "Too many files".to_string(),
-        68 => "Device unavailable".to_string(),
-        70 => "Permission denied".to_string(),
-        71 => "Disk not ready".to_string(),
-        74 => "Can't rename with different drive".to_string(),
-        75 => "Path/File access error".to_string(),
-        76 => "Path not found".to_string(),
-        91 => "Object variable or With block variable not set".to_string(),
-        92 => "For loop not initialized".to_string(),
-        93 => "Invalid pattern string".to_string(),
-        94 => "Invalid use of Null".to_string(),
-        97 => "Can't call Friend function on object which is not an instance of defining class".to_string(),
-        98 => "A property or method call cannot include a reference to a private object".to_string(),
-        321 => "Invalid file format".to_string(),
-        322 => "Can't create necessary temporary file".to_string(),
-        325 => "Invalid format in resource file".to_string(),
-        380 => "Invalid property value".to_string(),
-        381 => "Invalid property array index".to_string(),
-        382 => "Set not supported at runtime".to_string(),
-        383 => "Set not supported (read-only property)".to_string(),
-        385 => "Need property array index".to_string(),
-        387 => "Set not permitted".to_string(),
-        393 => "Get not supported at runtime".to_string(),
-        394 => "Get not supported (write-only property)".to_string(),
-        422 => "Property not found".to_string(),
-        423 => "Property or method not found".to_string(),
-        424 => "Object required".to_string(),
-        429 => "ActiveX component can't create object".to_string(),
-        430 => "Class doesn't support Automation or doesn't support expected interface".to_string(),
-        432 => "File name or class name not found during Automation operation".to_string(),
-        438 => "Object doesn't support this property or method".to_string(),
-        440 => "Automation error".to_string(),
-        442 => "Connection to type library or object library for remote process has been lost".to_string(),
-        443 => "Automation object does not have a default value".to_string(),
-        445 => "Object doesn't support this action".to_string(),
-        446 => "Object doesn't support named arguments".to_string(),
-        447 => "Object doesn't support current locale setting".to_string(),
-        448 => "Named argument not found".to_string(),
-        449 => "Argument not optional".to_string(),
-        450 => "Wrong number of arguments or invalid property assignment".to_string(),
-        451 => "Property let procedure not defined and property get procedure did not return an object".to_string(),
-        452 => "Invalid ordinal".to_string(),
-        453 => "Specified DLL function not found".to_string(),
-        454 => "Code resource not found".to_string(),
-        455 => "Code resource lock error".to_string(),
-        457 => "This key is already associated with an element of this collection".to_string(),
-        458 => "Variable uses an Automation type not supported in Visual Basic".to_string(),
-        459 => "Object or class does not support the set of events".to_string(),
-        460 => "Invalid clipboard format".to_string(),
-        461 => "Method or data member not found".to_string(),
-        462 => "The remote server machine does not exist or is unavailable".to_string(),
-        463 => "Class not registered on local machine".to_string(),
-        481 => "Invalid picture".to_string(),
-        482 => "Printer error".to_string(),
-        735 => "Can't save file to TEMP".to_string(),
-        744 => "Search text not found".to_string(),
-        746 => "Replacements too long".to_string(),
-        1004 => "Application-defined or object-defined error".to_string(),
-        _ => format!("Unknown error {}", err_num)
-    }
-}
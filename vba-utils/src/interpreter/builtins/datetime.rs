@@ -11,14 +11,15 @@
 //!
 //! All date/time functions respect the user's timezone configured in
 //! `Context.runtime_config.timezone`. This is set at session start by
-//! the application layer.
+//! the application layer. With a virtual clock (used by Application.OnTime
+//! tests) Now, Date, Time and Timer read that clock instead.
 
 use anyhow::Result;
-use chrono::{Datelike, NaiveDate, Timelike, Utc};
-use chrono::TimeZone as _;  // Extension trait for with_timezone()
+use chrono::{Datelike, NaiveDate, Timelike};
 use crate::ast::Expression;
 use crate::context::{Context, Value};
 use crate::interpreter::evaluate_expression;
+use super::common::null_argument;
 
 /// Handle date/time-related builtin function calls
 pub(crate) fn handle_datetime_function(function: &str, args: &[Expression], ctx: &mut Context) -> Result<Option<Value>> {
@@ -29,33 +30,22 @@ pub(crate) fn handle_datetime_function(function: &str, args: &[Expression], ctx:
 
         // NOW — Returns current date and time in user's timezone
         "now" => {
-            let tz = ctx.runtime_config.timezone;
-            let now_in_tz = Utc::now().with_timezone(&tz);
-            let datetime = now_in_tz.naive_local();
-            Ok(Some(Value::DateTime(datetime)))
+            Ok(Some(Value::DateTime(ctx.now())))
         }
 
         // DATE — Returns the current system date in user's timezone
         "date" => {
-            let tz = ctx.runtime_config.timezone;
-            let now_in_tz = Utc::now().with_timezone(&tz);
-            let today = now_in_tz.date_naive();
-            Ok(Some(Value::Date(today)))
+            Ok(Some(Value::Date(ctx.now().date())))
         }
 
         // TIME — Returns the current system time in user's timezone
         "time" => {
-            let tz = ctx.runtime_config.timezone;
-            let now_in_tz = Utc::now().with_timezone(&tz);
-            let time = now_in_tz.time();
-            Ok(Some(Value::Time(time)))
+            Ok(Some(Value::Time(ctx.now().time())))
         }
 
         // TIMER — Returns seconds since midnight as a Single (float)
         "timer" => {
-            let tz = ctx.runtime_config.timezone;
-            let now_in_tz = Utc::now().with_timezone(&tz);
-            let time = now_in_tz.time();
+            let time = ctx.now().time();
             // Calculate seconds since midnight including fractional seconds
             let seconds = time.hour() as f64 * 3600.0 
                         + time.minute() as f64 * 60.0 
@@ -74,6 +64,9 @@ pub(crate) fn handle_datetime_function(function: &str, args: &[Expression], ctx:
                 return Ok(Some(Value::Integer(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             match val {
                 Value::Date(d) => Ok(Some(Value::Integer(d.year() as i64))),
                 Value::DateTime(dt) => Ok(Some(Value::Integer(dt.date().year() as i64))),
@@ -87,6 +80,9 @@ pub(crate) fn handle_datetime_function(function: &str, args: &[Expression], ctx:
                 return Ok(Some(Value::Integer(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             match val {
                 Value::Date(d) => Ok(Some(Value::Integer(d.month() as i64))),
                 Value::DateTime(dt) => Ok(Some(Value::Integer(dt.date().month() as i64))),
@@ -100,6 +96,9 @@ pub(crate) fn handle_datetime_function(function: &str, args: &[Expression], ctx:
                 return Ok(Some(Value::Integer(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             match val {
                 Value::Date(d) => Ok(Some(Value::Integer(d.day() as i64))),
                 Value::DateTime(dt) => Ok(Some(Value::Integer(dt.date().day() as i64))),
@@ -113,6 +112,9 @@ pub(crate) fn handle_datetime_function(function: &str, args: &[Expression], ctx:
                 return Ok(Some(Value::Integer(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             match val {
                 Value::Date(d) => {
                     // chrono: Mon=0, Sun=6
@@ -134,6 +136,9 @@ pub(crate) fn handle_datetime_function(function: &str, args: &[Expression], ctx:
                 return Ok(Some(Value::Integer(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             match val {
                 Value::DateTime(dt) => Ok(Some(Value::Integer(dt.hour() as i64))),
                 Value::Time(t) => Ok(Some(Value::Integer(t.hour() as i64))),
@@ -148,6 +153,9 @@ pub(crate) fn handle_datetime_function(function: &str, args: &[Expression], ctx:
                 return Ok(Some(Value::Integer(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             match val {
                 Value::DateTime(dt) => Ok(Some(Value::Integer(dt.minute() as i64))),
                 Value::Time(t) => Ok(Some(Value::Integer(t.minute() as i64))),
@@ -162,6 +170,9 @@ pub(crate) fn handle_datetime_function(function: &str, args: &[Expression], ctx:
                 return Ok(Some(Value::Integer(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             match val {
                 Value::DateTime(dt) => Ok(Some(Value::Integer(dt.second() as i64))),
                 Value::Time(t) => Ok(Some(Value::Integer(t.second() as i64))),
@@ -218,7 +229,7 @@ pub(crate) fn handle_datetime_function(function: &str, args: &[Expression], ctx:
                 
                 // Add days (day - 1 because we started at day 1)
                 if let Some(date_with_months) = with_months {
-                    let with_days = date_with_months.checked_add_signed(chrono::Duration::days((day - 1) as i64));
+                    let with_days = chrono::Duration::try_days((day - 1) as i64).and_then(|delta| date_with_months.checked_add_signed(delta));
                     if let Some(final_date) = with_days {
                         return Ok(Some(Value::Date(final_date)));
                     }
@@ -376,7 +387,7 @@ pub(crate) fn handle_datetime_function(function: &str, args: &[Expression], ctx:
                     // Add hours - result is DateTime
                     let base_time = time_opt.unwrap_or_else(|| chrono::NaiveTime::from_hms_opt(0, 0, 0).unwrap());
                     let dt = chrono::NaiveDateTime::new(date, base_time);
-                    if let Some(new_dt) = dt.checked_add_signed(chrono::Duration::hours(number)) {
+                    if let Some(new_dt) = chrono::Duration::try_hours(number).and_then(|delta| dt.checked_add_signed(delta)) {
                         return Ok(Some(Value::DateTime(new_dt)));
                     }
                     return Ok(Some(Value::Empty));
@@ -385,7 +396,7 @@ pub(crate) fn handle_datetime_function(function: &str, args: &[Expression], ctx:
                     // Add minutes - result is DateTime
                     let base_time = time_opt.unwrap_or_else(|| chrono::NaiveTime::from_hms_opt(0, 0, 0).unwrap());
                     let dt = chrono::NaiveDateTime::new(date, base_time);
-                    if let Some(new_dt) = dt.checked_add_signed(chrono::Duration::minutes(number)) {
+                    if let Some(new_dt) = chrono::Duration::try_minutes(number).and_then(|delta| dt.checked_add_signed(delta)) {
                         return Ok(Some(Value::DateTime(new_dt)));
                     }
                     return Ok(Some(Value::Empty));
@@ -394,7 +405,7 @@ pub(crate) fn handle_datetime_function(function: &str, args: &[Expression], ctx:
                     // Add seconds - result is DateTime
                     let base_time = time_opt.unwrap_or_else(|| chrono::NaiveTime::from_hms_opt(0, 0, 0).unwrap());
                     let dt = chrono::NaiveDateTime::new(date, base_time);
-                    if let Some(new_dt) = dt.checked_add_signed(chrono::Duration::seconds(number)) {
+                    if let Some(new_dt) = chrono::Duration::try_seconds(number).and_then(|delta| dt.checked_add_signed(delta)) {
                         return Ok(Some(Value::DateTime(new_dt)));
                     }
                     return Ok(Some(Value::Empty));
@@ -416,11 +427,11 @@ pub(crate) fn handle_datetime_function(function: &str, args: &[Expression], ctx:
                 }
                 "d" => {
                     // Add days
-                    date.checked_add_signed(chrono::Duration::days(number))
+                    chrono::Duration::try_days(number).and_then(|delta| date.checked_add_signed(delta))
                 }
                 "ww" | "w" => {
                     // Add weeks
-                    date.checked_add_signed(chrono::Duration::weeks(number))
+                    chrono::Duration::try_weeks(number).and_then(|delta| date.checked_add_signed(delta))
                 }
                 _ => None
             };
@@ -12,6 +12,7 @@ use anyhow::Result;
 use crate::ast::Expression;
 use crate::context::{Context, Value, ErrObject};
 use crate::interpreter::evaluate_expression;
+use crate::vba_errors;
 
 /// Handle Err() function call (returns default property = Number)
 pub(crate) fn handle_err_function(args: &[Expression], ctx: &mut Context) -> Result<Option<Value>> {
@@ -46,7 +47,7 @@ pub(crate) fn handle_err_method(method: &str, args: &[Expression], ctx: &mut Con
             let description = if args.len() > 2 {
                 evaluate_expression(&args[2], ctx)?.as_string()
             } else {
-                get_default_error_description(number)
+                vba_errors::description(number).to_string()
             };
             
             // HelpFile and HelpContext are ignored (args[3] and args[4])
@@ -57,6 +58,7 @@ pub(crate) fn handle_err_method(method: &str, args: &[Expression], ctx: &mut Con
                 number,
                 description,
                 source,
+                limit: None,
             });
             
             // Return error indication - the calling code should handle this
@@ -77,11 +79,13 @@ pub(crate) fn handle_err_method(method: &str, args: &[Expression], ctx: &mut Con
                 let number = value_to_i32(&evaluate_expression(&args[0], ctx)?);
                 if let Some(ref mut err) = ctx.err {
                     err.number = number;
+                    err.limit = None;
                 } else {
                     ctx.err = Some(ErrObject {
                         number,
                         description: String::new(),
                         source: String::new(),
+                        limit: None,
                     });
                 }
             }
@@ -101,6 +105,7 @@ pub(crate) fn handle_err_method(method: &str, args: &[Expression], ctx: &mut Con
                         number: 0,
                         description: desc,
                         source: String::new(),
+                        limit: None,
                     });
                 }
             }
@@ -120,6 +125,7 @@ pub(crate) fn handle_err_method(method: &str, args: &[Expression], ctx: &mut Con
                         number: 0,
                         description: String::new(),
                         source: src,
+                        limit: None,
                     });
                 }
             }
@@ -150,49 +156,41 @@ fn value_to_i32(val: &Value) -> i32 {
     }
 }
 
-/// Get default error description for common VBA error numbers
-pub fn get_default_error_description(err_num: i32) -> String {
-    match err_num {
-        0 => String::new(),
-        3 => "Return without GoSub".to_string(),
-        5 => "Invalid procedure call or argument".to_string(),
-        6 => "Overflow".to_string(),
-        7 => "Out of memory".to_string(),
-        9 => "Subscript out of range".to_string(),
-        10 => "This array is fixed or temporarily locked".to_string(),
-        11 => "Division by zero".to_string(),
-        13 => "Type mismatch".to_string(),
-        14 => "Out of string space".to_string(),
-        17 => "Can't perform requested operation".to_string(),
-        18 => "User interrupt occurred".to_string(),
-        20 => "Resume without error".to_string(),
-        28 => "Out of stack space".to_string(),
-        35 => "Sub or Function not defined".to_string(),
-        48 => "Error in loading DLL".to_string(),
-        51 => "Internal error".to_string(),
-        52 => "Bad file name or number".to_string(),
-        53 => "File not found".to_string(),
-        54 => "Bad file mode".to_string(),
-        55 => "File already open".to_string(),
-        57 => "Device I/O error".to_string(),
-        58 => "File already exists".to_string(),
-        61 => "Disk full".to_string(),
-        62 => "Input past end of file".to_string(),
-        68 => "Device unavailable".to_string(),
-        70 => "Permission denied".to_string(),
-        71 => "Disk not ready".to_string(),
-        75 => "Path/File access error".to_string(),
-        76 => "Path not found".to_string(),
-        91 => "Object variable or With block variable not set".to_string(),
-        92 => "For loop not initialized".to_string(),
-        94 => "Invalid use of Null".to_string(),
-        424 => "Object required".to_string(),
-        429 => "ActiveX component can't create object".to_string(),
-        438 => "Object doesn't support this property or method".to_string(),
-        440 => "Automation error".to_string(),
-        449 => "Argument not optional".to_string(),
-        450 => "Wrong number of arguments or invalid property assignment".to_string(),
-        1004 => "Application-defined or object-defined error".to_string(),
-        _ => format!("Application-defined or object-defined error {}", err_num)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(name: &str, args: Vec<Expression>) -> Expression {
+        Expression::FunctionCall { function: Box::new(Expression::Identifier(name.into())), args }
+    }
+
+    fn raise(args: Vec<Expression>) -> Expression {
+        let function = Expression::PropertyAccess { obj: Box::new(Expression::Identifier("Err".into())), property: "Raise".into() };
+        Expression::FunctionCall { function: Box::new(function), args }
+    }
+
+    #[test]
+    fn test_error_descriptions() {
+        let mut ctx = Context::new();
+        let mut eval = |expr: Expression| evaluate_expression(&expr, &mut ctx).unwrap().as_string();
+        assert_eq!(eval(call("Error", vec![Expression::Integer(13)])), "Type mismatch");
+        assert_eq!(eval(call("Error$", vec![Expression::Integer(11)])), "Division by zero");
+        assert_eq!(eval(call("Error", vec![Expression::Integer(1000)])), "Application-defined or object-defined error");
+
+        let custom = Expression::BinaryOp {
+            left: Box::new(Expression::Identifier("vbObjectError".into())),
+            op: "+".into(),
+            right: Box::new(Expression::Integer(513)),
+        };
+        assert_eq!(eval(custom.clone()), "-2147220991");
+        assert_eq!(eval(call("Error", vec![custom.clone()])), "Automation error");
+
+        // Err.Raise fills in the standard text; Error() reads it back
+        let _ = evaluate_expression(&raise(vec![Expression::Integer(9)]), &mut ctx);
+        assert_eq!(ctx.err.as_ref().map(|e| e.description.as_str()), Some("Subscript out of range"));
+        let _ = evaluate_expression(&raise(vec![custom]), &mut ctx);
+        assert_eq!(ctx.err.as_ref().map(|e| e.number), Some(vba_errors::VB_OBJECT_ERROR + 513));
+        let description = evaluate_expression(&call("Error", vec![]), &mut ctx).unwrap();
+        assert_eq!(description.as_string(), "Automation error");
     }
 }
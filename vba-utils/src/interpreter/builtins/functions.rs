@@ -30,6 +30,11 @@ use super::common::value_to_string;
 pub(crate) fn handle_builtin_call(function: &str, args: &[Expression], ctx: &mut Context)
     -> Result<Option<Value>>
 {
+    // Functions the embedder registered come before every builtin
+    if let Some(result) = crate::host::functions::call_host_function(function, args, ctx) {
+        return result.map(Some);
+    }
+
     let func_lower = function.to_ascii_lowercase();
     
     // Try each category module in order
@@ -76,7 +81,7 @@ pub(crate) fn handle_builtin_call(function: &str, args: &[Expression], ctx: &mut
         // MSGBOX — allow statement-style and call-style (legacy with logging)
         "msgbox" => {
             let text = if let Some(e0) = args.get(0) {
-                let v = evaluate_expression(e0, ctx)?;
+                let v = crate::interpreter::expressions::value_of(evaluate_expression(e0, ctx)?, ctx)?;
                 value_to_string(&v)
             } else {
                 String::new()
@@ -89,9 +94,15 @@ pub(crate) fn handle_builtin_call(function: &str, args: &[Expression], ctx: &mut
     }
 }
 
-/// Convenience wrapper to match your original `-> bool` usage
-pub(crate) fn handle_builtin_call_bool(function: &str, args: &[Expression], ctx: &mut Context) -> bool {
-    matches!(handle_builtin_call(function, args, ctx), Ok(Some(_)))
+/// Run a builtin as a statement: Ok(true) if `function` is one. Errors
+/// raised with a VBA number are returned; other failures count as "not a
+/// builtin" so the caller can try user procedures.
+pub(crate) fn handle_builtin_call_bool(function: &str, args: &[Expression], ctx: &mut Context) -> Result<bool> {
+    match handle_builtin_call(function, args, ctx) {
+        Ok(Some(_)) => Ok(true),
+        Err(e) if crate::host::excel::error_number(&e).is_some() => Err(e),
+        _ => Ok(false),
+    }
 }
 
 /// Set a property on a Range object
@@ -3,6 +3,7 @@
 //! This module contains all VBA information/type checking functions including:
 //! - IsArray, IsDate, IsEmpty, IsError, IsMissing, IsNull, IsNumeric, IsObject
 //! - VarType, TypeName
+//! - Nz (Access-style Null replacement)
 //! - QBColor, RGB
 
 use anyhow::Result;
@@ -55,6 +56,22 @@ pub(crate) fn handle_information_function(function: &str, args: &[Expression], c
             Ok(Some(Value::Boolean(val.is_null())))
         }
 
+        // NZ — Nz(value, [valueIfNull]): Access-style Null replacement,
+        // common in code ported from Access; Empty when no default is given
+        "nz" => {
+            if args.is_empty() {
+                return Ok(Some(Value::Empty));
+            }
+            let val = evaluate_expression(&args[0], ctx)?;
+            if !val.is_null() {
+                return Ok(Some(val));
+            }
+            match args.get(1) {
+                Some(default) => Ok(Some(evaluate_expression(default, ctx)?)),
+                None => Ok(Some(Value::Empty)),
+            }
+        }
+
         // ISNUMERIC — Returns True if expression can be evaluated as a number
         "isnumeric" => {
             if args.is_empty() {
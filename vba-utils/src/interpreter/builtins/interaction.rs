@@ -3,7 +3,7 @@
 //! This module contains all VBA interaction and control flow functions including:
 //! - IIf — Inline If
 //! - Choose, Switch
-//! - MsgBox, InputBox (stub implementations)
+//! - MsgBox, InputBox (answered by host::ui)
 //! - Shell, Beep, DoEvents
 //! - Environ, CurDir, Dir, Command
 //! - AppActivate, SendKeys, CreateObject, GetObject
@@ -12,7 +12,12 @@ use anyhow::Result;
 use crate::ast::Expression;
 use crate::context::{Context, Value};
 use crate::interpreter::evaluate_expression;
+use crate::interpreter::expressions::value_of;
+use crate::host::behavior::{self, InteractionKind};
+use crate::host::ui;
+use crate::runtime_config::UnknownObjectPolicy;
 use super::common::{get_optional_int, get_optional_string, value_to_string};
+use super::constants;
 
 /// Handle interaction-related builtin function calls
 pub(crate) fn handle_interaction_function(function: &str, args: &[Expression], ctx: &mut Context) -> Result<Option<Value>> {
@@ -123,58 +128,56 @@ pub(crate) fn handle_interaction_function(function: &str, args: &[Expression], c
 
         // MSGBOX — Displays a message in a dialog box
         // MsgBox(Prompt, [Buttons], [Title], [HelpFile], [Context])
-        // Buttons constants:
-        //   vbOKOnly = 0, vbOKCancel = 1, vbAbortRetryIgnore = 2
-        //   vbYesNoCancel = 3, vbYesNo = 4, vbRetryCancel = 5
-        // Return values:
-        //   vbOK = 1, vbCancel = 2, vbAbort = 3, vbRetry = 4
-        //   vbIgnore = 5, vbYes = 6, vbNo = 7
+        // The configured HostUi picks the button; returns its VbMsgBoxResult
         "msgbox" => {
             if args.is_empty() {
-                return Ok(Some(Value::Integer(1))); // vbOK
+                return Ok(Some(Value::Integer(constants::vbOK)));
             }
             
-            let message = evaluate_expression(&args[0], ctx)?;
+            let message = value_of(evaluate_expression(&args[0], ctx)?, ctx)?;
             let message_str = value_to_string(&message);
             
             // Log to context output for testing
             ctx.log(&format!("MsgBox: {}", message_str));
             
             // Get buttons parameter (default 0 = vbOKOnly)
-            let buttons = get_optional_int(args, 1, 0, ctx)?;
-            
-            // Return appropriate default button based on button style
-            // Lower 4 bits determine button configuration
-            let button_type = buttons & 0x0F;
-            let default_return = match button_type {
-                0 => 1,  // vbOKOnly -> vbOK (1)
-                1 => 1,  // vbOKCancel -> vbOK (1)
-                2 => 3,  // vbAbortRetryIgnore -> vbAbort (3)
-                3 => 6,  // vbYesNoCancel -> vbYes (6)
-                4 => 6,  // vbYesNo -> vbYes (6)
-                5 => 4,  // vbRetryCancel -> vbRetry (4)
-                _ => 1,  // Default to vbOK
+            let buttons = get_optional_int(args, 1, constants::vbOKOnly, ctx)?;
+            let title = match args.get(2) {
+                Some(_) => Some(get_optional_string(args, 2, "", ctx)?),
+                None => None,
             };
             
-            Ok(Some(Value::Integer(default_return)))
+            let result = ui::msg_box(ctx, ui::MsgBoxRequest { prompt: message_str, buttons, title });
+            Ok(Some(Value::Integer(result)))
         }
 
         // INPUTBOX — Displays a prompt in a dialog box, waits for user input
         // InputBox(Prompt, [Title], [Default], [XPos], [YPos], [HelpFile], [Context])
         // In non-interactive mode:
         //   1. Returns mock value if set in context
-        //   2. Returns Default parameter if provided
-        //   3. Returns empty string otherwise
+        //   2. Returns the next scripted answer (RuntimeConfig::input_answers)
+        //   3. Asks the HostUi, which by default accepts Default ("" if none)
+        // Cancel returns an empty string
         "inputbox" => {
             // Check if there's a mock input value set in context
             if let Some(mock_value) = ctx.get_var("__INPUT_MOCK__") {
                 return Ok(Some(mock_value.clone()));
             }
             
-            // Get default value (3rd parameter, index 2)
-            let default_value = get_optional_string(args, 2, "", ctx)?;
+            let optional = |index: usize, ctx: &mut Context| -> Result<Option<Value>> {
+                match args.get(index) {
+                    Some(arg) => evaluate_expression(arg, ctx).map(Some),
+                    None => Ok(None),
+                }
+            };
+            let prompt = get_optional_string(args, 0, "", ctx)?;
+            let title = optional(1, ctx)?.map(|v| value_to_string(&v));
+            let default = optional(2, ctx)?.map(|v| value_to_string(&v));
+            let xpos = optional(3, ctx)?.and_then(|v| v.as_integer());
+            let ypos = optional(4, ctx)?.and_then(|v| v.as_integer());
             
-            Ok(Some(Value::String(default_value)))
+            let request = ui::InputBoxRequest { prompt, title, default, xpos, ypos, input_type: None };
+            Ok(Some(Value::String(ui::input_box(ctx, &request).unwrap_or_default())))
         }
 
         // ============================================================
@@ -183,8 +186,8 @@ pub(crate) fn handle_interaction_function(function: &str, args: &[Expression], c
 
         // BEEP — Sounds a tone through the computer's speaker
         "beep" => {
-            // No-op in this implementation (no sound)
-            ctx.log("Beep");
+            // No sound; recorded in the behavior report
+            behavior::perform(ctx, InteractionKind::Beep, "", String::new())?;
             Ok(Some(Value::Empty))
         }
 
@@ -215,6 +218,9 @@ pub(crate) fn handle_interaction_function(function: &str, args: &[Expression], c
                 return Ok(Some(Value::String(String::new())));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if !ctx.runtime_config.expose_environment {
+                return Ok(Some(Value::String(String::new())));
+            }
             let result = match val {
                 Value::String(name) => {
                     // Look up by name
@@ -261,6 +267,9 @@ pub(crate) fn handle_interaction_function(function: &str, args: &[Expression], c
         // COMMAND — Returns the argument portion of the command line
         // Command$ is the string version
         "command" | "command$" => {
+            if !ctx.runtime_config.expose_environment {
+                return Ok(Some(Value::String(String::new())));
+            }
             // Get command line arguments (skip program name)
             let args: Vec<String> = std::env::args().skip(1).collect();
             Ok(Some(Value::String(args.join(" "))))
@@ -269,6 +278,9 @@ pub(crate) fn handle_interaction_function(function: &str, args: &[Expression], c
         // CURDIR — Returns the current path
         // CurDir([drive]) - drive parameter is ignored in modern systems
         "curdir" | "curdir$" => {
+            if !ctx.runtime_config.expose_environment {
+                return Ok(Some(Value::String(String::new())));
+            }
             let path = std::env::current_dir()
                 .map(|p| p.to_string_lossy().to_string())
                 .unwrap_or_default();
@@ -295,11 +307,9 @@ pub(crate) fn handle_interaction_function(function: &str, args: &[Expression], c
         // APPACTIVATE — Activates an application window
         // AppActivate(Title, [Wait])
         "appactivate" => {
-            // No-op - can't activate windows in this context
-            if !args.is_empty() {
-                let title = evaluate_expression(&args[0], ctx)?;
-                ctx.log(&format!("AppActivate (stub): {}", value_to_string(&title)));
-            }
+            // Never touches a window; recorded in the behavior report
+            let title = get_optional_string(args, 0, "", ctx)?;
+            behavior::perform(ctx, InteractionKind::AppActivate, &title, String::new())?;
             Ok(Some(Value::Empty))
         }
 
@@ -307,10 +317,9 @@ pub(crate) fn handle_interaction_function(function: &str, args: &[Expression], c
         // SendKeys(String, [Wait])
         // SECURITY: Disabled - sending keystrokes can be dangerous
         "sendkeys" => {
-            if !args.is_empty() {
-                let keys = evaluate_expression(&args[0], ctx)?;
-                ctx.log(&format!("SendKeys (blocked): {}", value_to_string(&keys)));
-            }
+            let keys = get_optional_string(args, 0, "", ctx)?;
+            let described = behavior::describe_keys(&keys);
+            behavior::perform(ctx, InteractionKind::SendKeys, &keys, described)?;
             Ok(Some(Value::Empty))
         }
 
@@ -322,10 +331,8 @@ pub(crate) fn handle_interaction_function(function: &str, args: &[Expression], c
             }
             let class_name = evaluate_expression(&args[0], ctx)?;
             let class_str = value_to_string(&class_name);
-            ctx.log(&format!("CreateObject (stub): {}", class_str));
-            
-            // Return a stub object
-            Ok(Some(Value::Object(Some(Box::new(Value::String(class_str))))))
+            behavior::perform(ctx, InteractionKind::CreateObject, &class_str, String::new())?;
+            Ok(Some(late_bound_object(&class_str, ctx)?))
         }
 
         // GETOBJECT — Returns a reference to an object provided by an OLE server
@@ -333,18 +340,30 @@ pub(crate) fn handle_interaction_function(function: &str, args: &[Expression], c
         "getobject" => {
             let path = get_optional_string(args, 0, "", ctx)?;
             let class = get_optional_string(args, 1, "", ctx)?;
-            ctx.log(&format!("GetObject (stub): path={}, class={}", path, class));
+            behavior::perform(ctx, InteractionKind::GetObject, &path, class.clone())?;
             
             // Return a stub object or Nothing
             if path.is_empty() && class.is_empty() {
                 Ok(Some(Value::Object(None)))  // Nothing
             } else {
-                Ok(Some(Value::Object(Some(Box::new(Value::String(
-                    if !class.is_empty() { class } else { path }
-                ))))))
+                Ok(Some(late_bound_object(if !class.is_empty() { &class } else { &path }, ctx)?))
             }
         }
 
         _ => Ok(None)
     }
 }
+
+/// Run-time error 70 for an interaction the policy refuses
+/// Object for a ProgID or moniker: Outlook.Application is implemented by
+/// the Outlook host, anything else is a recording stub or error 429,
+/// depending on the runtime configuration
+fn late_bound_object(prog_id: &str, ctx: &Context) -> Result<Value> {
+    if prog_id.eq_ignore_ascii_case("Outlook.Application") {
+        return Ok(crate::host::outlook::objects::application_object());
+    }
+    match ctx.runtime_config.unknown_objects {
+        UnknownObjectPolicy::Record => Ok(crate::host::stub::create(prog_id)),
+        UnknownObjectPolicy::Error => Err(crate::vba_errors::VbaErr::CannotCreateObject.error(prog_id)),
+    }
+}
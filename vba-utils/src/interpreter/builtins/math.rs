@@ -11,6 +11,7 @@ use anyhow::Result;
 use crate::ast::Expression;
 use crate::context::{Context, Value};
 use crate::interpreter::evaluate_expression;
+use super::common::null_argument;
 
 /// Handle math-related builtin function calls
 pub(crate) fn handle_math_function(function: &str, args: &[Expression], ctx: &mut Context) -> Result<Option<Value>> {
@@ -25,6 +26,9 @@ pub(crate) fn handle_math_function(function: &str, args: &[Expression], ctx: &mu
                 return Ok(Some(Value::Integer(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             match val {
                 Value::Integer(i) => Ok(Some(Value::Integer(i.abs()))),
                 Value::Long(l) => Ok(Some(Value::Long(l.abs()))),
@@ -41,6 +45,9 @@ pub(crate) fn handle_math_function(function: &str, args: &[Expression], ctx: &mu
                 return Ok(Some(Value::Integer(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
             let f = match &val {
                 Value::Integer(i) => *i as f64,
                 Value::Long(l) => *l as f64,
@@ -59,6 +66,9 @@ pub(crate) fn handle_math_function(function: &str, args: &[Expression], ctx: &mu
                 return Ok(Some(Value::Integer(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             let f = match &val {
                 Value::Integer(i) => *i as f64,
                 Value::Long(l) => *l as f64,
@@ -76,6 +86,9 @@ pub(crate) fn handle_math_function(function: &str, args: &[Expression], ctx: &mu
                 return Ok(Some(Value::Integer(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             let f = match &val {
                 Value::Integer(i) => *i as f64,
                 Value::Long(l) => *l as f64,
@@ -97,6 +110,9 @@ pub(crate) fn handle_math_function(function: &str, args: &[Expression], ctx: &mu
                 return Ok(Some(Value::Double(0.0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
             let f = match &val {
                 Value::Integer(i) => *i as f64,
                 Value::Long(l) => *l as f64,
@@ -271,6 +287,9 @@ pub(crate) fn handle_math_function(function: &str, args: &[Expression], ctx: &mu
                 return Ok(Some(Value::String(String::new())));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             let n = match val {
                 Value::Integer(i) => i,
                 Value::Long(l) => l as i64,
@@ -286,6 +305,9 @@ pub(crate) fn handle_math_function(function: &str, args: &[Expression], ctx: &mu
                 return Ok(Some(Value::String(String::new())));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             let n = match val {
                 Value::Integer(i) => i,
                 Value::Long(l) => l as i64,
@@ -301,6 +323,9 @@ pub(crate) fn handle_math_function(function: &str, args: &[Expression], ctx: &mu
                 return Ok(Some(Value::Double(0.0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
             match val {
                 Value::String(s) => {
                     // Parse leading numeric portion
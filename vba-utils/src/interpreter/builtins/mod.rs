@@ -1,4 +1,4 @@
-mod constants;
+pub mod constants;
 pub mod functions;
 
 // Category-specific function modules
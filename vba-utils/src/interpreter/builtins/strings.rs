@@ -15,11 +15,12 @@
 //! - StrComp, StrConv
 //! - Format, Format$, FormatCurrency, FormatNumber, FormatPercent
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use crate::ast::Expression;
 use crate::context::{Context, Value};
 use crate::interpreter::evaluate_expression;
-use super::common::value_to_string;
+use super::common::{null_argument, value_to_string};
+use super::constants;
 
 /// Handle string-related builtin function calls
 pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &mut Context) -> Result<Option<Value>> {
@@ -35,6 +36,9 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
                 return Ok(Some(Value::Integer(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             match val {
                 Value::String(s) => Ok(Some(Value::Integer(s.len() as i64))),
                 _ => Ok(Some(Value::Integer(0)))
@@ -47,6 +51,9 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
                 return Ok(Some(Value::Integer(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             match val {
                 Value::String(s) => Ok(Some(Value::Integer((s.len() * 2) as i64))), // UTF-16 bytes
                 _ => Ok(Some(Value::Integer(0)))
@@ -63,6 +70,9 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
                 return Ok(Some(Value::String(String::new())));
             }
             let string_val = evaluate_expression(&args[0], ctx)?;
+            if matches!(string_val, Value::Null) {
+                return null_argument(function, true);
+            }
             let start_val = evaluate_expression(&args[1], ctx)?;
             let s = match string_val { Value::String(s) => s, _ => return Ok(Some(Value::String(String::new()))) };
             let start = match start_val { Value::Integer(i) => (i - 1).max(0) as usize, _ => return Ok(Some(Value::String(String::new()))) };
@@ -105,6 +115,9 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
                 return Ok(Some(Value::String(String::new())));
             }
             let string_val = evaluate_expression(&args[0], ctx)?;
+            if matches!(string_val, Value::Null) {
+                return null_argument(function, true);
+            }
             let length_val = evaluate_expression(&args[1], ctx)?;
             match (string_val, length_val) {
                 (Value::String(s), Value::Integer(len)) => {
@@ -139,6 +152,9 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
                 return Ok(Some(Value::String(String::new())));
             }
             let string_val = evaluate_expression(&args[0], ctx)?;
+            if matches!(string_val, Value::Null) {
+                return null_argument(function, true);
+            }
             let length_val = evaluate_expression(&args[1], ctx)?;
             match (string_val, length_val) {
                 (Value::String(s), Value::Integer(len)) => {
@@ -181,6 +197,9 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
                 return Ok(Some(Value::String(String::new())));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             match val {
                 Value::String(s) => Ok(Some(Value::String(s.to_uppercase()))),
                 _ => Ok(Some(Value::String(String::new())))
@@ -193,6 +212,9 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
                 return Ok(Some(Value::String(String::new())));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             match val {
                 Value::String(s) => Ok(Some(Value::String(s.to_lowercase()))),
                 _ => Ok(Some(Value::String(String::new())))
@@ -209,6 +231,9 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
                 return Ok(Some(Value::String(String::new())));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             match val {
                 Value::String(s) => Ok(Some(Value::String(s.trim().to_string()))),
                 _ => Ok(Some(Value::String(String::new())))
@@ -221,6 +246,9 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
                 return Ok(Some(Value::String(String::new())));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             match val {
                 Value::String(s) => Ok(Some(Value::String(s.trim_start().to_string()))),
                 _ => Ok(Some(Value::String(String::new())))
@@ -233,6 +261,9 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
                 return Ok(Some(Value::String(String::new())));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, true);
+            }
             match val {
                 Value::String(s) => Ok(Some(Value::String(s.trim_end().to_string()))),
                 _ => Ok(Some(Value::String(String::new())))
@@ -292,21 +323,11 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
                 return Ok(Some(Value::Integer(0)));
             }
 
-            let start_idx = ((start - 1).max(0) as usize).min(str1.len());
+            let start_idx = (start - 1).max(0) as usize;
             
-            // Perform search based on compare mode
-            let result = if compare == 1 {
-                // Case-insensitive search
-                let str1_lower = str1.to_lowercase();
-                let str2_lower = str2.to_lowercase();
-                str1_lower[start_idx..].find(&str2_lower)
-            } else {
-                // Case-sensitive search (default)
-                str1[start_idx..].find(&str2)
-            };
-            
-            match result {
-                Some(pos) => Ok(Some(Value::Integer((start_idx + pos + 1) as i64))),
+            // Search by character position; compare == 1 is case-insensitive
+            match find_chars(&str1, &str2, start_idx, compare == 1) {
+                Some(pos) => Ok(Some(Value::Integer((pos + 1) as i64))),
                 None => Ok(Some(Value::Integer(0)))
             }
         }
@@ -339,30 +360,21 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
             let compare = super::common::get_optional_int(args, 3, 0, ctx)?;
             
             if str2.is_empty() {
-                return Ok(Some(Value::Integer(if start < 0 { str1.len() as i64 } else { start })));
+                return Ok(Some(Value::Integer(if start < 0 { str1.chars().count() as i64 } else { start })));
             }
             if str1.is_empty() {
                 return Ok(Some(Value::Integer(0)));
             }
             
-            // Determine search range
-            let search_str = if start < 0 {
-                str1.as_str()
+            // Determine search range (first `start` characters)
+            let search_str: String = if start < 0 {
+                str1.clone()
             } else {
-                let end_idx = (start as usize).min(str1.len());
-                &str1[..end_idx]
+                str1.chars().take(start as usize).collect()
             };
             
-            // Perform reverse search based on compare mode
-            let result = if compare == 1 {
-                // Case-insensitive search
-                let search_lower = search_str.to_lowercase();
-                let str2_lower = str2.to_lowercase();
-                search_lower.rfind(&str2_lower)
-            } else {
-                // Case-sensitive search (default)
-                search_str.rfind(&str2)
-            };
+            // Reverse search by character position; compare == 1 is case-insensitive
+            let result = rfind_chars(&search_str, &str2, compare == 1);
             
             match result {
                 Some(pos) => Ok(Some(Value::Integer((pos + 1) as i64))),
@@ -400,29 +412,30 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
             // Split string at start position
             let prefix: String = expr.chars().take(start_idx).collect();
             let work_str: String = expr.chars().skip(start_idx).collect();
+            if repl.len() > find.len() {
+                let growth = (repl.len() - find.len()) * work_str.matches(find.as_str()).count();
+                ctx.check_allocation(expr.len() + growth)?;
+            }
             
             // Perform replacement based on compare mode
             let result = if compare == 1 {
-                // Case-insensitive replacement
-                let find_lower = find.to_lowercase();
+                // Case-insensitive replacement, walking characters so
+                // multibyte text never splits inside a code point
+                let hay: Vec<char> = work_str.chars().collect();
+                let needle: Vec<char> = find.chars().collect();
                 let mut result = String::new();
-                let mut remaining = work_str.as_str();
                 let mut replacements = 0i64;
+                let mut i = 0;
                 
-                while !remaining.is_empty() {
-                    if count >= 0 && replacements >= count {
-                        result.push_str(remaining);
-                        break;
-                    }
-                    
-                    if let Some(pos) = remaining.to_lowercase().find(&find_lower) {
-                        result.push_str(&remaining[..pos]);
+                while i < hay.len() {
+                    let limit_hit = count >= 0 && replacements >= count;
+                    if !limit_hit && chars_match_at(&hay, &needle, i, true) {
                         result.push_str(&repl);
-                        remaining = &remaining[pos + find.len()..];
+                        i += needle.len();
                         replacements += 1;
                     } else {
-                        result.push_str(remaining);
-                        break;
+                        result.push(hay[i]);
+                        i += 1;
                     }
                 }
                 result
@@ -444,6 +457,9 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
                 return Ok(Some(Value::String(String::new())));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
             match val {
                 Value::String(s) => Ok(Some(Value::String(s.chars().rev().collect()))),
                 _ => Ok(Some(Value::String(String::new())))
@@ -460,6 +476,9 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
                 return Ok(Some(Value::Integer(0)));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
             match val {
                 Value::String(s) => {
                     if let Some(c) = s.chars().next() {
@@ -514,6 +533,9 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
                 return Ok(Some(Value::String(String::new())));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
             let code = match val {
                 Value::Integer(i) => i,
                 Value::Long(l) => l as i64,
@@ -572,11 +594,18 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
                 return Ok(Some(Value::String(String::new())));
             }
             let val = evaluate_expression(&args[0], ctx)?;
+            if matches!(val, Value::Null) {
+                return null_argument(function, false);
+            }
             let count = match val {
                 Value::Integer(i) => i.max(0) as usize,
                 Value::Long(l) => l.max(0) as usize,
                 _ => 0
             };
+            if count > MAX_STRING_LEN {
+                bail!("Out of string space");
+            }
+            ctx.check_allocation(count)?;
             Ok(Some(Value::String(" ".repeat(count))))
         }
 
@@ -600,6 +629,10 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
                 _ => ' '
             };
             
+            if count > MAX_STRING_LEN {
+                bail!("Out of string space");
+            }
+            ctx.check_allocation(count * ch.len_utf8())?;
             Ok(Some(Value::String(ch.to_string().repeat(count))))
         }
 
@@ -651,10 +684,10 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
             let conv = match conv_val { Value::Integer(i) => i, _ => return Ok(Some(Value::String(s))) };
             
             let result = match conv {
-                1 => s.to_uppercase(),  // vbUpperCase
-                2 => s.to_lowercase(),  // vbLowerCase
-                3 => {
-                    // vbProperCase - capitalize first letter of each word
+                constants::vbUpperCase => s.to_uppercase(),
+                constants::vbLowerCase => s.to_lowercase(),
+                constants::vbProperCase => {
+                    // capitalize first letter of each word
                     s.split_whitespace()
                         .map(|word| {
                             let mut chars: Vec<char> = word.chars().collect();
@@ -820,6 +853,37 @@ pub(crate) fn handle_string_function(function: &str, args: &[Expression], ctx: &
 // HELPER FUNCTIONS
 // ============================================================
 
+/// Longest string VBA can hold (2^31 characters).
+const MAX_STRING_LEN: usize = i32::MAX as usize;
+
+/// Does `needle` occur in `hay` at character index `i`?
+fn chars_match_at(hay: &[char], needle: &[char], i: usize, ignore_case: bool) -> bool {
+    if needle.is_empty() || i + needle.len() > hay.len() {
+        return false;
+    }
+    hay[i..i + needle.len()].iter().zip(needle).all(|(a, b)| {
+        if ignore_case {
+            a.to_lowercase().eq(b.to_lowercase())
+        } else {
+            a == b
+        }
+    })
+}
+
+/// Character index of the first `needle` in `hay` at or after `from`.
+fn find_chars(hay: &str, needle: &str, from: usize, ignore_case: bool) -> Option<usize> {
+    let hay: Vec<char> = hay.chars().collect();
+    let needle: Vec<char> = needle.chars().collect();
+    (from..hay.len()).find(|&i| chars_match_at(&hay, &needle, i, ignore_case))
+}
+
+/// Character index of the last `needle` in `hay`.
+fn rfind_chars(hay: &str, needle: &str, ignore_case: bool) -> Option<usize> {
+    let hay: Vec<char> = hay.chars().collect();
+    let needle: Vec<char> = needle.chars().collect();
+    (0..hay.len()).rev().find(|&i| chars_match_at(&hay, &needle, i, ignore_case))
+}
+
 fn value_to_number(val: &Value) -> f64 {
     match val {
         Value::Integer(i) => *i as f64,
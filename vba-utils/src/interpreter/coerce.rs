@@ -1,6 +1,7 @@
 use anyhow::{anyhow, bail, Result};
 use chrono::Timelike;  // For hour(), minute(), second() on NaiveTime
 use crate::context::{Value, DeclaredType};
+use crate::vba_errors::VbaErr;
 
 pub(crate) fn to_bool(v: &Value) -> Result<bool> {
     Ok(match v {
@@ -233,9 +234,7 @@ pub(crate) fn add(l: Value, r: Value) -> Result<Value> {
             if is_numeric_string(a) && is_numeric_string(b) {
                 Value::Double(to_f64(&l)? + to_f64(&r)?)
             } else {
-                // Type mismatch - in VBA this would be Error 13
-                // For now, we'll concatenate as a fallback
-                bail!("Type mismatch: cannot add non-numeric strings '{}' + '{}'", a, b)
+                return Err(VbaErr::TypeMismatch.error(format!("cannot add non-numeric strings '{}' + '{}'", a, b)));
             }
         }
         
@@ -246,10 +245,10 @@ pub(crate) fn add(l: Value, r: Value) -> Result<Value> {
                 if matches!(r, Value::Double(_) | Value::Decimal(_) | Value::Single(_)) {
                     Value::Double(to_f64(&l)? + to_f64(&r)?)
                 } else {
-                    Value::Integer(to_i64(&l)? + to_i64(&r)?)
+                    checked_int_add(to_i64(&l)?, to_i64(&r)?)?
                 }
             } else {
-                bail!("Type mismatch: cannot convert '{}' to a number", s)
+                return Err(VbaErr::TypeMismatch.error(format!("cannot convert '{}' to a number", s)))
             }
         }
         (_, Value::String(s)) => {
@@ -258,10 +257,10 @@ pub(crate) fn add(l: Value, r: Value) -> Result<Value> {
                 if matches!(l, Value::Double(_) | Value::Decimal(_) | Value::Single(_)) {
                     Value::Double(to_f64(&l)? + to_f64(&r)?)
                 } else {
-                    Value::Integer(to_i64(&l)? + to_i64(&r)?)
+                    checked_int_add(to_i64(&l)?, to_i64(&r)?)?
                 }
             } else {
-                bail!("Type mismatch: cannot convert '{}' to a number", s)
+                return Err(VbaErr::TypeMismatch.error(format!("cannot convert '{}' to a number", s)))
             }
         }
         
@@ -273,12 +272,20 @@ pub(crate) fn add(l: Value, r: Value) -> Result<Value> {
             {
                 Value::Double(to_f64(&l)? + to_f64(&r)?)
             } else {
-                Value::Integer(to_i64(&l)? + to_i64(&r)?)
+                checked_int_add(to_i64(&l)?, to_i64(&r)?)?
             }
         }
     })
 }
 
+/// Integer `+` that reports VBA's "Overflow" instead of wrapping or panicking.
+fn checked_int_add(a: i64, b: i64) -> Result<Value> {
+    match a.checked_add(b) {
+        Some(v) => Ok(Value::Integer(v)),
+        None => Err(VbaErr::Overflow.error("")),
+    }
+}
+
 pub(crate) fn cmp_eq(l: &Value, r: &Value) -> Result<bool> {
     Ok(match (l, r) {
         (Value::String(a), Value::String(b)) => a == b,
@@ -308,6 +315,11 @@ fn parse_bool(s: &str) -> Result<bool> {
 pub(crate) fn coerce_to_declared(val: Value, ty: DeclaredType) -> Result<Value> {
     use DeclaredType as DT;
 
+    // Only a Variant can hold Null
+    if matches!(val, Value::Null) && ty != DT::Variant {
+        return Err(VbaErr::InvalidUseOfNull.error(""));
+    }
+
     match ty {
         DT::Byte => {
             let n = to_i64(&val)?;
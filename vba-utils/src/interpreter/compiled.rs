@@ -0,0 +1,251 @@
+// src/interpreter/compiled.rs
+
+//! Pre-compiled expressions for hot statements.
+//!
+//! `evaluate_expression` re-walks the `Expression` tree on every call:
+//! identifier names are re-checked against the special objects and the
+//! built-in constant table, and operators are matched as strings. Loop
+//! conditions and assignments inside loop bodies pay that cost on every
+//! iteration.
+//!
+//! [`CompiledExpr`] flattens the common subset (literals, variables,
+//! unary/binary operators) into a postfix op list with operators and names
+//! resolved up front. Anything else becomes a single [`Op::Eval`] that defers
+//! to `evaluate_expression`, so results and errors are identical to the
+//! tree-walker. Statements cache the compiled form in a [`CompiledCache`].
+
+use std::cell::{Cell, OnceCell};
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+
+use super::builtins::resolve_builtin_identifier;
+use super::evaluate_expression;
+use super::expressions::value_of;
+use super::operations::{eval_binop, eval_unop, BinOp, UnOp};
+use crate::ast::Expression;
+use crate::context::{Context, Value};
+use crate::scope::LocalSlot;
+use crate::symbol::{self, Symbol};
+
+#[derive(Debug, Clone)]
+enum Op {
+    Const(Value),
+    /// Plain variable read. The slot of the last frame it resolved in is
+    /// cached; otherwise scope lookup, then Option Explicit check. A name no
+    /// variable holds (host globals, forms, COM globals) goes to the
+    /// tree-walker.
+    Load(Symbol, String, Cell<Option<LocalSlot>>),
+    Unary(UnOp),
+    Binary(BinOp),
+    /// Subtree the compiler does not handle; evaluated by the tree-walker
+    Eval(Expression),
+}
+
+/// Postfix form of an expression.
+#[derive(Debug, Clone)]
+pub struct CompiledExpr {
+    ops: Vec<Op>,
+}
+
+impl CompiledExpr {
+    pub fn compile(expr: &Expression) -> Self {
+        let mut ops = Vec::new();
+        emit(expr, &mut ops);
+        CompiledExpr { ops }
+    }
+
+    pub fn eval(&self, ctx: &mut Context) -> Result<Value> {
+        let mut stack: Vec<Value> = Vec::with_capacity(self.ops.len());
+        for op in &self.ops {
+            let v = match op {
+                Op::Const(v) => v.clone(),
+                Op::Load(sym, name, slot) => {
+                    // A declared local is already known to Option Explicit
+                    if let Some(v) = slot.get().and_then(|s| ctx.get_local(s)) {
+                        v.clone()
+                    } else {
+                        slot.set(ctx.resolve_local(*sym));
+                        match ctx.get_var(*sym) {
+                            Some(v) => {
+                                if let Err(e) = ctx.validate_variable_usage(name) {
+                                    return Err(anyhow!("{}", e));
+                                }
+                                v
+                            }
+                            None => evaluate_expression(&Expression::Identifier(name.clone()), ctx)?,
+                        }
+                    }
+                }
+                Op::Unary(op) => {
+                    let v = stack.pop().expect("unary operand");
+                    eval_unop(*op, value_of(v, ctx)?)?
+                }
+                Op::Binary(op) => {
+                    let r = stack.pop().expect("right operand");
+                    let l = stack.pop().expect("left operand");
+                    eval_binop(ctx, *op, l, r)?
+                }
+                Op::Eval(expr) => evaluate_expression(expr, ctx)?,
+            };
+            stack.push(v);
+        }
+        Ok(stack.pop().unwrap_or(Value::Empty))
+    }
+}
+
+fn emit(expr: &Expression, ops: &mut Vec<Op>) {
+    use Expression::*;
+
+    match expr {
+        Integer(n) => ops.push(Op::Const(Value::Integer(*n))),
+        Byte(b) => ops.push(Op::Const(Value::Byte(*b))),
+        String(s) => ops.push(Op::Const(Value::String(s.clone()))),
+        Boolean(b) => ops.push(Op::Const(Value::Boolean(*b))),
+        Double(f) => ops.push(Op::Const(Value::Double(*f))),
+        Decimal(f) => ops.push(Op::Const(Value::Decimal(*f))),
+        Single(s) => ops.push(Op::Const(Value::Single(*s))),
+        Currency(c) => ops.push(Op::Const(Value::Currency(*c))),
+        Date(d) => ops.push(Op::Const(Value::Date(*d))),
+        Long(n) => ops.push(Op::Const(Value::Long(*n))),
+        LongLong(n) => ops.push(Op::Const(Value::LongLong(*n))),
+
+        Identifier(name) => {
+            // Err, the Excel and Word globals and qualified enum members
+            // resolve through context-dependent paths in evaluate_expression.
+            let special = matches!(
+                &*symbol::lower(name),
+                "err" | "activesheet" | "activeworkbook" | "application"
+                    | "activedocument" | "thisdocument" | "documents" | "selection"
+            );
+            if special || name.contains('.') {
+                ops.push(Op::Eval(expr.clone()));
+            } else if let Some(v) = resolve_builtin_identifier(name) {
+                ops.push(Op::Const(v));
+            } else {
+                ops.push(Op::Load(Symbol::intern(name), name.clone(), Cell::new(None)));
+            }
+        }
+
+        Parenthesized(inner) => emit(inner, ops),
+
+        UnaryOp { op, expr: inner } => match UnOp::parse(op) {
+            Some(op) => {
+                emit(inner, ops);
+                ops.push(Op::Unary(op));
+            }
+            None => ops.push(Op::Eval(expr.clone())),
+        },
+
+        BinaryOp { left, op, right } => match BinOp::parse(op) {
+            Some(op) => {
+                emit(left, ops);
+                emit(right, ops);
+                ops.push(Op::Binary(op));
+            }
+            None => ops.push(Op::Eval(expr.clone())),
+        },
+
+        _ => ops.push(Op::Eval(expr.clone())),
+    }
+}
+
+/// Lazily compiled form of an expression, stored next to it in the AST.
+#[derive(Clone, Default)]
+pub struct CompiledCache(OnceCell<CompiledExpr>);
+
+impl CompiledCache {
+    /// Evaluate `expr`, compiling it on first use.
+    pub fn eval(&self, expr: &Expression, ctx: &mut Context) -> Result<Value> {
+        self.0.get_or_init(|| CompiledExpr::compile(expr)).eval(ctx)
+    }
+}
+
+impl fmt::Debug for CompiledCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0.get().is_some() { "CompiledCache(ready)" } else { "CompiledCache" })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(n: i64) -> Box<Expression> {
+        Box::new(Expression::Integer(n))
+    }
+
+    #[test]
+    fn test_arithmetic_matches_tree_walker() {
+        // 1 + 2 * x
+        let expr = Expression::BinaryOp {
+            left: int(1),
+            op: "+".into(),
+            right: Box::new(Expression::BinaryOp {
+                left: int(2),
+                op: "*".into(),
+                right: Box::new(Expression::Identifier("x".into())),
+            }),
+        };
+        let mut ctx = Context::new();
+        ctx.set_var("x", Value::Integer(3));
+
+        let compiled = CompiledExpr::compile(&expr);
+        assert!(compiled.ops.iter().all(|op| !matches!(op, Op::Eval(_))));
+        assert_eq!(
+            format!("{:?}", compiled.eval(&mut ctx).unwrap()),
+            format!("{:?}", evaluate_expression(&expr, &mut ctx).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_host_globals_match_tree_walker() {
+        use crate::host::excel::engine::tests::lock_engine;
+        use crate::runtime_config::RuntimeConfig;
+        use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+        let _engine = lock_engine();
+        let config = RuntimeConfig::builder().user_form(crate::host::userform::UserForm::new("UserForm1")).build();
+        let mut ctx = Context::with_config(config);
+        ctx.enable_option_explicit();
+        ctx.declare_variable("x");
+        let assert = crate::host::assert::AssertObject::new(Default::default());
+        ctx.com_registry.register_global("Assert", Rc::new(RefCell::new(assert)));
+        ctx.define_enum("Colour".into(), HashMap::from([("Red".to_string(), 2)]));
+
+        let names = [
+            "ActiveCell", "ThisWorkbook", "Cells", "Rows", "Columns", "Worksheets", "Sheets", "Workbooks",
+            "ActiveWindow", "Clipboard", "Printer", "Screen", "UserForm1", "Assert", "Colour.Red",
+        ];
+        for name in names {
+            let rvalue = Expression::Identifier(name.into());
+            assert_eq!(
+                format!("{:?}", CompiledExpr::compile(&rvalue).eval(&mut ctx).unwrap()),
+                format!("{:?}", evaluate_expression(&rvalue, &mut ctx).unwrap()),
+                "{}",
+                name
+            );
+            // x = <name>
+            let assign = crate::ast::Statement::Assignment {
+                lvalue: crate::ast::AssignmentTarget::Identifier("x".into()),
+                rvalue,
+                rvalue_compiled: Default::default(),
+            };
+            crate::interpreter::execute_statement_list(std::slice::from_ref(&assign), &mut ctx);
+            assert!(ctx.err.is_none(), "{}: {:?}", name, ctx.err);
+        }
+
+        // A declared variable still shadows the global
+        ctx.declare_variable("Screen");
+        ctx.set_var("Screen", Value::Integer(4));
+        let compiled = CompiledExpr::compile(&Expression::Identifier("Screen".into()));
+        assert!(matches!(compiled.eval(&mut ctx).unwrap(), Value::Integer(4)));
+    }
+
+    #[test]
+    fn test_unknown_nodes_fall_back_to_eval() {
+        let expr = Expression::Identifier("Err".into());
+        let compiled = CompiledExpr::compile(&expr);
+        assert!(matches!(compiled.ops.as_slice(), [Op::Eval(_)]));
+    }
+}
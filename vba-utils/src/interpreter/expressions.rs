@@ -17,6 +17,8 @@ pub(crate) fn evaluate_expression(expr: &Expression, ctx: &mut Context) -> Resul
         Single(s) => Ok(Value::Single(*s)),
         Currency(c) => Ok(Value::Currency(*c)),
         Date(d)     => Ok(Value::Date(*d)),
+        Long(n)     => Ok(Value::Long(*n)),
+        LongLong(n) => Ok(Value::LongLong(*n)),
 
         // ——— Identifiers: built-in constants first, then variables
         Identifier(name) => {
@@ -32,18 +34,35 @@ pub(crate) fn evaluate_expression(expr: &Expression, ctx: &mut Context) -> Resul
                 // This is tricky because Identifier alone shouldn't fail
             }
             
-            // 0. Handle special Excel global objects
-            let name_lower = name.to_lowercase();
-            if name_lower == "activesheet" {
-                // Return a worksheet object reference
-                return Ok(Value::Object(Some(Box::new(Value::String("ActiveSheet".into())))));
+            // 0. Handle host global objects (a declared variable of the same name wins)
+            if let Some(object) = crate::host::word::global_object(ctx, name) {
+                if ctx.get_var(name).is_none() {
+                    return Ok(object);
+                }
+            }
+            if let Some(object) = excel_global_object(name) {
+                if ctx.get_var(name).is_none() {
+                    return Ok(object);
+                }
+            }
+            if let Some(object) = crate::host::userform::global_object(ctx, name) {
+                if ctx.get_var(name).is_none() {
+                    return Ok(object);
+                }
             }
-            if name_lower == "activeworkbook" {
-                return Ok(Value::Object(Some(Box::new(Value::String("ActiveWorkbook".into())))));
+            if let Some(object) = crate::host::vb::global_object(name) {
+                if ctx.get_var(name).is_none() {
+                    return Ok(object);
+                }
             }
+            let name_lower = crate::symbol::lower(name);
             if name_lower == "application" {
                 return Ok(Value::Object(Some(Box::new(Value::String("Application".into())))));
             }
+            // Other registered COM objects, so `With Host` can dispatch to them
+            if ctx.com_registry.get_global(name.as_str()).is_some() && ctx.get_var(name).is_none() {
+                return Ok(Value::Object(Some(Box::new(Value::String(name.clone())))));
+            }
             
             // 1. Check built-in constants first (vbTrue, vbCrLf, etc.)
             if let Some(v) = resolve_builtin_identifier(name) {
@@ -66,6 +85,8 @@ pub(crate) fn evaluate_expression(expr: &Expression, ctx: &mut Context) -> Resul
                 .ok_or_else(|| anyhow::anyhow!("Variable '{}' not found", name))
         }
         
+        Parenthesized(inner) => evaluate_expression(inner, ctx),
+
         BuiltInConstant(name) => {
             resolve_builtin_identifier(name)
                 .ok_or_else(|| anyhow::anyhow!("Unknown built-in constant: {}", name))
@@ -74,7 +95,7 @@ pub(crate) fn evaluate_expression(expr: &Expression, ctx: &mut Context) -> Resul
         // ——— Unary: op is a String (e.g., "+", "-", "Not")
         UnaryOp { op, expr } => {
             let v = evaluate_expression(expr, ctx)?;
-            crate::interpreter::operations::eval_unary(op.as_str(), v)
+            crate::interpreter::operations::eval_unary(op.as_str(), value_of(v, ctx)?)
         }
 
         // ——— Binary: op is a String (e.g., "+", "*", "<>", etc.)
@@ -90,10 +111,8 @@ pub(crate) fn evaluate_expression(expr: &Expression, ctx: &mut Context) -> Resul
 
         // ——— Function calls used as expressions
         FunctionCall { function, args } => {
-            // Handle method calls on objects: obj.Method(args)
-            // e.g., ws.Range("A1") where ws is a variable holding an object
+            // Handle Err.Raise, Err.Clear as method calls with arguments
             if let Expression::PropertyAccess { obj, property: method_name } = &**function {
-                // Handle Err.Raise, Err.Clear as method calls with arguments
                 if let Expression::Identifier(var_name) = &**obj {
                     if var_name.eq_ignore_ascii_case("Err") {
                         // Dispatch to Err method handler
@@ -102,49 +121,24 @@ pub(crate) fn evaluate_expression(expr: &Expression, ctx: &mut Context) -> Resul
                         }
                     }
                 }
-                
-                // Evaluate the object to see what it is
-                if let Expression::Identifier(var_name) = &**obj {
-                    // Check if this variable holds an object reference
-                    if let Some(var_val) = ctx.get_var(var_name) {
-                        if let Value::Object(Some(inner)) = var_val {
-                            if let Value::String(obj_type) = *inner {
-                                // Object variable - dispatch method call
-                                if obj_type == "ActiveSheet" && method_name.eq_ignore_ascii_case("Range") {
-                                    // ws.Range("A1") where ws = ActiveSheet
-                                    if let Some(first_arg) = args.first() {
-                                        let address = evaluate_expression(first_arg, ctx)?;
-                                        if let Value::String(addr) = address {
-                                            return Ok(Value::Object(Some(Box::new(Value::String(format!("Range:{}", addr))))));
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    // Also handle direct ActiveSheet.Range(), Worksheets("...").Range(), etc.
-                    if var_name.eq_ignore_ascii_case("ActiveSheet") && method_name.eq_ignore_ascii_case("Range") {
-                        if let Some(first_arg) = args.first() {
-                            let address = evaluate_expression(first_arg, ctx)?;
-                            if let Value::String(addr) = address {
-                                return Ok(Value::Object(Some(Box::new(Value::String(format!("Range:{}", addr))))));
-                            }
-                        }
-                    }
-                }
-                // Handle Worksheets("Sheet1").Range("A1")
-                if let Expression::FunctionCall { function: inner_fn, args: inner_args } = &**obj {
-                    if let Expression::Identifier(fn_name) = &**inner_fn {
-                        if fn_name.eq_ignore_ascii_case("Worksheets") && method_name.eq_ignore_ascii_case("Range") {
-                            // Worksheets("Sheet1").Range("A1")
-                            if let Some(first_arg) = args.first() {
-                                let address = evaluate_expression(first_arg, ctx)?;
-                                if let Value::String(addr) = address {
-                                    return Ok(Value::Object(Some(Box::new(Value::String(format!("Range:{}", addr))))));
-                                }
-                            }
-                        }
-                    }
+            }
+
+            // Module-qualified call: Module2.Total(1, 2)
+            if let Some((key, name)) = qualified_procedure(function, ctx)? {
+                return call_user_procedure(&key, &name, args, ctx);
+            }
+
+            // Method call or indexed property of an object, at any depth:
+            // Range("A1").AddComment("text"), Worksheets("S").Range("A1").Offset(1, 0),
+            // Selection.TypeText("Hello"), ActiveSheet.Hyperlinks(1)
+            if let Expression::PropertyAccess { obj, property: method_name } = &**function {
+                let target = evaluate_expression(obj, ctx)?;
+                let arg_values = args
+                    .iter()
+                    .map(|a| evaluate_expression(a, ctx))
+                    .collect::<Result<Vec<_>>>()?;
+                if let Some(result) = resolve_member(&target, method_name, Some(&arg_values), ctx) {
+                    return result;
                 }
             }
 
@@ -154,9 +148,56 @@ pub(crate) fn evaluate_expression(expr: &Expression, ctx: &mut Context) -> Resul
             } else {
                 bail!("Only simple identifier calls supported for now")
             };
+            // Indexing an object through its default member: c(1), rng(2, 1)
+            if !args.is_empty() && ctx.get_sub(name).is_none() {
+                let target = match ctx.get_var(name) {
+                    Some(value) => Some(value),
+                    None if ctx.com_registry.get_global(name.as_str()).is_some() => {
+                        Some(Value::Object(Some(Box::new(Value::String(name.clone())))))
+                    }
+                    None => None,
+                };
+                if let Some(target) = target.filter(|t| default_member(t, true, ctx).is_some()) {
+                    let arg_values = args
+                        .iter()
+                        .map(|a| evaluate_expression(a, ctx))
+                        .collect::<Result<Vec<_>>>()?;
+                    if let Some(result) = resolve_default(&target, Some(&arg_values), ctx) {
+                        return result;
+                    }
+                }
+            }
+            // Indexing a Word collection: Documents(1), Documents("Report.docx")
+            if let Some(collection) = crate::host::word::global_object(ctx, name) {
+                if let Some(object) = crate::host::ObjectRef::parse(&collection) {
+                    let arg_values = args
+                        .iter()
+                        .map(|a| evaluate_expression(a, ctx))
+                        .collect::<Result<Vec<_>>>()?;
+                    return object.call_method("Item", &arg_values, ctx);
+                }
+            }
+            // Indexing an Excel collection: Worksheets("Data"), Workbooks(1)
+            if let Some(collection) = excel_global_object(name).filter(|_| ctx.get_var(name).is_none() && ctx.get_sub(name).is_none()) {
+                if crate::host::excel::objects::object_ref(&collection).is_some() && !args.is_empty() {
+                    let arg_values = args
+                        .iter()
+                        .map(|a| evaluate_expression(a, ctx))
+                        .collect::<Result<Vec<_>>>()?;
+                    if let Some(result) = resolve_member(&collection, "Item", Some(&arg_values), ctx) {
+                        return result;
+                    }
+                }
+            }
+            // Declare'd DLL procedures shadow builtins of the same name
+            if let Some(result) = crate::host::dll::call_declared(name, args, ctx) {
+                return result;
+            }
              // Try builtin functions first
-            if let Ok(Some(val)) = crate::interpreter::builtins::functions::handle_builtin_call(name, args, ctx) {
-                return Ok(val);
+            match crate::interpreter::builtins::functions::handle_builtin_call(name, args, ctx) {
+                Ok(Some(val)) => return Ok(val),
+                Err(e) if crate::host::excel::error_number(&e).is_some() => return Err(e),
+                _ => {}
             }
         
             // Built-in: Format(value, pattern)
@@ -200,40 +241,40 @@ pub(crate) fn evaluate_expression(expr: &Expression, ctx: &mut Context) -> Resul
                     }
                     bail!("Range() requires a string address argument");
                 }
+                // Cells(row, col) / Cells(index) on the active sheet
+                if fn_name.eq_ignore_ascii_case("Cells") && ctx.get_sub(name).is_none() {
+                    let arg_values = args
+                        .iter()
+                        .map(|a| evaluate_expression(a, ctx))
+                        .collect::<Result<Vec<_>>>()?;
+                    let cell = crate::host::excel::methods::call_method("range", "1:1048576", "Cells", &arg_values, ctx)?;
+                    return Ok(crate::host::excel::objects::object_ref(&cell)
+                        .map(|(_, address)| crate::host::excel::objects::range_object(&address))
+                        .unwrap_or(cell));
+                }
+                // Union(r1, r2) / Intersect(r1, r2) without the Application prefix
+                if (fn_name.eq_ignore_ascii_case("Union") || fn_name.eq_ignore_ascii_case("Intersect")) && ctx.get_sub(name).is_none() {
+                    let arg_values = args
+                        .iter()
+                        .map(|a| evaluate_expression(a, ctx))
+                        .collect::<Result<Vec<_>>>()?;
+                    return crate::host::excel::objects::dispatch_method_call("application", "", fn_name, &arg_values, ctx);
+                }
+                // Rows(2) / Columns("C") return the entire row/column as a Range
+                if fn_name.eq_ignore_ascii_case("Rows") || fn_name.eq_ignore_ascii_case("Columns") {
+                    if let Some(first_arg) = args.first() {
+                        let index = evaluate_expression(first_arg, ctx)?;
+                        if let Some(addr) = crate::host::excel::objects::range::rows_columns_address(fn_name, &index) {
+                            return Ok(Value::Object(Some(Box::new(Value::String(format!("Range:{}", addr))))));
+                        }
+                    }
+                    bail!("{}() requires a row/column index", fn_name);
+                }
             }
         
             // Try user-defined functions
-            if let Some((params, body)) = ctx.subs.get(name).cloned() {
-                // Evaluate arguments
-                let mut arg_vals = Vec::with_capacity(args.len());
-                for a in args.iter() {
-                    arg_vals.push(evaluate_expression(a, ctx)?);
-                }
-                
-                // Push a new scope for the function
-                ctx.push_scope(name.clone(), crate::context::ScopeKind::Function);
-                
-                // Bind parameters
-                for (param, val) in params.iter().zip(arg_vals.into_iter()) {
-                    ctx.declare_variable(&param.name);
-                    ctx.declare_local(param.name.clone(), val);
-                }
-                
-                // Initialize the function return variable (FunctionName = ...)
-                // In VBA, the function name acts as the return variable
-                ctx.declare_variable(name);
-                ctx.declare_local(name.clone(), Value::Empty);
-                
-                // Execute function body
-                crate::interpreter::statements::execute_statement_list(&body, ctx);
-                
-                // Get the return value (the value assigned to the function name)
-                let return_value = ctx.get_var(name).unwrap_or(Value::Empty);
-                
-                // Pop scope
-                ctx.pop_scope();
-                
-                return Ok(return_value);
+            if let Some(key) = ctx.resolve_procedure(name)? {
+                return call_user_procedure(&key, name, args, ctx);
             }
         
             // Unknown functions -> for now, just return 0
@@ -244,6 +285,10 @@ pub(crate) fn evaluate_expression(expr: &Expression, ctx: &mut Context) -> Resul
 
         // ——— Property Access: Handle enum member access and user types
         PropertyAccess { obj, property } => {
+            // Module-qualified call without arguments: Module2.Init
+            if let Some((key, name)) = qualified_procedure(expr, ctx)? {
+                return call_user_procedure(&key, &name, &[], ctx);
+            }
             
         
             // 4) Handle special-case VBA Err object properties
@@ -295,7 +340,7 @@ pub(crate) fn evaluate_expression(expr: &Expression, ctx: &mut Context) -> Resul
                         Ok(value) => return Ok(value),
                         Err(_) => {}
                     }
-                } else if obj_name.eq_ignore_ascii_case("Application") {
+                } else if obj_name.eq_ignore_ascii_case("Application") && !crate::host::word::is_active(ctx) {
                     // Route to application properties
                     match crate::host::excel::properties::get_property("application", "", property, ctx) {
                         Ok(value) => return Ok(value),
@@ -319,64 +364,6 @@ pub(crate) fn evaluate_expression(expr: &Expression, ctx: &mut Context) -> Resul
                 }
             }
             
-            // ✨ Special handling for Range().property_name, Range().method_name,
-            //    Worksheets().property_name, and also ActiveSheet.Range("A1").property_name
-            if let Expression::FunctionCall { function, args } = &**obj {
-                // Case 1: Range("A1").Value or Range("B" & i).Value (simple function call)
-                if let Expression::Identifier(fn_name) = &**function {
-                    if fn_name.eq_ignore_ascii_case("Range") {
-                        // Evaluate the argument (supports both literals and expressions like "B" & i)
-                        if let Some(arg) = args.first() {
-                            let arg_value = evaluate_expression(arg, ctx)?;
-                            let address = match arg_value {
-                                Value::String(s) => s,
-                                other => format!("{:?}", other),
-                            };
-                            match crate::host::excel::properties::get_property("range", &address, property, ctx) {
-                                Ok(value) => return Ok(value),
-                                Err(_) => {
-                                    return crate::host::excel::methods::call_method("range", &address, property, &[]);
-                                }
-                            }
-                        }
-                    }
-                    // Case 1b: Worksheets("Sheet1").Name (Worksheets function call)
-                    else if fn_name.eq_ignore_ascii_case("Worksheets") {
-                        if let Some(Expression::String(sheet_name)) = args.first() {
-                            // Format as "name:workbook_id:index" - for Worksheets(), we don't have workbook_id yet
-                            // but we can pass just the name and let the handler use empty workbook_id
-                            let data = format!("{}::", sheet_name);
-                            match crate::host::excel::properties::get_property("worksheet", &data, property, ctx) {
-                                Ok(value) => return Ok(value),
-                                Err(_) => {
-                                    return crate::host::excel::methods::call_method("worksheet", &data, property, &[]);
-                                }
-                            }
-                        }
-                    }
-                }
-                // Case 2: ActiveSheet.Range("A1").Value or ActiveSheet.Range("B" & i).Value (method call on object property)
-                else if let Expression::PropertyAccess { obj: _obj_inner, property: inner_prop } = &**function {
-                    if inner_prop.eq_ignore_ascii_case("Range") {
-                        // Evaluate the argument (supports both literals and expressions like "B" & i)
-                        if let Some(arg) = args.first() {
-                            let arg_value = evaluate_expression(arg, ctx)?;
-                            let address = match arg_value {
-                                Value::String(s) => s,
-                                other => format!("{:?}", other),
-                            };
-                            match crate::host::excel::properties::get_property("range", &address, property, ctx) {
-                                Ok(value) => return Ok(value),
-                                Err(_) => {
-                                    return crate::host::excel::methods::call_method("range", &address, property, &[]);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        
-
             // 1) Evaluate the object expression first
             let object_val = evaluate_expression(obj, ctx)?;
         
@@ -389,20 +376,9 @@ pub(crate) fn evaluate_expression(expr: &Expression, ctx: &mut Context) -> Resul
                 }
             }
             
-            // 2b) Handle object references (Range, Worksheet, etc.)
-            if let Value::Object(Some(inner)) = &object_val {
-                if let Value::String(obj_ref) = &**inner {
-                    // Handle Range:address objects
-                    if obj_ref.starts_with("Range:") {
-                        let address = &obj_ref[6..]; // Skip "Range:" prefix
-                        match crate::host::excel::properties::get_property("range", address, property, ctx) {
-                            Ok(value) => return Ok(value),
-                            Err(_) => {
-                                return crate::host::excel::methods::call_method("range", address, property, &[]);
-                            }
-                        }
-                    }
-                }
+            // 2b) Handle object references (Range:address, Comment:cell, Worksheet:name, COM objects)
+            if let Some(result) = resolve_member(&object_val, property, None, ctx) {
+                return result;
             }
         
             // 3) Handle enum member access (EnumName.Member)
@@ -446,6 +422,10 @@ pub(crate) fn evaluate_expression(expr: &Expression, ctx: &mut Context) -> Resul
                         if let Some(val) = inner.get_field(property) {
                             return Ok(val.clone());
                         }
+                        // With Application / a host object: .DisplayAlerts, .Calculate
+                        if let Some(result) = resolve_member(&with_obj, property, None, ctx) {
+                            return result;
+                        }
                         bail!("Property '{}' not found on With object", property);
                     }
                     Value::String(obj_str) => {
@@ -483,34 +463,219 @@ pub(crate) fn evaluate_expression(expr: &Expression, ctx: &mut Context) -> Resul
                 }
                 
                 // The With object should be a Worksheet, so .Range("A1") means calling Range on that sheet
-                match &with_obj {
-                    Value::Object(Some(inner)) => {
-                        if let Value::String(obj_str) = inner.as_ref() {
-                            // Check if this is a Worksheet reference
-                            if obj_str.to_lowercase().starts_with("worksheet:") {
-                                let sheet_name = obj_str.strip_prefix("worksheet:").unwrap_or(obj_str);
-                                
-                                // If method is "Range", we need to return a Range object for that sheet
-                                if method.eq_ignore_ascii_case("Range") {
-                                    if let Some(Value::String(addr)) = evaluated_args.first() {
-                                        // Return a Range reference that includes the sheet context
-                                        return Ok(Value::Object(Some(Box::new(Value::String(
-                                            format!("range:{}!{}", sheet_name, addr)
-                                        )))));
-                                    }
+                if let Value::Object(Some(inner)) = &with_obj {
+                    if let Value::String(obj_str) = inner.as_ref() {
+                        // Check if this is a Worksheet reference
+                        if obj_str.to_lowercase().starts_with("worksheet:") {
+                            let sheet_name = obj_str.strip_prefix("worksheet:").unwrap_or(obj_str);
+                            
+                            // If method is "Range", we need to return a Range object for that sheet
+                            if method.eq_ignore_ascii_case("Range") {
+                                if let Some(Value::String(addr)) = evaluated_args.first() {
+                                    // Return a Range reference that includes the sheet context
+                                    return Ok(Value::Object(Some(Box::new(Value::String(
+                                        format!("range:{}!{}", sheet_name, addr)
+                                    )))));
                                 }
                             }
                         }
-                        // Generic method call on object
-                        bail!("Method '.{}' not supported on With object", method);
-                    }
-                    _ => {
-                        bail!("Cannot call method '.{}' on {:?}", method, with_obj);
                     }
                 }
+                // Any other member of the With object: .SendKeys "~" in With Application
+                match resolve_member(&with_obj, method, Some(&evaluated_args), ctx) {
+                    Some(result) => result,
+                    None => bail!("Cannot call method '.{}' on {:?}", method, with_obj),
+                }
             } else {
                 bail!("'.{}()' used outside of With block", method);
             }
         }
     }
-}
\ No newline at end of file
+}
+/// `member` of `target`, a registered COM object (`Application`) or a host
+/// object reference: a method call with `args`, a property read (falling
+/// back to a call without arguments) without. An Excel property that takes
+/// no arguments is indexed instead (`rng.Areas(2)`, `wb.Worksheets("S")`).
+/// Every link of a chain such as `Workbooks(1).Worksheets("S").Range("A1")`
+/// resolves here on the value of the link before it. None when `target`
+/// is not an object.
+fn resolve_member(target: &Value, member: &str, args: Option<&[Value]>, ctx: &mut Context) -> Option<Result<Value>> {
+    let registered = match target {
+        Value::Object(Some(inner)) => match inner.as_ref() {
+            Value::String(name) if ctx.com_registry.get_global(name.as_str()).is_some() => Some(name.clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+    if let Some(name) = registered {
+        return Some(match args {
+            Some(args) => crate::host::dispatch_com_call(&name, member, Some(args), false, ctx),
+            None => crate::host::dispatch_com_call(&name, member, None, false, ctx).or_else(|e| {
+                // Neither a property nor a method: report the property
+                crate::host::dispatch_com_call(&name, member, Some(&[]), false, ctx)
+                    .map_err(|call| if crate::host::members::is_unknown_member(&call) { e } else { call })
+            }),
+        });
+    }
+    if let Some(object) = crate::host::ObjectRef::parse(target) {
+        return Some(match args {
+            Some(args) => object.call_method(member, args, ctx),
+            None => object.get_property(member, ctx).or_else(|_| object.call_method(member, &[], ctx)),
+        });
+    }
+    let (object_type, data) = crate::host::excel::objects::object_ref(target)?;
+    let Some(args) = args else {
+        return Some(
+            crate::host::excel::objects::dispatch_property_get(&object_type, &data, member, ctx)
+                .or_else(|_| crate::host::excel::objects::dispatch_method_call(&object_type, &data, member, &[], ctx)),
+        );
+    };
+    let result = crate::host::excel::objects::dispatch_method_call(&object_type, &data, member, args, ctx);
+    if result.is_ok() {
+        return Some(result);
+    }
+    let collection = crate::host::excel::objects::dispatch_property_get(&object_type, &data, member, ctx).ok();
+    Some(match collection.as_ref().and_then(crate::host::excel::objects::object_ref) {
+        Some((object_type, data)) => crate::host::excel::objects::dispatch_method_call(&object_type, &data, "Item", args, ctx),
+        None => result,
+    })
+}
+
+/// Name of the default member of `target` when it is read or assigned, or
+/// called with arguments when `indexed`: the registered COM object's
+/// `ComObject::default_member`, or the Excel object type's. None when
+/// `target` is not an object or has no default member.
+pub(crate) fn default_member(target: &Value, indexed: bool, ctx: &Context) -> Option<String> {
+    if let Value::Object(Some(inner)) = target {
+        if let Value::String(name) = inner.as_ref() {
+            if let Some(handle) = ctx.com_registry.get_global(name.as_str()) {
+                return handle.try_borrow().ok()?.default_member().map(str::to_string);
+            }
+        }
+    }
+    let (object_type, _) = crate::host::excel::objects::object_ref(target)?;
+    crate::host::excel::objects::default_member(&object_type, indexed).map(str::to_string)
+}
+
+/// Read `target`'s default member, or call it with `args`: `x = Range("A1")`,
+/// `c(1)`. None when `target` has no default member.
+pub(crate) fn resolve_default(target: &Value, args: Option<&[Value]>, ctx: &mut Context) -> Option<Result<Value>> {
+    let member = default_member(target, args.is_some(), ctx)?;
+    resolve_member(target, &member, args, ctx)
+}
+
+/// `value` where an expression needs a value (operands, MsgBox prompts):
+/// an object stands for its default member, `Range("A1") + 1` adds to the
+/// cell's Value. Objects without one are returned as they are.
+pub(crate) fn value_of(value: Value, ctx: &mut Context) -> Result<Value> {
+    if !matches!(value, Value::Object(Some(_))) {
+        return Ok(value);
+    }
+    resolve_default(&value, None, ctx).unwrap_or(Ok(value))
+}
+
+/// Assign `value` to `target`'s default member: `Range("A1") = 5`. None
+/// when `target` has no default member.
+pub(crate) fn assign_default(target: &Value, value: Value, ctx: &mut Context) -> Option<Result<()>> {
+    let member = default_member(target, false, ctx)?;
+    if let Value::Object(Some(inner)) = target {
+        if let Value::String(name) = inner.as_ref() {
+            if ctx.com_registry.get_global(name.as_str()).is_some() {
+                let name = name.clone();
+                return Some(crate::host::dispatch_com_call(&name, &member, Some(&[value]), true, ctx).map(|_| ()));
+            }
+        }
+    }
+    let (object_type, data) = crate::host::excel::objects::object_ref(target)?;
+    Some(crate::host::excel::objects::dispatch_property_set(&object_type, &data, &member, value, ctx))
+}
+
+/// `Module.Proc` naming a procedure of a loaded module: its `subs` key and
+/// bare name. A variable called like the module wins.
+fn qualified_procedure(expr: &Expression, ctx: &Context) -> Result<Option<(String, String)>> {
+    let Expression::PropertyAccess { obj, property } = expr else {
+        return Ok(None);
+    };
+    let Expression::Identifier(module) = &**obj else {
+        return Ok(None);
+    };
+    if !ctx.is_module(module) || ctx.get_var(module.as_str()).is_some() {
+        return Ok(None);
+    }
+    let key = ctx.resolve_procedure(&format!("{}.{}", module, property))?;
+    Ok(key.map(|key| (key, property.clone())))
+}
+
+/// Run the Sub/Function registered under `key` with `args`; `name` is its
+/// bare name, which holds the return value
+fn call_user_procedure(key: &str, name: &str, args: &[Expression], ctx: &mut Context) -> Result<Value> {
+    let Some((params, _)) = ctx.get_sub(key).cloned() else {
+        bail!("Sub or Function not defined: {}", key);
+    };
+    // Evaluate arguments
+    let mut arg_vals = Vec::with_capacity(args.len());
+    for a in args.iter() {
+        arg_vals.push(evaluate_expression(a, ctx)?);
+    }
+    let bindings = crate::interpreter::byref_bindings(&params, args);
+    run_user_procedure(key, name, arg_vals, &bindings, ctx)
+}
+
+/// Run the Sub/Function registered under `key` with evaluated arguments,
+/// copying the ByRef `bindings` back when it returns
+pub(crate) fn run_user_procedure(
+    key: &str,
+    name: &str,
+    arg_vals: Vec<Value>,
+    bindings: &[(String, String)],
+    ctx: &mut Context,
+) -> Result<Value> {
+    let Some((params, body)) = ctx.get_sub(key).cloned() else {
+        bail!("Sub or Function not defined: {}", key);
+    };
+
+    // Push a new scope for the function
+    ctx.enter_procedure(key, crate::context::ScopeKind::Function)?;
+
+    // Bind parameters
+    for (param, val) in params.iter().zip(arg_vals) {
+        ctx.declare_variable(&param.name);
+        ctx.declare_local(param.name.as_str(), val);
+    }
+
+    // Initialize the function return variable (FunctionName = ...)
+    // In VBA, the function name acts as the return variable
+    ctx.declare_variable(name);
+    ctx.declare_local(name, Value::Empty);
+
+    // Execute function body
+    crate::interpreter::statements::execute_statement_list(&body, ctx);
+
+    // Get the return value (the value assigned to the function name)
+    let return_value = ctx.get_var(name).unwrap_or(Value::Empty);
+
+    // Pop scope, copying ByRef arguments back
+    crate::interpreter::pop_call_scope(ctx, bindings);
+
+    Ok(return_value)
+}
+
+/// Excel globals that evaluate to host objects: ActiveSheet, ActiveWorkbook,
+/// ThisWorkbook, ActiveCell, Selection, ActiveWindow and the Workbooks and
+/// Worksheets (Sheets) collections
+pub(crate) fn excel_global_object(name: &str) -> Option<Value> {
+    let object = match &*crate::symbol::lower(name) {
+        "activesheet" => Value::Object(Some(Box::new(Value::String("ActiveSheet".into())))),
+        "activeworkbook" | "thisworkbook" => Value::Object(Some(Box::new(Value::String("ActiveWorkbook".into())))),
+        "activecell" => crate::host::excel::objects::range_object(&crate::host::excel::engine::get_selection().active_cell),
+        "selection" => crate::host::excel::objects::range_object(&crate::host::excel::engine::get_selection().range),
+        "activewindow" => Value::String("Window:1".into()),
+        "workbooks" => Value::String("Workbooks:".into()),
+        "worksheets" | "sheets" => Value::String("Worksheets:".into()),
+        "cells" | "rows" | "columns" => {
+            return crate::host::excel::properties::worksheet_properties::get_worksheet_property("", name).ok();
+        }
+        _ => return None,
+    };
+    Some(object)
+}
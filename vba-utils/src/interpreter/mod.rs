@@ -1,29 +1,33 @@
 // src/interpreter/mod.rs
 mod expressions;
 mod statements;
-mod operations;
-mod coerce;
+pub(crate) mod operations;
+pub(crate) mod coerce;
+pub mod compiled;
 
 pub mod builtins;
 // pub mod host;
 
-pub(crate) use expressions::evaluate_expression;
+pub(crate) use expressions::{evaluate_expression, run_user_procedure};
 pub use statements::execute_statement_list;
 pub use crate::vm::run_statement_list_vm;  // ← ADD THIS
 
 // Re-export core control-flow and helpers so other modules (like `vm`) can use them
 pub use self::statements::ControlFlow;
 pub(crate) use self::statements::execute_statement;
+pub(crate) use self::statements::{byref_bindings, maybe_handle_error, pop_call_scope, stop};
+pub(crate) use self::operations::{BinOp, UnOp};
 pub use self::statements::value_to_integer;
 
-use crate::ast::{Program, Statement};
-use crate::context::Context;
+use crate::ast::{Body, Program, Statement};
+use crate::context::{Context, ScopeKind};
+use crate::runtime_config::ExecutionEngine;
 use anyhow::Result;
 
-pub fn execute_ast(program: &Program, ctx: &mut Context) -> Result<()> {
+pub fn execute_ast(program: &Program, ctx: &mut Context) -> Result<(), crate::error::VbaError> {
     for stmt in &program.statements {
-        if let Statement::Subroutine { name, params, body } = stmt {
-            ctx.subs.insert(name.clone(), (params.clone(), body.clone()));
+        if let Statement::Subroutine { name, params, body, .. } = stmt {
+            ctx.define_sub(name, params.clone(), body.clone());
         }
     }
     Ok(())
@@ -31,20 +35,36 @@ pub fn execute_ast(program: &Program, ctx: &mut Context) -> Result<()> {
 
 /// Updated to use the VM
 pub fn run_subroutine(ctx: &mut Context, name: &str) {
-    let body: Vec<Statement> = match ctx.subs.get(name) {
+    // `Module1.Main`, or a Main only one loaded module defines
+    let key = match ctx.resolve_procedure(name) {
+        Ok(key) => key.unwrap_or_else(|| name.to_string()),
+        Err(e) => return eprintln!("Subroutine '{}': {}", name, e),
+    };
+    let body: Body = match ctx.get_sub(&key) {
         Some((_params, body)) => body.clone(),
         None => {
             eprintln!("Subroutine '{}' not found", name);
             return;
         }
     };
+    let module = ctx.module_of(&key);
+    let caller_module = std::mem::replace(&mut ctx.entry_module, module);
 
     println!("Entering Sub {}", name);
+    // The entry Sub gets a procedure scope like any call, so its locals
+    // are gone once it returns instead of lingering as module variables
+    let depth = ctx.scope_depth();
+    ctx.push_scope(key.as_str(), ScopeKind::Subroutine);
 
-    // ← USE THE VM HERE
-    let flow = run_statement_list_vm(&body, ctx, 0);
+    let flow = match ctx.runtime_config.engine {
+        ExecutionEngine::Vm => run_statement_list_vm(body.clone(), ctx, 0),
+        ExecutionEngine::Interpreter => execute_statement_list(&body, ctx),
+    };
 
+    // End or Stop can leave the scopes of the Subs it unwound
+    ctx.pop_scopes_to(depth);
     println!("Leaving Sub {}", name);
+    ctx.entry_module = caller_module;
 
     match flow {
         ControlFlow::Continue
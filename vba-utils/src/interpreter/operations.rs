@@ -1,53 +1,173 @@
 use anyhow::{anyhow, Result};
-use crate::context::{Context, ErrObject, Value};
+use crate::context::{Context, Value};
+use crate::host::excel::error_number;
+use crate::vba_errors::{raise, VbaErr};
 use super::coerce;
+use super::expressions::value_of;
 
-// Small helper
-fn set_err(ctx: &mut Context, number: i32, description: &str) {
-    ctx.err = Some(ErrObject {
-        number,
-        description: description.to_string(),
-        source: "Interpreter".into(),
-    });
+/// Unary operator, resolved once from the AST's operator text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UnOp {
+    Plus,
+    Neg,
+    Not,
+}
+
+impl UnOp {
+    pub(crate) fn parse(op: &str) -> Option<Self> {
+        match op {
+            "+" => Some(UnOp::Plus),
+            "-" => Some(UnOp::Neg),
+            _ if op.eq_ignore_ascii_case("Not") => Some(UnOp::Not),
+            _ => None,
+        }
+    }
+}
+
+/// Binary operator, resolved once from the AST's operator text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BinOp {
+    Add,
+    Concat,
+    Sub,
+    Mul,
+    Div,
+    IntDiv,
+    Mod,
+    Pow,
+    And,
+    Or,
+    Xor,
+    Eqv,
+    Imp,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl BinOp {
+    pub(crate) fn parse(op: &str) -> Option<Self> {
+        let op = match op {
+            "+" => BinOp::Add,
+            "&" => BinOp::Concat,
+            "-" => BinOp::Sub,
+            "*" => BinOp::Mul,
+            "/" => BinOp::Div,
+            "\\" => BinOp::IntDiv,
+            "^" => BinOp::Pow,
+            "=" => BinOp::Eq,
+            "<>" => BinOp::Ne,
+            "<" => BinOp::Lt,
+            "<=" => BinOp::Le,
+            ">" => BinOp::Gt,
+            ">=" => BinOp::Ge,
+            _ if op.eq_ignore_ascii_case("Mod") => BinOp::Mod,
+            _ if op.eq_ignore_ascii_case("And") => BinOp::And,
+            _ if op.eq_ignore_ascii_case("Or") => BinOp::Or,
+            _ if op.eq_ignore_ascii_case("Xor") => BinOp::Xor,
+            _ if op.eq_ignore_ascii_case("Eqv") => BinOp::Eqv,
+            _ if op.eq_ignore_ascii_case("Imp") => BinOp::Imp,
+            _ => return None,
+        };
+        Some(op)
+    }
 }
 
 // Unary stays pure (no ctx needed)
 pub(crate) fn eval_unary(op: &str, v: Value) -> Result<Value> {
+    match UnOp::parse(op) {
+        Some(op) => eval_unop(op, v),
+        None if matches!(v, Value::Null) => Ok(Value::Null),
+        None => Err(anyhow!("unary op not implemented: {}", op)),
+    }
+}
+
+pub(crate) fn eval_unop(op: UnOp, v: Value) -> Result<Value> {
     // Handle Null propagation - Null in, Null out
     if matches!(v, Value::Null) {
         return Ok(Value::Null);
     }
     
+    if matches!(v, Value::Error(_)) {
+        return Err(VbaErr::TypeMismatch.error(""));
+    }
     match op {
-        "+" => Ok(Value::Double(super::coerce::to_f64(&v)?)),
-        "-" => Ok(Value::Double(-super::coerce::to_f64(&v)?)),
-        "Not" | "not" | "NOT" => Ok(Value::Boolean(!super::coerce::to_bool(&v)?)),
-        other => Err(anyhow!("unary op not implemented: {}", other)),
+        UnOp::Plus => Ok(Value::Double(super::coerce::to_f64(&v)?)),
+        UnOp::Neg => Ok(Value::Double(-super::coerce::to_f64(&v)?)),
+        UnOp::Not => Ok(Value::Boolean(!super::coerce::to_bool(&v)?)),
     }
 }
 
+/// `l op r` with at least one Null operand. Arithmetic and comparisons
+/// give Null; `&` treats Null as "" unless both sides are Null; And, Or
+/// and Imp give a definite answer when the other operand decides it
+/// (`Null And False` is False, `Null Or True` is True).
+fn null_binop(op: BinOp, l: &Value, r: &Value) -> Result<Value> {
+    let known = |v: &Value| (!matches!(v, Value::Null)).then(|| coerce::to_bool(v).ok()).flatten();
+    let result = match op {
+        BinOp::Concat if matches!((l, r), (Value::Null, Value::Null)) => Value::Null,
+        BinOp::Concat => Value::String(coerce::to_string(l) + &coerce::to_string(r)),
+        BinOp::And if known(l) == Some(false) || known(r) == Some(false) => Value::Boolean(false),
+        BinOp::Or if known(l) == Some(true) || known(r) == Some(true) => Value::Boolean(true),
+        BinOp::Imp if known(l) == Some(false) || known(r) == Some(true) => Value::Boolean(true),
+        _ => Value::Null,
+    };
+    Ok(result)
+}
+
 /// IMPORTANT: now takes &mut Context so we can set Err on runtime faults
 pub(crate) fn eval_binary(ctx: &mut Context, op: &str, l: Value, r: Value) -> Result<Value> {
-    // Handle Null propagation - if either operand is Null, result is Null
-    // Exception: & (string concat) converts Null to ""
+    match BinOp::parse(op) {
+        Some(op) => eval_binop(ctx, op, l, r),
+        None if matches!((&l, &r), (Value::Null, _) | (_, Value::Null)) => Ok(Value::Null),
+        None => Err(anyhow!("binary op not implemented: {}", op)),
+    }
+}
+
+pub(crate) fn eval_binop(ctx: &mut Context, op: BinOp, l: Value, r: Value) -> Result<Value> {
+    let (l, r) = (value_of(l, ctx)?, value_of(r, ctx)?);
     if matches!((&l, &r), (Value::Null, _) | (_, Value::Null)) {
-        if op != "&" {
-            return Ok(Value::Null);
+        return null_binop(op, &l, &r);
+    }
+
+    // Error values (CVErr, #N/A cells) only compare with each other; any
+    // other use is a type mismatch
+    match (&l, &r) {
+        (Value::Error(a), Value::Error(b)) if matches!(op, BinOp::Eq | BinOp::Ne) => {
+            return Ok(Value::Boolean((a == b) == (op == BinOp::Eq)));
         }
+        (Value::Error(_), _) | (_, Value::Error(_)) => {
+            return Err(VbaErr::TypeMismatch.error(""));
+        }
+        _ => {}
     }
     
     match op {
         // VBA `+` is numeric add unless either side is a string (then concat via + rules).
-        "+" => super::coerce::add(l, r),
+        BinOp::Add => match super::coerce::add(l, r) {
+            Err(e) if error_number(&e) == Some(VbaErr::Overflow.number()) => {
+                raise(ctx, VbaErr::Overflow, "");
+                Ok(Value::Integer(0))
+            }
+            Ok(Value::String(s)) => {
+                ctx.check_allocation(s.len())?;
+                Ok(Value::String(s))
+            }
+            other => other,
+        },
 
         // `&` is *always* string concatenation in VBA
-        "&" => {
+        BinOp::Concat => {
             let ls = coerce::to_string(&l);
             let rs = coerce::to_string(&r);
+            ctx.check_allocation(ls.len() + rs.len())?;
             Ok(Value::String(ls + &rs))
         }
 
-        "-" => {
+        BinOp::Sub => {
             // Use checked arithmetic on integer-compatible values; fall back to Double as needed
             // VBA promotes as needed; a practical compromise: try i64 first, if conversion fails, do Double
             if let (Ok(li), Ok(ri)) = (coerce::to_i64(&l), coerce::to_i64(&r)) {
@@ -58,7 +178,7 @@ pub(crate) fn eval_binary(ctx: &mut Context, op: &str, l: Value, r: Value) -> Re
                     None => {
                         // Overflow → Err 6
                         //println!("🔴 OVERFLOW DETECTED in subtraction: {} - {}", li, ri);
-                        set_err(ctx, 6, "Overflow");
+                        raise(ctx, VbaErr::Overflow, "");
                         Ok(Value::Integer(0)) // placeholder; Assignment guard will skip the write
                     }
                 }
@@ -68,12 +188,12 @@ pub(crate) fn eval_binary(ctx: &mut Context, op: &str, l: Value, r: Value) -> Re
             }
         }
 
-        "*" => {
+        BinOp::Mul => {
             if let (Ok(li), Ok(ri)) = (coerce::to_i64(&l), coerce::to_i64(&r)) {
                 match li.checked_mul(ri) {
                     Some(v) => Ok(Value::Integer(v)),
                     None => {
-                        set_err(ctx, 6, "Overflow");
+                        raise(ctx, VbaErr::Overflow, "");
                         Ok(Value::Integer(0))
                     }
                 }
@@ -82,12 +202,12 @@ pub(crate) fn eval_binary(ctx: &mut Context, op: &str, l: Value, r: Value) -> Re
             }
         }
 
-        "/" => {
+        BinOp::Div => {
             let denom = super::coerce::to_f64(&r)?;
             if denom == 0.0 {
                 // This bubbles as Err up to Assignment where we set Err.Number = 11
                 //println!("🔴 DIVISION BY ZERO DETECTED! Setting error...");
-                set_err(ctx, 11, "Division by zero");
+                raise(ctx, VbaErr::DivisionByZero, "");
                 return Ok(Value::Double(f64::NAN));
             }
             let num = super::coerce::to_f64(&l)?;
@@ -96,61 +216,138 @@ pub(crate) fn eval_binary(ctx: &mut Context, op: &str, l: Value, r: Value) -> Re
         }
 
         // Integer division
-        "\\" => {
+        BinOp::IntDiv => {
             let denom = coerce::to_i64(&r)?;
             if denom == 0 {
-                set_err(ctx, 11, "Division by zero");
+                raise(ctx, VbaErr::DivisionByZero, "");
                 return Ok(Value::Integer(0));
             }
             let num = coerce::to_i64(&l)?;
-            Ok(Value::Integer(num / denom))
+            match num.checked_div(denom) {
+                Some(v) => Ok(Value::Integer(v)),
+                None => {
+                    raise(ctx, VbaErr::Overflow, "");
+                    Ok(Value::Integer(0))
+                }
+            }
         }
 
         // Modulus
-        "Mod" | "mod" | "MOD" => {
+        BinOp::Mod => {
             let denom = coerce::to_i64(&r)?;
             if denom == 0 {
-                set_err(ctx, 11, "Division by zero");
+                raise(ctx, VbaErr::DivisionByZero, "");
                 return Ok(Value::Integer(0));
             }
             let num = coerce::to_i64(&l)?;
-            Ok(Value::Integer(num % denom))
+            match num.checked_rem(denom) {
+                Some(v) => Ok(Value::Integer(v)),
+                None => {
+                    raise(ctx, VbaErr::Overflow, "");
+                    Ok(Value::Integer(0))
+                }
+            }
         }
 
         // Exponentiation
-        "^" => {
+        BinOp::Pow => {
             let base = coerce::to_f64(&l)?;
             let exp = coerce::to_f64(&r)?;
             Ok(Value::Double(base.powf(exp)))
         }
 
         // Logical
-        "And" | "and" => Ok(Value::Boolean(coerce::to_bool(&l)? && coerce::to_bool(&r)?)),
-        "Or"  | "or"  => Ok(Value::Boolean(coerce::to_bool(&l)? || coerce::to_bool(&r)?)),
-        "Xor" | "xor" => {
+        BinOp::And => Ok(Value::Boolean(coerce::to_bool(&l)? && coerce::to_bool(&r)?)),
+        BinOp::Or => Ok(Value::Boolean(coerce::to_bool(&l)? || coerce::to_bool(&r)?)),
+        BinOp::Xor => {
             let lb = coerce::to_bool(&l)?;
             let rb = coerce::to_bool(&r)?;
             Ok(Value::Boolean((lb || rb) && !(lb && rb)))  // XOR = (A OR B) AND NOT (A AND B)
         }
-        "Eqv" | "eqv" => {
+        BinOp::Eqv => {
             let lb = coerce::to_bool(&l)?;
             let rb = coerce::to_bool(&r)?;
             Ok(Value::Boolean(lb == rb))  // EQV = both same
         }
-        "Imp" | "imp" => {
+        BinOp::Imp => {
             let lb = coerce::to_bool(&l)?;
             let rb = coerce::to_bool(&r)?;
             Ok(Value::Boolean(!lb || rb))  // IMP = NOT A OR B
         }
 
         // Comparisons: coerce to VBA-like numeric comparison for non-strings
-        "="  => Ok(Value::Boolean(coerce::cmp_eq(&l, &r)?)),
-        "<>" => Ok(Value::Boolean(!coerce::cmp_eq(&l, &r)?)),
-        "<"  => Ok(Value::Boolean(coerce::to_f64(&l)? <  coerce::to_f64(&r)?)),
-        "<=" => Ok(Value::Boolean(coerce::to_f64(&l)? <= coerce::to_f64(&r)?)),
-        ">"  => Ok(Value::Boolean(coerce::to_f64(&l)? >  coerce::to_f64(&r)?)),
-        ">=" => Ok(Value::Boolean(coerce::to_f64(&l)? >= coerce::to_f64(&r)?)),
-
-        other => Err(anyhow!("binary op not implemented: {}", other)),
+        BinOp::Eq => Ok(Value::Boolean(coerce::cmp_eq(&l, &r)?)),
+        BinOp::Ne => Ok(Value::Boolean(!coerce::cmp_eq(&l, &r)?)),
+        BinOp::Lt => Ok(Value::Boolean(coerce::to_f64(&l)? <  coerce::to_f64(&r)?)),
+        BinOp::Le => Ok(Value::Boolean(coerce::to_f64(&l)? <= coerce::to_f64(&r)?)),
+        BinOp::Gt => Ok(Value::Boolean(coerce::to_f64(&l)? >  coerce::to_f64(&r)?)),
+        BinOp::Ge => Ok(Value::Boolean(coerce::to_f64(&l)? >= coerce::to_f64(&r)?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Expression;
+
+    fn null() -> Expression {
+        Expression::Identifier("Null".into())
+    }
+
+    fn binary(l: Expression, op: &str, r: Expression) -> Expression {
+        Expression::BinaryOp { left: Box::new(l), op: op.into(), right: Box::new(r) }
+    }
+
+    fn call(name: &str, args: Vec<Expression>) -> Expression {
+        Expression::FunctionCall { function: Box::new(Expression::Identifier(name.into())), args }
+    }
+
+    #[test]
+    fn test_add_overflow_raises_error_6() {
+        let mut ctx = Context::new();
+        let sum = eval_binary(&mut ctx, "+", Value::Integer(i64::MAX), Value::Integer(1)).unwrap();
+        assert!(matches!(sum, Value::Integer(0)));
+        assert_eq!(ctx.err.as_ref().map(|e| (e.number, e.description.as_str())), Some((6, "Overflow")));
+    }
+
+    // Expected results from the VBA language reference (Null propagation)
+    #[test]
+    fn test_null_propagation() {
+        let mut ctx = Context::new();
+        let mut eval = |expr: Expression| crate::interpreter::evaluate_expression(&expr, &mut ctx);
+        let t = || Expression::Boolean(true);
+        let f = || Expression::Boolean(false);
+
+        assert!(eval(binary(Expression::Integer(1), "+", null())).unwrap().is_null());
+        assert!(eval(binary(null(), "*", Expression::Integer(2))).unwrap().is_null());
+        assert!(eval(binary(null(), "=", null())).unwrap().is_null());
+        assert!(eval(binary(Expression::Integer(1), "<", null())).unwrap().is_null());
+        assert_eq!(eval(binary(Expression::String("a".into()), "&", null())).unwrap().as_string(), "a");
+        assert!(eval(binary(null(), "&", null())).unwrap().is_null());
+
+        assert!(matches!(eval(binary(null(), "And", f())).unwrap(), Value::Boolean(false)));
+        assert!(eval(binary(null(), "And", t())).unwrap().is_null());
+        assert!(matches!(eval(binary(t(), "Or", null())).unwrap(), Value::Boolean(true)));
+        assert!(eval(binary(f(), "Or", null())).unwrap().is_null());
+        assert!(matches!(eval(binary(f(), "Imp", null())).unwrap(), Value::Boolean(true)));
+        assert!(eval(binary(t(), "Imp", null())).unwrap().is_null());
+        assert!(eval(binary(null(), "Xor", t())).unwrap().is_null());
+        assert!(eval(Expression::UnaryOp { op: "Not".into(), expr: Box::new(null()) }).unwrap().is_null());
+
+        // Variant builtins pass Null through; $ forms and conversions raise 94
+        assert!(eval(call("Len", vec![null()])).unwrap().is_null());
+        assert!(eval(call("Trim", vec![null()])).unwrap().is_null());
+        let err = eval(call("Trim$", vec![null()])).unwrap_err();
+        assert_eq!(crate::host::excel::error_number(&err), Some(94));
+        let err = eval(call("CStr", vec![null()])).unwrap_err();
+        assert_eq!(crate::host::excel::error_number(&err), Some(94));
+
+        assert!(matches!(eval(call("IsNull", vec![binary(null(), "+", Expression::Integer(1))])).unwrap(), Value::Boolean(true)));
+        assert_eq!(eval(call("Nz", vec![null(), Expression::String("n/a".into())])).unwrap().as_string(), "n/a");
+        assert!(matches!(eval(call("Nz", vec![null()])).unwrap(), Value::Empty));
+        assert!(matches!(eval(call("Nz", vec![Expression::Integer(5), Expression::Integer(0)])).unwrap(), Value::Integer(5)));
+
+        let err = coerce::coerce_to_declared(Value::Null, crate::context::DeclaredType::Long).unwrap_err();
+        assert_eq!(crate::host::excel::error_number(&err), Some(94));
     }
 }
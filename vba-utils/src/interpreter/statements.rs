@@ -1,8 +1,11 @@
-use crate::ast::{Statement, ForStatement, DoWhileStatement, Expression, OnErrorKind, ResumeKind, EnumMember,TypeField, DoWhileConditionType};
+use crate::ast::{Statement, Parameter, ParameterPassing, ForStatement, DoWhileStatement, Expression, OnErrorKind, ResumeKind, EnumMember,TypeField, DoWhileConditionType};
 use crate::interpreter::evaluate_expression;
-use crate::context::{Context, Value, ScopeKind, FieldDefinition, ErrObject, OnErrorMode};
+use crate::context::{Context, Value, ScopeKind, FieldDefinition, OnErrorMode};
+use crate::vba_errors::{raise, raise_from, VbaErr};
 use crate::interpreter::builtins::handle_builtin_call_bool;
 use crate::interpreter::coerce::coerce_to_declared;
+use crate::runtime_config::StopPolicy;
+use crate::symbol::Symbol;
 use std::collections::HashMap;
 
 // === Control flow signals used internally by the interpreter ===
@@ -24,6 +27,7 @@ pub enum ControlFlow {
     ResumeNext,      // On Error Resume Next, or Resume Next
     ResumeCurrent,
     FramePushed,   // Indicates a new frame was pushed, don't advance
+    FrameReused,   // A tail call replaced the current frame's statements; run from its first
 }
 
 impl ControlFlow {
@@ -56,45 +60,72 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
             ControlFlow::Continue
         }
 
+        // Reported in Program::diagnostics; the embedder chose to run anyway
+        Statement::Unparsed { .. } => ControlFlow::Continue,
+
         Statement::OptionExplicit => {
             ctx.enable_option_explicit();
             ControlFlow::Continue
         }
 
+        // Visibility only matters to cross-module resolution (analysis::callgraph)
+        Statement::OptionPrivateModule => ControlFlow::Continue,
+
+        // Export metadata (Program::attributes); nothing to run
+        Statement::Attribute(_) => ControlFlow::Continue,
+
+        Statement::Declare { name, is_function, library, alias, return_type, .. } => {
+            ctx.register_declared(name, crate::host::dll::DeclaredProcedure {
+                library: library.clone(),
+                entry: alias.clone().unwrap_or_else(|| name.clone()),
+                is_function: *is_function,
+                return_type: return_type.clone(),
+            });
+            ControlFlow::Continue
+        }
+
+        Statement::DefType { type_name, ranges } => {
+            let ty = ctx.declared_type(Some(type_name));
+            for (first, last) in ranges {
+                ctx.set_def_type(*first, *last, ty);
+            }
+            ControlFlow::Continue
+        }
+
         // Record subroutines for later calls
-        Statement::Subroutine { name, params, body } => {
+        Statement::Subroutine { name, params, body, .. } => {
             ctx.define_sub(name.clone(), params.clone(), body.clone());
             ctx.log(&format!("Defined subroutine {}", name));
             ControlFlow::Continue
         }
 
         // Record functions for later calls
-        Statement::Function { name, params, return_type, body } => {
+        Statement::Function { name, params, return_type, body, .. } => {
             ctx.define_function(name.clone(), params.clone(), body.clone(), return_type.clone());
             ctx.log(&format!("Defined function {}", name));
             ControlFlow::Continue
         }
 
         // Record Property Get for later calls
-        Statement::PropertyGet { name, params, return_type, body } => {
+        Statement::PropertyGet { name, params, return_type, body, .. } => {
             ctx.register_property("Get", name, params, body);
             // Store return type if needed
             if let Some(ref rt) = return_type {
-                ctx.function_return_types.insert(format!("Get_{}", name), Some(rt.clone()));
+                ctx.function_return_types.insert(Symbol::intern(&format!("Get_{}", name)), Some(rt.clone()));
             }
             ctx.log(&format!("Defined Property Get {}", name));
             ControlFlow::Continue
         }
 
         // Record Property Let for later calls
-        Statement::PropertyLet { name, params, body } => {
+        Statement::PropertyLet { name, params, body, .. } => {
             ctx.register_property("Let", name, params, body);
             ctx.log(&format!("Defined Property Let {}", name));
             ControlFlow::Continue
         }
 
         // Record Property Set for later calls
-        Statement::PropertySet { name, params, body } => {
+        Statement::PropertySet { name, params, body, .. } => {
             ctx.register_property("Set", name, params, body);
             ctx.log(&format!("Defined Property Set {}", name));
             ControlFlow::Continue
@@ -167,7 +198,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
         //     ControlFlow::Continue
         // }
 
-        Statement::Dim { names } => {
+        Statement::Dim { names } | Statement::Static { names } => {
             for (v, maybe_type) in names {
                 // Register this variable as declared (for Option Explicit)
                 ctx.declare_variable(v);
@@ -178,40 +209,32 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                         match ctx.create_type_instance(type_name) {
                             Some(instance) => {
                                 // Set the type in context
-                                ctx.set_var_type(v.clone(), crate::context::DeclaredType::Variant);
+                                ctx.set_var_type(v.as_str(), crate::context::DeclaredType::Variant);
                                 instance
                             }
                             None => {
                                 // fallback to empty string
-                                ctx.set_var_type(v.clone(), crate::context::DeclaredType::Variant);
+                                ctx.set_var_type(v.as_str(), crate::context::DeclaredType::Variant);
                                 Value::String(String::new())
                             }
                         }
                     } else {
-                        let ty = crate::context::DeclaredType::from_opt_str(Some(type_name));
-                        ctx.set_var_type(v.clone(), ty);
-                        match ty {
-                            crate::context::DeclaredType::Byte     => Value::Byte(0),
-                            crate::context::DeclaredType::Integer  => Value::Integer(0),
-                            crate::context::DeclaredType::Long     => Value::Long(0),
-                            crate::context::DeclaredType::LongLong => Value::LongLong(0),
-                            crate::context::DeclaredType::Object   => Value::Object(None), 
-                            crate::context::DeclaredType::Currency => Value::Currency(0.0),
-                            crate::context::DeclaredType::Date     => chrono::NaiveDate::from_ymd_opt(1899,12,30).map(Value::Date).unwrap_or(Value::Date(chrono::NaiveDate::from_ymd_opt(1899,12,30).unwrap())),
-                            crate::context::DeclaredType::Double   => Value::Double(0.0),
-                            crate::context::DeclaredType::Decimal  => Value::Decimal(0.0),
-                            crate::context::DeclaredType::Single   => Value::Single(0.0),
-                            crate::context::DeclaredType::String   => Value::String(String::new()),
-                            crate::context::DeclaredType::Boolean  => Value::Boolean(false),
-                            crate::context::DeclaredType::Variant  => Value::Empty,  // Uninitialized Variant is Empty
-                        }
+                        let ty = ctx.declared_type(Some(type_name));
+                        ctx.set_var_type(v.as_str(), ty);
+                        ty.default_value()
                     }
                 } else {
-                    // No type specified - default to Variant (Empty)
-                    ctx.set_var_type(v.clone(), crate::context::DeclaredType::Variant);
-                    Value::Empty
+                    // No type specified - the DefType for its first letter, Variant (Empty) by default
+                    let ty = ctx.def_type(v);
+                    ctx.set_var_type(v.as_str(), ty);
+                    ty.default_value()
+                };
+                // A Static local picks up where the last call left it
+                let initial_value = match stmt {
+                    Statement::Static { .. } => ctx.declare_static(v.as_str(), initial_value),
+                    _ => initial_value,
                 };
-                ctx.declare_local(v.clone(), initial_value);
+                ctx.declare_local(v.as_str(), initial_value);
             }
             ControlFlow::Continue
         }
@@ -219,8 +242,17 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
 
         // SET/Assignment
         Statement::Set { target, expr } => {
+            // Set stores a reference; a value that is not an object (or
+            // Nothing) is error 424
             if let Some(val) = eval_opt(expr, ctx) {
-                ctx.set_var(target.clone(), val);
+                if !is_object(&val) {
+                    raise(ctx, VbaErr::ObjectRequired, format!("Set {} = {}", target, val.as_string()));
+                    if let Some(flow) = maybe_handle_error(ctx, pc) {
+                        return flow;
+                    }
+                    return ControlFlow::Continue;
+                }
+                ctx.set_var(target.as_str(), val);
             }
             ControlFlow::Continue
         }
@@ -326,18 +358,14 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
         
         //     ControlFlow::Continue
         // }
-        Statement::Assignment { lvalue, rvalue } => {
+        Statement::Assignment { lvalue, rvalue, rvalue_compiled } => {
             let had_previous_error = ctx.err.is_some();
             // 1) Evaluate the RHS expression safely, catching interpreter errors
-            let rhs_val_res = crate::interpreter::evaluate_expression(rvalue, ctx);
+            let rhs_val_res = rvalue_compiled.eval(rvalue, ctx);
 
             if let Err(e) = rhs_val_res.as_ref() {
                 // Capture the runtime error into the VBA Err object
-                ctx.err = Some(ErrObject {
-                    number: 13,
-                    description: e.to_string(),
-                    source: "Interpreter".into(),
-                });
+                raise_from(ctx, e, VbaErr::TypeMismatch);
             }
             // Only trigger error handling if this is a NEW error
             if ctx.err.is_some() && !had_previous_error {
@@ -367,6 +395,29 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                 Ok(v) => v,
                 Err(_) => return ControlFlow::Continue,
             };
+            // Without Set, an object is assigned through its default member:
+            // x = Range("A1") reads the cell's Value, and Nothing has none
+            if matches!(rhs_val, Value::Object(None)) {
+                raise(ctx, VbaErr::ObjectNotSet, format!("{} = Nothing", lvalue));
+                if let Some(flow) = maybe_handle_error(ctx, pc) {
+                    return flow;
+                }
+                return ControlFlow::Continue;
+            }
+            let rhs_val = match &rhs_val {
+                Value::Object(Some(_)) => match crate::interpreter::expressions::resolve_default(&rhs_val, None, ctx) {
+                    Some(Ok(value)) => value,
+                    Some(Err(e)) => {
+                        raise_from(ctx, &e, VbaErr::ObjectDoesntSupport);
+                        if let Some(flow) = maybe_handle_error(ctx, pc) {
+                            return flow;
+                        }
+                        return ControlFlow::Continue;
+                    }
+                    None => rhs_val,
+                },
+                _ => rhs_val,
+            };
 
             // 4) Now perform the actual assignment
             match lvalue {
@@ -394,11 +445,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                                                     return ControlFlow::Continue;
                                                 }
                                                 Err(e) => {
-                                                    ctx.err = Some(ErrObject {
-                                                        number: 13,
-                                                        description: format!("Error setting Range property: {}", e),
-                                                        source: "Interpreter".into(),
-                                                    });
+                                                    raise_from(ctx, &e, VbaErr::TypeMismatch);
                                                     if let Some(flow) = maybe_handle_error(ctx, pc) {
                                                         return flow;
                                                     }
@@ -407,11 +454,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                                             }
                                         }
                                         Err(e) => {
-                                            ctx.err = Some(ErrObject {
-                                                number: 11,
-                                                description: e.to_string(),
-                                                source: "Interpreter".into(),
-                                            });
+                                            raise_from(ctx, &e, VbaErr::TypeMismatch);
                                             if let Some(flow) = maybe_handle_error(ctx, pc) {
                                                 return flow;
                                             }
@@ -420,11 +463,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                                     }
                                 }
                             } else {
-                                ctx.err = Some(ErrObject {
-                                    number: 91,
-                                    description: "'.Range()' used outside of With block".to_string(),
-                                    source: "Interpreter".into(),
-                                });
+                                raise(ctx, VbaErr::ObjectNotSet, "'.Range()' used outside of With block");
                                 if let Some(flow) = maybe_handle_error(ctx, pc) {
                                     return flow;
                                 }
@@ -433,9 +472,52 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                         }
                     }
                     
+                    // Word/Outlook/VB objects and forms: Selection.Text = "...", mail.Subject = "...",
+                    // ActiveDocument.Range(0, 5).Text = "..." (Word host only)
+                    {
+                        let target = match object.as_ref() {
+                            crate::ast::Expression::Identifier(obj_name) => {
+                                ctx.get_var(obj_name)
+                                    .or_else(|| crate::host::word::global_object(ctx, obj_name))
+                                    .or_else(|| crate::host::userform::global_object(ctx, obj_name))
+                                    .or_else(|| crate::host::vb::global_object(obj_name))
+                            }
+                            other if crate::host::word::is_active(ctx) || crate::host::userform::is_form_expression(ctx, other) => {
+                                crate::interpreter::evaluate_expression(other, ctx).ok()
+                            }
+                            _ => None,
+                        };
+                        if let Some(target) = target.as_ref().and_then(crate::host::ObjectRef::parse) {
+                            if let Err(e) = target.set_property(property, rhs_val.clone(), ctx) {
+                                raise_from(ctx, &e, VbaErr::ObjectDoesntSupport);
+                                if let Some(flow) = maybe_handle_error(ctx, pc) {
+                                    return flow;
+                                }
+                            }
+                            return ControlFlow::Continue;
+                        }
+                    }
+
                     // Try to handle FunctionCall objects (e.g., Range(...).something)
                     if let crate::ast::Expression::FunctionCall { function, args } = object.as_ref() {
                         if let crate::ast::Expression::Identifier(fn_name) = function.as_ref() {
+                            if fn_name.eq_ignore_ascii_case("Rows") || fn_name.eq_ignore_ascii_case("Columns") {
+                                // Case: Rows(2).Hidden = True / Columns("C").ColumnWidth = 12
+                                let address = args.first()
+                                    .and_then(|arg| crate::interpreter::evaluate_expression(arg, ctx).ok())
+                                    .and_then(|index| crate::host::excel::objects::range::rows_columns_address(fn_name, &index));
+                                let result = match address {
+                                    Some(address) => crate::host::excel::properties::set_property("range", &address, property, rhs_val.clone(), ctx),
+                                    None => Err(anyhow::anyhow!("{}() requires a row/column index", fn_name)),
+                                };
+                                if let Err(e) = result {
+                                    raise_from(ctx, &e, VbaErr::ApplicationDefined);
+                                    if let Some(flow) = maybe_handle_error(ctx, pc) {
+                                        return flow;
+                                    }
+                                }
+                                return ControlFlow::Continue;
+                            }
                             if fn_name.eq_ignore_ascii_case("Range") {
                                 // Case: Range(...).Value = xxx
                                 if let Some(arg) = args.first() {
@@ -449,11 +531,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                                             match crate::host::excel::properties::set_property("range", &address, property, rhs_val.clone(), ctx) {
                                                 Ok(_) => return ControlFlow::Continue,
                                                 Err(e) => {
-                                                    ctx.err = Some(ErrObject {
-                                                        number: 13,
-                                                        description: format!("Error setting Range property: {}", e),
-                                                        source: "Interpreter".into(),
-                                                    });
+                                                    raise_from(ctx, &e, VbaErr::TypeMismatch);
                                                     if let Some(flow) = maybe_handle_error(ctx, pc) {
                                                         return flow;
                                                     }
@@ -462,11 +540,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                                             }
                                         }
                                         Err(e) => {
-                                            ctx.err = Some(ErrObject {
-                                                number: 11,
-                                                description: e.to_string(),
-                                                source: "Interpreter".into(),
-                                            });
+                                            raise_from(ctx, &e, VbaErr::TypeMismatch);
                                             if let Some(flow) = maybe_handle_error(ctx, pc) {
                                                 return flow;
                                             }
@@ -492,11 +566,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                                             match crate::host::excel::properties::set_property("range", &address, property, rhs_val.clone(), ctx) {
                                                 Ok(_) => return ControlFlow::Continue,
                                                 Err(e) => {
-                                                    ctx.err = Some(ErrObject {
-                                                        number: 13,
-                                                        description: format!("Error setting Range property: {}", e),
-                                                        source: "Interpreter".into(),
-                                                    });
+                                                    raise_from(ctx, &e, VbaErr::TypeMismatch);
                                                     if let Some(flow) = maybe_handle_error(ctx, pc) {
                                                         return flow;
                                                     }
@@ -505,11 +575,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                                             }
                                         }
                                         Err(e) => {
-                                            ctx.err = Some(ErrObject {
-                                                number: 11,
-                                                description: e.to_string(),
-                                                source: "Interpreter".into(),
-                                            });
+                                            raise_from(ctx, &e, VbaErr::TypeMismatch);
                                             if let Some(flow) = maybe_handle_error(ctx, pc) {
                                                 return flow;
                                             }
@@ -522,16 +588,33 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                         }
                     }
                     
+                    // Case: Range("A2").Validation.InputMessage = "..." / c.Visible = True / ActiveCell.Value = 1 /
+                    // Worksheets(1).Range("A1").Offset(1, 0).Value = 2
+                    // where the object evaluates to an Excel sub-object reference
+                    if let crate::ast::Expression::PropertyAccess { .. } | crate::ast::Expression::Identifier(_) | crate::ast::Expression::FunctionCall { .. } = object.as_ref() {
+                        let target = match object.as_ref() {
+                            crate::ast::Expression::Identifier(obj_name) => {
+                                ctx.get_var(obj_name).or_else(|| crate::interpreter::expressions::excel_global_object(obj_name))
+                            }
+                            other => crate::interpreter::evaluate_expression(other, ctx).ok(),
+                        };
+                        if let Some((object_type, data)) = target.as_ref().and_then(crate::host::excel::objects::object_ref) {
+                            if let Err(e) = crate::host::excel::objects::dispatch_property_set(&object_type, &data, property, rhs_val.clone(), ctx) {
+                                raise_from(ctx, &e, VbaErr::ApplicationDefined);
+                                if let Some(flow) = maybe_handle_error(ctx, pc) {
+                                    return flow;
+                                }
+                            }
+                            return ControlFlow::Continue;
+                        }
+                    }
+
                     // Fallback: treat object as identifier
                     if let crate::ast::Expression::Identifier(obj_name) = object.as_ref() {
                         // Check if object variable is declared (Option Explicit)
                         if let Err(e) = ctx.validate_variable_usage(obj_name) {
                             ctx.log(&e);
-                            ctx.err = Some(ErrObject {
-                                number: 451, // VBA error: Variable not defined
-                                description: e,
-                                source: "Interpreter".into(),
-                            });
+                            raise(ctx, VbaErr::VariableNotDefined, e);
                             if let Some(flow) = maybe_handle_error(ctx, pc) {
                                 return flow;
                             }
@@ -549,11 +632,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                             ) {
                                 Ok(_) => return ControlFlow::Continue,
                                 Err(e) => {
-                                    ctx.err = Some(ErrObject {
-                                        number: 13, // Type mismatch, or more specific COM error
-                                        description: format!("COM error: {}", e),
-                                        source: "Interpreter".into(),
-                                    });
+                                    raise_from(ctx, &e, VbaErr::TypeMismatch);
                                     if let Some(flow) = maybe_handle_error(ctx, pc) {
                                         return flow;
                                     }
@@ -570,11 +649,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                                 }
                                 Err(e) => {
                                     ctx.log(&format!("Error setting field: {}", e));
-                                    ctx.err = Some(ErrObject {
-                                        number: 13,
-                                        description: format!("Error setting field: {}", e),
-                                        source: "Interpreter".into(),
-                                    });
+                                    raise(ctx, VbaErr::ObjectRequired, e);
                                     if let Some(flow) = maybe_handle_error(ctx, pc) {
                                         return flow;
                                     }
@@ -583,11 +658,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                             }
                         } else {
                             ctx.log(&format!("Error: Variable '{}' not found", obj_name));
-                            ctx.err = Some(ErrObject {
-                                number: 91,
-                                description: format!("Variable '{}' not found", obj_name),
-                                source: "Interpreter".into(),
-                            });
+                            raise(ctx, VbaErr::ObjectRequired, obj_name);
                             if let Some(flow) = maybe_handle_error(ctx, pc) {
                                 return flow;
                             }
@@ -596,33 +667,52 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                     }
                 }
 
-                crate::ast::AssignmentTarget::Identifier(var_name) => {
-                    // Check if variable is declared when Option Explicit is enabled
-                    if let Err(e) = ctx.validate_variable_usage(var_name) {
+                crate::ast::AssignmentTarget::Identifier(spelled) => {
+                    // `x% = 1` declares x As Integer if it is new
+                    let (var_name, suffix) = crate::ast::split_type_suffix(spelled);
+
+                    // Check if variable is declared when Option Explicit is
+                    // enabled, or whether RuntimeConfig::implicit_variables
+                    // lets the assignment create it
+                    if let Err(e) = ctx.validate_variable_usage(var_name).and_then(|()| ctx.create_implicit(var_name)) {
                         ctx.log(&e);
-                        ctx.err = Some(ErrObject {
-                            number: 451, // VBA error: Variable not defined
-                            description: e,
-                            source: "Interpreter".into(),
-                        });
+                        raise(ctx, VbaErr::VariableNotDefined, e);
+                        if let Some(flow) = maybe_handle_error(ctx, pc) {
+                            return flow;
+                        }
+                        return ControlFlow::Continue;
+                    }
+
+                    // A variable holding an object assigns to its default member:
+                    // Set r = Range("A1"): r = 5. An Object variable set to
+                    // Nothing has no object to assign through.
+                    if matches!(ctx.get_var(var_name), Some(Value::Object(None))) && ctx.get_var_type(var_name) == Some(crate::context::DeclaredType::Object) {
+                        raise(ctx, VbaErr::ObjectNotSet, var_name);
                         if let Some(flow) = maybe_handle_error(ctx, pc) {
                             return flow;
                         }
                         return ControlFlow::Continue;
                     }
+                    if let Some(current @ Value::Object(Some(_))) = ctx.get_var(var_name) {
+                        if let Some(result) = crate::interpreter::expressions::assign_default(&current, rhs_val.clone(), ctx) {
+                            if let Err(e) = result {
+                                raise_from(ctx, &e, VbaErr::ObjectDoesntSupport);
+                                if let Some(flow) = maybe_handle_error(ctx, pc) {
+                                    return flow;
+                                }
+                            }
+                            return ControlFlow::Continue;
+                        }
+                    }
                     
                     if let Some(ty) = ctx.get_var_type(var_name) {
                         match crate::interpreter::coerce::coerce_to_declared(rhs_val, ty) {
                             Ok(v) => {
-                                ctx.set_var(var_name.clone(), v);
+                                ctx.set_var(var_name, v);
                             }
                             Err(e) => {
                                 ctx.log(&format!("Type mismatch assigning to {}: {}", var_name, e));
-                                ctx.err = Some(ErrObject {
-                                    number: 13,
-                                    description: format!("Type mismatch assigning to {}: {}", var_name, e),
-                                    source: "Interpreter".into(),
-                                });
+                                raise_from(ctx, &e, VbaErr::TypeMismatch);
                                 if let Some(flow) = maybe_handle_error(ctx, pc) {
                                     return flow;
                                 }
@@ -630,19 +720,50 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                             }
                         }
                     } else {
-                        // No declared type => Variant semantics
-                        ctx.set_var(var_name.clone(), rhs_val);
+                        // Undeclared: the type character or DefType, else Variant semantics
+                        let ty = match suffix {
+                            Some(t) => crate::context::DeclaredType::from_opt_str(Some(t)),
+                            None => ctx.def_type(var_name),
+                        };
+                        if ty == crate::context::DeclaredType::Variant || ctx.get_var(var_name).is_some() {
+                            ctx.set_var(var_name, rhs_val);
+                        } else {
+                            match crate::interpreter::coerce::coerce_to_declared(rhs_val, ty) {
+                                Ok(v) => {
+                                    ctx.set_var(var_name, v);
+                                    ctx.set_var_type(var_name, ty);
+                                }
+                                Err(e) => {
+                                    raise_from(ctx, &e, VbaErr::TypeMismatch);
+                                    if let Some(flow) = maybe_handle_error(ctx, pc) {
+                                        return flow;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                crate::ast::AssignmentTarget::DefaultMember { object } => {
+                    // Range("A1") = 5 / c(1) = "x": set the default member of
+                    // the object the target evaluates to
+                    let result = crate::interpreter::evaluate_expression(object, ctx).and_then(|target| {
+                        crate::interpreter::expressions::assign_default(&target, rhs_val.clone(), ctx).unwrap_or_else(|| {
+                            Err(VbaErr::ObjectDoesntSupport.error(format!("{:?}", target)))
+                        })
+                    });
+                    if let Err(e) = result {
+                        raise_from(ctx, &e, VbaErr::ObjectDoesntSupport);
+                        if let Some(flow) = maybe_handle_error(ctx, pc) {
+                            return flow;
+                        }
                     }
                 }
 
                 crate::ast::AssignmentTarget::WithMemberAccess { property } => {
                     // Handle .Property = value inside a With block
                     if ctx.with_stack.is_empty() {
-                        ctx.err = Some(ErrObject {
-                            number: 91,
-                            description: "Invalid use of '.' - no With object in scope".to_string(),
-                            source: "Interpreter".into(),
-                        });
+                        raise(ctx, VbaErr::ObjectNotSet, "Invalid use of '.' - no With object in scope");
                         if let Some(flow) = maybe_handle_error(ctx, pc) {
                             return flow;
                         }
@@ -662,11 +783,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                         Err(e) => {
                             let err_msg = format!("Error setting With field: {}", e);
                             ctx.log(&err_msg);
-                            ctx.err = Some(ErrObject {
-                                number: 13,
-                                description: err_msg,
-                                source: "Interpreter".into(),
-                            });
+                            raise(ctx, VbaErr::ObjectRequired, e);
                             if let Some(flow) = maybe_handle_error(ctx, pc) {
                                 return flow;
                             }
@@ -678,11 +795,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                 crate::ast::AssignmentTarget::WithMethodCall { method, args } => {
                     // Handle .Method(args).Property = value inside a With block (e.g., .Range("A1").Value = 5)
                     if ctx.with_stack.is_empty() {
-                        ctx.err = Some(ErrObject {
-                            number: 91,
-                            description: "Invalid use of '.' - no With object in scope".to_string(),
-                            source: "Interpreter".into(),
-                        });
+                        raise(ctx, VbaErr::ObjectNotSet, "Invalid use of '.' - no With object in scope");
                         if let Some(flow) = maybe_handle_error(ctx, pc) {
                             return flow;
                         }
@@ -712,11 +825,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                                                         return ControlFlow::Continue;
                                                     }
                                                     Err(e) => {
-                                                        ctx.err = Some(ErrObject {
-                                                            number: 13,
-                                                            description: format!("Error setting Range property: {}", e),
-                                                            source: "Interpreter".into(),
-                                                        });
+                                                        raise_from(ctx, &e, VbaErr::TypeMismatch);
                                                         if let Some(flow) = maybe_handle_error(ctx, pc) {
                                                             return flow;
                                                         }
@@ -733,11 +842,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                                                         return ControlFlow::Continue;
                                                     }
                                                     Err(e) => {
-                                                        ctx.err = Some(ErrObject {
-                                                            number: 13,
-                                                            description: format!("Error setting Range property: {}", e),
-                                                            source: "Interpreter".into(),
-                                                        });
+                                                        raise_from(ctx, &e, VbaErr::TypeMismatch);
                                                         if let Some(flow) = maybe_handle_error(ctx, pc) {
                                                             return flow;
                                                         }
@@ -746,11 +851,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                                                 }
                                             }
                                             Err(e) => {
-                                                ctx.err = Some(ErrObject {
-                                                    number: 11,
-                                                    description: e.to_string(),
-                                                    source: "Interpreter".into(),
-                                                });
+                                                raise_from(ctx, &e, VbaErr::TypeMismatch);
                                                 if let Some(flow) = maybe_handle_error(ctx, pc) {
                                                     return flow;
                                                 }
@@ -763,11 +864,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                         }
                     }
                     
-                    ctx.err = Some(ErrObject {
-                        number: 438,
-                        description: format!("Object doesn't support this property or method: .{}", method),
-                        source: "Interpreter".into(),
-                    });
+                    raise(ctx, VbaErr::ObjectDoesntSupport, format!(".{}", method));
                     if let Some(flow) = maybe_handle_error(ctx, pc) {
                         return flow;
                     }
@@ -779,9 +876,23 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
         }
         
 
-        Statement::MsgBox { expr } => {
+        Statement::MsgBox { expr, buttons, title } => {
             if let Some(val) = eval_opt(expr, ctx) {
-                ctx.log(&to_string(&val));
+                let val = match crate::interpreter::expressions::value_of(val, ctx) {
+                    Ok(val) => val,
+                    Err(e) => {
+                        raise_from(ctx, &e, VbaErr::ObjectDoesntSupport);
+                        if let Some(flow) = maybe_handle_error(ctx, pc) {
+                            return flow;
+                        }
+                        return ControlFlow::Continue;
+                    }
+                };
+                let prompt = to_string(&val);
+                ctx.log(&prompt);
+                let buttons = buttons.as_ref().and_then(|b| eval_opt(b, ctx)).and_then(|b| b.as_integer()).unwrap_or(0);
+                let title = title.as_ref().and_then(|t| eval_opt(t, ctx)).map(|t| to_string(&t));
+                crate::host::ui::msg_box(ctx, crate::host::ui::MsgBoxRequest { prompt, buttons, title });
             }
             ControlFlow::Continue
         }
@@ -827,11 +938,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                     result
                 }
                 Err(e) => {
-                    ctx.err = Some(ErrObject {
-                        number: 91,
-                        description: format!("With object evaluation failed: {}", e),
-                        source: "Interpreter".into(),
-                    });
+                    raise_from(ctx, &e, VbaErr::ObjectNotSet);
                     if let Some(flow) = maybe_handle_error(ctx, pc) {
                         return flow;
                     }
@@ -842,10 +949,22 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
 
         Statement::Exit(exit_type) => ControlFlow::from_exit_type(exit_type),
 
+        // The VM knows the line; see execute_statement_in_vm
+        Statement::Stop => stop(ctx, None, false),
+
         Statement::Label(_) => ControlFlow::Continue,
 
         Statement::Expression(expr) => {
-            let _ = eval_opt(expr, ctx);
+            // Host errors with a VBA error number (e.g. a protected sheet)
+            // are raised; other failed calls are ignored as before
+            if let Err(e) = crate::interpreter::evaluate_expression(expr, ctx) {
+                if crate::host::excel::error_number(&e).is_some() {
+                    raise_from(ctx, &e, VbaErr::ApplicationDefined);
+                    if let Some(flow) = maybe_handle_error(ctx, pc) {
+                        return flow;
+                    }
+                }
+            }
             ControlFlow::Continue
         }
 
@@ -947,11 +1066,29 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
 
         // In the Call statement handler, update parameter declaration:
         Statement::Call { function, args } => {
-            if handle_builtin_call_bool(function, args, ctx) {
-                return ControlFlow::Continue;
+            // Declare'd DLL procedures (host::dll), then builtins
+            let handled = match crate::host::dll::call_declared(function, args, ctx) {
+                Some(result) => result.map(|_| true),
+                None => handle_builtin_call_bool(function, args, ctx),
+            };
+            match handled {
+                Ok(true) => return ControlFlow::Continue,
+                Ok(false) => {}
+                Err(e) => {
+                    raise_from(ctx, &e, VbaErr::DllLoadError);
+                    return maybe_handle_error(ctx, pc).unwrap_or(ControlFlow::Continue);
+                }
             }
 
-            let (params, body) = match ctx.subs.get(function).cloned() {
+            // `Module.Proc`, or the caller's module's Proc when several define it
+            let function = &match ctx.resolve_procedure(function) {
+                Ok(key) => key.unwrap_or_else(|| function.clone()),
+                Err(e) => {
+                    raise_from(ctx, &e, VbaErr::SubOrFunctionNotDefined);
+                    return maybe_handle_error(ctx, pc).unwrap_or(ControlFlow::Continue);
+                }
+            };
+            let (params, body) = match ctx.get_sub(function).cloned() {
                 Some(pb) => pb,
                 None => {
                     ctx.log(&format!("*** Call `{}` not implemented", function));
@@ -978,19 +1115,22 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                 }
             }
 
-            ctx.log(&format!("Entering Sub {}", function));
-            ctx.push_scope(function.clone(), ScopeKind::Subroutine);
-            
+            let bindings = byref_bindings(&params, args);
+
+            if let Err(e) = ctx.enter_procedure(function.clone(), ScopeKind::Subroutine) {
+                raise_from(ctx, &e, VbaErr::OutOfStackSpace);
+                return maybe_handle_error(ctx, pc).unwrap_or(ControlFlow::Continue);
+            }
+
             // Declare parameters in the new scope (important for Option Explicit)
             for (param, val) in params.iter().zip(arg_vals.into_iter()) {
                 ctx.declare_variable(&param.name);  // Use param.name for Parameter struct
-                ctx.declare_local(param.name.clone(), val);
+                ctx.declare_local(param.name.as_str(), val);
             }
 
             let flow = execute_statement_list(&body, ctx);
 
-            ctx.pop_scope();
-            ctx.log(&format!("Leaving Sub {}", function));
+            pop_call_scope(ctx, &bindings);
 
             match flow {
                 ControlFlow::Continue
@@ -1008,6 +1148,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
                 | ControlFlow::ErrorGoToLabel(_)
                 | ControlFlow::ResumeNext
                 | ControlFlow::FramePushed
+                | ControlFlow::FrameReused
                 | ControlFlow::ResumeCurrent => ControlFlow::Continue,
             }
         }
@@ -1023,13 +1164,7 @@ pub(crate) fn execute_statement(stmt: &Statement, ctx: &mut Context, pc: usize)
 
 /// Execute a list of statements until completion or control transfer.
 pub fn execute_statement_list(stmts: &[Statement], ctx: &mut Context) -> ControlFlow {
-    // Pre-index labels
-    let mut labels = HashMap::<String, usize>::new();
-    for (idx, s) in stmts.iter().enumerate() {
-        if let Statement::Label(name) = s {
-            labels.insert(name.clone(), idx);
-        }
-    }
+    let labels = crate::vm::frame::index_labels(stmts);
 
     let mut i = 0usize;
     while i < stmts.len() {
@@ -1038,31 +1173,33 @@ pub fn execute_statement_list(stmts: &[Statement], ctx: &mut Context) -> Control
         let flow = execute_statement(&stmts[i], ctx, i);
         //println!("◀️  Statement returned: {:?}", flow);
         match flow {
+            // A Stop under StopPolicy::Halt ends the whole run
+            ControlFlow::Continue if ctx.halted => return ControlFlow::ExitSub,
             ControlFlow::Continue => i += 1,
 
             ControlFlow::ResumeCurrent => {
                 //println!("   🔄 Processing ResumeCurrent");
                 if !ctx.resume_valid {
-                    return raise_runtime_error(ctx, 20, "Invalid Resume", i);
+                    return raise_runtime_error(ctx, VbaErr::ResumeWithoutError, "Resume", i);
                 }
                 if let Some(pc) = ctx.resume_pc {
                     ctx.resume_valid = false;
                     i = pc; // re-exec faulting statement
                 } else {
-                    return raise_runtime_error(ctx, 20, "Resume without error", i);
+                    return raise_runtime_error(ctx, VbaErr::ResumeWithoutError, "", i);
                 }
             }
 
             ControlFlow::ResumeNext => {
                 if !ctx.resume_valid {
-                    return raise_runtime_error(ctx, 20, "Invalid Resume Next", i);
+                    return raise_runtime_error(ctx, VbaErr::ResumeWithoutError, "Resume Next", i);
                 }
                 if let Some(pc) = ctx.resume_pc {
                     ctx.resume_valid = false;
                     //println!("   🔄 Continuing at statement {}", pc + 1);
                     i = pc + 1; // continue after faulting statement
                 } else {
-                    return raise_runtime_error(ctx, 20, "Resume Next without error", i);
+                    return raise_runtime_error(ctx, VbaErr::ResumeWithoutError, "Resume Next", i);
                 }
             }
             ControlFlow::ErrorGoToLabel(lbl) => {
@@ -1073,14 +1210,14 @@ pub fn execute_statement_list(stmts: &[Statement], ctx: &mut Context) -> Control
             ControlFlow::GoToLabel(lbl) => {
                 //println!("   🎯 Processing GoTo: {}", lbl);
                 
-                if let Some(&dest) = labels.get(&lbl) {
+                if let Some(&dest) = labels.get(&Symbol::intern(&lbl)) {
                     // jumping invalidates armed resume
                     ctx.resume_valid = false;
                     // println!("   🎯 Jumping to statement {}", dest);
                     i = dest + 1;
                 } else {
                     // println!("   🎯 Label not in this scope, bubbling up");
-                    // return raise_runtime_error(ctx, 35, "Label not defined", i);
+                    // return raise_runtime_error(ctx, VbaErr::SubOrFunctionNotDefined, "Label not defined", i);
                     return ControlFlow::GoToLabel(lbl);
                 }
             }
@@ -1097,6 +1234,7 @@ pub fn execute_statement_list(stmts: &[Statement], ctx: &mut Context) -> Control
             | ControlFlow::ExitWhile
             | ControlFlow::ContinueWhile
             | ControlFlow::FramePushed
+            | ControlFlow::FrameReused
             | ControlFlow::ExitSelect => {
                     return flow;
             }
@@ -1107,23 +1245,44 @@ pub fn execute_statement_list(stmts: &[Statement], ctx: &mut Context) -> Control
     ControlFlow::Continue
 }
 
+/// Act on a `Stop` statement, or a breakpoint on `line`, per
+/// `RuntimeConfig::stop_policy`
+pub(crate) fn stop(ctx: &mut Context, line: Option<usize>, breakpoint: bool) -> ControlFlow {
+    let policy = ctx.runtime_config.stop_policy;
+    let report = crate::context::StopReport { line, breakpoint, policy };
+    ctx.log(&report.to_text());
+    ctx.set_stop(report);
+    match policy {
+        StopPolicy::Halt => {
+            ctx.halted = true;
+            ControlFlow::ExitSub
+        }
+        // The VM hibernates before the next statement
+        StopPolicy::Pause => {
+            ctx.request_hibernation();
+            ControlFlow::Continue
+        }
+        StopPolicy::Ignore => ControlFlow::Continue,
+    }
+}
+
 /// Minimal `For` loop driver.
 fn execute_for_loop(for_stmt: &ForStatement, ctx: &mut Context, pc: usize) -> ControlFlow {
     // Evaluate bounds
     let start_val = match eval_opt(&for_stmt.start, ctx) {
         Some(v) => v,
-        None => return raise_runtime_error(ctx, 13, "Type mismatch in For start", pc),
+        None => return raise_runtime_error(ctx, VbaErr::TypeMismatch, "For start", pc),
     };
 
     let end_val = match eval_opt(&for_stmt.end, ctx) {
         Some(v) => v,
-        None => return raise_runtime_error(ctx, 13, "Type mismatch in For end", pc),
+        None => return raise_runtime_error(ctx, VbaErr::TypeMismatch, "For end", pc),
     };
 
     let step_val = if let Some(step_expr) = &for_stmt.step {
         match eval_opt(step_expr, ctx) {
             Some(v) => v,
-            None => return raise_runtime_error(ctx, 13, "Type mismatch in For Step", pc),
+            None => return raise_runtime_error(ctx, VbaErr::TypeMismatch, "For Step", pc),
         }
     } else {
         Value::Integer(1)
@@ -1132,24 +1291,28 @@ fn execute_for_loop(for_stmt: &ForStatement, ctx: &mut Context, pc: usize) -> Co
     // Coerce
     let start_int = match value_to_integer(&start_val) {
         Ok(n) => n,
-        Err(_) => return raise_runtime_error(ctx, 13, "Type mismatch in For start", pc),
+        Err(_) => return raise_runtime_error(ctx, VbaErr::TypeMismatch, "For start", pc),
     };
     let end_int = match value_to_integer(&end_val) {
         Ok(n) => n,
-        Err(_) => return raise_runtime_error(ctx, 13, "Type mismatch in For end", pc),
+        Err(_) => return raise_runtime_error(ctx, VbaErr::TypeMismatch, "For end", pc),
     };
     let step_int = match value_to_integer(&step_val) {
         Ok(n) => n,
-        Err(_) => return raise_runtime_error(ctx, 13, "Type mismatch in For Step", pc),
+        Err(_) => return raise_runtime_error(ctx, VbaErr::TypeMismatch, "For Step", pc),
     };
 
     if step_int == 0 {
-        return raise_runtime_error(ctx, 6, "For Step cannot be zero", pc);
+        return raise_runtime_error(ctx, VbaErr::Overflow, "For Step cannot be zero", pc);
+    }
+
+    if let Err(e) = ctx.create_implicit(&for_stmt.counter) {
+        return raise_runtime_error(ctx, VbaErr::VariableNotDefined, &e, pc);
     }
 
     // Initialize loop counter
     let mut counter = start_int;
-    ctx.set_var(for_stmt.counter.clone(), Value::Integer(counter));
+    ctx.set_var(for_stmt.counter.as_str(), Value::Integer(counter));
     //println!("\n🔁 === FOR LOOP START: {} from {} to {} step {} ===", 
             //for_stmt.counter, start_int, end_int, step_int);
     loop {
@@ -1189,12 +1352,16 @@ fn execute_for_loop(for_stmt: &ForStatement, ctx: &mut Context, pc: usize) -> Co
 
             ControlFlow::ResumeCurrent => return ControlFlow::ResumeCurrent,
             ControlFlow::FramePushed => return ControlFlow::FramePushed,
+            ControlFlow::FrameReused => return ControlFlow::FrameReused,
         }
 
         // Step
-        counter += step_int;
+        counter = match counter.checked_add(step_int) {
+            Some(next) => next,
+            None => return raise_runtime_error(ctx, VbaErr::Overflow, "", pc),
+        };
         println!("🔁 Stepping: {} = {}", for_stmt.counter, counter);
-        ctx.set_var(for_stmt.counter.clone(), Value::Integer(counter));
+        ctx.set_var(for_stmt.counter.as_str(), Value::Integer(counter));
     }
 
     ControlFlow::Continue
@@ -1210,7 +1377,7 @@ pub fn execute_do_while_loop(do_stmt: &DoWhileStatement, ctx: &mut Context, pc:
     let should_continue = |ctx: &mut Context| -> Result<bool, ControlFlow> {
         match &do_stmt.condition {
             Some(cond_expr) => {
-                match eval_opt(cond_expr, ctx) {
+                match do_stmt.condition_compiled.eval(cond_expr, ctx).ok() {
                     Some(val) => {
                         let truthy = is_truthy(&val);
                         match do_stmt.condition_type {
@@ -1220,7 +1387,7 @@ pub fn execute_do_while_loop(do_stmt: &DoWhileStatement, ctx: &mut Context, pc:
                         }
                     }
                     Option::None => {  // Explicitly use Option::None
-                        Err(raise_runtime_error(ctx, 13, "Type mismatch in Do loop condition", pc))
+                        Err(raise_runtime_error(ctx, VbaErr::TypeMismatch, "Do loop condition", pc))
                     }
                 }
             }
@@ -1272,6 +1439,7 @@ pub fn execute_do_while_loop(do_stmt: &DoWhileStatement, ctx: &mut Context, pc:
                 ControlFlow::ExitProperty  => return ControlFlow::ExitProperty,
                 ControlFlow::ResumeCurrent => return ControlFlow::ResumeCurrent,
                 ControlFlow::FramePushed => return ControlFlow::FramePushed,
+                ControlFlow::FrameReused => return ControlFlow::FrameReused,
             }
         }
     } 
@@ -1309,6 +1477,7 @@ pub fn execute_do_while_loop(do_stmt: &DoWhileStatement, ctx: &mut Context, pc:
                 ControlFlow::ExitProperty  => return ControlFlow::ExitProperty,
                 ControlFlow::ResumeCurrent => return ControlFlow::ResumeCurrent,
                 ControlFlow::FramePushed => return ControlFlow::FramePushed,
+                ControlFlow::FrameReused => return ControlFlow::FrameReused,
             }
             
             // Check condition at end
@@ -1326,10 +1495,43 @@ pub fn execute_do_while_loop(do_stmt: &DoWhileStatement, ctx: &mut Context, pc:
     ControlFlow::Continue
 }
 
+/// Whether Set can store `value`: an object reference, Nothing, or a host
+/// sub-object ("Range:B2" from Offset, "Comment:B2")
+fn is_object(value: &Value) -> bool {
+    matches!(value, Value::Object(_)) || crate::host::excel::objects::object_ref(value).is_some()
+}
+
 fn eval_opt(expr: &Expression, ctx: &mut Context) -> Option<Value> {
     crate::interpreter::evaluate_expression(expr, ctx).ok()
 }
 
+/// Pair each ByRef parameter with the caller variable it aliases.
+/// Only bare identifiers are passed by reference; literals, expressions and
+/// parenthesized arguments (`Foo (x)`) are temporaries and behave as ByVal.
+pub(crate) fn byref_bindings(params: &[Parameter], args: &[Expression]) -> Vec<(String, String)> {
+    params
+        .iter()
+        .zip(args)
+        .filter(|(p, _)| p.passing == ParameterPassing::ByRef)
+        .filter_map(|(p, a)| match a {
+            Expression::Identifier(var) => Some((p.name.clone(), var.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Pop a Sub's scope, copying ByRef parameter values back to the caller.
+pub(crate) fn pop_call_scope(ctx: &mut Context, bindings: &[(String, String)]) {
+    let updates: Vec<(String, Value)> = bindings
+        .iter()
+        .filter_map(|(param, var)| ctx.get_var(param).map(|v| (var.clone(), v)))
+        .collect();
+    ctx.pop_scope();
+    for (var, val) in updates {
+        ctx.set_var(var, val);
+    }
+}
+
 fn is_truthy(v: &Value) -> bool {
     match v {
         Value::Boolean(b) => *b,
@@ -1415,8 +1617,8 @@ pub fn value_to_integer(value: &Value) -> Result<i64, String> {
 // Error raising that arms Resume and uses PC
 fn raise_runtime_error(
     ctx: &mut Context,
-    number: i32,
-    description: &str,
+    err: VbaErr,
+    detail: &str,
     current_pc: usize,
 ) -> ControlFlow {
     // eprintln!(
@@ -1424,12 +1626,9 @@ fn raise_runtime_error(
     //     number, description, current_pc, ctx.on_error_mode
     // );
 
-    ctx.err = Some(ErrObject {
-        number,
-        description: description.into(),
-        source: "Interpreter".into(),
-    });
+    raise(ctx, err, detail);
 
+    ctx.unhandled_error = ctx.on_error_mode == OnErrorMode::None;
     match ctx.on_error_mode {
         OnErrorMode::ResumeNextAuto => {
             // eprintln!("   → On Error Resume Next: skipping failing statement");
@@ -1463,11 +1662,12 @@ fn raise_runtime_error(
         }
     }
 }
-fn maybe_handle_error(ctx: &mut Context, pc: usize) -> Option<ControlFlow> {
+pub(crate) fn maybe_handle_error(ctx: &mut Context, pc: usize) -> Option<ControlFlow> {
     if ctx.err.is_none() {
         return None;
     }
 
+    ctx.unhandled_error = ctx.on_error_mode == OnErrorMode::None;
     match ctx.on_error_mode {
         OnErrorMode::ResumeNextAuto => {
             ctx.resume_valid = true;
@@ -1527,7 +1727,7 @@ fn execute_enum_statement(
         };
 
         enum_members.insert(member.name.clone(), value);
-        next_value = value + 1;
+        next_value = value.saturating_add(1);
     }
 
     ctx.define_enum(name.to_string(), enum_members);
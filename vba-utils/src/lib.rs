@@ -1,22 +1,41 @@
+pub mod analysis;
 pub mod ast;
+pub mod config_source;
 pub mod context;
+pub mod error;
 pub mod interpreter;
+pub mod memory;
+pub mod preprocess;
 pub mod runtime_config;
+pub mod scope;
+pub mod symbol;
+pub mod transform;
+pub mod transpiler;
+pub mod vba_errors;
+pub mod visit;
 pub mod vm;
 pub mod host;
 
 pub use ast::{Program, Statement as VbaAstNode, build_ast as _build_ast};
-pub use context::{Context, Value as VbaValue};
-pub use runtime_config::{RuntimeConfig, RuntimeConfigBuilder};
+pub use context::{Context, ContextSnapshot, Value as VbaValue};
+pub use error::{Limit, VbaError};
+pub use host::excel::async_backend::{AsyncExcelBackend, BackendFuture};
+pub use host::excel::backend::ExcelBackend;
+pub use host::excel::fixture::{assert_cell_eq, WorkbookFixture};
+pub use runtime_config::{AutoRunPolicy, ExcelBackendKind, ExecutionEngine, HostApplication, RuntimeConfig, RuntimeConfigBuilder, UnknownObjectPolicy, DllPolicy, InteractionPolicy, SandboxProfile, UserForm, StopPolicy, ImplicitVariablePolicy, ScheduledProcedure, HostFunction, HostUi};
+pub use symbol::Symbol;
 pub use interpreter::execute_ast;
-pub use vm::{ProgramExecutor, VbaRuntime};
+pub use vm::{Hibernation, ProgramExecutor, VbaRuntime};
 
 use tree_sitter::TreeCursor;
 
 /// Turn a `TreeCursor` at the root into a flat `Vec<Statement>` for your `main.rs`.
+///
+/// The tree is built as parsed; `ast::build_ast` also resolves `#If` and
+/// splits `:` separated statements and `_` continuations.
 pub fn walk_parse_tree(cursor: &mut TreeCursor, source: &str) -> Vec<VbaAstNode> {
     let root = cursor.node();
-    ast::build_ast(root, source).statements
+    ast::build_parsed_tree(root, source).statements
 }
 
 /// Existing parse‐tree printer you already have…
@@ -0,0 +1,131 @@
+// vba-utils/src/memory.rs
+
+//! Approximate memory accounting for a [`Context`].
+//!
+//! A macro can try to exhaust the host with `s = s & s` or
+//! `String$(2000000000, "x")`. With `RuntimeConfig::memory_limit` set, the
+//! places that build large values (concatenation, `Space`, `String$`,
+//! `Replace`) ask [`Context::check_allocation`] first. It adds the new
+//! value to what the variables, the With stack and the logged output
+//! already hold and raises error 7 ("Out of memory") past the limit, which
+//! `On Error` handles like any other run-time error.
+//!
+//! Sizes are estimates: string bytes are counted exactly, every variable,
+//! field and object reference is charged a fixed cost. Allocations below
+//! [`CHECK_THRESHOLD`] are not checked, so the walk over the variables only
+//! happens when something big is being built.
+
+use crate::context::{Context, Value};
+
+/// Allocations smaller than this are not checked
+pub const CHECK_THRESHOLD: usize = 4096;
+
+/// Charged per variable or user-defined type field
+const VALUE_BYTES: usize = std::mem::size_of::<Value>();
+/// Charged per object reference
+const OBJECT_BYTES: usize = 64;
+
+/// What a Context holds, as counted against `RuntimeConfig::memory_limit`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// Bytes in String values, including fields and object payloads
+    pub string_bytes: usize,
+    /// Variables and user-defined type fields
+    pub values: usize,
+    /// Object references (Nothing is not counted)
+    pub objects: usize,
+    /// Bytes logged to `Context::output`
+    pub output_bytes: usize,
+}
+
+impl MemoryUsage {
+    /// Estimated total in bytes
+    pub fn total_bytes(&self) -> usize {
+        self.string_bytes + self.output_bytes + self.values * VALUE_BYTES + self.objects * OBJECT_BYTES
+    }
+
+    fn add_value(&mut self, value: &Value) {
+        self.values += 1;
+        match value {
+            Value::String(s) => self.string_bytes += s.len(),
+            Value::Object(Some(inner)) => {
+                self.objects += 1;
+                self.values -= 1;
+                self.add_value(inner);
+            }
+            Value::UserType { fields, .. } => fields.values().for_each(|field| self.add_value(field)),
+            _ => {}
+        }
+    }
+}
+
+impl Context {
+    /// Estimate what the variables, With stack and output currently hold
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let mut usage = MemoryUsage::default();
+        self.variables.values().for_each(|v| usage.add_value(v));
+        for frame in self.scope_frames() {
+            frame.iter().for_each(|(_, v)| usage.add_value(v));
+        }
+        self.with_stack.iter().for_each(|v| usage.add_value(v));
+        usage.output_bytes = self.output.iter().map(String::len).sum();
+        usage
+    }
+
+    /// Error 7 if building a value of `bytes` more would go past
+    /// `RuntimeConfig::memory_limit`
+    pub(crate) fn check_allocation(&self, bytes: usize) -> anyhow::Result<()> {
+        let Some(limit) = self.runtime_config.memory_limit else {
+            return Ok(());
+        };
+        if bytes < CHECK_THRESHOLD {
+            return Ok(());
+        }
+        if bytes > limit || self.memory_usage().total_bytes().saturating_add(bytes) > limit {
+            return Err(crate::error::Limit::Memory.error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{AssignmentTarget, Expression, Statement};
+    use crate::runtime_config::RuntimeConfig;
+
+    #[test]
+    fn test_memory_limit_raises_error_7() {
+        let config = RuntimeConfig::builder().memory_limit(1 << 20).build();
+        let mut ctx = Context::with_config(config);
+        ctx.set_var("MemSeed", Value::String("x".repeat(1000)));
+        assert_eq!(ctx.memory_usage().string_bytes, 1000);
+
+        // s = s & s doubles until it no longer fits
+        let double = Statement::Assignment {
+            lvalue: AssignmentTarget::Identifier("MemSeed".into()),
+            rvalue: Expression::BinaryOp {
+                left: Box::new(Expression::Identifier("MemSeed".into())),
+                op: "&".into(),
+                right: Box::new(Expression::Identifier("MemSeed".into())),
+            },
+            rvalue_compiled: Default::default(),
+        };
+        for _ in 0..20 {
+            crate::interpreter::execute_statement_list(std::slice::from_ref(&double), &mut ctx);
+            if ctx.err.is_some() {
+                break;
+            }
+        }
+        assert_eq!(ctx.err.as_ref().map(|e| (e.number, e.limit)), Some((7, Some(crate::error::Limit::Memory))));
+        let held = ctx.get_var("MemSeed").unwrap().as_string().len();
+        assert!(held <= 1 << 20 && held >= 1 << 18);
+
+        let space = Expression::FunctionCall {
+            function: Box::new(Expression::Identifier("Space".into())),
+            args: vec![Expression::Integer(2_000_000_000)],
+        };
+        let err = crate::interpreter::evaluate_expression(&space, &mut ctx).unwrap_err();
+        assert_eq!(crate::host::excel::error_number(&err), Some(7));
+    }
+}
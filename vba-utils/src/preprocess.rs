@@ -0,0 +1,384 @@
+// src/preprocess.rs
+// ============================================================================
+// CONDITIONAL COMPILATION - #Const, #If ... #ElseIf ... #Else ... #End If
+//
+// Runs on the source text before tree-sitter sees it. Directive lines and
+// lines in branches that are not taken come out blank, so every surviving
+// line keeps its row and spans need no remapping. Conditions see the
+// module's #Const definitions on top of the compiler constants from
+// RuntimeConfig (VBA7, Win64, Mac, ... plus anything the embedder adds);
+// a name nobody defined is Empty, i.e. False, as in VBA.
+// ============================================================================
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::context::{Context, Value};
+
+/// Compiler constants keyed by lowercase name
+pub type CompilerConstants = HashMap<String, Value>;
+
+/// What the 64-bit Windows builds of Office define
+pub fn default_constants() -> CompilerConstants {
+    [("vba6", true), ("vba7", true), ("win16", false), ("win32", true), ("win64", true), ("mac", false)]
+        .into_iter()
+        .map(|(name, on)| (name.to_string(), Value::Boolean(on)))
+        .collect()
+}
+
+/// `Win64` is on: LongPtr is LongLong and Declare needs PtrSafe
+pub fn is_win64(constants: &CompilerConstants) -> bool {
+    constants
+        .get("win64")
+        .is_some_and(|v| crate::interpreter::coerce::to_bool(v).unwrap_or(false))
+}
+
+/// A malformed directive, by 1-based source line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectiveError {
+    pub line: usize,
+    pub message: String,
+}
+
+/// One open #If block
+struct Branch {
+    /// The enclosing block is live
+    parent_active: bool,
+    /// Some branch of this block has been taken already
+    taken: bool,
+    /// Lines in the current branch are kept
+    active: bool,
+}
+
+/// Resolve conditional compilation; the output has the same number of lines
+pub fn preprocess(source: &str, constants: &CompilerConstants) -> (String, Vec<DirectiveError>) {
+    if !source.lines().any(|l| l.trim_start().starts_with('#')) {
+        return (source.to_string(), Vec::new());
+    }
+
+    let mut constants = constants.clone();
+    let mut stack: Vec<Branch> = Vec::new();
+    let mut errors = Vec::new();
+    let mut out = String::with_capacity(source.len());
+
+    for (row, line) in source.split_inclusive('\n').enumerate() {
+        let eol_len = if line.ends_with("\r\n") { 2 } else if line.ends_with('\n') { 1 } else { 0 };
+        let (content, eol) = line.split_at(line.len() - eol_len);
+        let active = stack.last().is_none_or(|b| b.active);
+        let mut error = |message: String| errors.push(DirectiveError { line: row + 1, message });
+
+        let Some(directive) = content.trim_start().strip_prefix('#') else {
+            if active {
+                out.push_str(content);
+            }
+            out.push_str(eol);
+            continue;
+        };
+        out.push_str(eol);
+
+        let directive = strip_comment(directive).trim();
+        let (keyword, rest) = directive.split_once(char::is_whitespace).unwrap_or((directive, ""));
+        let rest = rest.trim();
+        match keyword.to_ascii_lowercase().as_str() {
+            "const" => {
+                if !active {
+                    continue;
+                }
+                let Some((name, expr)) = rest.split_once('=') else {
+                    error(format!("#Const needs a value: {}", directive));
+                    continue;
+                };
+                match evaluate(expr, &constants) {
+                    Ok(value) => {
+                        constants.insert(name.trim().to_ascii_lowercase(), value);
+                    }
+                    Err(e) => error(format!("#Const {}: {}", name.trim(), e)),
+                }
+            }
+            "if" => {
+                let taken = active && condition(rest, &constants, &mut error);
+                stack.push(Branch { parent_active: active, taken, active: taken });
+            }
+            "elseif" => {
+                let Some(branch) = stack.last_mut() else {
+                    error("#ElseIf without #If".into());
+                    continue;
+                };
+                branch.active = branch.parent_active && !branch.taken && condition(rest, &constants, &mut error);
+                branch.taken |= branch.active;
+            }
+            "else" => {
+                let Some(branch) = stack.last_mut() else {
+                    error("#Else without #If".into());
+                    continue;
+                };
+                branch.active = branch.parent_active && !branch.taken;
+                branch.taken = true;
+            }
+            "end" | "endif" => {
+                if stack.pop().is_none() {
+                    error("#End If without #If".into());
+                }
+            }
+            _ => error(format!("Unknown directive: #{}", directive)),
+        }
+    }
+
+    if !stack.is_empty() {
+        let line = source.lines().count();
+        errors.push(DirectiveError { line, message: "#If without #End If".into() });
+    }
+    (out, errors)
+}
+
+/// `#If <expr> Then`: the condition, reporting and treating errors as False
+fn condition(rest: &str, constants: &CompilerConstants, error: &mut impl FnMut(String)) -> bool {
+    let expr = match rest.len().checked_sub(4) {
+        Some(i) if rest.is_char_boundary(i) && rest[i..].eq_ignore_ascii_case("then") => &rest[..i],
+        _ => rest,
+    };
+    match evaluate(expr, constants).and_then(|v| crate::interpreter::coerce::to_bool(&v)) {
+        Ok(taken) => taken,
+        Err(e) => {
+            error(format!("#If {}: {}", expr.trim(), e));
+            false
+        }
+    }
+}
+
+/// Drop a trailing `' comment` outside string literals
+fn strip_comment(text: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '\'' if !in_string => return &text[..i],
+            _ => {}
+        }
+    }
+    text
+}
+
+/// `Declare` lines without `PtrSafe`, which 64-bit Office does not compile;
+/// run on preprocessed source so `#If VBA7` alternatives are already gone
+pub fn declares_without_ptr_safe(source: &str) -> Vec<DirectiveError> {
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(row, line)| {
+            let mut words = strip_comment(line).split_whitespace().map(str::to_ascii_lowercase);
+            let mut word = words.next()?;
+            if word == "public" || word == "private" {
+                word = words.next()?;
+            }
+            if word != "declare" || words.next()? == "ptrsafe" {
+                return None;
+            }
+            Some(DirectiveError { line: row + 1, message: "Declare needs PtrSafe on 64-bit Office (Win64)".into() })
+        })
+        .collect()
+}
+
+/// Evaluate a directive expression: literals, constants, arithmetic,
+/// comparisons and Not/And/Or/Xor
+pub fn evaluate(expr: &str, constants: &CompilerConstants) -> Result<Value> {
+    let tokens = tokenize(expr)?;
+    let mut parser = Parser { tokens, pos: 0, constants, ctx: Context::new() };
+    let value = parser.logical(0)?;
+    match parser.tokens.get(parser.pos) {
+        None => Ok(value),
+        Some(token) => bail!("unexpected {:?}", token),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Str(String),
+    Word(String),
+    Op(String),
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = expr.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '"' => {
+                chars.next();
+                let text: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                tokens.push(Token::Str(text));
+            }
+            '&' => {
+                chars.next();
+                let radix = match chars.peek().map(|c| c.to_ascii_uppercase()) {
+                    Some('H') => 16,
+                    Some('O') => 8,
+                    _ => {
+                        tokens.push(Token::Op("&".into()));
+                        continue;
+                    }
+                };
+                chars.next();
+                let digits: String = std::iter::from_fn(|| chars.next_if(|c| c.is_ascii_alphanumeric())).collect();
+                let n = i64::from_str_radix(&digits, radix).map_err(|_| anyhow!("bad number &{}", digits))?;
+                tokens.push(Token::Number(n as f64));
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let digits: String = std::iter::from_fn(|| chars.next_if(|c| c.is_ascii_digit() || *c == '.')).collect();
+                let n = digits.parse().map_err(|_| anyhow!("bad number {}", digits))?;
+                tokens.push(Token::Number(n));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let word: String = std::iter::from_fn(|| chars.next_if(|c| c.is_alphanumeric() || *c == '_')).collect();
+                tokens.push(Token::Word(word));
+            }
+            '<' | '>' => {
+                chars.next();
+                let mut op = c.to_string();
+                if let Some(next) = chars.next_if(|&n| n == '=' || (c == '<' && n == '>')) {
+                    op.push(next);
+                }
+                tokens.push(Token::Op(op));
+            }
+            '=' | '+' | '-' | '*' | '/' | '\\' | '^' | '(' | ')' => {
+                chars.next();
+                tokens.push(Token::Op(c.to_string()));
+            }
+            other => bail!("unexpected character {:?}", other),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Precedence climbing over the VBA operator table
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    constants: &'a CompilerConstants,
+    ctx: Context,
+}
+
+/// Binary operators from loosest to tightest
+const LEVELS: &[&[&str]] = &[
+    &["Imp"],
+    &["Eqv"],
+    &["Xor"],
+    &["Or"],
+    &["And"],
+    // Not sits here
+    &["=", "<>", "<", "<=", ">", ">="],
+    &["&"],
+    &["+", "-"],
+    &["Mod"],
+    &["\\"],
+    &["*", "/"],
+    &["^"],
+];
+
+/// LEVELS index of the comparison operators; `Not` binds just looser
+const COMPARISON: usize = 5;
+
+impl Parser<'_> {
+    fn peek_op(&self, level: usize) -> Option<String> {
+        let text = match self.tokens.get(self.pos)? {
+            Token::Op(op) | Token::Word(op) => op,
+            _ => return None,
+        };
+        LEVELS[level].iter().find(|op| op.eq_ignore_ascii_case(text)).map(|op| op.to_string())
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self.tokens.get(self.pos), Some(Token::Word(w)) if w.eq_ignore_ascii_case(word))
+    }
+
+    fn logical(&mut self, level: usize) -> Result<Value> {
+        if level == COMPARISON && self.is_word("Not") {
+            self.pos += 1;
+            let operand = self.logical(level)?;
+            return crate::interpreter::operations::eval_unary("Not", operand);
+        }
+        if level == LEVELS.len() {
+            return self.primary();
+        }
+        let mut left = self.logical(level + 1)?;
+        while let Some(op) = self.peek_op(level) {
+            self.pos += 1;
+            let right = self.logical(level + 1)?;
+            left = crate::interpreter::operations::eval_binary(&mut self.ctx, &op, left, right)?;
+        }
+        Ok(left)
+    }
+
+    fn primary(&mut self) -> Result<Value> {
+        let token = self.tokens.get(self.pos).cloned().ok_or_else(|| anyhow!("expression ends early"))?;
+        self.pos += 1;
+        match token {
+            Token::Number(n) if n.fract() == 0.0 => Ok(Value::Integer(n as i64)),
+            Token::Number(n) => Ok(Value::Double(n)),
+            Token::Str(s) => Ok(Value::String(s)),
+            Token::Op(op) if op == "(" => {
+                let value = self.logical(0)?;
+                match self.tokens.get(self.pos) {
+                    Some(Token::Op(close)) if close == ")" => {
+                        self.pos += 1;
+                        Ok(value)
+                    }
+                    _ => bail!("missing )"),
+                }
+            }
+            Token::Op(op) if op == "-" || op == "+" => {
+                let operand = self.logical(LEVELS.len())?;
+                crate::interpreter::operations::eval_unary(&op, operand)
+            }
+            Token::Word(w) if w.eq_ignore_ascii_case("True") => Ok(Value::Boolean(true)),
+            Token::Word(w) if w.eq_ignore_ascii_case("False") => Ok(Value::Boolean(false)),
+            Token::Word(w) => Ok(self.constants.get(&w.to_ascii_lowercase()).cloned().unwrap_or(Value::Empty)),
+            Token::Op(op) => bail!("unexpected {}", op),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conditional_blocks_keep_line_numbers() {
+        let source = "#Const DEBUG_LEVEL = 2\r\n\
+                      #If VBA7 And Win64 Then\r\n\
+                      Private Declare PtrSafe Function A Lib \"k\" () As LongPtr\r\n\
+                      #Else\r\n\
+                      Private Declare Function A Lib \"k\" () As Long\r\n\
+                      #End If\r\n\
+                      #If Mac Then ' never\r\n\
+                      x = 1\r\n\
+                      #ElseIf DEBUG_LEVEL > 1 And Not UNDEFINED Then\r\n\
+                      x = 2\r\n\
+                      #Else\r\n\
+                      x = 3\r\n\
+                      #End If\r\n";
+        let (out, errors) = preprocess(source, &default_constants());
+        assert!(errors.is_empty(), "{:?}", errors);
+        let lines: Vec<&str> = out.split("\r\n").collect();
+        assert_eq!(lines.len(), source.split("\r\n").count());
+        assert_eq!(lines[2], "Private Declare PtrSafe Function A Lib \"k\" () As LongPtr");
+        assert_eq!(lines[4], "");
+        assert_eq!(lines[7], "");
+        assert_eq!(lines[9], "x = 2");
+        assert_eq!(lines[11], "");
+
+        let mut mac = default_constants();
+        mac.insert("mac".into(), Value::Boolean(true));
+        let (out, _) = preprocess(source, &mac);
+        assert_eq!(out.split("\r\n").nth(7), Some("x = 1"));
+
+        let (_, errors) = preprocess("#If Win64 Then\nx = 1\n", &default_constants());
+        assert_eq!(errors[0].message, "#If without #End If");
+        assert!(matches!(evaluate("&H10 + 1 = 17", &CompilerConstants::new()), Ok(Value::Boolean(true))));
+    }
+}
@@ -44,6 +44,26 @@
 use chrono_tz::Tz;
 use std::str::FromStr;
 
+pub use crate::host::behavior::InteractionPolicy;
+pub use crate::host::dll::{DllHandler, DllPolicy, DllRegistry};
+pub use crate::config_source::ConfigSetting;
+pub use crate::host::excel::backend::ExcelBackendKind;
+use crate::host::excel::locale::CellLocale;
+use crate::host::journal::WriteJournal;
+pub use crate::host::functions::{HostFunction, HostFunctionRegistry, HostHandler};
+pub use crate::host::ontime::{ScheduleConfig, ScheduleHook, ScheduledProcedure};
+pub use crate::host::ui::{HostUi, UiHandle};
+pub use crate::host::userform::{ShowHandler, UserForm, UserFormRegistry};
+pub use crate::preprocess::CompilerConstants;
+
+/// Default for [`RuntimeConfig::max_call_depth`]. Function calls recurse on
+/// the Rust stack (tens of KB per call in debug builds), so this leaves room
+/// on an 8 MB main thread.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 128;
+
+/// Memory limit of the Analysis and Interactive sandbox profiles
+pub const SANDBOX_MEMORY_LIMIT: usize = 256 << 20;
+
 /// Runtime configuration passed from application layer to interpreter.
 /// 
 /// This struct contains all session-level metadata needed during VBA execution.
@@ -54,15 +74,24 @@ pub struct RuntimeConfig {
     /// Examples: "Asia/Kolkata", "America/New_York", "Europe/London", "UTC"
     pub timezone: Tz,
     
-    /// User's locale for formatting (future use)
+    /// User's locale; cell text from the Excel backend is read with its
+    /// decimal separator and date order
     /// Examples: "en-US", "en-IN", "de-DE"
     pub locale: String,
+
+    /// Read backend cell text in `locale` (on by default); off reads every
+    /// backend as en-US
+    pub locale_cell_parsing: bool,
     
     /// Active workbook identifier (passed to excel-host)
     pub workbook_id: Option<String>,
     
     /// Current user identifier (for audit/permissions)
     pub user_id: Option<String>,
+
+    /// ID traces and spans of the run carry (see `Context::run_id`); a
+    /// fresh one per Context when None
+    pub run_id: Option<String>,
     
     /// First day of week for date functions (1=Sunday, 2=Monday, etc.)
     /// VBA default is Sunday (1)
@@ -73,6 +102,259 @@ pub struct RuntimeConfig {
     /// 2 = First week with at least 4 days
     /// 3 = First full week
     pub first_week_of_year: u8,
+
+    /// Which executor runs procedure bodies (VM by default)
+    pub engine: ExecutionEngine,
+
+    /// Office application whose object model the macro sees (Excel by default)
+    pub host: HostApplication,
+
+    /// What CreateObject/GetObject return for ProgIDs no host implements
+    pub unknown_objects: UnknownObjectPolicy,
+
+    /// Cell store behind Range values, formulas and formats (native engine by default)
+    pub excel_backend: ExcelBackendKind,
+
+    /// Coalesce consecutive cell writes into block writes to the backend
+    pub write_batching: bool,
+
+    /// Record the writes the macro makes (cells, sheets, saves, files) in a
+    /// plan instead of applying them (see `host::dry_run`)
+    pub dry_run: bool,
+
+    /// Where journaled runs are filed by run ID; a run whose ID committed
+    /// before is not executed again (see `host::journal`)
+    pub journal: Option<WriteJournal>,
+
+    /// Undo the cell writes of a run that fails partway
+    pub rollback_on_error: bool,
+
+    /// Application.Wait hibernates the run instead of pausing it (see `vm::hibernate`)
+    pub hibernate_on_wait: bool,
+
+    /// Most Sub/Function calls active at once; one more raises error 28
+    /// ("Out of stack space")
+    pub max_call_depth: usize,
+
+    /// A Sub call that ends its caller's body runs in the caller's frame
+    /// instead of a new one, so tail recursion is not bound by
+    /// `max_call_depth`. Off by default: runaway tail recursion then loops
+    /// instead of raising error 28.
+    pub tail_calls: bool,
+
+    /// What assigning to a name no Dim declared does when Option Explicit
+    /// is off (creates a Variant, as VBA does, by default)
+    pub implicit_variables: ImplicitVariablePolicy,
+
+    /// Approximate bytes the macro's values may hold before error 7
+    /// ("Out of memory"); unlimited by default (see `memory`)
+    pub memory_limit: Option<usize>,
+
+    /// What Declare'd procedures with no registered handler do (deny by default)
+    pub dll_policy: DllPolicy,
+
+    /// Rust implementations of Declare'd DLL entry points
+    pub dll_functions: DllRegistry,
+
+    /// Whether SendKeys/AppActivate succeed or raise error 70 (both are recorded)
+    pub interactions: InteractionPolicy,
+
+    /// Answers MsgBox and InputBox dialogs (the default button and the
+    /// Default text by default)
+    pub ui: UiHandle,
+
+    /// Answers InputBox dialogs get, in order, before `ui` is asked;
+    /// None presses Cancel
+    pub input_answers: Vec<Option<String>>,
+
+    /// Environ, Command$ and CurDir read the host process (true by
+    /// default); false makes them return empty strings
+    pub expose_environment: bool,
+
+    /// Profile the security settings came from, if one was used
+    pub sandbox: Option<SandboxProfile>,
+
+    /// Forms the macro may show, with their control values and .Show handlers
+    pub user_forms: UserFormRegistry,
+
+    /// Constants `#If` conditions see (VBA7, Win64, Mac, ... by default);
+    /// pass them to `ast::parse_source_with_constants`
+    pub compiler_constants: CompilerConstants,
+
+    /// What `Stop` and breakpoints do (end the run by default)
+    pub stop_policy: StopPolicy,
+
+    /// Source lines the VM treats as a `Stop` before running them
+    pub breakpoints: Vec<usize>,
+
+    /// Virtual clock and scheduler hook for Application.OnTime
+    pub schedule: ScheduleConfig,
+
+    /// Which Sub runs when the program is executed (auto-exec, then Main, by default)
+    pub auto_run: AutoRunPolicy,
+
+    /// VBA functions implemented in Rust, called before the builtins
+    pub host_functions: HostFunctionRegistry,
+
+    /// Module-level variables defined before the macro runs (see
+    /// `Context::define_global`)
+    pub globals: Vec<(String, crate::context::Value)>,
+}
+
+/// Statement executor used for procedure bodies.
+///
+/// Both engines share the expression evaluator and builtins; they differ in
+/// how control flow is driven. `Interpreter` is kept as a reference
+/// implementation for differential testing against the VM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExecutionEngine {
+    /// Explicit frame stack (`run_statement_list_vm`)
+    #[default]
+    Vm,
+    /// Recursive tree-walker (`execute_statement_list`)
+    Interpreter,
+}
+
+/// Office application hosting the macro.
+///
+/// Selects which object model globals such as `Application` and `Selection`
+/// resolve to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HostApplication {
+    /// Workbooks, worksheets and ranges (`host::excel`)
+    #[default]
+    Excel,
+    /// Documents, Selection and text ranges (`host::word`)
+    Word,
+}
+
+/// Handling of late-bound objects with no host implementation
+/// (`CreateObject("WScript.Shell")`, `GetObject("winmgmts:")`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownObjectPolicy {
+    /// Return a recording stub (`host::stub`) that accepts any member
+    #[default]
+    Record,
+    /// Raise run-time error 429 (ActiveX component can't create object)
+    Error,
+}
+
+/// Named bundle of the security-relevant settings, applied with
+/// [`RuntimeConfigBuilder::sandbox`]. Settings passed to the builder
+/// explicitly override the profile's.
+///
+/// | | Analysis | Trusted | Interactive |
+/// |---|---|---|---|
+/// | Declare'd DLLs without a handler | zero value | zero value | error 70 |
+/// | SendKeys / AppActivate | recorded | recorded | error 70 |
+/// | Unknown CreateObject ProgIDs | recording stub | recording stub | error 429 |
+/// | Environ, Command$, CurDir | empty | host values | empty |
+/// | Stop / breakpoints | ignored | end the run | pause |
+/// | Call depth | 64 | default | default |
+/// | Memory limit | 256 MB | none | 256 MB |
+///
+/// Shell never starts a process and the filesystem and network are never
+/// touched in any profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SandboxProfile {
+    /// Untrusted sample under inspection: let it run as far as it will so
+    /// every action is recorded, without leaking anything about the host
+    Analysis,
+    /// Known-good macros doing real work for their owner
+    Trusted,
+    /// Untrusted macro run for a user: refuse anything outside the document
+    Interactive,
+}
+
+impl SandboxProfile {
+    fn dll_policy(self) -> DllPolicy {
+        match self {
+            Self::Analysis | Self::Trusted => DllPolicy::Stub,
+            Self::Interactive => DllPolicy::Deny,
+        }
+    }
+
+    fn interactions(self) -> InteractionPolicy {
+        match self {
+            Self::Analysis | Self::Trusted => InteractionPolicy::Record,
+            Self::Interactive => InteractionPolicy::Deny,
+        }
+    }
+
+    fn unknown_objects(self) -> UnknownObjectPolicy {
+        match self {
+            Self::Analysis | Self::Trusted => UnknownObjectPolicy::Record,
+            Self::Interactive => UnknownObjectPolicy::Error,
+        }
+    }
+
+    fn expose_environment(self) -> bool {
+        self == Self::Trusted
+    }
+
+    fn stop_policy(self) -> StopPolicy {
+        match self {
+            Self::Analysis => StopPolicy::Ignore,
+            Self::Trusted => StopPolicy::Halt,
+            Self::Interactive => StopPolicy::Pause,
+        }
+    }
+
+    fn memory_limit(self) -> Option<usize> {
+        match self {
+            Self::Analysis | Self::Interactive => Some(SANDBOX_MEMORY_LIMIT),
+            Self::Trusted => None,
+        }
+    }
+
+    fn max_call_depth(self) -> usize {
+        match self {
+            Self::Analysis => 64,
+            Self::Trusted | Self::Interactive => DEFAULT_MAX_CALL_DEPTH,
+        }
+    }
+}
+
+/// Which Sub `ProgramExecutor::execute` runs after loading the program
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum AutoRunPolicy {
+    /// The first auto-exec macro present (AutoOpen, AutoExec, Document_Open,
+    /// Workbook_Open, Auto_Open), else `Main`
+    #[default]
+    FirstFound,
+    /// Only an auto-exec macro, as Office would; never `Main`
+    AutoExecOnly,
+    /// This Sub, whatever it is called
+    Named(String),
+    /// Nothing; the embedder calls Subs itself
+    Disabled,
+}
+
+/// What happens to a variable created by assigning to a name that was
+/// never declared, for hardening legacy macros without Option Explicit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImplicitVariablePolicy {
+    /// Create a Variant, as VBA does
+    #[default]
+    Allow,
+    /// Create it and record an `ImplicitVariable` on the Context (see
+    /// `Context::implicit_variables`)
+    Report,
+    /// Raise error 451, as if Option Explicit were on
+    Deny,
+}
+
+/// What a `Stop` statement or breakpoint does; there is no IDE to break into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StopPolicy {
+    /// End the run, leaving a `StopReport` on the Context
+    #[default]
+    Halt,
+    /// Hibernate (see `vm::hibernate`) so the embedder can inspect the run
+    /// and resume it
+    Pause,
+    /// Log it and carry on
+    Ignore,
 }
 
 impl Default for RuntimeConfig {
@@ -80,10 +362,40 @@ impl Default for RuntimeConfig {
         Self {
             timezone: Tz::UTC,
             locale: "en-US".to_string(),
+            locale_cell_parsing: true,
             workbook_id: None,
             user_id: None,
+            run_id: None,
             first_day_of_week: 1,  // Sunday
             first_week_of_year: 1, // Week containing Jan 1
+            engine: ExecutionEngine::Vm,
+            host: HostApplication::Excel,
+            unknown_objects: UnknownObjectPolicy::Record,
+            excel_backend: ExcelBackendKind::Native,
+            write_batching: false,
+            dry_run: false,
+            journal: None,
+            rollback_on_error: false,
+            hibernate_on_wait: false,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            tail_calls: false,
+            implicit_variables: ImplicitVariablePolicy::Allow,
+            memory_limit: None,
+            dll_policy: DllPolicy::Deny,
+            dll_functions: DllRegistry::default(),
+            interactions: InteractionPolicy::Record,
+            ui: UiHandle::default(),
+            input_answers: Vec::new(),
+            expose_environment: true,
+            sandbox: None,
+            user_forms: UserFormRegistry::default(),
+            compiler_constants: crate::preprocess::default_constants(),
+            stop_policy: StopPolicy::Halt,
+            breakpoints: Vec::new(),
+            schedule: ScheduleConfig::default(),
+            auto_run: AutoRunPolicy::FirstFound,
+            host_functions: HostFunctionRegistry::default(),
+            globals: Vec::new(),
         }
     }
 }
@@ -113,6 +425,16 @@ impl RuntimeConfig {
     pub fn timezone_name(&self) -> &str {
         self.timezone.name()
     }
+
+    /// What `LongPtr` means: LongLong when the `Win64` compiler constant
+    /// is on (the default), Long otherwise
+    pub fn pointer_type(&self) -> crate::context::DeclaredType {
+        if crate::preprocess::is_win64(&self.compiler_constants) {
+            crate::context::DeclaredType::LongLong
+        } else {
+            crate::context::DeclaredType::Long
+        }
+    }
 }
 
 /// Builder for RuntimeConfig
@@ -120,10 +442,41 @@ impl RuntimeConfig {
 pub struct RuntimeConfigBuilder {
     timezone: Option<Tz>,
     locale: Option<String>,
+    locale_cell_parsing: Option<bool>,
     workbook_id: Option<String>,
     user_id: Option<String>,
+    run_id: Option<String>,
     first_day_of_week: Option<u8>,
     first_week_of_year: Option<u8>,
+    engine: Option<ExecutionEngine>,
+    host: Option<HostApplication>,
+    unknown_objects: Option<UnknownObjectPolicy>,
+    excel_backend: Option<ExcelBackendKind>,
+    write_batching: Option<bool>,
+    dry_run: Option<bool>,
+    journal: Option<WriteJournal>,
+    rollback_on_error: Option<bool>,
+    hibernate_on_wait: Option<bool>,
+    max_call_depth: Option<usize>,
+    tail_calls: Option<bool>,
+    implicit_variables: Option<ImplicitVariablePolicy>,
+    memory_limit: Option<usize>,
+    dll_policy: Option<DllPolicy>,
+    dll_functions: DllRegistry,
+    interactions: Option<InteractionPolicy>,
+    ui: Option<UiHandle>,
+    input_answers: Vec<Option<String>>,
+    expose_environment: Option<bool>,
+    sandbox: Option<SandboxProfile>,
+    user_forms: UserFormRegistry,
+    compiler_constants: CompilerConstants,
+    stop_policy: Option<StopPolicy>,
+    breakpoints: Vec<usize>,
+    schedule: ScheduleConfig,
+    auto_run: Option<AutoRunPolicy>,
+    host_functions: HostFunctionRegistry,
+    globals: Vec<(String, crate::context::Value)>,
+    errors: Vec<String>,
 }
 
 impl RuntimeConfigBuilder {
@@ -138,16 +491,26 @@ impl RuntimeConfigBuilder {
         } else {
             eprintln!("Warning: Invalid timezone '{}', using UTC", tz_name);
             self.timezone = Some(Tz::UTC);
+            self.errors.push(format!("Invalid timezone: {}", tz_name));
         }
         self
     }
     
     /// Set the locale (e.g., "en-US", "en-IN")
     pub fn locale(mut self, locale: &str) -> Self {
+        if CellLocale::for_locale(locale).is_none() {
+            self.errors.push(format!("Unknown locale: {}", locale));
+        }
         self.locale = Some(locale.to_string());
         self
     }
     
+    /// Read backend cell text in the locale (the default) or, when off, as en-US
+    pub fn locale_cell_parsing(mut self, enabled: bool) -> Self {
+        self.locale_cell_parsing = Some(enabled);
+        self
+    }
+    
     /// Set the workbook ID
     pub fn workbook_id(mut self, id: &str) -> Self {
         self.workbook_id = Some(id.to_string());
@@ -160,6 +523,12 @@ impl RuntimeConfigBuilder {
         self
     }
     
+    /// Use the caller's ID (a request or job ID) as the run ID
+    pub fn run_id(mut self, id: &str) -> Self {
+        self.run_id = Some(id.to_string());
+        self
+    }
+    
     /// Set the first day of week (1=Sunday, 2=Monday, ..., 7=Saturday)
     pub fn first_day_of_week(mut self, day: u8) -> Self {
         self.first_day_of_week = Some(day.clamp(1, 7));
@@ -172,15 +541,255 @@ impl RuntimeConfigBuilder {
         self
     }
     
-    /// Build the RuntimeConfig
+    /// Select the statement executor (VM or tree-walking interpreter)
+    pub fn engine(mut self, engine: ExecutionEngine) -> Self {
+        self.engine = Some(engine);
+        self
+    }
+    
+    /// Select the host application object model (Excel or Word)
+    pub fn host(mut self, host: HostApplication) -> Self {
+        self.host = Some(host);
+        self
+    }
+    
+    /// Choose how CreateObject/GetObject handle unknown ProgIDs
+    pub fn unknown_objects(mut self, policy: UnknownObjectPolicy) -> Self {
+        self.unknown_objects = Some(policy);
+        self
+    }
+    
+    /// Select the Excel cell store (native, static or an embedder backend)
+    pub fn excel_backend(mut self, backend: ExcelBackendKind) -> Self {
+        self.excel_backend = Some(backend);
+        self
+    }
+    
+    /// Buffer cell writes and send contiguous runs as one backend call
+    pub fn write_batching(mut self, enabled: bool) -> Self {
+        self.write_batching = Some(enabled);
+        self
+    }
+    
+    /// Plan the macro's writes instead of applying them
+    pub fn dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = Some(enabled);
+        self
+    }
+    
+    /// Journal the run's writes in `journal` under its run ID
+    pub fn journal(mut self, journal: WriteJournal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+    
+    /// Undo the run's cell writes when it fails partway
+    pub fn rollback_on_error(mut self, enabled: bool) -> Self {
+        self.rollback_on_error = Some(enabled);
+        self
+    }
+    
+    /// Stop the run at Application.Wait so it can be saved and resumed later
+    pub fn hibernate_on_wait(mut self, enabled: bool) -> Self {
+        self.hibernate_on_wait = Some(enabled);
+        self
+    }
+    
+    /// Limit nested Sub/Function calls (error 28 beyond it)
+    pub fn max_call_depth(mut self, depth: usize) -> Self {
+        self.max_call_depth = Some(depth);
+        self
+    }
+
+    /// Run Sub calls in tail position in their caller's frame
+    pub fn tail_calls(mut self, enabled: bool) -> Self {
+        self.tail_calls = Some(enabled);
+        self
+    }
+    
+    /// Report or refuse variables created without a declaration
+    pub fn implicit_variables(mut self, policy: ImplicitVariablePolicy) -> Self {
+        self.implicit_variables = Some(policy);
+        self
+    }
+    
+    /// Raise error 7 once the macro's values would hold more than `bytes`
+    pub fn memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = Some(bytes);
+        self
+    }
+    
+    /// Choose how Declare'd procedures without a handler behave
+    pub fn dll_policy(mut self, policy: DllPolicy) -> Self {
+        self.dll_policy = Some(policy);
+        self
+    }
+    
+    /// Implement a DLL entry point (e.g. "kernel32", "GetTickCount") in Rust
+    pub fn dll_function(mut self, library: &str, entry: &str, handler: DllHandler) -> Self {
+        self.dll_functions.register(library, entry, handler);
+        self
+    }
+    
+    /// Choose whether SendKeys/AppActivate are allowed or denied
+    pub fn interactions(mut self, policy: InteractionPolicy) -> Self {
+        self.interactions = Some(policy);
+        self
+    }
+    
+    /// Answer the macro's dialogs with `ui` instead of the defaults
+    pub fn ui(mut self, ui: std::sync::Arc<dyn HostUi>) -> Self {
+        self.ui = Some(UiHandle(ui));
+        self
+    }
+    
+    /// Type `text` into the next InputBox the macro shows
+    pub fn input_answer(mut self, text: &str) -> Self {
+        self.input_answers.push(Some(text.to_string()));
+        self
+    }
+    
+    /// Press Cancel on the next InputBox the macro shows
+    pub fn input_cancel(mut self) -> Self {
+        self.input_answers.push(None);
+        self
+    }
+    
+    /// Let Environ, Command$ and CurDir see the host process, or not
+    pub fn expose_environment(mut self, enabled: bool) -> Self {
+        self.expose_environment = Some(enabled);
+        self
+    }
+    
+    /// Start from a profile's security settings (DLLs, SendKeys,
+    /// CreateObject, environment, Stop, call depth, memory)
+    pub fn sandbox(mut self, profile: SandboxProfile) -> Self {
+        self.sandbox = Some(profile);
+        self
+    }
+    
+    /// Register a form with pre-populated control values
+    pub fn user_form(mut self, form: UserForm) -> Self {
+        self.user_forms.register(form);
+        self
+    }
+    
+    /// Fill in a form's controls when the macro calls `.Show`
+    pub fn on_show(mut self, form: &str, handler: ShowHandler) -> Self {
+        self.user_forms.on_show(form, handler);
+        self
+    }
+    
+    /// Define or override a compiler constant, e.g. ("Mac", True) or ("DEBUG_LEVEL", 2).
+    /// ("Win64", False) emulates 32-bit Office: LongPtr becomes Long.
+    pub fn compiler_constant(mut self, name: &str, value: crate::context::Value) -> Self {
+        self.compiler_constants.insert(name.to_ascii_lowercase(), value);
+        self
+    }
+    
+    /// Choose whether `Stop` ends, pauses or is ignored
+    pub fn stop_policy(mut self, policy: StopPolicy) -> Self {
+        self.stop_policy = Some(policy);
+        self
+    }
+    
+    /// Stop before running the statement on a source line (1-based)
+    pub fn breakpoint(mut self, line: usize) -> Self {
+        self.breakpoints.push(line);
+        self
+    }
+    
+    /// Run on a virtual clock starting at `start`: Now() stands still until
+    /// `ProgramExecutor::advance_clock` moves it
+    pub fn virtual_clock(mut self, start: chrono::NaiveDateTime) -> Self {
+        self.schedule.clock_start = Some(start);
+        self
+    }
+    
+    /// Tell the embedder's scheduler about each Application.OnTime call
+    pub fn on_schedule(mut self, hook: ScheduleHook) -> Self {
+        self.schedule.hook = Some(hook);
+        self
+    }
+    
+    /// Choose which Sub, if any, runs automatically
+    pub fn auto_run(mut self, policy: AutoRunPolicy) -> Self {
+        self.auto_run = Some(policy);
+        self
+    }
+    
+    /// Make a Rust function callable from VBA under `name`
+    pub fn function(mut self, name: &str, function: HostFunction) -> Self {
+        self.host_functions.register(name, function);
+        self
+    }
+    
+    /// Give the macro a global it can use without declaring, e.g.
+    /// ("TaxRate", 0.2) or a host object under an alias
+    pub fn global(mut self, name: &str, value: crate::context::Value) -> Self {
+        self.globals.push((name.to_string(), value));
+        self
+    }
+    
+    /// Record a setting that could not be applied, for `try_build`
+    pub(crate) fn error(mut self, message: String) -> Self {
+        self.errors.push(message);
+        self
+    }
+    
+    /// Build the RuntimeConfig, or report every setting `build` would
+    /// have replaced with its default (an invalid timezone, an unknown
+    /// locale, a bad value from a configuration source)
+    pub fn try_build(self) -> Result<RuntimeConfig, String> {
+        if !self.errors.is_empty() {
+            return Err(self.errors.join("; "));
+        }
+        Ok(self.build())
+    }
+    
+    /// Build the RuntimeConfig; invalid settings fall back to defaults
+    /// with a warning (see `try_build`)
     pub fn build(self) -> RuntimeConfig {
+        let mut compiler_constants = crate::preprocess::default_constants();
+        compiler_constants.extend(self.compiler_constants);
+        let profile = self.sandbox;
         RuntimeConfig {
             timezone: self.timezone.unwrap_or(Tz::UTC),
             locale: self.locale.unwrap_or_else(|| "en-US".to_string()),
+            locale_cell_parsing: self.locale_cell_parsing.unwrap_or(true),
             workbook_id: self.workbook_id,
             user_id: self.user_id,
+            run_id: self.run_id,
             first_day_of_week: self.first_day_of_week.unwrap_or(1),
             first_week_of_year: self.first_week_of_year.unwrap_or(1),
+            engine: self.engine.unwrap_or_default(),
+            host: self.host.unwrap_or_default(),
+            unknown_objects: self.unknown_objects.or(profile.map(SandboxProfile::unknown_objects)).unwrap_or_default(),
+            excel_backend: self.excel_backend.unwrap_or_default(),
+            write_batching: self.write_batching.unwrap_or(false),
+            dry_run: self.dry_run.unwrap_or(false),
+            journal: self.journal,
+            rollback_on_error: self.rollback_on_error.unwrap_or(false),
+            hibernate_on_wait: self.hibernate_on_wait.unwrap_or(false),
+            max_call_depth: self.max_call_depth.or(profile.map(SandboxProfile::max_call_depth)).unwrap_or(DEFAULT_MAX_CALL_DEPTH),
+            tail_calls: self.tail_calls.unwrap_or(false),
+            implicit_variables: self.implicit_variables.unwrap_or_default(),
+            memory_limit: self.memory_limit.or(profile.and_then(SandboxProfile::memory_limit)),
+            dll_policy: self.dll_policy.or(profile.map(SandboxProfile::dll_policy)).unwrap_or_default(),
+            dll_functions: self.dll_functions,
+            interactions: self.interactions.or(profile.map(SandboxProfile::interactions)).unwrap_or_default(),
+            ui: self.ui.unwrap_or_default(),
+            input_answers: self.input_answers,
+            expose_environment: self.expose_environment.or(profile.map(SandboxProfile::expose_environment)).unwrap_or(true),
+            sandbox: profile,
+            user_forms: self.user_forms,
+            compiler_constants,
+            stop_policy: self.stop_policy.or(profile.map(SandboxProfile::stop_policy)).unwrap_or_default(),
+            breakpoints: self.breakpoints,
+            schedule: self.schedule,
+            auto_run: self.auto_run.unwrap_or_default(),
+            host_functions: self.host_functions,
+            globals: self.globals,
         }
     }
 }
@@ -219,4 +828,116 @@ mod tests {
         
         assert_eq!(config.timezone, Tz::UTC);
     }
+    
+    #[test]
+    fn test_engine_defaults_to_vm() {
+        assert_eq!(RuntimeConfig::default().engine, ExecutionEngine::Vm);
+        let config = RuntimeConfig::builder()
+            .engine(ExecutionEngine::Interpreter)
+            .build();
+        assert_eq!(config.engine, ExecutionEngine::Interpreter);
+    }
+    
+    #[test]
+    fn test_host_defaults_to_excel() {
+        assert_eq!(RuntimeConfig::default().host, HostApplication::Excel);
+        let config = RuntimeConfig::builder()
+            .host(HostApplication::Word)
+            .build();
+        assert_eq!(config.host, HostApplication::Word);
+    }
+    
+    #[test]
+    fn test_sandbox_profile_with_override() {
+        let config = RuntimeConfig::builder().sandbox(SandboxProfile::Interactive).build();
+        assert_eq!(config.sandbox, Some(SandboxProfile::Interactive));
+        assert_eq!(config.dll_policy, DllPolicy::Deny);
+        assert_eq!(config.interactions, InteractionPolicy::Deny);
+        assert_eq!(config.unknown_objects, UnknownObjectPolicy::Error);
+        assert!(!config.expose_environment);
+        
+        // An explicit setting wins whichever order it is given in
+        let config = RuntimeConfig::builder()
+            .stop_policy(StopPolicy::Halt)
+            .sandbox(SandboxProfile::Analysis)
+            .build();
+        assert_eq!(config.stop_policy, StopPolicy::Halt);
+        assert_eq!(config.dll_policy, DllPolicy::Stub);
+        assert_eq!(config.max_call_depth, 64);
+        assert!(RuntimeConfig::builder().sandbox(SandboxProfile::Trusted).build().expose_environment);
+    }
+
+    #[test]
+    fn test_try_build_reports_invalid_settings() {
+        let err = RuntimeConfig::builder().timezone("Mars/Olympus").locale("klingon-empire").try_build().unwrap_err();
+        assert_eq!(err, "Invalid timezone: Mars/Olympus; Unknown locale: klingon-empire");
+        assert!(RuntimeConfig::builder().timezone("Europe/Berlin").locale("de-DE").try_build().is_ok());
+    }
+
+    #[test]
+    fn test_layered_sources() {
+        let toml = "# shared settings\ntimezone = \"Europe/Berlin\"\nlocale = 'de-DE'\nmax_call_depth = 64 # deep enough\nsandbox = \"analysis\"\n";
+        let json = r#"{"max_call_depth": 32, "engine": "interpreter", "user_id": null}"#;
+        let env = [("VBA_STOP_POLICY", "pause"), ("VBA_UNRELATED", "x"), ("PATH", "/bin")]
+            .map(|(name, value)| (name.to_string(), value.to_string()));
+        let config = RuntimeConfig::builder().toml(toml).json(json).env_vars(env).tail_calls(true).try_build().unwrap();
+
+        assert_eq!(config.timezone_name(), "Europe/Berlin");
+        assert_eq!(config.locale, "de-DE");
+        assert_eq!(config.max_call_depth, 32);
+        assert_eq!(config.engine, ExecutionEngine::Interpreter);
+        assert_eq!(config.sandbox, Some(SandboxProfile::Analysis));
+        // The later source wins over the profile's Ignore
+        assert_eq!(config.stop_policy, StopPolicy::Pause);
+        assert!(config.tail_calls);
+
+        let described = config.describe();
+        let depth = described.iter().find(|s| s.key == "max_call_depth").unwrap();
+        assert_eq!((depth.value.as_str(), depth.default.as_str()), ("32", "128"));
+        let auto_run = described.iter().find(|s| s.key == "auto_run").unwrap();
+        assert_eq!(auto_run.value, "first_found");
+        // Every described setting can be fed back in
+        let round_trip = described.iter().filter(|s| s.value != "none").fold(RuntimeConfig::builder(), |b, s| b.set(s.key, &s.value));
+        assert_eq!(round_trip.try_build().unwrap().describe(), described);
+    }
+
+    #[test]
+    fn test_sources_read_full_toml_and_json_syntax() {
+        let toml = r#"
+timezone = "Europe/\u0042erlin"   # escaped
+user_id = """
+user-1"""
+"workbook_id" = 'C:\Books\a.xlsm'
+max_call_depth = 0x40
+"#;
+        let config = RuntimeConfig::builder().toml(toml).try_build().unwrap();
+        assert_eq!(config.timezone_name(), "Europe/Berlin");
+        assert_eq!(config.user_id.as_deref(), Some("user-1"));
+        assert_eq!(config.workbook_id.as_deref(), Some(r"C:\Books\a.xlsm"));
+        assert_eq!(config.max_call_depth, 64);
+
+        let json = r#"{ "locale": "\u0064e-DE", "tail_calls": true, "memory_limit": 1048576 }"#;
+        let config = RuntimeConfig::builder().json(json).try_build().unwrap();
+        assert_eq!(config.locale, "de-DE");
+        assert!(config.tail_calls);
+        assert_eq!(config.memory_limit, Some(1_048_576));
+    }
+
+    #[test]
+    fn test_source_errors() {
+        let err = |builder: RuntimeConfigBuilder| builder.try_build().unwrap_err();
+        assert_eq!(err(RuntimeConfig::builder().set("max_call_depth", "deep")), "max_call_depth: expected a whole number, got 'deep'");
+        assert_eq!(err(RuntimeConfig::builder().set("first_day_of_week", "9")), "first_day_of_week: expected 1 to 7, got 9");
+        assert_eq!(err(RuntimeConfig::builder().set("engine", "jit")), "engine: expected vm, interpreter, got 'jit'");
+        assert_eq!(err(RuntimeConfig::builder().set("colour", "blue")), "colour: unknown setting");
+        assert_eq!(err(RuntimeConfig::builder().toml("[runtime]\nengine = \"vm\"")), "TOML: \"runtime\" must be a string, number or boolean; settings are not nested");
+        assert!(err(RuntimeConfig::builder().toml("engine = \"vm\"\ntimezone = ")).starts_with("TOML: line 2: "));
+        assert!(err(RuntimeConfig::builder().json("{\"engine\": \"vm\",}")).starts_with("JSON: trailing comma"));
+        assert_eq!(err(RuntimeConfig::builder().json("{\"engine\": [1]}")), "JSON: \"engine\" must be a string, number, boolean or null");
+        assert!(err(RuntimeConfig::builder().file("/nonexistent/vba.toml")).starts_with("/nonexistent/vba.toml: "));
+
+        // A bad value leaves the earlier one in place for build()
+        let config = RuntimeConfig::builder().set("max_call_depth", "50").set("max_call_depth", "-1").build();
+        assert_eq!(config.max_call_depth, 50);
+    }
 }
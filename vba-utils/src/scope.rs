@@ -0,0 +1,247 @@
+// vba-utils/src/scope.rs
+
+//! Lexical procedure frames with slot-indexed locals.
+//!
+//! When a Sub/Function is registered, its parameters, `Dim` names and
+//! return variable are assigned fixed slot numbers in a [`FrameLayout`].
+//! Each call pushes a [`ScopeFrame`] that stores those locals in a `Vec`,
+//! so declared variables are found by index instead of by probing one hash
+//! map per active scope. Names the layout does not know about (implicit
+//! variables, or procedures registered without a layout) go to a per-frame
+//! dynamic map instead.
+//!
+//! Lookup is lexical: a procedure sees its own frame and module globals,
+//! not the locals of whichever procedure called it.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::ast::{DoWhileStatement, ForStatement, Parameter, Statement};
+use crate::context::{DeclaredType, ScopeKind, Value};
+use crate::symbol::Symbol;
+
+static NEXT_LAYOUT_ID: AtomicU32 = AtomicU32::new(0);
+
+/// Slot assignment for one procedure's declared locals.
+#[derive(Debug)]
+pub struct FrameLayout {
+    id: u32,
+    slots: HashMap<Symbol, usize>,
+}
+
+impl FrameLayout {
+    /// Layout with no slots; every local lives in the dynamic map.
+    pub fn empty() -> Self {
+        Self::from_names(std::iter::empty())
+    }
+
+    /// Assign slots to the parameters, every `Dim` and `Static` in `body` (including
+    /// nested blocks, since VBA locals are procedure-wide) and the return
+    /// variable, if any.
+    pub fn for_procedure(params: &[Parameter], body: &[Statement], return_var: Option<&str>) -> Self {
+        let mut names: Vec<Symbol> = params.iter().map(|p| Symbol::intern(&p.name)).collect();
+        names.extend(return_var.map(Symbol::intern));
+        collect_dims(body, &mut names);
+        Self::from_names(names)
+    }
+
+    fn from_names(names: impl IntoIterator<Item = Symbol>) -> Self {
+        let mut slots = HashMap::new();
+        for name in names {
+            let next = slots.len();
+            slots.entry(name).or_insert(next);
+        }
+        FrameLayout {
+            id: NEXT_LAYOUT_ID.fetch_add(1, Ordering::Relaxed),
+            slots,
+        }
+    }
+
+    /// Slot index of `name`, if it is a declared local of this procedure.
+    pub fn slot(&self, name: Symbol) -> Option<usize> {
+        self.slots.get(&name).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+}
+
+fn collect_dims(stmts: &[Statement], out: &mut Vec<Symbol>) {
+    for stmt in stmts {
+        match stmt {
+            Statement::Dim { names } | Statement::Static { names } => out.extend(names.iter().map(|(n, _)| Symbol::intern(n))),
+            Statement::With { body, .. }
+            | Statement::For(ForStatement { body, .. })
+            | Statement::DoWhile(DoWhileStatement { body, .. }) => collect_dims(body, out),
+            Statement::If { then_branch, else_if, else_branch, .. } => {
+                collect_dims(then_branch, out);
+                for (_, branch) in else_if {
+                    collect_dims(branch, out);
+                }
+                collect_dims(else_branch, out);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Resolved location of a local: valid while a frame with the same layout
+/// is innermost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalSlot {
+    layout: u32,
+    index: u32,
+}
+
+/// One active procedure (or block) scope.
+#[derive(Debug, Clone)]
+pub(crate) struct ScopeFrame {
+    pub(crate) name: Option<String>,
+    pub(crate) kind: ScopeKind,
+    layout: Rc<FrameLayout>,
+    /// `None` until the slot is declared or assigned
+    slots: Vec<Option<Value>>,
+    slot_types: Vec<Option<DeclaredType>>,
+    /// Locals the layout has no slot for
+    vars: HashMap<Symbol, Value>,
+    types: HashMap<Symbol, DeclaredType>,
+}
+
+impl ScopeFrame {
+    pub(crate) fn new(name: Option<String>, kind: ScopeKind, layout: Rc<FrameLayout>) -> Self {
+        let n = layout.len();
+        ScopeFrame {
+            name,
+            kind,
+            layout,
+            slots: vec![None; n],
+            slot_types: vec![None; n],
+            vars: HashMap::new(),
+            types: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn get(&self, name: Symbol) -> Option<&Value> {
+        match self.layout.slot(name) {
+            Some(i) => self.slots[i].as_ref(),
+            None => self.vars.get(&name),
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, name: Symbol) -> Option<&mut Value> {
+        match self.layout.slot(name) {
+            Some(i) => self.slots[i].as_mut(),
+            None => self.vars.get_mut(&name),
+        }
+    }
+
+    /// Whether `name` is a local of this frame, declared yet or not.
+    pub(crate) fn owns(&self, name: Symbol) -> bool {
+        self.layout.slot(name).is_some() || self.vars.contains_key(&name) || self.types.contains_key(&name)
+    }
+
+    /// Declare (or overwrite) a local of this frame.
+    pub(crate) fn declare(&mut self, name: Symbol, val: Value) {
+        match self.layout.slot(name) {
+            Some(i) => self.slots[i] = Some(val),
+            None => {
+                self.vars.insert(name, val);
+            }
+        }
+    }
+
+    /// Whether `name` has a slot in this frame's layout.
+    pub(crate) fn has_slot(&self, name: Symbol) -> bool {
+        self.layout.slot(name).is_some()
+    }
+
+    pub(crate) fn get_type(&self, name: Symbol) -> Option<DeclaredType> {
+        match self.layout.slot(name) {
+            Some(i) => self.slot_types[i],
+            None => self.types.get(&name).copied(),
+        }
+    }
+
+    pub(crate) fn set_type(&mut self, name: Symbol, ty: DeclaredType) {
+        match self.layout.slot(name) {
+            Some(i) => self.slot_types[i] = Some(ty),
+            None => {
+                self.types.insert(name, ty);
+            }
+        }
+    }
+
+    pub(crate) fn resolve(&self, name: Symbol) -> Option<LocalSlot> {
+        self.layout.slot(name).map(|i| LocalSlot { layout: self.layout.id, index: i as u32 })
+    }
+
+    pub(crate) fn get_slot(&self, slot: LocalSlot) -> Option<&Value> {
+        if slot.layout != self.layout.id {
+            return None;
+        }
+        self.slots.get(slot.index as usize)?.as_ref()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.vars.is_empty() && self.slots.iter().all(Option::is_none)
+    }
+
+    /// Declared locals, slots first.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (Symbol, &Value)> + '_ {
+        let mut slotted: Vec<(Symbol, usize)> = self.layout.slots.iter().map(|(s, i)| (*s, *i)).collect();
+        slotted.sort_by_key(|(_, i)| *i);
+        slotted
+            .into_iter()
+            .filter_map(move |(s, i)| self.slots[i].as_ref().map(|v| (s, v)))
+            .chain(self.vars.iter().map(|(s, v)| (*s, v)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_covers_params_dims_and_return() {
+        let body = vec![
+            Statement::Dim { names: vec![("ScopeTestA".into(), None)] },
+            Statement::If {
+                condition: crate::ast::Expression::Boolean(true),
+                then_branch: vec![Statement::Dim { names: vec![("ScopeTestB".into(), None)] }].into(),
+                else_if: vec![],
+                else_branch: Default::default(),
+            },
+        ];
+        let params = vec![Parameter::simple("ScopeTestP".into())];
+        let layout = FrameLayout::for_procedure(&params, &body, Some("ScopeTestFn"));
+
+        assert_eq!(layout.slot(Symbol::intern("scopetestp")), Some(0));
+        assert_eq!(layout.slot(Symbol::intern("ScopeTestFn")), Some(1));
+        assert!(layout.slot(Symbol::intern("SCOPETESTB")).is_some());
+        assert_eq!(layout.len(), 4);
+    }
+
+    #[test]
+    fn test_undeclared_slot_and_dynamic_fallback() {
+        let layout = Rc::new(FrameLayout::for_procedure(&[Parameter::simple("ScopeTestX".into())], &[], None));
+        let mut frame = ScopeFrame::new(None, ScopeKind::Subroutine, layout);
+        let x = Symbol::intern("ScopeTestX");
+        let y = Symbol::intern("ScopeTestY");
+
+        assert!(frame.get(x).is_none());
+        assert!(frame.owns(x));
+        frame.declare(x, Value::Integer(1));
+        frame.declare(y, Value::Integer(2));
+
+        let slot = frame.resolve(x).unwrap();
+        assert!(matches!(frame.get_slot(slot), Some(Value::Integer(1))));
+        assert!(frame.resolve(y).is_none());
+        assert!(matches!(frame.get(y), Some(Value::Integer(2))));
+    }
+}
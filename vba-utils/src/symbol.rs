@@ -0,0 +1,231 @@
+// vba-utils/src/symbol.rs
+
+//! Interned, case-insensitive identifiers.
+//!
+//! VBA names are case-insensitive (`myVar`, `MYVAR` and `MyVar` are the same
+//! variable). A [`Symbol`] is a small `Copy` handle that compares and hashes
+//! by the identifier's case-folded form, so scope and registry maps can be
+//! keyed by it without cloning or lowercasing strings on every lookup.
+//!
+//! Interned names live for the rest of the process, in an append-only
+//! arena: [`Symbol::as_str`] and [`Symbol::lower`] read it without locking,
+//! and only interning a new name takes the index's write lock. Only
+//! identifiers the program declares or assigns are interned, a bounded set
+//! per module, so the table stays small; [`lower`] looks names up without
+//! interning them.
+
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::RwLock;
+
+use once_cell::sync::{Lazy, OnceCell};
+
+/// Handle to an interned identifier. Equal for names that differ only in case.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+struct Spellings {
+    /// Spelling used the first time the name was seen
+    name: Box<str>,
+    /// Lowercase form, used by dispatch tables that match on lowercase names
+    lower: Box<str>,
+}
+
+/// Slots of the first arena segment; segment `i` holds `SEGMENT_BASE << i`
+const SEGMENT_BASE: usize = 64;
+
+/// Spellings by symbol. Segments are allocated once and never move, and a
+/// slot is written once, before its symbol is handed out.
+static ARENA: [OnceCell<Box<[OnceCell<Spellings>]>>; 32] = [const { OnceCell::new() }; 32];
+
+/// Case-folded hash → symbols sharing that hash, and the next free symbol
+#[derive(Default)]
+struct Index {
+    buckets: HashMap<u64, Vec<Symbol>>,
+    len: u32,
+}
+
+static INDEX: Lazy<RwLock<Index>> = Lazy::new(RwLock::default);
+
+/// Segment and offset of symbol number `n`
+fn slot(n: usize) -> (usize, usize) {
+    let segment = (usize::BITS - 1 - (n / SEGMENT_BASE + 1).leading_zeros()) as usize;
+    (segment, n - SEGMENT_BASE * ((1 << segment) - 1))
+}
+
+fn spellings(sym: Symbol) -> &'static Spellings {
+    let (segment, offset) = slot(sym.0 as usize);
+    ARENA[segment].get().and_then(|slots| slots[offset].get()).expect("symbols are only made by interning")
+}
+
+fn folded_chars(s: &str) -> impl Iterator<Item = char> + '_ {
+    s.chars().flat_map(char::to_lowercase)
+}
+
+fn folded_hash(s: &str) -> u64 {
+    let mut h = DefaultHasher::new();
+    for c in folded_chars(s) {
+        c.hash(&mut h);
+    }
+    h.finish()
+}
+
+fn eq_ignore_case(a: &str, b: &str) -> bool {
+    if a.is_ascii() && b.is_ascii() {
+        return a.eq_ignore_ascii_case(b);
+    }
+    folded_chars(a).eq(folded_chars(b))
+}
+
+impl Index {
+    fn find(&self, name: &str, hash: u64) -> Option<Symbol> {
+        let bucket = self.buckets.get(&hash)?;
+        bucket.iter().copied().find(|sym| eq_ignore_case(sym.as_str(), name))
+    }
+
+    fn intern(&mut self, name: &str, hash: u64) -> Symbol {
+        if let Some(sym) = self.find(name, hash) {
+            return sym;
+        }
+
+        let sym = Symbol(self.len);
+        let (segment, offset) = slot(self.len as usize);
+        let slots = ARENA[segment].get_or_init(|| (0..SEGMENT_BASE << segment).map(|_| OnceCell::new()).collect());
+        let spellings = Spellings { name: name.into(), lower: name.to_lowercase().into() };
+        assert!(slots[offset].set(spellings).is_ok(), "symbol slot written twice");
+        self.len += 1;
+        self.buckets.entry(hash).or_default().push(sym);
+        sym
+    }
+}
+
+impl Symbol {
+    /// Intern `name`, returning the existing symbol if a case-insensitive
+    /// match was interned before.
+    pub fn intern(name: &str) -> Symbol {
+        let hash = folded_hash(name);
+        if let Some(sym) = INDEX.read().unwrap().find(name, hash) {
+            return sym;
+        }
+        INDEX.write().unwrap().intern(name, hash)
+    }
+
+    /// The spelling this name was first interned with.
+    pub fn as_str(self) -> &'static str {
+        &spellings(self).name
+    }
+
+    /// The lowercase spelling, for matching against lowercase name tables.
+    pub fn lower(self) -> &'static str {
+        &spellings(self).lower
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Self {
+        Symbol::intern(name)
+    }
+}
+
+impl From<&String> for Symbol {
+    fn from(name: &String) -> Self {
+        Symbol::intern(name)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(name: String) -> Self {
+        Symbol::intern(&name)
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Symbol({:?})", self.as_str())
+    }
+}
+
+/// Lowercase form of `name`, for `match` based dispatch
+/// (`match &*lower(name)`). Borrowed when `name` is already lowercase or
+/// interned; other names are lowercased without being interned, so
+/// looking up arbitrary member and object names does not grow the table.
+pub fn lower(name: &str) -> Cow<'_, str> {
+    if !name.chars().any(char::is_uppercase) {
+        return Cow::Borrowed(name);
+    }
+    match INDEX.read().unwrap().find(name, folded_hash(name)) {
+        Some(sym) => Cow::Borrowed(sym.lower()),
+        None => Cow::Owned(name.to_lowercase()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_insensitive_identity() {
+        let a = Symbol::intern("SymbolTestCounter");
+        assert_eq!(a, Symbol::intern("symboltestcounter"));
+        assert_eq!(a, Symbol::intern("SYMBOLTESTCOUNTER"));
+        assert_ne!(a, Symbol::intern("SymbolTestCounter2"));
+        assert_eq!(a.as_str(), "SymbolTestCounter");
+        assert_eq!(a.lower(), "symboltestcounter");
+    }
+
+    #[test]
+    fn test_lower_looks_up_without_interning() {
+        let interned = |name: &str| INDEX.read().unwrap().find(name, folded_hash(name));
+        assert_eq!(lower("SymbolTestLookupOnly"), "symboltestlookuponly");
+        assert!(interned("SymbolTestLookupOnly").is_none());
+        assert!(matches!(lower("already lower"), Cow::Borrowed("already lower")));
+
+        let sym = Symbol::intern("SymbolTestSeen");
+        assert!(matches!(lower("SYMBOLTESTSEEN"), Cow::Borrowed(s) if s == sym.lower()));
+    }
+
+    #[test]
+    fn test_non_ascii_names() {
+        assert_eq!(Symbol::intern("Größe"), Symbol::intern("GRÖßE"));
+        assert_eq!(Symbol::intern("Ärger"), Symbol::intern("ärger"));
+    }
+
+    #[test]
+    fn test_arena_slots_fill_segments_in_order() {
+        assert_eq!(slot(0), (0, 0));
+        assert_eq!(slot(SEGMENT_BASE - 1), (0, SEGMENT_BASE - 1));
+        assert_eq!(slot(SEGMENT_BASE), (1, 0));
+        assert_eq!(slot(3 * SEGMENT_BASE - 1), (1, 2 * SEGMENT_BASE - 1));
+        assert_eq!(slot(3 * SEGMENT_BASE), (2, 0));
+    }
+
+    #[test]
+    fn test_interning_from_many_threads() {
+        let names: Vec<String> = (0..200).map(|i| format!("SymbolTestThreaded{}", i)).collect();
+        let symbols: Vec<Vec<Symbol>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|t| {
+                    let names = &names;
+                    scope.spawn(move || {
+                        names.iter().map(|n| if t % 2 == 0 { Symbol::intern(n) } else { Symbol::intern(&n.to_uppercase()) }).collect()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert!(symbols.iter().all(|s| *s == symbols[0]));
+        for (name, sym) in names.iter().zip(&symbols[0]) {
+            assert!(sym.as_str().eq_ignore_ascii_case(name));
+            assert_eq!(sym.lower(), name.to_lowercase());
+        }
+    }
+}
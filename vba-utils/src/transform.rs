@@ -0,0 +1,449 @@
+// vba-utils/src/transform.rs
+
+//! Rewriting passes run over the AST before it executes.
+//!
+//! A [`TransformPass`] changes a [`Program`] in place and reports how many
+//! rewrites it made. The embedder collects passes in a [`PassPipeline`] and
+//! runs them, in the order they were added, with
+//! [`ProgramExecutor::transform`] before executing, e.g. to deobfuscate a
+//! macro for analysis:
+//!
+//! ```rust,ignore
+//! let mut executor = ProgramExecutor::new(program);
+//! for outcome in executor.transform(&mut PassPipeline::deobfuscation()) {
+//!     println!("{}: {} rewrites", outcome.pass, outcome.changes);
+//! }
+//! executor.execute(&mut ctx)?;
+//! ```
+//!
+//! Built-in passes:
+//!
+//! - [`ConstantFolding`]: operators on literals (`3 * 4`, `"cmd" & ".exe"`,
+//!   `Not False`, `&H41 Xor 1`).
+//! - [`StringDecryption`]: string builtins on literals (`Chr`, `Asc`,
+//!   `StrReverse`, `Replace`, `Mid`, ...) together with the operators
+//!   between them, so a string assembled from `Chr(99) & Chr(109) & ...`
+//!   to hide it from scanners becomes one literal.
+//! - [`DeadBranchElimination`]: `If` arms and `Do While` loops whose
+//!   condition is a literal. `#If` blocks never reach the AST (the
+//!   preprocessor resolves them), so this handles the `If False Then` the
+//!   obfuscator wrote, or the one folding left behind.
+//!
+//! Folding evaluates with the interpreter, so a folded literal is exactly
+//! what the run would have computed; anything that raises (overflow,
+//! division by zero) is left for the run to raise. A Sub, Function or
+//! Declare named like a builtin is respected; host functions registered
+//! with `RuntimeConfig::function` are not known to the passes.
+//!
+//! [`ProgramExecutor::transform`]: crate::vm::ProgramExecutor::transform
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::ast::{Body, DoWhileConditionType, DoWhileStatement, Expression, Program, Statement};
+use crate::context::{Context, Value};
+use crate::visit::{walk_body_mut, walk_expression_mut, walk_statement, Visitor, VisitorMut};
+
+/// A rewrite of the whole program
+pub trait TransformPass {
+    /// Shown in [`PassOutcome`]
+    fn name(&self) -> &str;
+
+    /// Rewrite `program`; returns how many rewrites were made
+    fn run(&mut self, program: &mut Program) -> usize;
+}
+
+/// What one pass of a pipeline did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PassOutcome {
+    pub pass: String,
+    pub changes: usize,
+}
+
+/// Passes run in the order they were added
+#[derive(Default)]
+pub struct PassPipeline {
+    passes: Vec<Box<dyn TransformPass>>,
+}
+
+impl PassPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constant folding, string decryption, then dead-branch elimination
+    pub fn deobfuscation() -> Self {
+        Self::new().with(ConstantFolding).with(StringDecryption).with(DeadBranchElimination)
+    }
+
+    /// Add a pass to the end of the pipeline
+    pub fn with(mut self, pass: impl TransformPass + 'static) -> Self {
+        self.push(pass);
+        self
+    }
+
+    pub fn push(&mut self, pass: impl TransformPass + 'static) {
+        self.passes.push(Box::new(pass));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    /// Run every pass over `program`, in order
+    pub fn run(&mut self, program: &mut Program) -> Vec<PassOutcome> {
+        self.passes
+            .iter_mut()
+            .map(|pass| {
+                let changes = pass.run(program);
+                PassOutcome { pass: pass.name().to_string(), changes }
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for PassPipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.passes.iter().map(|p| p.name())).finish()
+    }
+}
+
+/// Fold operators whose operands are literals
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConstantFolding;
+
+impl TransformPass for ConstantFolding {
+    fn name(&self) -> &str {
+        "constant-folding"
+    }
+
+    fn run(&mut self, program: &mut Program) -> usize {
+        Folder::new(program, false).fold(program)
+    }
+}
+
+/// Fold string builtins called with literals, and the operators around them
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StringDecryption;
+
+impl TransformPass for StringDecryption {
+    fn name(&self) -> &str {
+        "string-decryption"
+    }
+
+    fn run(&mut self, program: &mut Program) -> usize {
+        Folder::new(program, true).fold(program)
+    }
+}
+
+/// Builtins without side effects whose result depends only on the arguments
+const PURE_STRING_BUILTINS: [&str; 31] = [
+    "asc", "ascb", "ascw", "chr", "chr$", "chrb", "chrw", "chrw$", "cstr", "hex", "hex$", "lcase", "lcase$",
+    "left", "left$", "len", "ltrim", "mid", "mid$", "oct", "replace", "right", "right$", "rtrim", "strreverse",
+    "trim", "trim$", "ucase", "ucase$", "string", "string$",
+];
+
+/// Bottom-up folding, evaluated on a scratch Context
+struct Folder {
+    ctx: Context,
+    calls: bool,
+    /// Procedures the program defines, which shadow the builtins
+    defined: HashSet<String>,
+    changes: usize,
+}
+
+impl Folder {
+    fn new(program: &Program, calls: bool) -> Self {
+        let defined = program
+            .statements
+            .iter()
+            .filter_map(|stmt| match stmt {
+                Statement::Subroutine { name, .. }
+                | Statement::Function { name, .. }
+                | Statement::Declare { name, .. } => Some(name.to_ascii_lowercase()),
+                _ => None,
+            })
+            .collect();
+        Self { ctx: Context::new(), calls, defined, changes: 0 }
+    }
+
+    fn fold(mut self, program: &mut Program) -> usize {
+        self.visit_program(program);
+        self.changes
+    }
+
+    fn foldable(&self, expr: &Expression) -> bool {
+        match expr {
+            Expression::BinaryOp { left, right, .. } => is_literal(left) && is_literal(right),
+            Expression::UnaryOp { expr, .. } | Expression::Parenthesized(expr) => is_literal(expr),
+            Expression::FunctionCall { function, args } if self.calls => {
+                matches!(&**function, Expression::Identifier(name)
+                    if PURE_STRING_BUILTINS.contains(&name.to_ascii_lowercase().as_str())
+                        && !self.defined.contains(&name.to_ascii_lowercase()))
+                    && args.iter().all(is_literal)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl VisitorMut for Folder {
+    fn visit_expression(&mut self, expr: &mut Expression) {
+        walk_expression_mut(self, expr);
+        if !self.foldable(expr) {
+            return;
+        }
+        // Large String$ results would be built now rather than at run time
+        if let Expression::FunctionCall { args, .. } = expr {
+            if args.iter().any(|a| matches!(a, Expression::Integer(n) if *n > crate::memory::CHECK_THRESHOLD as i64)) {
+                return;
+            }
+        }
+        // Some operators report errors through Err and return a placeholder
+        self.ctx.err = None;
+        let value = crate::interpreter::evaluate_expression(expr, &mut self.ctx).ok();
+        if self.ctx.err.is_some() {
+            return;
+        }
+        if let Some(folded) = value.as_ref().and_then(literal) {
+            *expr = folded;
+            self.changes += 1;
+        }
+    }
+}
+
+fn is_literal(expr: &Expression) -> bool {
+    matches!(
+        expr,
+        Expression::Integer(_)
+            | Expression::Byte(_)
+            | Expression::Single(_)
+            | Expression::String(_)
+            | Expression::Boolean(_)
+            | Expression::Currency(_)
+            | Expression::Date(_)
+            | Expression::Double(_)
+            | Expression::Decimal(_)
+            | Expression::Long(_)
+            | Expression::LongLong(_)
+    )
+}
+
+/// The literal that evaluates to `value`, if there is one
+fn literal(value: &Value) -> Option<Expression> {
+    Some(match value {
+        Value::Integer(n) => Expression::Integer(*n),
+        Value::Byte(b) => Expression::Byte(*b),
+        Value::Single(f) => Expression::Single(*f),
+        Value::String(s) => Expression::String(s.clone()),
+        Value::Boolean(b) => Expression::Boolean(*b),
+        Value::Currency(c) => Expression::Currency(*c),
+        Value::Date(d) => Expression::Date(*d),
+        Value::Double(f) => Expression::Double(*f),
+        Value::Decimal(f) => Expression::Decimal(*f),
+        Value::Long(n) => Expression::Long(*n),
+        Value::LongLong(n) => Expression::LongLong(*n),
+        _ => return None,
+    })
+}
+
+/// Remove `If` arms and pre-test `Do` loops that can never run, and
+/// unwrap arms that always do. Code containing a label is kept, since a
+/// GoTo may jump into it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeadBranchElimination;
+
+impl TransformPass for DeadBranchElimination {
+    fn name(&self) -> &str {
+        "dead-branch-elimination"
+    }
+
+    fn run(&mut self, program: &mut Program) -> usize {
+        let mut pruner = Pruner { changes: 0 };
+        pruner.visit_program(program);
+        pruner.changes
+    }
+}
+
+/// How a statement is replaced
+enum Pruned {
+    Keep(Statement),
+    Splice(Body),
+}
+
+struct Pruner {
+    changes: usize,
+}
+
+impl Pruner {
+    fn prune(stmt: &Statement) -> Option<Pruned> {
+        match stmt {
+            Statement::If { condition, then_branch, else_if, else_branch } => {
+                let arms = std::iter::once((condition, then_branch)).chain(else_if.iter().map(|(c, b)| (c, b)));
+                let mut kept = Vec::new();
+                let mut removed = Vec::new();
+                let mut otherwise = else_branch.clone();
+                let mut taken = false;
+                for (condition, body) in arms {
+                    if taken {
+                        removed.push(body);
+                        continue;
+                    }
+                    match literal_truth(condition) {
+                        Some(false) => removed.push(body),
+                        Some(true) => {
+                            // Always taken once reached: it is the Else now
+                            removed.push(else_branch);
+                            otherwise = body.clone();
+                            taken = true;
+                        }
+                        None => kept.push((condition.clone(), body.clone())),
+                    }
+                }
+                if !taken && removed.is_empty() || removed.into_iter().any(has_label) {
+                    return None;
+                }
+                if kept.is_empty() {
+                    return Some(Pruned::Splice(otherwise));
+                }
+                let (condition, then_branch) = kept.remove(0);
+                Some(Pruned::Keep(Statement::If { condition, then_branch, else_if: kept, else_branch: otherwise }))
+            }
+            Statement::DoWhile(DoWhileStatement { condition: Some(condition), condition_type, test_at_end: false, body, .. }) => {
+                let never = match condition_type {
+                    DoWhileConditionType::While => literal_truth(condition) == Some(false),
+                    DoWhileConditionType::Until => literal_truth(condition) == Some(true),
+                    DoWhileConditionType::Infinite => false,
+                };
+                (never && !has_label(body)).then(Body::default).map(Pruned::Splice)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl VisitorMut for Pruner {
+    fn visit_body(&mut self, body: &mut Body) {
+        walk_body_mut(self, body);
+        let pruned: Vec<_> = body.iter().map(Self::prune).collect();
+        if pruned.iter().all(Option::is_none) {
+            return;
+        }
+        let mut stmts = Vec::with_capacity(body.len());
+        let mut lines = Vec::with_capacity(body.len());
+        for ((stmt, &line), pruned) in body.iter().zip(body.lines()).zip(pruned) {
+            match pruned {
+                None => {
+                    stmts.push(stmt.clone());
+                    lines.push(line);
+                }
+                Some(Pruned::Keep(stmt)) => {
+                    stmts.push(stmt);
+                    lines.push(line);
+                    self.changes += 1;
+                }
+                Some(Pruned::Splice(inner)) => {
+                    stmts.extend(inner.iter().cloned());
+                    lines.extend_from_slice(inner.lines());
+                    self.changes += 1;
+                }
+            }
+        }
+        *body = Body::new(stmts, lines);
+    }
+}
+
+/// Whether a condition is a literal, and which way it goes
+fn literal_truth(condition: &Expression) -> Option<bool> {
+    match condition {
+        Expression::Boolean(b) => Some(*b),
+        Expression::Integer(n) | Expression::LongLong(n) => Some(*n != 0),
+        Expression::Long(n) => Some(*n != 0),
+        Expression::Byte(b) => Some(*b != 0),
+        Expression::Double(f) | Expression::Decimal(f) | Expression::Currency(f) => Some(*f != 0.0),
+        Expression::Single(f) => Some(*f != 0.0),
+        Expression::Parenthesized(inner) => literal_truth(inner),
+        _ => None,
+    }
+}
+
+/// Whether `body` declares a label anywhere inside
+fn has_label(body: &Body) -> bool {
+    struct Labels(bool);
+    impl Visitor for Labels {
+        fn visit_statement(&mut self, stmt: &Statement) {
+            self.0 |= matches!(stmt, Statement::Label(_));
+            walk_statement(self, stmt);
+        }
+    }
+    let mut labels = Labels(false);
+    labels.visit_body(body);
+    labels.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::AssignmentTarget;
+
+    fn call(name: &str, args: Vec<Expression>) -> Expression {
+        Expression::FunctionCall { function: Box::new(Expression::Identifier(name.into())), args }
+    }
+
+    fn binary(left: Expression, op: &str, right: Expression) -> Expression {
+        Expression::BinaryOp { left: Box::new(left), op: op.into(), right: Box::new(right) }
+    }
+
+    fn assign(name: &str, rvalue: Expression) -> Statement {
+        Statement::Assignment {
+            lvalue: AssignmentTarget::Identifier(name.into()),
+            rvalue,
+            rvalue_compiled: Default::default(),
+        }
+    }
+
+    fn main_body(program: &Program) -> &Body {
+        match &program.statements[0] {
+            Statement::Subroutine { body, .. } => body,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_deobfuscation_pipeline() {
+        // x = Chr(99) & Chr(Asc("l") + 1) & "d"
+        let hidden = binary(
+            binary(call("Chr", vec![Expression::Integer(99)]), "&", call("Chr", vec![binary(call("Asc", vec![Expression::String("l".into())]), "+", Expression::Integer(1))])),
+            "&",
+            Expression::String("d".into()),
+        );
+        // If 2 > 3 Then: y = 1 / Else: y = StrReverse("olleh")
+        let branch = Statement::If {
+            condition: binary(Expression::Integer(2), ">", Expression::Integer(3)),
+            then_branch: vec![assign("TfY", Expression::Integer(1))].into(),
+            else_if: vec![],
+            else_branch: Body::new(vec![assign("TfY", call("StrReverse", vec![Expression::String("olleh".into())]))], vec![7]),
+        };
+        let kept = Statement::If {
+            condition: Expression::Boolean(false),
+            then_branch: vec![Statement::Label("Skip".into())].into(),
+            else_if: vec![],
+            else_branch: Body::default(),
+        };
+        let raises = assign("TfZ", binary(Expression::Integer(1), "/", Expression::Integer(0)));
+        let body = Body::new(vec![assign("TfX", hidden), branch, kept, raises], vec![3, 4, 9, 10]);
+        let main = Statement::Subroutine { visibility: None, name: "TfMain".into(), params: vec![], body };
+        let mut executor = crate::vm::ProgramExecutor::new(Program { statements: vec![main], diagnostics: vec![] });
+
+        let outcomes = executor.transform(&mut PassPipeline::deobfuscation());
+        let changes: Vec<_> = outcomes.iter().map(|o| (o.pass.as_str(), o.changes)).collect();
+        assert_eq!(changes, vec![("constant-folding", 1), ("string-decryption", 7), ("dead-branch-elimination", 1)]);
+
+        let body = main_body(executor.program());
+        assert!(matches!(&body[0], Statement::Assignment { rvalue: Expression::String(s), .. } if s == "cmd"));
+        assert!(matches!(&body[1], Statement::Assignment { rvalue: Expression::String(s), .. } if s == "hello"));
+        assert_eq!(body.lines(), &[3, 7, 9, 10]);
+        assert!(matches!(&body[2], Statement::If { .. }));
+        assert!(matches!(&body[3], Statement::Assignment { rvalue: Expression::BinaryOp { .. }, .. }));
+    }
+}
@@ -0,0 +1,1106 @@
+// vba-utils/src/transpiler/mod.rs
+
+//! Translate simple macros into readable Python or Rust, as a migration aid.
+//!
+//! Supported subset:
+//! - Subs and Functions with typed or untyped locals and parameters
+//! - Assignments, `If`/`ElseIf`/`Else`, `For`/`Next`, `Do`/`Loop`, `Exit`
+//! - Arithmetic, comparison, logical and `&` operators
+//! - Common string and conversion functions (`Len`, `Mid`, `InStr`, `CLng`, ...)
+//! - `MsgBox` / `Debug.Print` (printed), calls between translated procedures
+//! - `Range(...)` and `Cells(...)` reads and writes, optionally through
+//!   `Worksheets(name)`
+//!
+//! Anything else is emitted as a `TODO` comment and listed in
+//! [`Transpiled::untranslated`], so the output is a starting point to
+//! review, not a drop-in replacement.
+
+mod python;
+mod rust;
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write as _;
+
+use crate::ast::{AssignmentTarget, Body, DoWhileConditionType, Expression, ExitType, Parameter, Program, Statement, split_type_suffix};
+use crate::context::Value;
+use crate::interpreter::builtins::resolve_builtin_identifier;
+use crate::interpreter::{BinOp, UnOp};
+
+/// Output language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Python,
+    Rust,
+}
+
+/// A construct that was not translated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Untranslated {
+    /// Enclosing procedure, `None` at module level
+    pub procedure: Option<String>,
+    pub line: Option<usize>,
+    /// Statement kind, see `Statement::kind_name`
+    pub construct: String,
+    pub reason: String,
+}
+
+/// Result of [`transpile`].
+#[derive(Debug, Clone)]
+pub struct Transpiled {
+    pub source: String,
+    pub untranslated: Vec<Untranslated>,
+}
+
+impl Transpiled {
+    /// True when every statement was translated.
+    pub fn is_complete(&self) -> bool {
+        self.untranslated.is_empty()
+    }
+
+    /// One line per untranslated construct.
+    pub fn report(&self) -> String {
+        let mut out = String::new();
+        for u in &self.untranslated {
+            let line = u.line.map(|l| format!("line {}: ", l)).unwrap_or_default();
+            let proc = u.procedure.as_deref().unwrap_or("(module)");
+            let _ = writeln!(out, "{}{} in {}: {}", line, u.construct, proc, u.reason);
+        }
+        out
+    }
+}
+
+/// Translate `program` into `target` source.
+pub fn transpile(program: &Program, target: Target) -> Transpiled {
+    match target {
+        Target::Python => Translator::new(python::Python).run(program),
+        Target::Rust => Translator::new(rust::Rust).run(program),
+    }
+}
+
+/// Value types tracked during translation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Ty {
+    Int,
+    Float,
+    Str,
+    Bool,
+    /// No declared type (Python only; Rust declares these as `f64`)
+    Variant,
+}
+
+impl Ty {
+    fn from_vba(name: Option<&str>) -> Ty {
+        match name.map(|n| n.to_ascii_lowercase()).as_deref() {
+            Some("integer" | "long" | "longlong" | "longptr" | "byte") => Ty::Int,
+            Some("double" | "single" | "currency" | "decimal") => Ty::Float,
+            Some("string") => Ty::Str,
+            Some("boolean") => Ty::Bool,
+            _ => Ty::Variant,
+        }
+    }
+
+    fn is_numeric(self) -> bool {
+        matches!(self, Ty::Int | Ty::Float)
+    }
+}
+
+/// Where a `Range`/`Cells` access points.
+pub(crate) enum Addr {
+    /// `Range(address)`; the address is already a string argument
+    A1(String),
+    /// `Cells(row, column)`
+    RowCol(String, String),
+}
+
+/// Target-language syntax. Statement methods return unindented lines;
+/// expression methods return code.
+pub(crate) trait Syntax {
+    fn comment(&self, text: &str) -> String;
+    /// Line that closes a block, if the language has one
+    fn block_end(&self) -> Option<&'static str>;
+    /// Line needed in a block with no statements
+    fn empty_block(&self) -> Option<&'static str>;
+    fn prelude(&self, helpers: &BTreeSet<&'static str>) -> String;
+    /// Name safe to use as an identifier
+    fn ident(&self, snake: &str) -> String;
+    /// Type used for undeclared or untyped variables
+    fn variant_ty(&self) -> Ty;
+
+    fn global(&self, name: &str, ty: Ty) -> Result<String, String>;
+    fn uses_globals(&self, names: &[String]) -> Option<String>;
+    fn declare(&self, name: &str, ty: Ty) -> String;
+    fn proc_head(&self, name: &str, params: &[(String, Ty, bool)], ret: Option<Ty>) -> String;
+    /// Last line of a Function body
+    fn final_return(&self, result: &str) -> String;
+    fn exit_proc(&self, result: Option<&str>) -> String;
+    fn statement(&self, code: &str) -> String;
+    fn assign(&self, name: &str, value: &str) -> String;
+    fn print(&self, args: Vec<String>) -> String;
+    fn call(&self, name: &str, args: Vec<String>) -> String;
+
+    fn if_head(&self, cond: &str) -> String;
+    fn elif_head(&self, cond: &str) -> String;
+    fn else_head(&self) -> String;
+    fn while_head(&self, cond: &str) -> String;
+    fn loop_head(&self) -> String;
+    fn break_stmt(&self) -> String;
+    fn break_if(&self, cond: &str) -> String;
+    /// A counted loop, when it maps onto a native range loop
+    fn for_range(&self, var: &str, start: &str, end: &str, step: i64) -> Option<String>;
+
+    fn string_lit(&self, s: &str) -> String;
+    fn bool_lit(&self, b: bool) -> String;
+    fn convert(&self, code: String, from: Ty, to: Ty) -> String;
+    /// A value about to be stored or passed by value
+    fn owned(&self, code: String, ty: Ty) -> String;
+    /// A string passed to a helper or method
+    fn str_arg(&self, code: String) -> String;
+    fn concat(&self, parts: Vec<(String, Ty)>) -> String;
+    fn binop(&self, op: BinOp, l: (String, Ty), r: (String, Ty)) -> Result<(String, Ty), String>;
+    fn not(&self, code: String, ty: Ty) -> (String, Ty);
+    fn builtin(
+        &self,
+        name: &str,
+        args: Vec<(String, Ty)>,
+        helpers: &mut BTreeSet<&'static str>,
+    ) -> Option<Result<(String, Ty), String>>;
+    /// Type of a value read from a cell
+    fn range_ty(&self) -> Ty;
+    fn range_get(&self, sheet: Option<String>, addr: Addr, helpers: &mut BTreeSet<&'static str>) -> String;
+    fn range_set(&self, sheet: Option<String>, addr: Addr, value: String, helpers: &mut BTreeSet<&'static str>) -> String;
+}
+
+struct ProcSig {
+    ident: String,
+    params: Vec<Ty>,
+    ret: Option<Ty>,
+}
+
+/// The procedure being translated.
+struct Current {
+    name: String,
+    result: Option<(String, Ty)>,
+}
+
+struct Translator<S: Syntax> {
+    syntax: S,
+    lines: Vec<String>,
+    indent: usize,
+    helpers: BTreeSet<&'static str>,
+    untranslated: Vec<Untranslated>,
+    /// lowercase VBA name → identifier, so every spelling maps the same way
+    names: HashMap<String, String>,
+    procs: HashMap<String, ProcSig>,
+    globals: HashMap<String, Ty>,
+    locals: HashMap<String, Ty>,
+    current: Option<Current>,
+    line: Option<usize>,
+}
+
+impl<S: Syntax> Translator<S> {
+    fn new(syntax: S) -> Self {
+        Translator {
+            syntax,
+            lines: Vec::new(),
+            indent: 0,
+            helpers: BTreeSet::new(),
+            untranslated: Vec::new(),
+            names: HashMap::new(),
+            procs: HashMap::new(),
+            globals: HashMap::new(),
+            locals: HashMap::new(),
+            current: None,
+            line: None,
+        }
+    }
+
+    fn run(mut self, program: &Program) -> Transpiled {
+        for stmt in &program.statements {
+            let (name, params, ret) = match stmt {
+                Statement::Subroutine { name, params, .. } => (name, params, None),
+                Statement::Function { name, params, return_type, .. } => {
+                    (name, params, Some(self.declared_ty(return_type.as_deref())))
+                }
+                _ => continue,
+            };
+            let ident = self.ident(name);
+            let params = params.iter().map(|p| self.declared_ty(p.param_type.as_deref())).collect();
+            self.procs.insert(name.to_lowercase(), ProcSig { ident, params, ret });
+        }
+
+        for stmt in &program.statements {
+            self.line = None;
+            match stmt {
+                Statement::Subroutine { name, params, body, .. } => self.procedure(name, params, None, body),
+                Statement::Function { name, params, return_type, body, .. } => {
+                    self.procedure(name, params, Some(return_type.as_deref()), body)
+                }
+                Statement::Dim { names } => {
+                    for (name, ty) in names {
+                        let ty = self.declared_ty(ty.as_deref());
+                        let ident = self.ident(name);
+                        match self.syntax.global(&ident, ty) {
+                            Ok(line) => {
+                                self.emit(line);
+                                self.globals.insert(name.to_lowercase(), ty);
+                            }
+                            Err(reason) => self.skip(stmt, reason),
+                        }
+                    }
+                }
+                Statement::Comment(text) => self.emit_comment(text),
+                Statement::BlankLine
+                | Statement::OptionExplicit
+                | Statement::OptionPrivateModule
+                | Statement::Attribute(_) => {}
+                other => self.skip(other, "not supported at module level".into()),
+            }
+        }
+
+        let mut source = self.syntax.prelude(&self.helpers);
+        let start = self.lines.iter().position(|l| !l.is_empty()).unwrap_or(self.lines.len());
+        source.push('\n');
+        for line in &self.lines[start..] {
+            source.push_str(line);
+            source.push('\n');
+        }
+        Transpiled { source, untranslated: self.untranslated }
+    }
+
+    // -- names and output ---------------------------------------------------
+
+    fn ident(&mut self, name: &str) -> String {
+        if let Some(id) = self.names.get(&name.to_lowercase()) {
+            return id.clone();
+        }
+        let id = self.syntax.ident(&snake_case(name));
+        self.names.insert(name.to_lowercase(), id.clone());
+        id
+    }
+
+    fn declared_ty(&self, name: Option<&str>) -> Ty {
+        match Ty::from_vba(name) {
+            Ty::Variant => self.syntax.variant_ty(),
+            ty => ty,
+        }
+    }
+
+    /// Add `code` (one or more lines) at the current indentation.
+    fn emit(&mut self, code: String) {
+        for line in code.split('\n') {
+            if line.is_empty() {
+                self.lines.push(String::new());
+            } else {
+                self.lines.push(format!("{}{}", "    ".repeat(self.indent), line));
+            }
+        }
+    }
+
+    fn emit_comment(&mut self, text: &str) {
+        let text = text.trim().trim_start_matches('\'').trim();
+        let line = self.syntax.comment(text);
+        self.emit(line);
+    }
+
+    fn skip(&mut self, stmt: &Statement, reason: String) {
+        let construct = stmt.kind_name().to_string();
+        let line = self.syntax.comment(&format!("TODO: untranslated {}: {}", construct, reason));
+        self.emit(line);
+        self.untranslated.push(Untranslated {
+            procedure: self.current.as_ref().map(|c| c.name.clone()),
+            line: self.line,
+            construct,
+            reason,
+        });
+    }
+
+    /// Emit `head`, run `body` one level deeper, and close the block.
+    fn block(&mut self, head: String, body: impl FnOnce(&mut Self)) {
+        self.emit(head);
+        self.indent += 1;
+        let before = self.lines.len();
+        body(self);
+        if self.lines.len() == before {
+            if let Some(empty) = self.syntax.empty_block() {
+                self.emit(empty.to_string());
+            }
+        }
+        self.indent -= 1;
+        if let Some(end) = self.syntax.block_end() {
+            self.emit(end.to_string());
+        }
+    }
+
+    // -- procedures -------------------------------------------------------
+
+    fn procedure(&mut self, name: &str, params: &[Parameter], ret: Option<Option<&str>>, body: &Body) {
+        self.locals.clear();
+        let ret_ty = ret.map(|r| self.declared_ty(r));
+        let assigned = assigned_names(body);
+
+        let mut sig = Vec::new();
+        for p in params {
+            let ty = self.declared_ty(p.param_type.as_deref());
+            self.locals.insert(p.name.to_lowercase(), ty);
+            sig.push((self.ident(&p.name), ty, assigned.contains(&p.name.to_lowercase())));
+        }
+
+        let mut dims = Vec::new();
+        collect_dims(body, &mut dims);
+        let mut declared = Vec::new();
+        for (var, ty) in dims {
+            let key = var.to_lowercase();
+            if self.locals.contains_key(&key) {
+                continue;
+            }
+            let ty = self.declared_ty(ty.as_deref());
+            self.locals.insert(key, ty);
+            declared.push((self.ident(&var), ty));
+        }
+
+        let result = ret_ty.map(|ty| {
+            let taken = declared.iter().any(|(n, _)| n == "result") || sig.iter().any(|(n, _, _)| n == "result");
+            (if taken { "fn_result" } else { "result" }.to_string(), ty)
+        });
+        self.current = Some(Current { name: name.to_string(), result: result.clone() });
+
+        // Implicit variables: assigned but never declared
+        for var in &assigned {
+            let is_self = var.eq_ignore_ascii_case(name);
+            if !is_self && !self.locals.contains_key(var) && !self.globals.contains_key(var) {
+                let ty = self.syntax.variant_ty();
+                self.locals.insert(var.clone(), ty);
+                declared.push((self.ident(var), ty));
+            }
+        }
+
+        let ident = self.ident(name);
+        let head = self.syntax.proc_head(&ident, &sig, ret_ty);
+        let used_globals: Vec<String> = self
+            .globals
+            .keys()
+            .filter(|g| assigned.contains(*g))
+            .map(|g| self.names[g].clone())
+            .collect();
+
+        self.emit(String::new());
+        self.block(head, |t| {
+            for p in params.iter().filter(|p| p.optional || p.param_array) {
+                let reason = format!("parameter `{}` is Optional/ParamArray; translated as required", p.name);
+                t.note(reason);
+            }
+            if let Some(line) = t.syntax.uses_globals(&used_globals) {
+                t.emit(line);
+            }
+            for (var, ty) in &declared {
+                let line = t.syntax.declare(var, *ty);
+                t.emit(line);
+            }
+            if let Some((var, ty)) = &result {
+                let line = t.syntax.declare(var, *ty);
+                t.emit(line);
+            }
+            t.body(body);
+            if let Some((var, _)) = &result {
+                let line = t.syntax.final_return(var);
+                t.emit(line);
+            }
+        });
+        self.current = None;
+    }
+
+    /// Record a translation caveat that is not tied to a skipped statement.
+    fn note(&mut self, reason: String) {
+        let line = self.syntax.comment(&format!("NOTE: {}", reason));
+        self.emit(line);
+        self.untranslated.push(Untranslated {
+            procedure: self.current.as_ref().map(|c| c.name.clone()),
+            line: self.line,
+            construct: "Parameter".into(),
+            reason,
+        });
+    }
+
+    // -- statements -------------------------------------------------------
+
+    fn body(&mut self, body: &Body) {
+        for (pc, stmt) in body.iter().enumerate() {
+            self.line = body.line(pc);
+            if let Err(reason) = self.statement(stmt) {
+                self.skip(stmt, reason);
+            }
+        }
+    }
+
+    fn statement(&mut self, stmt: &Statement) -> Result<(), String> {
+        match stmt {
+            Statement::BlankLine => self.emit(String::new()),
+            Statement::Comment(text) => self.emit_comment(text),
+            Statement::Dim { .. } | Statement::OptionExplicit | Statement::Attribute(_) => {}
+
+            Statement::Assignment { lvalue: AssignmentTarget::Identifier(name), rvalue, .. } => {
+                let (target, ty) = self.variable(split_type_suffix(name).0)?;
+                let (code, from) = self.expr(rvalue)?;
+                let value = self.syntax.owned(self.syntax.convert(code, from, ty), ty);
+                let line = self.syntax.assign(&target, &value);
+                self.emit(line);
+            }
+            Statement::Assignment { lvalue: AssignmentTarget::PropertyAccess { object, property }, rvalue, .. } => {
+                let target = Expression::PropertyAccess { obj: object.clone(), property: property.clone() };
+                let (sheet, addr) = self.range(&target)?.ok_or("only Range/Cells values can be assigned")?;
+                let (code, from) = self.expr(rvalue)?;
+                let ty = self.syntax.range_ty();
+                let value = self.syntax.owned(self.syntax.convert(code, from, ty), ty);
+                let line = self.syntax.range_set(sheet, addr, value, &mut self.helpers);
+                self.emit(line);
+            }
+
+            Statement::MsgBox { expr, .. } => {
+                let (code, _) = self.expr(expr)?;
+                let line = self.syntax.print(vec![code]);
+                self.emit(line);
+            }
+            Statement::Call { function, args } => self.call_statement(function, args)?,
+            Statement::Expression(expr) => self.expression_statement(expr)?,
+
+            Statement::If { condition, then_branch, else_if, else_branch } => {
+                let cond = self.condition(condition)?;
+                let mut arms = Vec::new();
+                for (c, body) in else_if {
+                    arms.push((self.condition(c)?, body));
+                }
+                self.if_chain(cond, then_branch, arms, else_branch);
+            }
+
+            Statement::For(f) => {
+                let (var, ty) = self.variable(&f.counter)?;
+                let (start, st) = self.expr(&f.start)?;
+                let (end, et) = self.expr(&f.end)?;
+                let step = match &f.step {
+                    Some(e) => Some(self.expr(e)?),
+                    None => None,
+                };
+                let literal_step = match &f.step {
+                    None => Some(1),
+                    Some(e) => literal_int(e),
+                };
+                if !ty.is_numeric() && ty != Ty::Variant {
+                    return Err(format!("loop counter `{}` is not numeric", f.counter));
+                }
+                let num = if ty == Ty::Variant { Ty::Int } else { ty };
+                let start = self.syntax.convert(start, st, num);
+                let end = self.syntax.convert(end, et, num);
+
+                let native = match (ty, literal_step) {
+                    (Ty::Int | Ty::Variant, Some(step)) if step != 0 => {
+                        self.syntax.for_range(&var, &start, &end, step)
+                    }
+                    _ => None,
+                };
+                if let Some(head) = native {
+                    self.block(head, |t| t.body(&f.body));
+                } else {
+                    // Generic form: counter = start / while in range / body / step
+                    let (step_code, step_ty) = step.unwrap_or(("1".into(), Ty::Int));
+                    let step_code = self.syntax.convert(step_code, step_ty, num);
+                    let cmp = if literal_step.is_some_and(|s| s < 0) { ">=" } else { "<=" };
+                    let assign = self.syntax.assign(&var, &start);
+                    self.emit(assign);
+                    let cond = format!("{} {} {}", var, cmp, end);
+                    let head = self.syntax.while_head(&cond);
+                    let increment = self.syntax.statement(&format!("{} += {}", var, step_code));
+                    self.block(head, |t| {
+                        t.body(&f.body);
+                        t.emit(increment);
+                    });
+                }
+            }
+
+            Statement::DoWhile(d) => {
+                let cond = match &d.condition {
+                    Some(c) => Some(self.condition(c)?),
+                    None => None,
+                };
+                let until = d.condition_type == DoWhileConditionType::Until;
+                match cond {
+                    None => {
+                        let head = self.syntax.loop_head();
+                        self.block(head, |t| t.body(&d.body));
+                    }
+                    Some(cond) if !d.test_at_end => {
+                        let cond = if until { self.syntax.not(cond, Ty::Bool).0 } else { cond };
+                        let head = self.syntax.while_head(&cond);
+                        self.block(head, |t| t.body(&d.body));
+                    }
+                    Some(cond) => {
+                        let stop = if until { cond } else { self.syntax.not(cond, Ty::Bool).0 };
+                        let head = self.syntax.loop_head();
+                        let exit = self.syntax.break_if(&stop);
+                        self.block(head, |t| {
+                            t.body(&d.body);
+                            t.emit(exit);
+                        });
+                    }
+                }
+            }
+
+            Statement::Exit(kind) => {
+                let line = match kind {
+                    ExitType::For | ExitType::Do | ExitType::While => self.syntax.break_stmt(),
+                    ExitType::Sub | ExitType::Function => {
+                        let result = self.current.as_ref().and_then(|c| c.result.as_ref()).map(|(r, _)| r.clone());
+                        self.syntax.exit_proc(result.as_deref())
+                    }
+                    other => return Err(format!("Exit {:?}", other)),
+                };
+                self.emit(line);
+            }
+
+            Statement::Set { .. } => return Err("object references (Set) have no equivalent".into()),
+            Statement::GoTo { .. } | Statement::Label(_) => return Err("GoTo/labels need restructuring".into()),
+            Statement::OnError(_) | Statement::Resume(_) => {
+                return Err("VBA error handling needs manual translation".into())
+            }
+            Statement::With { .. } => return Err("With blocks act on objects".into()),
+            Statement::ReDim { .. } => return Err("dynamic arrays are not supported".into()),
+            Statement::Unparsed { .. } => return Err("statement could not be parsed".into()),
+            _ => return Err("not supported inside procedures".into()),
+        }
+        Ok(())
+    }
+
+    fn if_chain(&mut self, cond: String, then_branch: &Body, arms: Vec<(String, &Body)>, else_branch: &Body) {
+        let head = self.syntax.if_head(&cond);
+        // Each arm is emitted as a block whose closing line is replaced by
+        // the next arm's head (Rust `} else if`, Python dedented `elif`)
+        self.emit(head);
+        self.indent += 1;
+        self.arm(then_branch);
+        for (cond, body) in arms {
+            self.indent -= 1;
+            let head = self.syntax.elif_head(&cond);
+            self.emit(head);
+            self.indent += 1;
+            self.arm(body);
+        }
+        if !else_branch.is_empty() {
+            self.indent -= 1;
+            let head = self.syntax.else_head();
+            self.emit(head);
+            self.indent += 1;
+            self.arm(else_branch);
+        }
+        self.indent -= 1;
+        if let Some(end) = self.syntax.block_end() {
+            self.emit(end.to_string());
+        }
+    }
+
+    fn arm(&mut self, body: &Body) {
+        let before = self.lines.len();
+        self.body(body);
+        if self.lines.len() == before {
+            if let Some(empty) = self.syntax.empty_block() {
+                self.emit(empty.to_string());
+            }
+        }
+    }
+
+    fn call_statement(&mut self, function: &str, args: &[Expression]) -> Result<(), String> {
+        let lower = function.to_ascii_lowercase();
+        if lower == "msgbox" || lower == "debug.print" {
+            let mut codes = Vec::new();
+            for a in args.iter().take(if lower == "msgbox" { 1 } else { args.len() }) {
+                codes.push(self.expr(a)?.0);
+            }
+            let line = self.syntax.print(codes);
+            self.emit(line);
+            return Ok(());
+        }
+        let call = self.user_call(function, args)?.ok_or_else(|| format!("unknown procedure `{}`", function))?;
+        let line = self.syntax.statement(&call.0);
+        self.emit(line);
+        Ok(())
+    }
+
+    fn expression_statement(&mut self, expr: &Expression) -> Result<(), String> {
+        match expr {
+            Expression::FunctionCall { function, args } => match function.as_ref() {
+                Expression::Identifier(name) => self.call_statement(name, args),
+                Expression::PropertyAccess { obj, property }
+                    if matches!(obj.as_ref(), Expression::Identifier(o) if o.eq_ignore_ascii_case("Debug"))
+                        && property.eq_ignore_ascii_case("Print") =>
+                {
+                    self.call_statement("Debug.Print", args)
+                }
+                _ => Err("call on an object".into()),
+            },
+            Expression::Identifier(name) => self.call_statement(name, &[]),
+            _ => Err("expression statement".into()),
+        }
+    }
+
+    // -- expressions ------------------------------------------------------
+
+    /// An assignable variable: a local, a global, or the Function result.
+    fn variable(&mut self, name: &str) -> Result<(String, Ty), String> {
+        let key = name.to_lowercase();
+        if let Some(Current { name: current, result: Some((result, ty)) }) = &self.current {
+            if current.eq_ignore_ascii_case(name) {
+                return Ok((result.clone(), *ty));
+            }
+        }
+        let ty = self
+            .locals
+            .get(&key)
+            .or_else(|| self.globals.get(&key))
+            .copied()
+            .ok_or_else(|| format!("unknown variable `{}`", name))?;
+        Ok((self.ident(name), ty))
+    }
+
+    fn condition(&mut self, expr: &Expression) -> Result<String, String> {
+        let (code, ty) = self.expr(expr)?;
+        Ok(self.syntax.convert(code, ty, Ty::Bool))
+    }
+
+    fn expr(&mut self, expr: &Expression) -> Result<(String, Ty), String> {
+        match expr {
+            Expression::Integer(n) | Expression::LongLong(n) => Ok((n.to_string(), Ty::Int)),
+            Expression::Long(n) => Ok((n.to_string(), Ty::Int)),
+            Expression::Byte(n) => Ok((n.to_string(), Ty::Int)),
+            Expression::Double(f) | Expression::Decimal(f) | Expression::Currency(f) => Ok((format!("{:?}", f), Ty::Float)),
+            Expression::Single(f) => Ok((format!("{:?}", *f as f64), Ty::Float)),
+            Expression::String(s) => Ok((self.syntax.string_lit(s), Ty::Str)),
+            Expression::Boolean(b) => Ok((self.syntax.bool_lit(*b), Ty::Bool)),
+            Expression::Parenthesized(inner) => {
+                let (code, ty) = self.expr(inner)?;
+                Ok((format!("({})", code), ty))
+            }
+            Expression::Identifier(name) | Expression::BuiltInConstant(name) => self.identifier(name),
+            Expression::UnaryOp { op, expr } => {
+                let (code, ty) = self.expr(expr)?;
+                let code = if matches!(expr.as_ref(), Expression::BinaryOp { .. }) { format!("({})", code) } else { code };
+                match UnOp::parse(op) {
+                    Some(UnOp::Plus) => Ok((code, ty)),
+                    Some(UnOp::Neg) => Ok((format!("-{}", code), ty)),
+                    Some(UnOp::Not) => Ok(self.syntax.not(code, ty)),
+                    None => Err(format!("operator `{}`", op)),
+                }
+            }
+            Expression::BinaryOp { left, op, right } => {
+                let op = BinOp::parse(op).ok_or_else(|| format!("operator `{}`", op))?;
+                let is_concat = op == BinOp::Concat
+                    || (op == BinOp::Add && self.type_of(left)? == Ty::Str && self.type_of(right)? == Ty::Str);
+                if is_concat {
+                    let mut parts = Vec::new();
+                    self.concat_parts(expr, &mut parts)?;
+                    return Ok((self.syntax.concat(parts), Ty::Str));
+                }
+                let l = self.operand(left, op, false)?;
+                let r = self.operand(right, op, true)?;
+                self.syntax.binop(op, l, r)
+            }
+            Expression::FunctionCall { function, args } => {
+                if let Some((sheet, addr)) = self.range(expr)? {
+                    let code = self.syntax.range_get(sheet, addr, &mut self.helpers);
+                    return Ok((code, self.syntax.range_ty()));
+                }
+                let Expression::Identifier(name) = function.as_ref() else {
+                    return Err("call on an object".into());
+                };
+                if let Some(call) = self.user_call(name, args)? {
+                    return Ok(call);
+                }
+                let mut codes = Vec::new();
+                for a in args {
+                    codes.push(self.expr(a)?);
+                }
+                match self.syntax.builtin(&name.to_ascii_lowercase(), codes, &mut self.helpers) {
+                    Some(result) => result,
+                    None => Err(format!("unsupported function `{}`", name)),
+                }
+            }
+            Expression::PropertyAccess { obj, property } => {
+                if let Some((sheet, addr)) = self.range(expr)? {
+                    let code = self.syntax.range_get(sheet, addr, &mut self.helpers);
+                    return Ok((code, self.syntax.range_ty()));
+                }
+                match obj.as_ref() {
+                    Expression::Identifier(o) => Err(format!("member access `{}.{}`", o, property)),
+                    _ => Err(format!("member access `.{}`", property)),
+                }
+            }
+            Expression::Date(_) => Err("date literals".into()),
+            Expression::WithMemberAccess { .. } | Expression::WithMethodCall { .. } => {
+                Err("With member access".into())
+            }
+        }
+    }
+
+    /// One side of `parent`, parenthesized unless precedence already
+    /// groups it the same way in every target language.
+    fn operand(&mut self, expr: &Expression, parent: BinOp, right: bool) -> Result<(String, Ty), String> {
+        let (code, ty) = self.expr(expr)?;
+        let child = match expr {
+            Expression::BinaryOp { op, .. } => BinOp::parse(op),
+            _ => None,
+        };
+        match child {
+            Some(child) if needs_parens(child, parent, right) => Ok((format!("({})", code), ty)),
+            _ => Ok((code, ty)),
+        }
+    }
+
+    /// Flatten `a & b & c` into its parts.
+    fn concat_parts(&mut self, expr: &Expression, parts: &mut Vec<(String, Ty)>) -> Result<(), String> {
+        if let Expression::BinaryOp { left, op, right } = expr {
+            let add_str = op == "+" && self.type_of(left)? == Ty::Str && self.type_of(right)? == Ty::Str;
+            if op == "&" || add_str {
+                self.concat_parts(left, parts)?;
+                return self.concat_parts(right, parts);
+            }
+        }
+        parts.push(self.expr(expr)?);
+        Ok(())
+    }
+
+    /// Type of `expr`, discarding its translation.
+    fn type_of(&mut self, expr: &Expression) -> Result<Ty, String> {
+        let helpers = self.helpers.clone();
+        let ty = self.expr(expr).map(|(_, ty)| ty);
+        self.helpers = helpers;
+        ty
+    }
+
+    fn identifier(&mut self, name: &str) -> Result<(String, Ty), String> {
+        let key = name.to_lowercase();
+        if self.locals.contains_key(&key)
+            || self.globals.contains_key(&key)
+            || self.current.as_ref().is_some_and(|c| c.name.eq_ignore_ascii_case(name) && c.result.is_some())
+        {
+            return self.variable(name);
+        }
+        // Parameterless Function or Sub
+        if let Some(call) = self.user_call(name, &[])? {
+            return Ok(call);
+        }
+        match resolve_builtin_identifier(name) {
+            Some(Value::String(s)) => Ok((self.syntax.string_lit(&s), Ty::Str)),
+            Some(Value::Boolean(b)) => Ok((self.syntax.bool_lit(b), Ty::Bool)),
+            Some(Value::Integer(n)) => Ok((n.to_string(), Ty::Int)),
+            Some(Value::Long(n)) => Ok((n.to_string(), Ty::Int)),
+            Some(Value::Double(f)) => Ok((format!("{:?}", f), Ty::Float)),
+            _ => Err(format!("unknown name `{}`", name)),
+        }
+    }
+
+    /// A call to a translated Sub/Function, or `None` if `name` is not one.
+    fn user_call(&mut self, name: &str, args: &[Expression]) -> Result<Option<(String, Ty)>, String> {
+        let Some(sig) = self.procs.get(&name.to_lowercase()) else {
+            return Ok(None);
+        };
+        let (ident, params, ret) = (sig.ident.clone(), sig.params.clone(), sig.ret);
+        if args.len() > params.len() {
+            return Err(format!("too many arguments to `{}`", name));
+        }
+        let mut codes = Vec::new();
+        for (a, ty) in args.iter().zip(params) {
+            let (code, from) = self.expr(a)?;
+            codes.push(self.syntax.owned(self.syntax.convert(code, from, ty), ty));
+        }
+        Ok(Some((self.syntax.call(&ident, codes), ret.unwrap_or(Ty::Variant))))
+    }
+
+    /// Match `Range(a)`, `Cells(r, c)` and `Worksheets(n).Range(a)` (with or
+    /// without `.Value`).
+    fn range(&mut self, expr: &Expression) -> Result<Option<(Option<String>, Addr)>, String> {
+        let expr = match expr {
+            Expression::PropertyAccess { obj, property }
+                if property.eq_ignore_ascii_case("Value") || property.eq_ignore_ascii_case("Value2") =>
+            {
+                obj.as_ref()
+            }
+            other => other,
+        };
+        let Expression::FunctionCall { function, args } = expr else {
+            return Ok(None);
+        };
+        let (sheet, kind) = match function.as_ref() {
+            Expression::Identifier(kind) => (None, kind),
+            Expression::PropertyAccess { obj, property } => (Some(obj.as_ref()), property),
+            _ => return Ok(None),
+        };
+
+        let sheet = match sheet {
+            None => None,
+            Some(Expression::Identifier(s)) if s.eq_ignore_ascii_case("ActiveSheet") => None,
+            Some(Expression::FunctionCall { function, args })
+                if matches!(function.as_ref(), Expression::Identifier(f)
+                    if f.eq_ignore_ascii_case("Worksheets") || f.eq_ignore_ascii_case("Sheets"))
+                    && args.len() == 1 =>
+            {
+                let (code, ty) = self.expr(&args[0])?;
+                if ty != Ty::Str {
+                    return Err("sheets must be named".into());
+                }
+                Some(self.syntax.str_arg(code))
+            }
+            Some(_) => return Ok(None),
+        };
+
+        let addr = match (kind.to_ascii_lowercase().as_str(), args.as_slice()) {
+            ("range", [a]) => {
+                let (code, ty) = self.expr(a)?;
+                Addr::A1(self.syntax.str_arg(self.syntax.convert(code, ty, Ty::Str)))
+            }
+            ("cells", [r, c]) => {
+                let (row, rt) = self.expr(r)?;
+                let (col, ct) = self.expr(c)?;
+                Addr::RowCol(self.syntax.convert(row, rt, Ty::Int), self.syntax.convert(col, ct, Ty::Int))
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some((sheet, addr)))
+    }
+}
+
+/// `TotalSales` → `total_sales`
+fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() && prev_lower {
+            out.push('_');
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        out.push(c.to_ascii_lowercase());
+    }
+    out
+}
+
+/// Coarse binding strength shared by VBA, Python and Rust.
+fn level(op: BinOp) -> u8 {
+    match op {
+        BinOp::Pow => 7,
+        BinOp::Mul | BinOp::Div | BinOp::IntDiv | BinOp::Mod => 6,
+        BinOp::Add | BinOp::Sub => 5,
+        BinOp::Concat => 4,
+        BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => 3,
+        BinOp::And => 2,
+        BinOp::Or => 1,
+        BinOp::Xor | BinOp::Eqv | BinOp::Imp => 0,
+    }
+}
+
+fn needs_parens(child: BinOp, parent: BinOp, right: bool) -> bool {
+    let (c, p) = (level(child), level(parent));
+    // Same level: left-associative chains read the same everywhere, except
+    // `^` (right-associative in Python), comparisons (non-associative in
+    // Rust) and Xor (lowered to `!=`)
+    c < p || (c == p && (right || matches!(p, 7 | 3 | 0)))
+}
+
+/// Double-quoted literal with backslash escapes (valid in both targets).
+pub(crate) fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// True when `code` can take a method call or cast without parentheses.
+pub(crate) fn is_atomic(code: &str) -> bool {
+    if code.is_empty() || code.starts_with(['-', '!', '&', '*']) {
+        return false;
+    }
+    let (mut depth, mut in_str, mut escaped) = (0usize, false, false);
+    for c in code.chars() {
+        if in_str {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_str = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_str = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ' ' if depth == 0 => return false,
+            _ => {}
+        }
+    }
+    true
+}
+
+/// True when `code` is a single string literal.
+pub(crate) fn is_str_lit(code: &str) -> bool {
+    code.len() >= 2 && code.starts_with('"') && code.ends_with('"') && is_atomic(code)
+}
+
+/// `code` ready to take `.method()` or `as T`.
+pub(crate) fn recv(code: &str) -> String {
+    if is_atomic(code) {
+        code.to_string()
+    } else {
+        format!("({})", code)
+    }
+}
+
+/// The value of an integer literal in generated code.
+pub(crate) fn int_lit(code: &str) -> Option<i64> {
+    code.parse().ok()
+}
+
+fn literal_int(expr: &Expression) -> Option<i64> {
+    match expr {
+        Expression::Integer(n) | Expression::LongLong(n) => Some(*n),
+        Expression::Long(n) => Some((*n).into()),
+        Expression::UnaryOp { op, expr } if op == "-" => literal_int(expr).map(|n| -n),
+        Expression::Parenthesized(inner) => literal_int(inner),
+        _ => None,
+    }
+}
+
+/// `Dim` declarations anywhere in `stmts`, in order.
+fn collect_dims(stmts: &[Statement], out: &mut Vec<(String, Option<String>)>) {
+    for stmt in stmts {
+        if let Statement::Dim { names } = stmt {
+            out.extend(names.iter().cloned());
+        }
+        for body in stmt.child_bodies() {
+            collect_dims(body, out);
+        }
+    }
+}
+
+/// Lowercase names assigned anywhere in `stmts` (including loop counters).
+fn assigned_names(stmts: &[Statement]) -> BTreeSet<String> {
+    let mut out = BTreeSet::new();
+    collect_assigned(stmts, &mut out);
+    out
+}
+
+fn collect_assigned(stmts: &[Statement], out: &mut BTreeSet<String>) {
+    for stmt in stmts {
+        match stmt {
+            Statement::Assignment { lvalue: AssignmentTarget::Identifier(name), .. } => {
+                out.insert(split_type_suffix(name).0.to_lowercase());
+            }
+            Statement::For(f) => {
+                out.insert(f.counter.to_lowercase());
+            }
+            _ => {}
+        }
+        for body in stmt.child_bodies() {
+            collect_assigned(body, out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::ForStatement;
+
+    fn ident(name: &str) -> Expression {
+        Expression::Identifier(name.into())
+    }
+
+    fn assign(name: &str, rvalue: Expression) -> Statement {
+        Statement::Assignment {
+            lvalue: AssignmentTarget::Identifier(name.into()),
+            rvalue,
+            rvalue_compiled: Default::default(),
+        }
+    }
+
+    fn binop(left: Expression, op: &str, right: Expression) -> Expression {
+        Expression::BinaryOp { left: Box::new(left), op: op.into(), right: Box::new(right) }
+    }
+
+    /// `Function SumTo(n As Long) As Long` adding 1..n, then `Set` (untranslatable)
+    fn sum_to() -> Program {
+        let body = vec![
+            Statement::Dim { names: vec![("i".into(), Some("Long".into()))] },
+            Statement::For(ForStatement {
+                counter: "i".into(),
+                start: Expression::Integer(1),
+                end: ident("n"),
+                step: None,
+                body: vec![assign("SumTo", binop(ident("SumTo"), "+", ident("i")))].into(),
+                next_counter: None,
+            }),
+            Statement::Set { target: "obj".into(), expr: Expression::Identifier("Nothing".into()) },
+        ];
+        let mut param = Parameter::simple("n".into());
+        param.param_type = Some("Long".into());
+        let function = Statement::Function {
+            visibility: None,
+            name: "SumTo".into(),
+            params: vec![param],
+            return_type: Some("Long".into()),
+            body: body.into(),
+        };
+        Program { statements: vec![function], diagnostics: vec![] }
+    }
+
+    #[test]
+    fn test_python_function_and_report() {
+        let out = transpile(&sum_to(), Target::Python);
+        assert!(out.source.contains("def sum_to(n: int) -> int:"), "{}", out.source);
+        assert!(out.source.contains("    for i in range(1, n + 1):\n        result = result + i\n"));
+        assert!(out.source.contains("    return result\n"));
+        assert!(out.source.contains("# TODO: untranslated Set"));
+        assert_eq!(out.untranslated.len(), 1);
+        assert_eq!(out.untranslated[0].procedure.as_deref(), Some("SumTo"));
+        assert!(out.report().contains("Set in SumTo"));
+    }
+
+    #[test]
+    fn test_rust_function() {
+        let out = transpile(&sum_to(), Target::Rust);
+        assert!(out.source.contains("pub fn sum_to(n: i64, wb: &mut dyn Workbook) -> i64 {"), "{}", out.source);
+        assert!(out.source.contains("    let mut i: i64 = 0;\n    let mut result: i64 = 0;\n"));
+        assert!(out.source.contains("    for i in 1..=n {\n        result = result + i;\n    }\n"));
+        assert!(out.source.contains("    result\n}"));
+    }
+
+    #[test]
+    fn test_precedence_parentheses() {
+        let mut t = Translator::new(python::Python);
+        // (a - b) - (c * (d + e)), with a..e undeclared names
+        for v in ["a", "b", "c", "d", "e"] {
+            t.locals.insert(v.into(), Ty::Int);
+        }
+        let expr = binop(
+            binop(ident("a"), "-", ident("b")),
+            "-",
+            binop(ident("c"), "*", Expression::Parenthesized(Box::new(binop(ident("d"), "+", ident("e"))))),
+        );
+        assert_eq!(t.expr(&expr).unwrap().0, "a - b - c * (d + e)");
+        let right = binop(ident("a"), "-", binop(ident("b"), "-", ident("c")));
+        assert_eq!(t.expr(&right).unwrap().0, "a - (b - c)");
+    }
+
+    #[test]
+    fn test_snake_case() {
+        assert_eq!(snake_case("TotalSales"), "total_sales");
+        assert_eq!(snake_case("i"), "i");
+        assert_eq!(snake_case("rowCount2"), "row_count2");
+        assert_eq!(snake_case("HTTP"), "http");
+    }
+}
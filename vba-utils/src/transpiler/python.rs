@@ -0,0 +1,362 @@
+// vba-utils/src/transpiler/python.rs
+
+//! Python output. Cells go through an openpyxl-style workbook in the
+//! module-level `wb`.
+
+use std::collections::BTreeSet;
+
+use super::{int_lit, quote, recv, Addr, Syntax, Ty};
+use crate::interpreter::BinOp;
+
+pub(crate) struct Python;
+
+/// Keywords, and builtins the generated code calls.
+const RESERVED: [&str; 49] = [
+    "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif", "else", "except",
+    "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass",
+    "raise", "return", "try", "while", "with", "yield", "none", "true", "false", "len", "str", "int", "float",
+    "bool", "round", "abs", "chr", "ord", "print", "range", "math", "wb", "result",
+];
+
+fn hint(ty: Ty) -> Option<&'static str> {
+    match ty {
+        Ty::Int => Some("int"),
+        Ty::Float => Some("float"),
+        Ty::Str => Some("str"),
+        Ty::Bool => Some("bool"),
+        Ty::Variant => None,
+    }
+}
+
+fn default(ty: Ty) -> &'static str {
+    match ty {
+        Ty::Int => "0",
+        Ty::Float => "0.0",
+        Ty::Str => "\"\"",
+        Ty::Bool => "False",
+        Ty::Variant => "0",
+    }
+}
+
+fn sheet(sheet: Option<String>) -> String {
+    format!("_sheet({})", sheet.unwrap_or_default())
+}
+
+fn cell(sheet_code: Option<String>, addr: Addr) -> String {
+    match addr {
+        Addr::A1(a) => format!("{}[{}].value", sheet(sheet_code), a),
+        Addr::RowCol(r, c) => format!("{}.cell(row={}, column={}).value", sheet(sheet_code), r, c),
+    }
+}
+
+impl Syntax for Python {
+    fn comment(&self, text: &str) -> String {
+        if text.is_empty() { "#".into() } else { format!("# {}", text) }
+    }
+
+    fn block_end(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn empty_block(&self) -> Option<&'static str> {
+        Some("pass")
+    }
+
+    fn prelude(&self, helpers: &BTreeSet<&'static str>) -> String {
+        let mut out = String::from("# Translated from VBA. Review before use.\n");
+        if helpers.contains("math") {
+            out.push_str("\nimport math\n");
+        }
+        if helpers.contains("sheet") {
+            out.push_str(concat!(
+                "\n# An openpyxl Workbook, e.g. openpyxl.load_workbook(\"book.xlsx\")\n",
+                "wb = None\n",
+                "\n\n",
+                "def _sheet(name=None):\n",
+                "    return wb[name] if name else wb.active\n",
+            ));
+        }
+        out
+    }
+
+    fn ident(&self, snake: &str) -> String {
+        if RESERVED.contains(&snake) { format!("{}_", snake) } else { snake.to_string() }
+    }
+
+    fn variant_ty(&self) -> Ty {
+        Ty::Variant
+    }
+
+    fn global(&self, name: &str, ty: Ty) -> Result<String, String> {
+        Ok(self.declare(name, ty))
+    }
+
+    fn uses_globals(&self, names: &[String]) -> Option<String> {
+        if names.is_empty() {
+            return None;
+        }
+        let mut names = names.to_vec();
+        names.sort();
+        Some(format!("global {}", names.join(", ")))
+    }
+
+    fn declare(&self, name: &str, ty: Ty) -> String {
+        match hint(ty) {
+            Some(hint) => format!("{}: {} = {}", name, hint, default(ty)),
+            None => format!("{} = {}", name, default(ty)),
+        }
+    }
+
+    fn proc_head(&self, name: &str, params: &[(String, Ty, bool)], ret: Option<Ty>) -> String {
+        let params: Vec<String> = params
+            .iter()
+            .map(|(p, ty, _)| match hint(*ty) {
+                Some(hint) => format!("{}: {}", p, hint),
+                None => p.clone(),
+            })
+            .collect();
+        let ret = ret.and_then(hint).map(|h| format!(" -> {}", h)).unwrap_or_default();
+        format!("\ndef {}({}){}:", name, params.join(", "), ret)
+    }
+
+    fn final_return(&self, result: &str) -> String {
+        format!("return {}", result)
+    }
+
+    fn exit_proc(&self, result: Option<&str>) -> String {
+        match result {
+            Some(r) => format!("return {}", r),
+            None => "return".into(),
+        }
+    }
+
+    fn statement(&self, code: &str) -> String {
+        code.to_string()
+    }
+
+    fn assign(&self, name: &str, value: &str) -> String {
+        format!("{} = {}", name, value)
+    }
+
+    fn print(&self, args: Vec<String>) -> String {
+        format!("print({})", args.join(", "))
+    }
+
+    fn call(&self, name: &str, args: Vec<String>) -> String {
+        format!("{}({})", name, args.join(", "))
+    }
+
+    fn if_head(&self, cond: &str) -> String {
+        format!("if {}:", cond)
+    }
+
+    fn elif_head(&self, cond: &str) -> String {
+        format!("elif {}:", cond)
+    }
+
+    fn else_head(&self) -> String {
+        "else:".into()
+    }
+
+    fn while_head(&self, cond: &str) -> String {
+        format!("while {}:", cond)
+    }
+
+    fn loop_head(&self) -> String {
+        "while True:".into()
+    }
+
+    fn break_stmt(&self) -> String {
+        "break".into()
+    }
+
+    fn break_if(&self, cond: &str) -> String {
+        format!("if {}: break", cond)
+    }
+
+    fn for_range(&self, var: &str, start: &str, end: &str, step: i64) -> Option<String> {
+        // range() excludes its end, so move it one step past the VBA bound
+        let stop = match int_lit(end) {
+            Some(n) => (n + step.signum()).to_string(),
+            None if step > 0 => format!("{} + 1", end),
+            None => format!("{} - 1", end),
+        };
+        if step == 1 {
+            Some(format!("for {} in range({}, {}):", var, start, stop))
+        } else {
+            Some(format!("for {} in range({}, {}, {}):", var, start, stop, step))
+        }
+    }
+
+    fn string_lit(&self, s: &str) -> String {
+        quote(s)
+    }
+
+    fn bool_lit(&self, b: bool) -> String {
+        if b { "True".into() } else { "False".into() }
+    }
+
+    fn convert(&self, code: String, from: Ty, to: Ty) -> String {
+        match (from, to) {
+            (a, b) if a == b => code,
+            (_, Ty::Variant) | (Ty::Int, Ty::Float) => code,
+            (_, Ty::Str) => format!("str({})", code),
+            (Ty::Float, Ty::Int) => format!("round({})", code),
+            (_, Ty::Int) => format!("int({})", code),
+            (_, Ty::Float) => format!("float({})", code),
+            (_, Ty::Bool) => format!("bool({})", code),
+        }
+    }
+
+    fn owned(&self, code: String, _ty: Ty) -> String {
+        code
+    }
+
+    fn str_arg(&self, code: String) -> String {
+        code
+    }
+
+    fn concat(&self, parts: Vec<(String, Ty)>) -> String {
+        let parts: Vec<String> = parts
+            .into_iter()
+            .map(|(code, ty)| match ty {
+                Ty::Str => code,
+                _ => format!("str({})", code),
+            })
+            .collect();
+        parts.join(" + ")
+    }
+
+    fn binop(&self, op: BinOp, l: (String, Ty), r: (String, Ty)) -> Result<(String, Ty), String> {
+        let both_bool = l.1 == Ty::Bool && r.1 == Ty::Bool;
+        let number = match (l.1, r.1) {
+            (Ty::Variant, _) | (_, Ty::Variant) => Ty::Variant,
+            (Ty::Float, _) | (_, Ty::Float) => Ty::Float,
+            _ => Ty::Int,
+        };
+        let (sym, ty) = match op {
+            BinOp::Add | BinOp::Concat => ("+", number),
+            BinOp::Sub => ("-", number),
+            BinOp::Mul => ("*", number),
+            BinOp::Div => ("/", Ty::Float),
+            BinOp::IntDiv => ("//", Ty::Int),
+            BinOp::Mod => ("%", Ty::Int),
+            BinOp::Pow => ("**", Ty::Float),
+            BinOp::And if both_bool => ("and", Ty::Bool),
+            BinOp::Or if both_bool => ("or", Ty::Bool),
+            BinOp::Xor if both_bool => ("!=", Ty::Bool),
+            BinOp::And => ("&", Ty::Int),
+            BinOp::Or => ("|", Ty::Int),
+            BinOp::Xor => ("^", Ty::Int),
+            BinOp::Eqv | BinOp::Imp => return Err("Eqv/Imp operators".into()),
+            BinOp::Eq => ("==", Ty::Bool),
+            BinOp::Ne => ("!=", Ty::Bool),
+            BinOp::Lt => ("<", Ty::Bool),
+            BinOp::Le => ("<=", Ty::Bool),
+            BinOp::Gt => (">", Ty::Bool),
+            BinOp::Ge => (">=", Ty::Bool),
+        };
+        Ok((format!("{} {} {}", l.0, sym, r.0), ty))
+    }
+
+    fn not(&self, code: String, ty: Ty) -> (String, Ty) {
+        match ty {
+            Ty::Int => (format!("~{}", recv(&code)), Ty::Int),
+            _ => (format!("not {}", recv(&code)), Ty::Bool),
+        }
+    }
+
+    fn builtin(
+        &self,
+        name: &str,
+        args: Vec<(String, Ty)>,
+        helpers: &mut BTreeSet<&'static str>,
+    ) -> Option<Result<(String, Ty), String>> {
+        let s = |i: usize| self.convert(args[i].0.clone(), args[i].1, Ty::Str);
+        let n = |i: usize| self.convert(args[i].0.clone(), args[i].1, Ty::Int);
+        let f = |i: usize| self.convert(args[i].0.clone(), args[i].1, Ty::Float);
+        let arity = |range: std::ops::RangeInclusive<usize>| {
+            if range.contains(&args.len()) { Ok(()) } else { Err(format!("wrong number of arguments to {}", name)) }
+        };
+
+        let result = match name {
+            "len" => arity(1..=1).map(|_| (format!("len({})", s(0)), Ty::Int)),
+            "left" => arity(2..=2).map(|_| (format!("{}[:{}]", recv(&s(0)), n(1)), Ty::Str)),
+            "right" => arity(2..=2).map(|_| {
+                let code = match int_lit(&n(1)) {
+                    Some(k) if k > 0 => format!("{}[-{}:]", recv(&s(0)), k),
+                    _ => format!("{0}[max(len({0}) - {1}, 0):]", recv(&s(0)), n(1)),
+                };
+                (code, Ty::Str)
+            }),
+            "mid" => arity(2..=3).map(|_| {
+                let start = match int_lit(&n(1)) {
+                    Some(k) => (k - 1).to_string(),
+                    None => format!("{} - 1", n(1)),
+                };
+                let code = match args.len() {
+                    3 => match (int_lit(&start), int_lit(&n(2))) {
+                        (Some(a), Some(b)) => format!("{}[{}:{}]", recv(&s(0)), a, a + b),
+                        _ => format!("{0}[{1}:{1} + {2}]", recv(&s(0)), start, n(2)),
+                    },
+                    _ => format!("{}[{}:]", recv(&s(0)), start),
+                };
+                (code, Ty::Str)
+            }),
+            "instr" => arity(2..=3).map(|_| {
+                let code = match args.len() {
+                    3 => format!("{}.find({}, {} - 1) + 1", recv(&s(1)), s(2), n(0)),
+                    _ => format!("{}.find({}) + 1", recv(&s(0)), s(1)),
+                };
+                (code, Ty::Int)
+            }),
+            "ucase" => arity(1..=1).map(|_| (format!("{}.upper()", recv(&s(0))), Ty::Str)),
+            "lcase" => arity(1..=1).map(|_| (format!("{}.lower()", recv(&s(0))), Ty::Str)),
+            "trim" => arity(1..=1).map(|_| (format!("{}.strip()", recv(&s(0))), Ty::Str)),
+            "ltrim" => arity(1..=1).map(|_| (format!("{}.lstrip()", recv(&s(0))), Ty::Str)),
+            "rtrim" => arity(1..=1).map(|_| (format!("{}.rstrip()", recv(&s(0))), Ty::Str)),
+            "replace" => arity(3..=3).map(|_| (format!("{}.replace({}, {})", recv(&s(0)), s(1), s(2)), Ty::Str)),
+            "space" => arity(1..=1).map(|_| (format!("\" \" * {}", n(0)), Ty::Str)),
+            "chr" => arity(1..=1).map(|_| (format!("chr({})", n(0)), Ty::Str)),
+            "asc" => arity(1..=1).map(|_| (format!("ord({}[0])", recv(&s(0))), Ty::Int)),
+            "cstr" => arity(1..=1).map(|_| (s(0), Ty::Str)),
+            "cint" | "clng" => arity(1..=1).map(|_| (n(0), Ty::Int)),
+            "cdbl" | "csng" => arity(1..=1).map(|_| (f(0), Ty::Float)),
+            "cbool" => arity(1..=1).map(|_| (self.convert(args[0].0.clone(), args[0].1, Ty::Bool), Ty::Bool)),
+            "val" => arity(1..=1).map(|_| (format!("float({})", s(0)), Ty::Float)),
+            "abs" => arity(1..=1).map(|_| (format!("abs({})", args[0].0), args[0].1)),
+            "sqr" => arity(1..=1).map(|_| {
+                helpers.insert("math");
+                (format!("math.sqrt({})", args[0].0), Ty::Float)
+            }),
+            "int" => arity(1..=1).map(|_| {
+                helpers.insert("math");
+                (format!("math.floor({})", args[0].0), Ty::Int)
+            }),
+            "fix" => arity(1..=1).map(|_| {
+                helpers.insert("math");
+                (format!("math.trunc({})", args[0].0), Ty::Int)
+            }),
+            "round" => arity(1..=2).map(|_| match args.len() {
+                2 => (format!("round({}, {})", args[0].0, n(1)), Ty::Float),
+                _ => (format!("round({})", args[0].0), Ty::Int),
+            }),
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    fn range_ty(&self) -> Ty {
+        Ty::Variant
+    }
+
+    fn range_get(&self, sheet: Option<String>, addr: Addr, helpers: &mut BTreeSet<&'static str>) -> String {
+        helpers.insert("sheet");
+        cell(sheet, addr)
+    }
+
+    fn range_set(&self, sheet: Option<String>, addr: Addr, value: String, helpers: &mut BTreeSet<&'static str>) -> String {
+        helpers.insert("sheet");
+        format!("{} = {}", cell(sheet, addr), value)
+    }
+}
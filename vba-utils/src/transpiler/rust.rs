@@ -0,0 +1,486 @@
+// vba-utils/src/transpiler/rust.rs
+
+//! Rust output. Every procedure takes the caller's `Workbook` as its last
+//! parameter, so arguments that read cells are evaluated before `wb` is
+//! reborrowed. Untyped variables become `f64`.
+
+use std::collections::BTreeSet;
+
+use super::{int_lit, is_str_lit, quote, recv, Addr, Syntax, Ty};
+use crate::interpreter::BinOp;
+
+pub(crate) struct Rust;
+
+const KEYWORDS: [&str; 42] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn",
+    "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self",
+    "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while", "yield", "box", "try",
+    "wb", "result",
+];
+
+const WORKBOOK: &str = "
+/// Cell access used by the translated procedures. `sheet` is `None` for
+/// the active sheet; `address` is A1-style.
+pub trait Workbook {
+    fn get(&self, sheet: Option<&str>, address: &str) -> String;
+    fn set(&mut self, sheet: Option<&str>, address: &str, value: String);
+}
+";
+
+const HELPERS: [(&str, &str); 5] = [
+    (
+        "left",
+        "
+fn left(s: &str, n: i64) -> String {
+    s.chars().take(n.max(0) as usize).collect()
+}
+",
+    ),
+    (
+        "right",
+        "
+fn right(s: &str, n: i64) -> String {
+    let len = s.chars().count();
+    s.chars().skip(len.saturating_sub(n.max(0) as usize)).collect()
+}
+",
+    ),
+    (
+        "mid",
+        "
+fn mid(s: &str, start: i64, len: Option<i64>) -> String {
+    let rest = s.chars().skip((start - 1).max(0) as usize);
+    match len {
+        Some(n) => rest.take(n.max(0) as usize).collect(),
+        None => rest.collect(),
+    }
+}
+",
+    ),
+    (
+        "instr",
+        "
+/// 1-based position of `sub` in `s` from `start`, or 0.
+fn instr(start: i64, s: &str, sub: &str) -> i64 {
+    let skip = (start - 1).max(0) as usize;
+    let from: String = s.chars().skip(skip).collect();
+    match from.find(sub) {
+        Some(i) => (skip + from[..i].chars().count() + 1) as i64,
+        None => 0,
+    }
+}
+",
+    ),
+    (
+        "cell_address",
+        "
+/// `cell_address(2, 28)` is `\"AB2\"`.
+fn cell_address(row: i64, column: i64) -> String {
+    let mut letters = String::new();
+    let mut n = column;
+    while n > 0 {
+        letters.insert(0, (b'A' + ((n - 1) % 26) as u8) as char);
+        n = (n - 1) / 26;
+    }
+    format!(\"{}{}\", letters, row)
+}
+",
+    ),
+];
+
+fn rust_ty(ty: Ty) -> &'static str {
+    match ty {
+        Ty::Int => "i64",
+        Ty::Str => "String",
+        Ty::Bool => "bool",
+        Ty::Float | Ty::Variant => "f64",
+    }
+}
+
+fn default(ty: Ty) -> &'static str {
+    match ty {
+        Ty::Int => "0",
+        Ty::Str => "String::new()",
+        Ty::Bool => "false",
+        Ty::Float | Ty::Variant => "0.0",
+    }
+}
+
+fn is_ident(code: &str) -> bool {
+    code.chars().all(|c| c.is_alphanumeric() || c == '_') && !code.starts_with(|c: char| c.is_ascii_digit())
+}
+
+fn location(sheet: Option<String>, addr: Addr, helpers: &mut BTreeSet<&'static str>) -> (String, String) {
+    let sheet = match sheet {
+        Some(s) => format!("Some({})", s),
+        None => "None".into(),
+    };
+    let addr = match addr {
+        Addr::A1(a) => a,
+        Addr::RowCol(r, c) => {
+            helpers.insert("cell_address");
+            format!("&cell_address({}, {})", r, c)
+        }
+    };
+    (sheet, addr)
+}
+
+impl Syntax for Rust {
+    fn comment(&self, text: &str) -> String {
+        if text.is_empty() { "//".into() } else { format!("// {}", text) }
+    }
+
+    fn block_end(&self) -> Option<&'static str> {
+        Some("}")
+    }
+
+    fn empty_block(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn prelude(&self, helpers: &BTreeSet<&'static str>) -> String {
+        let mut out = String::from("// Translated from VBA. Review before use.\n");
+        out.push_str("#![allow(unused_mut, unused_variables, unused_assignments)]\n");
+        out.push_str(WORKBOOK);
+        for (name, code) in HELPERS {
+            if helpers.contains(name) {
+                out.push_str(code);
+            }
+        }
+        out
+    }
+
+    fn ident(&self, snake: &str) -> String {
+        if KEYWORDS.contains(&snake) { format!("{}_", snake) } else { snake.to_string() }
+    }
+
+    fn variant_ty(&self) -> Ty {
+        Ty::Float
+    }
+
+    fn global(&self, _name: &str, _ty: Ty) -> Result<String, String> {
+        Err("module-level variables need a struct or statics".into())
+    }
+
+    fn uses_globals(&self, _names: &[String]) -> Option<String> {
+        None
+    }
+
+    fn declare(&self, name: &str, ty: Ty) -> String {
+        format!("let mut {}: {} = {};", name, rust_ty(ty), default(ty))
+    }
+
+    fn proc_head(&self, name: &str, params: &[(String, Ty, bool)], ret: Option<Ty>) -> String {
+        let mut list = Vec::new();
+        for (p, ty, assigned) in params {
+            let mutability = if *assigned { "mut " } else { "" };
+            list.push(format!("{}{}: {}", mutability, p, rust_ty(*ty)));
+        }
+        list.push("wb: &mut dyn Workbook".to_string());
+        let ret = ret.map(|t| format!(" -> {}", rust_ty(t))).unwrap_or_default();
+        format!("pub fn {}({}){} {{", name, list.join(", "), ret)
+    }
+
+    fn final_return(&self, result: &str) -> String {
+        result.to_string()
+    }
+
+    fn exit_proc(&self, result: Option<&str>) -> String {
+        match result {
+            Some(r) => format!("return {};", r),
+            None => "return;".into(),
+        }
+    }
+
+    fn statement(&self, code: &str) -> String {
+        format!("{};", code)
+    }
+
+    fn assign(&self, name: &str, value: &str) -> String {
+        format!("{} = {};", name, value)
+    }
+
+    fn print(&self, args: Vec<String>) -> String {
+        match args.as_slice() {
+            [lit] if is_str_lit(lit) && !lit.contains(['{', '}']) => {
+                format!("println!({});", lit)
+            }
+            [fmt] if fmt.starts_with("format!(") => format!("println!({};", &fmt["format!(".len()..]),
+            _ => format!("println!(\"{}\", {});", vec!["{}"; args.len()].join(" "), args.join(", ")),
+        }
+    }
+
+    fn call(&self, name: &str, args: Vec<String>) -> String {
+        let mut list = args;
+        list.push("wb".to_string());
+        format!("{}({})", name, list.join(", "))
+    }
+
+    fn if_head(&self, cond: &str) -> String {
+        format!("if {} {{", cond)
+    }
+
+    fn elif_head(&self, cond: &str) -> String {
+        format!("}} else if {} {{", cond)
+    }
+
+    fn else_head(&self) -> String {
+        "} else {".into()
+    }
+
+    fn while_head(&self, cond: &str) -> String {
+        format!("while {} {{", cond)
+    }
+
+    fn loop_head(&self) -> String {
+        "loop {".into()
+    }
+
+    fn break_stmt(&self) -> String {
+        "break;".into()
+    }
+
+    fn break_if(&self, cond: &str) -> String {
+        format!("if {} {{ break; }}", cond)
+    }
+
+    fn for_range(&self, var: &str, start: &str, end: &str, step: i64) -> Option<String> {
+        let range = match step {
+            1 => return Some(format!("for {} in {}..={} {{", var, start, end)),
+            s if s > 0 => format!("({}..={}).step_by({})", start, end, s),
+            -1 => format!("({}..={}).rev()", end, start),
+            s => format!("({}..={}).rev().step_by({})", end, start, -s),
+        };
+        Some(format!("for {} in {} {{", var, range))
+    }
+
+    fn string_lit(&self, s: &str) -> String {
+        quote(s)
+    }
+
+    fn bool_lit(&self, b: bool) -> String {
+        b.to_string()
+    }
+
+    fn convert(&self, code: String, from: Ty, to: Ty) -> String {
+        let from = if from == Ty::Variant { Ty::Float } else { from };
+        let to = if to == Ty::Variant { Ty::Float } else { to };
+        match (from, to) {
+            (a, b) if a == b => code,
+            (Ty::Int, Ty::Float) if int_lit(&code).is_some() => format!("{}.0", code),
+            (Ty::Int, Ty::Float) => format!("{} as f64", recv(&code)),
+            (Ty::Float, Ty::Int) => format!("{}.round() as i64", recv(&code)),
+            (Ty::Str, Ty::Int) => format!("{}.trim().parse::<i64>().unwrap_or(0)", recv(&code)),
+            (Ty::Str, Ty::Float) => format!("{}.trim().parse::<f64>().unwrap_or(0.0)", recv(&code)),
+            (Ty::Str, _) => format!("{}.eq_ignore_ascii_case(\"true\")", recv(&code)),
+            (Ty::Bool, Ty::Str) => format!("if {} {{ \"True\" }} else {{ \"False\" }}.to_string()", code),
+            (Ty::Bool, Ty::Int) => format!("if {} {{ -1 }} else {{ 0 }}", code),
+            (Ty::Bool, _) => format!("if {} {{ -1.0 }} else {{ 0.0 }}", code),
+            (_, Ty::Str) => format!("{}.to_string()", recv(&code)),
+            (Ty::Int, _) => format!("{} != 0", code),
+            _ => format!("{} != 0.0", code),
+        }
+    }
+
+    fn owned(&self, code: String, ty: Ty) -> String {
+        match ty {
+            Ty::Str if is_str_lit(&code) => format!("{}.to_string()", code),
+            Ty::Str if is_ident(&code) => format!("{}.clone()", code),
+            _ => code,
+        }
+    }
+
+    fn str_arg(&self, code: String) -> String {
+        if is_str_lit(&code) { code } else { format!("&{}", code) }
+    }
+
+    fn concat(&self, parts: Vec<(String, Ty)>) -> String {
+        let mut fmt = String::new();
+        let mut args = Vec::new();
+        for (code, ty) in parts {
+            if is_str_lit(&code) {
+                // Inline literals; braces must be doubled in a format string
+                fmt.push_str(&code[1..code.len() - 1].replace('{', "{{").replace('}', "}}"));
+            } else {
+                fmt.push_str("{}");
+                args.push(if ty == Ty::Bool { self.convert(code, ty, Ty::Str) } else { code });
+            }
+        }
+        if args.is_empty() {
+            format!("\"{}\".to_string()", fmt)
+        } else {
+            format!("format!(\"{}\", {})", fmt, args.join(", "))
+        }
+    }
+
+    fn binop(&self, op: BinOp, l: (String, Ty), r: (String, Ty)) -> Result<(String, Ty), String> {
+        let is_compare = matches!(op, BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge);
+        if is_compare && (l.1 == Ty::Str) != (r.1 == Ty::Str) {
+            return Err("comparison between text and a number".into());
+        }
+        if is_compare && l.1 == Ty::Str {
+            // `String` only compares for equality with `&str`
+            let side = |code: String| {
+                if matches!(op, BinOp::Eq | BinOp::Ne) || is_str_lit(&code) { code } else { format!("{}.as_str()", recv(&code)) }
+            };
+            return Ok((format!("{} {} {}", side(l.0), compare_symbol(op), side(r.0)), Ty::Bool));
+        }
+
+        // Arithmetic on text converts it, as VBA does implicitly
+        let l = if l.1 == Ty::Str { (self.convert(l.0, Ty::Str, Ty::Float), Ty::Float) } else { l };
+        let r = if r.1 == Ty::Str { (self.convert(r.0, Ty::Str, Ty::Float), Ty::Float) } else { r };
+        let both_bool = l.1 == Ty::Bool && r.1 == Ty::Bool;
+        let number = if l.1 == Ty::Float || r.1 == Ty::Float { Ty::Float } else { Ty::Int };
+        let as_ty = |(code, ty): (String, Ty), to: Ty| self.convert(code, ty, to);
+
+        let (l, r, sym, ty) = match op {
+            BinOp::And | BinOp::Or | BinOp::Xor if both_bool => {
+                let sym = match op {
+                    BinOp::And => "&&",
+                    BinOp::Or => "||",
+                    _ => "!=",
+                };
+                (l.0, r.0, sym, Ty::Bool)
+            }
+            BinOp::And | BinOp::Or | BinOp::Xor => {
+                let sym = match op {
+                    BinOp::And => "&",
+                    BinOp::Or => "|",
+                    _ => "^",
+                };
+                (as_ty(l, Ty::Int), as_ty(r, Ty::Int), sym, Ty::Int)
+            }
+            BinOp::Eqv | BinOp::Imp => return Err("Eqv/Imp operators".into()),
+            BinOp::Pow => {
+                let base = recv(&as_ty(l, Ty::Float));
+                return Ok(match r.1 {
+                    Ty::Int if int_lit(&r.0).is_some() => (format!("{}.powi({})", base, r.0), Ty::Float),
+                    Ty::Int => (format!("{}.powi({} as i32)", base, recv(&r.0)), Ty::Float),
+                    _ => (format!("{}.powf({})", base, as_ty(r, Ty::Float)), Ty::Float),
+                });
+            }
+            BinOp::Div => (as_ty(l, Ty::Float), as_ty(r, Ty::Float), "/", Ty::Float),
+            BinOp::IntDiv => (as_ty(l, Ty::Int), as_ty(r, Ty::Int), "/", Ty::Int),
+            BinOp::Mod => (as_ty(l, Ty::Int), as_ty(r, Ty::Int), "%", Ty::Int),
+            _ => {
+                let sym = match op {
+                    BinOp::Add | BinOp::Concat => "+",
+                    BinOp::Sub => "-",
+                    BinOp::Mul => "*",
+                    other => compare_symbol(other),
+                };
+                let ty = if is_compare { Ty::Bool } else { number };
+                (as_ty(l, number), as_ty(r, number), sym, ty)
+            }
+        };
+        Ok((format!("{} {} {}", l, sym, r), ty))
+    }
+
+    fn not(&self, code: String, ty: Ty) -> (String, Ty) {
+        match ty {
+            Ty::Int => (format!("!{}", recv(&code)), Ty::Int),
+            _ => (format!("!{}", recv(&self.convert(code, ty, Ty::Bool))), Ty::Bool),
+        }
+    }
+
+    fn builtin(
+        &self,
+        name: &str,
+        args: Vec<(String, Ty)>,
+        helpers: &mut BTreeSet<&'static str>,
+    ) -> Option<Result<(String, Ty), String>> {
+        let s = |i: usize| self.convert(args[i].0.clone(), args[i].1, Ty::Str);
+        let sa = |i: usize| self.str_arg(s(i));
+        let n = |i: usize| self.convert(args[i].0.clone(), args[i].1, Ty::Int);
+        let f = |i: usize| self.convert(args[i].0.clone(), args[i].1, Ty::Float);
+        let arity = |range: std::ops::RangeInclusive<usize>| {
+            if range.contains(&args.len()) { Ok(()) } else { Err(format!("wrong number of arguments to {}", name)) }
+        };
+        let mut helper = |name: &'static str| {
+            helpers.insert(name);
+        };
+
+        let result = match name {
+            "len" => arity(1..=1).map(|_| (format!("{}.chars().count() as i64", recv(&s(0))), Ty::Int)),
+            "left" | "right" => arity(2..=2).map(|_| {
+                helper(if name == "left" { "left" } else { "right" });
+                (format!("{}({}, {})", name, sa(0), n(1)), Ty::Str)
+            }),
+            "mid" => arity(2..=3).map(|_| {
+                helper("mid");
+                let len = if args.len() == 3 { format!("Some({})", n(2)) } else { "None".into() };
+                (format!("mid({}, {}, {})", sa(0), n(1), len), Ty::Str)
+            }),
+            "instr" => arity(2..=3).map(|_| {
+                helper("instr");
+                let code = match args.len() {
+                    3 => format!("instr({}, {}, {})", n(0), sa(1), sa(2)),
+                    _ => format!("instr(1, {}, {})", sa(0), sa(1)),
+                };
+                (code, Ty::Int)
+            }),
+            "ucase" => arity(1..=1).map(|_| (format!("{}.to_uppercase()", recv(&s(0))), Ty::Str)),
+            "lcase" => arity(1..=1).map(|_| (format!("{}.to_lowercase()", recv(&s(0))), Ty::Str)),
+            "trim" => arity(1..=1).map(|_| (format!("{}.trim().to_string()", recv(&s(0))), Ty::Str)),
+            "ltrim" => arity(1..=1).map(|_| (format!("{}.trim_start().to_string()", recv(&s(0))), Ty::Str)),
+            "rtrim" => arity(1..=1).map(|_| (format!("{}.trim_end().to_string()", recv(&s(0))), Ty::Str)),
+            "replace" => arity(3..=3).map(|_| (format!("{}.replace({}, {})", recv(&s(0)), sa(1), sa(2)), Ty::Str)),
+            "space" => arity(1..=1).map(|_| (format!("\" \".repeat({} as usize)", recv(&n(0))), Ty::Str)),
+            "chr" => arity(1..=1).map(|_| (format!("char::from({} as u8).to_string()", recv(&n(0))), Ty::Str)),
+            "asc" => arity(1..=1).map(|_| (format!("{}.chars().next().map_or(0, |c| c as i64)", recv(&s(0))), Ty::Int)),
+            "cstr" => arity(1..=1).map(|_| (s(0), Ty::Str)),
+            "cint" | "clng" => arity(1..=1).map(|_| (n(0), Ty::Int)),
+            "cdbl" | "csng" | "val" => arity(1..=1).map(|_| (f(0), Ty::Float)),
+            "cbool" => arity(1..=1).map(|_| (self.convert(args[0].0.clone(), args[0].1, Ty::Bool), Ty::Bool)),
+            "abs" => arity(1..=1).map(|_| match args[0].1 {
+                Ty::Int => (format!("{}.abs()", recv(&args[0].0)), Ty::Int),
+                _ => (format!("{}.abs()", recv(&f(0))), Ty::Float),
+            }),
+            "sqr" => arity(1..=1).map(|_| (format!("{}.sqrt()", recv(&f(0))), Ty::Float)),
+            "int" => arity(1..=1).map(|_| (format!("{}.floor() as i64", recv(&f(0))), Ty::Int)),
+            "fix" => arity(1..=1).map(|_| (format!("{}.trunc() as i64", recv(&f(0))), Ty::Int)),
+            "round" => arity(1..=2).map(|_| match args.len() {
+                2 => {
+                    let scale = match int_lit(&n(1)) {
+                        Some(k) => format!("1e{}", k),
+                        None => format!("10f64.powi({} as i32)", recv(&n(1))),
+                    };
+                    (format!("({} * {1}).round() / {1}", recv(&f(0)), scale), Ty::Float)
+                }
+                _ => (format!("{}.round()", recv(&f(0))), Ty::Float),
+            }),
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    fn range_ty(&self) -> Ty {
+        Ty::Str
+    }
+
+    fn range_get(&self, sheet: Option<String>, addr: Addr, helpers: &mut BTreeSet<&'static str>) -> String {
+        let (sheet, addr) = location(sheet, addr, helpers);
+        format!("wb.get({}, {})", sheet, addr)
+    }
+
+    fn range_set(&self, sheet: Option<String>, addr: Addr, value: String, helpers: &mut BTreeSet<&'static str>) -> String {
+        let (sheet, addr) = location(sheet, addr, helpers);
+        if value.contains("wb") {
+            // `wb.set(.., f(wb))` would borrow `wb` mutably twice
+            format!("let value = {};\nwb.set({}, {}, value);", value, sheet, addr)
+        } else {
+            format!("wb.set({}, {}, {});", sheet, addr, value)
+        }
+    }
+}
+
+fn compare_symbol(op: BinOp) -> &'static str {
+    match op {
+        BinOp::Eq => "==",
+        BinOp::Ne => "!=",
+        BinOp::Lt => "<",
+        BinOp::Le => "<=",
+        BinOp::Gt => ">",
+        _ => ">=",
+    }
+}
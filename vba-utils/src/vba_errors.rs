@@ -0,0 +1,265 @@
+// vba-utils/src/vba_errors.rs
+
+//! VBA's run-time error numbers.
+//!
+//! [`VbaErr`] names the errors the interpreter, the VM and the hosts raise,
+//! so they carry the number a handler branching on `Err.Number` expects
+//! from Excel. An error's description is VBA's text for the number,
+//! followed by what went wrong when there is more to say:
+//!
+//! ```text
+//! Type mismatch
+//! Object variable or With block variable not set: Set ws = Nothing
+//! ```
+//!
+//! [`raise`] puts an error in `ctx.err`, where statements leave it for
+//! `On Error` to handle; [`VbaErr::error`] makes one for code returning
+//! `anyhow::Result` (an `ExcelError`, which keeps the number on its way up).
+
+use std::fmt;
+
+use crate::context::{Context, ErrObject};
+use crate::error::Limit;
+use crate::host::excel::{error_number, ExcelError};
+
+/// `vbObjectError`: base of the numbers classes and components raise
+/// (`Err.Raise vbObjectError + 513`)
+pub const VB_OBJECT_ERROR: i32 = -2147221504;
+
+/// `Err.Source` of errors the runtime raises, as for a workbook's project
+pub const ERROR_SOURCE: &str = "VBAProject";
+
+/// A run-time error the runtime raises
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VbaErr {
+    InvalidProcedureCall = 5,
+    Overflow = 6,
+    OutOfMemory = 7,
+    SubscriptOutOfRange = 9,
+    DivisionByZero = 11,
+    TypeMismatch = 13,
+    ResumeWithoutError = 20,
+    OutOfStackSpace = 28,
+    SubOrFunctionNotDefined = 35,
+    DllLoadError = 48,
+    FileNotFound = 53,
+    PermissionDenied = 70,
+    ObjectNotSet = 91,
+    ForLoopNotInitialized = 92,
+    InvalidUseOfNull = 94,
+    ReadOnlyProperty = 383,
+    ObjectRequired = 424,
+    CannotCreateObject = 429,
+    ObjectDoesntSupport = 438,
+    AutomationError = 440,
+    ArgumentNotOptional = 449,
+    WrongNumberOfArguments = 450,
+    /// Assigning an undeclared variable under
+    /// `ImplicitVariablePolicy::Deny`. VBA reports "Variable not defined"
+    /// when compiling and has no number for it; this is the one Err.Raise
+    /// would give (`Error(451)` keeps VBA's text for it).
+    VariableNotDefined = 451,
+    ApplicationDefined = 1004,
+}
+
+impl VbaErr {
+    /// Every variant, in number order
+    pub const ALL: [VbaErr; 24] = [
+        VbaErr::InvalidProcedureCall, VbaErr::Overflow, VbaErr::OutOfMemory, VbaErr::SubscriptOutOfRange,
+        VbaErr::DivisionByZero, VbaErr::TypeMismatch, VbaErr::ResumeWithoutError, VbaErr::OutOfStackSpace,
+        VbaErr::SubOrFunctionNotDefined, VbaErr::DllLoadError, VbaErr::FileNotFound, VbaErr::PermissionDenied,
+        VbaErr::ObjectNotSet, VbaErr::ForLoopNotInitialized, VbaErr::InvalidUseOfNull, VbaErr::ReadOnlyProperty,
+        VbaErr::ObjectRequired, VbaErr::CannotCreateObject, VbaErr::ObjectDoesntSupport, VbaErr::AutomationError,
+        VbaErr::ArgumentNotOptional, VbaErr::WrongNumberOfArguments, VbaErr::VariableNotDefined,
+        VbaErr::ApplicationDefined,
+    ];
+
+    /// `Err.Number`
+    pub fn number(self) -> i32 {
+        self as i32
+    }
+
+    pub fn from_number(number: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|err| err.number() == number)
+    }
+
+    /// VBA's text for the error
+    pub fn description(self) -> &'static str {
+        match self {
+            VbaErr::VariableNotDefined => "Variable not defined",
+            err => description(err.number()),
+        }
+    }
+
+    /// The description of this error with `detail` after it, if any
+    pub fn describe(self, detail: impl fmt::Display) -> String {
+        let detail = detail.to_string();
+        match detail.is_empty() {
+            true => self.description().to_string(),
+            false => format!("{}: {}", self.description(), detail),
+        }
+    }
+
+    /// The error as host and builtin code returns it, described as
+    /// [`VbaErr::describe`] does
+    pub fn error(self, detail: impl fmt::Display) -> anyhow::Error {
+        ExcelError::raise(self.number(), self.describe(detail))
+    }
+
+    /// The error with a message of its own instead of VBA's text, for hosts
+    /// whose messages differ (Excel's 1004 "No cells were found.")
+    pub fn message(self, message: impl Into<String>) -> anyhow::Error {
+        ExcelError::raise(self.number(), message)
+    }
+}
+
+impl fmt::Display for VbaErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.description())
+    }
+}
+
+/// Put `err` in `ctx.err`, described as [`VbaErr::describe`] does
+pub(crate) fn raise(ctx: &mut Context, err: VbaErr, detail: impl fmt::Display) {
+    ctx.err = Some(ErrObject {
+        number: err.number(),
+        description: err.describe(detail),
+        source: ERROR_SOURCE.into(),
+        limit: None,
+    });
+}
+
+/// Put a failed evaluation in `ctx.err`: with the number it carries, or as
+/// `default` when it has none. An error a limit raised stays marked as such.
+pub(crate) fn raise_from(ctx: &mut Context, e: &anyhow::Error, default: VbaErr) {
+    let (number, description) = match error_number(e) {
+        Some(number) => (number, e.to_string()),
+        None => (default.number(), default.describe(e)),
+    };
+    ctx.err = Some(ErrObject { number, description, source: ERROR_SOURCE.into(), limit: Limit::of(e) });
+}
+
+/// The message VBA shows for a run-time error number (`Error(13)`, the
+/// default `Err.Description`). Numbers offset from vbObjectError are
+/// "Automation error"; other unassigned numbers are
+/// "Application-defined or object-defined error".
+pub fn description(number: i32) -> &'static str {
+    match number {
+        0 => "",
+        3 => "Return without GoSub",
+        5 => "Invalid procedure call or argument",
+        6 => "Overflow",
+        7 => "Out of memory",
+        9 => "Subscript out of range",
+        10 => "This array is fixed or temporarily locked",
+        11 => "Division by zero",
+        13 => "Type mismatch",
+        14 => "Out of string space",
+        16 => "Expression too complex",
+        17 => "Can't perform requested operation",
+        18 => "User interrupt occurred",
+        20 => "Resume without error",
+        28 => "Out of stack space",
+        35 => "Sub or Function not defined",
+        48 => "Error in loading DLL",
+        49 => "Bad DLL calling convention",
+        51 => "Internal error",
+        52 => "Bad file name or number",
+        53 => "File not found",
+        54 => "Bad file mode",
+        55 => "File already open",
+        57 => "Device I/O error",
+        58 => "File already exists",
+        59 => "Bad record length",
+        61 => "Disk full",
+        62 => "Input past end of file",
+        63 => "Bad record number",
+        67 => "Too many files",
+        68 => "Device unavailable",
+        70 => "Permission denied",
+        71 => "Disk not ready",
+        74 => "Can't rename with different drive",
+        75 => "Path/File access error",
+        76 => "Path not found",
+        91 => "Object variable or With block variable not set",
+        92 => "For loop not initialized",
+        93 => "Invalid pattern string",
+        94 => "Invalid use of Null",
+        97 => "Can't call Friend function on object which is not an instance of defining class",
+        98 => "A property or method call cannot include a reference to a private object",
+        321 => "Invalid file format",
+        322 => "Can't create necessary temporary file",
+        325 => "Invalid format in resource file",
+        380 => "Invalid property value",
+        381 => "Invalid property array index",
+        382 => "Set not supported at runtime",
+        383 => "Set not supported (read-only property)",
+        385 => "Need property array index",
+        387 => "Set not permitted",
+        393 => "Get not supported at runtime",
+        394 => "Get not supported (write-only property)",
+        422 => "Property not found",
+        423 => "Property or method not found",
+        424 => "Object required",
+        429 => "ActiveX component can't create object",
+        430 => "Class doesn't support Automation or doesn't support expected interface",
+        432 => "File name or class name not found during Automation operation",
+        438 => "Object doesn't support this property or method",
+        440 => "Automation error",
+        442 => "Connection to type library or object library for remote process has been lost",
+        443 => "Automation object does not have a default value",
+        445 => "Object doesn't support this action",
+        446 => "Object doesn't support named arguments",
+        447 => "Object doesn't support current locale setting",
+        448 => "Named argument not found",
+        449 => "Argument not optional",
+        450 => "Wrong number of arguments or invalid property assignment",
+        451 => "Property let procedure not defined and property get procedure did not return an object",
+        452 => "Invalid ordinal",
+        453 => "Specified DLL function not found",
+        454 => "Code resource not found",
+        455 => "Code resource lock error",
+        457 => "This key is already associated with an element of this collection",
+        458 => "Variable uses an Automation type not supported in Visual Basic",
+        459 => "Object or class does not support the set of events",
+        460 => "Invalid clipboard format",
+        461 => "Method or data member not found",
+        462 => "The remote server machine does not exist or is unavailable",
+        463 => "Class not registered on local machine",
+        481 => "Invalid picture",
+        482 => "Printer error",
+        735 => "Can't save file to TEMP",
+        744 => "Search text not found",
+        746 => "Replacements too long",
+        1004 => "Application-defined or object-defined error",
+        _ if (VB_OBJECT_ERROR..=VB_OBJECT_ERROR + 0xFFFF).contains(&number) => "Automation error",
+        _ => "Application-defined or object-defined error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_errors_match_vba() {
+        assert_eq!(VbaErr::TypeMismatch.number(), 13);
+        assert_eq!(VbaErr::from_number(91), Some(VbaErr::ObjectNotSet));
+        assert_eq!(VbaErr::from_number(12), None);
+        // Every named error but 451 reads as VBA's Error(n)
+        for err in VbaErr::ALL.into_iter().filter(|err| *err != VbaErr::VariableNotDefined) {
+            assert_eq!(err.description(), description(err.number()), "{:?}", err);
+        }
+
+        let mut ctx = Context::new();
+        raise(&mut ctx, VbaErr::ObjectNotSet, "Set ws = Nothing");
+        let err = ctx.err.take().unwrap();
+        assert_eq!((err.number, err.description.as_str()), (91, "Object variable or With block variable not set: Set ws = Nothing"));
+        assert_eq!(err.source, "VBAProject");
+
+        raise_from(&mut ctx, &anyhow::anyhow!("no such variable"), VbaErr::TypeMismatch);
+        assert_eq!(ctx.err.as_ref().map(|e| e.description.as_str()), Some("Type mismatch: no such variable"));
+        raise_from(&mut ctx, &VbaErr::InvalidUseOfNull.error(""), VbaErr::TypeMismatch);
+        assert_eq!(ctx.err.as_ref().map(|e| (e.number, e.description.as_str())), Some((94, "Invalid use of Null")));
+    }
+}
@@ -0,0 +1,449 @@
+// vba-utils/src/visit.rs
+
+//! Traversal of the AST for analyses and rewrites.
+//!
+//! Implement [`Visitor`] to read a program or [`VisitorMut`] to change it,
+//! overriding only the `visit_*` methods you care about. Each default
+//! method calls the matching `walk_*` function, which visits the children;
+//! an override that still wants the children visited calls it too:
+//!
+//! ```rust,ignore
+//! struct Calls(Vec<String>);
+//!
+//! impl Visitor for Calls {
+//!     fn visit_expression(&mut self, expr: &Expression) {
+//!         if let Expression::FunctionCall { function, .. } = expr {
+//!             if let Expression::Identifier(name) = &**function {
+//!                 self.0.push(name.clone());
+//!             }
+//!         }
+//!         walk_expression(self, expr);
+//!     }
+//! }
+//! ```
+//!
+//! Children are visited in source order: parameter defaults before a
+//! procedure body, an `If` condition before its branches, a `For` range
+//! before its body. Statement lists are shared (`Body`), so the mutable
+//! walk copies a list before changing it; `visit_body` in a `VisitorMut`
+//! may also replace the whole list, to drop or splice statements.
+
+use crate::ast::{
+    AssignmentTarget, Body, DoWhileStatement, Expression, ForStatement, Parameter, Program, Statement,
+};
+
+/// Read-only traversal
+pub trait Visitor {
+    fn visit_program(&mut self, program: &Program) {
+        walk_program(self, program);
+    }
+
+    /// A procedure body or block (loop, branch, With)
+    fn visit_body(&mut self, body: &Body) {
+        walk_body(self, body);
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement) {
+        walk_statement(self, stmt);
+    }
+
+    fn visit_expression(&mut self, expr: &Expression) {
+        walk_expression(self, expr);
+    }
+}
+
+/// Visit every top-level statement
+pub fn walk_program<V: Visitor + ?Sized>(visitor: &mut V, program: &Program) {
+    for stmt in &program.statements {
+        visitor.visit_statement(stmt);
+    }
+}
+
+pub fn walk_body<V: Visitor + ?Sized>(visitor: &mut V, body: &Body) {
+    for stmt in body {
+        visitor.visit_statement(stmt);
+    }
+}
+
+fn walk_params<V: Visitor + ?Sized>(visitor: &mut V, params: &[Parameter]) {
+    for default in params.iter().filter_map(|p| p.default_value.as_ref()) {
+        visitor.visit_expression(default);
+    }
+}
+
+fn walk_target<V: Visitor + ?Sized>(visitor: &mut V, target: &AssignmentTarget) {
+    match target {
+        AssignmentTarget::PropertyAccess { object, .. } | AssignmentTarget::DefaultMember { object } => visitor.visit_expression(object),
+        AssignmentTarget::WithMethodCall { args, .. } => args.iter().for_each(|a| visitor.visit_expression(a)),
+        AssignmentTarget::Identifier(_) | AssignmentTarget::WithMemberAccess { .. } => {}
+    }
+}
+
+/// Visit the expressions and bodies directly inside `stmt`
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, stmt: &Statement) {
+    match stmt {
+        Statement::Declare { params, .. } => walk_params(visitor, params),
+        Statement::Subroutine { params, body, .. }
+        | Statement::Function { params, body, .. }
+        | Statement::PropertyGet { params, body, .. }
+        | Statement::PropertyLet { params, body, .. }
+        | Statement::PropertySet { params, body, .. } => {
+            walk_params(visitor, params);
+            visitor.visit_body(body);
+        }
+        Statement::ReDim { variables, .. } => {
+            for bound in variables.iter().flat_map(|v| &v.bounds) {
+                if let Some(lower) = &bound.lower {
+                    visitor.visit_expression(lower);
+                }
+                visitor.visit_expression(&bound.upper);
+            }
+        }
+        Statement::Set { expr, .. } | Statement::Expression(expr) => {
+            visitor.visit_expression(expr);
+        }
+        Statement::MsgBox { expr, buttons, title } => {
+            visitor.visit_expression(expr);
+            for arg in [buttons, title].into_iter().flatten() {
+                visitor.visit_expression(arg);
+            }
+        }
+        Statement::Assignment { lvalue, rvalue, .. } => {
+            walk_target(visitor, lvalue);
+            visitor.visit_expression(rvalue);
+        }
+        Statement::If { condition, then_branch, else_if, else_branch } => {
+            visitor.visit_expression(condition);
+            visitor.visit_body(then_branch);
+            for (condition, body) in else_if {
+                visitor.visit_expression(condition);
+                visitor.visit_body(body);
+            }
+            visitor.visit_body(else_branch);
+        }
+        Statement::For(ForStatement { start, end, step, body, .. }) => {
+            visitor.visit_expression(start);
+            visitor.visit_expression(end);
+            if let Some(step) = step {
+                visitor.visit_expression(step);
+            }
+            visitor.visit_body(body);
+        }
+        Statement::DoWhile(DoWhileStatement { condition, body, .. }) => {
+            if let Some(condition) = condition {
+                visitor.visit_expression(condition);
+            }
+            visitor.visit_body(body);
+        }
+        Statement::Enum { members, .. } => {
+            for value in members.iter().filter_map(|m| m.value.as_ref()) {
+                visitor.visit_expression(value);
+            }
+        }
+        Statement::Type { fields, .. } => {
+            for dim in fields.iter().filter_map(|f| f.dimensions.as_ref()).flatten() {
+                if let Some(lower) = &dim.lower {
+                    visitor.visit_expression(lower);
+                }
+                visitor.visit_expression(&dim.upper);
+            }
+        }
+        Statement::Call { args, .. } => args.iter().for_each(|a| visitor.visit_expression(a)),
+        Statement::With { object, body } => {
+            visitor.visit_expression(object);
+            visitor.visit_body(body);
+        }
+        Statement::BlankLine
+        | Statement::Comment(_)
+        | Statement::OptionExplicit
+        | Statement::OptionPrivateModule
+        | Statement::Attribute(_)
+        | Statement::DefType { .. }
+        | Statement::Dim { .. }
+        | Statement::Static { .. }
+        | Statement::GoTo { .. }
+        | Statement::Exit(_)
+        | Statement::Stop
+        | Statement::Label(_)
+        | Statement::OnError(_)
+        | Statement::Resume(_)
+        | Statement::Unparsed { .. } => {}
+    }
+}
+
+/// Visit the operands, callee, arguments or object of `expr`
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expression) {
+    match expr {
+        Expression::BinaryOp { left, right, .. } => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(right);
+        }
+        Expression::UnaryOp { expr, .. } | Expression::Parenthesized(expr) => visitor.visit_expression(expr),
+        Expression::FunctionCall { function, args } => {
+            visitor.visit_expression(function);
+            args.iter().for_each(|a| visitor.visit_expression(a));
+        }
+        Expression::PropertyAccess { obj, .. } => visitor.visit_expression(obj),
+        Expression::WithMethodCall { args, .. } => args.iter().for_each(|a| visitor.visit_expression(a)),
+        Expression::Integer(_)
+        | Expression::Byte(_)
+        | Expression::Single(_)
+        | Expression::String(_)
+        | Expression::Identifier(_)
+        | Expression::Boolean(_)
+        | Expression::Currency(_)
+        | Expression::Date(_)
+        | Expression::Double(_)
+        | Expression::Decimal(_)
+        | Expression::Long(_)
+        | Expression::LongLong(_)
+        | Expression::WithMemberAccess { .. }
+        | Expression::BuiltInConstant(_) => {}
+    }
+}
+
+/// Traversal that may change what it visits
+pub trait VisitorMut {
+    fn visit_program(&mut self, program: &mut Program) {
+        walk_program_mut(self, program);
+    }
+
+    /// A procedure body or block; replace `*body` to drop or add statements
+    fn visit_body(&mut self, body: &mut Body) {
+        walk_body_mut(self, body);
+    }
+
+    fn visit_statement(&mut self, stmt: &mut Statement) {
+        walk_statement_mut(self, stmt);
+    }
+
+    fn visit_expression(&mut self, expr: &mut Expression) {
+        walk_expression_mut(self, expr);
+    }
+}
+
+pub fn walk_program_mut<V: VisitorMut + ?Sized>(visitor: &mut V, program: &mut Program) {
+    for stmt in &mut program.statements {
+        visitor.visit_statement(stmt);
+    }
+}
+
+/// Visit a copy of the statements, keeping their source lines
+pub fn walk_body_mut<V: VisitorMut + ?Sized>(visitor: &mut V, body: &mut Body) {
+    let mut stmts = body.to_vec();
+    for stmt in &mut stmts {
+        visitor.visit_statement(stmt);
+    }
+    *body = Body::new(stmts, body.lines().to_vec());
+}
+
+fn walk_params_mut<V: VisitorMut + ?Sized>(visitor: &mut V, params: &mut [Parameter]) {
+    for default in params.iter_mut().filter_map(|p| p.default_value.as_mut()) {
+        visitor.visit_expression(default);
+    }
+}
+
+fn walk_target_mut<V: VisitorMut + ?Sized>(visitor: &mut V, target: &mut AssignmentTarget) {
+    match target {
+        AssignmentTarget::PropertyAccess { object, .. } | AssignmentTarget::DefaultMember { object } => visitor.visit_expression(object),
+        AssignmentTarget::WithMethodCall { args, .. } => args.iter_mut().for_each(|a| visitor.visit_expression(a)),
+        AssignmentTarget::Identifier(_) | AssignmentTarget::WithMemberAccess { .. } => {}
+    }
+}
+
+/// Like [`walk_statement`]; compiled caches of visited expressions are
+/// cleared so they are recompiled from the new tree
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, stmt: &mut Statement) {
+    match stmt {
+        Statement::Declare { params, .. } => walk_params_mut(visitor, params),
+        Statement::Subroutine { params, body, .. }
+        | Statement::Function { params, body, .. }
+        | Statement::PropertyGet { params, body, .. }
+        | Statement::PropertyLet { params, body, .. }
+        | Statement::PropertySet { params, body, .. } => {
+            walk_params_mut(visitor, params);
+            visitor.visit_body(body);
+        }
+        Statement::ReDim { variables, .. } => {
+            for bound in variables.iter_mut().flat_map(|v| &mut v.bounds) {
+                if let Some(lower) = &mut bound.lower {
+                    visitor.visit_expression(lower);
+                }
+                visitor.visit_expression(&mut bound.upper);
+            }
+        }
+        Statement::Set { expr, .. } | Statement::Expression(expr) => {
+            visitor.visit_expression(expr);
+        }
+        Statement::MsgBox { expr, buttons, title } => {
+            visitor.visit_expression(expr);
+            for arg in [buttons, title].into_iter().flatten() {
+                visitor.visit_expression(arg);
+            }
+        }
+        Statement::Assignment { lvalue, rvalue, rvalue_compiled } => {
+            walk_target_mut(visitor, lvalue);
+            visitor.visit_expression(rvalue);
+            *rvalue_compiled = Default::default();
+        }
+        Statement::If { condition, then_branch, else_if, else_branch } => {
+            visitor.visit_expression(condition);
+            visitor.visit_body(then_branch);
+            for (condition, body) in else_if {
+                visitor.visit_expression(condition);
+                visitor.visit_body(body);
+            }
+            visitor.visit_body(else_branch);
+        }
+        Statement::For(ForStatement { start, end, step, body, .. }) => {
+            visitor.visit_expression(start);
+            visitor.visit_expression(end);
+            if let Some(step) = step {
+                visitor.visit_expression(step);
+            }
+            visitor.visit_body(body);
+        }
+        Statement::DoWhile(DoWhileStatement { condition, body, condition_compiled, .. }) => {
+            if let Some(condition) = condition {
+                visitor.visit_expression(condition);
+            }
+            *condition_compiled = Default::default();
+            visitor.visit_body(body);
+        }
+        Statement::Enum { members, .. } => {
+            for value in members.iter_mut().filter_map(|m| m.value.as_mut()) {
+                visitor.visit_expression(value);
+            }
+        }
+        Statement::Type { fields, .. } => {
+            for dim in fields.iter_mut().filter_map(|f| f.dimensions.as_mut()).flatten() {
+                if let Some(lower) = &mut dim.lower {
+                    visitor.visit_expression(lower);
+                }
+                visitor.visit_expression(&mut dim.upper);
+            }
+        }
+        Statement::Call { args, .. } => args.iter_mut().for_each(|a| visitor.visit_expression(a)),
+        Statement::With { object, body } => {
+            visitor.visit_expression(object);
+            visitor.visit_body(body);
+        }
+        Statement::BlankLine
+        | Statement::Comment(_)
+        | Statement::OptionExplicit
+        | Statement::OptionPrivateModule
+        | Statement::Attribute(_)
+        | Statement::DefType { .. }
+        | Statement::Dim { .. }
+        | Statement::Static { .. }
+        | Statement::GoTo { .. }
+        | Statement::Exit(_)
+        | Statement::Stop
+        | Statement::Label(_)
+        | Statement::OnError(_)
+        | Statement::Resume(_)
+        | Statement::Unparsed { .. } => {}
+    }
+}
+
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut Expression) {
+    match expr {
+        Expression::BinaryOp { left, right, .. } => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(right);
+        }
+        Expression::UnaryOp { expr, .. } | Expression::Parenthesized(expr) => visitor.visit_expression(expr),
+        Expression::FunctionCall { function, args } => {
+            visitor.visit_expression(function);
+            args.iter_mut().for_each(|a| visitor.visit_expression(a));
+        }
+        Expression::PropertyAccess { obj, .. } => visitor.visit_expression(obj),
+        Expression::WithMethodCall { args, .. } => args.iter_mut().for_each(|a| visitor.visit_expression(a)),
+        Expression::Integer(_)
+        | Expression::Byte(_)
+        | Expression::Single(_)
+        | Expression::String(_)
+        | Expression::Identifier(_)
+        | Expression::Boolean(_)
+        | Expression::Currency(_)
+        | Expression::Date(_)
+        | Expression::Double(_)
+        | Expression::Decimal(_)
+        | Expression::Long(_)
+        | Expression::LongLong(_)
+        | Expression::WithMemberAccess { .. }
+        | Expression::BuiltInConstant(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ident(name: &str) -> Expression {
+        Expression::Identifier(name.into())
+    }
+
+    fn program() -> Program {
+        let call = Expression::FunctionCall { function: Box::new(ident("Chr")), args: vec![Expression::Integer(65)] };
+        let inner = Statement::If {
+            condition: Expression::BinaryOp { left: Box::new(ident("x")), op: ">".into(), right: Box::new(Expression::Integer(1)) },
+            then_branch: vec![Statement::MsgBox { expr: call, buttons: None, title: None }].into(),
+            else_if: vec![],
+            else_branch: vec![Statement::Call { function: "Helper".into(), args: vec![ident("y")] }].into(),
+        };
+        let main = Statement::Subroutine { visibility: None, name: "Main".into(), params: vec![], body: Body::new(vec![inner], vec![3]) };
+        Program { statements: vec![main], diagnostics: vec![] }
+    }
+
+    #[test]
+    fn test_visitor_reaches_nested_expressions() {
+        struct Names(Vec<String>);
+        impl Visitor for Names {
+            fn visit_expression(&mut self, expr: &Expression) {
+                if let Expression::Identifier(name) = expr {
+                    self.0.push(name.clone());
+                }
+                walk_expression(self, expr);
+            }
+        }
+        let mut names = Names(Vec::new());
+        names.visit_program(&program());
+        assert_eq!(names.0, vec!["x", "Chr", "y"]);
+    }
+
+    #[test]
+    fn test_visitor_mut_rewrites_in_place() {
+        // Rename x to total, and drop every Call statement
+        struct Rewrite;
+        impl VisitorMut for Rewrite {
+            fn visit_body(&mut self, body: &mut Body) {
+                walk_body_mut(self, body);
+                let (stmts, lines): (Vec<_>, Vec<_>) = body
+                    .iter()
+                    .cloned()
+                    .zip(body.lines().iter().copied())
+                    .filter(|(s, _)| !matches!(s, Statement::Call { .. }))
+                    .unzip();
+                *body = Body::new(stmts, lines);
+            }
+            fn visit_expression(&mut self, expr: &mut Expression) {
+                if let Expression::Identifier(name) = expr {
+                    if name == "x" {
+                        *name = "total".into();
+                    }
+                }
+                walk_expression_mut(self, expr);
+            }
+        }
+        let mut program = program();
+        Rewrite.visit_program(&mut program);
+
+        let Statement::Subroutine { body, .. } = &program.statements[0] else { panic!() };
+        assert_eq!(body.line(0), Some(3));
+        let Statement::If { condition, else_branch, .. } = &body[0] else { panic!() };
+        assert!(else_branch.is_empty());
+        let Expression::BinaryOp { left, .. } = condition else { panic!() };
+        assert!(matches!(&**left, Expression::Identifier(name) if name == "total"));
+    }
+}
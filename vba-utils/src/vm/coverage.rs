@@ -0,0 +1,297 @@
+// vba-utils/src/vm/coverage.rs
+
+//! Statement and branch coverage.
+//!
+//! With `ctx.start_coverage()` the VM counts how often each source line
+//! runs and which arm of every `If` is taken. `ctx.take_coverage()` returns
+//! the raw [`CoverageHits`]; combine them with the parsed [`Program`] in a
+//! [`CoverageReport`] to also see the lines and arms that never ran, then
+//! write it out as LCOV ([`CoverageReport::to_lcov`]) or as a standalone
+//! HTML page ([`CoverageReport::to_html`]).
+//!
+//! Arms of an `If` are numbered in source order: `Then` is 0, each `ElseIf`
+//! follows, and the last arm is `Else` (or falling through when there is
+//! none). Branches are keyed by the line of the `If`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use crate::ast::{Body, Program, Statement};
+
+/// Raw counters collected while the VM runs.
+#[derive(Debug, Clone, Default)]
+pub struct CoverageHits {
+    /// line → times a statement on that line started
+    pub lines: BTreeMap<usize, u64>,
+    /// (line of the If, arm) → times that arm was taken
+    pub branches: BTreeMap<(usize, usize), u64>,
+    current: Option<usize>,
+}
+
+impl CoverageHits {
+    /// Count a statement about to run on `line` (ignored when unknown).
+    pub fn hit(&mut self, line: Option<usize>) {
+        self.current = line;
+        if let Some(line) = line {
+            *self.lines.entry(line).or_default() += 1;
+        }
+    }
+
+    /// Line of the statement most recently passed to [`CoverageHits::hit`].
+    pub fn current_line(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// Count `arm` of the `If` on `line` as taken.
+    pub fn branch(&mut self, line: usize, arm: usize) {
+        *self.branches.entry((line, arm)).or_default() += 1;
+    }
+
+    /// Add the counts of another run.
+    pub fn merge(&mut self, other: &CoverageHits) {
+        for (line, n) in &other.lines {
+            *self.lines.entry(*line).or_default() += n;
+        }
+        for (key, n) in &other.branches {
+            *self.branches.entry(*key).or_default() += n;
+        }
+    }
+}
+
+/// One arm of an `If`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BranchCoverage {
+    pub line: usize,
+    pub arm: usize,
+    pub hits: u64,
+}
+
+/// Hits for every executable line and `If` arm of a program.
+#[derive(Debug, Clone, Default)]
+pub struct CoverageReport {
+    /// Every executable line → hits (0 when it never ran)
+    pub lines: BTreeMap<usize, u64>,
+    pub branches: Vec<BranchCoverage>,
+}
+
+impl CoverageReport {
+    pub fn new(program: &Program, hits: &CoverageHits) -> Self {
+        let mut report = CoverageReport::default();
+        for stmt in &program.statements {
+            for body in stmt.child_bodies() {
+                report.add_body(body, hits);
+            }
+        }
+        report
+    }
+
+    fn add_body(&mut self, body: &Body, hits: &CoverageHits) {
+        for (pc, stmt) in body.iter().enumerate() {
+            let line = body.line(pc);
+            if let Some(line) = line.filter(|_| is_executable(stmt)) {
+                self.lines.insert(line, hits.lines.get(&line).copied().unwrap_or(0));
+            }
+            if let (Some(line), Statement::If { else_if, .. }) = (line, stmt) {
+                for arm in 0..else_if.len() + 2 {
+                    let hits = hits.branches.get(&(line, arm)).copied().unwrap_or(0);
+                    self.branches.push(BranchCoverage { line, arm, hits });
+                }
+            }
+            for child in stmt.child_bodies() {
+                self.add_body(child, hits);
+            }
+        }
+    }
+
+    pub fn lines_found(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn lines_hit(&self) -> usize {
+        self.lines.values().filter(|&&n| n > 0).count()
+    }
+
+    pub fn branches_found(&self) -> usize {
+        self.branches.len()
+    }
+
+    pub fn branches_hit(&self) -> usize {
+        self.branches.iter().filter(|b| b.hits > 0).count()
+    }
+
+    /// Lines that are executable but never ran.
+    pub fn missed_lines(&self) -> Vec<usize> {
+        self.lines.iter().filter(|(_, n)| **n == 0).map(|(l, _)| *l).collect()
+    }
+
+    /// LCOV tracefile for a single source file named `source_file`.
+    pub fn to_lcov(&self, source_file: &str) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "TN:");
+        let _ = writeln!(out, "SF:{}", source_file);
+        for b in &self.branches {
+            let taken = if b.hits > 0 { b.hits.to_string() } else { "-".into() };
+            let _ = writeln!(out, "BRDA:{},0,{},{}", b.line, b.arm, taken);
+        }
+        let _ = writeln!(out, "BRF:{}", self.branches_found());
+        let _ = writeln!(out, "BRH:{}", self.branches_hit());
+        for (line, hits) in &self.lines {
+            let _ = writeln!(out, "DA:{},{}", line, hits);
+        }
+        let _ = writeln!(out, "LF:{}", self.lines_found());
+        let _ = writeln!(out, "LH:{}", self.lines_hit());
+        out.push_str("end_of_record\n");
+        out
+    }
+
+    /// Standalone HTML page showing `source` with hit counts per line.
+    pub fn to_html(&self, title: &str, source: &str) -> String {
+        let mut out = String::new();
+        let _ = write!(
+            out,
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title>\n<style>\n\
+             body {{ font-family: sans-serif; }}\n\
+             table {{ border-collapse: collapse; font-family: monospace; }}\n\
+             td {{ padding: 0 8px; white-space: pre; }}\n\
+             .num {{ color: #888; text-align: right; }}\n\
+             .hit {{ background: #dfd; }}\n\
+             .miss {{ background: #fdd; }}\n\
+             .partial {{ background: #ffd; }}\n\
+             </style></head><body>\n",
+            escape_html(title)
+        );
+        let _ = writeln!(
+            out,
+            "<h1>{}</h1>\n<p>Lines: {}/{} &middot; Branches: {}/{}</p>\n<table>",
+            escape_html(title),
+            self.lines_hit(),
+            self.lines_found(),
+            self.branches_hit(),
+            self.branches_found()
+        );
+        for (i, text) in source.lines().enumerate() {
+            let line = i + 1;
+            let hits = self.lines.get(&line);
+            let arms: Vec<&BranchCoverage> = self.branches.iter().filter(|b| b.line == line).collect();
+            let class = match hits {
+                None => "",
+                Some(0) => "miss",
+                Some(_) if arms.iter().any(|b| b.hits == 0) => "partial",
+                Some(_) => "hit",
+            };
+            let count = hits.map(|n| n.to_string()).unwrap_or_default();
+            let branch_note = if arms.is_empty() {
+                String::new()
+            } else {
+                let taken = arms.iter().filter(|b| b.hits > 0).count();
+                format!("{}/{} arms", taken, arms.len())
+            };
+            let _ = writeln!(
+                out,
+                "<tr class=\"{}\"><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td>{}</td></tr>",
+                class,
+                line,
+                count,
+                branch_note,
+                escape_html(text)
+            );
+        }
+        out.push_str("</table>\n</body></html>\n");
+        out
+    }
+}
+
+/// Statements that do something when run; declarations, labels and
+/// comments are not counted.
+fn is_executable(stmt: &Statement) -> bool {
+    !matches!(
+        stmt,
+        Statement::BlankLine
+            | Statement::Comment(_)
+            | Statement::OptionExplicit
+            | Statement::OptionPrivateModule
+            | Statement::Attribute(_)
+            | Statement::DefType { .. }
+            | Statement::Declare { .. }
+            | Statement::Label(_)
+            | Statement::Enum { .. }
+            | Statement::Type { .. }
+            | Statement::Unparsed { .. }
+            | Statement::Subroutine { .. }
+            | Statement::Function { .. }
+            | Statement::PropertyGet { .. }
+            | Statement::PropertyLet { .. }
+            | Statement::PropertySet { .. }
+    )
+}
+
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Expression;
+
+    fn program() -> Program {
+        let stmt = |n: i64| Statement::Expression(Expression::Integer(n));
+        let if_stmt = Statement::If {
+            condition: Expression::Boolean(true),
+            then_branch: Body::new(vec![stmt(1)], vec![3]),
+            else_if: vec![],
+            else_branch: Body::new(vec![stmt(2)], vec![5]),
+        };
+        let body = Body::new(
+            vec![Statement::Comment("x".into()), if_stmt, stmt(3)],
+            vec![1, 2, 7],
+        );
+        let sub = Statement::Subroutine { visibility: None, name: "AutoOpen".into(), params: vec![], body };
+        Program { statements: vec![sub], diagnostics: vec![] }
+    }
+
+    fn hits() -> CoverageHits {
+        let mut hits = CoverageHits::default();
+        hits.hit(Some(2));
+        hits.branch(2, 0);
+        hits.hit(Some(3));
+        hits.hit(Some(7));
+        hits
+    }
+
+    #[test]
+    fn test_report_counts_missed_lines_and_arms() {
+        let report = CoverageReport::new(&program(), &hits());
+        assert_eq!(report.lines_found(), 4);
+        assert_eq!(report.lines_hit(), 3);
+        assert_eq!(report.missed_lines(), vec![5]);
+        assert_eq!(report.branches_found(), 2);
+        assert_eq!(report.branches_hit(), 1);
+    }
+
+    #[test]
+    fn test_lcov_and_html_output() {
+        let report = CoverageReport::new(&program(), &hits());
+        let lcov = report.to_lcov("macro.bas");
+        assert!(lcov.starts_with("TN:\nSF:macro.bas\n"));
+        assert!(lcov.contains("BRDA:2,0,0,1\nBRDA:2,0,1,-\n"));
+        assert!(lcov.contains("DA:5,0\n"));
+        assert!(lcov.contains("LF:4\nLH:3\n"));
+        assert!(lcov.ends_with("end_of_record\n"));
+
+        let html = report.to_html("macro.bas", "' x\nIf a < b Then\n");
+        assert!(html.contains("If a &lt; b Then"));
+        assert!(html.contains("<tr class=\"partial\">"));
+        assert!(html.contains("Lines: 3/4"));
+    }
+}
@@ -1,4 +1,7 @@
-use crate::ast::{Statement, DoWhileStatement};
+use std::collections::HashMap;
+
+use crate::ast::{Body, Statement, DoWhileStatement};
+use crate::symbol::Symbol;
 
 /// A single execution frame (analogous to a call stack frame in a real VM).
 #[derive(Debug, Clone)]
@@ -7,8 +10,9 @@ pub struct Frame {
     pub kind: FrameKind,                // What type of frame is this?
     pub list_id: usize,                 // Statement list ID (for resume tracking)
     pub pc: usize,                      // Program counter within the list
-    pub statements: Vec<Statement>,     // The statements in this frame
+    pub statements: Body,               // The statements in this frame (shared, never copied)
     pub depth: usize,                   // Nesting depth
+    labels: HashMap<Symbol, usize>,     // Label name → pc, built once per frame
 }
 
 /// Different types of frames (each has different semantics for control flow).
@@ -16,7 +20,7 @@ pub struct Frame {
 pub enum FrameKind {
     Main,                               // Top-level sub body
     For {
-        counter: String,
+        counter: Symbol,
         current_value: i64,
         end_value: i64,
         step: i64,
@@ -35,7 +39,7 @@ impl Frame {
         id: usize,
         kind: FrameKind,
         list_id: usize,
-        statements: Vec<Statement>,
+        statements: Body,
         depth: usize,
     ) -> Self {
         Frame {
@@ -43,6 +47,7 @@ impl Frame {
             kind,
             list_id,
             pc: 0,
+            labels: index_labels(&statements),
             statements,
             depth,
         }
@@ -67,4 +72,34 @@ impl Frame {
     pub fn current_statement(&self) -> Option<&Statement> {
         self.statements.get(self.pc)
     }
+
+    /// Source line of the current statement, if known.
+    pub fn current_line(&self) -> Option<usize> {
+        self.statements.line(self.pc)
+    }
+
+    /// PC of `label` in this frame's statement list (case-insensitive).
+    pub fn find_label(&self, label: &str) -> Option<usize> {
+        self.labels.get(&Symbol::intern(label)).copied()
+    }
+
+    /// Run `statements` from the start in place of this frame's own (a
+    /// tail call reusing its caller's frame).
+    pub fn reuse(&mut self, statements: Body) {
+        self.labels = index_labels(&statements);
+        self.statements = statements;
+        self.pc = 0;
+    }
+}
+
+/// Map each `Label:` in `stmts` to its index. The first definition wins;
+/// duplicates are reported as diagnostics when the AST is built.
+pub(crate) fn index_labels(stmts: &[Statement]) -> HashMap<Symbol, usize> {
+    let mut labels = HashMap::new();
+    for (idx, stmt) in stmts.iter().enumerate() {
+        if let Statement::Label(name) = stmt {
+            labels.entry(Symbol::intern(name)).or_insert(idx);
+        }
+    }
+    labels
 }
@@ -0,0 +1,754 @@
+// vba-utils/src/vm/hibernate.rs
+
+//! Hibernating a paused run so another process can resume it.
+//!
+//! After [`Context::request_hibernation`] (called by `Application.Wait` when
+//! `RuntimeConfig::hibernate_on_wait` is set, or by a host object waiting
+//! for input) the VM stops before its next statement and leaves a
+//! [`Hibernation`] in the context: the frame stack with pcs and loop state,
+//! ByRef bookkeeping, local scopes, module variables, the With stack and
+//! error-handling state. Statement lists are not copied; each frame names
+//! its procedure and the path to its body, so an image can only be resumed
+//! against the same source, with [`ProgramExecutor::resume`].
+//!
+//! Limits:
+//! - Only the VM running the entry procedure is saved. A request made inside
+//!   a Function called from an expression takes effect once it returns.
+//! - No hibernation while an error handler runs (before `Resume`).
+//! - Host state (cells, documents) is not included; it belongs to the
+//!   Excel backend.
+//! - User-defined type values cannot be saved.
+//! - `Static` locals are not saved: the next call of their procedure
+//!   starts them over.
+//!
+//! File format (one record per line, fields separated by tabs):
+//!
+//! ```text
+//! vba-hibernate 1
+//! entry   <procedure>
+//! next    <next frame id>
+//! frame   <id> <list id> <pc> <procedure> <path> <kind> [<counter> <current> <end> <step> | <first iteration>]
+//! byref   <frame id> <param> <caller variable>
+//! scope   <kind> <name>
+//! local   <name> <type> <value>
+//! global  <name> <value>
+//! with    <value>
+//! onerror <mode> <label>
+//! err     <number> <source> <description>
+//! ```
+//!
+//! A path is `-` for the procedure body itself, else `stmt.arm` steps
+//! joined by `/`, where `arm` indexes `Statement::child_bodies`. Values
+//! are `tag:text`, e.g. `int:5` or `str:abc`; text escapes `\`, tabs and
+//! line breaks.
+//!
+//! [`ProgramExecutor::resume`]: crate::vm::ProgramExecutor::resume
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::str::FromStr;
+
+use crate::ast::{Body, Statement};
+use crate::context::{Context, DeclaredType, ErrObject, OnErrorMode, ScopeKind, Value};
+use crate::symbol::Symbol;
+use super::frame::{Frame, FrameKind};
+use super::runtime::VbaVm;
+
+const HEADER: &str = "vba-hibernate 1";
+
+/// `(statement index, child body index)` steps from a procedure body
+type BodyPath = Vec<(usize, usize)>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FrameKindImage {
+    Main,
+    Block,
+    If,
+    With,
+    For { counter: String, current: i64, end: i64, step: i64 },
+    Do { first_iteration: bool },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FrameImage {
+    id: usize,
+    list_id: usize,
+    pc: usize,
+    procedure: String,
+    path: BodyPath,
+    kind: FrameKindImage,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LocalImage {
+    name: String,
+    ty: Option<DeclaredType>,
+    value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ScopeImage {
+    kind: ScopeKind,
+    name: String,
+    locals: Vec<LocalImage>,
+}
+
+/// A run stopped at a statement boundary; see the module docs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hibernation {
+    /// Procedure the run started in
+    pub entrypoint: String,
+    next_frame_id: usize,
+    frames: Vec<FrameImage>,
+    byref: Vec<(usize, String, String)>,
+    scopes: Vec<ScopeImage>,
+    globals: Vec<(String, String)>,
+    with_stack: Vec<String>,
+    on_error_mode: OnErrorMode,
+    on_error_label: Option<String>,
+    /// (number, source, description)
+    err: Option<(i32, String, String)>,
+}
+
+impl Hibernation {
+    /// Image of `vm` and the macro state in `ctx`.
+    pub(crate) fn capture(vm: &VbaVm, ctx: &Context) -> Result<Hibernation, String> {
+        let mut frames = Vec::new();
+        for frame in vm.frames() {
+            let (procedure, path) = locate(ctx, &frame.statements)
+                .ok_or_else(|| format!("frame #{} is not part of a procedure", frame.id))?;
+            let kind = match &frame.kind {
+                FrameKind::Main => FrameKindImage::Main,
+                FrameKind::Block => FrameKindImage::Block,
+                FrameKind::If => FrameKindImage::If,
+                FrameKind::With => FrameKindImage::With,
+                FrameKind::For { counter, current_value, end_value, step } => FrameKindImage::For {
+                    counter: counter.as_str().to_string(),
+                    current: *current_value,
+                    end: *end_value,
+                    step: *step,
+                },
+                FrameKind::Do { first_iteration, .. } => FrameKindImage::Do { first_iteration: *first_iteration },
+            };
+            frames.push(FrameImage { id: frame.id, list_id: frame.list_id, pc: frame.pc, procedure, path, kind });
+        }
+        let entrypoint = frames.first().map(|f| f.procedure.clone()).ok_or("the VM has no frames")?;
+
+        let mut byref: Vec<(usize, String, String)> = vm
+            .byref_bindings()
+            .iter()
+            .flat_map(|(id, pairs)| pairs.iter().map(move |(p, v)| (*id, p.clone(), v.clone())))
+            .collect();
+        byref.sort();
+
+        let mut scopes = Vec::new();
+        for scope in ctx.scope_frames() {
+            let mut locals = Vec::new();
+            for (name, value) in scope.iter() {
+                locals.push(LocalImage {
+                    name: name.as_str().to_string(),
+                    ty: scope.get_type(name),
+                    value: encode_value(value)?,
+                });
+            }
+            scopes.push(ScopeImage { kind: scope.kind, name: scope.name.clone().unwrap_or_default(), locals });
+        }
+
+        let mut globals = Vec::new();
+        for (name, value) in &ctx.variables {
+            globals.push((name.as_str().to_string(), encode_value(value)?));
+        }
+        globals.sort();
+
+        Ok(Hibernation {
+            entrypoint,
+            next_frame_id: vm.next_frame_id(),
+            frames,
+            byref,
+            scopes,
+            globals,
+            with_stack: ctx.with_stack.iter().map(encode_value).collect::<Result<_, _>>()?,
+            on_error_mode: ctx.on_error_mode,
+            on_error_label: ctx.on_error_label.clone(),
+            err: ctx.err.as_ref().map(|e| (e.number, e.source.clone(), e.description.clone())),
+        })
+    }
+
+    /// Put the saved macro state into `ctx`, which already has the program
+    /// loaded, and rebuild the VM.
+    pub(crate) fn restore(&self, ctx: &mut Context) -> Result<VbaVm, String> {
+        let mut frames = Vec::new();
+        for (depth, image) in self.frames.iter().enumerate() {
+            let (body, owner) = resolve(ctx, &image.procedure, &image.path).ok_or_else(|| {
+                format!("{} has no statement list at {}", image.procedure, path_text(&image.path))
+            })?;
+            let kind = match (&image.kind, owner) {
+                (FrameKindImage::Main, _) => FrameKind::Main,
+                (FrameKindImage::Block, _) => FrameKind::Block,
+                (FrameKindImage::If, _) => FrameKind::If,
+                (FrameKindImage::With, _) => FrameKind::With,
+                (FrameKindImage::For { counter, current, end, step }, _) => FrameKind::For {
+                    counter: Symbol::intern(counter),
+                    current_value: *current,
+                    end_value: *end,
+                    step: *step,
+                },
+                (FrameKindImage::Do { first_iteration }, Some(Statement::DoWhile(statement))) => FrameKind::Do {
+                    statement: statement.clone(),
+                    first_iteration: *first_iteration,
+                },
+                (FrameKindImage::Do { .. }, _) => return Err(format!("frame #{} is not in a Do loop", image.id)),
+            };
+            let mut frame = Frame::new(image.id, kind, image.list_id, body, depth);
+            frame.jump_to(image.pc);
+            frames.push(frame);
+        }
+
+        let mut bindings: HashMap<usize, Vec<(String, String)>> = HashMap::new();
+        for (id, param, var) in &self.byref {
+            bindings.entry(*id).or_default().push((param.clone(), var.clone()));
+        }
+
+        for (name, value) in &self.globals {
+            ctx.variables.insert(Symbol::intern(name), decode_value(value)?);
+        }
+        for scope in &self.scopes {
+            ctx.push_scope(scope.name.clone(), scope.kind);
+            for local in &scope.locals {
+                ctx.declare_variable(&local.name);
+                ctx.declare_local(local.name.as_str(), decode_value(&local.value)?);
+                if let Some(ty) = local.ty {
+                    ctx.set_var_type(local.name.as_str(), ty);
+                }
+            }
+        }
+        ctx.with_stack = self.with_stack.iter().map(|v| decode_value(v)).collect::<Result<_, _>>()?;
+        ctx.on_error_mode = self.on_error_mode;
+        ctx.on_error_label = self.on_error_label.clone();
+        ctx.err = self.err.as_ref().map(|(number, source, description)| ErrObject {
+            number: *number,
+            description: description.clone(),
+            source: source.clone(),
+            limit: None,
+        });
+
+        Ok(VbaVm::from_frames(frames, self.next_frame_id, bindings))
+    }
+
+    /// Serialize in the hibernation file format.
+    pub fn to_text(&self) -> String {
+        let mut out = String::from(HEADER);
+        let _ = write!(out, "\nentry\t{}\nnext\t{}\n", escape(&self.entrypoint), self.next_frame_id);
+        for f in &self.frames {
+            let _ = write!(out, "frame\t{}\t{}\t{}\t{}\t{}\t", f.id, f.list_id, f.pc, escape(&f.procedure), path_text(&f.path));
+            let _ = match &f.kind {
+                FrameKindImage::Main => write!(out, "main"),
+                FrameKindImage::Block => write!(out, "block"),
+                FrameKindImage::If => write!(out, "if"),
+                FrameKindImage::With => write!(out, "with"),
+                FrameKindImage::For { counter, current, end, step } => {
+                    write!(out, "for\t{}\t{}\t{}\t{}", escape(counter), current, end, step)
+                }
+                FrameKindImage::Do { first_iteration } => write!(out, "do\t{}", first_iteration),
+            };
+            out.push('\n');
+        }
+        for (id, param, var) in &self.byref {
+            let _ = writeln!(out, "byref\t{}\t{}\t{}", id, escape(param), escape(var));
+        }
+        for scope in &self.scopes {
+            let _ = writeln!(out, "scope\t{}\t{}", scope_kind_name(scope.kind), escape(&scope.name));
+            for local in &scope.locals {
+                let ty = local.ty.map_or_else(|| "-".to_string(), |t| format!("{:?}", t));
+                let _ = writeln!(out, "local\t{}\t{}\t{}", escape(&local.name), ty, local.value);
+            }
+        }
+        for (name, value) in &self.globals {
+            let _ = writeln!(out, "global\t{}\t{}", escape(name), value);
+        }
+        for value in &self.with_stack {
+            let _ = writeln!(out, "with\t{}", value);
+        }
+        let label = self.on_error_label.as_deref().map(escape).unwrap_or_default();
+        let _ = writeln!(out, "onerror\t{}\t{}", on_error_mode_name(self.on_error_mode), label);
+        if let Some((number, source, description)) = &self.err {
+            let _ = writeln!(out, "err\t{}\t{}\t{}", number, escape(source), escape(description));
+        }
+        out
+    }
+
+    /// Parse the hibernation file format.
+    pub fn parse(text: &str) -> Result<Hibernation, String> {
+        let mut lines = text.lines();
+        if lines.next() != Some(HEADER) {
+            return Err("not a vba hibernation image".into());
+        }
+
+        let mut image = Hibernation {
+            entrypoint: String::new(),
+            next_frame_id: 0,
+            frames: Vec::new(),
+            byref: Vec::new(),
+            scopes: Vec::new(),
+            globals: Vec::new(),
+            with_stack: Vec::new(),
+            on_error_mode: OnErrorMode::None,
+            on_error_label: None,
+            err: None,
+        };
+        for (n, line) in lines.enumerate() {
+            let n = n + 2;
+            let fields: Vec<&str> = line.split('\t').collect();
+            let bad = || format!("line {}: malformed {} record", n, fields[0]);
+            match fields.as_slice() {
+                ["entry", name] => image.entrypoint = unescape(name),
+                ["next", id] => image.next_frame_id = number(id, n)?,
+                ["frame", id, list_id, pc, procedure, path, kind @ ..] => {
+                    let kind = match kind {
+                        ["main"] => FrameKindImage::Main,
+                        ["block"] => FrameKindImage::Block,
+                        ["if"] => FrameKindImage::If,
+                        ["with"] => FrameKindImage::With,
+                        ["for", counter, current, end, step] => FrameKindImage::For {
+                            counter: unescape(counter),
+                            current: number(current, n)?,
+                            end: number(end, n)?,
+                            step: number(step, n)?,
+                        },
+                        ["do", first] => FrameKindImage::Do { first_iteration: number(first, n)? },
+                        _ => return Err(bad()),
+                    };
+                    image.frames.push(FrameImage {
+                        id: number(id, n)?,
+                        list_id: number(list_id, n)?,
+                        pc: number(pc, n)?,
+                        procedure: unescape(procedure),
+                        path: parse_path(path).ok_or_else(bad)?,
+                        kind,
+                    });
+                }
+                ["byref", id, param, var] => image.byref.push((number(id, n)?, unescape(param), unescape(var))),
+                ["scope", kind, name] => image.scopes.push(ScopeImage {
+                    kind: scope_kind_from_name(kind).ok_or_else(bad)?,
+                    name: unescape(name),
+                    locals: Vec::new(),
+                }),
+                ["local", name, ty, value] => {
+                    decode_value(value).map_err(|e| format!("line {}: {}", n, e))?;
+                    let ty = match *ty {
+                        "-" => None,
+                        other => Some(declared_type_from_name(other).ok_or_else(bad)?),
+                    };
+                    let scope = image.scopes.last_mut().ok_or_else(bad)?;
+                    scope.locals.push(LocalImage { name: unescape(name), ty, value: value.to_string() });
+                }
+                ["global", name, value] => {
+                    decode_value(value).map_err(|e| format!("line {}: {}", n, e))?;
+                    image.globals.push((unescape(name), value.to_string()));
+                }
+                ["with", value] => {
+                    decode_value(value).map_err(|e| format!("line {}: {}", n, e))?;
+                    image.with_stack.push(value.to_string());
+                }
+                ["onerror", mode, label] => {
+                    image.on_error_mode = on_error_mode_from_name(mode).ok_or_else(bad)?;
+                    image.on_error_label = (!label.is_empty()).then(|| unescape(label));
+                }
+                ["err", num, source, description] => {
+                    image.err = Some((number(num, n)?, unescape(source), unescape(description)));
+                }
+                _ => return Err(format!("line {}: unknown record", n)),
+            }
+        }
+        if image.frames.is_empty() {
+            return Err("image has no frames".into());
+        }
+        Ok(image)
+    }
+}
+
+/// Procedure and path of a statement list run by a frame
+fn locate(ctx: &Context, body: &Body) -> Option<(String, BodyPath)> {
+    let mut procedures: Vec<_> = ctx.subs.iter().collect();
+    procedures.sort_by_key(|(name, _)| name.as_str());
+    procedures.into_iter().find_map(|(name, (_, proc_body))| {
+        let mut path = Vec::new();
+        find_path(proc_body, body, &mut path).then(|| (name.as_str().to_string(), path))
+    })
+}
+
+fn find_path(within: &Body, target: &Body, path: &mut BodyPath) -> bool {
+    if within.ptr_eq(target) {
+        return true;
+    }
+    for (i, stmt) in within.iter().enumerate() {
+        for (arm, child) in stmt.child_bodies().into_iter().enumerate() {
+            path.push((i, arm));
+            if find_path(child, target, path) {
+                return true;
+            }
+            path.pop();
+        }
+    }
+    false
+}
+
+/// Statement list at `path` in `procedure`, with the statement it belongs to
+fn resolve<'a>(ctx: &'a Context, procedure: &str, path: &[(usize, usize)]) -> Option<(Body, Option<&'a Statement>)> {
+    let mut body: &Body = &ctx.get_sub(procedure)?.1;
+    let mut owner = None;
+    for &(i, arm) in path {
+        let stmt = body.get(i)?;
+        body = *stmt.child_bodies().get(arm)?;
+        owner = Some(stmt);
+    }
+    Some((body.clone(), owner))
+}
+
+fn path_text(path: &[(usize, usize)]) -> String {
+    if path.is_empty() {
+        return "-".into();
+    }
+    path.iter().map(|(i, arm)| format!("{}.{}", i, arm)).collect::<Vec<_>>().join("/")
+}
+
+fn parse_path(text: &str) -> Option<BodyPath> {
+    if text == "-" {
+        return Some(Vec::new());
+    }
+    text.split('/')
+        .map(|step| {
+            let (i, arm) = step.split_once('.')?;
+            Some((i.parse().ok()?, arm.parse().ok()?))
+        })
+        .collect()
+}
+
+fn number<T: FromStr>(field: &str, line: usize) -> Result<T, String> {
+    field.parse().map_err(|_| format!("line {}: bad number {:?}", line, field))
+}
+
+fn scope_kind_name(kind: ScopeKind) -> &'static str {
+    match kind {
+        ScopeKind::Subroutine => "sub",
+        ScopeKind::Function => "function",
+        ScopeKind::Block => "block",
+    }
+}
+
+fn scope_kind_from_name(name: &str) -> Option<ScopeKind> {
+    match name {
+        "sub" => Some(ScopeKind::Subroutine),
+        "function" => Some(ScopeKind::Function),
+        "block" => Some(ScopeKind::Block),
+        _ => None,
+    }
+}
+
+fn on_error_mode_name(mode: OnErrorMode) -> &'static str {
+    match mode {
+        OnErrorMode::None => "none",
+        OnErrorMode::ResumeNextAuto => "resumenext",
+        OnErrorMode::GoTo => "goto",
+    }
+}
+
+fn on_error_mode_from_name(name: &str) -> Option<OnErrorMode> {
+    match name {
+        "none" => Some(OnErrorMode::None),
+        "resumenext" => Some(OnErrorMode::ResumeNextAuto),
+        "goto" => Some(OnErrorMode::GoTo),
+        _ => None,
+    }
+}
+
+fn declared_type_from_name(name: &str) -> Option<DeclaredType> {
+    Some(match name {
+        "Boolean" => DeclaredType::Boolean,
+        "Byte" => DeclaredType::Byte,
+        "Currency" => DeclaredType::Currency,
+        "Date" => DeclaredType::Date,
+        "Double" => DeclaredType::Double,
+        "Decimal" => DeclaredType::Decimal,
+        "Integer" => DeclaredType::Integer,
+        "Long" => DeclaredType::Long,
+        "LongLong" => DeclaredType::LongLong,
+        "Object" => DeclaredType::Object,
+        "Single" => DeclaredType::Single,
+        "String" => DeclaredType::String,
+        "Variant" => DeclaredType::Variant,
+        _ => return None,
+    })
+}
+
+/// `tag:text` form of a value
+fn encode_value(value: &Value) -> Result<String, String> {
+    Ok(match value {
+        Value::Boolean(b) => format!("bool:{}", b),
+        Value::Byte(b) => format!("byte:{}", b),
+        Value::Currency(c) => format!("cur:{}", c),
+        Value::Date(d) => format!("date:{}", d),
+        Value::DateTime(dt) => format!("datetime:{:?}", dt),
+        Value::Time(t) => format!("time:{:?}", t),
+        Value::Double(f) => format!("dbl:{}", f),
+        Value::Decimal(f) => format!("dec:{}", f),
+        Value::Integer(i) => format!("int:{}", i),
+        Value::Long(i) => format!("lng:{}", i),
+        Value::LongLong(i) => format!("llng:{}", i),
+        Value::Single(f) => format!("sng:{}", f),
+        Value::String(s) => format!("str:{}", escape(s)),
+        Value::Empty => "empty".into(),
+        Value::Null => "null".into(),
+        Value::Error(n) => format!("err:{}", n),
+        Value::Object(None) => "nothing".into(),
+        Value::Object(Some(inner)) => format!("obj:{}", encode_value(inner)?),
+        Value::UserType { type_name, .. } => {
+            return Err(format!("values of user-defined type {} cannot be saved", type_name))
+        }
+    })
+}
+
+fn decode_value(text: &str) -> Result<Value, String> {
+    let (tag, rest) = text.split_once(':').unwrap_or((text, ""));
+    let bad = || format!("bad value {:?}", text);
+    Ok(match tag {
+        "bool" => Value::Boolean(rest.parse().map_err(|_| bad())?),
+        "byte" => Value::Byte(rest.parse().map_err(|_| bad())?),
+        "cur" => Value::Currency(rest.parse().map_err(|_| bad())?),
+        "date" => Value::Date(rest.parse().map_err(|_| bad())?),
+        "datetime" => Value::DateTime(rest.parse().map_err(|_| bad())?),
+        "time" => Value::Time(rest.parse().map_err(|_| bad())?),
+        "dbl" => Value::Double(rest.parse().map_err(|_| bad())?),
+        "dec" => Value::Decimal(rest.parse().map_err(|_| bad())?),
+        "int" => Value::Integer(rest.parse().map_err(|_| bad())?),
+        "lng" => Value::Long(rest.parse().map_err(|_| bad())?),
+        "llng" => Value::LongLong(rest.parse().map_err(|_| bad())?),
+        "sng" => Value::Single(rest.parse().map_err(|_| bad())?),
+        "str" => Value::String(unescape(rest)),
+        "empty" => Value::Empty,
+        "null" => Value::Null,
+        "err" => Value::Error(rest.parse().map_err(|_| bad())?),
+        "nothing" => Value::Object(None),
+        "obj" => Value::Object(Some(Box::new(decode_value(rest)?))),
+        _ => return Err(bad()),
+    })
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::ast::{AssignmentTarget, Expression, ForStatement, Program};
+    use crate::interpreter::compiled::CompiledCache;
+    use crate::host::ComObject;
+    use crate::vm::ProgramExecutor;
+
+    /// Host object whose `Pause` waits for input by hibernating
+    #[derive(Debug)]
+    struct PauseHost;
+
+    impl ComObject for PauseHost {
+        fn get_property(&self, _name: &str, ctx: &mut Context) -> anyhow::Result<Value> {
+            ctx.request_hibernation();
+            Ok(Value::Empty)
+        }
+
+        fn set_property(&mut self, _name: &str, _value: Value, _ctx: &mut Context) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn call_method(&mut self, _name: &str, _args: &[Value], _ctx: &mut Context) -> anyhow::Result<Value> {
+            Ok(Value::Empty)
+        }
+
+        fn type_name(&self) -> &str {
+            "PauseHost"
+        }
+    }
+
+    fn assign(name: &str, rvalue: Expression) -> Statement {
+        Statement::Assignment {
+            lvalue: AssignmentTarget::Identifier(name.into()),
+            rvalue,
+            rvalue_compiled: CompiledCache::default(),
+        }
+    }
+
+    fn x_op(op: &str, n: i64) -> Expression {
+        Expression::BinaryOp {
+            left: Box::new(Expression::Identifier("HibernateTestX".into())),
+            op: op.into(),
+            right: Box::new(Expression::Integer(n)),
+        }
+    }
+
+    // Dim HibernateTestX, HibernateTestI
+    //
+    // HibernateTestX = 1
+    // For HibernateTestI = 1 To 3
+    //     HibernateTestX = HibernateTestX * 2
+    //     HibernateTestHost.Pause
+    // Next
+    // HibernateTestX = HibernateTestX + 100
+    fn program() -> Program {
+        let pause = Statement::Expression(Expression::PropertyAccess {
+            obj: Box::new(Expression::Identifier("HibernateTestHost".into())),
+            property: "Pause".into(),
+        });
+        let for_stmt = Statement::For(ForStatement {
+            counter: "HibernateTestI".into(),
+            start: Expression::Integer(1),
+            end: Expression::Integer(3),
+            step: None,
+            body: vec![assign("HibernateTestX", x_op("*", 2)), pause].into(),
+            next_counter: None,
+        });
+        let body = vec![
+            assign("HibernateTestX", Expression::Integer(1)),
+            for_stmt,
+            assign("HibernateTestX", x_op("+", 100)),
+        ];
+        let sub = Statement::Subroutine { visibility: None, name: "HibernateTestMain".into(), params: vec![], body: body.into() };
+        let dim = Statement::Dim { names: vec![("HibernateTestX".into(), None), ("HibernateTestI".into(), None)] };
+        Program { statements: vec![dim, sub], diagnostics: vec![] }
+    }
+
+    fn context() -> Context {
+        let mut ctx = Context::new();
+        ctx.com_registry.register_global("HibernateTestHost", Rc::new(RefCell::new(PauseHost)));
+        ctx
+    }
+
+    #[test]
+    fn test_resume_across_contexts() {
+        let executor = ProgramExecutor::new(program());
+        let mut ctx = context();
+        executor.execute_entrypoint(&mut ctx, "HibernateTestMain").unwrap();
+        assert_eq!(ctx.get_var("HibernateTestX").unwrap().as_string(), "2");
+
+        // Each image goes through text into a fresh context, as if a new
+        // process picked it up
+        let mut images = 0;
+        while let Some(image) = ctx.take_hibernation() {
+            images += 1;
+            let text = image.to_text();
+            if images == 1 {
+                assert!(text.contains("\tfor\tHibernateTestI\t2\t3\t1\n"));
+            }
+            let parsed = Hibernation::parse(&text).unwrap();
+            assert_eq!(parsed, image);
+
+            ctx = context();
+            executor.resume(&mut ctx, &parsed).unwrap();
+        }
+
+        assert_eq!(images, 3);
+        assert_eq!(ctx.get_var("HibernateTestX").unwrap().as_string(), "108");
+        assert_eq!(ctx.get_var("HibernateTestI").unwrap().as_string(), "4");
+    }
+
+    // Dim StopTestX
+    //
+    // 10 StopTestX = 1
+    // 11 Stop
+    // 12 StopTestX = 2
+    // 13 StopTestX = 3
+    fn stop_program() -> Program {
+        let body = vec![
+            assign("StopTestX", Expression::Integer(1)),
+            Statement::Stop,
+            assign("StopTestX", Expression::Integer(2)),
+            assign("StopTestX", Expression::Integer(3)),
+        ];
+        let body = Body::new(body, vec![10, 11, 12, 13]);
+        let sub = Statement::Subroutine { visibility: None, name: "StopTestMain".into(), params: vec![], body };
+        let dim = Statement::Dim { names: vec![("StopTestX".into(), None)] };
+        Program { statements: vec![dim, sub], diagnostics: vec![] }
+    }
+
+    #[test]
+    fn test_stop_policies_and_breakpoints() {
+        use crate::runtime_config::{RuntimeConfig, StopPolicy};
+        let executor = ProgramExecutor::new(stop_program());
+        let x = |ctx: &Context| ctx.get_var("StopTestX").unwrap().as_string();
+
+        let mut ctx = Context::new();
+        executor.execute_entrypoint(&mut ctx, "StopTestMain").unwrap();
+        assert_eq!(x(&ctx), "1");
+        assert_eq!(ctx.stop_report().unwrap().to_text(), "Stop at line 11 (halted)");
+
+        let mut ctx = Context::with_config(RuntimeConfig::builder().stop_policy(StopPolicy::Ignore).build());
+        executor.execute_entrypoint(&mut ctx, "StopTestMain").unwrap();
+        assert_eq!(x(&ctx), "3");
+
+        // Paused at the Stop, then before line 13, then to the end
+        let config = || RuntimeConfig::builder().stop_policy(StopPolicy::Pause).breakpoint(13).build();
+        let mut ctx = Context::with_config(config());
+        executor.execute_entrypoint(&mut ctx, "StopTestMain").unwrap();
+        assert_eq!(x(&ctx), "1");
+        let mut seen = Vec::new();
+        while let Some(image) = ctx.take_hibernation() {
+            seen.push(x(&ctx));
+            ctx = Context::with_config(config());
+            executor.resume(&mut ctx, &image).unwrap();
+            if let Some(report) = ctx.stop_report() {
+                assert!(report.breakpoint && report.line == Some(13));
+            }
+        }
+        assert_eq!(seen, ["1", "2"]);
+        assert_eq!(x(&ctx), "3");
+    }
+
+    #[test]
+    fn test_value_text_round_trip() {
+        let values = [
+            Value::String("a\tb\\c\nd".into()),
+            Value::Double(-0.1),
+            Value::DateTime(chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap().and_hms_opt(13, 5, 0).unwrap()),
+            Value::Object(Some(Box::new(Value::String("Excel.Range:A1".into())))),
+            Value::Error(2042),
+        ];
+        for value in &values {
+            let text = encode_value(value).unwrap();
+            assert!(!text.contains('\t') && !text.contains('\n'));
+            assert_eq!(decode_value(&text).unwrap().as_string(), value.as_string());
+        }
+        assert!(encode_value(&Value::UserType { type_name: "Point".into(), fields: HashMap::new() }).is_err());
+        assert!(Hibernation::parse("vba-hibernate 1\nentry\tMain\n").is_err());
+    }
+}
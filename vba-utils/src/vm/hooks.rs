@@ -0,0 +1,172 @@
+// vba-utils/src/vm/hooks.rs
+
+//! Execution hooks for instrumentation built outside the crate.
+//!
+//! Install an [`ExecutionHooks`] implementation with
+//! [`Context::set_hooks`] and the VM calls it around every statement it
+//! runs, and every call that leaves the macro for the host: Declare'd DLL
+//! procedures, host functions, SendKeys/AppActivate/CreateObject/GetObject
+//! and methods of host objects (`Range.Clear`, `MailItem.Send`, ...).
+//!
+//! All methods have do-nothing defaults. A security monitor can refuse a
+//! host call ([`HookAction::Deny`], run-time error 70 in the macro, which
+//! `On Error` can handle) or end the run from `on_statement_enter`
+//! ([`HookAction::Halt`]). Hooks run on the VM engine only.
+
+use std::fmt;
+
+use anyhow::Result;
+
+use crate::context::{Context, Value};
+
+/// What the VM does after a hook returns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HookAction {
+    #[default]
+    Continue,
+    /// Refuse a host call: run-time error 70 (Permission denied)
+    Deny,
+    /// End the run before the statement executes
+    Halt,
+}
+
+/// The statement about to run or just run
+#[derive(Debug, Clone, Copy)]
+pub struct StatementSite<'a> {
+    /// Source line (1-based), when known
+    pub line: Option<usize>,
+    /// Statement kind, see `Statement::kind_name`
+    pub kind: &'a str,
+}
+
+/// The VM frame running the statement
+#[derive(Debug, Clone, Copy)]
+pub struct FrameSite<'a> {
+    pub id: usize,
+    pub depth: usize,
+    pub pc: usize,
+    /// Innermost called Sub/Function; None in the entry Sub, which runs
+    /// at module level
+    pub procedure: Option<&'a str>,
+}
+
+/// What kind of host boundary a call crosses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostCallKind {
+    /// Declare'd DLL entry point (`kernel32!GetTickCount`)
+    Dll,
+    /// Function registered with `RuntimeConfig::function`
+    HostFunction,
+    /// SendKeys, AppActivate, CreateObject or GetObject
+    Interaction,
+    /// Method of a host object (`Range.Clear`, `MailItem.Send`)
+    Method,
+}
+
+/// A call leaving the macro
+#[derive(Debug, Clone, Copy)]
+pub struct HostCall<'a> {
+    pub kind: HostCallKind,
+    /// `library!entry`, function name, interaction name or `Type.Method`
+    pub name: &'a str,
+    /// Evaluated arguments (the target for interactions)
+    pub args: &'a [Value],
+}
+
+/// Callbacks the VM makes while running
+pub trait ExecutionHooks {
+    /// Before a statement runs; `Halt` ends the run
+    fn on_statement_enter(&mut self, _statement: &StatementSite, _frame: &FrameSite, _ctx: &Context) -> HookAction {
+        HookAction::Continue
+    }
+
+    /// After a statement ran (including everything it called)
+    fn on_statement_exit(&mut self, _statement: &StatementSite, _frame: &FrameSite, _ctx: &Context) {}
+
+    /// Before a call leaves the macro; `Deny` raises error 70 instead
+    fn on_host_call(&mut self, _call: &HostCall) -> HookAction {
+        HookAction::Continue
+    }
+}
+
+/// The installed hooks, kept on the Context
+pub(crate) struct HookSlot(pub(crate) Box<dyn ExecutionHooks>);
+
+impl fmt::Debug for HookSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ExecutionHooks")
+    }
+}
+
+/// Tell the hooks about a host call; error 70 if they deny it
+pub(crate) fn host_call(ctx: &mut Context, kind: HostCallKind, name: &str, args: &[Value]) -> Result<()> {
+    let Some(mut hooks) = ctx.hooks.take() else {
+        return Ok(());
+    };
+    let action = hooks.0.on_host_call(&HostCall { kind, name, args });
+    ctx.hooks = Some(hooks);
+    if action == HookAction::Deny {
+        ctx.log(&format!("Hook denied {:?} {}", kind, name));
+        return Err(crate::vba_errors::VbaErr::PermissionDenied.error(name));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{AssignmentTarget, Expression, Program, Statement};
+    use std::sync::{Arc, Mutex};
+
+    /// Counts statements and refuses CreateObject
+    struct Monitor {
+        seen: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl ExecutionHooks for Monitor {
+        fn on_statement_enter(&mut self, statement: &StatementSite, frame: &FrameSite, _ctx: &Context) -> HookAction {
+            let procedure = frame.procedure.unwrap_or("(entry)");
+            self.seen.lock().unwrap().push(format!("enter {} {}", procedure, statement.kind));
+            HookAction::Continue
+        }
+
+        fn on_statement_exit(&mut self, statement: &StatementSite, _frame: &FrameSite, _ctx: &Context) {
+            self.seen.lock().unwrap().push(format!("exit {}", statement.kind));
+        }
+
+        fn on_host_call(&mut self, call: &HostCall) -> HookAction {
+            self.seen.lock().unwrap().push(format!("host {:?} {}", call.kind, call.name));
+            match call.name {
+                "CreateObject" => HookAction::Deny,
+                _ => HookAction::Continue,
+            }
+        }
+    }
+
+    #[test]
+    fn test_hooks_see_statements_and_deny_host_calls() {
+        let create = Statement::Assignment {
+            lvalue: AssignmentTarget::Identifier("HookShell".into()),
+            rvalue: Expression::FunctionCall {
+                function: Box::new(Expression::Identifier("CreateObject".into())),
+                args: vec![Expression::String("WScript.Shell".into())],
+            },
+            rvalue_compiled: Default::default(),
+        };
+        let main = Statement::Subroutine { visibility: None, name: "HookMain".into(), params: vec![], body: vec![create].into() };
+        let executor = crate::vm::ProgramExecutor::new(Program { statements: vec![main], diagnostics: vec![] });
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut ctx = Context::new();
+        ctx.set_hooks(Box::new(Monitor { seen: seen.clone() }));
+        executor.execute_entrypoint(&mut ctx, "HookMain").unwrap();
+
+        assert_eq!(ctx.err.as_ref().map(|e| e.number), Some(70));
+        let seen = seen.lock().unwrap();
+        assert_eq!(
+            *seen,
+            vec!["enter HookMain Assignment", "host Interaction CreateObject", "exit Assignment"]
+        );
+        assert!(ctx.take_hooks().is_some());
+    }
+}
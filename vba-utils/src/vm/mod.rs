@@ -1,7 +1,21 @@
+pub mod coverage;
 pub mod frame;
+pub mod hibernate;
+pub mod hooks;
 pub mod runtime;
+pub mod profile;
 pub mod program;
+pub mod telemetry;
+pub mod testing;
+pub mod trace;
 
-pub use program::{ProgramExecutor, VbaRuntime}; 
+pub use program::{EntryPoint, ProgramExecutor, VbaRuntime}; 
 pub use frame::{Frame, FrameKind};
-pub use runtime::{VbaVm, run_statement_list_vm};
\ No newline at end of file
+pub use hibernate::Hibernation;
+pub use hooks::{ExecutionHooks, FrameSite, HookAction, HostCall, HostCallKind, StatementSite};
+pub use runtime::{VbaVm, run_statement_list_vm};
+pub use trace::{Trace, TraceEvent, VarChange};
+pub use coverage::{CoverageHits, CoverageReport};
+pub use profile::{LineProfile, ProcedureProfile, ProfileReport};
+pub use telemetry::{Span, SpanKind, TelemetryReport};
+pub use testing::{TestOutcome, TestReport, TestResult};
\ No newline at end of file
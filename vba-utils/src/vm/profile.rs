@@ -0,0 +1,228 @@
+// vba-utils/src/vm/profile.rs
+
+//! Instrumenting profiler: time per Sub/Function and per source line.
+//!
+//! With `ctx.start_profile()` every procedure entry and exit is timed (via
+//! the scope stack, so both engines are covered) and the VM times each
+//! statement it runs. `ctx.take_profile()` — or
+//! `ProgramExecutor::execute_profiled` — returns a [`ProfileReport`].
+//!
+//! - Procedure `total` time includes callees; `self_time` excludes them.
+//! - Line time is the time spent running the statement on that line,
+//!   including Functions called from its expressions and the branch of an
+//!   inline `If`. Loop bodies and called Subs run as their own VM frames
+//!   and are counted on their own lines.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::time::{Duration, Instant};
+
+/// Time spent in one Sub/Function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcedureProfile {
+    pub name: String,
+    pub calls: u64,
+    pub total: Duration,
+    pub self_time: Duration,
+}
+
+/// Time spent on one source line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineProfile {
+    pub line: usize,
+    pub hits: u64,
+    pub time: Duration,
+}
+
+/// Result of a profiled run.
+#[derive(Debug, Clone, Default)]
+pub struct ProfileReport {
+    /// Wall time from `start_profile` to `take_profile`
+    pub total: Duration,
+    /// Sorted by total time, slowest first
+    pub procedures: Vec<ProcedureProfile>,
+    /// Sorted by line number
+    pub lines: Vec<LineProfile>,
+}
+
+impl ProfileReport {
+    pub fn procedure(&self, name: &str) -> Option<&ProcedureProfile> {
+        self.procedures.iter().find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The `n` lines with the most time, slowest first.
+    pub fn hottest_lines(&self, n: usize) -> Vec<LineProfile> {
+        let mut lines = self.lines.clone();
+        lines.sort_by(|a, b| b.time.cmp(&a.time).then(a.line.cmp(&b.line)));
+        lines.truncate(n);
+        lines
+    }
+
+    /// Plain-text tables of procedures and the ten hottest lines.
+    pub fn to_text(&self) -> String {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let mut out = String::new();
+        let _ = writeln!(out, "Total: {:.3} ms", ms(self.total));
+        let _ = writeln!(out, "\n{:<32} {:>8} {:>12} {:>12}", "Procedure", "Calls", "Total ms", "Self ms");
+        for p in &self.procedures {
+            let _ = writeln!(out, "{:<32} {:>8} {:>12.3} {:>12.3}", p.name, p.calls, ms(p.total), ms(p.self_time));
+        }
+        let _ = writeln!(out, "\n{:<8} {:>10} {:>12}", "Line", "Hits", "Time ms");
+        for l in self.hottest_lines(10) {
+            let _ = writeln!(out, "{:<8} {:>10} {:>12.3}", l.line, l.hits, ms(l.time));
+        }
+        out
+    }
+}
+
+#[derive(Debug)]
+struct OpenCall {
+    name: String,
+    depth: usize,
+    started: Instant,
+    /// Total time of callees that already returned
+    children: Duration,
+}
+
+/// Collects timings while attached to a `Context` (see `Context::start_profile`).
+#[derive(Debug)]
+pub struct Profiler {
+    started: Instant,
+    stack: Vec<OpenCall>,
+    /// lowercase name → (display name, calls, total, self)
+    procedures: HashMap<String, (String, u64, Duration, Duration)>,
+    lines: BTreeMap<usize, (u64, Duration)>,
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Profiler {
+            started: Instant::now(),
+            stack: Vec::new(),
+            procedures: HashMap::new(),
+            lines: BTreeMap::new(),
+        }
+    }
+}
+
+impl Profiler {
+    /// A procedure started. `depth` orders nested calls: a later `leave`
+    /// with the same or a lower depth closes it.
+    pub fn enter(&mut self, name: &str, depth: usize) {
+        self.stack.push(OpenCall {
+            name: name.to_string(),
+            depth,
+            started: Instant::now(),
+            children: Duration::ZERO,
+        });
+    }
+
+    /// Close every open call at `depth` or deeper.
+    pub fn leave(&mut self, depth: usize) {
+        while self.stack.last().is_some_and(|c| c.depth >= depth) {
+            self.close_top();
+        }
+    }
+
+    /// Add the time of one statement on `line`.
+    pub fn line(&mut self, line: usize, elapsed: Duration) {
+        let entry = self.lines.entry(line).or_default();
+        entry.0 += 1;
+        entry.1 += elapsed;
+    }
+
+    pub fn finish(mut self) -> ProfileReport {
+        while !self.stack.is_empty() {
+            self.close_top();
+        }
+
+        let mut procedures: Vec<ProcedureProfile> = self
+            .procedures
+            .into_values()
+            .map(|(name, calls, total, self_time)| ProcedureProfile { name, calls, total, self_time })
+            .collect();
+        procedures.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+
+        let lines = self
+            .lines
+            .into_iter()
+            .map(|(line, (hits, time))| LineProfile { line, hits, time })
+            .collect();
+
+        ProfileReport { total: self.started.elapsed(), procedures, lines }
+    }
+
+    fn close_top(&mut self) {
+        let Some(call) = self.stack.pop() else { return };
+        let total = call.started.elapsed();
+        let own = total.saturating_sub(call.children);
+        if let Some(parent) = self.stack.last_mut() {
+            parent.children += total;
+        }
+
+        // Recursive calls: only the outermost activation adds to the total
+        let recursive = self.stack.iter().any(|c| c.name.eq_ignore_ascii_case(&call.name));
+        let entry = self
+            .procedures
+            .entry(call.name.to_lowercase())
+            .or_insert_with(|| (call.name.clone(), 0, Duration::ZERO, Duration::ZERO));
+        entry.1 += 1;
+        if !recursive {
+            entry.2 += total;
+        }
+        entry.3 += own;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_time_excludes_callees() {
+        let mut p = Profiler::default();
+        p.enter("Main", 0);
+        p.enter("Helper", 1);
+        std::thread::sleep(Duration::from_millis(5));
+        p.leave(1);
+        p.enter("helper", 1);
+        p.leave(1);
+        let report = p.finish();
+
+        let main = report.procedure("main").unwrap();
+        let helper = report.procedure("Helper").unwrap();
+        assert_eq!((main.calls, helper.calls), (1, 2));
+        assert!(main.total >= helper.total);
+        assert!(main.self_time < helper.total);
+        assert_eq!(report.procedures[0].name, "Main");
+    }
+
+    #[test]
+    fn test_recursion_counts_outer_total_once() {
+        let mut p = Profiler::default();
+        p.enter("Fib", 1);
+        p.enter("Fib", 2);
+        std::thread::sleep(Duration::from_millis(2));
+        p.leave(1);
+        let report = p.finish();
+
+        let fib = report.procedure("Fib").unwrap();
+        assert_eq!(fib.calls, 2);
+        assert!(fib.total < report.total + Duration::from_millis(1));
+        assert!(fib.self_time <= fib.total);
+    }
+
+    #[test]
+    fn test_hottest_lines() {
+        let mut p = Profiler::default();
+        p.line(3, Duration::from_millis(1));
+        p.line(5, Duration::from_millis(4));
+        p.line(3, Duration::from_millis(1));
+        let report = p.finish();
+
+        assert_eq!(report.lines.len(), 2);
+        assert_eq!(report.lines[0], LineProfile { line: 3, hits: 2, time: Duration::from_millis(2) });
+        assert_eq!(report.hottest_lines(1)[0].line, 5);
+        assert!(report.to_text().contains("Line"));
+    }
+}
@@ -1,6 +1,38 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
 use crate::ast::{Program, Statement};
 use crate::context::Context;
+use crate::error::VbaError;
+use crate::host::assert::{AssertObject, SharedAssertLog};
+use crate::host::ComObjectHandle;
 use crate::interpreter::{execute_statement, run_subroutine};
+use crate::runtime_config::{AutoRunPolicy, HostApplication};
+use crate::symbol::Symbol;
+use crate::vba_errors::VbaErr;
+use crate::vm::testing::{self, TestReport, TestResult};
+
+/// Macros a host runs on its own (document/workbook events, auto-run).
+pub const KNOWN_ENTRYPOINTS: [&str; 11] = [
+    "AutoOpen", "AutoExec", "AutoClose", "AutoNew", "Document_Open",
+    "Workbook_Open", "Workbook_Close", "Workbook_BeforeSave",
+    "Auto_Open", "Auto_Close",
+    "Main",
+];
+
+/// Macros Office runs by itself when a document or workbook opens, in the
+/// order [`AutoRunPolicy::FirstFound`] tries them
+pub const AUTO_EXEC_ENTRYPOINTS: [&str; 5] = ["AutoOpen", "AutoExec", "Document_Open", "Workbook_Open", "Auto_Open"];
+
+/// A Sub the user could run from the Macros dialog: one without parameters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryPoint {
+    pub name: String,
+    /// Declared `Private` (hidden from the Macros dialog, still runnable by name)
+    pub private: bool,
+    /// One of [`AUTO_EXEC_ENTRYPOINTS`]
+    pub auto_exec: bool,
+}
 
 /// The main entry point for executing a VBA program.
 /// Follows VBA's 3-phase execution model:
@@ -9,38 +41,254 @@ use crate::interpreter::{execute_statement, run_subroutine};
 /// 3. Execute an entrypoint (AutoOpen, Workbook_Open, etc.)
 pub struct ProgramExecutor {
     program: Program,
+    /// Module the program's procedures belong to
+    module: Option<String>,
 }
 
 impl ProgramExecutor {
+    /// The program's module is its `Attribute VB_Name`, if it has one
     pub fn new(program: Program) -> Self {
-        Self { program }
+        let module = program.attributes().module_name().map(str::to_string);
+        Self { program, module }
+    }
+
+    /// Load the program as module `name`: its procedures can then be called
+    /// as `name.Proc`, and same-named procedures of other modules loaded
+    /// into the Context no longer replace each other
+    /// (see [`Context::resolve_procedure`]).
+    pub fn with_module(mut self, name: impl Into<String>) -> Self {
+        self.module = Some(name.into());
+        self
+    }
+
+    /// The module set by [`ProgramExecutor::with_module`] or `VB_Name`.
+    pub fn module(&self) -> Option<&str> {
+        self.module.as_deref()
+    }
+
+    /// The program being executed.
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    /// Rewrite the program with `pipeline`'s passes, in order; call before
+    /// executing. See [`crate::transform`].
+    pub fn transform(&mut self, pipeline: &mut crate::transform::PassPipeline) -> Vec<crate::transform::PassOutcome> {
+        pipeline.run(&mut self.program)
     }
 
     /// Execute the full 3-phase process with automatic entrypoint detection
-    pub fn execute(&self, ctx: &mut Context) -> Result<(), String> {
-        // Phase 1: Register declarations
-        self.register_declarations(ctx)?;
-        // Initialize Excel host
-        crate::host::excel::initialize_excel_host(ctx);
-        
-        // Phase 2: Initialize module variables
-        self.initialize_module_variables(ctx)?;
+    pub fn execute(&self, ctx: &mut Context) -> Result<(), VbaError> {
+        let _workbook = ctx.static_workbook.enter();
+        if self.already_committed(ctx) {
+            return Ok(());
+        }
+        let result = self.run(ctx);
+        // Buffered cell writes, then host writes still in flight on an async backend
+        let flushed = crate::host::excel::engine::flush_writes()
+            .and_then(|_| crate::host::excel::async_backend::flush_blocking());
+        self.settle(ctx, result.and(flushed.map_err(VbaError::from)))
+    }
+
+    /// Like [`ProgramExecutor::execute`], for async Excel backends
+    /// (`ExcelBackendKind::Async`): the macro yields to the caller's
+    /// executor between statements, and its reads and the writes it leaves
+    /// in flight are awaited instead of blocked on (see
+    /// `host::excel::async_backend`).
+    pub async fn execute_async(&self, ctx: &mut Context) -> Result<(), VbaError> {
+        {
+            let _workbook = ctx.static_workbook.enter();
+            if self.already_committed(ctx) {
+                return Ok(());
+            }
+        }
+        let workbook = ctx.static_workbook.clone();
+        let run = || (self.run(ctx), crate::host::excel::engine::flush_writes());
+        let (result, buffered) = crate::host::excel::async_backend::drive(&workbook, run).await;
+        let flushed = match buffered {
+            Ok(()) => {
+                // Takes the workbook's writes now, so the scope ends before the await
+                let writes = {
+                    let _workbook = workbook.enter();
+                    crate::host::excel::async_backend::flush()
+                };
+                writes.await
+            }
+            Err(e) => Err(e),
+        };
+        let _workbook = ctx.static_workbook.enter();
+        self.settle(ctx, result.and(flushed.map_err(VbaError::from)))
+    }
+
+    /// Whether the configured journal has this run ID committed, in which
+    /// case the run must not happen again (see [`crate::host::journal`])
+    fn already_committed(&self, ctx: &Context) -> bool {
+        let committed = ctx.runtime_config.journal.as_ref().is_some_and(|journal| journal.is_committed(ctx.run_id()));
+        if committed {
+            eprintln!("⏭️ Run {} already committed; not executing it again", ctx.run_id());
+        }
+        committed
+    }
+
+    /// End a journaled run once its writes are flushed: roll it back if it
+    /// failed (the executor failed, or the macro stopped on an error no
+    /// handler took) and that is configured, and file its journal
+    fn settle(&self, ctx: &mut Context, result: Result<(), VbaError>) -> Result<(), VbaError> {
+        let failed = result.is_err() || (ctx.unhandled_error && ctx.err.is_some());
+        let run = match ctx.active_journal.take() {
+            Some(journal) => journal.finish(failed, ctx.runtime_config.rollback_on_error)?,
+            None => None,
+        };
+        if let (Some(run), Some(journal)) = (run, &ctx.runtime_config.journal) {
+            journal.file(run);
+        }
+        result
+    }
+
+    /// Tear down what [`ProgramExecutor::execute`] set up: the host
+    /// application (see `host::excel::shutdown_excel_host`) and the recorded
+    /// Outlook, DLL, UserForm and stub state, so the next execution in this
+    /// process starts from nothing. Read cells back before calling this.
+    pub fn shutdown(&self, ctx: &mut Context) -> Result<(), VbaError> {
+        let _workbook = ctx.static_workbook.enter();
+        let result = match ctx.runtime_config.host {
+            HostApplication::Excel => crate::host::excel::shutdown_excel_host(ctx).map_err(VbaError::from),
+            HostApplication::Word => {
+                crate::host::word::shutdown_word_host(ctx);
+                Ok(())
+            }
+        };
+        crate::host::outlook::mail::reset();
+        crate::host::stub::reset();
+        crate::host::dll::reset();
+        crate::host::behavior::reset();
+        crate::host::userform::reset();
+        crate::host::vb::reset();
+        crate::host::dry_run::take(ctx);
+        result
+    }
+
+    fn run(&self, ctx: &mut Context) -> Result<(), VbaError> {
+        if ctx.runtime_config.journal.is_some() || ctx.runtime_config.rollback_on_error {
+            ctx.active_journal = Some(crate::host::journal::begin(ctx.run_id()));
+        }
+        self.start(ctx)?;
 
         // Phase 3: Run entrypoint (auto-detect)
+        ctx.halted = false;
+        ctx.unhandled_error = false;
         let entrypoint = self.detect_entrypoint(ctx);
         if let Some(name) = entrypoint {
             eprintln!("▶️ Auto-detected entrypoint: {}", name);
             // run_subroutine does not return Result, so no `?` here
             run_subroutine(ctx, &name);
         } else {
-            eprintln!("⚠️ No entrypoint found (AutoOpen, Workbook_Open, Main)");
+            eprintln!("⚠️ No entrypoint to run ({:?})", ctx.runtime_config.auto_run);
         }
 
         Ok(())
     }
 
+    /// Phases 1 and 2, with the host application set up in between
+    fn start(&self, ctx: &mut Context) -> Result<(), VbaError> {
+        // Phase 1: Register declarations
+        self.register_declarations(ctx)?;
+        if ctx.runtime_config.dry_run {
+            crate::host::dry_run::start(ctx);
+        }
+        // Initialize the host application
+        match ctx.runtime_config.host {
+            HostApplication::Excel => crate::host::excel::initialize_excel_host(ctx),
+            HostApplication::Word => crate::host::word::initialize_word_host(ctx),
+        }
+        // Outlook and recording stubs are reached through CreateObject from either host
+        crate::host::outlook::mail::reset();
+        crate::host::stub::reset();
+        crate::host::dll::reset();
+        crate::host::behavior::reset();
+        crate::host::userform::reset();
+        crate::host::vb::reset();
+        
+        // Phase 2: Initialize module variables
+        self.initialize_module_variables(ctx)
+    }
+
+    /// Run the program's unit tests (see [`crate::vm::testing`]) instead of
+    /// an entrypoint: every Sub named `Test*`, in source order, each with
+    /// `Assert` available and between `TestInitialize` and `TestCleanup`.
+    /// The host is set up as for [`ProgramExecutor::execute`], once for
+    /// all tests, so tests see the workbook earlier ones left.
+    pub fn run_tests(&self, ctx: &mut Context) -> Result<TestReport, VbaError> {
+        let _workbook = ctx.static_workbook.enter();
+        self.start(ctx)?;
+
+        let log = SharedAssertLog::default();
+        let assert: ComObjectHandle = Rc::new(RefCell::new(AssertObject::new(log.clone())));
+        ctx.com_registry.register_global("Assert", assert);
+
+        let entrypoints = self.entrypoints();
+        let hook = |name: &str| entrypoints.iter().find(|e| e.name.eq_ignore_ascii_case(name)).map(|e| e.name.clone());
+        let run_hook = |ctx: &mut Context, name: &str| {
+            if let Some(name) = hook(name) {
+                ctx.halted = false;
+                run_subroutine(ctx, &name);
+            }
+        };
+
+        let mut report = TestReport::default();
+        run_hook(ctx, testing::MODULE_INITIALIZE);
+        for test in entrypoints.iter().filter(|e| !e.private && testing::is_test_name(&e.name)) {
+            *log.borrow_mut() = Default::default();
+            ctx.err = None;
+            run_hook(ctx, testing::TEST_INITIALIZE);
+            ctx.halted = false;
+            eprintln!("🧪 Running test: {}", test.name);
+            run_subroutine(ctx, &test.name);
+            let error = ctx.err.take().map(|err| (err, crate::host::excel::engine::source_line()));
+            run_hook(ctx, testing::TEST_CLEANUP);
+            let recorded = std::mem::take(&mut *log.borrow_mut());
+            report.results.push(TestResult::new(&test.name, self.module.clone(), recorded, error));
+        }
+        ctx.err = None;
+        run_hook(ctx, testing::MODULE_CLEANUP);
+
+        ctx.com_registry.unregister_global("Assert");
+        crate::host::excel::engine::flush_writes()
+            .and_then(|_| crate::host::excel::async_backend::flush_blocking())?;
+        Ok(report)
+    }
+
+    /// Like [`ProgramExecutor::execute`], timing every Sub/Function and
+    /// line that runs.
+    pub fn execute_profiled(&self, ctx: &mut Context) -> Result<crate::vm::ProfileReport, VbaError> {
+        ctx.start_profile();
+        let result = self.execute(ctx);
+        let report = ctx.take_profile().unwrap_or_default();
+        result.map(|_| report)
+    }
+
+    /// Like [`ProgramExecutor::execute`] with `RuntimeConfig::dry_run` on:
+    /// returns the writes the macro would have made, none of them applied
+    /// (see [`crate::host::dry_run`]).
+    pub fn execute_dry_run(&self, ctx: &mut Context) -> Result<crate::host::dry_run::MutationPlan, VbaError> {
+        let configured = std::mem::replace(&mut ctx.runtime_config.dry_run, true);
+        let result = self.execute(ctx);
+        ctx.runtime_config.dry_run = configured;
+        let plan = crate::host::dry_run::take(ctx).unwrap_or_default();
+        result.map(|_| plan)
+    }
+
+    /// Run phases 1 and 2 only: register declarations and initialize
+    /// module variables without running an entrypoint. Calling this again
+    /// with another program adds to (or replaces) what `ctx` already has.
+    pub fn load(&self, ctx: &mut Context) -> Result<(), VbaError> {
+        self.register_declarations(ctx)?;
+        self.initialize_module_variables(ctx)
+    }
+
     /// Execute with a specific entrypoint
-    pub fn execute_entrypoint(&self, ctx: &mut Context, entrypoint: &str) -> Result<(), String> {
+    pub fn execute_entrypoint(&self, ctx: &mut Context, entrypoint: &str) -> Result<(), VbaError> {
+        let _workbook = ctx.static_workbook.enter();
         // Phase 1: Register declarations
         self.register_declarations(ctx)?;
 
@@ -48,22 +296,87 @@ impl ProgramExecutor {
         self.initialize_module_variables(ctx)?;
 
         // Phase 3: Run specified entrypoint
+        ctx.halted = false;
         eprintln!("▶️ Running entrypoint: {}", entrypoint);
         run_subroutine(ctx, entrypoint);
 
         Ok(())
     }
 
+    /// Continue a run that hibernated (see [`crate::vm::hibernate`]), e.g.
+    /// in a fresh context in another process. As with
+    /// [`ProgramExecutor::execute_entrypoint`], declarations and module
+    /// variables are set up first and the host is left as it is. The run
+    /// may hibernate again; check [`Context::take_hibernation`].
+    pub fn resume(&self, ctx: &mut Context, image: &crate::vm::Hibernation) -> Result<(), VbaError> {
+        let _workbook = ctx.static_workbook.enter();
+        self.register_declarations(ctx)?;
+        self.initialize_module_variables(ctx)?;
+
+        let depth = ctx.scope_depth();
+        let vm = image.restore(ctx)?;
+        ctx.halted = false;
+        // A run paused at a breakpoint resumes by running that statement
+        ctx.skip_breakpoint = true;
+        eprintln!("▶️ Resuming entrypoint: {}", image.entrypoint);
+        crate::vm::runtime::resume_vm(vm, ctx);
+        // The restored scopes, the entry Sub's included, end with the run
+        ctx.pop_scopes_to(depth);
+
+        Ok(())
+    }
+
+    /// Run the Application.OnTime procedures due by `now`, earliest first,
+    /// for embedders that keep time themselves (see `RuntimeConfig::on_schedule`).
+    /// Procedures past their LatestTime are dropped. Returns the names run.
+    pub fn run_due(&self, ctx: &mut Context, now: chrono::NaiveDateTime) -> Vec<String> {
+        let _workbook = ctx.static_workbook.enter();
+        let mut fired = Vec::new();
+        for entry in ctx.take_due(now) {
+            if !entry.can_run_at(now) {
+                eprintln!("⏰ Skipping {}: past its latest time", entry.procedure);
+                continue;
+            }
+            eprintln!("⏰ Running scheduled {}", entry.procedure);
+            ctx.halted = false;
+            run_subroutine(ctx, crate::host::ontime::procedure_name(&entry.procedure));
+            fired.push(entry.procedure);
+        }
+        fired
+    }
+
+    /// Move the virtual clock forward by `by`, stopping at each scheduled
+    /// time on the way to run what is due, including procedures scheduled
+    /// by the ones that ran. Returns the names run.
+    pub fn advance_clock(&self, ctx: &mut Context, by: chrono::Duration) -> Vec<String> {
+        let until = ctx.now() + by;
+        let mut fired = Vec::new();
+        while let Some(at) = ctx.scheduled().first().map(|e| e.at).filter(|at| *at <= until) {
+            let now = at.max(ctx.now());
+            ctx.set_clock(now);
+            fired.extend(self.run_due(ctx, now));
+        }
+        ctx.set_clock(until);
+        fired
+    }
+
     /// Phase 1: Register all module-level declarations
     /// Order: Option Explicit → Types → Enums → Variables (declare) → Subs
-    fn register_declarations(&self, ctx: &mut Context) -> Result<(), String> {
+    fn register_declarations(&self, ctx: &mut Context) -> Result<(), VbaError> {
         // eprintln!("📦 Phase 1: Registering module declarations");
+        ctx.loading_module = self.module.clone();
 
-        // 1.1: Option Explicit (if present)
+        // 1.1: Option Explicit, DefInt/DefStr/... and Declare (if present)
         for stmt in &self.program.statements {
-            if let Statement::OptionExplicit = stmt {
-                ctx.enable_option_explicit();
-                // eprintln!("   ✅ Option Explicit enabled");
+            match stmt {
+                Statement::OptionExplicit => {
+                    ctx.enable_option_explicit();
+                    // eprintln!("   ✅ Option Explicit enabled");
+                }
+                Statement::DefType { .. } | Statement::Declare { .. } => {
+                    execute_statement(stmt, ctx, 0);
+                }
+                _ => {}
             }
         }
 
@@ -104,7 +417,7 @@ impl ProgramExecutor {
 
         // 1.6: Register Subs FIFTH (your AST uses `Subroutine`)
         for stmt in &self.program.statements {
-            if let Statement::Subroutine { name, params, body } = stmt {
+            if let Statement::Subroutine { name, params, body, .. } = stmt {
                 ctx.register_sub(name, params, body);
                 // eprintln!("   ✅ Registered Subroutine: {}", name);
             }
@@ -112,7 +425,7 @@ impl ProgramExecutor {
 
         // 1.7: Register Functions SIXTH
         for stmt in &self.program.statements {
-            if let Statement::Function { name, params, return_type, body } = stmt {
+            if let Statement::Function { name, params, return_type, body, .. } = stmt {
                 ctx.register_function(name, params, body, return_type);
             }
         }
@@ -120,28 +433,31 @@ impl ProgramExecutor {
         // 1.8: Register Properties SEVENTH
         for stmt in &self.program.statements {
             match stmt {
-                Statement::PropertyGet { name, params, body, return_type } => {
+                Statement::PropertyGet { name, params, body, return_type, .. } => {
                     ctx.register_property("Get", name, params, body);
                     if let Some(ref rt) = return_type {
-                        ctx.function_return_types.insert(format!("Get_{}", name), Some(rt.clone()));
+                        ctx.function_return_types.insert(Symbol::intern(&format!("Get_{}", name)), Some(rt.clone()));
                     }
                 }
-                Statement::PropertyLet { name, params, body } => {
+                Statement::PropertyLet { name, params, body, .. } => {
                     ctx.register_property("Let", name, params, body);
                 }
-                Statement::PropertySet { name, params, body } => {
+                Statement::PropertySet { name, params, body, .. } => {
                     ctx.register_property("Set", name, params, body);
                 }
                 _ => {}
             }
         }
 
+        ctx.loading_module = None;
         Ok(())
     }
 
     /// Phase 2: Initialize module-level variables with their default values
-    fn initialize_module_variables(&self, ctx: &mut Context) -> Result<(), String> {
+    fn initialize_module_variables(&self, ctx: &mut Context) -> Result<(), VbaError> {
         // eprintln!("🔧 Phase 2: Initializing module variables");
+        // Static locals live as long as the module variables
+        ctx.reset_statics();
 
         for stmt in &self.program.statements {
             if let Statement::Dim { names } = stmt {
@@ -155,26 +471,46 @@ impl ProgramExecutor {
             }
         }
 
+        // Embedder globals win over the program's Dim defaults
+        for (name, value) in ctx.runtime_config.globals.clone() {
+            ctx.define_global(&name, value).map_err(|e| match VbaError::from_anyhow(&e, VbaErr::TypeMismatch.number()) {
+                VbaError::RuntimeError { number, description, span } => {
+                    VbaError::RuntimeError { number, description: format!("Global '{}': {}", name, description), span }
+                }
+                other => other,
+            })?;
+        }
+
         Ok(())
     }
 
-    /// Detect common VBA entrypoints in priority order
-    fn detect_entrypoint(&self, ctx: &Context) -> Option<String> {
-        let candidates = [
-            "AutoOpen",      // Word - opens with document
-            "AutoExec",      // Word - starts with Word
-            "Workbook_Open", // Excel - workbook opens
-            "Auto_Open",     // Excel legacy
-            "Main",          // Generic entry point
-        ];
-
-        for name in candidates {
-            if ctx.has_sub(name) {
-                return Some(name.to_string());
-            }
+    /// The Sub [`ProgramExecutor::execute`] runs under the configured
+    /// [`AutoRunPolicy`], if any
+    pub fn detect_entrypoint(&self, ctx: &Context) -> Option<String> {
+        let first = |names: &[&str]| names.iter().find(|name| ctx.has_sub(name)).map(|name| name.to_string());
+        match &ctx.runtime_config.auto_run {
+            AutoRunPolicy::FirstFound => first(&AUTO_EXEC_ENTRYPOINTS).or_else(|| first(&["Main"])),
+            AutoRunPolicy::AutoExecOnly => first(&AUTO_EXEC_ENTRYPOINTS),
+            AutoRunPolicy::Named(name) => first(&[name.as_str()]),
+            AutoRunPolicy::Disabled => None,
         }
+    }
 
-        None
+    /// Every Sub without parameters, in source order, flagging the
+    /// auto-exec ones
+    pub fn entrypoints(&self) -> Vec<EntryPoint> {
+        self.program
+            .statements
+            .iter()
+            .filter_map(|stmt| match stmt {
+                Statement::Subroutine { visibility, name, params, .. } if params.is_empty() => Some(EntryPoint {
+                    name: name.clone(),
+                    private: visibility.as_deref().is_some_and(|v| v.eq_ignore_ascii_case("private")),
+                    auto_exec: AUTO_EXEC_ENTRYPOINTS.iter().any(|auto| auto.eq_ignore_ascii_case(name)),
+                }),
+                _ => None,
+            })
+            .collect()
     }
 
     /// Optional helpers if you want them:
@@ -186,14 +522,7 @@ impl ProgramExecutor {
 
     /// Get list of all interesting entrypoints present
     pub fn list_entrypoints(&self, ctx: &Context) -> Vec<String> {
-        let candidates = [
-            "AutoOpen", "AutoExec", "AutoClose", "AutoNew",
-            "Workbook_Open", "Workbook_Close", "Workbook_BeforeSave",
-            "Auto_Open", "Auto_Close",
-            "Main",
-        ];
-
-        candidates
+        KNOWN_ENTRYPOINTS
             .iter()
             .copied()
             .filter(|name| ctx.has_sub(name))
@@ -209,7 +538,7 @@ pub struct VbaRuntime {
 
 impl VbaRuntime {
     /// Create a new runtime with initialized context
-    pub fn new(program: Program) -> Result<Self, String> {
+    pub fn new(program: Program) -> Result<Self, VbaError> {
         // You don't have `Context::new()`, you have `Default`
         let mut ctx = Context::default();
         let executor = ProgramExecutor::new(program);
@@ -222,22 +551,89 @@ impl VbaRuntime {
     }
 
     /// Execute a specific entrypoint/callback
-    pub fn call_sub(&mut self, name: &str) -> Result<(), String> {
+    pub fn call_sub(&mut self, name: &str) -> Result<(), VbaError> {
         // eprintln!("🔔 Host calling: {}", name);
         // run_subroutine returns (), so just call and then return Ok(())
+        let _workbook = self.ctx.static_workbook.enter();
         run_subroutine(&mut self.ctx, name);
         Ok(())
     }
 
-    /// Execute a function and get return value (future work)
+    /// Expose a Rust function to VBA, e.g. `GetCustomerName(id)`; it is
+    /// found before the builtins. Calls must pass one argument per entry of
+    /// `params` (error 450 otherwise), each converted to its type first
+    /// (error 13 when it cannot be). For optional arguments use
+    /// [`crate::host::functions::HostFunction::args`] with
+    /// `RuntimeConfig::function`.
+    pub fn register_function(
+        &mut self,
+        name: &str,
+        params: &[crate::context::DeclaredType],
+        handler: impl Fn(&[crate::context::Value], &mut Context) -> anyhow::Result<crate::context::Value> + Send + Sync + 'static,
+    ) {
+        let function = crate::host::functions::HostFunction::new(handler).params(params);
+        self.ctx.runtime_config.host_functions.register(name, function);
+    }
+
+    /// Call a VBA Function (or Sub, which returns Empty) with `args` and
+    /// return its value. Arguments are checked against the procedure's
+    /// parameters like a call from VBA: too many or too few is error 450, a
+    /// value its declared type cannot hold error 13. An error the procedure
+    /// raises and does not handle is returned as a `RuntimeError`.
     pub fn call_function(
         &mut self,
-        _name: &str,
-        _args: Vec<crate::context::Value>,
-    ) -> Result<crate::context::Value, String> {
-        // TODO: Implement function calls with arguments and return values
-        // This requires extending run_subroutine / a new run_function API.
-        unimplemented!("Function calls with return values not yet implemented")
+        name: &str,
+        args: Vec<crate::context::Value>,
+    ) -> Result<crate::context::Value, VbaError> {
+        let _workbook = self.ctx.static_workbook.enter();
+        let ctx = &mut self.ctx;
+        let key = ctx
+            .resolve_procedure(name)
+            .map_err(|e| VbaError::from_anyhow(&e, VbaErr::SubOrFunctionNotDefined.number()))?
+            .unwrap_or_else(|| name.to_string());
+        let Some((params, _)) = ctx.get_sub(&key).cloned() else {
+            return Err(VbaError::from_anyhow(&VbaErr::SubOrFunctionNotDefined.error(name), 0));
+        };
+
+        let required = params.iter().filter(|p| !p.optional && !p.param_array).count();
+        let fits = args.len() >= required && (args.len() <= params.len() || params.iter().any(|p| p.param_array));
+        if !fits {
+            return Err(VbaError::from_anyhow(&VbaErr::WrongNumberOfArguments.error(name), 0));
+        }
+        let args = args
+            .into_iter()
+            .zip(params.iter().map(Some).chain(std::iter::repeat(None)))
+            .map(|(arg, param)| match param.filter(|p| !p.param_array).map(|p| crate::context::DeclaredType::from_opt_str(p.param_type.as_deref())) {
+                Some(ty) if ty != crate::context::DeclaredType::Variant => crate::interpreter::coerce::coerce_to_declared(arg, ty)
+                    .map_err(|_| VbaError::from_anyhow(&VbaErr::TypeMismatch.error(name), 0)),
+                _ => Ok(arg),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let bare = key.rsplit('.').next().unwrap_or(&key).to_string();
+        let previous = ctx.err.take();
+        let result = crate::interpreter::run_user_procedure(&key, &bare, args, &[], ctx)
+            .map_err(|e| VbaError::from_anyhow(&e, VbaErr::InvalidProcedureCall.number()));
+        match std::mem::replace(&mut ctx.err, previous) {
+            Some(raised) => Err(VbaError::from_err(&raised)),
+            None => result,
+        }
+    }
+
+    /// Parse and evaluate one expression against `ctx`, e.g. for watch
+    /// windows or immediate-mode tools. Text that does not parse is a
+    /// `ParseError`, an expression that fails a `RuntimeError` with the
+    /// number VBA would raise. `ctx.err` is left as it was.
+    pub fn eval_expression(expr: &str, ctx: &mut Context) -> Result<crate::context::Value, VbaError> {
+        let expr = crate::ast::parse_expression(expr)?;
+        let _workbook = ctx.static_workbook.enter();
+        let previous = ctx.err.take();
+        let result = crate::interpreter::evaluate_expression(&expr, ctx).map_err(|e| VbaError::from_anyhow(&e, VbaErr::TypeMismatch.number()));
+        // Division by zero and the like set Err instead of failing
+        match std::mem::replace(&mut ctx.err, previous) {
+            Some(raised) => Err(VbaError::from_err(&raised)),
+            None => result,
+        }
     }
 
     /// Get a variable value (for host to read VBA state)
@@ -260,3 +656,191 @@ impl VbaRuntime {
         &mut self.ctx
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{AssignmentTarget, Expression, Parameter};
+    use crate::runtime_config::RuntimeConfig;
+
+    fn sub(visibility: Option<&str>, name: &str, params: Vec<Parameter>) -> Statement {
+        let mark = Statement::Assignment {
+            lvalue: AssignmentTarget::Identifier("EntryRan".into()),
+            rvalue: Expression::String(name.into()),
+            rvalue_compiled: Default::default(),
+        };
+        Statement::Subroutine { visibility: visibility.map(String::from), name: name.into(), params, body: vec![mark].into() }
+    }
+
+    #[test]
+    fn test_entrypoints_and_auto_run_policy() {
+        let program = Program {
+            statements: vec![
+                Statement::Dim { names: vec![("EntryRan".into(), None)] },
+                sub(None, "Main", vec![]),
+                sub(Some("Private"), "Helper", vec![]),
+                sub(None, "Report", vec![Parameter::simple("sheet".into())]),
+                sub(Some("Public"), "Workbook_Open", vec![]),
+            ],
+            diagnostics: vec![],
+        };
+        let executor = ProgramExecutor::new(program);
+        let found: Vec<(String, bool, bool)> =
+            executor.entrypoints().into_iter().map(|e| (e.name, e.private, e.auto_exec)).collect();
+        assert_eq!(
+            found,
+            vec![("Main".into(), false, false), ("Helper".into(), true, false), ("Workbook_Open".into(), false, true)]
+        );
+
+        let ran = |policy: AutoRunPolicy| {
+            let mut ctx = Context::with_config(RuntimeConfig::builder().auto_run(policy).build());
+            executor.execute(&mut ctx).unwrap();
+            ctx.get_var("EntryRan").map(|v| v.as_string())
+        };
+        assert_eq!(ran(AutoRunPolicy::FirstFound).as_deref(), Some("Workbook_Open"));
+        assert_eq!(ran(AutoRunPolicy::Named("helper".into())).as_deref(), Some("Helper"));
+        assert_eq!(ran(AutoRunPolicy::Disabled).as_deref(), Some(""));
+    }
+
+    #[test]
+    fn test_module_qualified_calls_and_ambiguity() {
+        let append = |text: Expression| Statement::Assignment {
+            lvalue: AssignmentTarget::Identifier("CallLog".into()),
+            rvalue: Expression::BinaryOp {
+                op: "&".into(),
+                left: Box::new(Expression::Identifier("CallLog".into())),
+                right: Box::new(text),
+            },
+            rvalue_compiled: Default::default(),
+        };
+        let procedure = |name: &str, body: Vec<Statement>| Statement::Subroutine {
+            visibility: None,
+            name: name.into(),
+            params: vec![],
+            body: body.into(),
+        };
+        let call = |function: &str| Statement::Call { function: function.into(), args: vec![] };
+        let module = |statements: Vec<Statement>| Program { statements, diagnostics: vec![] };
+
+        let first = module(vec![
+            procedure("Init", vec![append(Expression::String("1".into()))]),
+            procedure("FirstMain", vec![
+                call("Init"),
+                call("SecondModule.Init"),
+                Statement::Expression(Expression::PropertyAccess {
+                    obj: Box::new(Expression::Identifier("secondmodule".into())),
+                    property: "Init".into(),
+                }),
+            ]),
+        ]);
+        let second = module(vec![procedure("Init", vec![append(Expression::String("2".into()))])]);
+        let third = module(vec![procedure("ThirdMain", vec![call("Init")])]);
+
+        let mut ctx = Context::new();
+        ctx.define_global("CallLog", crate::context::Value::String(String::new())).unwrap();
+        ProgramExecutor::new(first).with_module("FirstModule").load(&mut ctx).unwrap();
+        ProgramExecutor::new(second).with_module("SecondModule").load(&mut ctx).unwrap();
+        let third = ProgramExecutor::new(third).with_module("ThirdModule");
+        third.load(&mut ctx).unwrap();
+        assert_eq!(ctx.procedure_modules("init"), ["FirstModule", "SecondModule"]);
+
+        // A bare Init means the caller's own module's
+        crate::interpreter::run_subroutine(&mut ctx, "FirstMain");
+        assert_eq!(ctx.get_var("CallLog").map(|v| v.as_string()).as_deref(), Some("122"));
+        crate::interpreter::run_subroutine(&mut ctx, "secondmodule.init");
+        assert_eq!(ctx.get_var("CallLog").map(|v| v.as_string()).as_deref(), Some("1222"));
+        assert!(ctx.err.is_none());
+
+        // ThirdModule defines no Init, so it cannot tell which one it means
+        third.execute_entrypoint(&mut ctx, "ThirdMain").unwrap();
+        assert_eq!(ctx.err.as_ref().map(|e| e.number), Some(35));
+        assert_eq!(ctx.get_var("CallLog").map(|v| v.as_string()).as_deref(), Some("1222"));
+    }
+
+    fn twice_runtime() -> VbaRuntime {
+        let n = Parameter { param_type: Some("Integer".into()), ..Parameter::simple("n".into()) };
+        let label = Parameter { optional: true, ..Parameter::simple("label".into()) };
+        let function = |name: &str, params: Vec<Parameter>, rvalue: Expression| Statement::Function {
+            visibility: None,
+            name: name.into(),
+            params,
+            return_type: None,
+            body: vec![Statement::Assignment {
+                lvalue: AssignmentTarget::Identifier(name.into()),
+                rvalue,
+                rvalue_compiled: Default::default(),
+            }]
+            .into(),
+        };
+        let times = |factor: i64| Expression::BinaryOp {
+            op: "*".into(),
+            left: Box::new(Expression::Identifier("n".into())),
+            right: Box::new(Expression::Integer(factor)),
+        };
+        let divide_by_zero = Expression::BinaryOp {
+            op: "/".into(),
+            left: Box::new(Expression::Integer(1)),
+            right: Box::new(Expression::Integer(0)),
+        };
+        VbaRuntime::new(Program {
+            statements: vec![
+                function("Twice", vec![n, label], times(2)),
+                function("Failing", vec![], divide_by_zero),
+            ],
+            diagnostics: vec![],
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_call_function_returns_the_function_value() {
+        use crate::context::Value;
+        let mut runtime = twice_runtime();
+        let twice = runtime.call_function("twice", vec![Value::Integer(21)]).unwrap();
+        assert_eq!(twice.as_string(), "42");
+        // Arguments convert to the parameter's type, and optional ones may be left out
+        let twice = runtime.call_function("Twice", vec![Value::String("4".into()), Value::String("x".into())]).unwrap();
+        assert_eq!(twice.as_string(), "8");
+    }
+
+    #[test]
+    fn test_call_function_rejects_mismatched_calls() {
+        use crate::context::Value;
+        let mut runtime = twice_runtime();
+        let number = |r: Result<Value, VbaError>| r.unwrap_err().number();
+        assert_eq!(number(runtime.call_function("Twice", vec![])), Some(450));
+        let three = vec![Value::Integer(1), Value::Integer(2), Value::Integer(3)];
+        assert_eq!(number(runtime.call_function("Twice", three)), Some(450));
+        assert_eq!(number(runtime.call_function("Twice", vec![Value::String("abc".into())])), Some(13));
+        assert_eq!(number(runtime.call_function("Missing", vec![])), Some(35));
+    }
+
+    #[test]
+    fn test_call_function_reports_raised_errors() {
+        let mut runtime = twice_runtime();
+        assert_eq!(runtime.call_function("Failing", vec![]).unwrap_err().number(), Some(11));
+        // The error does not stay raised for the next call
+        assert!(runtime.context_mut().err.is_none());
+        assert!(runtime.call_function("Twice", vec![crate::context::Value::Integer(1)]).is_ok());
+    }
+
+    #[test]
+    fn test_register_function_checks_arity_and_types() {
+        use crate::context::{DeclaredType, Value};
+        let mut runtime = twice_runtime();
+        runtime.register_function("Shout", &[DeclaredType::String, DeclaredType::Integer], |args, _| {
+            Ok(Value::String(args[0].as_string().to_uppercase().repeat(args[1].as_string().parse().unwrap())))
+        });
+        let mut shout = |args: Vec<Expression>| {
+            let call = Expression::FunctionCall { function: Box::new(Expression::Identifier("shout".into())), args };
+            crate::interpreter::evaluate_expression(&call, runtime.context_mut())
+        };
+        let hi = || Expression::String("hi".into());
+        let two = Expression::String("2".into());
+        assert_eq!(shout(vec![hi(), two]).unwrap().as_string(), "HIHI");
+        let number = |r: anyhow::Result<Value>| crate::host::excel::error_number(&r.unwrap_err());
+        assert_eq!(number(shout(vec![hi()])), Some(450));
+        assert_eq!(number(shout(vec![hi(), Expression::Integer(2), Expression::Integer(3)])), Some(450));
+        assert_eq!(number(shout(vec![hi(), Expression::String("many".into())])), Some(13));
+    }
+}
@@ -1,11 +1,13 @@
 
-use crate::ast::Statement;
+use crate::ast::{Body, Statement};
 use crate::ast::Expression;
 use crate::context::Context;
 use crate::interpreter::builtins::handle_builtin_call_bool;
 use crate::context::ScopeKind;
 use crate::interpreter::ControlFlow;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use crate::symbol::Symbol;
+use crate::vba_errors::{raise, raise_from, VbaErr};
 use super::frame::{Frame, FrameKind};
 
 /// The VBA execution virtual machine.
@@ -15,6 +17,7 @@ pub struct VbaVm {
     next_frame_id: usize,
     pub vm_state: VmState,             // Current execution state
     pub saved_error_frame: Option<Frame>,
+    byref_bindings: HashMap<usize, Vec<(String, String)>>, // Block frame id -> (param, caller var)
 }
 
 /// Execution state of the VM.
@@ -39,16 +42,15 @@ impl VbaVm {
             next_frame_id: 0,
             vm_state: VmState::Running,
             saved_error_frame: None,
+            byref_bindings: HashMap::new(),
         }
     }
 
     /// Push a new frame onto the stack.
-    pub fn push_frame(&mut self, kind: FrameKind, list_id: usize, statements: Vec<Statement>) {
+    pub fn push_frame(&mut self, kind: FrameKind, list_id: usize, statements: Body) {
         let depth = self.frames.len();
         let frame = Frame::new(self.next_frame_id, kind, list_id, statements, depth);
         self.next_frame_id += 1;
-        let _frame_id = frame.id;
-        let _kind_debug = format!("{:?}", frame.kind);
         self.frames.push_back(frame);
 
         // eprintln!("📍 VM: pushed frame #{} {} (depth={})", frame_id, kind_debug, depth);
@@ -143,6 +145,43 @@ impl VbaVm {
         self.saved_error_frame.take()
     }
 
+    /// VM with a given frame stack, for resuming a hibernated run.
+    pub(crate) fn from_frames(
+        frames: Vec<Frame>,
+        next_frame_id: usize,
+        byref_bindings: HashMap<usize, Vec<(String, String)>>,
+    ) -> Self {
+        VbaVm {
+            frames: frames.into(),
+            next_frame_id,
+            vm_state: VmState::Running,
+            saved_error_frame: None,
+            byref_bindings,
+        }
+    }
+
+    /// Frames from the bottom (entry procedure) to the top.
+    pub(crate) fn frames(&self) -> impl Iterator<Item = &Frame> {
+        self.frames.iter()
+    }
+
+    pub(crate) fn next_frame_id(&self) -> usize {
+        self.next_frame_id
+    }
+
+    pub(crate) fn byref_bindings(&self) -> &HashMap<usize, Vec<(String, String)>> {
+        &self.byref_bindings
+    }
+
+    /// Index of the frame of the running Sub/Function body; the loops and
+    /// With blocks it is in are above it
+    pub(crate) fn procedure_start(&self) -> usize {
+        self.frames
+            .iter()
+            .rposition(|f| matches!(f.kind, FrameKind::Block | FrameKind::Main))
+            .unwrap_or(0)
+    }
+
     
     
 }
@@ -153,7 +192,7 @@ pub fn execute_for_frame(
 ) -> Option<ControlFlow> {
     // Extract loop state from frame.kind
     if let FrameKind::For { counter, current_value, end_value, step } = &frame.kind {
-        let counter_name = counter.clone();
+        let counter_name = *counter;
         let current = *current_value;
         let end = *end_value;
         let step = *step;
@@ -170,7 +209,7 @@ pub fn execute_for_frame(
         }
         
         // Set counter variable
-        ctx.set_var(counter_name.clone(), crate::context::Value::Integer(current));
+        ctx.set_var(counter_name, crate::context::Value::Integer(current));
         
         // Execute next statement in body
         if frame.pc >= frame.statements.len() {
@@ -191,17 +230,29 @@ pub fn execute_for_frame(
 /// Execute a statement list using the VM.
 /// Called from interpreter/mod.rs via run_subroutine.
 pub fn run_statement_list_vm(
-    stmts: &[Statement],
+    stmts: impl Into<Body>,
     ctx: &mut Context,
     list_id: usize,
 ) -> ControlFlow {
     let mut vm = VbaVm::new();
-    vm.push_frame(FrameKind::Main, list_id, stmts.to_vec());
+    vm.push_frame(FrameKind::Main, list_id, stmts.into());
     // eprintln!("📋 Frame #0 statements:");
     // for (i, stmt) in stmts.iter().enumerate() {
     //     eprintln!("  [{}]: {:?}", i, stmt);
     // }
+    resume_vm(vm, ctx)
+}
+
+/// Run a VM until its frames are done, e.g. one rebuilt from a
+/// hibernation image (see `vm::hibernate`).
+pub(crate) fn resume_vm(mut vm: VbaVm, ctx: &mut Context) -> ControlFlow {
+    ctx.vm_nesting += 1;
+    let flow = run_vm(&mut vm, ctx);
+    ctx.vm_nesting -= 1;
+    flow
+}
 
+fn run_vm(vm: &mut VbaVm, ctx: &mut Context) -> ControlFlow {
     loop {
         // 1) Check if frames left
         if vm.frames.is_empty() {
@@ -209,6 +260,24 @@ pub fn run_statement_list_vm(
             return ControlFlow::Continue;
         }
 
+        // 1.5) Hibernate at a statement boundary. Only the outermost VM can
+        // be persisted; a request from a nested call waits until it returns.
+        // 1.4) A Stop under StopPolicy::Halt unwinds every VM
+        if ctx.halted {
+            return ControlFlow::ExitSub;
+        }
+
+        if ctx.hibernation_requested && ctx.vm_nesting == 1 && vm.vm_state == VmState::Running && !ctx.resume_valid {
+            ctx.hibernation_requested = false;
+            match super::hibernate::Hibernation::capture(vm, ctx) {
+                Ok(image) => {
+                    ctx.set_hibernation(image);
+                    return ControlFlow::ExitSub;
+                }
+                Err(e) => ctx.log(&format!("Hibernation failed: {}", e)),
+            }
+        }
+
         // 2) Handle error state
         if vm.is_in_error_state() {
             // eprintln!("💥 VM: error state active, searching for handler label");
@@ -217,17 +286,21 @@ pub fn run_statement_list_vm(
                 let label = label_str.to_string();
                 let mut found = false;
                 
-                if let Some(loc) = &ctx.resume_location {
-                    vm.save_error_frame(loc.frame_id);
-                }
-        
                 if !vm.frames.is_empty() {
                     for i in (0..vm.frames.len()).rev() {
-                        if let Some(target_pc) = find_label_in_statements(&vm.frames[i].statements, &label) {
+                        if let Some(target_pc) = vm.frames[i].find_label(&label) {
                             // eprintln!("✅ VM: found handler at frame index {}, pc={}", i, target_pc);
-                            
+
+                            // Keep the loop body or If arm that failed for
+                            // Resume Next; the handler's own frame stays put
+                            if let Some(loc) = &ctx.resume_location {
+                                if vm.frames.iter().position(|f| f.id == loc.frame_id).is_some_and(|at| at > i) {
+                                    vm.save_error_frame(loc.frame_id);
+                                }
+                            }
+
                             while vm.frames.len() > i + 1 {
-                                vm.pop_frame();
+                                leave_frame(vm, ctx);
                             }
                             
                             let handler_frame_id = vm.frames[i].id;
@@ -265,28 +338,61 @@ pub fn run_statement_list_vm(
         let current_stmt = match frame.current_statement() {
             Some(stmt) => stmt.clone(),
             None => {
-               // Frame is done
-                let frame_kind = vm.current_frame().map(|f| f.kind.clone());
-                vm.pop_frame();
-                
-                // ✅ If this was a Block (subroutine), pop scope
-                if matches!(frame_kind, Some(FrameKind::Block)) {
-                    ctx.pop_scope();
-                    // ✅ DON'T advance parent for Block frames - already advanced when pushed
-                } else {
-                    // ✅ For other frame types (For, Do), DO advance parent
-                    if let Some(parent) = vm.current_frame_mut() {
-                        parent.advance();
-                    }
-                }
+                // Body done: a loop goes round again or ends, anything else
+                // is popped
+                finish_frame(vm, ctx);
                 continue;
             }
         };
 
         // eprintln!("▶️ [frame #{}] pc={} stmt={:?}", frame.id, frame.pc, current_stmt);
 
+        let trace_pos = ctx.trace.is_some().then_some((frame.id, frame.depth, frame.pc));
+        let line = frame.current_line();
+
+        // 4.5) Breakpoints stop before their statement runs
+        if !std::mem::take(&mut ctx.skip_breakpoint)
+            && ctx.runtime_config.stop_policy != crate::runtime_config::StopPolicy::Ignore
+            && line.is_some_and(|l| ctx.runtime_config.breakpoints.contains(&l))
+        {
+            if crate::interpreter::stop(ctx, line, true) == ControlFlow::ExitSub {
+                return ControlFlow::ExitSub;
+            }
+            // Paused: hibernate at 1.5 before running it, or run it if this
+            // VM cannot hibernate
+            ctx.skip_breakpoint = true;
+            continue;
+        }
+        crate::host::excel::engine::set_source_line(line);
+        crate::host::excel::async_backend::statement_boundary(ctx);
+        if let Some(coverage) = ctx.coverage.as_mut() {
+            coverage.hit(line);
+        }
+        let started = ctx.profiler.is_some().then(std::time::Instant::now);
+        let hook_pos = ctx.hooks.is_some().then_some((frame.id, frame.depth, frame.pc));
+        if let (Some(pos), Some(mut hooks)) = (hook_pos, ctx.hooks.take()) {
+            let action = hooks.0.on_statement_enter(&statement_site(&current_stmt, line), &frame_site(ctx, pos), ctx);
+            ctx.hooks = Some(hooks);
+            if action == crate::vm::HookAction::Halt {
+                ctx.log("Hook halted the run");
+                ctx.halted = true;
+                return ControlFlow::ExitSub;
+            }
+        }
+
         // 5) Execute statement
-        let flow = execute_statement_in_vm(&current_stmt, ctx, &mut vm);
+        let flow = execute_statement_in_vm(&current_stmt, ctx, vm);
+        if let (Some(pos), Some(mut hooks)) = (hook_pos, ctx.hooks.take()) {
+            hooks.0.on_statement_exit(&statement_site(&current_stmt, line), &frame_site(ctx, pos), ctx);
+            ctx.hooks = Some(hooks);
+        }
+        if let (Some(started), Some(line), Some(profiler)) = (started, line, ctx.profiler.as_mut()) {
+            profiler.line(line, started.elapsed());
+        }
+        if let (Some((id, depth, pc)), Some(mut recorder)) = (trace_pos, ctx.trace.take()) {
+            recorder.record(ctx, id, depth, pc, current_stmt.kind_name());
+            ctx.trace = Some(recorder);
+        }
         // eprintln!("  ↳ flow: {:?}", flow);
         // if ctx.err.is_some() {
         //     eprintln!("  ⚠️ ctx.err = {:?}", ctx.err);
@@ -298,6 +404,7 @@ pub fn run_statement_list_vm(
         if ctx.err.is_some() && flow == ControlFlow::Continue && !ctx.resume_valid {
             if ctx.on_error_mode == crate::context::OnErrorMode::GoTo {
                 if let Some(label) = ctx.on_error_label.clone() {
+                    ctx.unhandled_error = false;
                     let error_frame_id = vm.current_frame().map(|f| f.id).unwrap_or(0);
                     let error_pc = vm.current_frame().map(|f| f.pc).unwrap_or(0);
                     let parent_pc = if vm.frames.len() >= 2 {
@@ -330,6 +437,9 @@ pub fn run_statement_list_vm(
                 }
                 continue;
             }
+
+            // The caller was already advanced when its frame was pushed
+            ControlFlow::FrameReused => continue,
             
             ControlFlow::Continue => {
                 if let Some(frame) = vm.current_frame_mut() {
@@ -387,19 +497,22 @@ pub fn run_statement_list_vm(
                 }
                 
                 if let Some(frame) = vm.current_frame_mut() {
-                    if let Some(target_pc) = find_label_in_statements(&frame.statements, &label) {
+                    if let Some(target_pc) = frame.find_label(&label) {
                         // eprintln!("✅ VM: label '{}' found in current frame at pc={}", label, target_pc);
                         frame.jump_to(target_pc);
                         continue;
                     }
                 }
             
+                // Out of loops and If arms (their frames are dropped) but
+                // never out of the procedure; jumps into a loop were
+                // refused at the GoTo
                 let mut found = false;
-                for i in (0..vm.frames.len() - 1).rev() {
-                    if let Some(target_pc) = find_label_in_statements(&vm.frames[i].statements, &label) {
+                for i in (vm.procedure_start()..vm.frames.len() - 1).rev() {
+                    if let Some(target_pc) = vm.frames[i].find_label(&label) {
                         // eprintln!("✅ VM: label '{}' found in parent frame at pc={}", label, target_pc);
                         while vm.frames.len() > i + 1 {
-                            vm.pop_frame();
+                            leave_frame(vm, ctx);
                         }
                         vm.frames[i].jump_to(target_pc);
                         found = true;
@@ -407,6 +520,30 @@ pub fn run_statement_list_vm(
                     }
                 }
 
+                // Into an If arm that is not running: enter the arms on
+                // the way to the label as if their conditions held
+                if !found {
+                    for i in (vm.procedure_start()..vm.frames.len()).rev() {
+                        if let Some(path) = if_arms_to_label(&vm.frames[i].statements, &label) {
+                            while vm.frames.len() > i + 1 {
+                                leave_frame(vm, ctx);
+                            }
+                            for (pc, arm) in path {
+                                if let Some(frame) = vm.current_frame_mut() {
+                                    frame.jump_to(pc + 1);
+                                }
+                                vm.push_frame(FrameKind::If, vm.next_frame_id, arm);
+                            }
+                            if let Some(frame) = vm.current_frame_mut() {
+                                let target_pc = frame.find_label(&label).unwrap_or(0);
+                                frame.jump_to(target_pc);
+                            }
+                            found = true;
+                            break;
+                        }
+                    }
+                }
+
                 if !found {
                     // eprintln!("❌ VM: label '{}' not found in any frame, exiting", label);
                     return ControlFlow::GoToLabel(label);
@@ -414,10 +551,10 @@ pub fn run_statement_list_vm(
             }
             ControlFlow::ResumeNext => {
                 // eprintln!("🔄 VM: ResumeNext - resume_location={:?}", ctx.resume_location);
-                if let Some(loc) = &ctx.resume_location {
+                if let Some(loc) = ctx.resume_location.clone() {
                     if let Some(target_idx) = vm.frames.iter().position(|f| f.id == loc.frame_id) {
                         while vm.frames.len() > target_idx + 1 {
-                            vm.pop_frame();
+                            leave_frame(vm, ctx);
                         }
                         if let Some(frame) = vm.current_frame_mut() {
                             frame.jump_to(loc.pc + 1);
@@ -479,30 +616,23 @@ pub fn run_statement_list_vm(
                 }
             }
 
-            ControlFlow::ExitFor => {
-                // eprintln!("🚪 VM: ExitFor");
-                vm.pop_frame();
-                // if let Some(parent) = vm.current_frame_mut() {
-                //     parent.advance();
-                // }
+            // Leave the innermost For (or Do) and whatever sits inside it
+            ControlFlow::ExitFor | ControlFlow::ExitDo => {
+                exit_loop(vm, ctx, flow == ControlFlow::ExitFor);
                 continue;
             }
 
-            ControlFlow::ExitDo => {
-                // eprintln!("🚪 VM: ExitDo");
-                vm.pop_frame();
-                if let Some(parent) = vm.current_frame_mut() {
-                    parent.advance();
-                }
-            }
-
             ControlFlow::ExitSub | ControlFlow::ExitFunction | ControlFlow::ExitProperty => {
                 // eprintln!("🚪 VM: {:?}", flow);
-                 // Pop the current frame (the Sub/Function being exited)
-                vm.pop_frame();
-                
-                // Pop the scope
-                ctx.pop_scope();
+                // Pop the loops and With blocks the Exit sits in, then the
+                // frame of the Sub/Function being exited, and its scope
+                while let Some(popped) = leave_frame(vm, ctx) {
+                    if matches!(popped.kind, FrameKind::Block | FrameKind::Main) {
+                        let bindings = vm.byref_bindings.remove(&popped.id).unwrap_or_default();
+                        crate::interpreter::pop_call_scope(ctx, &bindings);
+                        break;
+                    }
+                }
                 
                 // If there are still frames, advance the parent and continue
                 if !vm.frames.is_empty() {
@@ -522,139 +652,137 @@ pub fn run_statement_list_vm(
             }
         }
 
-        // 7) Loop control logic
-        if !vm.frames.is_empty() {
-            let frame_kind = vm.current_frame().map(|f| f.kind.clone());
-            
-            match frame_kind {
-                Some(FrameKind::For { counter, current_value, end_value, step }) => {
-                    let body_len = vm.current_frame().unwrap().statements.len();
-                    let body_complete = vm.current_frame().unwrap().pc >= body_len;
-
-                    if body_complete {
-                        let should_continue = if step > 0 {
-                            current_value + step <= end_value
-                        } else {
-                            current_value + step >= end_value
-                        };
-
-                        if should_continue {
-                            if let Some(frame) = vm.current_frame_mut() {
-                                if let FrameKind::For { current_value: cv, step: s, .. } = &mut frame.kind {
-                                    *cv += *s;
-                                }
-                                frame.pc = 0;
-                            }
-                            ctx.set_var(counter.clone(), crate::context::Value::Integer(current_value + step));
-                        } else {
-                            vm.pop_frame();
-                            // if let Some(parent) = vm.current_frame_mut() {
-                            //     parent.advance();
-                            // }
-                        }
-                    }
-                }
+        // 7) A loop whose body just ended goes round again (or ends) now,
+        // before a hibernation image can capture it half-stepped
+        if vm.current_frame().is_some_and(Frame::is_done) {
+            finish_frame(vm, ctx);
+        }
+    }
+}
 
-                Some(FrameKind::Do { ref statement, first_iteration: _ }) => {
-                    let body_len = vm.current_frame().unwrap().statements.len();
-                    let at_start = vm.current_frame().unwrap().pc == 0;
-                    let body_complete = vm.current_frame().unwrap().pc >= body_len;
-
-                    // Pre-test: check condition at start (except first iteration)
-                    if !statement.test_at_end && at_start  {
-                        // eprintln!("  ↳ Do loop (pre-test): checking condition at start");
-                        match should_do_loop_continue(statement, ctx) {
-                            Ok(true) => {
-                                // eprintln!("     Condition true, continuing loop");
-                                // Mark first iteration as done
-                                if let Some(frame) = vm.current_frame_mut() {
-                                    if let FrameKind::Do { first_iteration: fi, .. } = &mut frame.kind {
-                                        *fi = false;
-                                    }
-                                }
-                            }
-                            Ok(false) => {
-                                // eprintln!("     Condition false, exiting loop");
-                                vm.pop_frame();
-                                if let Some(parent) = vm.current_frame_mut() {
-                                    parent.advance();
-                                }
-                                continue;
-                            }
-                            Err(e) => {
-                                // eprintln!("     Error evaluating condition: {}", e);
-                                ctx.err = Some(crate::context::ErrObject {
-                                    number: 13,
-                                    description: e,
-                                    source: "Interpreter".into(),
-                                });
-                            }
-                        }
-                    }
+/// Pop the top frame; a With block takes its object off the With stack
+fn leave_frame(vm: &mut VbaVm, ctx: &mut Context) -> Option<Frame> {
+    let frame = vm.pop_frame()?;
+    if matches!(frame.kind, FrameKind::With) {
+        ctx.with_stack.pop();
+    }
+    Some(frame)
+}
 
-                    // Body complete: check post-test condition or restart
-                    if body_complete {
-                        // eprintln!("  ↳ Do loop body complete");
-                        
-                        if statement.test_at_end {
-                            // Post-test: check condition after body
-                            match should_do_loop_continue(statement, ctx) {
-                                Ok(true) => {
-                                    // eprintln!("     Post-test condition true, restarting loop");
-                                    if let Some(frame) = vm.current_frame_mut() {
-                                        if let FrameKind::Do { first_iteration: fi, .. } = &mut frame.kind {
-                                            *fi = false;
-                                        }
-                                        frame.pc = 0;
-                                    }
-                                }
-                                Ok(false) => {
-                                    // eprintln!("     Post-test condition false, exiting loop");
-                                    vm.pop_frame();
-                                    if let Some(parent) = vm.current_frame_mut() {
-                                        parent.advance();
-                                    }
-                                }
-                                Err(e) => {
-                                    // eprintln!("     Error evaluating condition: {}", e);
-                                    ctx.err = Some(crate::context::ErrObject {
-                                        number: 13,
-                                        description: e,
-                                        source: "Interpreter".into(),
-                                    });
-                                }
-                            }
-                        } else {
-                            // ✅ FIX: Pre-test loop - check condition BEFORE restarting
-                            // eprintln!("     Pre-test loop body complete, checking condition before restart");
-                            match should_do_loop_continue(statement, ctx) {
-                                Ok(true) => {
-                                    // eprintln!("     Condition true, restarting loop");
-                                    if let Some(frame) = vm.current_frame_mut() {
-                                        frame.pc = 0;
-                                    }
-                                }
-                                Ok(false) => {
-                                    // eprintln!("     Condition false, exiting loop");
-                                    vm.pop_frame();
-                                    if let Some(parent) = vm.current_frame_mut() {
-                                        parent.advance();
-                                    }
-                                }
-                                Err(e) => {
-                                    // eprintln!("     Error evaluating condition: {}", e);
-                                    ctx.err = Some(crate::context::ErrObject {
-                                        number: 13,
-                                        description: e,
-                                        source: "Interpreter".into(),
-                                    });
-                                }
-                            }
-                        }
-                    }
+/// The current frame has run its last statement. A For steps its counter
+/// and a Do tests its condition, starting the body over or ending the
+/// loop; a Sub body leaves its scope. The parent was advanced when the
+/// frame was pushed, so it resumes after the loop or call as it is.
+fn finish_frame(vm: &mut VbaVm, ctx: &mut Context) {
+    let Some(frame) = vm.current_frame_mut() else { return };
+    match &mut frame.kind {
+        FrameKind::For { counter, current_value, end_value, step } => {
+            // Stepping past i64 range ends the loop instead of panicking
+            let next = current_value.checked_add(*step);
+            let again = match next {
+                Some(next) if *step > 0 => next <= *end_value,
+                Some(next) => next >= *end_value,
+                None => false,
+            };
+            // VBA leaves the counter one step past the end
+            if let Some(next) = next {
+                *current_value = next;
+                ctx.set_var(*counter, crate::context::Value::Integer(next));
+            }
+            if again {
+                frame.pc = 0;
+                return;
+            }
+        }
+        FrameKind::Do { statement, first_iteration } => {
+            // Pre-test loops were tested before entering, so both kinds
+            // test here, after each pass through the body
+            match should_do_loop_continue(statement, ctx) {
+                Ok(true) => {
+                    *first_iteration = false;
+                    frame.pc = 0;
+                    return;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    raise(ctx, VbaErr::TypeMismatch, e);
                 }
+            }
+        }
+        _ => {}
+    }
+    if let Some(popped) = leave_frame(vm, ctx) {
+        if matches!(popped.kind, FrameKind::Block) {
+            let bindings = vm.byref_bindings.remove(&popped.id).unwrap_or_default();
+            crate::interpreter::pop_call_scope(ctx, &bindings);
+        }
+    }
+}
+
+/// "For" or "Do" when `label` is not in any running body of the current
+/// procedure but inside a loop nested in one of them
+fn loop_holding_label(vm: &VbaVm, label: &str) -> Option<&'static str> {
+    let frames: Vec<&Frame> = vm.frames.iter().skip(vm.procedure_start()).collect();
+    if frames.iter().any(|f| f.find_label(label).is_some()) {
+        return None;
+    }
+    frames.iter().rev().find_map(|f| nested_loop_with_label(&f.statements, label))
+}
+
+fn nested_loop_with_label(stmts: &[Statement], label: &str) -> Option<&'static str> {
+    stmts.iter().find_map(|stmt| {
+        let body = stmt.child_bodies().into_iter().find(|b| holds_label(b, label))?;
+        match stmt {
+            Statement::For(_) => Some("For"),
+            Statement::DoWhile(_) => Some("Do"),
+            _ => nested_loop_with_label(body, label),
+        }
+    })
+}
 
-                _ => {}
+/// (index of the If, arm) for each If entered on the way from `stmts` to
+/// the arm defining `label`
+fn if_arms_to_label(stmts: &[Statement], label: &str) -> Option<Vec<(usize, Body)>> {
+    stmts.iter().enumerate().find_map(|(pc, stmt)| {
+        if !matches!(stmt, Statement::If { .. }) {
+            return None;
+        }
+        let arm = stmt.child_bodies().into_iter().find(|b| holds_label(b, label))?;
+        let mut path = vec![(pc, arm.clone())];
+        if !arm.iter().any(|s| matches!(s, Statement::Label(name) if name.eq_ignore_ascii_case(label))) {
+            path.extend(if_arms_to_label(arm, label)?);
+        }
+        Some(path)
+    })
+}
+
+fn holds_label(stmts: &[Statement], label: &str) -> bool {
+    stmts.iter().any(|stmt| match stmt {
+        Statement::Label(name) => name.eq_ignore_ascii_case(label),
+        _ => stmt.child_bodies().into_iter().any(|b| holds_label(b, label)),
+    })
+}
+
+/// Exit For / Exit Do: pop frames up to and including the innermost For
+/// (or Do) of the running procedure. Loops of another kind and With
+/// blocks in between go with it; the frame of the Sub itself does not.
+fn exit_loop(vm: &mut VbaVm, ctx: &mut Context, exit_for: bool) {
+    let target = vm
+        .frames
+        .iter()
+        .rev()
+        .take_while(|f| !matches!(f.kind, FrameKind::Block | FrameKind::Main))
+        .position(|f| matches!((&f.kind, exit_for), (FrameKind::For { .. }, true) | (FrameKind::Do { .. }, false)));
+    match target {
+        Some(depth) => {
+            for _ in 0..=depth {
+                leave_frame(vm, ctx);
+            }
+        }
+        // Not in such a loop: carry on with the next statement
+        None => {
+            if let Some(frame) = vm.current_frame_mut() {
+                frame.advance();
             }
         }
     }
@@ -670,6 +798,11 @@ fn execute_statement_in_vm(
     use crate::ast::Statement;
 
     match stmt {
+        Statement::Stop => {
+            let line = vm.current_frame().and_then(|f| f.current_line());
+            crate::interpreter::stop(ctx, line, false)
+        }
+
         Statement::For(for_stmt) => {
             // eprintln!("📍 execute_statement_in_vm: FOR arm");
             crate::vm::runtime::handle_for_statement(for_stmt, ctx, vm)
@@ -691,6 +824,17 @@ fn execute_statement_in_vm(
             handle_with_statement(object, body, ctx, vm)
         }
 
+        Statement::GoTo { label } => {
+            // VBA only notices a jump into a For at its Next (error 92);
+            // the loop's frame would be missing here, so refuse the jump
+            if let Some(kind) = loop_holding_label(vm, label) {
+                raise(ctx, VbaErr::ForLoopNotInitialized, format!("GoTo {} jumps into a {} loop", label, kind));
+                let pc = vm.current_frame().map(|f| f.pc).unwrap_or(0);
+                return crate::interpreter::maybe_handle_error(ctx, pc).unwrap_or(ControlFlow::Continue);
+            }
+            ControlFlow::GoToLabel(label.clone())
+        }
+
         // For all other statements, delegate to existing execute_statement
         _ =>{
             // eprintln!("📍 execute_statement_in_vm: delegating to interpreter");
@@ -724,11 +868,7 @@ fn handle_do_statement(
             }
             Err(e) => {
                 // eprintln!("   Error evaluating condition: {}", e);
-                ctx.err = Some(crate::context::ErrObject {
-                    number: 13,
-                    description: e,
-                    source: "Interpreter".into(),
-                });
+                raise(ctx, VbaErr::TypeMismatch, e);
                 return ControlFlow::Continue;
             }
         }
@@ -754,7 +894,7 @@ fn should_do_loop_continue(
     
     match &do_stmt.condition {
         Some(cond_expr) => {
-            match crate::interpreter::evaluate_expression(cond_expr, ctx) {
+            match do_stmt.condition_compiled.eval(cond_expr, ctx) {
                 Ok(val) => {
                     let truthy = is_truthy(&val);
                     match do_stmt.condition_type {
@@ -773,7 +913,7 @@ fn should_do_loop_continue(
 /// Handle With block execution
 fn handle_with_statement(
     object: &crate::ast::Expression,
-    body: &[Statement],
+    body: &Body,
     ctx: &mut Context,
     vm: &mut VbaVm,
 ) -> ControlFlow {
@@ -785,7 +925,7 @@ fn handle_with_statement(
             
             // Push a new frame for the With block body
             let list_id = vm.next_frame_id;
-            vm.push_frame(FrameKind::With, list_id, body.to_vec());
+            vm.push_frame(FrameKind::With, list_id, body.clone());
             
             // The With object will be popped when the frame completes
             // Return FramePushed so parent advances but new frame doesn't skip first statement
@@ -793,11 +933,7 @@ fn handle_with_statement(
         }
         Err(e) => {
             let pc = vm.current_frame().map(|f| f.pc).unwrap_or(0);
-            ctx.err = Some(crate::context::ErrObject {
-                number: 91,
-                description: format!("With object evaluation failed: {}", e),
-                source: "VM".into(),
-            });
+            raise_from(ctx, &e, VbaErr::ObjectNotSet);
             // Simple error handling - just continue
             ControlFlow::Continue
         }
@@ -807,12 +943,16 @@ fn handle_with_statement(
 /// Create an If block frame
 fn handle_if_statement(
     condition: &crate::ast::Expression,
-    then_branch: &[Statement],
-    else_if: &[(crate::ast::Expression, Vec<Statement>)],
-    else_branch: &[Statement],
+    then_branch: &Body,
+    else_if: &[(crate::ast::Expression, Body)],
+    else_branch: &Body,
     ctx: &mut Context,
     vm: &mut VbaVm,
 ) -> ControlFlow {
+    // Line of this If, for branch coverage; read before any condition can
+    // call into other code
+    let if_line = ctx.coverage.as_ref().and_then(|c| c.current_line());
+
     // eprintln!("📍 If statement: evaluating condition");
     // eprintln!("   Condition: {:?}", condition);
     // Evaluate the main condition
@@ -826,58 +966,52 @@ fn handle_if_statement(
             if is_true {
                 // eprintln!("   Executing then_branch with {} statements", then_branch.len());
                 // Execute then branch
-                for stmt in then_branch {
-                    let flow = execute_statement_in_vm(stmt, ctx, vm);
-                    if flow != ControlFlow::Continue {
-                        return flow;
-                    }
-                }
-                return ControlFlow::Continue;
+                return run_if_branch(then_branch, if_line.map(|l| (l, 0)), ctx, vm);
             }
             
             // Check else-if conditions
-            for (elseif_cond, elseif_stmts) in else_if {
+            for (arm, (elseif_cond, elseif_stmts)) in else_if.iter().enumerate() {
                 let elseif_result = crate::interpreter::evaluate_expression(elseif_cond, ctx);
                 if let Ok(elseif_val) = elseif_result {
                     if is_truthy(&elseif_val) {
                         // eprintln!("   Executing else-if branch");
-                        for stmt in elseif_stmts {
-                            let flow = execute_statement_in_vm(stmt, ctx, vm);
-                            if flow != ControlFlow::Continue {
-                                return flow;
-                            }
-                        }
-                        return ControlFlow::Continue;
+                        return run_if_branch(elseif_stmts, if_line.map(|l| (l, arm + 1)), ctx, vm);
                     }
                 }
             }
             
-            // Execute else branch if present
-            if !else_branch.is_empty() {
-                // eprintln!("   Executing else_branch with {} statements", else_branch.len());
-                for stmt in else_branch {
-                    let flow = execute_statement_in_vm(stmt, ctx, vm);
-                    if flow != ControlFlow::Continue {
-                        return flow;
-                    }
-                }
-            }
-            
-            ControlFlow::Continue
+            // Execute else branch (also counts the fall-through for coverage)
+            // eprintln!("   Executing else_branch with {} statements", else_branch.len());
+            run_if_branch(else_branch, if_line.map(|l| (l, else_if.len() + 1)), ctx, vm)
         }
         Err(e) => {
             // eprintln!("   Error evaluating condition: {}", e);
             // Set error in context
-            ctx.err = Some(crate::context::ErrObject {
-                number: 13,
-                description: e.to_string(),
-                source: "Interpreter".into(),
-            });
+            raise_from(ctx, &e, VbaErr::TypeMismatch);
             ControlFlow::Continue
         }
     }
 }
 
+/// Run one arm of an If in a frame of its own, so GoTo, Resume and Exit
+/// unwind it like any loop body. `arm` is (line of the If, arm index)
+/// when coverage is being collected.
+fn run_if_branch(
+    branch: &Body,
+    arm: Option<(usize, usize)>,
+    ctx: &mut Context,
+    vm: &mut VbaVm,
+) -> ControlFlow {
+    if let (Some((line, arm)), Some(coverage)) = (arm, ctx.coverage.as_mut()) {
+        coverage.branch(line, arm);
+    }
+    if branch.is_empty() {
+        return ControlFlow::Continue;
+    }
+    vm.push_frame(FrameKind::If, vm.next_frame_id, branch.clone());
+    ControlFlow::FramePushed
+}
+
 /// Helper function to check if a value is truthy
 fn is_truthy(v: &crate::context::Value) -> bool {
     use crate::context::Value;
@@ -905,47 +1039,6 @@ fn is_truthy(v: &crate::context::Value) -> bool {
 }
 
 
-/// Find a label in a frame's statements.
-fn find_label_in_frame(frame: &Frame, label: &str) -> Option<usize> {
-    find_label_in_statements(&frame.statements, label)
-}
-
-/// Find a label in a statement list.
-fn find_label_in_statements(stmts: &[Statement], label: &str) -> Option<usize> {
-    let target = label.to_ascii_lowercase();
-
-    // 1) Exact (case-insensitive) match first – the correct / future path
-    if let Some(idx) = stmts.iter().enumerate().find_map(|(idx, stmt)| {
-        if let Statement::Label(name) = stmt {
-            if name.to_ascii_lowercase() == target {
-                return Some(idx);
-            }
-        }
-        None
-    }) {
-        return Some(idx);
-    }
-
-    // 2) Fallback: suffix match to work around labels like "Point" for "ExitPoint"
-    let mut fallback_idx: Option<usize> = None;
-
-    for (idx, stmt) in stmts.iter().enumerate() {
-        if let Statement::Label(name) = stmt {
-            let name_lower = name.to_ascii_lowercase();
-            if target.ends_with(&name_lower) {
-                // If we already have a fallback, it's ambiguous → bail
-                if fallback_idx.is_some() {
-                    // eprintln!("⚠️ Ambiguous label match: target='{}', candidate='{}'", label, name);
-                    return None;
-                }
-                fallback_idx = Some(idx);
-            }
-        }
-    }
-
-    fallback_idx
-}
-
 // In vm/runtime.rs, add a helper that is called from execute_statement_in_vm:
 
 pub fn handle_for_statement(
@@ -976,10 +1069,28 @@ pub fn handle_for_statement(
         .unwrap_or(0);
     let step_int = crate::interpreter::value_to_integer(&step_expr).unwrap_or(1);
 
+    if step_int == 0 {
+        raise(ctx, VbaErr::Overflow, "For Step cannot be zero");
+        return ControlFlow::Continue;
+    }
+
+    if let Err(e) = ctx.create_implicit(&for_stmt.counter) {
+        raise(ctx, VbaErr::VariableNotDefined, e);
+        let pc = vm.current_frame().map(|f| f.pc).unwrap_or(0);
+        return crate::interpreter::maybe_handle_error(ctx, pc).unwrap_or(ControlFlow::Continue);
+    }
+    ctx.set_var(for_stmt.counter.as_str(), Value::Integer(start_int));
+
+    // Zero-iteration loop: counter is set, body never runs
+    let enters = if step_int > 0 { start_int <= end_int } else { start_int >= end_int };
+    if !enters {
+        return ControlFlow::Continue;
+    }
+
     // Push For frame
     vm.push_frame(
         FrameKind::For {
-            counter: for_stmt.counter.clone(),
+            counter: Symbol::intern(&for_stmt.counter),
             current_value: start_int,
             end_value: end_int,
             step: step_int,
@@ -987,26 +1098,51 @@ pub fn handle_for_statement(
         /* list_id */ vm.next_frame_id, // or better: list_id passed into run_statement_list_vm
         for_stmt.body.clone(),
     );
-    ctx.set_var(for_stmt.counter.clone(), Value::Integer(start_int));
 
     // eprintln!("📍 VM handle_for_statement: returning FramePushed");
     ControlFlow::FramePushed
 }
+fn statement_site(stmt: &Statement, line: Option<usize>) -> crate::vm::StatementSite<'_> {
+    crate::vm::StatementSite { line, kind: stmt.kind_name() }
+}
+
+fn frame_site(ctx: &Context, (id, depth, pc): (usize, usize, usize)) -> crate::vm::FrameSite<'_> {
+    crate::vm::FrameSite { id, depth, pc, procedure: ctx.current_procedure() }
+}
+
 fn handle_call_statement(
     function: &str,
     args: &[Expression],
     ctx: &mut Context,
     vm: &mut VbaVm,
 ) -> ControlFlow {
-    // Handle builtins
-    // eprintln!("📍 Call statement: ");
-    
-    if handle_builtin_call_bool(function, args, ctx) {
-        return ControlFlow::Continue;
+    // Declare'd DLL procedures (host::dll), then builtins
+    let handled = match crate::host::dll::call_declared(function, args, ctx) {
+        Some(result) => result.map(|_| true),
+        None => handle_builtin_call_bool(function, args, ctx),
+    };
+    match handled {
+        Ok(true) => return ControlFlow::Continue,
+        Ok(false) => {}
+        Err(e) => {
+            raise_from(ctx, &e, VbaErr::DllLoadError);
+            let pc = vm.current_frame().map(|f| f.pc).unwrap_or(0);
+            return crate::interpreter::maybe_handle_error(ctx, pc).unwrap_or(ControlFlow::Continue);
+        }
     }
 
+    // `Module.Proc`, or the caller's module's Proc when several define it
+    let function = &match ctx.resolve_procedure(function) {
+        Ok(key) => key.unwrap_or_else(|| function.to_string()),
+        Err(e) => {
+            raise_from(ctx, &e, VbaErr::SubOrFunctionNotDefined);
+            let pc = vm.current_frame().map(|f| f.pc).unwrap_or(0);
+            return crate::interpreter::maybe_handle_error(ctx, pc).unwrap_or(ControlFlow::Continue);
+        }
+    };
+
     // Get sub definition
-    let (params, body) = match ctx.subs.get(function).cloned() {
+    let (params, body) = match ctx.get_sub(function).cloned() {
         Some(pb) => pb,
         None => return ControlFlow::Continue,
     };
@@ -1020,13 +1156,44 @@ fn handle_call_statement(
         }
     }
 
-    // Push scope
-    ctx.push_scope(function.to_string(), ScopeKind::Subroutine);
+    // With RuntimeConfig::tail_calls, a call ending its caller's body takes
+    // over the caller's frame and scope, so tail recursion (a folder walk
+    // calling itself last) runs at constant depth. ByRef arguments naming the caller's ByRef parameters
+    // are forwarded to the variables those were bound to.
+    let mut bindings = crate::interpreter::byref_bindings(&params, args);
+    let reused = tail_call_frame(vm, ctx);
+    if let Some(frame_id) = reused {
+        let outer = vm.byref_bindings.remove(&frame_id).unwrap_or_default();
+        bindings = forward_bindings(bindings, &outer, ctx);
+        crate::interpreter::pop_call_scope(ctx, &outer);
+    }
+
+    // Push scope; past the call depth limit this raises error 28
+    if let Err(e) = ctx.enter_procedure(function.to_string(), ScopeKind::Subroutine) {
+        raise_from(ctx, &e, VbaErr::OutOfStackSpace);
+        let pc = vm.current_frame().map(|f| f.pc).unwrap_or(0);
+        return crate::interpreter::maybe_handle_error(ctx, pc).unwrap_or(ControlFlow::Continue);
+    }
+
+    if !bindings.is_empty() {
+        vm.byref_bindings.insert(reused.unwrap_or(vm.next_frame_id), bindings);
+    }
     
     // Bind parameters
     for (param, val) in params.iter().zip(arg_vals) {
         ctx.declare_variable(&param.name);  // Use param.name for Parameter struct
-        ctx.declare_local(param.name.clone(), val);
+        ctx.declare_local(param.name.as_str(), val);
+    }
+
+    if let Some(frame_id) = reused {
+        // Drop the If arms the call ends
+        while vm.current_frame().is_some_and(|f| f.id != frame_id) {
+            leave_frame(vm, ctx);
+        }
+        if let Some(frame) = vm.current_frame_mut() {
+            frame.reuse(body);
+        }
+        return ControlFlow::FrameReused;
     }
 
     // ✅ Push VM frame for subroutine
@@ -1034,3 +1201,386 @@ fn handle_call_statement(
     // eprintln!("📍 VM handle_call_statement: returning FramePushed");
     ControlFlow::FramePushed
 }
+
+/// Id of the frame a call can take over: the calling Sub's own frame, when
+/// the call is its last statement (perhaps inside If arms that are its
+/// last statements) and no error handler or Resume could come back to it
+fn tail_call_frame(vm: &VbaVm, ctx: &Context) -> Option<usize> {
+    if !ctx.runtime_config.tail_calls {
+        return None;
+    }
+    let no_handler = ctx.on_error_mode == crate::context::OnErrorMode::None && !ctx.resume_valid && ctx.err.is_none();
+    if !no_handler {
+        return None;
+    }
+    // An If arm's parent has already moved past the If
+    let mut frames = vm.frames.iter().rev();
+    let top = frames.next()?;
+    if top.pc + 1 != top.statements.len() {
+        return None;
+    }
+    std::iter::once(top)
+        .chain(frames.take_while(|f| f.is_done()))
+        .take_while(|f| matches!(f.kind, FrameKind::If | FrameKind::Block))
+        .find(|f| matches!(f.kind, FrameKind::Block))
+        .map(|f| f.id)
+}
+
+/// ByRef bindings of a tail call, rebound past the caller being replaced:
+/// an argument that is one of the caller's ByRef parameters binds to what
+/// that parameter was bound to, one of its other locals is dropped (it goes
+/// away with the caller), anything else is kept.
+fn forward_bindings(bindings: Vec<(String, String)>, outer: &[(String, String)], ctx: &Context) -> Vec<(String, String)> {
+    bindings
+        .into_iter()
+        .filter_map(|(param, var)| {
+            if let Some((_, target)) = outer.iter().find(|(p, _)| p.eq_ignore_ascii_case(&var)) {
+                return Some((param, target.clone()));
+            }
+            ctx.resolve_local(Symbol::intern(&var)).is_none().then_some((param, var))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{AssignmentTarget, DoWhileConditionType, DoWhileStatement, ExitType, ForStatement};
+
+    fn id(name: &str) -> Expression {
+        Expression::Identifier(name.into())
+    }
+
+    fn op(left: Expression, op: &str, right: Expression) -> Expression {
+        Expression::BinaryOp { left: Box::new(left), op: op.into(), right: Box::new(right) }
+    }
+
+    fn assign(name: &str, rvalue: Expression) -> Statement {
+        Statement::Assignment { lvalue: AssignmentTarget::Identifier(name.into()), rvalue, rvalue_compiled: Default::default() }
+    }
+
+    fn bump(name: &str) -> Statement {
+        assign(name, op(id(name), "+", Expression::Integer(1)))
+    }
+
+    fn when(condition: Expression, then: Statement) -> Statement {
+        Statement::If { condition, then_branch: vec![then].into(), else_if: vec![], else_branch: Vec::new().into() }
+    }
+
+    fn for_loop(counter: &str, to: i64, body: Vec<Statement>) -> Statement {
+        Statement::For(ForStatement {
+            counter: counter.into(),
+            start: Expression::Integer(1),
+            end: Expression::Integer(to),
+            step: None,
+            body: body.into(),
+            next_counter: None,
+        })
+    }
+
+    fn do_loop(condition: Option<Expression>, body: Vec<Statement>) -> Statement {
+        Statement::DoWhile(DoWhileStatement {
+            condition_type: if condition.is_some() { DoWhileConditionType::While } else { DoWhileConditionType::Infinite },
+            condition,
+            test_at_end: false,
+            body: body.into(),
+            condition_compiled: Default::default(),
+        })
+    }
+
+    fn run(body: Vec<Statement>, vars: &[&str]) -> Vec<String> {
+        let mut ctx = Context::new();
+        for var in vars {
+            ctx.set_var(*var, crate::context::Value::Integer(0));
+        }
+        run_statement_list_vm(body, &mut ctx, 0);
+        assert!(ctx.err.is_none(), "{:?}", ctx.err);
+        vars.iter().map(|v| ctx.get_var(*v).map(|v| v.as_string()).unwrap_or_default()).collect()
+    }
+
+    // Frames run the procedure's and the loop's own statement list, so a
+    // call or an iteration never copies the AST
+    #[test]
+    fn test_frames_share_procedure_and_loop_bodies() {
+        let mut ctx = Context::new();
+        ctx.define_sub("SharedBody", vec![], vec![bump("SharedHits")]);
+        let mut vm = VbaVm::new();
+        for _ in 0..2 {
+            assert!(matches!(handle_call_statement("SharedBody", &[], &mut ctx, &mut vm), ControlFlow::FramePushed));
+            assert!(vm.current_frame().unwrap().statements.ptr_eq(&ctx.get_sub("SharedBody").unwrap().1));
+        }
+
+        let Statement::For(for_stmt) = for_loop("SharedI", 3, vec![bump("SharedHits")]) else { unreachable!() };
+        handle_for_statement(&for_stmt, &mut ctx, &mut vm);
+        assert!(vm.current_frame().unwrap().statements.ptr_eq(&for_stmt.body));
+    }
+
+    #[test]
+    fn test_nested_loops_resume_after_inner_loop() {
+        let body = vec![
+            for_loop("LoopI", 3, vec![for_loop("LoopJ", 2, vec![bump("LoopHits")])]),
+            do_loop(Some(op(id("LoopK"), "<", Expression::Integer(4))), vec![bump("LoopK")]),
+            bump("LoopAfter"),
+        ];
+        assert_eq!(run(body, &["LoopHits", "LoopK", "LoopAfter"]), ["6", "4", "1"]);
+    }
+
+    #[test]
+    fn test_exit_leaves_the_nearest_loop_of_its_kind() {
+        // For / Do / For: Exit Do leaves the inner For and the Do
+        let exit_do = vec![
+            for_loop("ExitI", 3, vec![
+                do_loop(None, vec![
+                    for_loop("ExitJ", 5, vec![
+                        when(op(id("ExitJ"), "=", Expression::Integer(2)), Statement::Exit(ExitType::Do)),
+                        bump("ExitHits"),
+                    ]),
+                    bump("ExitNever"),
+                ]),
+                bump("ExitAfterDo"),
+            ]),
+            bump("ExitDone"),
+        ];
+        assert_eq!(run(exit_do, &["ExitHits", "ExitNever", "ExitAfterDo", "ExitDone"]), ["3", "0", "3", "1"]);
+
+        // Do / For / Do: Exit For leaves the inner Do and the For
+        let exit_for = vec![
+            do_loop(Some(op(id("OuterRuns"), "<", Expression::Integer(2))), vec![
+                bump("OuterRuns"),
+                for_loop("InnerI", 3, vec![
+                    do_loop(None, vec![
+                        bump("InnerHits"),
+                        Statement::Exit(ExitType::For),
+                    ]),
+                    bump("InnerNever"),
+                ]),
+                bump("OuterAfterFor"),
+            ]),
+        ];
+        assert_eq!(run(exit_for, &["OuterRuns", "InnerHits", "InnerNever", "OuterAfterFor"]), ["2", "2", "0", "2"]);
+    }
+
+    #[test]
+    fn test_goto_out_of_nested_loops() {
+        let body = vec![
+            for_loop("FindI", 3, vec![for_loop("FindJ", 3, vec![for_loop("FindK", 3, vec![
+                when(
+                    op(op(op(id("FindI"), "*", id("FindJ")), "*", id("FindK")), "=", Expression::Integer(12)),
+                    Statement::GoTo { label: "Found".into() },
+                ),
+            ])])]),
+            bump("FindMissed"),
+            Statement::Label("Found".into()),
+            assign("FindAt", op(op(op(id("FindI"), "*", Expression::Integer(100)), "+", op(id("FindJ"), "*", Expression::Integer(10))), "+", id("FindK"))),
+            bump("FindAfter"),
+        ];
+        assert_eq!(run(body, &["FindMissed", "FindAt", "FindAfter"]), ["0", "223", "1"]);
+    }
+
+    #[test]
+    fn test_goto_to_enclosing_loop_body_keeps_it_running() {
+        // For i: For j: If j = 2 Then GoTo NextI; hits += 1: Next j
+        //        NextI: after += 1: Next i
+        let body = vec![
+            for_loop("SkipI", 3, vec![
+                for_loop("SkipJ", 3, vec![
+                    when(op(id("SkipJ"), "=", Expression::Integer(2)), Statement::GoTo { label: "NextI".into() }),
+                    bump("SkipHits"),
+                ]),
+                Statement::Label("NextI".into()),
+                bump("SkipAfter"),
+            ]),
+            bump("SkipDone"),
+        ];
+        assert_eq!(run(body, &["SkipHits", "SkipAfter", "SkipDone", "SkipI"]), ["3", "3", "1", "4"]);
+    }
+
+    #[test]
+    fn test_goto_into_loop_body_is_refused() {
+        for lp in [
+            for_loop("IntoI", 2, vec![Statement::Label("Inside".into()), bump("IntoHits")]),
+            do_loop(None, vec![Statement::Label("Inside".into()), bump("IntoHits")]),
+        ] {
+            let mut ctx = Context::new();
+            ctx.set_var("IntoHits", crate::context::Value::Integer(0));
+            run_statement_list_vm(vec![Statement::GoTo { label: "Inside".into() }, lp], &mut ctx, 0);
+            assert_eq!(ctx.err.as_ref().map(|e| e.number), Some(92));
+            assert_eq!(ctx.get_var("IntoHits").map(|v| v.as_string()).as_deref(), Some("0"));
+        }
+    }
+
+    #[test]
+    fn test_goto_into_if_arm() {
+        let body = vec![
+            Statement::GoTo { label: "Inner".into() },
+            Statement::If {
+                condition: Expression::Boolean(false),
+                then_branch: vec![bump("ArmNever"), Statement::Label("Inner".into()), bump("ArmHit")].into(),
+                else_if: vec![],
+                else_branch: vec![bump("ArmElse")].into(),
+            },
+            bump("ArmAfter"),
+        ];
+        assert_eq!(run(body, &["ArmNever", "ArmHit", "ArmElse", "ArmAfter"]), ["0", "1", "0", "1"]);
+    }
+
+    #[test]
+    fn test_resume_next_inside_if_arm() {
+        use crate::ast::{OnErrorKind, ResumeKind};
+        // On Error GoTo Handler
+        // If True Then x = 1 / 0: hit += 1
+        // after += 1: Exit Sub
+        // Handler: Resume Next
+        let body = vec![
+            Statement::OnError(OnErrorKind::GoToLabel("Handler".into())),
+            Statement::If {
+                condition: Expression::Boolean(true),
+                then_branch: vec![assign("ResumeX", op(Expression::Integer(1), "/", Expression::Integer(0))), bump("ResumeHit")].into(),
+                else_if: vec![],
+                else_branch: Vec::new().into(),
+            },
+            bump("ResumeAfter"),
+            Statement::Exit(ExitType::Sub),
+            Statement::Label("Handler".into()),
+            Statement::Resume(ResumeKind::Next),
+        ];
+        assert_eq!(run(body, &["ResumeHit", "ResumeAfter"]), ["1", "1"]);
+    }
+
+    #[test]
+    fn test_with_block_members_dispatch_to_com_objects() {
+        use crate::context::Value;
+        use crate::host::ComObject;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        /// Records every method call as "Name(args)"
+        struct Recorder(Rc<RefCell<Vec<String>>>);
+
+        impl ComObject for Recorder {
+            fn get_property(&self, name: &str, _ctx: &mut Context) -> anyhow::Result<Value> {
+                match name {
+                    "Count" => Ok(Value::Integer(self.0.borrow().len() as i64)),
+                    _ => Err(anyhow::anyhow!("no property {}", name)),
+                }
+            }
+
+            fn set_property(&mut self, _name: &str, _value: Value, _ctx: &mut Context) -> anyhow::Result<()> {
+                Ok(())
+            }
+
+            fn call_method(&mut self, name: &str, args: &[Value], _ctx: &mut Context) -> anyhow::Result<Value> {
+                let args: Vec<String> = args.iter().map(Value::as_string).collect();
+                self.0.borrow_mut().push(format!("{}({})", name, args.join(",")));
+                Ok(Value::Empty)
+            }
+
+            fn type_name(&self) -> &str {
+                "Recorder"
+            }
+        }
+
+        let call = |method: &str, args: Vec<Expression>| Statement::Expression(Expression::WithMethodCall { method: method.into(), args });
+        let with = |object: &str, body: Vec<Statement>| Statement::With { object: id(object), body: body.into() };
+        // With WithTestOuter
+        //     .Post "a", 2
+        //     .Flush
+        //     With WithTestInner: .Post "b": End With
+        //     WithCount = .Count
+        //     .Post "c"
+        // End With
+        let body = vec![with(
+            "WithTestOuter",
+            vec![
+                call("Post", vec![Expression::String("a".into()), Expression::Integer(2)]),
+                Statement::Expression(Expression::WithMemberAccess { property: "Flush".into() }),
+                with("WithTestInner", vec![call("Post", vec![Expression::String("b".into())])]),
+                assign("WithCount", Expression::WithMemberAccess { property: "Count".into() }),
+                call("Post", vec![Expression::String("c".into())]),
+            ],
+        )];
+
+        for engine in [crate::runtime_config::ExecutionEngine::Vm, crate::runtime_config::ExecutionEngine::Interpreter] {
+            let outer = Rc::new(RefCell::new(Vec::new()));
+            let inner = Rc::new(RefCell::new(Vec::new()));
+            let mut ctx = Context::with_config(crate::runtime_config::RuntimeConfig::builder().engine(engine).build());
+            ctx.com_registry.register_global("WithTestOuter", Rc::new(RefCell::new(Recorder(outer.clone()))));
+            ctx.com_registry.register_global("WithTestInner", Rc::new(RefCell::new(Recorder(inner.clone()))));
+            match engine {
+                crate::runtime_config::ExecutionEngine::Vm => run_statement_list_vm(body.clone(), &mut ctx, 0),
+                crate::runtime_config::ExecutionEngine::Interpreter => crate::interpreter::execute_statement_list(&body, &mut ctx),
+            };
+
+            assert!(ctx.err.is_none(), "{:?}", ctx.err);
+            assert_eq!(*outer.borrow(), ["Post(a,2)", "Flush()", "Post(c)"], "{:?}", engine);
+            assert_eq!(*inner.borrow(), ["Post(b)"]);
+            assert_eq!(ctx.get_var("WithCount").unwrap().as_string(), "2");
+            assert!(ctx.with_stack.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_chained_member_access_resolves_every_link() {
+        let member = |obj: Expression, name: &str| Expression::PropertyAccess { obj: Box::new(obj), property: name.into() };
+        let call = |function: Expression, args: Vec<Expression>| Expression::FunctionCall { function: Box::new(function), args };
+        let int = Expression::Integer;
+
+        // ChainOffset = Workbooks(1).Worksheets(1).Range("B2").Offset(1, 2).Address
+        let sheet = call(member(call(id("Workbooks"), vec![int(1)]), "Worksheets"), vec![int(1)]);
+        let offset = call(member(call(member(sheet, "Range"), vec![Expression::String("B2".into())]), "Offset"), vec![int(1), int(2)]);
+        // ChainCells = Application.Sheets(1).Cells(2, 3).Resize(2, 2).Cells(2, 2).Address
+        let sheet = call(member(id("Application"), "Sheets"), vec![int(1)]);
+        let cells = call(member(sheet, "Cells"), vec![int(2), int(3)]);
+        let cells = call(member(call(member(cells, "Resize"), vec![int(2), int(2)]), "Cells"), vec![int(2), int(2)]);
+        let body = vec![
+            assign("ChainOffset", member(offset, "Address")),
+            assign("ChainCells", member(cells, "Address")),
+            assign("ChainCount", member(member(id("ThisWorkbook"), "Worksheets"), "Count")),
+        ];
+        let missing = vec![assign("ChainMissing", member(call(id("Worksheets"), vec![int(99)]), "Name"))];
+
+        for engine in [crate::runtime_config::ExecutionEngine::Vm, crate::runtime_config::ExecutionEngine::Interpreter] {
+            let mut ctx = Context::with_config(crate::runtime_config::RuntimeConfig::builder().engine(engine).build());
+            let run = |body: &[Statement], ctx: &mut Context| match engine {
+                crate::runtime_config::ExecutionEngine::Vm => run_statement_list_vm(body.to_vec(), ctx, 0),
+                crate::runtime_config::ExecutionEngine::Interpreter => crate::interpreter::execute_statement_list(body, ctx),
+            };
+            run(&body, &mut ctx);
+            assert!(ctx.err.is_none(), "{:?}: {:?}", engine, ctx.err);
+            assert_eq!(ctx.get_var("ChainOffset").unwrap().as_string(), "$D$3");
+            assert_eq!(ctx.get_var("ChainCells").unwrap().as_string(), "$D$3");
+            assert!(ctx.get_var("ChainCount").unwrap().as_integer() >= Some(1));
+
+            run(&missing, &mut ctx);
+            assert_eq!(ctx.err.as_ref().map(|e| e.number), Some(9), "{:?}", engine);
+        }
+    }
+
+    #[test]
+    fn test_let_needs_a_value_and_set_an_object() {
+        let set = |target: &str, expr: Expression| Statement::Set { target: target.into(), expr };
+        let range = Expression::FunctionCall { function: Box::new(id("Range")), args: vec![Expression::String("B2".into())] };
+        // Set Target = Range("B2"): Set Target = Nothing: Copy = Target (91)
+        // Set Target = 5 (424)
+        let cases = [
+            (vec![set("SetTarget", range), set("SetTarget", id("Nothing"))], None),
+            (vec![set("SetTarget", id("Nothing")), assign("SetCopy", id("SetTarget"))], Some(91)),
+            (vec![set("SetTarget", Expression::Integer(5))], Some(424)),
+        ];
+
+        for engine in [crate::runtime_config::ExecutionEngine::Vm, crate::runtime_config::ExecutionEngine::Interpreter] {
+            for (body, error) in &cases {
+                let mut ctx = Context::with_config(crate::runtime_config::RuntimeConfig::builder().engine(engine).build());
+                match engine {
+                    crate::runtime_config::ExecutionEngine::Vm => run_statement_list_vm(body.clone(), &mut ctx, 0),
+                    crate::runtime_config::ExecutionEngine::Interpreter => crate::interpreter::execute_statement_list(body, &mut ctx),
+                };
+                assert_eq!(ctx.err.as_ref().map(|e| e.number), *error, "{:?} {:?}", engine, body);
+                assert!(ctx.get_var("SetCopy").is_none());
+            }
+        }
+        let mut ctx = Context::new();
+        crate::interpreter::execute_statement_list(&cases[0].0, &mut ctx);
+        assert!(matches!(ctx.get_var("SetTarget"), Some(crate::context::Value::Object(None))));
+    }
+}
@@ -0,0 +1,401 @@
+// vba-utils/src/vm/telemetry.rs
+
+//! Run IDs and spans for operators running macros as a service.
+//!
+//! Every Context has a run ID ([`Context::run_id`]): 32 hex digits, usable
+//! as an OpenTelemetry trace ID, or the caller's own request ID when
+//! `RuntimeConfig::run_id` is set. Traces ([`crate::vm::Trace`]) and spans
+//! carry it.
+//!
+//! With `ctx.start_telemetry()` the run records a [`Span`] for
+//!
+//! - the run itself, the root of all others
+//! - parsing done through [`Context::parse_source`]
+//! - each Sub/Function call (via the scope stack, so both engines are
+//!   covered)
+//! - each call leaving the macro: Declare'd DLL procedures, host functions
+//!   and methods of host objects
+//!
+//! Lines the macro logs (`Context::log`) are attached to the innermost open
+//! span as events. `ctx.take_telemetry()` returns a [`TelemetryReport`];
+//! with the `otel` feature, [`TelemetryReport::to_otlp_json`] encodes it as
+//! the OTLP/JSON body an OpenTelemetry collector accepts on `/v1/traces`.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+use anyhow::Result;
+
+use crate::context::Context;
+use crate::vm::HostCallKind;
+
+/// What a span measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    /// From `start_telemetry` to `take_telemetry`
+    Run,
+    Parse,
+    /// A Sub or Function call
+    Procedure,
+    /// A call leaving the macro
+    HostCall(HostCallKind),
+}
+
+/// One timed operation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    /// 16 hex digits, unique within the run
+    pub span_id: String,
+    /// The span that was open when this one started; None for the run
+    pub parent_span_id: Option<String>,
+    /// Procedure name, `library!entry`, `Type.Method`, ...
+    pub name: String,
+    pub kind: SpanKind,
+    pub start: SystemTime,
+    pub end: SystemTime,
+    /// The error a parse or host call failed with
+    pub error: Option<String>,
+    /// Lines logged while this was the innermost open span
+    pub events: Vec<(SystemTime, String)>,
+}
+
+/// Spans of a run, from `Context::take_telemetry`.
+#[derive(Debug, Clone, Default)]
+pub struct TelemetryReport {
+    pub run_id: String,
+    /// In the order they ended; the run span is last
+    pub spans: Vec<Span>,
+}
+
+impl TelemetryReport {
+    /// First span named `name` (case-insensitive)
+    pub fn span(&self, name: &str) -> Option<&Span> {
+        self.spans.iter().find(|s| s.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Spans whose parent is `span`
+    pub fn children<'a>(&'a self, span: &'a Span) -> impl Iterator<Item = &'a Span> + 'a {
+        self.spans.iter().filter(move |s| s.parent_span_id.as_ref() == Some(&span.span_id))
+    }
+
+    /// OTLP/JSON `ExportTraceServiceRequest` with the run as one trace
+    /// from `service_name`
+    #[cfg(feature = "otel")]
+    pub fn to_otlp_json(&self, service_name: &str) -> String {
+        let nanos = |t: SystemTime| t.duration_since(SystemTime::UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+        let attribute = |key: &str, value: &str| {
+            format!("{{\"key\": \"{}\", \"value\": {{\"stringValue\": \"{}\"}}}}", key, json_escape(value))
+        };
+        let spans: Vec<String> = self
+            .spans
+            .iter()
+            .map(|span| {
+                let (kind, vba_kind) = match span.kind {
+                    SpanKind::Run => (1, "run".to_string()),
+                    SpanKind::Parse => (1, "parse".to_string()),
+                    SpanKind::Procedure => (1, "procedure".to_string()),
+                    // SPAN_KIND_CLIENT: the work happens outside the macro
+                    SpanKind::HostCall(call) => (3, format!("host_call.{:?}", call).to_lowercase()),
+                };
+                let events: Vec<String> = span
+                    .events
+                    .iter()
+                    .map(|(time, message)| {
+                        format!(
+                            "{{\"timeUnixNano\": \"{}\", \"name\": \"log\", \"attributes\": [{}]}}",
+                            nanos(*time),
+                            attribute("message", message)
+                        )
+                    })
+                    .collect();
+                let parent = span
+                    .parent_span_id
+                    .as_ref()
+                    .map_or_else(String::new, |id| format!("\"parentSpanId\": \"{}\", ", id));
+                let status = match &span.error {
+                    Some(error) => format!("{{\"code\": 2, \"message\": \"{}\"}}", json_escape(error)),
+                    None => "{\"code\": 1}".to_string(),
+                };
+                format!(
+                    "{{\"traceId\": \"{}\", \"spanId\": \"{}\", {}\"name\": \"{}\", \"kind\": {}, \
+                     \"startTimeUnixNano\": \"{}\", \"endTimeUnixNano\": \"{}\", \"attributes\": [{}, {}], \
+                     \"events\": [{}], \"status\": {}}}",
+                    self.run_id,
+                    span.span_id,
+                    parent,
+                    json_escape(&span.name),
+                    kind,
+                    nanos(span.start),
+                    nanos(span.end),
+                    attribute("vba.run_id", &self.run_id),
+                    attribute("vba.span_kind", &vba_kind),
+                    events.join(", "),
+                    status
+                )
+            })
+            .collect();
+        format!(
+            "{{\"resourceSpans\": [{{\"resource\": {{\"attributes\": [{}]}}, \"scopeSpans\": [{{\"scope\": \
+             {{\"name\": \"vba-utils\", \"version\": \"{}\"}}, \"spans\": [{}]}}]}}]}}",
+            attribute("service.name", service_name),
+            env!("CARGO_PKG_VERSION"),
+            spans.join(", ")
+        )
+    }
+}
+
+#[cfg(feature = "otel")]
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// A fresh run ID: 32 hex digits, distinct across runs and processes
+pub fn new_run_id() -> String {
+    static RUNS: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+    let run = RUNS.fetch_add(1, Ordering::Relaxed);
+    let half = |salt: u8| {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(nanos);
+        hasher.write_u64(run);
+        hasher.write_u32(std::process::id());
+        hasher.write_u8(salt);
+        hasher.finish()
+    };
+    format!("{:016x}{:016x}", half(0), half(1))
+}
+
+/// Collects spans while attached to a `Context` (see
+/// `Context::start_telemetry`).
+///
+/// Open spans are ordered by level: a procedure entered at scope depth
+/// `d` is at level `2d`, a host call made from there at `2d + 1`, so
+/// leaving a procedure closes the host calls and procedures it left open.
+#[derive(Debug)]
+pub struct Telemetry {
+    run_id: String,
+    ids: RandomState,
+    next_id: u64,
+    open: Vec<(usize, Span)>,
+    spans: Vec<Span>,
+}
+
+impl Telemetry {
+    pub fn new(run_id: &str) -> Self {
+        let mut telemetry =
+            Telemetry { run_id: run_id.to_string(), ids: RandomState::new(), next_id: 0, open: Vec::new(), spans: Vec::new() };
+        telemetry.open(0, "run", SpanKind::Run);
+        telemetry
+    }
+
+    /// A Sub/Function started with `depth` scopes pushed
+    pub fn enter_procedure(&mut self, name: &str, depth: usize) {
+        self.close_from(2 * depth, None);
+        self.open(2 * depth, name, SpanKind::Procedure);
+    }
+
+    /// Close the procedure entered at `depth`, and everything it left open
+    pub fn leave_procedure(&mut self, depth: usize) {
+        self.close_from(2 * depth, None);
+    }
+
+    /// A call leaving the macro, made from a procedure `depth` scopes deep
+    pub fn enter_host_call(&mut self, kind: HostCallKind, name: &str, depth: usize) {
+        self.open(2 * depth + 1, name, SpanKind::HostCall(kind));
+    }
+
+    /// The host call made at `depth` returned
+    pub fn leave_host_call(&mut self, depth: usize, error: Option<String>) {
+        self.close_from(2 * depth + 1, error);
+    }
+
+    /// A span that already ended, under the innermost open one
+    pub fn record(&mut self, name: &str, kind: SpanKind, start: SystemTime, error: Option<String>) {
+        let span = Span {
+            span_id: self.next_span_id(),
+            parent_span_id: self.open.last().map(|(_, parent)| parent.span_id.clone()),
+            name: name.to_string(),
+            kind,
+            start,
+            end: SystemTime::now(),
+            error,
+            events: Vec::new(),
+        };
+        self.spans.push(span);
+    }
+
+    /// Attach a logged line to the innermost open span
+    pub fn event(&mut self, message: &str) {
+        if let Some((_, span)) = self.open.last_mut() {
+            span.events.push((SystemTime::now(), message.to_string()));
+        }
+    }
+
+    pub fn finish(mut self) -> TelemetryReport {
+        self.close_from(0, None);
+        TelemetryReport { run_id: self.run_id, spans: self.spans }
+    }
+
+    fn open(&mut self, level: usize, name: &str, kind: SpanKind) {
+        let now = SystemTime::now();
+        let span = Span {
+            span_id: self.next_span_id(),
+            parent_span_id: self.open.last().map(|(_, parent)| parent.span_id.clone()),
+            name: name.to_string(),
+            kind,
+            start: now,
+            end: now,
+            error: None,
+            events: Vec::new(),
+        };
+        self.open.push((level, span));
+    }
+
+    /// Close open spans at `level` or deeper; `error` goes to the
+    /// outermost of them
+    fn close_from(&mut self, level: usize, error: Option<String>) {
+        let mut error = error;
+        let start = self.open.iter().position(|(l, _)| *l >= level).unwrap_or(self.open.len());
+        let closing: Vec<_> = self.open.drain(start..).collect();
+        for (index, (_, mut span)) in closing.into_iter().enumerate().rev() {
+            span.end = SystemTime::now();
+            if index == 0 {
+                span.error = error.take();
+            }
+            self.spans.push(span);
+        }
+    }
+
+    fn next_span_id(&mut self) -> String {
+        self.next_id += 1;
+        let mut hasher = self.ids.build_hasher();
+        hasher.write(self.run_id.as_bytes());
+        hasher.write_u64(self.next_id);
+        // Zero is not a valid span ID
+        format!("{:016x}", hasher.finish().max(1))
+    }
+}
+
+/// Run `call` inside a host-call span when telemetry is on
+pub(crate) fn host_span<T>(
+    ctx: &mut Context,
+    kind: HostCallKind,
+    name: &str,
+    call: impl FnOnce(&mut Context) -> Result<T>,
+) -> Result<T> {
+    let depth = ctx.scope_depth();
+    if let Some(telemetry) = ctx.telemetry.as_mut() {
+        telemetry.enter_host_call(kind, name, depth);
+    }
+    let result = call(ctx);
+    if let Some(telemetry) = ctx.telemetry.as_mut() {
+        telemetry.leave_host_call(depth, result.as_ref().err().map(|e| e.to_string()));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spans_nest_by_level() {
+        let mut telemetry = Telemetry::new("0123456789abcdef0123456789abcdef");
+        telemetry.enter_procedure("Main", 1);
+        telemetry.enter_host_call(HostCallKind::Dll, "kernel32!Sleep", 1);
+        telemetry.event("sleeping");
+        telemetry.leave_host_call(1, Some("Permission denied".into()));
+        telemetry.enter_procedure("Helper", 2);
+        telemetry.enter_host_call(HostCallKind::Method, "Range.Clear", 2);
+        // Leaving Main closes Helper and the call it left open
+        telemetry.leave_procedure(1);
+        let report = telemetry.finish();
+
+        let names: Vec<&str> = report.spans.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["kernel32!Sleep", "Range.Clear", "Helper", "Main", "run"]);
+        let run = report.span("run").unwrap();
+        let main = report.span("main").unwrap();
+        assert_eq!(main.parent_span_id.as_ref(), Some(&run.span_id));
+        let main_children: Vec<&str> = report.children(main).map(|s| s.name.as_str()).collect();
+        assert_eq!(main_children, ["kernel32!Sleep", "Helper"]);
+        let sleep = report.span("kernel32!Sleep").unwrap();
+        assert_eq!(sleep.error.as_deref(), Some("Permission denied"));
+        assert_eq!(sleep.events.iter().map(|(_, m)| m.as_str()).collect::<Vec<_>>(), ["sleeping"]);
+        assert!(report.spans.iter().all(|s| s.span_id.len() == 16 && s.end >= s.start));
+
+        let (a, b) = (new_run_id(), new_run_id());
+        assert_eq!(a.len(), 32);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_run_records_procedure_and_host_call_spans() {
+        use crate::ast::{AssignmentTarget, Expression, Program, Statement};
+        use crate::context::Value;
+        use crate::host::functions::HostFunction;
+
+        let lookup = HostFunction::new(|_, ctx| {
+            ctx.log("looked up");
+            Ok(Value::Integer(7))
+        });
+        let config = crate::RuntimeConfig::builder().run_id("job-42").function("TelLookup", lookup).build();
+        let assign = Statement::Assignment {
+            lvalue: AssignmentTarget::Identifier("TelResult".into()),
+            rvalue: Expression::FunctionCall {
+                function: Box::new(Expression::Identifier("TelLookup".into())),
+                args: vec![Expression::Integer(1)],
+            },
+            rvalue_compiled: Default::default(),
+        };
+        let sub = |name: &str, body: Vec<Statement>| Statement::Subroutine { visibility: None, name: name.into(), params: vec![], body: body.into() };
+        let program = Program {
+            statements: vec![
+                sub("TelMain", vec![Statement::Call { function: "TelHelper".into(), args: vec![] }]),
+                sub("TelHelper", vec![assign]),
+            ],
+            diagnostics: vec![],
+        };
+
+        let mut ctx = Context::with_config(config);
+        assert_eq!(ctx.run_id(), "job-42");
+        ctx.start_telemetry();
+        crate::vm::ProgramExecutor::new(program).execute_entrypoint(&mut ctx, "TelMain").unwrap();
+        let report = ctx.take_telemetry().unwrap();
+
+        assert_eq!(report.run_id, "job-42");
+        let helper = report.span("TelHelper").unwrap();
+        let call = report.span("TelLookup").unwrap();
+        assert_eq!(call.kind, SpanKind::HostCall(HostCallKind::HostFunction));
+        assert_eq!(call.parent_span_id.as_ref(), Some(&helper.span_id));
+        assert_eq!(call.events.iter().map(|(_, m)| m.as_str()).collect::<Vec<_>>(), ["looked up"]);
+        assert_eq!(report.spans.last().map(|s| s.kind), Some(SpanKind::Run));
+    }
+
+    #[cfg(feature = "otel")]
+    #[test]
+    fn test_otlp_json() {
+        let mut telemetry = Telemetry::new("0123456789abcdef0123456789abcdef");
+        telemetry.enter_procedure("Main", 1);
+        telemetry.event("say \"hi\"\n");
+        let json = telemetry.finish().to_otlp_json("macro-runner");
+        assert!(json.starts_with("{\"resourceSpans\": [{\"resource\": {\"attributes\": [{\"key\": \"service.name\""));
+        assert!(json.contains("\"traceId\": \"0123456789abcdef0123456789abcdef\""));
+        assert!(json.contains("{\"stringValue\": \"say \\\"hi\\\"\\n\"}"));
+        assert_eq!(json.matches("\"spanId\"").count(), 2);
+        assert_eq!(json.matches("\"parentSpanId\"").count(), 1);
+    }
+}
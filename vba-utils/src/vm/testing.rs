@@ -0,0 +1,202 @@
+// vba-utils/src/vm/testing.rs
+
+//! VBA unit tests, Rubberduck style.
+//!
+//! `ProgramExecutor::run_tests` runs every public parameterless Sub whose
+//! name starts with `Test` as a test, with an `Assert` object registered
+//! (see `host::assert`). Four Subs are hooks rather than tests when present:
+//! `ModuleInitialize` and `ModuleCleanup` run once around all tests,
+//! `TestInitialize` and `TestCleanup` around each one.
+//!
+//! A test fails when an assertion failed or it stopped on a run-time error
+//! nothing handled; an error left in `Err` after `On Error Resume Next`
+//! counts too, so clear it (`Err.Clear`) in tests that expect one. A test
+//! that called `Assert.Inconclusive` and nothing failed is inconclusive.
+
+use std::fmt::Write as _;
+
+use crate::context::ErrObject;
+use crate::host::assert::{AssertFailure, AssertLog};
+
+pub const MODULE_INITIALIZE: &str = "ModuleInitialize";
+pub const MODULE_CLEANUP: &str = "ModuleCleanup";
+pub const TEST_INITIALIZE: &str = "TestInitialize";
+pub const TEST_CLEANUP: &str = "TestCleanup";
+
+/// Whether a Sub of this name is a test (`TestTotals`; not the
+/// `TestInitialize`/`TestCleanup` hooks)
+pub fn is_test_name(name: &str) -> bool {
+    name.get(..4).is_some_and(|prefix| prefix.eq_ignore_ascii_case("test"))
+        && !name.eq_ignore_ascii_case(TEST_INITIALIZE)
+        && !name.eq_ignore_ascii_case(TEST_CLEANUP)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestOutcome {
+    Passed,
+    Failed,
+    Inconclusive,
+}
+
+/// How one test went
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    pub name: String,
+    /// Module the test Sub is in, when the program was loaded as one
+    pub module: Option<String>,
+    pub outcome: TestOutcome,
+    /// Failed assertions in order, then the run-time error that stopped
+    /// the test, if any
+    pub failures: Vec<AssertFailure>,
+    /// Assert.Inconclusive's message
+    pub inconclusive: Option<String>,
+    /// Assert calls made
+    pub assertions: usize,
+}
+
+impl TestResult {
+    pub(crate) fn new(name: &str, module: Option<String>, log: AssertLog, error: Option<(ErrObject, Option<usize>)>) -> Self {
+        let mut failures = log.failures;
+        if let Some((err, line)) = error {
+            failures.push(AssertFailure { line, message: format!("Run-time error {}: {}", err.number, err.description) });
+        }
+        let outcome = match (failures.is_empty(), &log.inconclusive) {
+            (false, _) => TestOutcome::Failed,
+            (true, Some(_)) => TestOutcome::Inconclusive,
+            (true, None) => TestOutcome::Passed,
+        };
+        Self { name: name.to_string(), module, outcome, failures, inconclusive: log.inconclusive, assertions: log.count }
+    }
+
+    /// `Module1.TestTotals`, or the bare name
+    pub fn qualified_name(&self) -> String {
+        match &self.module {
+            Some(module) => format!("{}.{}", module, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Results of `ProgramExecutor::run_tests`, in source order
+#[derive(Debug, Clone, Default)]
+pub struct TestReport {
+    pub results: Vec<TestResult>,
+}
+
+impl TestReport {
+    pub fn count(&self, outcome: TestOutcome) -> usize {
+        self.results.iter().filter(|r| r.outcome == outcome).count()
+    }
+
+    /// No test failed (inconclusive ones do not count against a CI run)
+    pub fn succeeded(&self) -> bool {
+        self.count(TestOutcome::Failed) == 0
+    }
+
+    /// One line per test, each failure below its test with its line, and a
+    /// summary
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for result in &self.results {
+            let label = match result.outcome {
+                TestOutcome::Passed => "PASS",
+                TestOutcome::Failed => "FAIL",
+                TestOutcome::Inconclusive => "INCONCLUSIVE",
+            };
+            let _ = writeln!(out, "{:<12} {}", label, result.qualified_name());
+            for failure in &result.failures {
+                match failure.line {
+                    Some(line) => writeln!(out, "    line {}: {}", line, failure.message),
+                    None => writeln!(out, "    {}", failure.message),
+                }
+                .ok();
+            }
+            if let Some(message) = result.inconclusive.as_deref().filter(|m| !m.is_empty()) {
+                let _ = writeln!(out, "    {}", message);
+            }
+        }
+        let _ = writeln!(
+            out,
+            "\n{} test(s): {} passed, {} failed, {} inconclusive",
+            self.results.len(),
+            self.count(TestOutcome::Passed),
+            self.count(TestOutcome::Failed),
+            self.count(TestOutcome::Inconclusive)
+        );
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{Expression, Program, Statement};
+    use crate::context::Context;
+
+    fn assert_call(method: &str, args: Vec<Expression>) -> Statement {
+        Statement::Expression(Expression::FunctionCall {
+            function: Box::new(Expression::PropertyAccess {
+                obj: Box::new(Expression::Identifier("Assert".into())),
+                property: method.into(),
+            }),
+            args,
+        })
+    }
+
+    fn sub(name: &str, body: Vec<Statement>) -> Statement {
+        Statement::Subroutine { visibility: None, name: name.into(), params: vec![], body: body.into() }
+    }
+
+    #[test]
+    fn test_run_tests_reports_each_outcome() {
+        let sum = Expression::BinaryOp { left: Box::new(Expression::Integer(1)), op: "+".into(), right: Box::new(Expression::Integer(2)) };
+        let program = Program {
+            statements: vec![
+                sub("TestInitialize", vec![Statement::Call { function: "Helper".into(), args: vec![] }]),
+                sub("Helper", vec![]),
+                sub("TestAddition", vec![
+                    assert_call("AreEqual", vec![Expression::Integer(3), sum]),
+                    assert_call("IsTrue", vec![Expression::Boolean(true)]),
+                ]),
+                sub("TestWrongTotal", vec![
+                    assert_call("AreEqual", vec![Expression::Integer(4), Expression::String("3".into()), Expression::String("totals".into())]),
+                    assert_call("IsFalse", vec![Expression::Boolean(false)]),
+                ]),
+                sub("TestPending", vec![assert_call("Inconclusive", vec![Expression::String("not written yet".into())])]),
+                sub("TestDivision", vec![Statement::Expression(Expression::BinaryOp {
+                    left: Box::new(Expression::Integer(1)),
+                    op: "/".into(),
+                    right: Box::new(Expression::Integer(0)),
+                })]),
+            ],
+            diagnostics: vec![],
+        };
+
+        let _engine = crate::host::excel::engine::tests::lock_engine();
+        let mut ctx = Context::new();
+        let report = crate::vm::ProgramExecutor::new(program).run_tests(&mut ctx).unwrap();
+        let names: Vec<&str> = report.results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["TestAddition", "TestWrongTotal", "TestPending", "TestDivision"]);
+        let outcomes: Vec<TestOutcome> = report.results.iter().map(|r| r.outcome).collect();
+        assert_eq!(outcomes, [TestOutcome::Passed, TestOutcome::Failed, TestOutcome::Inconclusive, TestOutcome::Failed]);
+
+        let wrong = &report.results[1];
+        assert_eq!(wrong.assertions, 2);
+        assert_eq!(wrong.failures.len(), 1);
+        assert_eq!(wrong.failures[0].message, "AreEqual failed: expected 4, actual \"3\". totals");
+        assert!(report.results[3].failures[0].message.starts_with("Run-time error 11"));
+        assert!(!report.succeeded());
+        assert!(report.to_text().ends_with("4 test(s): 1 passed, 2 failed, 1 inconclusive\n"));
+        // Assert only exists while the tests run
+        assert!(ctx.com_registry.get_global("Assert").is_none());
+    }
+
+    #[test]
+    fn test_is_test_name() {
+        assert!(is_test_name("TestTotals"));
+        assert!(is_test_name("testtotals"));
+        assert!(!is_test_name("TestInitialize"));
+        assert!(!is_test_name("Totals"));
+        assert!(!is_test_name("Tes"));
+    }
+}
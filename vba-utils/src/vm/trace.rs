@@ -0,0 +1,315 @@
+// vba-utils/src/vm/trace.rs
+
+//! Execution trace recording and replay.
+//!
+//! With `ctx.start_trace()` the VM records one [`TraceEvent`] per executed
+//! statement: the frame it ran in, its pc, and the variables it changed.
+//! Events are appended as statements *complete*, so the statements of an
+//! inline `If` branch appear before the `If` itself.
+//!
+//! A [`Trace`] can be saved to a compact tab-separated text file and loaded
+//! back, then inspected with [`Trace::state_at`] (variables after any step)
+//! or compared against another run with [`Trace::first_divergence`].
+//!
+//! File format (one event per line, fields separated by tabs):
+//!
+//! ```text
+//! vba-trace 1
+//! run     <run id>
+//! init    <change>...
+//! <seq>   <frame> <depth> <pc> <kind> <change>...
+//! ```
+//!
+//! where a change is `name=value` (value in `Debug` form) or `-name` for a
+//! variable that went out of scope.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::context::Context;
+use crate::symbol::Symbol;
+
+const HEADER: &str = "vba-trace 1";
+
+/// A variable that changed during one step. `value` is `None` when the
+/// variable is no longer visible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VarChange {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+/// One executed statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEvent {
+    pub frame: usize,
+    pub depth: usize,
+    pub pc: usize,
+    /// Statement kind, see `Statement::kind_name`
+    pub kind: String,
+    pub changes: Vec<VarChange>,
+}
+
+/// A recorded run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trace {
+    /// `Context::run_id` of the recorded run (absent in older files)
+    pub run_id: Option<String>,
+    /// Variables visible when recording started
+    pub initial: Vec<VarChange>,
+    pub events: Vec<TraceEvent>,
+}
+
+impl Trace {
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Variables visible after event `step` has run (`name` → `Debug` value).
+    pub fn state_at(&self, step: usize) -> BTreeMap<String, String> {
+        let mut state = BTreeMap::new();
+        let steps = self.events.iter().take(step.saturating_add(1));
+        for change in self.initial.iter().chain(steps.flat_map(|e| &e.changes)) {
+            match &change.value {
+                Some(v) => state.insert(change.name.to_lowercase(), v.clone()),
+                None => state.remove(&change.name.to_lowercase()),
+            };
+        }
+        state
+    }
+
+    /// Index of the first event where the two traces differ, or `None` if
+    /// they are identical.
+    pub fn first_divergence(&self, other: &Trace) -> Option<usize> {
+        let first = self.events.iter().zip(&other.events).position(|(a, b)| a != b);
+        match first {
+            Some(i) => Some(i),
+            None if self.events.len() != other.events.len() => {
+                Some(self.events.len().min(other.events.len()))
+            }
+            None => None,
+        }
+    }
+
+    /// Serialize in the trace file format.
+    pub fn to_text(&self) -> String {
+        let mut out = String::from(HEADER);
+        if let Some(run_id) = &self.run_id {
+            let _ = write!(out, "\nrun\t{}", run_id);
+        }
+        out.push_str("\ninit");
+        push_changes(&mut out, &self.initial);
+        out.push('\n');
+        for (seq, e) in self.events.iter().enumerate() {
+            let _ = write!(out, "{}\t{}\t{}\t{}\t{}", seq, e.frame, e.depth, e.pc, e.kind);
+            push_changes(&mut out, &e.changes);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Parse the trace file format.
+    pub fn parse(text: &str) -> Result<Trace, String> {
+        let mut lines = text.lines();
+        if lines.next() != Some(HEADER) {
+            return Err("not a vba trace file".into());
+        }
+
+        let mut trace = Trace::default();
+        for (n, line) in lines.enumerate() {
+            let mut fields = line.split('\t');
+            let first = fields.next().unwrap_or("");
+            if first == "run" {
+                trace.run_id = fields.next().map(str::to_string);
+                continue;
+            }
+            if first == "init" {
+                trace.initial = fields.map(parse_change).collect::<Result<_, _>>()?;
+                continue;
+            }
+
+            let bad = || format!("malformed trace event on line {}", n + 2);
+            let mut num = || fields.next().and_then(|f| f.parse::<usize>().ok()).ok_or_else(bad);
+            let (frame, depth, pc) = (num()?, num()?, num()?);
+            let kind = fields.next().ok_or_else(bad)?.to_string();
+            let changes = fields.map(parse_change).collect::<Result<_, _>>()?;
+            trace.events.push(TraceEvent { frame, depth, pc, kind, changes });
+        }
+        Ok(trace)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Trace, String> {
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Trace::parse(&text)
+    }
+}
+
+fn push_changes(out: &mut String, changes: &[VarChange]) {
+    for c in changes {
+        out.push('\t');
+        match &c.value {
+            Some(v) => {
+                out.push_str(&c.name);
+                out.push('=');
+                out.push_str(&escape(v));
+            }
+            None => {
+                out.push('-');
+                out.push_str(&c.name);
+            }
+        }
+    }
+}
+
+fn parse_change(field: &str) -> Result<VarChange, String> {
+    if let Some(name) = field.strip_prefix('-') {
+        return Ok(VarChange { name: name.to_string(), value: None });
+    }
+    let (name, value) = field
+        .split_once('=')
+        .ok_or_else(|| format!("malformed variable change: {}", field))?;
+    Ok(VarChange { name: name.to_string(), value: Some(unescape(value)) })
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Records events while attached to a `Context` (see `Context::start_trace`).
+#[derive(Debug)]
+pub struct TraceRecorder {
+    trace: Trace,
+    last: HashMap<Symbol, String>,
+}
+
+impl TraceRecorder {
+    pub fn new(ctx: &Context) -> Self {
+        let trace = Trace { run_id: Some(ctx.run_id().to_string()), ..Trace::default() };
+        let mut recorder = TraceRecorder { trace, last: HashMap::new() };
+        recorder.trace.initial = recorder.diff(ctx);
+        recorder
+    }
+
+    /// Append an event for a statement that just finished.
+    pub fn record(&mut self, ctx: &Context, frame: usize, depth: usize, pc: usize, kind: &str) {
+        let changes = self.diff(ctx);
+        self.trace.events.push(TraceEvent { frame, depth, pc, kind: kind.to_string(), changes });
+    }
+
+    pub fn finish(self) -> Trace {
+        self.trace
+    }
+
+    /// Changes since the previous snapshot; updates the snapshot.
+    fn diff(&mut self, ctx: &Context) -> Vec<VarChange> {
+        let now: HashMap<Symbol, String> = ctx
+            .visible_vars()
+            .into_iter()
+            .map(|(k, v)| (k, format!("{:?}", v)))
+            .collect();
+
+        let mut changes: Vec<VarChange> = now
+            .iter()
+            .filter(|(k, v)| self.last.get(k) != Some(v))
+            .map(|(k, v)| VarChange { name: k.as_str().to_string(), value: Some(v.clone()) })
+            .chain(
+                self.last
+                    .keys()
+                    .filter(|k| !now.contains_key(k))
+                    .map(|k| VarChange { name: k.as_str().to_string(), value: None }),
+            )
+            .collect();
+        changes.sort_by_key(|c| c.name.to_lowercase());
+        self.last = now;
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(name: &str, value: Option<&str>) -> VarChange {
+        VarChange { name: name.into(), value: value.map(str::to_string) }
+    }
+
+    fn sample() -> Trace {
+        Trace {
+            run_id: Some("0123456789abcdef0123456789abcdef".into()),
+            initial: vec![change("g", Some("Integer(1)"))],
+            events: vec![
+                TraceEvent { frame: 0, depth: 0, pc: 0, kind: "Dim".into(), changes: vec![change("s", Some("String(\"\")"))] },
+                TraceEvent {
+                    frame: 0,
+                    depth: 0,
+                    pc: 1,
+                    kind: "Assignment".into(),
+                    changes: vec![change("s", Some("String(\"a\\tb\\nc\")"))],
+                },
+                TraceEvent { frame: 1, depth: 1, pc: 0, kind: "Exit".into(), changes: vec![change("s", None)] },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let trace = sample();
+        assert_eq!(Trace::parse(&trace.to_text()).unwrap(), trace);
+        assert!(Trace::parse("something else").is_err());
+    }
+
+    #[test]
+    fn test_state_at_and_divergence() {
+        let trace = sample();
+        assert_eq!(trace.state_at(0).get("s").map(String::as_str), Some("String(\"\")"));
+        assert_eq!(trace.state_at(2).get("s"), None);
+        assert_eq!(trace.state_at(2).get("g").map(String::as_str), Some("Integer(1)"));
+
+        let mut other = sample();
+        assert_eq!(trace.first_divergence(&other), None);
+        other.events[1].changes.clear();
+        assert_eq!(trace.first_divergence(&other), Some(1));
+        other.events.truncate(1);
+        assert_eq!(trace.first_divergence(&other), Some(1));
+    }
+}
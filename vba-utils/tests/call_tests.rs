@@ -0,0 +1,190 @@
+// Tests for Sub invocation syntax
+//
+// This test file covers:
+// - Call Foo(a, b)  - explicit Call with an argument list
+// - Foo a, b        - implicit call with bare, comma-separated arguments
+// - ByRef default   - caller variables updated by the callee
+// - Foo (x)         - parenthesized argument forces ByVal
+// - Name lookups ignore case (variables, parameters, Sub names)
+// - Locals are lexical: a callee cannot see or clobber its caller's locals
+
+use vba_utils::Context;
+use vba_utils::vm::ProgramExecutor;
+use vba_utils::ast::{build_ast, Expression, Statement};
+
+/// Helper to run VBA code and capture output
+fn run_vba(code: &str) -> Vec<String> {
+    let program = build_ast(code);
+
+    let mut ctx = Context::new();
+    let executor = ProgramExecutor::new(program);
+    let _ = executor.execute(&mut ctx);
+    ctx.output.clone()
+}
+
+/// Helper to find the Call statements inside the first Sub
+fn calls_in_first_sub(code: &str) -> Vec<(String, Vec<Expression>)> {
+    let program = build_ast(code);
+
+    for stmt in program.statements {
+        if let Statement::Subroutine { body, .. } = stmt {
+            return body
+                .into_iter()
+                .filter_map(|s| match s {
+                    Statement::Call { function, args } => Some((function, args)),
+                    _ => None,
+                })
+                .collect();
+        }
+    }
+    Vec::new()
+}
+
+// ============================================================
+// AST SHAPE
+// ============================================================
+
+#[test]
+fn test_implicit_call_with_multiple_args() {
+    let code = r#"
+Sub AutoOpen()
+    Greet "World", 3
+End Sub
+"#;
+    let calls = calls_in_first_sub(code);
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].0, "Greet");
+    assert_eq!(calls[0].1.len(), 2);
+}
+
+#[test]
+fn test_parenthesized_arg_without_call_is_byval() {
+    let code = r#"
+Sub AutoOpen()
+    Bump (n)
+    Call Bump(n)
+End Sub
+"#;
+    let calls = calls_in_first_sub(code);
+    assert_eq!(calls.len(), 2);
+    assert!(matches!(calls[0].1[0], Expression::Parenthesized(_)));
+    assert!(matches!(calls[1].1[0], Expression::Identifier(_)));
+}
+
+// ============================================================
+// EXECUTION
+// ============================================================
+
+#[test]
+fn test_implicit_call_executes() {
+    let code = r#"
+Sub Greet(who, times)
+    MsgBox who & " x" & times
+End Sub
+
+Sub AutoOpen()
+    Greet "World", 3
+End Sub
+"#;
+    let output = run_vba(code);
+    assert!(output.contains(&"World x3".to_string()), "output: {:?}", output);
+}
+
+#[test]
+fn test_byref_default_updates_caller() {
+    let code = r#"
+Sub Bump(x)
+    x = x + 1
+End Sub
+
+Sub AutoOpen()
+    Dim n As Integer
+    n = 1
+    Bump n
+    Call Bump(n)
+    MsgBox n
+End Sub
+"#;
+    let output = run_vba(code);
+    assert_eq!(output.last().map(String::as_str), Some("3"));
+}
+
+#[test]
+fn test_byval_param_does_not_update_caller() {
+    let code = r#"
+Sub Bump(ByVal x)
+    x = x + 1
+End Sub
+
+Sub AutoOpen()
+    Dim n As Integer
+    n = 1
+    Bump n
+    MsgBox n
+End Sub
+"#;
+    let output = run_vba(code);
+    assert_eq!(output.last().map(String::as_str), Some("1"));
+}
+
+#[test]
+fn test_parenthesized_arg_does_not_update_caller() {
+    let code = r#"
+Sub Bump(x)
+    x = x + 1
+End Sub
+
+Sub AutoOpen()
+    Dim n As Integer
+    n = 1
+    Bump (n)
+    MsgBox n
+End Sub
+"#;
+    let output = run_vba(code);
+    assert_eq!(output.last().map(String::as_str), Some("1"));
+}
+
+#[test]
+fn test_names_are_case_insensitive() {
+    let code = r#"
+Sub Bump(x)
+    X = x + 1
+End Sub
+
+Sub AutoOpen()
+    Dim Total As Integer
+    total = 1
+    bump TOTAL
+    MsgBox Total
+End Sub
+"#;
+    let output = run_vba(code);
+    assert_eq!(output.last().map(String::as_str), Some("2"));
+}
+
+#[test]
+fn test_callee_locals_are_lexical() {
+    let code = r#"
+Dim counter As Integer
+
+Sub Inner()
+    v = 99
+    counter = counter + 1
+End Sub
+
+Sub Outer()
+    Dim v As Integer
+    v = 5
+    Inner
+    MsgBox v & " " & counter
+End Sub
+
+Sub AutoOpen()
+    counter = 1
+    Outer
+End Sub
+"#;
+    let output = run_vba(code);
+    assert_eq!(output.last().map(String::as_str), Some("5 2"));
+}
@@ -0,0 +1,86 @@
+// Tests for the static call graph
+//
+// This test file covers:
+// - Calls through Call, bare Sub names, function calls and Module.Name
+// - Unreachable procedures and recursion cycles
+// - DOT and JSON output
+
+use vba_utils::analysis::CallGraph;
+use vba_utils::ast::{build_ast, Program};
+
+/// Helper to parse VBA code into a program
+fn parse_vba(code: &str) -> Program {
+    build_ast(code)
+}
+
+const MAIN: &str = r#"
+Sub AutoOpen()
+    Dim n As Long
+    Call Setup
+    n = Fact(5)
+    Reports.Summary
+End Sub
+
+Sub Setup()
+End Sub
+
+Function Fact(k As Long) As Long
+    If k <= 1 Then
+        Fact = 1
+    Else
+        Fact = k * Fact(k - 1)
+    End If
+End Function
+
+Sub OldHelper()
+End Sub
+"#;
+
+const REPORTS: &str = r#"
+Sub Summary()
+End Sub
+
+Sub Button1_Click()
+End Sub
+"#;
+
+fn graph() -> CallGraph {
+    let main = parse_vba(MAIN);
+    let reports = parse_vba(REPORTS);
+    CallGraph::build(&[("Main", &main), ("Reports", &reports)])
+}
+
+// ============================================================
+// Edges
+// ============================================================
+
+#[test]
+fn test_collects_calls() {
+    let graph = graph();
+    let callees: Vec<String> = graph.callees("AutoOpen").iter().map(|p| p.qualified_name()).collect();
+    assert_eq!(callees, vec!["Main.Setup", "Main.Fact", "Reports.Summary"]);
+}
+
+// ============================================================
+// Dead code and recursion
+// ============================================================
+
+#[test]
+fn test_reports_unreachable_and_recursion() {
+    let graph = graph();
+    let dead: Vec<&str> = graph.unreachable().iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(dead, vec!["OldHelper"]);
+
+    let cycles = graph.cycles();
+    assert_eq!(cycles.len(), 1);
+    assert_eq!(cycles[0][0].name, "Fact");
+}
+
+#[test]
+fn test_dot_and_json() {
+    let graph = graph();
+    assert!(graph.to_dot().starts_with("digraph calls {"));
+    let json = graph.to_json();
+    assert!(json.contains("[\"Main.AutoOpen\", \"Reports.Summary\"]"));
+    assert!(json.contains("\"unreachable\": [\"Main.OldHelper\"]"));
+}
@@ -3,7 +3,10 @@
 // Tests for Excel Range COM Object
 // ============================================================================
 
-use vba_utils::host::excel::objects::range::{ExcelRange, RangeBuilder, column_index_to_letter, indices_to_address};
+use vba_utils::host::excel::objects::range::{
+    ExcelRange, RangeBuilder, column_index_to_letter, indices_to_address,
+    address_areas, areas_address, intersect_areas, union_areas,
+};
 
 #[test]
 fn test_range_creation() {
@@ -130,3 +133,23 @@ fn test_range_top_left_bottom_right() {
     assert_eq!(rng.top_left().unwrap(), "B2");
     assert_eq!(rng.bottom_right().unwrap(), "D5");
 }
+
+#[test]
+fn test_multi_area_address() {
+    let areas = address_areas("A1:A5, C1:C5").unwrap();
+    assert_eq!(areas, vec![(0, 0, 4, 0), (0, 2, 4, 2)]);
+    assert_eq!(areas_address(&areas), "A1:A5,C1:C5");
+    assert_eq!(areas_address(&address_areas("2:4,C:C,B7").unwrap()), "2:4,C:C,B7");
+}
+
+#[test]
+fn test_union_and_intersect() {
+    // Neighbours join, contained areas disappear, the rest stay separate
+    let union = union_areas(address_areas("A1:A5,A6:A10,B2,A3,D1").unwrap());
+    assert_eq!(areas_address(&union), "A1:A10,B2,D1");
+
+    let first = address_areas("A1:C3,E1:E9").unwrap();
+    let second = address_areas("B2:E5").unwrap();
+    assert_eq!(areas_address(&intersect_areas(&first, &second)), "B2:C3,E2:E5");
+    assert!(intersect_areas(&first, &address_areas("G1").unwrap()).is_empty());
+}
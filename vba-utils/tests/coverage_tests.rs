@@ -0,0 +1,89 @@
+// Tests for code coverage reporting
+//
+// This test file covers:
+// - Context::start_coverage / take_coverage around a VM run
+// - Line hit counts, including statements inside loops and If branches
+// - Branch arms taken and missed; LCOV and HTML output
+
+use vba_utils::ast::build_ast;
+use vba_utils::vm::{CoverageReport, ProgramExecutor};
+use vba_utils::Context;
+
+/// Helper to run VBA code with coverage enabled
+fn cover_vba(code: &str) -> CoverageReport {
+    let program = build_ast(code);
+
+    let mut ctx = Context::new();
+    ctx.start_coverage();
+    let executor = ProgramExecutor::new(program);
+    let _ = executor.execute_entrypoint(&mut ctx, "AutoOpen");
+    let hits = ctx.take_coverage().expect("coverage was started");
+    CoverageReport::new(executor.program(), &hits)
+}
+
+// Line numbers below count from the blank first line of the literal
+const CODE: &str = r#"
+Sub AutoOpen()
+    Dim i As Integer
+    Dim small As Integer
+    For i = 1 To 4
+        If i < 3 Then
+            small = small + 1
+        Else
+            small = small - 1
+        End If
+    Next i
+End Sub
+
+Sub NeverCalled()
+    Dim x As Integer
+    x = 1
+End Sub
+"#;
+
+// ============================================================
+// Line coverage
+// ============================================================
+
+#[test]
+fn test_counts_line_hits() {
+    let report = cover_vba(CODE);
+    assert_eq!(report.lines.get(&3), Some(&1));
+    assert_eq!(report.lines.get(&5), Some(&1));
+    assert_eq!(report.lines.get(&6), Some(&4));
+    assert_eq!(report.lines.get(&7), Some(&2));
+    assert_eq!(report.lines.get(&9), Some(&2));
+}
+
+#[test]
+fn test_reports_lines_that_never_ran() {
+    let report = cover_vba(CODE);
+    assert_eq!(report.missed_lines(), vec![15, 16]);
+    assert_eq!(report.lines_hit() + 2, report.lines_found());
+}
+
+// ============================================================
+// Branch coverage and output formats
+// ============================================================
+
+#[test]
+fn test_counts_if_arms() {
+    let report = cover_vba(CODE);
+    let arms: Vec<(usize, u64)> = report.branches.iter().map(|b| (b.arm, b.hits)).collect();
+    assert_eq!(arms, vec![(0, 2), (1, 2)]);
+    assert!(report.branches.iter().all(|b| b.line == 6));
+}
+
+#[test]
+fn test_lcov_and_html() {
+    let report = cover_vba(CODE);
+    let lcov = report.to_lcov("sample.bas");
+    assert!(lcov.contains("SF:sample.bas\n"));
+    assert!(lcov.contains("DA:7,2\n"));
+    assert!(lcov.contains("DA:16,0\n"));
+    assert!(lcov.contains("BRDA:6,0,0,2\n"));
+
+    let html = report.to_html("sample.bas", CODE);
+    assert!(html.contains("<tr class=\"miss\">"));
+    assert!(html.contains("2/2 arms"));
+}
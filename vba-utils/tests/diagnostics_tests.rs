@@ -0,0 +1,158 @@
+// Tests for error-tolerant AST building
+//
+// This test file covers:
+// - Program::diagnostics for unparseable code
+// - Statement::Unparsed placeholders keep the rest of the body intact
+// - Partially parsed programs still run the statements that were understood
+// - Undefined and duplicate GoTo/Resume labels
+// - Attribute lines of exported modules
+// - BuildReport of the nodes that could not be converted
+
+use vba_utils::Context;
+use vba_utils::vm::ProgramExecutor;
+use vba_utils::ast::{build_ast, build_ast_with_report, Program, SkipCategory, Statement};
+use vba_utils::preprocess::default_constants;
+
+/// Helper to parse VBA code into a Program
+fn parse_program(code: &str) -> Program {
+    build_ast(code)
+}
+
+#[test]
+fn test_clean_program_has_no_diagnostics() {
+    let code = r#"
+Sub AutoOpen()
+    MsgBox "ok"
+End Sub
+"#;
+    let program = parse_program(code);
+    assert!(program.is_fully_parsed(), "diagnostics: {:?}", program.diagnostics);
+}
+
+#[test]
+fn test_garbage_line_is_reported_not_dropped() {
+    let code = r#"
+Sub AutoOpen()
+    MsgBox "before"
+    ))) %%% (((
+    MsgBox "after"
+End Sub
+"#;
+    let program = parse_program(code);
+    assert!(!program.is_fully_parsed());
+    assert!(program.diagnostics.iter().any(|d| d.span.line == 4), "diagnostics: {:?}", program.diagnostics);
+
+    let unparsed = |stmts: &[Statement]| stmts.iter().any(|s| matches!(s, Statement::Unparsed { .. }));
+    let found = program.statements.iter().any(|s| match s {
+        Statement::Subroutine { body, .. } => unparsed(body),
+        other => matches!(other, Statement::Unparsed { .. }),
+    });
+    assert!(found, "statements: {:?}", program.statements);
+}
+
+#[test]
+fn test_partial_program_still_runs() {
+    let code = r#"
+Sub AutoOpen()
+    MsgBox "before"
+    ))) %%% (((
+    MsgBox "after"
+End Sub
+"#;
+    let program = parse_program(code);
+    let mut ctx = Context::new();
+    let _ = ProgramExecutor::new(program).execute(&mut ctx);
+    assert!(ctx.output.contains(&"before".to_string()), "output: {:?}", ctx.output);
+}
+
+#[test]
+fn test_undefined_label_is_reported() {
+    let code = r#"
+Sub AutoOpen()
+    On Error GoTo Handler
+    GoTo ExitPoint
+Point:
+    MsgBox "unreachable"
+End Sub
+"#;
+    let program = parse_program(code);
+    let messages: Vec<&str> = program.diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert!(messages.contains(&"label not defined: Handler"), "diagnostics: {:?}", messages);
+    assert!(messages.contains(&"label not defined: ExitPoint"), "diagnostics: {:?}", messages);
+}
+
+#[test]
+fn test_labels_match_case_insensitively() {
+    let code = r#"
+Sub AutoOpen()
+    GoTo done
+    MsgBox "skipped"
+Done:
+    MsgBox "after"
+End Sub
+"#;
+    let program = parse_program(code);
+    assert!(program.is_fully_parsed(), "diagnostics: {:?}", program.diagnostics);
+
+    let mut ctx = Context::new();
+    let _ = ProgramExecutor::new(program).execute(&mut ctx);
+    assert_eq!(ctx.output, vec!["after".to_string()]);
+}
+
+#[test]
+fn test_duplicate_label_is_reported() {
+    let code = r#"
+Sub AutoOpen()
+Again:
+    MsgBox "one"
+again:
+End Sub
+"#;
+    let program = parse_program(code);
+    assert!(program.diagnostics.iter().any(|d| d.message == "duplicate label: again" && d.span.line == 5),
+        "diagnostics: {:?}", program.diagnostics);
+}
+
+#[test]
+fn test_attribute_lines_are_metadata() {
+    let code = r#"Attribute VB_Name = "Invoices"
+Attribute VB_PredeclaredId = True
+Sub Main()
+Attribute Main.VB_Description = "Posts the invoices"
+    MsgBox "ok"
+End Sub
+"#;
+    let program = parse_program(code);
+    assert!(program.is_fully_parsed(), "diagnostics: {:?}", program.diagnostics);
+
+    let attributes = program.attributes();
+    assert_eq!(attributes.module_name(), Some("Invoices"));
+    assert!(attributes.predeclared_id());
+    assert!(!attributes.exposed());
+    assert_eq!(attributes.description("main"), Some("Posts the invoices"));
+
+    let mut ctx = Context::new();
+    ProgramExecutor::new(program).execute_entrypoint(&mut ctx, "Main").unwrap();
+    assert!(ctx.err.is_none());
+}
+
+#[test]
+fn test_build_report_lists_skipped_nodes() {
+    let code = r#"
+Sub AutoOpen()
+    MsgBox "before"
+    ))) %%% (((
+    MsgBox "after"
+End Sub
+"#;
+    let (program, report) = build_ast_with_report(code, &default_constants());
+
+    assert!(!report.is_complete());
+    assert!(report.skipped.iter().all(|n| n.span.line == 4), "skipped: {:?}", report.skipped);
+    assert!(report.skipped.iter().any(|n| n.text.contains("%%%")), "skipped: {:?}", report.skipped);
+    assert_eq!(report.by_category().get(&SkipCategory::Syntax).copied().unwrap_or(0), program.diagnostics.len());
+
+    let clean = "Sub AutoOpen()\n    MsgBox \"ok\"\nEnd Sub\n";
+    let (_, report) = build_ast_with_report(clean, &default_constants());
+    assert!(report.is_complete(), "skipped: {:?}", report.skipped);
+}
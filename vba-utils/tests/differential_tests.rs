@@ -0,0 +1,200 @@
+// Differential tests: interpreter vs VM
+//
+// This test file covers:
+// - ExecutionEngine selection through RuntimeConfig
+// - Generated programs (Dim, assignment, MsgBox, If/Else, For, Call) run
+//   through both engines
+// - Identical output log, variable state and Err number after execution
+
+use proptest::prelude::*;
+use vba_utils::ast::build_ast;
+use vba_utils::vm::ProgramExecutor;
+use vba_utils::{Context, ExecutionEngine, RuntimeConfig};
+
+const VARS: [&str; 3] = ["a", "b", "c"];
+const MAX_DEPTH: usize = 2;
+
+/// Observable state after a run
+#[derive(Debug, PartialEq)]
+struct Outcome {
+    output: Vec<String>,
+    vars: Vec<(String, String)>,
+    err: Option<i32>,
+}
+
+/// Helper to run VBA code on the given engine and capture its outcome
+fn run_on(code: &str, engine: ExecutionEngine) -> Outcome {
+    let program = build_ast(code);
+
+    let mut ctx = Context::with_config(RuntimeConfig::builder().engine(engine).build());
+    let executor = ProgramExecutor::new(program);
+    let _ = executor.execute_entrypoint(&mut ctx, "AutoOpen");
+
+    let names = VARS
+        .iter()
+        .map(|v| v.to_string())
+        .chain((0..MAX_DEPTH).map(|d| format!("i{}", d)));
+    Outcome {
+        output: ctx.output.clone(),
+        vars: names
+            .map(|n| {
+                let v = format!("{:?}", ctx.get_var(&n));
+                (n, v)
+            })
+            .collect(),
+        err: ctx.err.as_ref().map(|e| e.number),
+    }
+}
+
+fn assert_same(code: &str) {
+    let vm = run_on(code, ExecutionEngine::Vm);
+    let interp = run_on(code, ExecutionEngine::Interpreter);
+    assert_eq!(vm, interp, "engines disagree on:\n{}", code);
+}
+
+// ============================================================
+// PROGRAM GENERATOR
+// ============================================================
+
+#[derive(Debug, Clone)]
+enum Gen {
+    Assign(usize, String),
+    Print(String),
+    If(String, Vec<Gen>, Vec<Gen>),
+    For(i32, i32, i32, Vec<Gen>),
+    CallHelper(usize),
+}
+
+fn operand() -> impl Strategy<Value = String> {
+    prop_oneof![
+        (0i32..10).prop_map(|n| n.to_string()),
+        (0..VARS.len()).prop_map(|i| VARS[i].to_string()),
+    ]
+}
+
+/// Small expressions; multiplication only between literals so nested
+/// loops cannot drive the values into Overflow.
+fn expr() -> impl Strategy<Value = String> {
+    prop_oneof![
+        operand(),
+        (operand(), prop_oneof![Just("+"), Just("-")], operand())
+            .prop_map(|(l, op, r)| format!("{} {} {}", l, op, r)),
+        (0i32..10, 0i32..10).prop_map(|(l, r)| format!("{} * {}", l, r)),
+    ]
+}
+
+fn condition() -> impl Strategy<Value = String> {
+    (
+        operand(),
+        prop_oneof![Just("="), Just("<>"), Just("<"), Just(">"), Just("<="), Just(">=")],
+        operand(),
+    )
+        .prop_map(|(l, op, r)| format!("{} {} {}", l, op, r))
+}
+
+fn simple_stmt() -> impl Strategy<Value = Gen> {
+    prop_oneof![
+        3 => (0..VARS.len(), expr()).prop_map(|(v, e)| Gen::Assign(v, e)),
+        2 => expr().prop_map(Gen::Print),
+        1 => (0..VARS.len()).prop_map(Gen::CallHelper),
+    ]
+}
+
+/// Nested statements. Calls appear at any depth, so the VM has to resume
+/// an If branch or loop body after the call frame it pushed returns.
+fn nested_stmt() -> impl Strategy<Value = Gen> {
+    simple_stmt().prop_recursive(MAX_DEPTH as u32, 24, 4, |inner| {
+        prop_oneof![
+            (
+                condition(),
+                prop::collection::vec(inner.clone(), 1..4),
+                prop::collection::vec(inner.clone(), 0..3),
+            )
+                .prop_map(|(c, t, e)| Gen::If(c, t, e)),
+            (0i32..3, 0i32..4, prop_oneof![Just(1), Just(2), Just(-1)],
+                prop::collection::vec(inner, 1..4))
+                .prop_map(|(s, e, st, body)| Gen::For(s, e, st, body)),
+        ]
+    })
+}
+
+/// Render `stmts` indented `depth` levels inside `loops` enclosing For loops
+fn render(stmts: &[Gen], depth: usize, loops: usize, out: &mut String) {
+    let pad = "    ".repeat(depth + 1);
+    for s in stmts {
+        match s {
+            Gen::Assign(v, e) => out.push_str(&format!("{}{} = {}\n", pad, VARS[*v], e)),
+            Gen::Print(e) => out.push_str(&format!("{}MsgBox {}\n", pad, e)),
+            Gen::If(c, t, e) => {
+                out.push_str(&format!("{}If {} Then\n", pad, c));
+                render(t, depth + 1, loops, out);
+                if !e.is_empty() {
+                    out.push_str(&format!("{}Else\n", pad));
+                    render(e, depth + 1, loops, out);
+                }
+                out.push_str(&format!("{}End If\n", pad));
+            }
+            Gen::For(start, end, step, body) => {
+                // Loop depth, not statement depth, picks the counter so
+                // nested loops never share one.
+                let counter = format!("i{}", loops);
+                out.push_str(&format!(
+                    "{}For {} = {} To {} Step {}\n",
+                    pad, counter, start, end, step
+                ));
+                render(body, depth + 1, loops + 1, out);
+                out.push_str(&format!("{}Next {}\n", pad, counter));
+            }
+            Gen::CallHelper(v) => out.push_str(&format!("{}Helper {}\n", pad, VARS[*v])),
+        }
+    }
+}
+
+fn program_source(stmts: &[Gen]) -> String {
+    let mut src = String::from(
+        "Sub Helper(x)\n    x = x + 1\n    MsgBox \"helper \" & x\nEnd Sub\n\nSub AutoOpen()\n",
+    );
+    src.push_str("    Dim a As Long\n    Dim b As Long\n    Dim c As Long\n");
+    render(stmts, 0, 0, &mut src);
+    src.push_str("End Sub\n");
+    src
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn prop_engines_agree(stmts in prop::collection::vec(nested_stmt(), 1..8)) {
+        assert_same(&program_source(&stmts));
+    }
+}
+
+// ============================================================
+// FIXED REGRESSIONS
+// ============================================================
+
+#[test]
+fn test_engine_flag_is_honoured() {
+    let code = "Sub AutoOpen()\n    MsgBox 1\nEnd Sub\n";
+    assert_eq!(run_on(code, ExecutionEngine::Vm).output, vec!["1".to_string()]);
+    assert_eq!(run_on(code, ExecutionEngine::Interpreter).output, vec!["1".to_string()]);
+}
+
+#[test]
+fn test_for_counter_after_loop() {
+    assert_same(
+        "Sub AutoOpen()\n    Dim a As Long\n    For i0 = 1 To 3\n        a = a + i0\n    Next i0\n    MsgBox i0\nEnd Sub\n",
+    );
+}
+
+#[test]
+fn test_for_zero_iterations() {
+    assert_same(
+        "Sub AutoOpen()\n    Dim a As Long\n    For i0 = 3 To 1\n        a = 9\n    Next i0\n    MsgBox a & \" \" & i0\nEnd Sub\n",
+    );
+}
+
+#[test]
+fn test_byref_call() {
+    assert_same(&program_source(&[Gen::CallHelper(0), Gen::CallHelper(0)]));
+}
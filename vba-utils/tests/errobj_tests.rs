@@ -7,19 +7,13 @@
 // - Err.Clear - Clears all error properties
 // - Err.Raise - Generates a runtime error
 
-use tree_sitter::Parser;
-use vba_parser::language as tree_sitter_vba;
 use vba_utils::Context;
 use vba_utils::vm::ProgramExecutor;
 use vba_utils::ast::build_ast;
 
 /// Helper to run VBA code and capture output
 fn run_vba(code: &str) -> Vec<String> {
-    let mut parser = Parser::new();
-    parser.set_language(tree_sitter_vba()).expect("Failed to set VBA language");
-    let tree = parser.parse(code, None).expect("Failed to parse VBA code");
-    let root_node = tree.root_node();
-    let program = build_ast(root_node, code);
+    let program = build_ast(code);
     
     let mut ctx = Context::new();
     let executor = ProgramExecutor::new(program);
@@ -0,0 +1,81 @@
+// Tests for evaluating a single expression string
+//
+// This test file covers:
+// - VbaRuntime::eval_expression against an existing Context
+// - Variables, operators and builtin functions
+// - Hex, octal, exponent and type-suffixed numeric literals
+// - Rejection of empty, multi-line and malformed input
+// - Parse and run-time errors told apart by VbaError
+
+use vba_utils::{Context, VbaError, VbaRuntime, VbaValue};
+
+fn eval(expr: &str, ctx: &mut Context) -> String {
+    VbaRuntime::eval_expression(expr, ctx)
+        .unwrap_or_else(|e| panic!("{} failed: {}", expr, e))
+        .as_string()
+}
+
+// ============================================================
+// EVALUATION
+// ============================================================
+
+#[test]
+fn test_literals_and_operators() {
+    let mut ctx = Context::new();
+    assert_eq!(eval("1 + 2 * 3", &mut ctx), "7");
+    assert_eq!(eval("\"a\" & \"b\"", &mut ctx), "ab");
+    assert_eq!(eval("3 > 2", &mut ctx), "true");
+}
+
+#[test]
+fn test_reads_context_variables() {
+    let mut ctx = Context::new();
+    ctx.set_var("Total", VbaValue::Integer(20));
+    assert_eq!(eval("total / 4", &mut ctx), "5");
+}
+
+#[test]
+fn test_builtin_functions() {
+    let mut ctx = Context::new();
+    assert_eq!(eval("Len(\"hello\")", &mut ctx), "5");
+    assert_eq!(eval("UCase(\"abc\") & Left(\"xyz\", 1)", &mut ctx), "ABCx");
+}
+
+#[test]
+fn test_radix_and_typed_literals() {
+    let mut ctx = Context::new();
+    assert_eq!(eval("&HFF + &O777", &mut ctx), "766");
+    assert_eq!(eval("&HFFFF", &mut ctx), "-1");
+    assert_eq!(eval("&HFFFF&", &mut ctx), "65535");
+    assert_eq!(eval("&HFFFFFFFF", &mut ctx), "-1");
+    assert_eq!(eval("TypeName(&HFFFF&) & TypeName(123&) & TypeName(100000)", &mut ctx), "LongLongLong");
+    assert_eq!(eval("TypeName(7^) & TypeName(12)", &mut ctx), "LongLongInteger");
+    assert_eq!(eval("1.5E+3 + 2e2", &mut ctx), "1700");
+}
+
+// ============================================================
+// ERRORS
+// ============================================================
+
+#[test]
+fn test_rejects_bad_input() {
+    let mut ctx = Context::new();
+    assert!(VbaRuntime::eval_expression("", &mut ctx).is_err());
+    assert!(VbaRuntime::eval_expression("1 +\n2", &mut ctx).is_err());
+    assert!(VbaRuntime::eval_expression("1 +", &mut ctx).is_err());
+}
+
+#[test]
+fn test_unknown_variable_is_an_error() {
+    let mut ctx = Context::new();
+    assert!(VbaRuntime::eval_expression("notDefinedAnywhere + 1", &mut ctx).is_err());
+}
+
+#[test]
+fn test_errors_are_typed() {
+    let mut ctx = Context::new();
+    let parse = VbaRuntime::eval_expression("1 +", &mut ctx).unwrap_err();
+    assert!(matches!(parse, VbaError::ParseError { .. }), "{:?}", parse);
+    let runtime = VbaRuntime::eval_expression("1 / 0", &mut ctx).unwrap_err();
+    assert!(matches!(runtime, VbaError::RuntimeError { number: 11, .. }), "{:?}", runtime);
+}
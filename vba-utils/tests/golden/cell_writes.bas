@@ -0,0 +1,8 @@
+' golden-cells: A1 A2 B1
+' Writes a small table through the Range object.
+Sub AutoOpen()
+    Range("A1").Value = "Name"
+    Range("B1").Value = "Score"
+    Range("A2").Value = "Alice"
+    MsgBox Range("A1").Value
+End Sub
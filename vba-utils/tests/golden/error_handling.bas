@@ -0,0 +1,9 @@
+' On Error Resume Next swallowing a division by zero.
+Sub AutoOpen()
+    Dim x As Long
+    On Error Resume Next
+    x = 1 / 0
+    MsgBox "Err " & Err.Number
+    Err.Clear
+    MsgBox "after clear " & Err.Number
+End Sub
@@ -0,0 +1,4 @@
+' Smallest macro: a single MsgBox from the auto-run entrypoint.
+Sub AutoOpen()
+    MsgBox "Hello, World"
+End Sub
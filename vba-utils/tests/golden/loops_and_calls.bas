@@ -0,0 +1,15 @@
+' Nested loops feeding a ByRef accumulator.
+Sub Accumulate(total, ByVal n)
+    total = total + n
+End Sub
+
+Sub AutoOpen()
+    Dim total As Long
+    Dim i As Integer, j As Integer
+    For i = 1 To 3
+        For j = 1 To i
+            Accumulate total, j
+        Next j
+    Next i
+    MsgBox "total=" & total
+End Sub
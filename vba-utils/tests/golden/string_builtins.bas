@@ -0,0 +1,10 @@
+' Common string helpers as they appear in document-open macros.
+Sub AutoOpen()
+    Dim s As String
+    s = "  Quarterly Report  "
+    MsgBox "[" & Trim(s) & "]"
+    MsgBox UCase(Left(Trim(s), 9))
+    MsgBox InStr(1, s, "Report")
+    MsgBox Replace("a-b-c", "-", "+")
+    MsgBox Len(s)
+End Sub
@@ -0,0 +1,130 @@
+// Golden snapshot tests for the macro corpus in tests/golden/
+//
+// This test file covers:
+// - Every `*.bas` file under tests/golden/ run through ProgramExecutor
+// - MsgBox/Debug output, Err number and selected cells compared against the
+//   matching `*.snap` file
+//
+// Adding a macro:
+// - Drop `name.bas` into tests/golden/. To capture cells of the active
+//   sheet, put a line like `' golden-cells: A1 B2` anywhere in the file.
+// - Run `cargo test --test golden_tests`. A missing or differing snapshot
+//   is written next to the macro as `name.snap.new` and the test fails.
+// - Review the `.snap.new` and rename it to `.snap`, or rerun with
+//   `VBA_UPDATE_SNAPSHOTS=1` to accept every pending snapshot in place.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use vba_utils::ast::build_ast;
+use vba_utils::host::excel::engine;
+use vba_utils::vm::ProgramExecutor;
+use vba_utils::Context;
+
+const CELLS_DIRECTIVE: &str = "' golden-cells:";
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden")
+}
+
+/// Cell addresses requested by the macro's `' golden-cells:` lines
+fn requested_cells(code: &str) -> Vec<String> {
+    code.lines()
+        .filter_map(|l| l.trim().strip_prefix(CELLS_DIRECTIVE))
+        .flat_map(|rest| rest.split_whitespace().map(str::to_string).collect::<Vec<_>>())
+        .collect()
+}
+
+/// Run one macro and render its observable state in snapshot format
+fn render_snapshot(code: &str) -> String {
+    let program = build_ast(code);
+
+    let mut out = String::new();
+    for d in &program.diagnostics {
+        out.push_str(&format!("diagnostic: {}:{}: {}\n", d.span.line, d.span.column, d.message));
+    }
+
+    let mut ctx = Context::new();
+    let executor = ProgramExecutor::new(program);
+    if let Err(e) = executor.execute(&mut ctx) {
+        out.push_str(&format!("execute error: {}\n", e));
+    }
+
+    out.push_str("-- output --\n");
+    for line in &ctx.output {
+        out.push_str(line);
+        out.push('\n');
+    }
+
+    let cells = requested_cells(code);
+    if !cells.is_empty() {
+        out.push_str("-- cells --\n");
+        // Cells are read through the run's own engine state
+        let _workbook = ctx.static_workbook.enter();
+        for addr in cells {
+            let value = engine::get_cell_value(&addr).unwrap_or_else(|e| format!("<error: {}>", e));
+            out.push_str(&format!("{} = {}\n", addr, value));
+        }
+    }
+
+    out.push_str("-- err --\n");
+    match &ctx.err {
+        Some(e) => out.push_str(&format!("{} {}\n", e.number, e.description)),
+        None => out.push_str("0\n"),
+    }
+    out
+}
+
+#[test]
+fn test_golden_corpus() {
+    let update = std::env::var_os("VBA_UPDATE_SNAPSHOTS").is_some();
+
+    let mut macros: Vec<PathBuf> = fs::read_dir(golden_dir())
+        .expect("tests/golden is missing")
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "bas"))
+        .collect();
+    macros.sort();
+    assert!(!macros.is_empty(), "no .bas files in tests/golden");
+
+    let mut failures = Vec::new();
+    for bas in &macros {
+        let code = fs::read_to_string(bas).expect("unreadable .bas file");
+        let actual = render_snapshot(&code);
+
+        let snap = bas.with_extension("snap");
+        let pending = bas.with_extension("snap.new");
+        let expected = fs::read_to_string(&snap).ok();
+
+        if expected.as_deref() == Some(actual.as_str()) {
+            let _ = fs::remove_file(&pending);
+            continue;
+        }
+        if update {
+            fs::write(&snap, &actual).expect("could not write snapshot");
+            let _ = fs::remove_file(&pending);
+            continue;
+        }
+
+        fs::write(&pending, &actual).expect("could not write pending snapshot");
+        let name = bas.file_name().unwrap().to_string_lossy().into_owned();
+        match expected {
+            None => failures.push(format!("{}: no snapshot, wrote {}", name, pending.display())),
+            Some(exp) => failures.push(format!(
+                "{}: snapshot mismatch, wrote {}\n--- expected\n{}--- actual\n{}",
+                name,
+                pending.display(),
+                exp,
+                actual
+            )),
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+}
+
+#[test]
+fn test_requested_cells_directive() {
+    let code = "' golden-cells: A1 B2\nSub AutoOpen()\nEnd Sub\n' golden-cells: C3\n";
+    assert_eq!(requested_cells(code), vec!["A1", "B2", "C3"]);
+}
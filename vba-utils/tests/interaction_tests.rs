@@ -4,8 +4,8 @@
 // - IIf — Inline If with edge cases
 // - Choose — Index-based selection
 // - Switch — Condition-based selection
-// - MsgBox — Message display (stub)
-// - InputBox — User input (stub with default value support)
+// - MsgBox — Message display, returning the default button
+// - InputBox — User input (default value and scripted answers)
 // - Beep, DoEvents — System functions
 // - Shell — Executable runner (disabled for security)
 // - Environ — Environment variables
@@ -14,21 +14,20 @@
 // - AppActivate, SendKeys — Application control (stubs)
 // - CreateObject, GetObject — OLE automation (stubs)
 
-use tree_sitter::Parser;
-use vba_parser::language as tree_sitter_vba;
-use vba_utils::Context;
+use vba_utils::{Context, RuntimeConfig};
 use vba_utils::vm::ProgramExecutor;
 use vba_utils::ast::build_ast;
 
 /// Helper to run VBA code and capture output
 fn run_vba(code: &str) -> Vec<String> {
-    let mut parser = Parser::new();
-    parser.set_language(tree_sitter_vba()).expect("Failed to set VBA language");
-    let tree = parser.parse(code, None).expect("Failed to parse VBA code");
-    let root_node = tree.root_node();
-    let program = build_ast(root_node, code);
+    run_vba_with(code, RuntimeConfig::default())
+}
+
+/// Helper to run VBA code under a RuntimeConfig and capture output
+fn run_vba_with(code: &str, config: RuntimeConfig) -> Vec<String> {
+    let program = build_ast(code);
     
-    let mut ctx = Context::new();
+    let mut ctx = Context::with_config(config);
     let executor = ProgramExecutor::new(program);
     let _ = executor.execute(&mut ctx);
     ctx.output.clone()
@@ -529,6 +528,36 @@ fn test_inputbox_only_prompt() {
     assert_eq!(run_vba_first(code), "0");
 }
 
+#[test]
+fn test_inputbox_scripted_answers_and_cancel() {
+    let code = r#"
+        Sub AutoOpen()
+            MsgBox "[" & InputBox("Name?", "Who", "Nobody") & "]"
+            MsgBox "[" & InputBox("Again?", "Who", "Nobody") & "]"
+            MsgBox "[" & InputBox("Last?", "Who", "Nobody") & "]"
+        End Sub
+    "#;
+    let config = RuntimeConfig::builder().input_answer("Ada").input_cancel().build();
+    assert_eq!(run_vba_with(code, config), vec!["[Ada]", "[]", "[Nobody]"]);
+}
+
+#[test]
+fn test_msgbox_function_returns_default_button() {
+    let code = r#"
+        Sub AutoOpen()
+            If MsgBox("Save?", vbYesNoCancel + vbQuestion, "Confirm") = vbYes Then MsgBox "saving"
+            Dim r As Integer
+            r = MsgBox("Delete?", vbYesNo + vbDefaultButton2)
+            If r = vbNo Then MsgBox "kept"
+            MsgBox "Done", vbInformation, "Report"
+        End Sub
+    "#;
+    let output = run_vba(code);
+    assert!(output.contains(&"saving".to_string()), "{:?}", output);
+    assert!(output.contains(&"kept".to_string()), "{:?}", output);
+    assert_eq!(output.last().map(String::as_str), Some("Done"));
+}
+
 // ============================================================
 // ENVIRON TESTS
 // ============================================================
@@ -0,0 +1,196 @@
+// Tests for multi-statement lines and line continuations
+//
+// This test file covers:
+// - `a = 1: b = 2`        - statement separator
+// - `Label: stmt`          - leading label on a multi-statement line
+// - `x = 1 + _`            - line continuation
+// - strings, `:=` and single-line If are left untouched
+// - `If x Then a = 1: Exit For Else MsgBox "n"` - single-line If branches
+
+use tree_sitter::Parser;
+use vba_parser::language as tree_sitter_vba;
+use vba_utils::Context;
+use vba_utils::vm::ProgramExecutor;
+use vba_utils::ast::{build_ast, normalize_logical_lines, Body, Statement};
+
+/// Helper to run VBA code and capture output
+fn run_vba(code: &str) -> Vec<String> {
+    let program = build_ast(code);
+
+    let mut ctx = Context::new();
+    let executor = ProgramExecutor::new(program);
+    let _ = executor.execute(&mut ctx);
+    ctx.output.clone()
+}
+
+// ============================================================
+// NORMALIZATION
+// ============================================================
+
+#[test]
+fn test_normalize_splits_separators() {
+    assert_eq!(normalize_logical_lines("a = 1: b = 2\n"), "a = 1\nb = 2\n");
+    assert_eq!(normalize_logical_lines("    Dim a: a = 5\n"), "    Dim a\n    a = 5\n");
+}
+
+#[test]
+fn test_normalize_keeps_leading_label() {
+    assert_eq!(normalize_logical_lines("Retry: x = 1\n"), "Retry:\nx = 1\n");
+    assert_eq!(normalize_logical_lines("Retry:\n"), "Retry:\n");
+}
+
+#[test]
+fn test_normalize_ignores_strings_comments_and_named_args() {
+    let code = "x = \"a:b\" ' c: d\nCall F(a:=1)\nd = #12:30:00#\n";
+    assert_eq!(normalize_logical_lines(code), code);
+}
+
+#[test]
+fn test_normalize_keeps_single_line_if() {
+    let code = "If x Then a = 1: b = 2\n";
+    assert_eq!(normalize_logical_lines(code), code);
+}
+
+#[test]
+fn test_normalize_joins_continuations_and_keeps_line_count() {
+    let code = "MsgBox 1 + _\n    2\nc = 3\n";
+    let normalized = normalize_logical_lines(code);
+    assert_eq!(normalized.lines().count(), code.lines().count());
+    assert!(normalized.starts_with("MsgBox 1 + "));
+    assert!(normalized.contains("c = 3\n"));
+}
+
+// ============================================================
+// EXECUTION
+// ============================================================
+
+#[test]
+fn test_separated_statements_all_run() {
+    let code = r#"
+Sub AutoOpen()
+    Dim a As Integer: Dim b As Integer
+    a = 1: b = 2
+    MsgBox a + b
+End Sub
+"#;
+    let output = run_vba(code);
+    assert_eq!(output.last().map(String::as_str), Some("3"));
+}
+
+#[test]
+fn test_continued_statement_runs() {
+    let code = r#"
+Sub AutoOpen()
+    Dim total As Integer
+    total = 1 + _
+            2 + _
+            3
+    MsgBox total
+End Sub
+"#;
+    let output = run_vba(code);
+    assert_eq!(output.last().map(String::as_str), Some("6"));
+}
+
+// ============================================================
+// SINGLE-LINE IF
+// ============================================================
+
+/// Statement kinds of the Then and Else branches of the single-line If in
+/// `line`, parsed inside a Sub
+fn inline_if_branches(line: &str) -> (Vec<String>, Vec<String>) {
+    let code = format!("Sub T()\n    {}\nEnd Sub\n", line);
+    let mut parser = Parser::new();
+    parser.set_language(tree_sitter_vba()).expect("Failed to set VBA language");
+    let tree = parser.parse(&code, None).expect("Failed to parse VBA code");
+    assert!(!tree.root_node().has_error(), "{}: {}", line, tree.root_node().to_sexp());
+    let program = build_ast(&code);
+    let Some(Statement::Subroutine { body, .. }) = program.statements.first() else {
+        panic!("{}: no Sub in {:?}", line, program.statements);
+    };
+    let Some(Statement::If { then_branch, else_branch, .. }) = body.iter().find(|s| matches!(s, Statement::If { .. })) else {
+        panic!("{}: no If in {:?}", line, body);
+    };
+    let kinds = |branch: &Body| branch.iter().map(|s| format!("{:?}", s).split(['(', ' ']).next().unwrap().to_string()).collect();
+    (kinds(then_branch), kinds(else_branch))
+}
+
+#[test]
+fn test_single_line_if_branch_statements() {
+    let cases: &[(&str, &[&str], &[&str])] = &[
+        ("If x Then a = 1", &["Assignment"], &[]),
+        ("If x Then a = 1: b = 2 Else c = 3", &["Assignment", "Assignment"], &["Assignment"]),
+        ("If x Then Set o = Nothing: Exit Sub", &["Set", "Exit"], &[]),
+        ("If x Then Foo 1, 2: GoTo Done Else Exit For", &["Call", "GoTo"], &["Exit"]),
+        ("If x Then Exit Do Else a = 1: Call Foo(a)", &["Exit"], &["Assignment", "Call"]),
+        (r#"If x Then MsgBox "a""#, &["MsgBox"], &[]),
+        (r#"If x Then MsgBox "a", vbOKOnly, "T" Else MsgBox "b""#, &["MsgBox"], &["MsgBox"]),
+        ("If x Then Resume Next", &["Resume"], &[]),
+        ("If x Then n = 1: Resume Retry Else Resume Next", &["Assignment", "Resume"], &["Resume"]),
+        ("If x Then Stop", &["Stop"], &[]),
+        ("If x Then Stop Else n = 1", &["Stop"], &["Assignment"]),
+        (r#"If x Then .Add "a""#, &["Expression"], &[]),
+        (r#"If x Then .Add "a", "k" Else .Remove 1"#, &["Expression"], &["Expression"]),
+        ("If x Then Exit While", &["Exit"], &[]),
+        ("If x Then n = 1: Exit Select Else Exit Do", &["Assignment", "Exit"], &["Exit"]),
+    ];
+    for (line, then_kinds, else_kinds) in cases {
+        let (then_branch, else_branch) = inline_if_branches(line);
+        assert_eq!(then_branch, *then_kinds, "{}", line);
+        assert_eq!(else_branch, *else_kinds, "{}", line);
+    }
+}
+
+#[test]
+fn test_single_line_if_branches_run() {
+    let code = r#"
+Sub AutoOpen()
+    Dim i As Integer, n As Integer, m As Integer
+    For i = 1 To 10
+        If i = 3 Then n = i * 10: Exit For Else m = m + 1: n = -1
+    Next i
+    MsgBox n
+    MsgBox m
+End Sub
+"#;
+    let output = run_vba(code);
+    assert_eq!(output[output.len().saturating_sub(2)..], ["30", "2"]);
+}
+
+#[test]
+fn test_single_line_if_at_end_of_file() {
+    for code in ["If x Then MsgBox 1", "If x Then a = 1 Else Stop"] {
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_vba()).expect("Failed to set VBA language");
+        let tree = parser.parse(code, None).expect("Failed to parse VBA code");
+        assert!(!tree.root_node().has_error(), "{}: {}", code, tree.root_node().to_sexp());
+    }
+}
+
+#[test]
+fn test_single_line_if_exit_resume_and_stop_run() {
+    let code = r#"
+Sub AutoOpen()
+    Dim i As Integer, d As Integer
+    For i = 1 To 10
+        If i = 3 Then Exit For
+    Next i
+    Do While True
+        If i > 4 Then Exit Do Else i = i + 1
+    Loop
+    MsgBox i
+    On Error GoTo Handler
+    MsgBox 10 / d
+    MsgBox 10 / (d - 5)
+    If d = 5 Then Stop Else MsgBox "not stopped"
+    MsgBox "after stop"
+    Exit Sub
+Handler:
+    If d <> 0 Then Resume Next Else d = 5: Resume
+End Sub
+"#;
+    let output = run_vba(code);
+    assert!(output.iter().any(|l| l == "5"), "{:?}", output);
+    assert!(output.iter().any(|l| l == "2"), "{:?}", output);
+    assert!(!output.iter().any(|l| l == "not stopped" || l == "after stop"), "{:?}", output);
+}
@@ -0,0 +1,74 @@
+// Tests for the profiler
+//
+// This test file covers:
+// - ProgramExecutor::execute_profiled returning a ProfileReport
+// - Call counts and total/self time per Sub and Function
+// - Per-line hit counts
+
+use vba_utils::ast::build_ast;
+use vba_utils::vm::{ProfileReport, ProgramExecutor};
+use vba_utils::Context;
+
+/// Helper to run VBA code under the profiler
+fn profile_vba(code: &str) -> ProfileReport {
+    let program = build_ast(code);
+
+    let mut ctx = Context::new();
+    ProgramExecutor::new(program).execute_profiled(&mut ctx).expect("execution failed")
+}
+
+const CODE: &str = r#"
+Sub AutoOpen()
+    Dim i As Integer
+    Dim total As Long
+    For i = 1 To 5
+        total = total + Twice(i)
+    Next i
+    Helper
+End Sub
+
+Function Twice(n As Integer) As Long
+    Twice = n * 2
+End Function
+
+Sub Helper()
+    Dim s As String
+    s = "done"
+End Sub
+"#;
+
+// ============================================================
+// Procedures
+// ============================================================
+
+#[test]
+fn test_counts_calls_per_procedure() {
+    let report = profile_vba(CODE);
+    assert_eq!(report.procedure("AutoOpen").map(|p| p.calls), Some(1));
+    assert_eq!(report.procedure("Twice").map(|p| p.calls), Some(5));
+    assert_eq!(report.procedure("helper").map(|p| p.calls), Some(1));
+}
+
+#[test]
+fn test_entry_total_includes_callees() {
+    let report = profile_vba(CODE);
+    let entry = report.procedure("AutoOpen").unwrap();
+    let twice = report.procedure("Twice").unwrap();
+    assert!(entry.total >= twice.total);
+    assert!(entry.self_time <= entry.total);
+    assert_eq!(report.procedures[0].name, "AutoOpen");
+}
+
+// ============================================================
+// Lines
+// ============================================================
+
+#[test]
+fn test_counts_line_hits() {
+    let report = profile_vba(CODE);
+    let hits = |line: usize| report.lines.iter().find(|l| l.line == line).map(|l| l.hits);
+    assert_eq!(hits(6), Some(5));
+    assert_eq!(hits(12), Some(5));
+    assert_eq!(hits(17), Some(1));
+    assert!(report.to_text().contains("Twice"));
+}
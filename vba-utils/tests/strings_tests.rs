@@ -12,19 +12,13 @@
 // - StrComp, StrConv
 // - Format, FormatCurrency, FormatNumber, FormatPercent
 
-use tree_sitter::Parser;
-use vba_parser::language as tree_sitter_vba;
 use vba_utils::Context;
 use vba_utils::vm::ProgramExecutor;
 use vba_utils::ast::build_ast;
 
 /// Helper to run VBA code and capture output
 fn run_vba(code: &str) -> Vec<String> {
-    let mut parser = Parser::new();
-    parser.set_language(tree_sitter_vba()).expect("Failed to set VBA language");
-    let tree = parser.parse(code, None).expect("Failed to parse VBA code");
-    let root_node = tree.root_node();
-    let program = build_ast(root_node, code);
+    let program = build_ast(code);
     
     let mut ctx = Context::new();
     let executor = ProgramExecutor::new(program);
@@ -801,3 +795,34 @@ fn test_replace_all_params() {
     // Start at pos 2 ("AaAa"), replace 1 occurrence case-insensitively
     assert_eq!(run_vba_first(code), "aXaAa");
 }
+
+// Multibyte text: positions are characters, and nothing may panic
+#[test]
+fn test_instr_multibyte_counts_characters() {
+    let code = r#"
+        Sub AutoOpen()
+            MsgBox InStr(2, "日本語テキスト", "テ")
+        End Sub
+    "#;
+    assert_eq!(run_vba_first(code), "4");
+}
+
+#[test]
+fn test_instrrev_multibyte_with_start() {
+    let code = r#"
+        Sub AutoOpen()
+            MsgBox InStrRev("äöüäöü", "ä", 3)
+        End Sub
+    "#;
+    assert_eq!(run_vba_first(code), "1");
+}
+
+#[test]
+fn test_replace_case_insensitive_multibyte() {
+    let code = r#"
+        Sub AutoOpen()
+            MsgBox Replace("Straße STRASSE straße", "STRASSE", "X", 1, -1, 1)
+        End Sub
+    "#;
+    assert_eq!(run_vba_first(code), "Straße X straße");
+}
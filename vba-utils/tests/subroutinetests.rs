@@ -6,7 +6,7 @@ use vba_utils::{walk_parse_tree, VbaAstNode};
 fn extract_all_subs(ast: Vec<VbaAstNode>) -> Vec<(String, Vec<VbaAstNode>)> {
     ast.into_iter()
         .filter_map(|n| {
-            if let VbaAstNode::Subroutine { name, body, params: _ } = n {
+            if let VbaAstNode::Subroutine { name, body, .. } = n {
                 Some((name, body))
             } else {
                 None
@@ -0,0 +1,60 @@
+// Tests for execution trace recording
+//
+// This test file covers:
+// - Context::start_trace / take_trace around a VM run
+// - One event per executed statement, with variable deltas
+// - Saving and loading a trace file; replaying state; diffing two runs
+
+use vba_utils::ast::build_ast;
+use vba_utils::vm::{ProgramExecutor, Trace};
+use vba_utils::Context;
+
+/// Helper to run VBA code with tracing enabled
+fn trace_vba(code: &str) -> Trace {
+    let program = build_ast(code);
+
+    let mut ctx = Context::new();
+    ctx.start_trace();
+    let _ = ProgramExecutor::new(program).execute_entrypoint(&mut ctx, "AutoOpen");
+    ctx.take_trace().expect("trace was started")
+}
+
+const LOOP: &str = r#"
+Sub AutoOpen()
+    Dim total As Long
+    Dim i As Integer
+    For i = 1 To 3
+        total = total + i
+    Next i
+End Sub
+"#;
+
+#[test]
+fn test_records_assignments_with_deltas() {
+    let trace = trace_vba(LOOP);
+    let assigns: Vec<_> = trace.events.iter().filter(|e| e.kind == "Assignment").collect();
+    assert_eq!(assigns.len(), 3);
+    assert!(assigns.iter().all(|e| e.changes.iter().any(|c| c.name.eq_ignore_ascii_case("total"))));
+
+    let last = trace.state_at(trace.len() - 1);
+    assert!(last.get("total").is_some_and(|v| v.ends_with("(6)")), "{:?}", last);
+}
+
+#[test]
+fn test_trace_file_round_trip() {
+    let trace = trace_vba(LOOP);
+    let path = std::env::temp_dir().join("vba_trace_round_trip.trace");
+    trace.save(&path).unwrap();
+    let loaded = Trace::load(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(loaded, trace);
+    assert_eq!(loaded.first_divergence(&trace), None);
+}
+
+#[test]
+fn test_divergence_between_runs() {
+    let a = trace_vba(LOOP);
+    let b = trace_vba(&LOOP.replace("For i = 1 To 3", "For i = 1 To 4"));
+    assert!(a.first_divergence(&b).is_some());
+}
@@ -0,0 +1,94 @@
+// Tests for the transpiler
+//
+// This test file covers:
+// - Python and Rust output for arithmetic, loops and string functions
+// - Range/Cells reads and writes
+// - Reporting of untranslated constructs
+
+use vba_utils::ast::build_ast;
+use vba_utils::transpiler::{transpile, Target, Transpiled};
+
+/// Helper to parse VBA code and translate it
+fn transpile_vba(code: &str, target: Target) -> Transpiled {
+    let program = build_ast(code);
+    transpile(&program, target)
+}
+
+const CODE: &str = r#"
+Sub AutoOpen()
+    Dim i As Integer
+    Dim total As Long
+    For i = 1 To 10
+        total = total + Twice(i)
+    Next i
+    Range("A1").Value = total
+    Cells(2, 1).Value = UCase(Left("hello", 3)) & "!"
+    MsgBox "Total: " & total
+End Sub
+
+Function Twice(n As Integer) As Long
+    Twice = n * 2
+End Function
+"#;
+
+// ============================================================
+// Python
+// ============================================================
+
+#[test]
+fn test_python_loops_and_functions() {
+    let out = transpile_vba(CODE, Target::Python);
+    assert!(out.is_complete(), "{}", out.report());
+    assert!(out.source.contains("def auto_open():"));
+    assert!(out.source.contains("for i in range(1, 11):"));
+    assert!(out.source.contains("total = total + twice(i)"));
+    assert!(out.source.contains("def twice(n: int) -> int:"));
+    assert!(out.source.contains("result = n * 2"));
+    assert!(out.source.contains("print(\"Total: \" + str(total))"));
+}
+
+#[test]
+fn test_python_ranges_and_strings() {
+    let out = transpile_vba(CODE, Target::Python);
+    assert!(out.source.contains("_sheet()[\"A1\"].value = total"));
+    assert!(out.source.contains("_sheet().cell(row=2, column=1).value = \"hello\"[:3].upper() + \"!\""));
+}
+
+// ============================================================
+// Rust
+// ============================================================
+
+#[test]
+fn test_rust_loops_and_ranges() {
+    let out = transpile_vba(CODE, Target::Rust);
+    assert!(out.is_complete(), "{}", out.report());
+    assert!(out.source.contains("pub fn auto_open(wb: &mut dyn Workbook) {"));
+    assert!(out.source.contains("for i in 1..=10 {"));
+    assert!(out.source.contains("total = total + twice(i, wb);"));
+    assert!(out.source.contains("wb.set(None, \"A1\", total.to_string());"));
+    assert!(out.source.contains("fn left(s: &str, n: i64) -> String"));
+    assert!(out.source.contains("println!(\"Total: {}\", total);"));
+}
+
+// ============================================================
+// Untranslated constructs
+// ============================================================
+
+#[test]
+fn test_reports_untranslated() {
+    let code = r#"
+Sub AutoOpen()
+    On Error Resume Next
+    Dim ws As Object
+    Set ws = ActiveSheet
+    x = 1
+End Sub
+"#;
+    let out = transpile_vba(code, Target::Python);
+    let kinds: Vec<&str> = out.untranslated.iter().map(|u| u.construct.as_str()).collect();
+    assert_eq!(kinds, vec!["OnError", "Set"]);
+    assert_eq!(out.untranslated[0].line, Some(3));
+    assert!(out.source.contains("# TODO: untranslated Set"));
+    assert!(out.source.contains("x = 1"));
+    assert!(out.report().contains("line 5: Set in AutoOpen"));
+}